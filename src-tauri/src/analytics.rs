@@ -0,0 +1,217 @@
+use chrono::{Duration, Local};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use tauri::ipc::Invoke;
+use tauri::{AppHandle, Manager, State};
+
+use crate::error::AppError;
+use crate::{default_session_store_version, hooks, now_ms, storage, AppState};
+
+const FLUSH_INTERVAL_SECS: u64 = 60;
+const RETENTION_DAYS: i64 = 90;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct LocalAnalytics {
+    #[serde(default = "default_session_store_version")]
+    version: u32,
+    #[serde(default)]
+    since_ms: Option<i64>,
+    #[serde(default)]
+    commands: BTreeMap<String, u64>,
+    #[serde(default)]
+    events: BTreeMap<String, u64>,
+    #[serde(default)]
+    daily: BTreeMap<String, u64>,
+}
+
+impl Default for LocalAnalytics {
+    fn default() -> Self {
+        Self {
+            version: default_session_store_version(),
+            since_ms: None,
+            commands: BTreeMap::new(),
+            events: BTreeMap::new(),
+            daily: BTreeMap::new(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct CommandCount {
+    name: String,
+    count: u64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct LocalAnalyticsReport {
+    enabled: bool,
+    since_ms: Option<i64>,
+    total_commands: u64,
+    commands: Vec<CommandCount>,
+    events: BTreeMap<String, u64>,
+    daily: BTreeMap<String, u64>,
+}
+
+// Counts live only in analytics.json under the app data dir. Names of
+// commands and events are recorded, never their arguments or payloads.
+pub(crate) struct Analytics {
+    path: PathBuf,
+    enabled: AtomicBool,
+    dirty: AtomicBool,
+    data: Mutex<LocalAnalytics>,
+}
+
+impl Analytics {
+    pub(crate) fn load(data_dir: &Path, enabled: bool) -> Self {
+        let path = data_dir.join("analytics.json");
+        let data = storage::read_json_or_default(&path).unwrap_or_else(|err| {
+            tracing::warn!("failed to read local analytics: {err}");
+            LocalAnalytics::default()
+        });
+        Self {
+            path,
+            enabled: AtomicBool::new(enabled),
+            dirty: AtomicBool::new(false),
+            data: Mutex::new(data),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, LocalAnalytics> {
+        self.data.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub(crate) fn set_enabled(&self, enabled: bool) {
+        self.enabled.store(enabled, Ordering::SeqCst);
+    }
+
+    fn bump(&self, update: impl FnOnce(&mut LocalAnalytics)) {
+        if !self.enabled.load(Ordering::SeqCst) {
+            return;
+        }
+        let mut data = self.lock();
+        data.since_ms.get_or_insert_with(now_ms);
+        update(&mut data);
+        let today = Local::now().format("%Y-%m-%d").to_string();
+        *data.daily.entry(today).or_default() += 1;
+        self.dirty.store(true, Ordering::SeqCst);
+    }
+
+    pub(crate) fn record(&self, event: &str) {
+        self.bump(|data| *data.events.entry(event.to_string()).or_default() += 1);
+    }
+
+    fn record_command(&self, command: &str) {
+        self.bump(|data| *data.commands.entry(command.to_string()).or_default() += 1);
+    }
+
+    pub(crate) fn flush(&self) {
+        if !self.dirty.swap(false, Ordering::SeqCst) {
+            return;
+        }
+        let snapshot = {
+            let mut data = self.lock();
+            let cutoff = (Local::now() - Duration::days(RETENTION_DAYS))
+                .format("%Y-%m-%d")
+                .to_string();
+            data.daily.retain(|day, _| *day >= cutoff);
+            data.clone()
+        };
+        if let Err(err) = storage::write_json_atomic(&self.path, &snapshot) {
+            tracing::warn!("failed to save local analytics: {err}");
+            self.dirty.store(true, Ordering::SeqCst);
+        }
+    }
+
+    fn report(&self) -> LocalAnalyticsReport {
+        let data = self.lock().clone();
+        let mut commands: Vec<CommandCount> = data
+            .commands
+            .into_iter()
+            .map(|(name, count)| CommandCount { name, count })
+            .collect();
+        commands.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.name.cmp(&b.name)));
+        LocalAnalyticsReport {
+            enabled: self.enabled.load(Ordering::SeqCst),
+            since_ms: data.since_ms,
+            total_commands: commands.iter().map(|command| command.count).sum(),
+            commands,
+            events: data.events,
+            daily: data.daily,
+        }
+    }
+}
+
+pub(crate) fn track<F>(handler: F) -> impl Fn(Invoke) -> bool + Send + Sync + 'static
+where
+    F: Fn(Invoke) -> bool + Send + Sync + 'static,
+{
+    move |invoke| {
+        let webview = invoke.message.webview();
+        if let Some(state) = webview.try_state::<AppState>() {
+            state.analytics.record_command(invoke.message.command());
+        }
+        handler(invoke)
+    }
+}
+
+pub(crate) fn observe(app: &AppHandle, message: &Value) {
+    let event = if hooks::approval_request_method(message).is_some() {
+        "approvalsRequested"
+    } else {
+        match message.get("method").and_then(|method| method.as_str()) {
+            Some("turn/started") => "turnsStarted",
+            Some("turn/completed") => "turnsCompleted",
+            _ => return,
+        }
+    };
+    app.state::<AppState>().analytics.record(event);
+}
+
+pub(crate) fn start(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval =
+            tokio::time::interval(std::time::Duration::from_secs(FLUSH_INTERVAL_SECS));
+        loop {
+            interval.tick().await;
+            let app = app.clone();
+            let _ = tokio::task::spawn_blocking(move || {
+                app.state::<AppState>().analytics.flush();
+            })
+            .await;
+        }
+    });
+}
+
+#[tauri::command]
+pub(crate) async fn get_local_analytics(
+    state: State<'_, AppState>,
+) -> Result<LocalAnalyticsReport, AppError> {
+    Ok(state.analytics.report())
+}
+
+#[tauri::command]
+pub(crate) async fn export_local_analytics(
+    path: String,
+    state: State<'_, AppState>,
+) -> Result<(), AppError> {
+    let report = state.analytics.report();
+    std::fs::write(&path, serde_json::to_vec_pretty(&report)?)?;
+    Ok(())
+}
+
+#[tauri::command]
+pub(crate) async fn reset_local_analytics(
+    state: State<'_, AppState>,
+) -> Result<LocalAnalyticsReport, AppError> {
+    *state.analytics.lock() = LocalAnalytics::default();
+    state.analytics.dirty.store(true, Ordering::SeqCst);
+    state.analytics.flush();
+    Ok(state.analytics.report())
+}
@@ -0,0 +1,386 @@
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+use tokio::sync::Mutex;
+
+use crate::thread_state::str_at;
+use crate::{
+    command_allowlist, diff_patch_to_string, hooks, is_executable_path, platform, shell_env,
+    AppState,
+};
+
+const MAX_FILES: usize = 20;
+const WRAPPER_SHELLS: &[&str] = &["bash", "sh", "zsh", "pwsh", "powershell", "cmd"];
+
+#[derive(Default)]
+pub(crate) struct ApprovalContexts {
+    items: Mutex<HashMap<(String, String), Value>>,
+}
+
+enum Change {
+    Add(String),
+    Delete,
+    Update {
+        diff: String,
+        move_path: Option<String>,
+    },
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct FileChangeContext {
+    path: String,
+    kind: &'static str,
+    move_path: Option<String>,
+    diff: String,
+    additions: usize,
+    deletions: usize,
+    stale: bool,
+    truncated: bool,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct CommandContext {
+    command: String,
+    cwd: String,
+    binary: Option<String>,
+    resolved_path: Option<String>,
+    allowlisted: bool,
+    allowlist_pattern: Option<String>,
+}
+
+fn parse_change(value: &Value) -> Option<Change> {
+    // Item changes carry `kind.type` plus `diff`; legacy approvals use either
+    // an externally tagged `{ "update": {...} }` or a `type` field.
+    let (kind, body) = match value.as_object()? {
+        object if object.contains_key("kind") => {
+            let kind = str_at(value, "/kind/type").or_else(|| str_at(value, "/kind"))?;
+            (kind, value)
+        }
+        object if object.contains_key("type") => (str_at(value, "/type")?, value),
+        object => {
+            let (kind, body) = object.iter().next()?;
+            (kind.as_str(), body)
+        }
+    };
+    let text = |keys: &[&str]| {
+        keys.iter()
+            .find_map(|key| body.get(*key).and_then(Value::as_str))
+            .unwrap_or_default()
+            .to_string()
+    };
+    match kind {
+        "add" => Some(Change::Add(text(&["content", "diff"]))),
+        "delete" => Some(Change::Delete),
+        "update" => Some(Change::Update {
+            diff: text(&["unified_diff", "unifiedDiff", "diff"]),
+            move_path: str_at(value, "/kind/move_path")
+                .or_else(|| str_at(value, "/kind/movePath"))
+                .or_else(|| str_at(body, "/move_path"))
+                .or_else(|| str_at(body, "/movePath"))
+                .map(str::to_string),
+        }),
+        _ => None,
+    }
+}
+
+fn collect_changes(params: &Value, item: Option<&Value>) -> Vec<(String, Change)> {
+    let mut changes = Vec::new();
+    if let Some(map) = params.get("fileChanges").and_then(Value::as_object) {
+        for (path, change) in map {
+            if let Some(change) = parse_change(change) {
+                changes.push((path.clone(), change));
+            }
+        }
+    }
+    let listed = params
+        .get("changes")
+        .or_else(|| item.and_then(|item| item.get("changes")))
+        .and_then(Value::as_array);
+    for change in listed.into_iter().flatten() {
+        if let (Some(path), Some(parsed)) = (str_at(change, "/path"), parse_change(change)) {
+            changes.push((path.to_string(), parsed));
+        }
+    }
+    changes
+}
+
+fn apply_hunks(current: &str, diff: &str) -> Option<String> {
+    let lines: Vec<&str> = current.lines().collect();
+    let mut output: Vec<&str> = Vec::new();
+    let mut cursor = 0;
+    let mut hunks: Vec<(Vec<&str>, Vec<&str>)> = Vec::new();
+    for line in diff.lines() {
+        if line.starts_with("@@") {
+            hunks.push((Vec::new(), Vec::new()));
+            continue;
+        }
+        let Some((old, new)) = hunks.last_mut() else {
+            continue;
+        };
+        if let Some(rest) = line.strip_prefix(' ') {
+            old.push(rest);
+            new.push(rest);
+        } else if let Some(rest) = line.strip_prefix('-') {
+            old.push(rest);
+        } else if let Some(rest) = line.strip_prefix('+') {
+            new.push(rest);
+        } else if line.is_empty() {
+            old.push("");
+            new.push("");
+        }
+    }
+    if hunks.is_empty() {
+        return None;
+    }
+    for (old, new) in &hunks {
+        let start = if old.is_empty() {
+            lines.len()
+        } else {
+            (cursor..=lines.len().saturating_sub(old.len()))
+                .find(|&start| lines.get(start..start + old.len()) == Some(&old[..]))?
+        };
+        output.extend_from_slice(&lines[cursor..start]);
+        output.extend_from_slice(new);
+        cursor = start + old.len();
+    }
+    output.extend_from_slice(&lines[cursor..]);
+    let mut updated = output.join("\n");
+    if current.ends_with('\n') || current.is_empty() {
+        updated.push('\n');
+    }
+    Some(updated)
+}
+
+fn render(path: &str, old: &str, new: &str) -> Option<(String, usize, usize)> {
+    let name = Some(Path::new(path));
+    let mut patch =
+        git2::Patch::from_buffers(old.as_bytes(), name, new.as_bytes(), name, None).ok()?;
+    let (_, additions, deletions) = patch.line_stats().ok()?;
+    Some((diff_patch_to_string(&mut patch).ok()?, additions, deletions))
+}
+
+fn count_lines(diff: &str) -> (usize, usize) {
+    diff.lines().fold((0, 0), |(additions, deletions), line| {
+        if line.starts_with('+') && !line.starts_with("+++") {
+            (additions + 1, deletions)
+        } else if line.starts_with('-') && !line.starts_with("---") {
+            (additions, deletions + 1)
+        } else {
+            (additions, deletions)
+        }
+    })
+}
+
+fn truncate(text: &mut String, max_bytes: usize) -> bool {
+    if text.len() <= max_bytes {
+        return false;
+    }
+    let mut end = max_bytes;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    text.truncate(end);
+    true
+}
+
+fn file_context(root: &Path, path: String, change: Change, max_bytes: usize) -> FileChangeContext {
+    let absolute = root.join(&path);
+    let current = fs::read_to_string(&absolute).unwrap_or_default();
+    let (kind, move_path, rendered, raw) = match change {
+        Change::Add(content) => ("add", None, render(&path, &current, &content), content),
+        Change::Delete => ("delete", None, render(&path, &current, ""), String::new()),
+        Change::Update { diff, move_path } => {
+            let rendered =
+                apply_hunks(&current, &diff).and_then(|updated| render(&path, &current, &updated));
+            ("update", move_path, rendered, diff)
+        }
+    };
+    let stale = rendered.is_none();
+    let (mut diff, additions, deletions) = rendered.unwrap_or_else(|| {
+        let (additions, deletions) = count_lines(&raw);
+        (raw, additions, deletions)
+    });
+    let truncated = truncate(&mut diff, max_bytes);
+    FileChangeContext {
+        path,
+        kind,
+        move_path,
+        diff,
+        additions,
+        deletions,
+        stale,
+        truncated,
+    }
+}
+
+fn command_text(value: Option<&Value>) -> Option<String> {
+    let parts: Vec<String> = match value? {
+        Value::Array(parts) => parts
+            .iter()
+            .filter_map(Value::as_str)
+            .map(str::to_string)
+            .collect(),
+        Value::String(command) => return Some(command.clone()),
+        _ => return None,
+    };
+    // `bash -lc "<script>"` is how the agent runs most commands; the script is
+    // what the user actually needs to judge.
+    if let [shell, flag, script] = parts.as_slice() {
+        let name = Path::new(shell)
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_ascii_lowercase())
+            .unwrap_or_default();
+        let is_script_flag = flag.starts_with('-') || flag.eq_ignore_ascii_case("/c");
+        if WRAPPER_SHELLS.contains(&name.as_str()) && is_script_flag {
+            return Some(script.clone());
+        }
+    }
+    Some(parts.join(" "))
+}
+
+fn first_binary(command: &str) -> Option<String> {
+    command
+        .split_whitespace()
+        .find(|token| !token.contains('=') || token.starts_with(['/', '.']))
+        .map(|token| token.trim_matches(|c| c == '"' || c == '\'').to_string())
+        .filter(|token| !token.is_empty())
+}
+
+fn resolve_binary(binary: &str, cwd: &Path, path_var: Option<&str>) -> Option<PathBuf> {
+    if binary.contains(['/', '\\']) {
+        let candidate = cwd.join(binary);
+        return is_executable_path(&candidate).then_some(candidate);
+    }
+    if let Some(shim) = platform::resolve_windows_shim(binary) {
+        return Some(shim);
+    }
+    let path_var = path_var
+        .map(std::ffi::OsString::from)
+        .or_else(|| env::var_os("PATH"))?;
+    env::split_paths(&path_var)
+        .map(|dir| dir.join(binary))
+        .find(|candidate| is_executable_path(candidate))
+}
+
+async fn command_context(
+    state: &AppState,
+    workspace_path: &str,
+    params: &Value,
+    item: Option<&Value>,
+) -> Option<CommandContext> {
+    let command = command_text(params.get("command"))
+        .or_else(|| command_text(item.and_then(|item| item.get("command"))))?;
+    let cwd = str_at(params, "/cwd")
+        .or_else(|| item.and_then(|item| str_at(item, "/cwd")))
+        .unwrap_or(workspace_path)
+        .to_string();
+    let settings = state.settings.lock().await.clone();
+    let path_var = shell_env::spawn_env(state, &settings)
+        .await
+        .and_then(|vars| vars.get("PATH").cloned());
+    let binary = first_binary(&command);
+    let resolved_path = binary
+        .as_deref()
+        .and_then(|binary| resolve_binary(binary, Path::new(&cwd), path_var.as_deref()))
+        .map(|path| path.to_string_lossy().to_string());
+    let patterns = command_allowlist::patterns_for(state, workspace_path).await;
+    let allowlist_pattern =
+        command_allowlist::matching_pattern(&command, &patterns).map(str::to_string);
+    Some(CommandContext {
+        allowlisted: allowlist_pattern.is_some(),
+        allowlist_pattern,
+        command,
+        cwd,
+        binary,
+        resolved_path,
+    })
+}
+
+// Remembers started fileChange and commandExecution items so approvals that
+// only reference an itemId can still be described.
+async fn track_item(state: &AppState, workspace_id: &str, message: &Value) {
+    let method = str_at(message, "/method").unwrap_or_default();
+    let Some(item) = message.pointer("/params/item") else {
+        return;
+    };
+    if !matches!(
+        str_at(item, "/type"),
+        Some("fileChange" | "commandExecution")
+    ) {
+        return;
+    }
+    let Some(item_id) = str_at(item, "/id") else {
+        return;
+    };
+    let key = (workspace_id.to_string(), item_id.to_string());
+    let mut items = state.approval_contexts.items.lock().await;
+    match method {
+        "item/started" => {
+            items.insert(key, item.clone());
+        }
+        "item/completed" => {
+            items.remove(&key);
+        }
+        _ => {}
+    }
+}
+
+pub(crate) async fn enrich(app: &AppHandle, workspace_id: &str, message: &mut Value) {
+    let state = app.state::<AppState>();
+    if hooks::approval_request_method(message).is_none() {
+        track_item(&state, workspace_id, message).await;
+        return;
+    }
+    let Some(workspace_path) = state
+        .workspaces
+        .lock()
+        .await
+        .get(workspace_id)
+        .map(|entry| entry.path.clone())
+    else {
+        return;
+    };
+    let params = message.get("params").cloned().unwrap_or(Value::Null);
+    let item = match str_at(&params, "/itemId") {
+        Some(item_id) => state
+            .approval_contexts
+            .items
+            .lock()
+            .await
+            .get(&(workspace_id.to_string(), item_id.to_string()))
+            .cloned(),
+        None => None,
+    };
+
+    let changes = collect_changes(&params, item.as_ref());
+    let context = if !changes.is_empty() {
+        let max_bytes = state.settings.lock().await.diff_max_bytes;
+        let root = PathBuf::from(&workspace_path);
+        let omitted = changes.len().saturating_sub(MAX_FILES);
+        let files = tokio::task::spawn_blocking(move || {
+            changes
+                .into_iter()
+                .take(MAX_FILES)
+                .map(|(path, change)| file_context(&root, path, change, max_bytes))
+                .collect::<Vec<_>>()
+        })
+        .await
+        .unwrap_or_default();
+        json!({ "files": files, "omittedFiles": omitted })
+    } else if let Some(command) =
+        command_context(&state, &workspace_path, &params, item.as_ref()).await
+    {
+        json!({ "command": command })
+    } else {
+        return;
+    };
+    if let Some(params) = message.get_mut("params").and_then(Value::as_object_mut) {
+        params.insert("approvalContext".to_string(), context);
+    }
+}
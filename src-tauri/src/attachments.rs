@@ -0,0 +1,129 @@
+use image::codecs::jpeg::JpegEncoder;
+use image::codecs::png::PngEncoder;
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageDecoder, ImageFormat, ImageReader};
+use std::fs;
+use std::io::Cursor;
+use std::process::Command;
+use uuid::Uuid;
+
+const JPEG_QUALITY: u8 = 90;
+const HEIF_BRANDS: &[&[u8]] = &[
+    b"heic", b"heix", b"hevc", b"hevx", b"heim", b"heis", b"mif1",
+];
+
+pub(crate) struct NormalizedImage {
+    pub(crate) bytes: Vec<u8>,
+    pub(crate) extension: &'static str,
+    pub(crate) mime: &'static str,
+    pub(crate) width: u32,
+    pub(crate) height: u32,
+    pub(crate) downscaled: bool,
+}
+
+fn is_heif(bytes: &[u8], extension: &str) -> bool {
+    matches!(extension, "heic" | "heif")
+        || (bytes.len() >= 12 && &bytes[4..8] == b"ftyp" && HEIF_BRANDS.contains(&&bytes[8..12]))
+}
+
+// There is no pure-Rust HEIF decoder, so conversion goes through the tools
+// the OS ships (sips on macOS) or libheif's heif-convert elsewhere.
+fn convert_heif(bytes: &[u8]) -> Result<Vec<u8>, String> {
+    let dir = std::env::temp_dir().join(format!("codexmonitor-heif-{}", Uuid::new_v4()));
+    fs::create_dir_all(&dir).map_err(|err| err.to_string())?;
+    let input = dir.join("input.heic");
+    let output = dir.join("output.png");
+    let result = (|| {
+        fs::write(&input, bytes).map_err(|err| err.to_string())?;
+        let mut command = if cfg!(target_os = "macos") {
+            let mut command = Command::new("sips");
+            command
+                .args(["-s", "format", "png"])
+                .arg(&input)
+                .arg("--out");
+            command
+        } else {
+            let mut command = Command::new("heif-convert");
+            command.arg(&input);
+            command
+        };
+        #[cfg(windows)]
+        crate::platform::hide_std_console_window(&mut command);
+        let converted = command
+            .arg(&output)
+            .output()
+            .map_err(|err| format!("HEIC images need heif-convert to be installed: {err}"))?;
+        if !converted.status.success() {
+            let stderr = String::from_utf8_lossy(&converted.stderr);
+            return Err(format!("Failed to convert HEIC image: {}", stderr.trim()));
+        }
+        fs::read(&output).map_err(|err| err.to_string())
+    })();
+    let _ = fs::remove_dir_all(&dir);
+    result
+}
+
+// Decoding validates the upload, and re-encoding drops EXIF and other
+// metadata after the orientation it carries has been applied.
+pub(crate) fn normalize(
+    bytes: Vec<u8>,
+    extension: &str,
+    max_dimension: u32,
+) -> Result<NormalizedImage, String> {
+    let bytes = if is_heif(&bytes, extension) {
+        convert_heif(&bytes)?
+    } else {
+        bytes
+    };
+    let reader = ImageReader::new(Cursor::new(&bytes))
+        .with_guessed_format()
+        .map_err(|err| err.to_string())?;
+    let format = reader.format().ok_or("Unsupported image format.")?;
+    let mut decoder = reader
+        .into_decoder()
+        .map_err(|err| format!("Invalid image: {err}"))?;
+    let orientation = decoder
+        .orientation()
+        .map_err(|err| format!("Invalid image: {err}"))?;
+    let mut image =
+        DynamicImage::from_decoder(decoder).map_err(|err| format!("Invalid image: {err}"))?;
+    image.apply_orientation(orientation);
+
+    let downscaled = max_dimension > 0 && image.width().max(image.height()) > max_dimension;
+    if downscaled {
+        image = image.resize(max_dimension, max_dimension, FilterType::Lanczos3);
+    }
+    let (width, height) = (image.width(), image.height());
+    // Re-encoding a GIF would keep only its first frame.
+    if format == ImageFormat::Gif && !downscaled {
+        return Ok(NormalizedImage {
+            bytes,
+            extension: "gif",
+            mime: "image/gif",
+            width,
+            height,
+            downscaled,
+        });
+    }
+
+    let mut output = Vec::new();
+    let (extension, mime) = if format == ImageFormat::Jpeg {
+        JpegEncoder::new_with_quality(&mut output, JPEG_QUALITY)
+            .encode_image(&image.to_rgb8())
+            .map_err(|err| err.to_string())?;
+        ("jpg", "image/jpeg")
+    } else {
+        image
+            .write_with_encoder(PngEncoder::new(&mut output))
+            .map_err(|err| err.to_string())?;
+        ("png", "image/png")
+    };
+    Ok(NormalizedImage {
+        bytes: output,
+        extension,
+        mime,
+        width,
+        height,
+        downscaled,
+    })
+}
@@ -0,0 +1,330 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager, State};
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::thread_state::{message_thread_id, str_at};
+use crate::{hooks, i18n, now_ms, workspace_entry, AppState};
+
+const DEFAULT_QUERY_LIMIT: usize = 500;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum AuditKind {
+    Command,
+    FileWrite,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct AuditEntry {
+    id: String,
+    at_ms: i64,
+    thread_id: String,
+    #[serde(default)]
+    turn_id: Option<String>,
+    #[serde(default)]
+    item_id: Option<String>,
+    kind: AuditKind,
+    target: String,
+    #[serde(default)]
+    change: Option<String>,
+    #[serde(default)]
+    decision: Option<String>,
+    #[serde(default)]
+    status: Option<String>,
+    #[serde(default)]
+    exit_code: Option<i64>,
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct AuditFilter {
+    #[serde(default)]
+    thread_id: Option<String>,
+    #[serde(default)]
+    kind: Option<AuditKind>,
+    #[serde(default)]
+    since_ms: Option<i64>,
+    #[serde(default)]
+    until_ms: Option<i64>,
+    #[serde(default)]
+    query: Option<String>,
+    #[serde(default)]
+    limit: Option<usize>,
+}
+
+#[derive(Default)]
+pub(crate) struct AuditLog {
+    requests: Mutex<HashMap<(String, u64), String>>,
+    decisions: Mutex<HashMap<(String, String), String>>,
+    write_lock: Mutex<()>,
+}
+
+fn audit_log_path(workspace_path: &str) -> PathBuf {
+    PathBuf::from(workspace_path)
+        .join(".codexmonitor")
+        .join("audit.jsonl")
+}
+
+fn append_entries(path: &Path, entries: &[AuditEntry]) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| e.to_string())?;
+    for entry in entries {
+        let line = serde_json::to_string(entry).map_err(|e| e.to_string())?;
+        writeln!(file, "{line}").map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+fn read_entries(path: &Path) -> Result<Vec<AuditEntry>, String> {
+    let raw = match fs::read_to_string(path) {
+        Ok(raw) => raw,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err.to_string()),
+    };
+    Ok(raw
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+fn command_text(item: &Value) -> String {
+    match item.get("command") {
+        Some(Value::Array(parts)) => parts
+            .iter()
+            .filter_map(Value::as_str)
+            .collect::<Vec<_>>()
+            .join(" "),
+        Some(Value::String(command)) => command.clone(),
+        _ => String::new(),
+    }
+}
+
+fn matches(entry: &AuditEntry, filter: &AuditFilter) -> bool {
+    if filter
+        .thread_id
+        .as_ref()
+        .is_some_and(|thread_id| &entry.thread_id != thread_id)
+    {
+        return false;
+    }
+    if filter.kind.is_some_and(|kind| entry.kind != kind) {
+        return false;
+    }
+    if filter.since_ms.is_some_and(|since| entry.at_ms < since)
+        || filter.until_ms.is_some_and(|until| entry.at_ms > until)
+    {
+        return false;
+    }
+    match filter.query.as_deref().map(str::trim) {
+        Some(query) if !query.is_empty() => entry
+            .target
+            .to_lowercase()
+            .contains(&query.to_lowercase()),
+        _ => true,
+    }
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn to_csv(entries: &[AuditEntry], lang: &str) -> String {
+    let mut csv = String::from(
+        "timestamp,local_time,thread_id,turn_id,kind,target,change,decision,status,exit_code\n",
+    );
+    for entry in entries {
+        let timestamp = chrono::DateTime::from_timestamp_millis(entry.at_ms)
+            .map(|time| time.to_rfc3339())
+            .unwrap_or_default();
+        let kind = match entry.kind {
+            AuditKind::Command => "command",
+            AuditKind::FileWrite => "fileWrite",
+        };
+        let fields = [
+            timestamp,
+            i18n::format_timestamp_ms(lang, entry.at_ms).unwrap_or_default(),
+            entry.thread_id.clone(),
+            entry.turn_id.clone().unwrap_or_default(),
+            kind.to_string(),
+            entry.target.clone(),
+            entry.change.clone().unwrap_or_default(),
+            entry.decision.clone().unwrap_or_default(),
+            entry.status.clone().unwrap_or_default(),
+            entry.exit_code.map(|code| code.to_string()).unwrap_or_default(),
+        ];
+        let row: Vec<String> = fields.iter().map(|field| csv_field(field)).collect();
+        csv.push_str(&row.join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+pub(crate) async fn record_decision(
+    app: &AppHandle,
+    workspace_id: &str,
+    request_id: u64,
+    result: &Value,
+) {
+    let Some(decision) = str_at(result, "/decision") else {
+        return;
+    };
+    let audit = &app.state::<AppState>().audit;
+    let key = (workspace_id.to_string(), request_id);
+    let Some(item_id) = audit.requests.lock().await.remove(&key) else {
+        return;
+    };
+    audit
+        .decisions
+        .lock()
+        .await
+        .insert((workspace_id.to_string(), item_id), decision.to_string());
+}
+
+fn entries_for_item(params: &Value, item: &Value, decision: Option<String>) -> Vec<AuditEntry> {
+    let thread_id = message_thread_id(params).unwrap_or_default();
+    let turn_id = str_at(params, "/turnId").map(str::to_string);
+    let item_id = str_at(item, "/id").map(str::to_string);
+    let status = str_at(item, "/status").map(str::to_string);
+    let entry = |kind, target: String, change: Option<String>| AuditEntry {
+        id: Uuid::new_v4().to_string(),
+        at_ms: now_ms(),
+        thread_id: thread_id.clone(),
+        turn_id: turn_id.clone(),
+        item_id: item_id.clone(),
+        kind,
+        target,
+        change,
+        decision: decision.clone(),
+        status: status.clone(),
+        exit_code: item.get("exitCode").and_then(Value::as_i64),
+    };
+    match str_at(item, "/type") {
+        Some("commandExecution") => vec![entry(AuditKind::Command, command_text(item), None)],
+        Some("fileChange") => item
+            .get("changes")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(|change| {
+                let path = str_at(change, "/path")?;
+                let kind = str_at(change, "/kind")
+                    .or_else(|| str_at(change, "/kind/type"))
+                    .map(str::to_string);
+                Some(entry(AuditKind::FileWrite, path.to_string(), kind))
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+pub(crate) async fn observe(app: &AppHandle, workspace_id: &str, message: &Value) {
+    let state = app.state::<AppState>();
+    let params = message.get("params").unwrap_or(&Value::Null);
+    if hooks::approval_request_method(message).is_some() {
+        let request_id = message.get("id").and_then(Value::as_u64);
+        if let (Some(request_id), Some(item_id)) = (request_id, str_at(params, "/itemId")) {
+            state
+                .audit
+                .requests
+                .lock()
+                .await
+                .insert((workspace_id.to_string(), request_id), item_id.to_string());
+        }
+        return;
+    }
+    if str_at(message, "/method") != Some("item/completed") {
+        return;
+    }
+    let item = params.get("item").unwrap_or(&Value::Null);
+    if !matches!(str_at(item, "/type"), Some("commandExecution" | "fileChange")) {
+        return;
+    }
+    let decision = match str_at(item, "/id") {
+        Some(item_id) => state
+            .audit
+            .decisions
+            .lock()
+            .await
+            .remove(&(workspace_id.to_string(), item_id.to_string())),
+        None => None,
+    };
+    let entries = entries_for_item(params, item, decision);
+    if entries.is_empty() {
+        return;
+    }
+    let Some(workspace_path) = state
+        .workspaces
+        .lock()
+        .await
+        .get(workspace_id)
+        .map(|entry| entry.path.clone())
+    else {
+        return;
+    };
+    let _guard = state.audit.write_lock.lock().await;
+    if let Err(err) = append_entries(&audit_log_path(&workspace_path), &entries) {
+        tracing::warn!(%workspace_id, "failed to write audit log: {err}");
+    }
+}
+
+async fn query(
+    state: &AppState,
+    workspace_id: &str,
+    filter: &AuditFilter,
+) -> Result<Vec<AuditEntry>, AppError> {
+    let entry = workspace_entry(state, workspace_id).await?;
+    let entries = {
+        let _guard = state.audit.write_lock.lock().await;
+        read_entries(&audit_log_path(&entry.path))?
+    };
+    let limit = filter.limit.unwrap_or(DEFAULT_QUERY_LIMIT);
+    Ok(entries
+        .into_iter()
+        .rev()
+        .filter(|entry| matches(entry, filter))
+        .take(limit)
+        .collect())
+}
+
+#[tauri::command]
+pub(crate) async fn query_audit_log(
+    workspace_id: String,
+    filter: Option<AuditFilter>,
+    state: State<'_, AppState>,
+) -> Result<Vec<AuditEntry>, AppError> {
+    query(&state, &workspace_id, &filter.unwrap_or_default()).await
+}
+
+#[tauri::command]
+pub(crate) async fn export_audit_log_csv(
+    workspace_id: String,
+    filter: Option<AuditFilter>,
+    path: String,
+    state: State<'_, AppState>,
+) -> Result<usize, AppError> {
+    let mut filter = filter.unwrap_or_default();
+    filter.limit = Some(filter.limit.unwrap_or(usize::MAX));
+    let mut entries = query(&state, &workspace_id, &filter).await?;
+    entries.reverse();
+    let lang = i18n::app_lang(&state).await;
+    fs::write(&path, to_csv(&entries, lang))?;
+    Ok(entries.len())
+}
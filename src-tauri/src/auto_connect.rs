@@ -0,0 +1,118 @@
+use serde::Serialize;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, State};
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+use crate::error::AppError;
+use crate::{connect_entry, write_workspaces, AppState};
+
+const MAX_PARALLEL: usize = 3;
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Serialize, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+enum AutoConnectStatus {
+    Connecting,
+    Connected,
+    Failed,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct AutoConnectProgress {
+    workspace_id: String,
+    status: AutoConnectStatus,
+    error: Option<String>,
+    completed: usize,
+    total: usize,
+}
+
+fn emit(app: &AppHandle, progress: AutoConnectProgress) {
+    let _ = app.emit("workspace-auto-connect", progress);
+}
+
+// Runs detached from setup so a slow or hanging codex binary only delays its
+// own workspace; the snapshot and the UI come up immediately.
+pub(crate) fn start(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let state = app.state::<AppState>();
+        let entries: Vec<_> = {
+            let workspaces = state.workspaces.lock().await;
+            let sessions = state.sessions.lock().await;
+            workspaces
+                .values()
+                .filter(|entry| entry.auto_connect && !sessions.contains_key(&entry.id))
+                .cloned()
+                .collect()
+        };
+        let total = entries.len();
+        if total == 0 {
+            return;
+        }
+        tracing::info!(total, "auto-connecting workspaces");
+        let permits = Arc::new(Semaphore::new(MAX_PARALLEL));
+        let completed = Arc::new(AtomicUsize::new(0));
+        let mut tasks = JoinSet::new();
+        for entry in entries {
+            let app = app.clone();
+            let permits = Arc::clone(&permits);
+            let completed = Arc::clone(&completed);
+            tasks.spawn(async move {
+                let _permit = permits.acquire_owned().await;
+                let workspace_id = entry.id.clone();
+                emit(
+                    &app,
+                    AutoConnectProgress {
+                        workspace_id: workspace_id.clone(),
+                        status: AutoConnectStatus::Connecting,
+                        error: None,
+                        completed: completed.load(Ordering::SeqCst),
+                        total,
+                    },
+                );
+                let state = app.state::<AppState>();
+                let connect = connect_entry(entry, &state, app.clone());
+                let error = match tokio::time::timeout(CONNECT_TIMEOUT, connect).await {
+                    Ok(Ok(())) => None,
+                    Ok(Err(err)) => Some(err.to_string()),
+                    Err(_) => Some("Timed out connecting the workspace.".to_string()),
+                };
+                if let Some(err) = &error {
+                    tracing::warn!(%workspace_id, "auto-connect failed: {err}");
+                }
+                let status = match error {
+                    Some(_) => AutoConnectStatus::Failed,
+                    None => AutoConnectStatus::Connected,
+                };
+                emit(
+                    &app,
+                    AutoConnectProgress {
+                        workspace_id,
+                        status,
+                        error,
+                        completed: completed.fetch_add(1, Ordering::SeqCst) + 1,
+                        total,
+                    },
+                );
+            });
+        }
+        while tasks.join_next().await.is_some() {}
+    });
+}
+
+#[tauri::command]
+pub(crate) async fn set_workspace_auto_connect(
+    id: String,
+    enabled: bool,
+    state: State<'_, AppState>,
+) -> Result<bool, AppError> {
+    let mut workspaces = state.workspaces.lock().await;
+    let entry = workspaces.get_mut(&id).ok_or(AppError::WorkspaceNotFound)?;
+    entry.auto_connect = enabled;
+    let list: Vec<_> = workspaces.values().cloned().collect();
+    write_workspaces(&state.storage_path, &list)?;
+    Ok(enabled)
+}
@@ -0,0 +1,93 @@
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, State};
+use tokio::sync::Mutex;
+
+use crate::error::AppError;
+use crate::thread_state;
+use crate::AppState;
+
+#[cfg(windows)]
+const OVERLAY_SIZE: u32 = 16;
+
+#[derive(Debug, Serialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BadgeCounts {
+    running_turns: usize,
+    pending_approvals: usize,
+    count: usize,
+}
+
+#[derive(Default)]
+pub(crate) struct Badge {
+    last: Mutex<Option<BadgeCounts>>,
+}
+
+async fn current_counts(state: &AppState) -> BadgeCounts {
+    let (running_turns, pending_approvals) = thread_state::activity_counts(state).await;
+    let config = state.settings.lock().await.notifications.clone();
+    let mut count = 0;
+    if config.turn_complete.badge {
+        count += running_turns;
+    }
+    if config.approval_needed.badge {
+        count += pending_approvals;
+    }
+    BadgeCounts {
+        running_turns,
+        pending_approvals,
+        count,
+    }
+}
+
+#[cfg(windows)]
+fn overlay_icon() -> tauri::image::Image<'static> {
+    let center = (OVERLAY_SIZE as f32 - 1.0) / 2.0;
+    let radius = OVERLAY_SIZE as f32 / 2.0;
+    let mut rgba = Vec::with_capacity((OVERLAY_SIZE * OVERLAY_SIZE * 4) as usize);
+    for y in 0..OVERLAY_SIZE {
+        for x in 0..OVERLAY_SIZE {
+            let dx = x as f32 - center;
+            let dy = y as f32 - center;
+            let alpha = if dx * dx + dy * dy <= radius * radius {
+                255
+            } else {
+                0
+            };
+            rgba.extend_from_slice(&[220, 38, 38, alpha]);
+        }
+    }
+    tauri::image::Image::new_owned(rgba, OVERLAY_SIZE, OVERLAY_SIZE)
+}
+
+#[cfg(windows)]
+fn apply_to_window(window: &tauri::WebviewWindow, count: usize) -> tauri::Result<()> {
+    window.set_overlay_icon((count > 0).then(overlay_icon))
+}
+
+#[cfg(not(windows))]
+fn apply_to_window(window: &tauri::WebviewWindow, count: usize) -> tauri::Result<()> {
+    window.set_badge_count((count > 0).then_some(count as i64))
+}
+
+pub(crate) async fn refresh(app: &AppHandle) {
+    let state = app.state::<AppState>();
+    let counts = current_counts(&state).await;
+    {
+        let mut last = state.badge.last.lock().await;
+        if *last == Some(counts) {
+            return;
+        }
+        *last = Some(counts);
+    }
+    if let Some(window) = app.get_webview_window("main") {
+        if let Err(err) = apply_to_window(&window, counts.count) {
+            tracing::debug!("failed to update badge: {err}");
+        }
+    }
+    let _ = app.emit("badge-updated", counts);
+}
+
+#[tauri::command]
+pub(crate) async fn get_badge_counts(state: State<'_, AppState>) -> Result<BadgeCounts, AppError> {
+    Ok(current_counts(&state).await)
+}
@@ -0,0 +1,360 @@
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use tauri::State;
+
+use crate::broadcast::{self, DeliveryStatus};
+use crate::error::AppError;
+use crate::metrics::{self, TurnFileChange};
+use crate::task_queue::{self, TaskStatus};
+use crate::{i18n, AppState};
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+enum BatchKind {
+    Broadcast,
+    Queue,
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+enum OutcomeStatus {
+    Pending,
+    Running,
+    Completed,
+    Failed,
+    Interrupted,
+    Cancelled,
+}
+
+impl OutcomeStatus {
+    fn label(self) -> &'static str {
+        match self {
+            OutcomeStatus::Pending => "pending",
+            OutcomeStatus::Running => "running",
+            OutcomeStatus::Completed => "completed",
+            OutcomeStatus::Failed => "failed",
+            OutcomeStatus::Interrupted => "interrupted",
+            OutcomeStatus::Cancelled => "cancelled",
+        }
+    }
+}
+
+impl From<DeliveryStatus> for OutcomeStatus {
+    fn from(status: DeliveryStatus) -> Self {
+        match status {
+            DeliveryStatus::Sending => OutcomeStatus::Pending,
+            DeliveryStatus::Running => OutcomeStatus::Running,
+            DeliveryStatus::Completed => OutcomeStatus::Completed,
+            DeliveryStatus::Failed => OutcomeStatus::Failed,
+            DeliveryStatus::Interrupted => OutcomeStatus::Interrupted,
+        }
+    }
+}
+
+impl From<TaskStatus> for OutcomeStatus {
+    fn from(status: TaskStatus) -> Self {
+        match status {
+            TaskStatus::Queued => OutcomeStatus::Pending,
+            TaskStatus::Running => OutcomeStatus::Running,
+            TaskStatus::Completed => OutcomeStatus::Completed,
+            TaskStatus::Failed => OutcomeStatus::Failed,
+            TaskStatus::Cancelled => OutcomeStatus::Cancelled,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct WorkspaceOutcome {
+    workspace_id: String,
+    workspace_name: String,
+    prompt: String,
+    status: OutcomeStatus,
+    thread_id: Option<String>,
+    turn_id: Option<String>,
+    error: Option<String>,
+    started_at_ms: Option<i64>,
+    finished_at_ms: Option<i64>,
+    duration_ms: Option<i64>,
+    tracked: bool,
+    files: Vec<TurnFileChange>,
+    additions: usize,
+    deletions: usize,
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+struct BatchTotals {
+    workspaces: usize,
+    completed: usize,
+    failed: usize,
+    in_progress: usize,
+    files_changed: usize,
+    additions: usize,
+    deletions: usize,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BatchReport {
+    batch_id: String,
+    kind: BatchKind,
+    prompt: Option<String>,
+    created_at_ms: i64,
+    completed_at_ms: Option<i64>,
+    totals: BatchTotals,
+    workspaces: Vec<WorkspaceOutcome>,
+}
+
+// Diff stats come from the turn metrics store; a turn without checkpoint
+// trees is reported as untracked rather than as having no changes.
+fn attach_changes(outcome: &mut WorkspaceOutcome, workspace_path: &str) {
+    let Some(thread_id) = outcome.thread_id.as_deref() else {
+        return;
+    };
+    let Ok(mut threads) = metrics::read_workspace_turns(workspace_path) else {
+        return;
+    };
+    let Some(turns) = threads.remove(thread_id) else {
+        return;
+    };
+    let started_at_ms = outcome.started_at_ms.unwrap_or(0);
+    let turn = turns
+        .into_iter()
+        .find(|turn| match outcome.turn_id.as_deref() {
+            Some(turn_id) => turn.turn_id.as_deref() == Some(turn_id),
+            None => turn.started_at_ms >= started_at_ms,
+        });
+    let Some(turn) = turn else {
+        return;
+    };
+    outcome.tracked = turn.before_tree.is_some() && turn.after_tree.is_some();
+    outcome.duration_ms = Some(turn.duration_ms);
+    outcome.additions = turn.changes.iter().map(|change| change.additions).sum();
+    outcome.deletions = turn.changes.iter().map(|change| change.deletions).sum();
+    outcome.files = turn.changes;
+}
+
+fn totals(outcomes: &[WorkspaceOutcome]) -> BatchTotals {
+    let count = |status: OutcomeStatus| {
+        outcomes
+            .iter()
+            .filter(|outcome| outcome.status == status)
+            .count()
+    };
+    BatchTotals {
+        workspaces: outcomes.len(),
+        completed: count(OutcomeStatus::Completed),
+        failed: count(OutcomeStatus::Failed),
+        in_progress: count(OutcomeStatus::Pending) + count(OutcomeStatus::Running),
+        files_changed: outcomes.iter().map(|outcome| outcome.files.len()).sum(),
+        additions: outcomes.iter().map(|outcome| outcome.additions).sum(),
+        deletions: outcomes.iter().map(|outcome| outcome.deletions).sum(),
+    }
+}
+
+async fn build_report(state: &AppState, batch_id: &str) -> Result<BatchReport, AppError> {
+    let paths: HashMap<String, String> = state
+        .workspaces
+        .lock()
+        .await
+        .values()
+        .map(|entry| (entry.id.clone(), entry.path.clone()))
+        .collect();
+    let (kind, prompt, created_at_ms, completed_at_ms, outcomes) =
+        if let Some(broadcast) = broadcast::find_broadcast(state, batch_id).await {
+            let outcomes: Vec<_> = broadcast
+                .targets
+                .into_iter()
+                .map(|target| WorkspaceOutcome {
+                    workspace_id: target.workspace_id,
+                    workspace_name: target.workspace_name,
+                    prompt: broadcast.prompt.clone(),
+                    status: target.status.into(),
+                    thread_id: target.thread_id,
+                    turn_id: target.turn_id,
+                    error: target.error,
+                    started_at_ms: target.started_at_ms,
+                    finished_at_ms: target.finished_at_ms,
+                    duration_ms: None,
+                    tracked: false,
+                    files: Vec::new(),
+                    additions: 0,
+                    deletions: 0,
+                })
+                .collect();
+            (
+                BatchKind::Broadcast,
+                Some(broadcast.prompt),
+                broadcast.created_at_ms,
+                broadcast.completed_at_ms,
+                outcomes,
+            )
+        } else {
+            let tasks = task_queue::batch_tasks(state, batch_id).await;
+            if tasks.is_empty() {
+                return Err(format!("batch not found: {batch_id}").into());
+            }
+            let created_at_ms = tasks
+                .iter()
+                .map(|(_, task)| task.created_at_ms)
+                .min()
+                .unwrap_or_default();
+            let finished: Option<Vec<i64>> =
+                tasks.iter().map(|(_, task)| task.finished_at_ms).collect();
+            let completed_at_ms = finished.and_then(|times| times.into_iter().max());
+            let first_prompt = tasks[0].1.prompt.clone();
+            let prompt = tasks
+                .iter()
+                .all(|(_, task)| task.prompt == first_prompt)
+                .then_some(first_prompt);
+            let outcomes: Vec<_> = tasks
+                .into_iter()
+                .map(|(entry, task)| WorkspaceOutcome {
+                    workspace_id: entry.id,
+                    workspace_name: entry.name,
+                    prompt: task.prompt,
+                    status: task.status.into(),
+                    thread_id: task.thread_id,
+                    turn_id: task.turn_id,
+                    error: task.error,
+                    started_at_ms: task.started_at_ms,
+                    finished_at_ms: task.finished_at_ms,
+                    duration_ms: None,
+                    tracked: false,
+                    files: Vec::new(),
+                    additions: 0,
+                    deletions: 0,
+                })
+                .collect();
+            (
+                BatchKind::Queue,
+                prompt,
+                created_at_ms,
+                completed_at_ms,
+                outcomes,
+            )
+        };
+
+    let outcomes = tokio::task::spawn_blocking(move || {
+        outcomes
+            .into_iter()
+            .map(|mut outcome| {
+                if let Some(path) = paths.get(&outcome.workspace_id) {
+                    attach_changes(&mut outcome, path);
+                }
+                outcome
+            })
+            .collect::<Vec<_>>()
+    })
+    .await?;
+    Ok(BatchReport {
+        batch_id: batch_id.to_string(),
+        kind,
+        prompt,
+        created_at_ms,
+        completed_at_ms,
+        totals: totals(&outcomes),
+        workspaces: outcomes,
+    })
+}
+
+fn to_markdown(report: &BatchReport, lang: &str) -> String {
+    let totals = &report.totals;
+    let mut out = String::from("# Batch report\n\n");
+    if let Some(prompt) = &report.prompt {
+        out.push_str(&format!("> {}\n\n", prompt.trim().replace('\n', "\n> ")));
+    }
+    if let Some(started) = i18n::format_timestamp_ms(lang, report.created_at_ms) {
+        out.push_str(&format!("- Started: {started}\n"));
+    }
+    if let Some(finished) = report
+        .completed_at_ms
+        .and_then(|at| i18n::format_timestamp_ms(lang, at))
+    {
+        out.push_str(&format!("- Finished: {finished}\n"));
+    }
+    out.push_str(&format!(
+        "- Workspaces: {} ({} completed, {} failed, {} in progress)\n",
+        totals.workspaces, totals.completed, totals.failed, totals.in_progress
+    ));
+    out.push_str(&format!(
+        "- Changes: {} files, +{} -{}\n",
+        totals.files_changed, totals.additions, totals.deletions
+    ));
+
+    for outcome in &report.workspaces {
+        out.push_str(&format!(
+            "\n## {} ({})\n\n",
+            outcome.workspace_name,
+            outcome.status.label()
+        ));
+        if report.prompt.is_none() {
+            out.push_str(&format!(
+                "> {}\n\n",
+                outcome.prompt.trim().replace('\n', "\n> ")
+            ));
+        }
+        if let Some(thread_id) = &outcome.thread_id {
+            out.push_str(&format!("- Thread: `{thread_id}`\n"));
+        }
+        if let Some(duration_ms) = outcome.duration_ms {
+            let duration = i18n::format_duration(lang, duration_ms);
+            out.push_str(&format!("- Duration: {duration}\n"));
+        }
+        if let Some(error) = &outcome.error {
+            out.push_str(&format!("- Error: {}\n", error.trim()));
+        }
+        if outcome.files.is_empty() {
+            let note = if outcome.tracked || outcome.status != OutcomeStatus::Completed {
+                "- No file changes\n"
+            } else {
+                "- File changes were not tracked for this turn\n"
+            };
+            out.push_str(note);
+            continue;
+        }
+        out.push_str(&format!(
+            "- Changes: {} files, +{} -{}\n\n",
+            outcome.files.len(),
+            outcome.additions,
+            outcome.deletions
+        ));
+        out.push_str("| File | Status | + | - |\n| --- | --- | ---: | ---: |\n");
+        for change in &outcome.files {
+            let path = match &change.old_path {
+                Some(old_path) => format!("{old_path} → {}", change.path),
+                None => change.path.clone(),
+            };
+            out.push_str(&format!(
+                "| `{path}` | {} | {} | {} |\n",
+                change.status, change.additions, change.deletions
+            ));
+        }
+    }
+    out
+}
+
+#[tauri::command]
+pub(crate) async fn get_batch_report(
+    batch_id: String,
+    state: State<'_, AppState>,
+) -> Result<BatchReport, AppError> {
+    build_report(&state, &batch_id).await
+}
+
+#[tauri::command]
+pub(crate) async fn export_batch_report_markdown(
+    batch_id: String,
+    path: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<String, AppError> {
+    let report = build_report(&state, &batch_id).await?;
+    let lang = i18n::app_lang(&state).await;
+    let markdown = to_markdown(&report, lang);
+    if let Some(path) = path {
+        fs::write(&path, &markdown)?;
+    }
+    Ok(markdown)
+}
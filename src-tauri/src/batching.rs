@@ -0,0 +1,140 @@
+use serde_json::Value;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::mpsc;
+use tokio::time::Instant;
+
+use crate::thread_state::str_at;
+use crate::{event_blobs, AppServerEvent, AppState};
+
+const MAX_BATCH_WINDOW_MS: u64 = 250;
+const DELTA_METHODS: &[&str] = &[
+    "item/agentMessage/delta",
+    "item/reasoning/summaryTextDelta",
+    "item/reasoning/textDelta",
+    "item/commandExecution/outputDelta",
+    "item/fileChange/outputDelta",
+];
+
+pub(crate) fn default_event_batch_window_ms() -> u64 {
+    16
+}
+
+#[derive(PartialEq, Eq)]
+struct DeltaKey {
+    method: String,
+    thread_id: String,
+    item_id: String,
+}
+
+struct PendingDelta {
+    key: DeltaKey,
+    message: Value,
+}
+
+pub(crate) struct EventBatcher {
+    tx: mpsc::UnboundedSender<Value>,
+}
+
+impl EventBatcher {
+    pub(crate) fn spawn(app: AppHandle, workspace_id: String) -> Self {
+        let (tx, rx) = mpsc::unbounded_channel();
+        tauri::async_runtime::spawn(run(app, workspace_id, rx));
+        Self { tx }
+    }
+
+    pub(crate) fn send(&self, message: Value) {
+        let _ = self.tx.send(message);
+    }
+}
+
+fn delta_key(message: &Value) -> Option<DeltaKey> {
+    let method = str_at(message, "/method")?;
+    if !DELTA_METHODS.contains(&method) {
+        return None;
+    }
+    str_at(message, "/params/delta")?;
+    Some(DeltaKey {
+        method: method.to_string(),
+        thread_id: str_at(message, "/params/threadId")?.to_string(),
+        item_id: str_at(message, "/params/itemId")?.to_string(),
+    })
+}
+
+// Deltas only merge into the latest pending entry of the same thread, so
+// each thread still sees its events in order; different threads coalesce
+// independently.
+fn merge(pending: &mut Vec<PendingDelta>, key: DeltaKey, message: Value) {
+    let latest = pending
+        .iter_mut()
+        .rev()
+        .find(|entry| entry.key.thread_id == key.thread_id)
+        .filter(|entry| entry.key == key);
+    if let Some(entry) = latest {
+        let delta = str_at(&message, "/params/delta").unwrap_or_default();
+        let merged = format!(
+            "{}{delta}",
+            str_at(&entry.message, "/params/delta").unwrap_or_default()
+        );
+        entry.message["params"]["delta"] = Value::String(merged);
+        return;
+    }
+    pending.push(PendingDelta { key, message });
+}
+
+fn emit(app: &AppHandle, workspace_id: &str, message: Value) {
+    let payload = AppServerEvent {
+        workspace_id: workspace_id.to_string(),
+        message: event_blobs::spill(app, message),
+    };
+    let _ = app.emit("app-server-event", payload);
+}
+
+fn flush(app: &AppHandle, workspace_id: &str, pending: &mut Vec<PendingDelta>) {
+    for entry in pending.drain(..) {
+        emit(app, workspace_id, entry.message);
+    }
+}
+
+async fn window(app: &AppHandle) -> Duration {
+    let state = app.state::<AppState>();
+    let window_ms = state.settings.lock().await.event_batch_window_ms;
+    Duration::from_millis(window_ms.min(MAX_BATCH_WINDOW_MS))
+}
+
+async fn run(app: AppHandle, workspace_id: String, mut rx: mpsc::UnboundedReceiver<Value>) {
+    let mut pending: Vec<PendingDelta> = Vec::new();
+    let mut deadline: Option<Instant> = None;
+    loop {
+        let next = match deadline {
+            Some(at) => match tokio::time::timeout_at(at, rx.recv()).await {
+                Ok(next) => next,
+                Err(_) => {
+                    flush(&app, &workspace_id, &mut pending);
+                    deadline = None;
+                    continue;
+                }
+            },
+            None => rx.recv().await,
+        };
+        let Some(message) = next else {
+            break;
+        };
+        if let Some(key) = delta_key(&message) {
+            if deadline.is_none() {
+                let window = window(&app).await;
+                if window.is_zero() {
+                    emit(&app, &workspace_id, message);
+                    continue;
+                }
+                deadline = Some(Instant::now() + window);
+            }
+            merge(&mut pending, key, message);
+            continue;
+        }
+        flush(&app, &workspace_id, &mut pending);
+        deadline = None;
+        emit(&app, &workspace_id, message);
+    }
+    flush(&app, &workspace_id, &mut pending);
+}
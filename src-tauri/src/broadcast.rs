@@ -0,0 +1,369 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter, Manager, State};
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::scheduler::{self, PromptOptions};
+use crate::thread_state::{message_thread_id, str_at};
+use crate::{default_session_store_version, now_ms, storage, AppState};
+
+const MAX_BROADCASTS: usize = 50;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum DeliveryStatus {
+    Sending,
+    Running,
+    Completed,
+    Failed,
+    Interrupted,
+}
+
+impl DeliveryStatus {
+    fn is_finished(self) -> bool {
+        !matches!(self, DeliveryStatus::Sending | DeliveryStatus::Running)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BroadcastTarget {
+    pub(crate) workspace_id: String,
+    pub(crate) workspace_name: String,
+    pub(crate) status: DeliveryStatus,
+    #[serde(default)]
+    pub(crate) thread_id: Option<String>,
+    #[serde(default)]
+    pub(crate) turn_id: Option<String>,
+    #[serde(default)]
+    pub(crate) error: Option<String>,
+    #[serde(default)]
+    pub(crate) started_at_ms: Option<i64>,
+    #[serde(default)]
+    pub(crate) finished_at_ms: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Broadcast {
+    pub(crate) id: String,
+    pub(crate) prompt: String,
+    #[serde(default)]
+    options: PromptOptions,
+    pub(crate) created_at_ms: i64,
+    #[serde(default)]
+    pub(crate) completed_at_ms: Option<i64>,
+    pub(crate) targets: Vec<BroadcastTarget>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct BroadcastStore {
+    #[serde(default = "default_session_store_version")]
+    version: u32,
+    #[serde(default)]
+    broadcasts: Vec<Broadcast>,
+}
+
+impl Default for BroadcastStore {
+    fn default() -> Self {
+        Self {
+            version: default_session_store_version(),
+            broadcasts: Vec::new(),
+        }
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct Broadcasts {
+    lock: Mutex<()>,
+    inflight: Mutex<HashMap<(String, String), String>>,
+}
+
+fn store_path(state: &AppState) -> PathBuf {
+    state.data_dir.join("broadcasts.json")
+}
+
+fn read_store(path: &Path) -> Result<BroadcastStore, String> {
+    storage::read_json_or_default(path)
+}
+
+fn write_store(path: &Path, store: &BroadcastStore) -> Result<(), String> {
+    storage::write_json_atomic(path, store)
+}
+
+pub(crate) async fn find_broadcast(state: &AppState, batch_id: &str) -> Option<Broadcast> {
+    let _guard = state.broadcasts.lock.lock().await;
+    read_store(&store_path(state))
+        .ok()?
+        .broadcasts
+        .into_iter()
+        .find(|broadcast| broadcast.id == batch_id)
+}
+
+async fn update_target(
+    app: &AppHandle,
+    batch_id: &str,
+    workspace_id: &str,
+    update: impl FnOnce(&mut BroadcastTarget),
+) {
+    let state = app.state::<AppState>();
+    let path = store_path(&state);
+    let _guard = state.broadcasts.lock.lock().await;
+    let mut store = match read_store(&path) {
+        Ok(store) => store,
+        Err(err) => {
+            tracing::warn!(batch_id, "failed to read broadcasts: {err}");
+            return;
+        }
+    };
+    let Some(broadcast) = store
+        .broadcasts
+        .iter_mut()
+        .find(|broadcast| broadcast.id == batch_id)
+    else {
+        return;
+    };
+    let Some(target) = broadcast
+        .targets
+        .iter_mut()
+        .find(|target| target.workspace_id == workspace_id)
+    else {
+        return;
+    };
+    update(target);
+    let just_completed = broadcast.completed_at_ms.is_none()
+        && broadcast
+            .targets
+            .iter()
+            .all(|target| target.status.is_finished());
+    if just_completed {
+        broadcast.completed_at_ms = Some(now_ms());
+    }
+    let broadcast = broadcast.clone();
+    if let Err(err) = write_store(&path, &store) {
+        tracing::warn!(batch_id, "failed to persist broadcast: {err}");
+    }
+    let _ = app.emit("broadcast-updated", &broadcast);
+    if just_completed {
+        let count = |status: DeliveryStatus| {
+            broadcast
+                .targets
+                .iter()
+                .filter(|target| target.status == status)
+                .count()
+        };
+        let _ = app.emit(
+            "broadcast-completed",
+            json!({
+                "batchId": broadcast.id,
+                "total": broadcast.targets.len(),
+                "completed": count(DeliveryStatus::Completed),
+                "failed": count(DeliveryStatus::Failed),
+                "interrupted": count(DeliveryStatus::Interrupted),
+            }),
+        );
+    }
+}
+
+async fn deliver(
+    app: AppHandle,
+    batch_id: String,
+    target: BroadcastTarget,
+    prompt: String,
+    options: PromptOptions,
+) {
+    let workspace_id = target.workspace_id;
+    let started = scheduler::start_prompt(&app, &workspace_id, &prompt, &options).await;
+    match started {
+        Ok((thread_id, turn_id)) => {
+            let key = (workspace_id.clone(), thread_id.clone());
+            let state = app.state::<AppState>();
+            state
+                .broadcasts
+                .inflight
+                .lock()
+                .await
+                .insert(key, batch_id.clone());
+            update_target(&app, &batch_id, &workspace_id, |target| {
+                target.status = DeliveryStatus::Running;
+                target.thread_id = Some(thread_id);
+                target.turn_id = turn_id;
+            })
+            .await;
+        }
+        Err(err) => {
+            tracing::warn!(%batch_id, %workspace_id, "broadcast delivery failed: {err}");
+            update_target(&app, &batch_id, &workspace_id, |target| {
+                target.status = DeliveryStatus::Failed;
+                target.error = Some(err.to_string());
+                target.finished_at_ms = Some(now_ms());
+            })
+            .await;
+        }
+    }
+}
+
+pub(crate) async fn observe(app: &AppHandle, workspace_id: &str, message: &Value) {
+    if message.get("method").and_then(|method| method.as_str()) != Some("turn/completed") {
+        return;
+    }
+    let params = message.get("params").unwrap_or(&Value::Null);
+    let Some(thread_id) = message_thread_id(params) else {
+        return;
+    };
+    let state = app.state::<AppState>();
+    let key = (workspace_id.to_string(), thread_id);
+    let Some(batch_id) = state.broadcasts.inflight.lock().await.remove(&key) else {
+        return;
+    };
+    let status = str_at(params, "/turn/status").unwrap_or_default();
+    let error = str_at(params, "/turn/error/message").map(|error| error.to_string());
+    let turn_id = str_at(params, "/turn/id").map(|id| id.to_string());
+    update_target(app, &batch_id, workspace_id, |target| {
+        target.status = if status == "failed" || error.is_some() {
+            DeliveryStatus::Failed
+        } else if status == "interrupted" {
+            DeliveryStatus::Interrupted
+        } else {
+            DeliveryStatus::Completed
+        };
+        if status == "failed" && error.is_none() {
+            target.error = Some("Turn failed.".to_string());
+        } else {
+            target.error = error;
+        }
+        if turn_id.is_some() {
+            target.turn_id = turn_id;
+        }
+        target.finished_at_ms = Some(now_ms());
+    })
+    .await;
+}
+
+pub(crate) async fn session_exited(app: &AppHandle, workspace_id: &str) {
+    let state = app.state::<AppState>();
+    let batches: Vec<String> = {
+        let mut inflight = state.broadcasts.inflight.lock().await;
+        let keys: Vec<_> = inflight
+            .keys()
+            .filter(|(id, _)| id == workspace_id)
+            .cloned()
+            .collect();
+        keys.iter().filter_map(|key| inflight.remove(key)).collect()
+    };
+    for batch_id in batches {
+        update_target(app, &batch_id, workspace_id, |target| {
+            target.status = DeliveryStatus::Failed;
+            target.error = Some("The app-server exited during the turn.".to_string());
+            target.finished_at_ms = Some(now_ms());
+        })
+        .await;
+    }
+}
+
+#[tauri::command]
+pub(crate) async fn broadcast_message(
+    workspace_ids: Vec<String>,
+    prompt: String,
+    options: Option<PromptOptions>,
+    threads: Option<HashMap<String, String>>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Broadcast, AppError> {
+    if prompt.trim().is_empty() {
+        return Err("Broadcast prompt is required.".into());
+    }
+    let mut ids: Vec<String> = Vec::new();
+    for id in workspace_ids {
+        if !ids.contains(&id) {
+            ids.push(id);
+        }
+    }
+    if ids.is_empty() {
+        return Err("Select at least one workspace.".into());
+    }
+    let now = now_ms();
+    let targets = {
+        let workspaces = state.workspaces.lock().await;
+        ids.iter()
+            .map(|id| {
+                let entry = workspaces.get(id).ok_or(AppError::WorkspaceNotFound)?;
+                Ok(BroadcastTarget {
+                    workspace_id: id.clone(),
+                    workspace_name: entry.name.clone(),
+                    status: DeliveryStatus::Sending,
+                    thread_id: None,
+                    turn_id: None,
+                    error: None,
+                    started_at_ms: Some(now),
+                    finished_at_ms: None,
+                })
+            })
+            .collect::<Result<Vec<_>, AppError>>()?
+    };
+    // A single thread id can't apply across workspaces; reuse goes through
+    // the per-workspace `threads` map instead.
+    let options = options.unwrap_or_default().with_thread(None).normalized();
+    let broadcast = Broadcast {
+        id: Uuid::new_v4().to_string(),
+        prompt,
+        options,
+        created_at_ms: now,
+        completed_at_ms: None,
+        targets,
+    };
+    {
+        let path = store_path(&state);
+        let _guard = state.broadcasts.lock.lock().await;
+        let mut store = read_store(&path)?;
+        store.broadcasts.push(broadcast.clone());
+        if store.broadcasts.len() > MAX_BROADCASTS {
+            let excess = store.broadcasts.len() - MAX_BROADCASTS;
+            store.broadcasts.drain(..excess);
+        }
+        write_store(&path, &store)?;
+    }
+
+    let threads = threads.unwrap_or_default();
+    for target in broadcast.targets.clone() {
+        let thread_id = threads.get(&target.workspace_id).cloned();
+        let options = broadcast
+            .options
+            .clone()
+            .with_thread(thread_id)
+            .normalized();
+        tauri::async_runtime::spawn(deliver(
+            app.clone(),
+            broadcast.id.clone(),
+            target,
+            broadcast.prompt.clone(),
+            options,
+        ));
+    }
+    Ok(broadcast)
+}
+
+#[tauri::command]
+pub(crate) async fn list_broadcasts(
+    state: State<'_, AppState>,
+) -> Result<Vec<Broadcast>, AppError> {
+    let _guard = state.broadcasts.lock.lock().await;
+    let mut broadcasts = read_store(&store_path(&state))?.broadcasts;
+    broadcasts.reverse();
+    Ok(broadcasts)
+}
+
+#[tauri::command]
+pub(crate) async fn get_broadcast(
+    batch_id: String,
+    state: State<'_, AppState>,
+) -> Result<Broadcast, AppError> {
+    find_broadcast(&state, &batch_id)
+        .await
+        .ok_or_else(|| format!("broadcast not found: {batch_id}").into())
+}
@@ -0,0 +1,185 @@
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+use tauri::State;
+
+use crate::error::AppError;
+use crate::AppState;
+
+const METHOD_NOT_FOUND: i64 = -32601;
+
+// Methods that only some codex releases implement; everything else is
+// assumed to be part of the baseline protocol.
+const OPTIONAL_METHODS: &[&str] = &[
+    "account/rateLimits/read",
+    "account/read",
+    "model/list",
+    "review/start",
+    "skills/list",
+    "thread/archive",
+    "thread/compact",
+    "thread/delete",
+];
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum Support {
+    Supported,
+    Unsupported,
+    Unknown,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct WorkspaceCapabilities {
+    workspace_id: String,
+    codex_version: Option<String>,
+    user_agent: Option<String>,
+    methods: BTreeMap<String, Support>,
+    initialize: Value,
+}
+
+#[derive(Default)]
+struct CapabilityState {
+    initialize: Value,
+    declared: HashMap<String, bool>,
+    learned: HashMap<String, bool>,
+}
+
+#[derive(Default)]
+pub(crate) struct SessionCapabilities {
+    state: Mutex<CapabilityState>,
+}
+
+fn declared_methods(result: &Value) -> HashMap<String, bool> {
+    let mut declared = HashMap::new();
+    let Some(capabilities) = result.get("capabilities") else {
+        return declared;
+    };
+    let listed = capabilities.get("methods").unwrap_or(capabilities);
+    match listed {
+        Value::Array(methods) => {
+            for method in methods.iter().filter_map(Value::as_str) {
+                declared.insert(method.to_string(), true);
+            }
+            for method in OPTIONAL_METHODS {
+                declared.entry(method.to_string()).or_insert(false);
+            }
+        }
+        Value::Object(methods) => {
+            for (method, value) in methods {
+                if let Some(supported) = value.as_bool() {
+                    declared.insert(method.clone(), supported);
+                }
+            }
+        }
+        _ => {}
+    }
+    declared
+}
+
+impl SessionCapabilities {
+    fn lock(&self) -> std::sync::MutexGuard<'_, CapabilityState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub(crate) fn record_initialize(&self, response: &Value) {
+        let result = response.get("result").cloned().unwrap_or(Value::Null);
+        let mut state = self.lock();
+        state.declared = declared_methods(&result);
+        state.initialize = result;
+    }
+
+    fn support(&self, method: &str) -> Support {
+        let state = self.lock();
+        let known = state
+            .learned
+            .get(method)
+            .or_else(|| state.declared.get(method));
+        match known {
+            Some(true) => Support::Supported,
+            Some(false) => Support::Unsupported,
+            None => Support::Unknown,
+        }
+    }
+
+    pub(crate) fn check(&self, method: &str, codex_version: Option<&str>) -> Result<(), AppError> {
+        if self.support(method) == Support::Unsupported {
+            return Err(AppError::unsupported(method, codex_version));
+        }
+        Ok(())
+    }
+
+    pub(crate) fn observe_response(
+        &self,
+        method: &str,
+        response: &Value,
+        codex_version: Option<&str>,
+    ) -> Result<(), AppError> {
+        let code = response
+            .get("error")
+            .map(|error| error.get("code").and_then(Value::as_i64));
+        let supported = match code {
+            None => true,
+            Some(Some(METHOD_NOT_FOUND)) => false,
+            Some(_) => return Ok(()),
+        };
+        self.lock().learned.insert(method.to_string(), supported);
+        if supported {
+            Ok(())
+        } else {
+            tracing::info!(method, "codex does not implement method");
+            Err(AppError::unsupported(method, codex_version))
+        }
+    }
+
+    fn snapshot(&self, workspace_id: &str, codex_version: Option<String>) -> WorkspaceCapabilities {
+        let (observed, initialize) = {
+            let state = self.lock();
+            let observed: Vec<String> = state
+                .declared
+                .keys()
+                .chain(state.learned.keys())
+                .cloned()
+                .collect();
+            (observed, state.initialize.clone())
+        };
+        let methods = OPTIONAL_METHODS
+            .iter()
+            .map(|method| method.to_string())
+            .chain(observed)
+            .map(|method| {
+                let support = self.support(&method);
+                (method, support)
+            })
+            .collect();
+        WorkspaceCapabilities {
+            workspace_id: workspace_id.to_string(),
+            codex_version,
+            user_agent: initialize
+                .get("userAgent")
+                .and_then(Value::as_str)
+                .map(|agent| agent.to_string()),
+            methods,
+            initialize,
+        }
+    }
+}
+
+#[tauri::command]
+pub(crate) async fn get_workspace_capabilities(
+    workspace_id: String,
+    state: State<'_, AppState>,
+) -> Result<WorkspaceCapabilities, AppError> {
+    let session = state
+        .sessions
+        .lock()
+        .await
+        .get(&workspace_id)
+        .cloned()
+        .ok_or(AppError::NotConnected)?;
+    Ok(session
+        .capabilities
+        .snapshot(&workspace_id, session.codex_version.clone()))
+}
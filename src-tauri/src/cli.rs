@@ -0,0 +1,147 @@
+use serde_json::{json, Map, Value};
+use std::env;
+use std::path::PathBuf;
+
+use crate::ipc;
+
+const APP_IDENTIFIER: &str = "com.huzi.codexola";
+
+const USAGE: &str = "Usage:
+  codexola send --workspace <name|id|path> --prompt <text> [--thread <id>]
+                [--model <id>] [--effort <level>] [--access-mode <mode>]
+  codexola status
+
+Options:
+  --data-dir <path>  Talk to the instance using this data directory";
+
+fn default_app_data_dir() -> Option<PathBuf> {
+    let home = env::var_os("HOME").map(PathBuf::from);
+    let base = if cfg!(target_os = "macos") {
+        home.map(|home| home.join("Library").join("Application Support"))
+    } else if cfg!(windows) {
+        env::var_os("APPDATA").map(PathBuf::from)
+    } else {
+        env::var_os("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .or_else(|| home.map(|home| home.join(".local").join("share")))
+    };
+    base.map(|base| base.join(APP_IDENTIFIER))
+}
+
+fn parse_options(args: &[String]) -> Result<Map<String, Value>, String> {
+    let mut options = Map::new();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let Some(flag) = arg.strip_prefix("--") else {
+            return Err(format!("unexpected argument: {arg}"));
+        };
+        let (name, value) = match flag.split_once('=') {
+            Some((name, value)) => (name.to_string(), value.to_string()),
+            None => {
+                let value = iter
+                    .next()
+                    .ok_or_else(|| format!("missing value for --{flag}"))?;
+                (flag.to_string(), value.clone())
+            }
+        };
+        let key = match name.as_str() {
+            "workspace" => "workspace",
+            "prompt" => "prompt",
+            "thread" => "threadId",
+            "model" => "model",
+            "effort" => "effort",
+            "access-mode" => "accessMode",
+            "data-dir" => continue,
+            _ => return Err(format!("unknown option: --{name}")),
+        };
+        options.insert(key.to_string(), json!(value));
+    }
+    Ok(options)
+}
+
+fn build_request(command: &str, args: &[String]) -> Result<Value, String> {
+    let mut request = parse_options(args)?;
+    if command == "send" {
+        for required in ["workspace", "prompt"] {
+            if !request.contains_key(required) {
+                return Err(format!("send requires --{required}"));
+            }
+        }
+    }
+    request.insert("type".to_string(), json!(command));
+    Ok(Value::Object(request))
+}
+
+fn print_result(command: &str, result: &Value) {
+    if command != "status" {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(result).unwrap_or_default()
+        );
+        return;
+    }
+    if let Some(version) = result.get("version").and_then(|value| value.as_str()) {
+        println!("Codexola {version}");
+    }
+    let workspaces = result
+        .get("workspaces")
+        .and_then(|value| value.as_array())
+        .cloned()
+        .unwrap_or_default();
+    for workspace in workspaces {
+        let connected = workspace["connected"].as_bool().unwrap_or(false);
+        println!(
+            "{} {}\t{}\t{}",
+            if connected { "*" } else { "-" },
+            workspace["name"].as_str().unwrap_or_default(),
+            workspace["id"].as_str().unwrap_or_default(),
+            workspace["path"].as_str().unwrap_or_default(),
+        );
+    }
+}
+
+fn run_command(command: &str, args: &[String]) -> i32 {
+    let request = match build_request(command, args) {
+        Ok(request) => request,
+        Err(err) => {
+            eprintln!("{err}\n\n{USAGE}");
+            return 2;
+        }
+    };
+    let Some(data_dir) = crate::data_dir_override().or_else(default_app_data_dir) else {
+        eprintln!("unable to resolve the Codexola data directory");
+        return 1;
+    };
+    let response = match ipc::send_request(&data_dir, &request) {
+        Ok(response) => response,
+        Err(err) => {
+            eprintln!("Codexola is not running ({err})");
+            return 1;
+        }
+    };
+    if response.get("ok").and_then(|value| value.as_bool()) == Some(true) {
+        print_result(command, response.get("result").unwrap_or(&Value::Null));
+        return 0;
+    }
+    let error = response.get("error").cloned().unwrap_or(Value::Null);
+    let message = error
+        .get("message")
+        .and_then(|value| value.as_str())
+        .or_else(|| error.as_str())
+        .unwrap_or("request failed");
+    eprintln!("{message}");
+    1
+}
+
+pub(crate) fn run_from_args() -> Option<i32> {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let command = args.first()?.as_str();
+    match command {
+        "send" | "status" => Some(run_command(command, &args[1..])),
+        "help" | "--help" | "-h" => {
+            println!("{USAGE}");
+            Some(0)
+        }
+        _ => None,
+    }
+}
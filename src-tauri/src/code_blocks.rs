@@ -0,0 +1,103 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs::{self, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Component, Path};
+use tauri::State;
+
+use crate::error::AppError;
+use crate::{
+    get_git_status, is_monitor_path, mark_file_index_stale, normalize_path, observer,
+    resolve_workspace_path, workspace_entry, AppState,
+};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum SaveMode {
+    Create,
+    Overwrite,
+    Append,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct SavedCodeBlock {
+    path: String,
+    mode: SaveMode,
+    created: bool,
+    bytes_written: usize,
+    git_status: Option<Value>,
+}
+
+fn write_block(target: &Path, content: &str, mode: SaveMode) -> Result<(bool, usize), String> {
+    let existed = target.exists();
+    if existed && target.is_dir() {
+        return Err("path is a directory".to_string());
+    }
+    if existed && mode == SaveMode::Create {
+        return Err("file already exists".to_string());
+    }
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent).map_err(|err| err.to_string())?;
+    }
+    let mut bytes = content.as_bytes().to_vec();
+    if mode == SaveMode::Append {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .read(true)
+            .append(true)
+            .open(target)
+            .map_err(|err| err.to_string())?;
+        // Keep the appended block on its own line.
+        if file.metadata().map(|meta| meta.len()).unwrap_or(0) > 0 {
+            let mut last = [0u8; 1];
+            file.seek(SeekFrom::End(-1))
+                .and_then(|_| file.read_exact(&mut last))
+                .map_err(|err| err.to_string())?;
+            if last[0] != b'\n' {
+                bytes.insert(0, b'\n');
+            }
+        }
+        file.write_all(&bytes).map_err(|err| err.to_string())?;
+    } else {
+        fs::write(target, &bytes).map_err(|err| err.to_string())?;
+    }
+    Ok((!existed, bytes.len()))
+}
+
+#[tauri::command]
+pub(crate) async fn save_code_block(
+    workspace_id: String,
+    relative_path: String,
+    content: String,
+    mode: SaveMode,
+    state: State<'_, AppState>,
+) -> Result<SavedCodeBlock, AppError> {
+    let entry = workspace_entry(&state, &workspace_id).await?;
+    observer::ensure_writable(&entry, "saving files")?;
+    let relative = relative_path.trim();
+    if relative.is_empty() {
+        return Err("File path is required.".into());
+    }
+    let requested = Path::new(relative);
+    if requested
+        .components()
+        .any(|component| matches!(component, Component::Normal(part) if part == ".git"))
+        || is_monitor_path(requested)
+    {
+        return Err("Cannot write inside .git or .codexmonitor.".into());
+    }
+    let target = resolve_workspace_path(&entry.path, relative)?;
+    let (created, bytes_written) =
+        tokio::task::spawn_blocking(move || write_block(&target, &content, mode)).await??;
+
+    mark_file_index_stale(&state, &workspace_id).await;
+    let git_status = get_git_status(workspace_id, state).await.ok();
+    Ok(SavedCodeBlock {
+        path: normalize_path(requested),
+        mode,
+        created,
+        bytes_written,
+        git_status,
+    })
+}
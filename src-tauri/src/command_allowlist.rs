@@ -0,0 +1,243 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::path::PathBuf;
+use tauri::{AppHandle, Emitter, State};
+
+use crate::error::AppError;
+use crate::thread_state::{message_thread_id, str_at};
+use crate::{
+    audit, hooks, storage, workspace_entry, write_settings, AppState, WorkspaceSession,
+};
+
+const SHELL_OPERATORS: &[&str] = &[";", "&", "|", ">", "<", "`", "$(", "\n"];
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum AllowlistScope {
+    Global,
+    Workspace,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+struct WorkspaceAllowlist {
+    #[serde(default)]
+    patterns: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct AllowlistEntry {
+    pattern: String,
+    scope: AllowlistScope,
+}
+
+fn workspace_allowlist_path(workspace_path: &str) -> PathBuf {
+    PathBuf::from(workspace_path)
+        .join(".codexmonitor")
+        .join("command-allowlist.json")
+}
+
+fn read_workspace_allowlist(workspace_path: &str) -> Result<WorkspaceAllowlist, String> {
+    storage::read_json_or_default(&workspace_allowlist_path(workspace_path))
+}
+
+fn write_workspace_allowlist(
+    workspace_path: &str,
+    allowlist: &WorkspaceAllowlist,
+) -> Result<(), String> {
+    storage::write_json_atomic(&workspace_allowlist_path(workspace_path), allowlist)
+}
+
+fn has_shell_operators(command: &str) -> bool {
+    SHELL_OPERATORS
+        .iter()
+        .any(|operator| command.contains(operator))
+}
+
+fn normalize_pattern(pattern: &str) -> Result<String, AppError> {
+    let pattern = pattern.split_whitespace().collect::<Vec<_>>().join(" ");
+    if pattern.is_empty() || pattern == "*" {
+        return Err("Allowlist patterns must name a command.".into());
+    }
+    if has_shell_operators(&pattern) {
+        return Err(format!("Allowlist patterns cannot contain shell operators: {pattern}").into());
+    }
+    Ok(pattern)
+}
+
+// Patterns match whole tokens; a trailing `*` also allows any further
+// arguments. Commands chaining or redirecting never match, so allowing
+// `npm test` can't approve `npm test && rm -rf .`.
+pub(crate) fn matching_pattern<'a>(command: &str, patterns: &'a [String]) -> Option<&'a str> {
+    if has_shell_operators(command) {
+        return None;
+    }
+    let tokens: Vec<&str> = command.split_whitespace().collect();
+    patterns
+        .iter()
+        .find(|pattern| {
+            let parts: Vec<&str> = pattern.split_whitespace().collect();
+            match parts.split_last() {
+                Some((&"*", prefix)) => !prefix.is_empty() && tokens.starts_with(prefix),
+                Some(_) => tokens == parts,
+                None => false,
+            }
+        })
+        .map(String::as_str)
+}
+
+pub(crate) async fn patterns_for(state: &AppState, workspace_path: &str) -> Vec<String> {
+    let mut patterns = state
+        .settings
+        .lock()
+        .await
+        .approval_command_allowlist
+        .clone();
+    match read_workspace_allowlist(workspace_path) {
+        Ok(allowlist) => patterns.extend(allowlist.patterns),
+        Err(err) => tracing::warn!(workspace_path, "invalid command allowlist: {err}"),
+    }
+    patterns
+}
+
+// Runs after approval_context::enrich, which resolves the command and the
+// matching pattern across both scopes. Called from the session's reader task,
+// so it must never wait on `state.sessions`.
+pub(crate) async fn intercept(
+    app: &AppHandle,
+    session: &WorkspaceSession,
+    message: &Value,
+) -> bool {
+    if hooks::approval_request_method(message).is_none() {
+        return false;
+    }
+    let Some(request_id) = message.get("id").and_then(Value::as_u64) else {
+        return false;
+    };
+    let params = message.get("params").unwrap_or(&Value::Null);
+    let Some(pattern) = str_at(params, "/approvalContext/command/allowlistPattern") else {
+        return false;
+    };
+    let workspace_id = session.entry.id.as_str();
+    let result = json!({ "decision": "accept" });
+    if let Err(err) = session.send_response(request_id, result.clone()).await {
+        tracing::warn!(%workspace_id, request_id, "failed to accept allowlisted command: {err}");
+        return false;
+    }
+    audit::record_decision(app, workspace_id, request_id, &result).await;
+    let _ = app.emit(
+        "command-allowlist-approved",
+        json!({
+            "workspaceId": workspace_id,
+            "threadId": message_thread_id(params),
+            "requestId": request_id,
+            "command": str_at(params, "/approvalContext/command/command"),
+            "pattern": pattern,
+        }),
+    );
+    true
+}
+
+async fn list_entries(state: &AppState, workspace_path: Option<&str>) -> Vec<AllowlistEntry> {
+    let global = state
+        .settings
+        .lock()
+        .await
+        .approval_command_allowlist
+        .clone();
+    let mut entries: Vec<AllowlistEntry> = global
+        .into_iter()
+        .map(|pattern| AllowlistEntry {
+            pattern,
+            scope: AllowlistScope::Global,
+        })
+        .collect();
+    if let Some(workspace_path) = workspace_path {
+        let patterns = read_workspace_allowlist(workspace_path)
+            .map(|allowlist| allowlist.patterns)
+            .unwrap_or_default();
+        entries.extend(patterns.into_iter().map(|pattern| AllowlistEntry {
+            pattern,
+            scope: AllowlistScope::Workspace,
+        }));
+    }
+    entries
+}
+
+async fn update_allowlist(
+    app: &AppHandle,
+    state: &AppState,
+    scope: AllowlistScope,
+    workspace_id: Option<String>,
+    change: impl FnOnce(&mut Vec<String>),
+) -> Result<Vec<AllowlistEntry>, AppError> {
+    let workspace_path = match workspace_id {
+        Some(workspace_id) => Some(workspace_entry(state, &workspace_id).await?.path),
+        None => None,
+    };
+    match scope {
+        AllowlistScope::Global => {
+            let settings = {
+                let mut settings = state.settings.lock().await;
+                change(&mut settings.approval_command_allowlist);
+                write_settings(&state.settings_path, &settings)?;
+                settings.clone()
+            };
+            let _ = app.emit("settings-updated", settings);
+        }
+        AllowlistScope::Workspace => {
+            let Some(workspace_path) = workspace_path.as_deref() else {
+                return Err("Workspace allowlist entries need a workspace.".into());
+            };
+            let mut allowlist = read_workspace_allowlist(workspace_path)?;
+            change(&mut allowlist.patterns);
+            write_workspace_allowlist(workspace_path, &allowlist)?;
+        }
+    }
+    Ok(list_entries(state, workspace_path.as_deref()).await)
+}
+
+#[tauri::command]
+pub(crate) async fn list_command_allowlist(
+    workspace_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<Vec<AllowlistEntry>, AppError> {
+    let workspace_path = match workspace_id {
+        Some(workspace_id) => Some(workspace_entry(&state, &workspace_id).await?.path),
+        None => None,
+    };
+    Ok(list_entries(&state, workspace_path.as_deref()).await)
+}
+
+#[tauri::command]
+pub(crate) async fn add_command_allowlist_entry(
+    pattern: String,
+    scope: AllowlistScope,
+    workspace_id: Option<String>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Vec<AllowlistEntry>, AppError> {
+    let pattern = normalize_pattern(&pattern)?;
+    update_allowlist(&app, &state, scope, workspace_id, |patterns| {
+        if !patterns.contains(&pattern) {
+            patterns.push(pattern);
+        }
+    })
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn remove_command_allowlist_entry(
+    pattern: String,
+    scope: AllowlistScope,
+    workspace_id: Option<String>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Vec<AllowlistEntry>, AppError> {
+    let pattern = pattern.split_whitespace().collect::<Vec<_>>().join(" ");
+    update_allowlist(&app, &state, scope, workspace_id, |patterns| {
+        patterns.retain(|existing| *existing != pattern);
+    })
+    .await
+}
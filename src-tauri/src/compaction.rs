@@ -0,0 +1,230 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::{HashMap, HashSet};
+use tauri::{AppHandle, Emitter, Manager, State};
+use tokio::sync::Mutex;
+
+use crate::context::{self, ContextUsage};
+use crate::error::AppError;
+use crate::metrics::{self, CompactionMetrics};
+use crate::thread_state::message_thread_id;
+use crate::{now_ms, AppState};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum CompactionMode {
+    Off,
+    #[default]
+    Offer,
+    Auto,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct CompactionConfig {
+    #[serde(default)]
+    mode: CompactionMode,
+    #[serde(default = "default_threshold_percent")]
+    threshold_percent: f64,
+}
+
+struct InFlight {
+    trigger: &'static str,
+    started_at_ms: i64,
+    before_tokens: i64,
+}
+
+#[derive(Default)]
+pub(crate) struct Compactions {
+    suggested: Mutex<HashSet<(String, String)>>,
+    pending: Mutex<HashSet<(String, String)>>,
+    in_flight: Mutex<HashMap<(String, String), InFlight>>,
+}
+
+fn default_threshold_percent() -> f64 {
+    85.0
+}
+
+impl Default for CompactionConfig {
+    fn default() -> Self {
+        Self {
+            mode: CompactionMode::default(),
+            threshold_percent: default_threshold_percent(),
+        }
+    }
+}
+
+fn emit_compaction(
+    app: &AppHandle,
+    workspace_id: &str,
+    thread_id: &str,
+    status: &str,
+    record: &CompactionMetrics,
+) {
+    let _ = app.emit(
+        "thread-compaction",
+        json!({
+            "workspaceId": workspace_id,
+            "threadId": thread_id,
+            "status": status,
+            "trigger": record.trigger,
+            "beforeTokens": record.before_tokens,
+            "afterTokens": record.after_tokens,
+            "error": record.error,
+        }),
+    );
+}
+
+async fn finish(app: &AppHandle, workspace_id: &str, thread_id: &str, record: CompactionMetrics) {
+    let status = if record.error.is_some() {
+        "failed"
+    } else {
+        "completed"
+    };
+    emit_compaction(app, workspace_id, thread_id, status, &record);
+    metrics::record_compaction(app, workspace_id, thread_id.to_string(), record).await;
+}
+
+async fn start(
+    app: &AppHandle,
+    workspace_id: &str,
+    thread_id: &str,
+    trigger: &'static str,
+) -> Result<Value, AppError> {
+    let state = app.state::<AppState>();
+    let session = state
+        .sessions
+        .lock()
+        .await
+        .get(workspace_id)
+        .cloned()
+        .ok_or(AppError::NotConnected)?;
+    let key = (workspace_id.to_string(), thread_id.to_string());
+    let before_tokens = context::thread_usage(&state, workspace_id, thread_id)
+        .await
+        .map(|usage| usage.tokens_in_context())
+        .unwrap_or(0);
+    let started_at_ms = now_ms();
+    {
+        let mut in_flight = state.compactions.in_flight.lock().await;
+        if in_flight.contains_key(&key) {
+            return Err("This thread is already being compacted.".into());
+        }
+        in_flight.insert(
+            key.clone(),
+            InFlight {
+                trigger,
+                started_at_ms,
+                before_tokens,
+            },
+        );
+    }
+    let started = CompactionMetrics::started(trigger, started_at_ms, before_tokens);
+    emit_compaction(app, workspace_id, thread_id, "started", &started);
+
+    let result = session
+        .send_request("thread/compact", json!({ "threadId": thread_id }))
+        .await
+        .and_then(|response| match AppError::from_rpc_response(&response) {
+            Some(error) => Err(error),
+            None => Ok(response),
+        });
+    if let Err(err) = &result {
+        state.compactions.in_flight.lock().await.remove(&key);
+        finish(app, workspace_id, thread_id, started.failed(err.to_string())).await;
+    }
+    result
+}
+
+fn spawn_start(app: &AppHandle, workspace_id: &str, thread_id: String) {
+    let app = app.clone();
+    let workspace_id = workspace_id.to_string();
+    tauri::async_runtime::spawn(async move {
+        if let Err(err) = start(&app, &workspace_id, &thread_id, "auto").await {
+            tracing::warn!(%workspace_id, %thread_id, "automatic compaction failed: {err}");
+        }
+    });
+}
+
+async fn on_usage(app: &AppHandle, workspace_id: &str, usage: ContextUsage) {
+    let state = app.state::<AppState>();
+    let thread_id = usage.thread_id().to_string();
+    let key = (workspace_id.to_string(), thread_id.clone());
+    let in_flight = state.compactions.in_flight.lock().await.remove(&key);
+    if let Some(in_flight) = in_flight {
+        let record = CompactionMetrics::started(
+            in_flight.trigger,
+            in_flight.started_at_ms,
+            in_flight.before_tokens,
+        )
+        .completed(usage.tokens_in_context());
+        finish(app, workspace_id, &thread_id, record).await;
+        state.compactions.suggested.lock().await.remove(&key);
+        return;
+    }
+
+    let config = state.settings.lock().await.context_compaction.clone();
+    let over = usage
+        .percent_used()
+        .is_some_and(|percent| percent >= config.threshold_percent);
+    if !over {
+        state.compactions.suggested.lock().await.remove(&key);
+        return;
+    }
+    if !state.compactions.suggested.lock().await.insert(key.clone()) {
+        return;
+    }
+    match config.mode {
+        CompactionMode::Off => {}
+        CompactionMode::Offer => {
+            let _ = app.emit("context-compaction-suggested", &usage);
+        }
+        CompactionMode::Auto => {
+            state.compactions.pending.lock().await.insert(key);
+        }
+    }
+}
+
+pub(crate) async fn observe(app: &AppHandle, workspace_id: &str, message: &Value) {
+    let Some(method) = message.get("method").and_then(Value::as_str) else {
+        return;
+    };
+    let params = message.get("params").unwrap_or(&Value::Null);
+    let Some(thread_id) = message_thread_id(params) else {
+        return;
+    };
+    match method {
+        "thread/tokenUsage/updated" => {
+            let state = app.state::<AppState>();
+            if let Some(usage) = context::thread_usage(&state, workspace_id, &thread_id).await {
+                on_usage(app, workspace_id, usage).await;
+            }
+        }
+        // Compacting mid-turn would race the running turn, so automatic
+        // compaction waits for it to finish.
+        "turn/completed" => {
+            let state = app.state::<AppState>();
+            let key = (workspace_id.to_string(), thread_id.clone());
+            if state.compactions.pending.lock().await.remove(&key) {
+                spawn_start(app, workspace_id, thread_id);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[tauri::command]
+pub(crate) async fn compact_thread(
+    workspace_id: String,
+    thread_id: String,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Value, AppError> {
+    state
+        .compactions
+        .pending
+        .lock()
+        .await
+        .remove(&(workspace_id.clone(), thread_id.clone()));
+    start(&app, &workspace_id, &thread_id, "manual").await
+}
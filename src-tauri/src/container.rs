@@ -0,0 +1,416 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tauri::State;
+use tokio::process::Command;
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::{normalize_path, platform, shell_env, write_workspaces, AppState, WorkspaceEntry};
+
+const DOCKER_TIMEOUT: Duration = Duration::from_secs(30);
+const DEVCONTAINER_UP_TIMEOUT: Duration = Duration::from_secs(600);
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum ContainerKind {
+    #[default]
+    Docker,
+    Devcontainer,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ContainerConfig {
+    #[serde(default)]
+    kind: ContainerKind,
+    #[serde(default)]
+    container: String,
+    #[serde(default)]
+    workdir: String,
+    #[serde(default)]
+    codex_bin: Option<String>,
+    #[serde(default)]
+    user: Option<String>,
+    #[serde(default)]
+    docker_bin: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ContainerStatus {
+    kind: ContainerKind,
+    container: String,
+    exists: bool,
+    running: bool,
+    status: Option<String>,
+    health: Option<String>,
+    started_at: Option<String>,
+    codex_version: Option<String>,
+    error: Option<String>,
+}
+
+impl ContainerConfig {
+    fn docker_bin(&self) -> &str {
+        self.docker_bin
+            .as_deref()
+            .map(str::trim)
+            .filter(|bin| !bin.is_empty())
+            .unwrap_or("docker")
+    }
+
+    fn codex_bin(&self) -> &str {
+        self.codex_bin
+            .as_deref()
+            .map(str::trim)
+            .filter(|bin| !bin.is_empty())
+            .unwrap_or("codex")
+    }
+}
+
+fn normalize_config(entry: &WorkspaceEntry, config: ContainerConfig) -> ContainerConfig {
+    let trimmed = |value: Option<String>| {
+        value
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+    };
+    let workdir = config.workdir.trim().trim_end_matches('/').to_string();
+    let workdir = if workdir.is_empty() {
+        format!("/workspaces/{}", entry.name)
+    } else {
+        workdir
+    };
+    ContainerConfig {
+        kind: config.kind,
+        container: config.container.trim().to_string(),
+        workdir,
+        codex_bin: trimmed(config.codex_bin),
+        user: trimmed(config.user),
+        docker_bin: trimmed(config.docker_bin),
+    }
+}
+
+pub(crate) fn agent_path(entry: &WorkspaceEntry, host_path: &str) -> String {
+    let Some(config) = entry.container.as_ref() else {
+        return host_path.to_string();
+    };
+    match Path::new(host_path).strip_prefix(&entry.path) {
+        Ok(relative) if relative.as_os_str().is_empty() => config.workdir.clone(),
+        Ok(relative) => format!("{}/{}", config.workdir, normalize_path(relative)),
+        Err(_) => host_path.to_string(),
+    }
+}
+
+pub(crate) fn agent_cwd(entry: &WorkspaceEntry) -> String {
+    agent_path(entry, &entry.path)
+}
+
+// The container only sees the workspace mount, so attachments from elsewhere
+// on the host are copied in before their path is handed to the agent.
+pub(crate) fn agent_attachment_path(entry: &WorkspaceEntry, host_path: &str) -> String {
+    if entry.container.is_none() || Path::new(host_path).starts_with(&entry.path) {
+        return agent_path(entry, host_path);
+    }
+    let source = PathBuf::from(host_path);
+    let name = source
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| "attachment".to_string());
+    let target = PathBuf::from(&entry.path)
+        .join(".codexmonitor")
+        .join("attachments")
+        .join(format!("{}-{name}", Uuid::new_v4()));
+    let copied = target
+        .parent()
+        .map(fs::create_dir_all)
+        .unwrap_or(Ok(()))
+        .and_then(|_| fs::copy(&source, &target));
+    match copied {
+        Ok(_) => agent_path(entry, &target.to_string_lossy()),
+        Err(err) => {
+            tracing::warn!(workspace_id = %entry.id, "failed to copy attachment: {err}");
+            host_path.to_string()
+        }
+    }
+}
+
+fn docker_command(config: &ContainerConfig, env: Option<&HashMap<String, String>>) -> Command {
+    let mut command = platform::command(config.docker_bin());
+    if let Some(env) = env {
+        command.envs(env);
+    }
+    command
+}
+
+pub(crate) fn exec_command(
+    config: &ContainerConfig,
+    entry: &WorkspaceEntry,
+    env: Option<&HashMap<String, String>>,
+) -> Command {
+    let mut command = docker_command(config, env);
+    command.arg("exec").arg("-i").arg("-w").arg(&config.workdir);
+    if let Some(user) = config.user.as_deref() {
+        command.arg("-u").arg(user);
+    }
+    for (key, value) in &entry.defaults.env {
+        command.arg("-e").arg(format!("{key}={value}"));
+    }
+    if let Some(home) = entry.codex_home.as_ref().filter(|home| !home.trim().is_empty()) {
+        command.arg("-e").arg(format!("CODEX_HOME={home}"));
+    }
+    command.arg(&config.container).arg(config.codex_bin());
+    command
+}
+
+async fn run_output(mut command: Command, timeout: Duration) -> Result<String, String> {
+    command.stdin(std::process::Stdio::null()).kill_on_drop(true);
+    let output = tokio::time::timeout(timeout, command.output())
+        .await
+        .map_err(|_| "container command timed out".to_string())?
+        .map_err(|err| {
+            if err.kind() == std::io::ErrorKind::NotFound {
+                "Docker CLI not found. Install Docker or set its path for this workspace."
+                    .to_string()
+            } else {
+                err.to_string()
+            }
+        })?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        return Err(if stderr.is_empty() {
+            format!("command exited with {}", output.status)
+        } else {
+            stderr
+        });
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+async fn inspect(
+    config: &ContainerConfig,
+    env: Option<&HashMap<String, String>>,
+) -> Result<Value, String> {
+    let mut command = docker_command(config, env);
+    command
+        .arg("inspect")
+        .arg("--format")
+        .arg("{{json .State}}")
+        .arg(&config.container);
+    let output = run_output(command, DOCKER_TIMEOUT).await?;
+    serde_json::from_str(&output).map_err(|err| err.to_string())
+}
+
+pub(crate) async fn ensure_running(
+    config: &ContainerConfig,
+    env: Option<&HashMap<String, String>>,
+) -> Result<(), String> {
+    if config.container.is_empty() {
+        return Err("No container is configured for this workspace. Start it first.".to_string());
+    }
+    let state = inspect(config, env).await?;
+    if state.get("Running").and_then(Value::as_bool) != Some(true) {
+        return Err(format!(
+            "Container {} is not running. Start it from the workspace settings.",
+            config.container
+        ));
+    }
+    Ok(())
+}
+
+async fn container_config(
+    state: &AppState,
+    workspace_id: &str,
+) -> Result<(WorkspaceEntry, ContainerConfig), AppError> {
+    let entry = state
+        .workspaces
+        .lock()
+        .await
+        .get(workspace_id)
+        .cloned()
+        .ok_or(AppError::WorkspaceNotFound)?;
+    let config = entry
+        .container
+        .clone()
+        .ok_or("This workspace does not run in a container.")?;
+    Ok((entry, config))
+}
+
+async fn host_env(state: &AppState) -> Option<HashMap<String, String>> {
+    let settings = state.settings.lock().await.clone();
+    shell_env::spawn_env(state, &settings).await
+}
+
+async fn store_config(
+    state: &AppState,
+    workspace_id: &str,
+    config: Option<ContainerConfig>,
+) -> Result<(), AppError> {
+    let mut workspaces = state.workspaces.lock().await;
+    let entry = workspaces
+        .get_mut(workspace_id)
+        .ok_or(AppError::WorkspaceNotFound)?;
+    entry.container = config;
+    let list: Vec<_> = workspaces.values().cloned().collect();
+    Ok(write_workspaces(&state.storage_path, &list)?)
+}
+
+async fn devcontainer_up(
+    entry: &WorkspaceEntry,
+    config: &ContainerConfig,
+    env: Option<&HashMap<String, String>>,
+) -> Result<ContainerConfig, String> {
+    let mut command = platform::command("devcontainer");
+    if let Some(env) = env {
+        command.envs(env);
+    }
+    command
+        .arg("up")
+        .arg("--workspace-folder")
+        .arg(&entry.path);
+    let output = run_output(command, DEVCONTAINER_UP_TIMEOUT).await?;
+    let result: Value = output
+        .lines()
+        .rev()
+        .find_map(|line| serde_json::from_str(line).ok())
+        .ok_or("devcontainer up did not report a result")?;
+    if result.get("outcome").and_then(Value::as_str) != Some("success") {
+        let message = result
+            .get("message")
+            .and_then(Value::as_str)
+            .unwrap_or("devcontainer up failed");
+        return Err(message.to_string());
+    }
+    let field = |key: &str| {
+        result
+            .get(key)
+            .and_then(Value::as_str)
+            .map(|value| value.to_string())
+    };
+    let mut updated = config.clone();
+    updated.container = field("containerId").ok_or("devcontainer up returned no container")?;
+    if let Some(workdir) = field("remoteWorkspaceFolder") {
+        updated.workdir = workdir;
+    }
+    if updated.user.is_none() {
+        updated.user = field("remoteUser");
+    }
+    Ok(updated)
+}
+
+async fn status_for(
+    entry: &WorkspaceEntry,
+    config: &ContainerConfig,
+    env: Option<&HashMap<String, String>>,
+) -> ContainerStatus {
+    let mut status = ContainerStatus {
+        kind: config.kind,
+        container: config.container.clone(),
+        ..ContainerStatus::default()
+    };
+    if config.container.is_empty() {
+        status.error = Some("The container has not been created yet.".to_string());
+        return status;
+    }
+    let state = match inspect(config, env).await {
+        Ok(state) => state,
+        Err(err) => {
+            status.error = Some(err);
+            return status;
+        }
+    };
+    let field = |pointer: &str| {
+        state
+            .pointer(pointer)
+            .and_then(Value::as_str)
+            .map(|value| value.to_string())
+    };
+    status.exists = true;
+    status.running = state.get("Running").and_then(Value::as_bool).unwrap_or(false);
+    status.status = field("/Status");
+    status.health = field("/Health/Status");
+    status.started_at = field("/StartedAt");
+    if status.running {
+        let mut command = exec_command(config, entry, env);
+        command.arg("--version");
+        match run_output(command, DOCKER_TIMEOUT).await {
+            Ok(output) => status.codex_version = Some(output),
+            Err(err) => status.error = Some(format!("codex is not available: {err}")),
+        }
+    }
+    status
+}
+
+#[tauri::command]
+pub(crate) async fn set_workspace_container(
+    id: String,
+    container: Option<ContainerConfig>,
+    state: State<'_, AppState>,
+) -> Result<Option<ContainerConfig>, AppError> {
+    let entry = state
+        .workspaces
+        .lock()
+        .await
+        .get(&id)
+        .cloned()
+        .ok_or(AppError::WorkspaceNotFound)?;
+    let container = container.map(|config| normalize_config(&entry, config));
+    if let Some(config) = container.as_ref() {
+        if config.kind == ContainerKind::Docker && config.container.is_empty() {
+            return Err("A container name or id is required.".into());
+        }
+    }
+    store_config(&state, &id, container.clone()).await?;
+    Ok(container)
+}
+
+#[tauri::command]
+pub(crate) async fn container_start(
+    workspace_id: String,
+    state: State<'_, AppState>,
+) -> Result<ContainerStatus, AppError> {
+    let (entry, config) = container_config(&state, &workspace_id).await?;
+    let env = host_env(&state).await;
+    let config = match config.kind {
+        ContainerKind::Docker => {
+            let mut command = docker_command(&config, env.as_ref());
+            command.arg("start").arg(&config.container);
+            run_output(command, DOCKER_TIMEOUT).await?;
+            config
+        }
+        ContainerKind::Devcontainer => {
+            let updated = devcontainer_up(&entry, &config, env.as_ref()).await?;
+            store_config(&state, &workspace_id, Some(updated.clone())).await?;
+            updated
+        }
+    };
+    Ok(status_for(&entry, &config, env.as_ref()).await)
+}
+
+#[tauri::command]
+pub(crate) async fn container_stop(
+    workspace_id: String,
+    state: State<'_, AppState>,
+) -> Result<ContainerStatus, AppError> {
+    let (entry, config) = container_config(&state, &workspace_id).await?;
+    if config.container.is_empty() {
+        return Err("The container has not been created yet.".into());
+    }
+    let env = host_env(&state).await;
+    let mut command = docker_command(&config, env.as_ref());
+    command.arg("stop").arg(&config.container);
+    run_output(command, DOCKER_TIMEOUT).await?;
+    Ok(status_for(&entry, &config, env.as_ref()).await)
+}
+
+#[tauri::command]
+pub(crate) async fn container_status(
+    workspace_id: String,
+    state: State<'_, AppState>,
+) -> Result<ContainerStatus, AppError> {
+    let (entry, config) = container_config(&state, &workspace_id).await?;
+    let env = host_env(&state).await;
+    Ok(status_for(&entry, &config, env.as_ref()).await)
+}
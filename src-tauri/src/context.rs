@@ -0,0 +1,137 @@
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use tauri::{AppHandle, Emitter, Manager, State};
+use tokio::sync::Mutex;
+
+use crate::error::AppError;
+use crate::thread_state::message_thread_id;
+use crate::{now_ms, AppState};
+
+const WARNING_PERCENT: f64 = 80.0;
+const CRITICAL_PERCENT: f64 = 95.0;
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum ContextLevel {
+    Ok,
+    Warning,
+    Critical,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ContextUsage {
+    workspace_id: String,
+    thread_id: String,
+    tokens_in_context: i64,
+    context_window: Option<i64>,
+    percent_used: Option<f64>,
+    total_tokens: i64,
+    level: ContextLevel,
+    updated_at_ms: i64,
+}
+
+#[derive(Default)]
+pub(crate) struct ContextTracker {
+    threads: Mutex<HashMap<(String, String), ContextUsage>>,
+}
+
+impl ContextUsage {
+    pub(crate) fn thread_id(&self) -> &str {
+        &self.thread_id
+    }
+
+    pub(crate) fn tokens_in_context(&self) -> i64 {
+        self.tokens_in_context
+    }
+
+    pub(crate) fn percent_used(&self) -> Option<f64> {
+        self.percent_used
+    }
+}
+
+fn field(value: &Value, camel: &str, snake: &str) -> Option<i64> {
+    value.get(camel).or_else(|| value.get(snake))?.as_i64()
+}
+
+fn usage_tokens(usage: &Value) -> Option<i64> {
+    field(usage, "totalTokens", "total_tokens").or_else(|| {
+        let input = field(usage, "inputTokens", "input_tokens")?;
+        let output = field(usage, "outputTokens", "output_tokens").unwrap_or(0);
+        Some(input + output)
+    })
+}
+
+fn level_for(percent: Option<f64>) -> ContextLevel {
+    match percent {
+        Some(percent) if percent >= CRITICAL_PERCENT => ContextLevel::Critical,
+        Some(percent) if percent >= WARNING_PERCENT => ContextLevel::Warning,
+        _ => ContextLevel::Ok,
+    }
+}
+
+fn parse_usage(workspace_id: &str, message: &Value) -> Option<ContextUsage> {
+    let params = message.get("params")?;
+    let thread_id = message_thread_id(params)?;
+    let token_usage = params.get("tokenUsage").or_else(|| params.get("token_usage"))?;
+    let last = token_usage.get("last").or_else(|| token_usage.get("last_usage"))?;
+    let tokens_in_context = usage_tokens(last)?;
+    let total_tokens = token_usage
+        .get("total")
+        .or_else(|| token_usage.get("total_usage"))
+        .and_then(usage_tokens)
+        .unwrap_or(tokens_in_context);
+    let context_window = field(token_usage, "modelContextWindow", "model_context_window")
+        .filter(|window| *window > 0);
+    let percent_used = context_window
+        .map(|window| (tokens_in_context as f64 / window as f64 * 100.0).min(100.0));
+    Some(ContextUsage {
+        workspace_id: workspace_id.to_string(),
+        thread_id,
+        tokens_in_context,
+        context_window,
+        percent_used,
+        total_tokens,
+        level: level_for(percent_used),
+        updated_at_ms: now_ms(),
+    })
+}
+
+pub(crate) async fn observe(app: &AppHandle, workspace_id: &str, message: &Value) {
+    if message.get("method").and_then(Value::as_str) != Some("thread/tokenUsage/updated") {
+        return;
+    }
+    let Some(usage) = parse_usage(workspace_id, message) else {
+        return;
+    };
+    let state = app.state::<AppState>();
+    state.context_usage.threads.lock().await.insert(
+        (workspace_id.to_string(), usage.thread_id.clone()),
+        usage.clone(),
+    );
+    let _ = app.emit("context-usage-updated", usage);
+}
+
+pub(crate) async fn thread_usage(
+    state: &AppState,
+    workspace_id: &str,
+    thread_id: &str,
+) -> Option<ContextUsage> {
+    state
+        .context_usage
+        .threads
+        .lock()
+        .await
+        .get(&(workspace_id.to_string(), thread_id.to_string()))
+        .cloned()
+}
+
+#[tauri::command]
+pub(crate) async fn get_thread_context_usage(
+    workspace_id: String,
+    thread_id: String,
+    state: State<'_, AppState>,
+) -> Result<Option<ContextUsage>, AppError> {
+    Ok(thread_usage(&state, &workspace_id, &thread_id).await)
+}
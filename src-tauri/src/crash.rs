@@ -0,0 +1,228 @@
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use std::backtrace::Backtrace;
+use std::fs;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use tauri::{AppHandle, State};
+use tauri_plugin_opener::OpenerExt;
+
+use crate::error::AppError;
+use crate::{logging, now_ms, storage, AppState};
+
+const LOG_TAIL_LINES: usize = 200;
+const MAX_REPORTS: usize = 50;
+const ISSUE_URL: &str = "https://github.com/chenghuzi/Codexola/issues/new";
+const ISSUE_BODY_LIMIT: usize = 6000;
+
+static CRASH_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum CrashKind {
+    Panic,
+    TaskError,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct CrashReport {
+    id: String,
+    created_at_ms: i64,
+    kind: CrashKind,
+    message: String,
+    #[serde(default)]
+    location: Option<String>,
+    #[serde(default)]
+    thread: Option<String>,
+    #[serde(default)]
+    backtrace: String,
+    app_version: String,
+    os: String,
+    arch: String,
+    #[serde(default)]
+    log_tail: Vec<String>,
+    #[serde(default)]
+    submitted_at_ms: Option<i64>,
+}
+
+fn report_path(dir: &Path, id: &str) -> PathBuf {
+    dir.join(format!("{id}.json"))
+}
+
+fn report_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|entry| entry.path())
+                .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+                .collect()
+        })
+        .unwrap_or_default();
+    files.sort();
+    files.reverse();
+    files
+}
+
+fn record(kind: CrashKind, message: String, location: Option<String>, backtrace: String) {
+    let Some(dir) = CRASH_DIR.get() else {
+        return;
+    };
+    let id = format!(
+        "crash-{}-{}",
+        Local::now().format("%Y%m%d-%H%M%S"),
+        &uuid::Uuid::new_v4().simple().to_string()[..8]
+    );
+    let report = CrashReport {
+        id: id.clone(),
+        created_at_ms: now_ms(),
+        kind,
+        message,
+        location,
+        thread: std::thread::current().name().map(str::to_string),
+        backtrace,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        os: std::env::consts::OS.to_string(),
+        arch: std::env::consts::ARCH.to_string(),
+        log_tail: logging::tail_lines(LOG_TAIL_LINES),
+        submitted_at_ms: None,
+    };
+    if fs::create_dir_all(dir).is_err() {
+        return;
+    }
+    if storage::write_json_atomic(&report_path(dir, &id), &report).is_ok() {
+        for path in report_files(dir).into_iter().skip(MAX_REPORTS) {
+            let _ = fs::remove_file(path);
+        }
+    }
+}
+
+// Runs before the default hook so the report exists even when the panic
+// aborts the process. Nothing here may log through tracing: the panic can
+// come from inside the log writer while it holds its lock.
+pub(crate) fn install(data_dir: &Path) {
+    if CRASH_DIR.set(data_dir.join("crash-reports")).is_err() {
+        return;
+    }
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let payload = info.payload();
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|message| message.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "panic with non-string payload".to_string());
+        let location = info.location().map(|location| {
+            format!("{}:{}:{}", location.file(), location.line(), location.column())
+        });
+        record(
+            CrashKind::Panic,
+            message,
+            location,
+            Backtrace::force_capture().to_string(),
+        );
+        previous(info);
+    }));
+}
+
+// Background tasks are detached, so an error return or a panic would
+// otherwise vanish along with the JoinHandle.
+pub(crate) fn spawn_monitored<F>(task: &'static str, future: F)
+where
+    F: Future<Output = Result<(), String>> + Send + 'static,
+{
+    let handle = tauri::async_runtime::spawn(future);
+    tauri::async_runtime::spawn(async move {
+        match handle.await {
+            Ok(Ok(())) => {}
+            Ok(Err(err)) => {
+                tracing::error!(task, "background task failed: {err}");
+                record(CrashKind::TaskError, format!("{task}: {err}"), None, String::new());
+            }
+            Err(err) => tracing::error!(task, "background task panicked: {err}"),
+        }
+    });
+}
+
+fn crash_dir(state: &AppState) -> PathBuf {
+    CRASH_DIR
+        .get()
+        .cloned()
+        .unwrap_or_else(|| state.data_dir.join("crash-reports"))
+}
+
+fn truncate(text: &str, limit: usize) -> &str {
+    match text.char_indices().nth(limit) {
+        Some((index, _)) => &text[..index],
+        None => text,
+    }
+}
+
+fn issue_url(report: &CrashReport) -> Result<String, String> {
+    let log_tail = report.log_tail[report.log_tail.len().saturating_sub(40)..].join("\n");
+    let body = format!(
+        "**Version:** {} ({} {})\n**Kind:** {:?}\n**Message:** {}\n**Location:** {}\n\n\
+         <details><summary>Backtrace</summary>\n\n```\n{}\n```\n</details>\n\n\
+         <details><summary>Recent log</summary>\n\n```\n{}\n```\n</details>\n",
+        report.app_version,
+        report.os,
+        report.arch,
+        report.kind,
+        report.message,
+        report.location.as_deref().unwrap_or("unknown"),
+        report.backtrace.trim(),
+        log_tail,
+    );
+    let title = format!("Crash: {}", truncate(&report.message, 80));
+    let url = url::Url::parse_with_params(
+        ISSUE_URL,
+        [
+            ("title", title.as_str()),
+            ("body", truncate(&body, ISSUE_BODY_LIMIT)),
+        ],
+    )
+    .map_err(|err| err.to_string())?;
+    Ok(url.to_string())
+}
+
+#[tauri::command]
+pub(crate) async fn list_crash_reports(
+    state: State<'_, AppState>,
+) -> Result<Vec<CrashReport>, AppError> {
+    let dir = crash_dir(&state);
+    let reports = tokio::task::spawn_blocking(move || {
+        report_files(&dir)
+            .iter()
+            .filter_map(|path| fs::read_to_string(path).ok())
+            .filter_map(|raw| serde_json::from_str(&raw).ok())
+            .collect()
+    })
+    .await?;
+    Ok(reports)
+}
+
+#[tauri::command]
+pub(crate) async fn submit_crash_report(
+    id: String,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<CrashReport, AppError> {
+    if !state.settings.lock().await.crash_reporting_enabled {
+        return Err(
+            "Crash reporting is turned off. Enable it in settings to submit reports.".into(),
+        );
+    }
+    if id.is_empty() || !id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+        return Err(format!("Invalid crash report id: {id}").into());
+    }
+    let path = report_path(&crash_dir(&state), &id);
+    let mut report: CrashReport = serde_json::from_str(&fs::read_to_string(&path)?)?;
+    app.opener()
+        .open_url(issue_url(&report)?, None::<&str>)
+        .map_err(|err| err.to_string())?;
+    report.submitted_at_ms = Some(now_ms());
+    storage::write_json_atomic(&path, &report)?;
+    Ok(report)
+}
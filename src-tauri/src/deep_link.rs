@@ -0,0 +1,260 @@
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use tauri::{AppHandle, Emitter, Manager, State};
+use tokio::sync::Mutex;
+use url::Url;
+
+use crate::error::AppError;
+use crate::{AppState, WorkspaceInfo};
+
+pub(crate) const URL_SCHEME: &str = "codexola";
+
+#[derive(Debug, Clone, PartialEq)]
+enum DeepLink {
+    OpenThread {
+        workspace_id: String,
+        thread_id: Option<String>,
+    },
+    NewTask {
+        path: String,
+        prompt: Option<String>,
+    },
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub(crate) enum DeepLinkEvent {
+    #[serde(rename_all = "camelCase")]
+    OpenThread {
+        workspace_id: String,
+        thread_id: Option<String>,
+    },
+    #[serde(rename_all = "camelCase")]
+    NewTask {
+        workspace: Box<WorkspaceInfo>,
+        prompt: Option<String>,
+    },
+    #[serde(rename_all = "camelCase")]
+    Error { url: String, message: String },
+}
+
+#[derive(Default)]
+pub(crate) struct DeepLinkQueue {
+    pending: Mutex<Vec<DeepLinkEvent>>,
+    ready: AtomicBool,
+}
+
+fn parse_deep_link(raw: &str) -> Result<DeepLink, String> {
+    let url = Url::parse(raw).map_err(|e| e.to_string())?;
+    if url.scheme() != URL_SCHEME {
+        return Err(format!("unsupported scheme: {}", url.scheme()));
+    }
+    let segments: Vec<&str> = url
+        .path_segments()
+        .map(|segments| segments.filter(|segment| !segment.is_empty()).collect())
+        .unwrap_or_default();
+    match url.host_str() {
+        Some("workspace") => match segments.as_slice() {
+            [workspace_id] => Ok(DeepLink::OpenThread {
+                workspace_id: workspace_id.to_string(),
+                thread_id: None,
+            }),
+            [workspace_id, "thread", thread_id] => Ok(DeepLink::OpenThread {
+                workspace_id: workspace_id.to_string(),
+                thread_id: Some(thread_id.to_string()),
+            }),
+            _ => Err(format!("invalid workspace link: {raw}")),
+        },
+        Some("new-task") => {
+            let mut path = None;
+            let mut prompt = None;
+            for (key, value) in url.query_pairs() {
+                match key.as_ref() {
+                    "path" => path = Some(value.to_string()),
+                    "prompt" => prompt = Some(value.to_string()),
+                    _ => {}
+                }
+            }
+            let path = path
+                .filter(|path| !path.trim().is_empty())
+                .ok_or("new-task link requires a path")?;
+            Ok(DeepLink::NewTask { path, prompt })
+        }
+        _ => Err(format!("unsupported link: {raw}")),
+    }
+}
+
+pub(crate) fn deep_link_args(args: impl IntoIterator<Item = String>) -> Vec<String> {
+    let prefix = format!("{URL_SCHEME}:");
+    args.into_iter()
+        .filter(|arg| arg.to_ascii_lowercase().starts_with(&prefix))
+        .collect()
+}
+
+pub(crate) fn normalize_workspace_path(path: &str) -> PathBuf {
+    std::fs::canonicalize(path).unwrap_or_else(|_| PathBuf::from(path))
+}
+
+async fn resolve_new_task(app: &AppHandle, path: String) -> Result<WorkspaceInfo, AppError> {
+    let state = app.state::<AppState>();
+    let target = normalize_workspace_path(&path);
+    let existing = state
+        .workspaces
+        .lock()
+        .await
+        .values()
+        .find(|entry| normalize_workspace_path(&entry.path) == target)
+        .cloned();
+    if let Some(entry) = existing {
+        let connected = state.sessions.lock().await.contains_key(&entry.id);
+        let codex_version_supported =
+            crate::codex_version_supported(entry.codex_version.as_deref());
+        return Ok(WorkspaceInfo {
+            id: entry.id,
+            name: entry.name,
+            path: entry.path,
+            connected,
+            codex_bin: entry.codex_bin,
+            codex_home: entry.codex_home,
+            codex_version_supported,
+            codex_version: entry.codex_version,
+            template_id: entry.template_id,
+            defaults: entry.defaults,
+            container: entry.container,
+            pre_connect_commands: entry.pre_connect_commands,
+            observer_mode: entry.observer_mode,
+            speak_completions: entry.speak_completions,
+            auto_connect: entry.auto_connect,
+        });
+    }
+    if !Path::new(&path).is_dir() {
+        return Err(format!("{path} is not a directory").into());
+    }
+    let path = target.to_string_lossy().to_string();
+    crate::add_workspace(path, None, None, state, app.clone()).await
+}
+
+async fn resolve_deep_link(app: &AppHandle, raw: &str) -> DeepLinkEvent {
+    let result = match parse_deep_link(raw) {
+        Ok(DeepLink::OpenThread {
+            workspace_id,
+            thread_id,
+        }) => Ok(DeepLinkEvent::OpenThread {
+            workspace_id,
+            thread_id,
+        }),
+        Ok(DeepLink::NewTask { path, prompt }) => resolve_new_task(app, path)
+            .await
+            .map(|workspace| DeepLinkEvent::NewTask {
+                workspace: Box::new(workspace),
+                prompt,
+            }),
+        Err(err) => Err(AppError::from(err)),
+    };
+    result.unwrap_or_else(|err| DeepLinkEvent::Error {
+        url: raw.to_string(),
+        message: err.to_string(),
+    })
+}
+
+async fn dispatch_deep_link(app: &AppHandle, event: DeepLinkEvent) {
+    let state = app.state::<AppState>();
+    let mut pending = state.deep_links.pending.lock().await;
+    if state.deep_links.ready.load(Ordering::SeqCst) {
+        let _ = app.emit("deep-link", event);
+    } else {
+        pending.push(event);
+    }
+}
+
+pub(crate) fn focus_main_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.show();
+        let _ = window.unminimize();
+        let _ = window.set_focus();
+    }
+}
+
+pub(crate) fn handle_urls(app: &AppHandle, urls: Vec<String>) {
+    if urls.is_empty() {
+        return;
+    }
+    focus_main_window(app);
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        for url in urls {
+            let event = resolve_deep_link(&app, &url).await;
+            dispatch_deep_link(&app, event).await;
+        }
+    });
+}
+
+#[tauri::command]
+pub(crate) async fn take_pending_deep_links(
+    state: State<'_, AppState>,
+) -> Result<Vec<DeepLinkEvent>, AppError> {
+    let mut pending = state.deep_links.pending.lock().await;
+    state.deep_links.ready.store(true, Ordering::SeqCst);
+    Ok(std::mem::take(&mut *pending))
+}
+
+#[cfg(target_os = "linux")]
+pub(crate) fn register_url_scheme() -> Result<(), String> {
+    let exe = std::env::current_exe().map_err(|e| e.to_string())?;
+    let data_home = std::env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .or_else(|| {
+            std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share"))
+        })
+        .ok_or("unable to resolve data home")?;
+    let applications = data_home.join("applications");
+    std::fs::create_dir_all(&applications).map_err(|e| e.to_string())?;
+    let desktop_name = format!("{URL_SCHEME}-url-handler.desktop");
+    let desktop = format!(
+        "[Desktop Entry]\nType=Application\nName=Codexola\nExec=\"{}\" %u\n\
+         Terminal=false\nNoDisplay=true\nMimeType=x-scheme-handler/{URL_SCHEME};\n",
+        exe.display()
+    );
+    std::fs::write(applications.join(&desktop_name), desktop).map_err(|e| e.to_string())?;
+    std::process::Command::new("xdg-mime")
+        .args(["default", &desktop_name, &format!("x-scheme-handler/{URL_SCHEME}")])
+        .status()
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[cfg(windows)]
+pub(crate) fn register_url_scheme() -> Result<(), String> {
+    let exe = std::env::current_exe().map_err(|e| e.to_string())?;
+    let key = format!(r"HKCU\Software\Classes\{URL_SCHEME}");
+    let command = format!("\"{}\" \"%1\"", exe.display());
+    let entries: [(String, &str, String); 3] = [
+        (key.clone(), "/ve", "URL:Codexola".to_string()),
+        (key.clone(), "URL Protocol", String::new()),
+        (format!(r"{key}\shell\open\command"), "/ve", command),
+    ];
+    for (path, value_name, data) in entries {
+        let mut reg = std::process::Command::new("reg");
+        crate::platform::hide_std_console_window(&mut reg);
+        reg.args(["add", &path]);
+        if value_name == "/ve" {
+            reg.arg("/ve");
+        } else {
+            reg.args(["/v", value_name]);
+        }
+        let status = reg
+            .args(["/d", &data, "/f"])
+            .status()
+            .map_err(|e| e.to_string())?;
+        if !status.success() {
+            return Err(format!("failed to register {URL_SCHEME}:// handler"));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(not(any(target_os = "linux", windows)))]
+pub(crate) fn register_url_scheme() -> Result<(), String> {
+    Ok(())
+}
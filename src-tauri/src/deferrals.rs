@@ -0,0 +1,349 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, State};
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::thread_state::{self, message_thread_id, str_at};
+use crate::{
+    audit, default_session_store_version, dispatch_user_message, enqueue_outbox_message, hooks,
+    now_ms, storage, AppState, UserMessageRequest,
+};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum DeferAction {
+    Deny,
+    #[default]
+    Defer,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum ReplayDecision {
+    Accept,
+    Decline,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct AutoDeferConfig {
+    #[serde(default)]
+    enabled: bool,
+    #[serde(default = "default_idle_minutes")]
+    idle_minutes: u64,
+    #[serde(default)]
+    action: DeferAction,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct DeferredApproval {
+    id: String,
+    workspace_id: String,
+    thread_id: String,
+    request_id: u64,
+    method: String,
+    #[serde(default)]
+    params: Value,
+    summary: String,
+    action: DeferAction,
+    deferred_at_ms: i64,
+    idle_ms: i64,
+    #[serde(default)]
+    replayed_decision: Option<ReplayDecision>,
+    #[serde(default)]
+    replayed_at_ms: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct DeferredApprovalStore {
+    #[serde(default = "default_session_store_version")]
+    version: u32,
+    #[serde(default)]
+    approvals: Vec<DeferredApproval>,
+}
+
+pub(crate) struct Deferrals {
+    last_activity_ms: AtomicI64,
+    store_lock: Mutex<()>,
+}
+
+fn default_idle_minutes() -> u64 {
+    30
+}
+
+impl Default for AutoDeferConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            idle_minutes: default_idle_minutes(),
+            action: DeferAction::default(),
+        }
+    }
+}
+
+impl Default for DeferredApprovalStore {
+    fn default() -> Self {
+        Self {
+            version: default_session_store_version(),
+            approvals: Vec::new(),
+        }
+    }
+}
+
+impl Default for Deferrals {
+    fn default() -> Self {
+        Self {
+            last_activity_ms: AtomicI64::new(now_ms()),
+            store_lock: Mutex::new(()),
+        }
+    }
+}
+
+impl Deferrals {
+    pub(crate) fn touch(&self) {
+        self.last_activity_ms.store(now_ms(), Ordering::SeqCst);
+    }
+
+    fn idle_ms(&self) -> i64 {
+        now_ms() - self.last_activity_ms.load(Ordering::SeqCst)
+    }
+}
+
+fn workspace_deferrals_path(workspace_path: &str) -> PathBuf {
+    PathBuf::from(workspace_path)
+        .join(".codexmonitor")
+        .join("deferred-approvals.json")
+}
+
+fn read_deferrals(path: &Path) -> Result<DeferredApprovalStore, String> {
+    storage::read_json_or_default(path)
+}
+
+fn write_deferrals(path: &Path, store: &DeferredApprovalStore) -> Result<(), String> {
+    storage::write_json_atomic(path, store)
+}
+
+fn request_summary(method: &str, params: &Value) -> String {
+    let command = match params.get("command") {
+        Some(Value::Array(parts)) => Some(
+            parts
+                .iter()
+                .filter_map(|part| part.as_str())
+                .collect::<Vec<_>>()
+                .join(" "),
+        ),
+        Some(Value::String(command)) => Some(command.clone()),
+        _ => None,
+    };
+    command
+        .filter(|command| !command.trim().is_empty())
+        .or_else(|| str_at(params, "/reason").map(|reason| reason.to_string()))
+        .unwrap_or_else(|| method.to_string())
+}
+
+async fn workspace_path(state: &AppState, workspace_id: &str) -> Result<String, AppError> {
+    state
+        .workspaces
+        .lock()
+        .await
+        .get(workspace_id)
+        .map(|entry| entry.path.clone())
+        .ok_or(AppError::WorkspaceNotFound)
+}
+
+async fn wait_for_idle(
+    app: &AppHandle,
+    workspace_id: &str,
+    request_id: u64,
+) -> Option<(AutoDeferConfig, i64)> {
+    loop {
+        let state = app.state::<AppState>();
+        let config = state.settings.lock().await.approval_auto_defer.clone();
+        if !config.enabled {
+            return None;
+        }
+        thread_state::pending_thread(&state, workspace_id, request_id).await?;
+        let limit_ms = config.idle_minutes.max(1) as i64 * 60_000;
+        let idle_ms = state.deferrals.idle_ms();
+        if idle_ms >= limit_ms {
+            return Some((config, idle_ms));
+        }
+        tokio::time::sleep(Duration::from_millis((limit_ms - idle_ms) as u64)).await;
+    }
+}
+
+async fn defer_when_idle(app: AppHandle, workspace_id: String, message: Value) {
+    let Some(request_id) = message.get("id").and_then(|id| id.as_u64()) else {
+        return;
+    };
+    let Some((config, idle_ms)) = wait_for_idle(&app, &workspace_id, request_id).await else {
+        return;
+    };
+    let state = app.state::<AppState>();
+    let Some(session) = state.sessions.lock().await.get(&workspace_id).cloned() else {
+        return;
+    };
+    let decision = match config.action {
+        DeferAction::Deny => "decline",
+        DeferAction::Defer => "cancel",
+    };
+    let result = json!({ "decision": decision });
+    if let Err(err) = session.send_response(request_id, result.clone()).await {
+        tracing::warn!(%workspace_id, request_id, "failed to auto-defer approval: {err}");
+        return;
+    }
+    audit::record_decision(&app, &workspace_id, request_id, &result).await;
+    thread_state::approval_resolved(&app, &workspace_id, request_id).await;
+
+    let method = str_at(&message, "/method").unwrap_or_default().to_string();
+    let params = message.get("params").cloned().unwrap_or(Value::Null);
+    let deferred = DeferredApproval {
+        id: Uuid::new_v4().to_string(),
+        workspace_id: workspace_id.clone(),
+        thread_id: message_thread_id(&params).unwrap_or_default(),
+        request_id,
+        summary: request_summary(&method, &params),
+        method,
+        params,
+        action: config.action,
+        deferred_at_ms: now_ms(),
+        idle_ms,
+        replayed_decision: None,
+        replayed_at_ms: None,
+    };
+    let path = workspace_deferrals_path(&session.entry.path);
+    let result = {
+        let _guard = state.deferrals.store_lock.lock().await;
+        read_deferrals(&path).and_then(|mut store| {
+            store.approvals.push(deferred.clone());
+            write_deferrals(&path, &store)
+        })
+    };
+    if let Err(err) = result {
+        tracing::warn!(%workspace_id, "failed to persist deferred approval: {err}");
+    }
+    tracing::info!(%workspace_id, request_id, decision, "auto-deferred approval while idle");
+    let _ = app.emit("approval-deferred", deferred);
+}
+
+pub(crate) async fn observe(app: &AppHandle, workspace_id: &str, message: &Value) {
+    if hooks::approval_request_method(message).is_none() {
+        return;
+    }
+    let state = app.state::<AppState>();
+    if !state.settings.lock().await.approval_auto_defer.enabled {
+        return;
+    }
+    let app = app.clone();
+    let workspace_id = workspace_id.to_string();
+    let message = message.clone();
+    tauri::async_runtime::spawn(async move {
+        defer_when_idle(app, workspace_id, message).await;
+    });
+}
+
+fn replay_text(deferred: &DeferredApproval) -> String {
+    let outcome = match deferred.action {
+        DeferAction::Deny => "declined",
+        DeferAction::Defer => "held back",
+    };
+    format!(
+        "While I was away, your approval request was automatically {outcome}:\n\n{}\n\n\
+         I've reviewed it now and approve it. Please go ahead and continue.",
+        deferred.summary
+    )
+}
+
+#[tauri::command]
+pub(crate) async fn report_user_activity(state: State<'_, AppState>) -> Result<(), AppError> {
+    state.deferrals.touch();
+    Ok(())
+}
+
+#[tauri::command]
+pub(crate) async fn list_deferred_approvals(
+    workspace_id: Option<String>,
+    include_replayed: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<Vec<DeferredApproval>, AppError> {
+    let paths: Vec<String> = match workspace_id {
+        Some(workspace_id) => vec![workspace_path(&state, &workspace_id).await?],
+        None => state
+            .workspaces
+            .lock()
+            .await
+            .values()
+            .map(|entry| entry.path.clone())
+            .collect(),
+    };
+    let include_replayed = include_replayed.unwrap_or(false);
+    let mut approvals = Vec::new();
+    for path in paths {
+        let store = read_deferrals(&workspace_deferrals_path(&path))?;
+        approvals.extend(
+            store
+                .approvals
+                .into_iter()
+                .filter(|approval| include_replayed || approval.replayed_decision.is_none()),
+        );
+    }
+    approvals.sort_by_key(|approval| approval.deferred_at_ms);
+    Ok(approvals)
+}
+
+#[tauri::command]
+pub(crate) async fn replay_deferred_approval(
+    workspace_id: String,
+    deferral_id: String,
+    decision: ReplayDecision,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<DeferredApproval, AppError> {
+    let path = workspace_deferrals_path(&workspace_path(&state, &workspace_id).await?);
+    let _guard = state.deferrals.store_lock.lock().await;
+    let mut store = read_deferrals(&path)?;
+    let deferred = store
+        .approvals
+        .iter_mut()
+        .find(|approval| approval.id == deferral_id)
+        .ok_or_else(|| AppError::from(format!("deferred approval not found: {deferral_id}")))?;
+    if deferred.replayed_decision.is_some() {
+        return Err("deferred approval was already replayed".into());
+    }
+    if decision == ReplayDecision::Accept {
+        let message = UserMessageRequest {
+            thread_id: deferred.thread_id.clone(),
+            text: replay_text(deferred),
+            model: None,
+            effort: None,
+            access_mode: None,
+            attachments: Vec::new(),
+        };
+        let session = state.sessions.lock().await.get(&workspace_id).cloned();
+        match session {
+            Some(session) => {
+                let checkpoint = state.settings.lock().await.turn_checkpoints_enabled;
+                let response = dispatch_user_message(&app, &session, &message, checkpoint).await?;
+                if let Some(error) = response.get("error") {
+                    return Err(error.to_string().into());
+                }
+            }
+            None => {
+                enqueue_outbox_message(&app, &state, &workspace_id, message).await?;
+            }
+        }
+    }
+    deferred.replayed_decision = Some(decision);
+    deferred.replayed_at_ms = Some(now_ms());
+    let deferred = deferred.clone();
+    write_deferrals(&path, &store)?;
+    state.deferrals.touch();
+    Ok(deferred)
+}
@@ -0,0 +1,160 @@
+use ignore::WalkBuilder;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use tauri::State;
+
+use crate::deep_link::normalize_workspace_path;
+use crate::error::AppError;
+use crate::{is_excluded_dir, normalize_path, open_repository, user_home_dir, AppState};
+
+const DEFAULT_MAX_DEPTH: usize = 3;
+const MAX_DEPTH_LIMIT: usize = 8;
+const MAX_RESULTS: usize = 500;
+const COMMON_CODE_DIRS: &[&str] = &[
+    "code",
+    "Code",
+    "dev",
+    "Developer",
+    "git",
+    "GitHub",
+    "projects",
+    "Projects",
+    "repos",
+    "src",
+    "workspace",
+    "Workspace",
+];
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct DiscoveredRepo {
+    path: String,
+    name: String,
+    branch: Option<String>,
+    last_commit_ms: Option<i64>,
+    already_added: bool,
+}
+
+fn default_roots() -> Vec<PathBuf> {
+    let Some(home) = user_home_dir() else {
+        return Vec::new();
+    };
+    COMMON_CODE_DIRS
+        .iter()
+        .map(|name| home.join(name))
+        .filter(|path| path.is_dir())
+        .collect()
+}
+
+fn expand_root(root: &str) -> Option<PathBuf> {
+    let root = root.trim();
+    if root.is_empty() {
+        return None;
+    }
+    if let Some(rest) = root.strip_prefix("~") {
+        let home = user_home_dir()?;
+        return Some(home.join(rest.trim_start_matches(['/', '\\'])));
+    }
+    Some(PathBuf::from(root))
+}
+
+fn inside_repository(path: &Path) -> bool {
+    path.parent()
+        .is_some_and(|parent| parent.join(".git").exists())
+}
+
+fn describe_repo(path: &Path, added: &HashSet<PathBuf>) -> Option<DiscoveredRepo> {
+    let repo = open_repository(path).ok()?;
+    let head = repo.head().ok();
+    let branch = head
+        .as_ref()
+        .filter(|head| head.is_branch())
+        .and_then(|head| head.shorthand())
+        .map(|name| name.to_string());
+    let last_commit_ms = head
+        .and_then(|head| head.peel_to_commit().ok())
+        .map(|commit| commit.time().seconds() * 1000);
+    let name = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or("Workspace")
+        .to_string();
+    let canonical = normalize_workspace_path(&path.to_string_lossy());
+    Some(DiscoveredRepo {
+        path: normalize_path(path),
+        name,
+        branch,
+        last_commit_ms,
+        already_added: added.contains(&canonical),
+    })
+}
+
+fn scan_roots(
+    roots: Vec<PathBuf>,
+    max_depth: usize,
+    added: HashSet<PathBuf>,
+) -> Vec<DiscoveredRepo> {
+    let mut seen = HashSet::new();
+    let mut repos = Vec::new();
+    for root in roots.iter().filter(|root| root.is_dir()) {
+        let walker = WalkBuilder::new(root)
+            .max_depth(Some(max_depth))
+            .hidden(true)
+            .git_ignore(true)
+            .git_exclude(true)
+            .require_git(false)
+            .filter_entry(|entry| {
+                let is_dir = entry.file_type().is_some_and(|file_type| file_type.is_dir());
+                is_dir
+                    && (entry.depth() == 0
+                        || (!is_excluded_dir(entry.path()) && !inside_repository(entry.path())))
+            })
+            .build();
+        for entry in walker.flatten() {
+            let path = entry.path();
+            if !path.join(".git").exists() {
+                continue;
+            }
+            let canonical = normalize_workspace_path(&path.to_string_lossy());
+            if !seen.insert(canonical) {
+                continue;
+            }
+            if let Some(repo) = describe_repo(path, &added) {
+                repos.push(repo);
+            }
+            if repos.len() >= MAX_RESULTS {
+                break;
+            }
+        }
+    }
+    repos.sort_by(|a, b| {
+        b.last_commit_ms
+            .cmp(&a.last_commit_ms)
+            .then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
+    });
+    repos
+}
+
+#[tauri::command]
+pub(crate) async fn discover_repos(
+    roots: Vec<String>,
+    max_depth: Option<usize>,
+    state: State<'_, AppState>,
+) -> Result<Vec<DiscoveredRepo>, AppError> {
+    let mut roots: Vec<PathBuf> = roots.iter().filter_map(|root| expand_root(root)).collect();
+    if roots.is_empty() {
+        roots = default_roots();
+    }
+    let max_depth = max_depth
+        .unwrap_or(DEFAULT_MAX_DEPTH)
+        .clamp(1, MAX_DEPTH_LIMIT);
+    let added: HashSet<PathBuf> = state
+        .workspaces
+        .lock()
+        .await
+        .values()
+        .map(|entry| normalize_workspace_path(&entry.path))
+        .collect();
+    Ok(tokio::task::spawn_blocking(move || scan_roots(roots, max_depth, added)).await?)
+}
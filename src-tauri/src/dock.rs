@@ -0,0 +1,241 @@
+use tauri::{AppHandle, Manager};
+use tokio::sync::Mutex;
+
+use crate::deep_link::{self, URL_SCHEME};
+use crate::quick_switch::{self, QuickSwitchKind};
+use crate::{i18n, AppState};
+
+const MAX_THREADS: usize = 8;
+const MAX_WORKSPACES: usize = 5;
+pub(crate) const DOCK_ITEM_PREFIX: &str = "dock-open:";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct DockEntry {
+    title: String,
+    url: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct DockItems {
+    lang: &'static str,
+    threads: Vec<DockEntry>,
+    workspaces: Vec<DockEntry>,
+}
+
+#[derive(Default)]
+pub(crate) struct Dock {
+    last: Mutex<Option<DockItems>>,
+}
+
+async fn current_items(state: &AppState) -> DockItems {
+    let mut items = DockItems {
+        lang: i18n::app_lang(state).await,
+        threads: Vec::new(),
+        workspaces: Vec::new(),
+    };
+    for item in quick_switch::recent(state, usize::MAX).await {
+        match (item.kind, item.thread_id) {
+            (QuickSwitchKind::Thread, Some(thread_id)) => {
+                if item.last_activity_ms.is_none() || items.threads.len() >= MAX_THREADS {
+                    continue;
+                }
+                items.threads.push(DockEntry {
+                    title: format!("{} — {}", item.title, item.workspace_name),
+                    url: format!(
+                        "{URL_SCHEME}://workspace/{}/thread/{thread_id}",
+                        item.workspace_id
+                    ),
+                });
+            }
+            (QuickSwitchKind::Workspace, _) if items.workspaces.len() < MAX_WORKSPACES => {
+                items.workspaces.push(DockEntry {
+                    title: item.title,
+                    url: format!("{URL_SCHEME}://workspace/{}", item.workspace_id),
+                });
+            }
+            _ => {}
+        }
+    }
+    items
+}
+
+pub(crate) async fn refresh(app: &AppHandle) {
+    let state = app.state::<AppState>();
+    let items = current_items(&state).await;
+    {
+        let mut last = state.dock.last.lock().await;
+        if last.as_ref() == Some(&items) {
+            return;
+        }
+        *last = Some(items.clone());
+    }
+    apply(app, items);
+}
+
+pub(crate) fn handle_event(app: &AppHandle, id: &str) -> bool {
+    let Some(url) = id.strip_prefix(DOCK_ITEM_PREFIX) else {
+        return false;
+    };
+    deep_link::handle_urls(app, vec![url.to_string()]);
+    true
+}
+
+#[cfg(target_os = "macos")]
+fn apply(app: &AppHandle, items: DockItems) {
+    let result = app.run_on_main_thread(move || macos::set_items(&items));
+    if let Err(err) = result {
+        tracing::warn!("failed to update dock menu: {err}");
+    }
+}
+
+#[cfg(windows)]
+fn apply(_app: &AppHandle, items: DockItems) {
+    tauri::async_runtime::spawn_blocking(move || {
+        if let Err(err) = jump_list::set_items(&items) {
+            tracing::warn!("failed to update jump list: {err}");
+        }
+    });
+}
+
+#[cfg(not(any(target_os = "macos", windows)))]
+fn apply(_app: &AppHandle, _items: DockItems) {}
+
+#[cfg(target_os = "macos")]
+mod macos {
+    use muda::{Menu, MenuItem, PredefinedMenuItem};
+    use std::cell::RefCell;
+    use std::ffi::{c_char, c_void};
+    use std::sync::Once;
+
+    use super::{DockEntry, DockItems, DOCK_ITEM_PREFIX};
+
+    thread_local! {
+        static DOCK_MENU: RefCell<Option<Menu>> = const { RefCell::new(None) };
+    }
+
+    #[link(name = "objc", kind = "dylib")]
+    extern "C" {
+        fn objc_getClass(name: *const c_char) -> *mut c_void;
+        fn sel_registerName(name: *const c_char) -> *mut c_void;
+        fn class_replaceMethod(
+            class: *mut c_void,
+            selector: *mut c_void,
+            imp: *const c_void,
+            types: *const c_char,
+        ) -> *const c_void;
+    }
+
+    extern "C" fn application_dock_menu(
+        _this: *mut c_void,
+        _cmd: *mut c_void,
+        _sender: *mut c_void,
+    ) -> *mut c_void {
+        DOCK_MENU.with(|menu| {
+            menu.borrow()
+                .as_ref()
+                .map(|menu| menu.ns_menu())
+                .unwrap_or(std::ptr::null_mut())
+        })
+    }
+
+    // tao owns the application delegate, so the dock menu callback is added to
+    // its class rather than installing a delegate of our own.
+    fn install() {
+        static INSTALL: Once = Once::new();
+        INSTALL.call_once(|| unsafe {
+            let class = objc_getClass(c"TaoAppDelegateParent".as_ptr());
+            if class.is_null() {
+                tracing::warn!("app delegate class not found; dock menu disabled");
+                return;
+            }
+            let selector = sel_registerName(c"applicationDockMenu:".as_ptr());
+            class_replaceMethod(
+                class,
+                selector,
+                application_dock_menu as *const c_void,
+                c"@@:@".as_ptr(),
+            );
+        });
+    }
+
+    fn append(menu: &Menu, entries: &[DockEntry]) {
+        for entry in entries {
+            let id = format!("{DOCK_ITEM_PREFIX}{}", entry.url);
+            let _ = menu.append(&MenuItem::with_id(id, &entry.title, true, None));
+        }
+    }
+
+    pub(super) fn set_items(items: &DockItems) {
+        install();
+        let menu = Menu::new();
+        append(&menu, &items.threads);
+        if !items.threads.is_empty() && !items.workspaces.is_empty() {
+            let _ = menu.append(&PredefinedMenuItem::separator());
+        }
+        append(&menu, &items.workspaces);
+        DOCK_MENU.with(|slot| *slot.borrow_mut() = Some(menu));
+    }
+}
+
+#[cfg(windows)]
+mod jump_list {
+    use windows::core::{Interface, HSTRING, PROPVARIANT};
+    use windows::Win32::Storage::EnhancedStorage::PKEY_Title;
+    use windows::Win32::System::Com::{
+        CoCreateInstance, CoInitializeEx, CLSCTX_INPROC_SERVER, COINIT_APARTMENTTHREADED,
+    };
+    use windows::Win32::UI::Shell::Common::{IObjectArray, IObjectCollection};
+    use windows::Win32::UI::Shell::PropertiesSystem::IPropertyStore;
+    use windows::Win32::UI::Shell::{
+        DestinationList, EnumerableObjectCollection, ICustomDestinationList, IShellLinkW,
+        ShellLink,
+    };
+
+    use super::{DockEntry, DockItems};
+    use crate::i18n;
+
+    unsafe fn shell_link(exe: &HSTRING, entry: &DockEntry) -> windows::core::Result<IShellLinkW> {
+        let link: IShellLinkW = CoCreateInstance(&ShellLink, None, CLSCTX_INPROC_SERVER)?;
+        link.SetPath(exe)?;
+        link.SetArguments(&HSTRING::from(format!("\"{}\"", entry.url)))?;
+        link.SetDescription(&HSTRING::from(entry.title.as_str()))?;
+        let store: IPropertyStore = link.cast()?;
+        store.SetValue(&PKEY_Title, &PROPVARIANT::from(entry.title.as_str()))?;
+        store.Commit()?;
+        Ok(link)
+    }
+
+    // Entries launch the app with a deep link, which a running instance picks
+    // up over ipc the same way a browser-opened link would.
+    pub(super) fn set_items(items: &DockItems) -> Result<(), String> {
+        let exe = std::env::current_exe().map_err(|err| err.to_string())?;
+        let exe = HSTRING::from(exe.as_os_str());
+        unsafe {
+            let _ = CoInitializeEx(None, COINIT_APARTMENTTHREADED);
+            let result = (|| -> windows::core::Result<()> {
+                let list: ICustomDestinationList =
+                    CoCreateInstance(&DestinationList, None, CLSCTX_INPROC_SERVER)?;
+                let mut max_slots = 0u32;
+                let _removed: IObjectArray = list.BeginList(&mut max_slots)?;
+                for (title_key, entries) in [
+                    ("dock.recentThreads", &items.threads),
+                    ("dock.workspaces", &items.workspaces),
+                ] {
+                    if entries.is_empty() {
+                        continue;
+                    }
+                    let collection: IObjectCollection =
+                        CoCreateInstance(&EnumerableObjectCollection, None, CLSCTX_INPROC_SERVER)?;
+                    for entry in entries {
+                        collection.AddObject(&shell_link(&exe, entry)?)?;
+                    }
+                    let array: IObjectArray = collection.cast()?;
+                    let title = HSTRING::from(i18n::t(items.lang, title_key));
+                    list.AppendCategory(&title, &array)?;
+                }
+                list.CommitList()
+            })();
+            result.map_err(|err| err.to_string())
+        }
+    }
+}
@@ -0,0 +1,185 @@
+use serde::ser::{Serialize, SerializeMap, Serializer};
+use serde_json::Value;
+use std::fmt;
+
+#[derive(Debug, Clone)]
+pub(crate) enum AppError {
+    NotConnected,
+    WorkspaceNotFound,
+    GitError { code: i32, message: String },
+    IoError { message: String },
+    RpcError { code: i64, message: String },
+    Timeout { message: String },
+    InvalidReference { reference: String, message: String },
+    NothingToReview { message: String },
+    SecretsDetected { message: String },
+    ObserverMode { message: String },
+    TurnsRunning { running_turns: usize, message: String },
+    AccessModeLocked { locked: String, requested: String, message: String },
+    Unsupported { method: String, codex_version: Option<String>, message: String },
+    Other { message: String },
+}
+
+impl AppError {
+    pub(crate) fn kind(&self) -> &'static str {
+        match self {
+            AppError::NotConnected => "notConnected",
+            AppError::WorkspaceNotFound => "workspaceNotFound",
+            AppError::GitError { .. } => "gitError",
+            AppError::IoError { .. } => "ioError",
+            AppError::RpcError { .. } => "rpcError",
+            AppError::Timeout { .. } => "timeout",
+            AppError::InvalidReference { .. } => "invalidReference",
+            AppError::NothingToReview { .. } => "nothingToReview",
+            AppError::SecretsDetected { .. } => "secretsDetected",
+            AppError::ObserverMode { .. } => "observerMode",
+            AppError::TurnsRunning { .. } => "turnsRunning",
+            AppError::AccessModeLocked { .. } => "accessModeLocked",
+            AppError::Unsupported { .. } => "unsupported",
+            AppError::Other { .. } => "other",
+        }
+    }
+
+    pub(crate) fn from_rpc_response(response: &Value) -> Option<AppError> {
+        let error = response.get("error")?;
+        let code = error.get("code").and_then(|code| code.as_i64()).unwrap_or(0);
+        let message = error
+            .get("message")
+            .and_then(|message| message.as_str())
+            .map(|message| message.to_string())
+            .unwrap_or_else(|| error.to_string());
+        Some(AppError::RpcError { code, message })
+    }
+
+    pub(crate) fn unsupported(method: &str, codex_version: Option<&str>) -> AppError {
+        let message = match codex_version {
+            Some(version) => format!("{method} is not supported by codex {version}."),
+            None => format!("{method} is not supported by this codex version."),
+        };
+        AppError::Unsupported {
+            method: method.to_string(),
+            codex_version: codex_version.map(|version| version.to_string()),
+            message,
+        }
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AppError::NotConnected => write!(f, "workspace not connected"),
+            AppError::WorkspaceNotFound => write!(f, "workspace not found"),
+            AppError::GitError { message, .. }
+            | AppError::IoError { message }
+            | AppError::RpcError { message, .. }
+            | AppError::Timeout { message }
+            | AppError::InvalidReference { message, .. }
+            | AppError::NothingToReview { message }
+            | AppError::SecretsDetected { message }
+            | AppError::ObserverMode { message }
+            | AppError::TurnsRunning { message, .. }
+            | AppError::AccessModeLocked { message, .. }
+            | AppError::Unsupported { message, .. }
+            | AppError::Other { message } => write!(f, "{message}"),
+        }
+    }
+}
+
+impl std::error::Error for AppError {}
+
+impl Serialize for AppError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("kind", self.kind())?;
+        map.serialize_entry("message", &self.to_string())?;
+        match self {
+            AppError::GitError { code, .. } => map.serialize_entry("code", code)?,
+            AppError::RpcError { code, .. } => map.serialize_entry("code", code)?,
+            AppError::InvalidReference { reference, .. } => {
+                map.serialize_entry("reference", reference)?
+            }
+            AppError::TurnsRunning { running_turns, .. } => {
+                map.serialize_entry("runningTurns", running_turns)?
+            }
+            AppError::AccessModeLocked {
+                locked, requested, ..
+            } => {
+                map.serialize_entry("lockedMode", locked)?;
+                map.serialize_entry("requestedMode", requested)?;
+            }
+            AppError::Unsupported {
+                method,
+                codex_version,
+                ..
+            } => {
+                map.serialize_entry("method", method)?;
+                map.serialize_entry("codexVersion", codex_version)?;
+            }
+            _ => {}
+        }
+        map.end()
+    }
+}
+
+impl From<String> for AppError {
+    fn from(message: String) -> Self {
+        AppError::Other { message }
+    }
+}
+
+impl From<&str> for AppError {
+    fn from(message: &str) -> Self {
+        AppError::Other {
+            message: message.to_string(),
+        }
+    }
+}
+
+impl From<git2::Error> for AppError {
+    fn from(error: git2::Error) -> Self {
+        AppError::GitError {
+            code: error.raw_code(),
+            message: error.message().to_string(),
+        }
+    }
+}
+
+impl From<std::io::Error> for AppError {
+    fn from(error: std::io::Error) -> Self {
+        AppError::IoError {
+            message: error.to_string(),
+        }
+    }
+}
+
+impl From<serde_json::Error> for AppError {
+    fn from(error: serde_json::Error) -> Self {
+        AppError::Other {
+            message: error.to_string(),
+        }
+    }
+}
+
+impl From<tokio::task::JoinError> for AppError {
+    fn from(error: tokio::task::JoinError) -> Self {
+        AppError::Other {
+            message: error.to_string(),
+        }
+    }
+}
+
+impl From<tauri::Error> for AppError {
+    fn from(error: tauri::Error) -> Self {
+        AppError::Other {
+            message: error.to_string(),
+        }
+    }
+}
+
+impl From<tokio::time::error::Elapsed> for AppError {
+    fn from(error: tokio::time::error::Elapsed) -> Self {
+        AppError::Timeout {
+            message: error.to_string(),
+        }
+    }
+}
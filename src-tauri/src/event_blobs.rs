@@ -0,0 +1,66 @@
+use serde_json::{json, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::AppState;
+
+const EVENT_BLOB_THRESHOLD_BYTES: usize = 256 * 1024;
+
+fn blob_dir(data_dir: &Path) -> PathBuf {
+    data_dir.join("event-blobs")
+}
+
+pub(crate) fn clear_stale(data_dir: &Path) {
+    let dir = blob_dir(data_dir);
+    if dir.exists() {
+        if let Err(err) = fs::remove_dir_all(&dir) {
+            tracing::warn!("failed to clear event blobs: {err}");
+        }
+    }
+}
+
+// Oversized events are written to disk and replaced by a reference so a
+// single huge tool output does not stall the webview bridge.
+pub(crate) fn spill(app: &AppHandle, message: Value) -> Value {
+    let Ok(serialized) = serde_json::to_vec(&message) else {
+        return message;
+    };
+    if serialized.len() <= EVENT_BLOB_THRESHOLD_BYTES {
+        return message;
+    }
+    let dir = blob_dir(&app.state::<AppState>().data_dir);
+    let path = dir.join(format!("{}.json", Uuid::new_v4()));
+    let written = fs::create_dir_all(&dir).and_then(|_| fs::write(&path, &serialized));
+    if let Err(err) = written {
+        tracing::warn!("failed to spill large event to disk: {err}");
+        return message;
+    }
+    json!({
+        "type": "blob",
+        "path": path.to_string_lossy(),
+        "bytes": serialized.len(),
+        "method": message.get("method"),
+    })
+}
+
+#[tauri::command]
+pub(crate) async fn read_event_blob(
+    path: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<Value, AppError> {
+    let dir = fs::canonicalize(blob_dir(&state.data_dir))?;
+    let path = fs::canonicalize(&path)?;
+    if !path.starts_with(&dir) {
+        return Err("event blob path is outside the blob directory".into());
+    }
+    let data = tokio::task::spawn_blocking(move || {
+        let data = fs::read(&path);
+        let _ = fs::remove_file(&path);
+        data
+    })
+    .await??;
+    Ok(serde_json::from_slice(&data)?)
+}
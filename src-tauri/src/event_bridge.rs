@@ -0,0 +1,311 @@
+use base64::Engine;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::tcp::{OwnedReadHalf, OwnedWriteHalf};
+use tokio::sync::{broadcast, Mutex};
+
+use crate::http_api::{self, BusEvent, Request};
+use crate::ipc::resolve_workspace;
+use crate::{now_ms, AppState};
+
+const ENVELOPE_VERSION: u32 = 1;
+const HANDSHAKE_GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+const MAX_FRAME_BYTES: u64 = 64 * 1024;
+const PING_INTERVAL: Duration = Duration::from_secs(15);
+
+const OP_TEXT: u8 = 0x1;
+const OP_CLOSE: u8 = 0x8;
+const OP_PING: u8 = 0x9;
+const OP_PONG: u8 = 0xA;
+
+type Filter = Arc<std::sync::Mutex<Vec<String>>>;
+type Writer = Arc<Mutex<OwnedWriteHalf>>;
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+enum ClientMessage {
+    #[serde(rename_all = "camelCase")]
+    Subscribe {
+        #[serde(default)]
+        workspace_ids: Vec<String>,
+    },
+}
+
+fn sha1(data: &[u8]) -> [u8; 20] {
+    let mut state: [u32; 5] = [0x67452301, 0xEFCDAB89, 0x98BADCFE, 0x10325476, 0xC3D2E1F0];
+    let mut message = data.to_vec();
+    let bit_len = (data.len() as u64).wrapping_mul(8);
+    message.push(0x80);
+    while message.len() % 64 != 56 {
+        message.push(0);
+    }
+    message.extend_from_slice(&bit_len.to_be_bytes());
+    for block in message.chunks_exact(64) {
+        let mut words = [0u32; 80];
+        for (word, bytes) in words.iter_mut().zip(block.chunks_exact(4)) {
+            *word = u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+        }
+        for i in 16..80 {
+            words[i] = (words[i - 3] ^ words[i - 8] ^ words[i - 14] ^ words[i - 16]).rotate_left(1);
+        }
+        let [mut a, mut b, mut c, mut d, mut e] = state;
+        for (i, word) in words.iter().enumerate() {
+            let (f, k) = match i {
+                0..=19 => ((b & c) | (!b & d), 0x5A827999),
+                20..=39 => (b ^ c ^ d, 0x6ED9EBA1),
+                40..=59 => ((b & c) | (b & d) | (c & d), 0x8F1BBCDC),
+                _ => (b ^ c ^ d, 0xCA62C1D6),
+            };
+            let next = a
+                .rotate_left(5)
+                .wrapping_add(f)
+                .wrapping_add(e)
+                .wrapping_add(k)
+                .wrapping_add(*word);
+            e = d;
+            d = c;
+            c = b.rotate_left(30);
+            b = a;
+            a = next;
+        }
+        for (value, add) in state.iter_mut().zip([a, b, c, d, e]) {
+            *value = value.wrapping_add(add);
+        }
+    }
+    let mut digest = [0u8; 20];
+    for (bytes, value) in digest.chunks_exact_mut(4).zip(state) {
+        bytes.copy_from_slice(&value.to_be_bytes());
+    }
+    digest
+}
+
+fn accept_key(key: &str) -> String {
+    let digest = sha1(format!("{}{HANDSHAKE_GUID}", key.trim()).as_bytes());
+    base64::engine::general_purpose::STANDARD.encode(digest)
+}
+
+fn encode_frame(opcode: u8, payload: &[u8]) -> Vec<u8> {
+    let mut frame = vec![0x80 | opcode];
+    match payload.len() {
+        len if len < 126 => frame.push(len as u8),
+        len if len <= u16::MAX as usize => {
+            frame.push(126);
+            frame.extend_from_slice(&(len as u16).to_be_bytes());
+        }
+        len => {
+            frame.push(127);
+            frame.extend_from_slice(&(len as u64).to_be_bytes());
+        }
+    }
+    frame.extend_from_slice(payload);
+    frame
+}
+
+// Client frames must be masked; fragmented messages are not supported.
+async fn read_frame<R>(reader: &mut R) -> Result<(u8, Vec<u8>), String>
+where
+    R: AsyncRead + Unpin,
+{
+    let mut head = [0u8; 2];
+    reader
+        .read_exact(&mut head)
+        .await
+        .map_err(|err| err.to_string())?;
+    let opcode = head[0] & 0x0F;
+    if head[1] & 0x80 == 0 {
+        return Err("client frames must be masked".to_string());
+    }
+    let len = match head[1] & 0x7F {
+        126 => reader.read_u16().await.map_err(|err| err.to_string())? as u64,
+        127 => reader.read_u64().await.map_err(|err| err.to_string())?,
+        len => len as u64,
+    };
+    if len > MAX_FRAME_BYTES {
+        return Err("frame too large".to_string());
+    }
+    let mut mask = [0u8; 4];
+    reader
+        .read_exact(&mut mask)
+        .await
+        .map_err(|err| err.to_string())?;
+    let mut payload = vec![0u8; len as usize];
+    reader
+        .read_exact(&mut payload)
+        .await
+        .map_err(|err| err.to_string())?;
+    for (index, byte) in payload.iter_mut().enumerate() {
+        *byte ^= mask[index % 4];
+    }
+    Ok((opcode, payload))
+}
+
+fn envelope(kind: &str, workspace_id: Option<&str>, payload: Value) -> String {
+    json!({
+        "version": ENVELOPE_VERSION,
+        "type": kind,
+        "workspaceId": workspace_id,
+        "timestampMs": now_ms(),
+        "payload": payload,
+    })
+    .to_string()
+}
+
+fn matches_filter(filter: &Filter, event: &BusEvent) -> bool {
+    let filter = filter.lock().unwrap_or_else(|err| err.into_inner());
+    match event.workspace_id() {
+        Some(id) => filter.is_empty() || filter.iter().any(|wanted| wanted == id),
+        None => true,
+    }
+}
+
+async fn resolve_filter(app: &AppHandle, queries: &[String]) -> Result<Vec<String>, String> {
+    let state = app.state::<AppState>();
+    let mut ids = Vec::new();
+    for query in queries.iter().map(|query| query.trim()) {
+        if query.is_empty() {
+            continue;
+        }
+        let entry = resolve_workspace(&state, query)
+            .await
+            .map_err(|err| format!("{query}: {err}"))?;
+        if !ids.contains(&entry.id) {
+            ids.push(entry.id);
+        }
+    }
+    Ok(ids)
+}
+
+async fn send(writer: &Writer, opcode: u8, payload: &[u8]) -> bool {
+    let frame = encode_frame(opcode, payload);
+    writer.lock().await.write_all(&frame).await.is_ok()
+}
+
+async fn read_loop(
+    app: AppHandle,
+    mut reader: BufReader<OwnedReadHalf>,
+    writer: Writer,
+    filter: Filter,
+    closed: Arc<AtomicBool>,
+) {
+    while let Ok((opcode, payload)) = read_frame(&mut reader).await {
+        match opcode {
+            OP_CLOSE => {
+                send(&writer, OP_CLOSE, &payload[..payload.len().min(2)]).await;
+                break;
+            }
+            OP_PING => {
+                send(&writer, OP_PONG, &payload).await;
+            }
+            OP_TEXT => {
+                let reply = match serde_json::from_slice::<ClientMessage>(&payload) {
+                    Ok(ClientMessage::Subscribe { workspace_ids }) => {
+                        match resolve_filter(&app, &workspace_ids).await {
+                            Ok(ids) => {
+                                *filter.lock().unwrap_or_else(|err| err.into_inner()) = ids.clone();
+                                envelope("subscribed", None, json!({ "workspaceIds": ids }))
+                            }
+                            Err(err) => envelope("error", None, json!({ "message": err })),
+                        }
+                    }
+                    Err(err) => envelope("error", None, json!({ "message": err.to_string() })),
+                };
+                send(&writer, OP_TEXT, reply.as_bytes()).await;
+            }
+            _ => {}
+        }
+    }
+    closed.store(true, Ordering::Relaxed);
+}
+
+pub(crate) async fn serve(
+    app: AppHandle,
+    reader: BufReader<OwnedReadHalf>,
+    mut writer: OwnedWriteHalf,
+    request: &Request,
+    stopped: Arc<AtomicBool>,
+) {
+    let upgrade = request
+        .headers
+        .get("upgrade")
+        .is_some_and(|value| value.eq_ignore_ascii_case("websocket"));
+    let Some(key) = request.headers.get("sec-websocket-key").filter(|_| upgrade) else {
+        let _ = writer
+            .write_all(b"HTTP/1.1 426 Upgrade Required\r\nConnection: close\r\n\r\n")
+            .await;
+        return;
+    };
+    let queries: Vec<String> = request
+        .query
+        .get("workspace")
+        .map(|value| value.split(',').map(|part| part.to_string()).collect())
+        .unwrap_or_default();
+    let filter = match resolve_filter(&app, &queries).await {
+        Ok(ids) => ids,
+        Err(err) => {
+            let body = json!({ "error": { "kind": "http", "message": err } }).to_string();
+            let head = format!(
+                "HTTP/1.1 404 Not Found\r\nContent-Type: application/json\r\n\
+                 Content-Length: {}\r\nConnection: close\r\n\r\n",
+                body.len()
+            );
+            let _ = writer.write_all(head.as_bytes()).await;
+            let _ = writer.write_all(body.as_bytes()).await;
+            return;
+        }
+    };
+    let handshake = format!(
+        "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\n\
+         Sec-WebSocket-Accept: {}\r\n\r\n",
+        accept_key(key)
+    );
+    if writer.write_all(handshake.as_bytes()).await.is_err() {
+        return;
+    }
+
+    let mut events = http_api::subscribe(&app);
+    let writer: Writer = Arc::new(Mutex::new(writer));
+    let filter: Filter = Arc::new(std::sync::Mutex::new(filter));
+    let closed = Arc::new(AtomicBool::new(false));
+    let hello = envelope(
+        "hello",
+        None,
+        json!({ "workspaceIds": filter.lock().unwrap_or_else(|err| err.into_inner()).clone() }),
+    );
+    if !send(&writer, OP_TEXT, hello.as_bytes()).await {
+        return;
+    }
+    tauri::async_runtime::spawn(read_loop(
+        app,
+        reader,
+        writer.clone(),
+        filter.clone(),
+        closed.clone(),
+    ));
+
+    while !stopped.load(Ordering::Relaxed) && !closed.load(Ordering::Relaxed) {
+        let sent = match tokio::time::timeout(PING_INTERVAL, events.recv()).await {
+            Err(_) => send(&writer, OP_PING, b"").await,
+            Ok(Ok(event)) => {
+                if !matches_filter(&filter, &event) {
+                    continue;
+                }
+                let message = envelope(event.name, event.workspace_id(), event.payload.clone());
+                send(&writer, OP_TEXT, message.as_bytes()).await
+            }
+            Ok(Err(broadcast::error::RecvError::Lagged(skipped))) => {
+                let message = envelope("lagged", None, json!({ "skipped": skipped }));
+                send(&writer, OP_TEXT, message.as_bytes()).await
+            }
+            Ok(Err(broadcast::error::RecvError::Closed)) => break,
+        };
+        if !sent {
+            break;
+        }
+    }
+    let _ = writer.lock().await.shutdown().await;
+}
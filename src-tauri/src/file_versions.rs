@@ -0,0 +1,191 @@
+use git2::Repository;
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::State;
+
+use crate::error::AppError;
+use crate::{
+    looks_binary, mark_file_index_stale, normalize_path, observer, open_repository,
+    read_checkpoints, resolve_workspace_path, workspace_checkpoints_path, workspace_entry,
+    AppState, Checkpoint,
+};
+
+const MAX_VERSION_BYTES: usize = 512 * 1024;
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct FileVersion {
+    checkpoint_id: Option<String>,
+    created_at_ms: Option<i64>,
+    exists: bool,
+    content: Option<String>,
+    size: u64,
+    is_binary: bool,
+    truncated: bool,
+    changed: bool,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct FileVersions {
+    path: String,
+    versions: Vec<FileVersion>,
+}
+
+fn relative_path(workspace_path: &str, path: &str) -> Result<PathBuf, AppError> {
+    let resolved = resolve_workspace_path(workspace_path, path)?;
+    let root = fs::canonicalize(workspace_path)?;
+    let relative = resolved
+        .strip_prefix(&root)
+        .map_err(|_| AppError::from("path escapes the workspace"))?;
+    if relative.as_os_str().is_empty() {
+        return Err("path must point to a file".into());
+    }
+    Ok(relative.to_path_buf())
+}
+
+fn version_from_bytes(
+    checkpoint: Option<&Checkpoint>,
+    bytes: Option<&[u8]>,
+    previous: Option<&Option<Vec<u8>>>,
+) -> FileVersion {
+    let is_binary = bytes.is_some_and(looks_binary);
+    let truncated = bytes.is_some_and(|bytes| bytes.len() > MAX_VERSION_BYTES);
+    let content = bytes.filter(|_| !is_binary).map(|bytes| {
+        String::from_utf8_lossy(&bytes[..bytes.len().min(MAX_VERSION_BYTES)]).to_string()
+    });
+    FileVersion {
+        checkpoint_id: checkpoint.map(|checkpoint| checkpoint.id.clone()),
+        created_at_ms: checkpoint.map(|checkpoint| checkpoint.created_at_ms),
+        exists: bytes.is_some(),
+        content,
+        size: bytes.map(|bytes| bytes.len() as u64).unwrap_or(0),
+        is_binary,
+        truncated,
+        changed: previous.is_some_and(|previous| previous.as_deref() != bytes),
+    }
+}
+
+fn checkpoint_blob(
+    repo: &Repository,
+    checkpoint: &Checkpoint,
+    path: &Path,
+) -> Result<Option<Vec<u8>>, AppError> {
+    let commit_id = git2::Oid::from_str(&checkpoint.commit)?;
+    let tree = repo.find_commit(commit_id)?.tree()?;
+    let Ok(entry) = tree.get_path(path) else {
+        return Ok(None);
+    };
+    let blob = repo.find_blob(entry.id())?;
+    Ok(Some(blob.content().to_vec()))
+}
+
+fn thread_checkpoints(workspace_path: &str, thread_id: &str) -> Result<Vec<Checkpoint>, AppError> {
+    let store = read_checkpoints(&workspace_checkpoints_path(workspace_path))?;
+    let mut checkpoints: Vec<Checkpoint> = store
+        .checkpoints
+        .into_iter()
+        .filter(|checkpoint| checkpoint.thread_id == thread_id)
+        .collect();
+    checkpoints.sort_by_key(|checkpoint| checkpoint.created_at_ms);
+    Ok(checkpoints)
+}
+
+fn collect_versions(
+    workspace_path: &str,
+    relative: &Path,
+    checkpoints: &[Checkpoint],
+) -> Result<Vec<FileVersion>, AppError> {
+    let repo = open_repository(workspace_path)?;
+    let mut versions = Vec::with_capacity(checkpoints.len() + 1);
+    let mut previous: Option<Option<Vec<u8>>> = None;
+    for checkpoint in checkpoints {
+        let bytes = checkpoint_blob(&repo, checkpoint, relative)?;
+        versions.push(version_from_bytes(
+            Some(checkpoint),
+            bytes.as_deref(),
+            previous.as_ref(),
+        ));
+        previous = Some(bytes);
+    }
+    let target = Path::new(workspace_path).join(relative);
+    let current = if target.is_file() {
+        Some(fs::read(&target)?)
+    } else {
+        None
+    };
+    versions.push(version_from_bytes(None, current.as_deref(), previous.as_ref()));
+    Ok(versions)
+}
+
+fn restore_from_checkpoint(
+    workspace_path: &str,
+    relative: &Path,
+    checkpoint: &Checkpoint,
+) -> Result<(), AppError> {
+    let repo = open_repository(workspace_path)?;
+    let target = Path::new(workspace_path).join(relative);
+    match checkpoint_blob(&repo, checkpoint, relative)? {
+        Some(bytes) => {
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(&target, bytes)?;
+        }
+        None => {
+            if target.is_file() {
+                fs::remove_file(&target)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub(crate) async fn get_file_versions(
+    workspace_id: String,
+    path: String,
+    thread_id: String,
+    state: State<'_, AppState>,
+) -> Result<FileVersions, AppError> {
+    let entry = workspace_entry(&state, &workspace_id).await?;
+    let relative = relative_path(&entry.path, &path)?;
+    let checkpoints = thread_checkpoints(&entry.path, &thread_id)?;
+    let workspace_path = entry.path.clone();
+    let lookup = relative.clone();
+    let versions = tokio::task::spawn_blocking(move || {
+        collect_versions(&workspace_path, &lookup, &checkpoints)
+    })
+    .await??;
+    Ok(FileVersions {
+        path: normalize_path(&relative),
+        versions,
+    })
+}
+
+#[tauri::command]
+pub(crate) async fn restore_file_version(
+    workspace_id: String,
+    path: String,
+    checkpoint_id: String,
+    state: State<'_, AppState>,
+) -> Result<Checkpoint, AppError> {
+    let entry = workspace_entry(&state, &workspace_id).await?;
+    observer::ensure_writable(&entry, "restoring file versions")?;
+    let relative = relative_path(&entry.path, &path)?;
+    let store = read_checkpoints(&workspace_checkpoints_path(&entry.path))?;
+    let checkpoint = store
+        .checkpoints
+        .into_iter()
+        .find(|checkpoint| checkpoint.id == checkpoint_id)
+        .ok_or_else(|| AppError::from("checkpoint not found"))?;
+    let workspace_path = entry.path.clone();
+    let restored = checkpoint.clone();
+    tokio::task::spawn_blocking(move || {
+        restore_from_checkpoint(&workspace_path, &relative, &restored)
+    })
+    .await??;
+    mark_file_index_stale(&state, &entry.id).await;
+    Ok(checkpoint)
+}
@@ -0,0 +1,353 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::{HashMap, VecDeque};
+use std::process::Stdio;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager, State};
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::{now_ms, platform, tasks, AppState};
+
+pub(crate) const HOOK_TURN_COMPLETED: &str = "turn-completed";
+pub(crate) const HOOK_APPROVAL_REQUESTED: &str = "approval-requested";
+pub(crate) const HOOK_RATE_LIMIT_WARNING: &str = "rate-limit-warning";
+
+const HOOK_MAX_ATTEMPTS: u32 = 3;
+const HOOK_RETRY_BASE_DELAY: Duration = Duration::from_secs(1);
+const HOOK_TIMEOUT: Duration = Duration::from_secs(30);
+const HOOK_LOG_LIMIT: usize = 200;
+const HOOK_OUTPUT_LIMIT: usize = 4096;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct HookConfig {
+    id: String,
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    events: Vec<String>,
+    action: HookAction,
+    #[serde(default = "default_hook_enabled")]
+    enabled: bool,
+}
+
+fn default_hook_enabled() -> bool {
+    true
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub(crate) enum HookAction {
+    Webhook {
+        url: String,
+        #[serde(default)]
+        headers: HashMap<String, String>,
+    },
+    Script {
+        command: String,
+    },
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct HookExecution {
+    id: String,
+    hook_id: String,
+    hook_name: String,
+    event: String,
+    workspace_id: Option<String>,
+    started_at_ms: i64,
+    duration_ms: u64,
+    attempts: u32,
+    success: bool,
+    status: Option<i32>,
+    error: Option<String>,
+    output: Option<String>,
+}
+
+#[derive(Default)]
+pub(crate) struct HookLog {
+    entries: Mutex<VecDeque<HookExecution>>,
+}
+
+struct AttemptOutcome {
+    success: bool,
+    status: Option<i32>,
+    error: Option<String>,
+    output: Option<String>,
+}
+
+fn truncate_output(output: &[u8]) -> Option<String> {
+    let text = String::from_utf8_lossy(output).trim().to_string();
+    if text.is_empty() {
+        return None;
+    }
+    if text.len() <= HOOK_OUTPUT_LIMIT {
+        return Some(text);
+    }
+    let mut end = HOOK_OUTPUT_LIMIT;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    Some(format!("{}…", &text[..end]))
+}
+
+async fn run_with_stdin(
+    mut command: Command,
+    payload: &str,
+) -> Result<std::process::Output, String> {
+    command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    let mut child = command.spawn().map_err(|e| e.to_string())?;
+    if let Some(mut stdin) = child.stdin.take() {
+        let _ = stdin.write_all(payload.as_bytes()).await;
+    }
+    match tokio::time::timeout(HOOK_TIMEOUT, child.wait_with_output()).await {
+        Ok(output) => output.map_err(|e| e.to_string()),
+        Err(_) => Err(format!("timed out after {}s", HOOK_TIMEOUT.as_secs())),
+    }
+}
+
+async fn post_webhook(
+    url: &str,
+    headers: &HashMap<String, String>,
+    payload: &str,
+) -> AttemptOutcome {
+    let null_device = if cfg!(windows) { "NUL" } else { "/dev/null" };
+    let mut command = platform::command("curl");
+    command
+        .args(["-sS", "-X", "POST", "-o", null_device, "-w", "%{http_code}"])
+        .args(["--max-time", &HOOK_TIMEOUT.as_secs().to_string()])
+        .args(["-H", "Content-Type: application/json"]);
+    for (name, value) in headers {
+        command.arg("-H").arg(format!("{name}: {value}"));
+    }
+    command.args(["--data-binary", "@-", url]);
+    match run_with_stdin(command, payload).await {
+        Ok(output) => {
+            let status = String::from_utf8_lossy(&output.stdout)
+                .trim()
+                .parse::<i32>()
+                .ok()
+                .filter(|code| *code > 0);
+            let success = output.status.success() && matches!(status, Some(200..=299));
+            let error = if success {
+                None
+            } else {
+                truncate_output(&output.stderr).or_else(|| {
+                    Some(match status {
+                        Some(code) => format!("HTTP {code}"),
+                        None => "request failed".to_string(),
+                    })
+                })
+            };
+            AttemptOutcome {
+                success,
+                status,
+                error,
+                output: None,
+            }
+        }
+        Err(err) => AttemptOutcome {
+            success: false,
+            status: None,
+            error: Some(err),
+            output: None,
+        },
+    }
+}
+
+async fn run_script(
+    command_line: &str,
+    event: &str,
+    workspace: Option<(&str, &str)>,
+    payload: &str,
+) -> AttemptOutcome {
+    let mut command = tasks::shell_command(command_line);
+    command
+        .env("CODEXOLA_EVENT", event)
+        .env("CODEXOLA_PAYLOAD", payload);
+    if let Some((workspace_id, workspace_path)) = workspace {
+        command
+            .env("CODEXOLA_WORKSPACE_ID", workspace_id)
+            .env("CODEXOLA_WORKSPACE_PATH", workspace_path)
+            .current_dir(workspace_path);
+    }
+    match run_with_stdin(command, payload).await {
+        Ok(output) => {
+            let success = output.status.success();
+            AttemptOutcome {
+                success,
+                status: output.status.code(),
+                error: if success {
+                    None
+                } else {
+                    truncate_output(&output.stderr)
+                        .or_else(|| Some(format!("exited with {}", output.status)))
+                },
+                output: truncate_output(&output.stdout),
+            }
+        }
+        Err(err) => AttemptOutcome {
+            success: false,
+            status: None,
+            error: Some(err),
+            output: None,
+        },
+    }
+}
+
+async fn execute_hook(
+    app: &AppHandle,
+    hook: &HookConfig,
+    event: &str,
+    workspace_id: Option<String>,
+    data: &Value,
+) -> HookExecution {
+    let state = app.state::<AppState>();
+    let workspace_path = match workspace_id.as_deref() {
+        Some(id) => state
+            .workspaces
+            .lock()
+            .await
+            .get(id)
+            .map(|entry| entry.path.clone()),
+        None => None,
+    };
+    let started_at_ms = now_ms();
+    let payload = json!({
+        "event": event,
+        "timestamp": started_at_ms,
+        "workspaceId": workspace_id,
+        "workspacePath": workspace_path,
+        "data": data,
+    })
+    .to_string();
+    let workspace = workspace_id.as_deref().zip(workspace_path.as_deref());
+
+    let started = Instant::now();
+    let mut attempts = 0;
+    let mut outcome;
+    loop {
+        attempts += 1;
+        outcome = match &hook.action {
+            HookAction::Webhook { url, headers } => post_webhook(url, headers, &payload).await,
+            HookAction::Script { command } => {
+                run_script(command, event, workspace, &payload).await
+            }
+        };
+        if outcome.success || attempts >= HOOK_MAX_ATTEMPTS {
+            break;
+        }
+        tokio::time::sleep(HOOK_RETRY_BASE_DELAY * 2u32.pow(attempts - 1)).await;
+    }
+
+    let execution = HookExecution {
+        id: Uuid::new_v4().to_string(),
+        hook_id: hook.id.clone(),
+        hook_name: hook.name.clone(),
+        event: event.to_string(),
+        workspace_id,
+        started_at_ms,
+        duration_ms: started.elapsed().as_millis() as u64,
+        attempts,
+        success: outcome.success,
+        status: outcome.status,
+        error: outcome.error,
+        output: outcome.output,
+    };
+    {
+        let mut entries = state.hooks.entries.lock().await;
+        entries.push_back(execution.clone());
+        while entries.len() > HOOK_LOG_LIMIT {
+            entries.pop_front();
+        }
+    }
+    if !execution.success {
+        tracing::warn!(
+            hook_id = %execution.hook_id,
+            event = %execution.event,
+            attempts = execution.attempts,
+            "hook failed: {}",
+            execution.error.as_deref().unwrap_or("unknown error")
+        );
+    }
+    let _ = app.emit("hook-executed", &execution);
+    execution
+}
+
+pub(crate) fn fire(
+    app: &AppHandle,
+    event: &'static str,
+    workspace_id: Option<String>,
+    data: Value,
+) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let hooks: Vec<HookConfig> = {
+            let state = app.state::<AppState>();
+            let settings = state.settings.lock().await;
+            settings
+                .hooks
+                .iter()
+                .filter(|hook| hook.enabled && hook.events.iter().any(|name| name == event))
+                .cloned()
+                .collect()
+        };
+        for hook in hooks {
+            let app = app.clone();
+            let workspace_id = workspace_id.clone();
+            let data = data.clone();
+            tauri::async_runtime::spawn(async move {
+                execute_hook(&app, &hook, event, workspace_id, &data).await;
+            });
+        }
+    });
+}
+
+pub(crate) fn approval_request_method(message: &Value) -> Option<&str> {
+    message.get("id")?;
+    let method = message.get("method")?.as_str()?;
+    method.to_ascii_lowercase().contains("approval").then_some(method)
+}
+
+#[tauri::command]
+pub(crate) async fn list_hook_executions(
+    limit: Option<usize>,
+    state: State<'_, AppState>,
+) -> Result<Vec<HookExecution>, AppError> {
+    let entries = state.hooks.entries.lock().await;
+    let limit = limit.unwrap_or(HOOK_LOG_LIMIT);
+    Ok(entries.iter().rev().take(limit).cloned().collect())
+}
+
+#[tauri::command]
+pub(crate) async fn clear_hook_executions(state: State<'_, AppState>) -> Result<(), AppError> {
+    state.hooks.entries.lock().await.clear();
+    Ok(())
+}
+
+#[tauri::command]
+pub(crate) async fn test_hook(
+    hook_id: String,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<HookExecution, AppError> {
+    let hook = state
+        .settings
+        .lock()
+        .await
+        .hooks
+        .iter()
+        .find(|hook| hook.id == hook_id)
+        .cloned()
+        .ok_or("hook not found")?;
+    Ok(execute_hook(&app, &hook, "test", None, &json!({ "test": true })).await)
+}
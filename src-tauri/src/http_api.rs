@@ -0,0 +1,533 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Listener, Manager, State};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::tcp::OwnedWriteHalf;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{broadcast, Mutex};
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::event_bridge;
+use crate::ipc::resolve_workspace;
+use crate::scheduler::{self, PromptOptions};
+use crate::{write_settings, AppState};
+
+const MAX_HEADER_BYTES: usize = 16 * 1024;
+const MAX_BODY_BYTES: usize = 1024 * 1024;
+const EVENT_BUFFER: usize = 1024;
+const KEEP_ALIVE: Duration = Duration::from_secs(15);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+const FORWARDED_EVENTS: &[&str] = &[
+    "app-server-event",
+    "context-usage-updated",
+    "usage-updated",
+    "rate-limit-warning",
+];
+
+fn default_port() -> u16 {
+    4870
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct HttpApiConfig {
+    #[serde(default)]
+    enabled: bool,
+    #[serde(default = "default_port")]
+    port: u16,
+    #[serde(default)]
+    token: Option<String>,
+    #[serde(default)]
+    websocket_enabled: bool,
+}
+
+impl Default for HttpApiConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: default_port(),
+            token: None,
+            websocket_enabled: false,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct HttpApiStatus {
+    running: bool,
+    address: Option<String>,
+    error: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct BusEvent {
+    pub(crate) name: &'static str,
+    pub(crate) payload: Value,
+}
+
+impl BusEvent {
+    pub(crate) fn workspace_id(&self) -> Option<&str> {
+        self.payload
+            .get("workspace_id")
+            .or_else(|| self.payload.get("workspaceId"))
+            .and_then(Value::as_str)
+    }
+}
+
+struct RunningServer {
+    config: HttpApiConfig,
+    stopped: Arc<AtomicBool>,
+    task: tauri::async_runtime::JoinHandle<()>,
+}
+
+pub(crate) struct HttpApi {
+    server: Mutex<Option<RunningServer>>,
+    last_error: Mutex<Option<String>>,
+    events: broadcast::Sender<BusEvent>,
+}
+
+impl Default for HttpApi {
+    fn default() -> Self {
+        let (events, _) = broadcast::channel(EVENT_BUFFER);
+        Self {
+            server: Mutex::new(None),
+            last_error: Mutex::new(None),
+            events,
+        }
+    }
+}
+
+pub(crate) struct Request {
+    method: String,
+    path: String,
+    pub(crate) query: HashMap<String, String>,
+    pub(crate) headers: HashMap<String, String>,
+    body: Vec<u8>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ThreadRequest {
+    workspace: String,
+    #[serde(flatten)]
+    options: PromptOptions,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MessageRequest {
+    workspace: String,
+    prompt: String,
+    #[serde(flatten)]
+    options: PromptOptions,
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        408 => "Request Timeout",
+        413 => "Payload Too Large",
+        _ => "Internal Server Error",
+    }
+}
+
+async fn write_json(writer: &mut OwnedWriteHalf, status: u16, body: &Value) {
+    let body = body.to_string();
+    let head = format!(
+        "HTTP/1.1 {status} {}\r\nContent-Type: application/json\r\n\
+         Content-Length: {}\r\nConnection: close\r\n\r\n",
+        reason(status),
+        body.len()
+    );
+    let _ = writer.write_all(head.as_bytes()).await;
+    let _ = writer.write_all(body.as_bytes()).await;
+    let _ = writer.shutdown().await;
+}
+
+async fn write_error(writer: &mut OwnedWriteHalf, status: u16, message: &str) {
+    let body = json!({ "error": { "kind": "http", "message": message } });
+    write_json(writer, status, &body).await;
+}
+
+// Reads one header line without letting it grow past the remaining header
+// budget, so a client that never sends a newline can't exhaust memory.
+async fn read_head_line<R>(
+    reader: &mut BufReader<R>,
+    line: &mut String,
+    head_bytes: &mut usize,
+) -> Result<usize, (u16, String)>
+where
+    R: tokio::io::AsyncRead + Unpin,
+{
+    line.clear();
+    let budget = MAX_HEADER_BYTES.saturating_sub(*head_bytes) as u64;
+    if budget == 0 {
+        return Err((400, "headers too large".to_string()));
+    }
+    let read = (&mut *reader)
+        .take(budget)
+        .read_line(line)
+        .await
+        .map_err(|err| (400, err.to_string()))?;
+    *head_bytes += read;
+    if read > 0 && !line.ends_with('\n') {
+        return Err((400, "headers too large".to_string()));
+    }
+    Ok(read)
+}
+
+async fn read_request<R>(reader: &mut BufReader<R>) -> Result<Request, (u16, String)>
+where
+    R: tokio::io::AsyncRead + Unpin,
+{
+    tokio::time::timeout(REQUEST_TIMEOUT, read_request_inner(reader))
+        .await
+        .map_err(|_| (408, "request timed out".to_string()))?
+}
+
+async fn read_request_inner<R>(reader: &mut BufReader<R>) -> Result<Request, (u16, String)>
+where
+    R: tokio::io::AsyncRead + Unpin,
+{
+    let bad = |message: &str| (400, message.to_string());
+    let mut head_bytes = 0;
+    let mut line = String::new();
+    read_head_line(reader, &mut line, &mut head_bytes).await?;
+    let mut parts = line.split_whitespace();
+    let method = parts
+        .next()
+        .ok_or_else(|| bad("missing method"))?
+        .to_string();
+    let target = parts.next().ok_or_else(|| bad("missing path"))?.to_string();
+    let mut headers = HashMap::new();
+    loop {
+        let read = read_head_line(reader, &mut line, &mut head_bytes).await?;
+        let header = line.trim_end();
+        if read == 0 || header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            headers.insert(name.trim().to_ascii_lowercase(), value.trim().to_string());
+        }
+    }
+    let length = match headers.get("content-length") {
+        Some(length) => length
+            .parse::<usize>()
+            .map_err(|_| bad("invalid Content-Length"))?,
+        None => 0,
+    };
+    if length > MAX_BODY_BYTES {
+        return Err((413, "request body too large".to_string()));
+    }
+    let mut body = vec![0; length];
+    reader
+        .read_exact(&mut body)
+        .await
+        .map_err(|err| (400, err.to_string()))?;
+
+    let (path, query) = target.split_once('?').unwrap_or((target.as_str(), ""));
+    let query = url::form_urlencoded::parse(query.as_bytes())
+        .into_owned()
+        .collect();
+    Ok(Request {
+        method,
+        path: path.to_string(),
+        query,
+        headers,
+        body,
+    })
+}
+
+fn token_matches(expected: &str, given: &str) -> bool {
+    expected.len() == given.len()
+        && expected
+            .bytes()
+            .zip(given.bytes())
+            .fold(0u8, |acc, (a, b)| acc | (a ^ b))
+            == 0
+}
+
+// EventSource can't set headers, so the token is also accepted as a query
+// parameter.
+fn authorized(request: &Request, token: &str) -> bool {
+    let given = request
+        .headers
+        .get("authorization")
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .or_else(|| request.query.get("token").map(String::as_str));
+    given.is_some_and(|given| token_matches(token, given.trim()))
+}
+
+fn parse_body<T: for<'de> Deserialize<'de>>(request: &Request) -> Result<T, AppError> {
+    serde_json::from_slice(&request.body)
+        .map_err(|err| format!("invalid request body: {err}").into())
+}
+
+async fn list_workspaces(app: &AppHandle) -> Result<Value, AppError> {
+    let state = app.state::<AppState>();
+    let entries: Vec<_> = state.workspaces.lock().await.values().cloned().collect();
+    let sessions = state.sessions.lock().await;
+    let mut workspaces: Vec<Value> = entries
+        .into_iter()
+        .map(|entry| {
+            json!({
+                "id": entry.id,
+                "name": entry.name,
+                "path": entry.path,
+                "connected": sessions.contains_key(&entry.id),
+            })
+        })
+        .collect();
+    workspaces.sort_by(|a, b| a["name"].as_str().cmp(&b["name"].as_str()));
+    Ok(json!({ "workspaces": workspaces }))
+}
+
+async fn start_thread(app: &AppHandle, request: &Request) -> Result<Value, AppError> {
+    let body: ThreadRequest = parse_body(request)?;
+    let state = app.state::<AppState>();
+    let entry = resolve_workspace(&state, body.workspace.trim()).await?;
+    let options = body.options.with_thread(None).normalized();
+    let (_, thread_id) = scheduler::open_thread(app, &entry.id, &options).await?;
+    Ok(json!({ "workspaceId": entry.id, "threadId": thread_id }))
+}
+
+async fn send_message(app: &AppHandle, request: &Request) -> Result<Value, AppError> {
+    let body: MessageRequest = parse_body(request)?;
+    if body.prompt.trim().is_empty() {
+        return Err("prompt is required".into());
+    }
+    let state = app.state::<AppState>();
+    let entry = resolve_workspace(&state, body.workspace.trim()).await?;
+    let options = body.options.normalized();
+    let (thread_id, turn_id) =
+        scheduler::start_prompt(app, &entry.id, &body.prompt, &options).await?;
+    Ok(json!({ "workspaceId": entry.id, "threadId": thread_id, "turnId": turn_id }))
+}
+
+pub(crate) fn subscribe(app: &AppHandle) -> broadcast::Receiver<BusEvent> {
+    app.state::<AppState>().http_api.events.subscribe()
+}
+
+async fn stream_events(
+    app: &AppHandle,
+    writer: &mut OwnedWriteHalf,
+    workspace_id: Option<String>,
+    stopped: &AtomicBool,
+) {
+    let mut events = subscribe(app);
+    let head = "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\n\
+                Cache-Control: no-cache\r\nConnection: keep-alive\r\n\r\n";
+    if writer.write_all(head.as_bytes()).await.is_err() {
+        return;
+    }
+    while !stopped.load(Ordering::Relaxed) {
+        let chunk = match tokio::time::timeout(KEEP_ALIVE, events.recv()).await {
+            Err(_) => ": keep-alive\n\n".to_string(),
+            Ok(Ok(event)) => {
+                let matches = match (workspace_id.as_deref(), event.workspace_id()) {
+                    (Some(wanted), Some(id)) => wanted == id,
+                    _ => true,
+                };
+                if !matches {
+                    continue;
+                }
+                format!("event: {}\ndata: {}\n\n", event.name, event.payload)
+            }
+            Ok(Err(broadcast::error::RecvError::Lagged(skipped))) => {
+                format!("event: lagged\ndata: {}\n\n", json!({ "skipped": skipped }))
+            }
+            Ok(Err(broadcast::error::RecvError::Closed)) => break,
+        };
+        if writer.write_all(chunk.as_bytes()).await.is_err() {
+            break;
+        }
+    }
+}
+
+async fn serve_connection(
+    app: AppHandle,
+    stream: TcpStream,
+    token: String,
+    websocket: bool,
+    stopped: Arc<AtomicBool>,
+) {
+    let (reader, mut writer) = stream.into_split();
+    let mut reader = BufReader::new(reader);
+    let request = match read_request(&mut reader).await {
+        Ok(request) => request,
+        Err((status, message)) => {
+            write_error(&mut writer, status, &message).await;
+            return;
+        }
+    };
+    if !authorized(&request, &token) {
+        write_error(&mut writer, 401, "missing or invalid token").await;
+        return;
+    }
+    let result = match (request.method.as_str(), request.path.as_str()) {
+        ("GET", "/v1/workspaces") => list_workspaces(&app).await,
+        ("POST", "/v1/threads") => start_thread(&app, &request).await,
+        ("POST", "/v1/messages") => send_message(&app, &request).await,
+        ("GET", "/v1/events") => {
+            let workspace_id = match request.query.get("workspace") {
+                Some(query) => {
+                    let state = app.state::<AppState>();
+                    match resolve_workspace(&state, query).await {
+                        Ok(entry) => Some(entry.id),
+                        Err(err) => {
+                            write_error(&mut writer, 404, &err.to_string()).await;
+                            return;
+                        }
+                    }
+                }
+                None => None,
+            };
+            stream_events(&app, &mut writer, workspace_id, &stopped).await;
+            return;
+        }
+        ("GET", "/v1/ws") if websocket => {
+            event_bridge::serve(app, reader, writer, &request, stopped).await;
+            return;
+        }
+        (_, "/v1/workspaces" | "/v1/threads" | "/v1/messages" | "/v1/events") => {
+            write_error(&mut writer, 405, "method not allowed").await;
+            return;
+        }
+        _ => {
+            write_error(&mut writer, 404, "not found").await;
+            return;
+        }
+    };
+    match result {
+        Ok(body) => write_json(&mut writer, 200, &body).await,
+        Err(err) => {
+            let status = if matches!(err, AppError::WorkspaceNotFound) {
+                404
+            } else {
+                400
+            };
+            write_json(&mut writer, status, &json!({ "error": err })).await;
+        }
+    }
+}
+
+async fn ensure_token(app: &AppHandle) -> HttpApiConfig {
+    let state = app.state::<AppState>();
+    let mut settings = state.settings.lock().await;
+    let missing = settings
+        .http_api
+        .token
+        .as_deref()
+        .is_none_or(|token| token.trim().is_empty());
+    if settings.http_api.enabled && missing {
+        settings.http_api.token = Some(Uuid::new_v4().simple().to_string());
+        if let Err(err) = write_settings(&state.settings_path, &settings) {
+            tracing::warn!("failed to persist HTTP API token: {err}");
+        }
+        let _ = app.emit("settings-updated", settings.clone());
+    }
+    settings.http_api.clone()
+}
+
+// Starts, restarts or stops the server so it matches the current settings.
+pub(crate) async fn sync(app: &AppHandle) {
+    let config = ensure_token(app).await;
+    let state = app.state::<AppState>();
+    let mut server = state.http_api.server.lock().await;
+    if server.as_ref().map(|running| &running.config) == Some(&config) {
+        return;
+    }
+    if let Some(running) = server.take() {
+        running.stopped.store(true, Ordering::Relaxed);
+        running.task.abort();
+    }
+    *state.http_api.last_error.lock().await = None;
+    if !config.enabled {
+        return;
+    }
+    let listener = match TcpListener::bind(("127.0.0.1", config.port)).await {
+        Ok(listener) => listener,
+        Err(err) => {
+            tracing::warn!(port = config.port, "failed to start HTTP API: {err}");
+            *state.http_api.last_error.lock().await = Some(err.to_string());
+            return;
+        }
+    };
+    let token = config.token.clone().unwrap_or_default();
+    let websocket = config.websocket_enabled;
+    let stopped = Arc::new(AtomicBool::new(false));
+    let accept_stopped = stopped.clone();
+    let app_handle = app.clone();
+    let task = tauri::async_runtime::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            tauri::async_runtime::spawn(serve_connection(
+                app_handle.clone(),
+                stream,
+                token.clone(),
+                websocket,
+                accept_stopped.clone(),
+            ));
+        }
+    });
+    *server = Some(RunningServer {
+        config,
+        stopped,
+        task,
+    });
+}
+
+pub(crate) fn start(app: AppHandle) {
+    let events = app.state::<AppState>().http_api.events.clone();
+    for &name in FORWARDED_EVENTS {
+        let events = events.clone();
+        app.listen_any(name, move |event| {
+            if events.receiver_count() == 0 {
+                return;
+            }
+            let payload = serde_json::from_str(event.payload()).unwrap_or(Value::Null);
+            let _ = events.send(BusEvent { name, payload });
+        });
+    }
+    tauri::async_runtime::spawn(async move { sync(&app).await });
+}
+
+#[tauri::command]
+pub(crate) async fn get_http_api_status(
+    state: State<'_, AppState>,
+) -> Result<HttpApiStatus, AppError> {
+    let server = state.http_api.server.lock().await;
+    Ok(HttpApiStatus {
+        running: server.is_some(),
+        address: server
+            .as_ref()
+            .map(|running| format!("http://127.0.0.1:{}", running.config.port)),
+        error: state.http_api.last_error.lock().await.clone(),
+    })
+}
+
+#[tauri::command]
+pub(crate) async fn regenerate_http_api_token(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<String, AppError> {
+    let token = Uuid::new_v4().simple().to_string();
+    let settings = {
+        let mut settings = state.settings.lock().await;
+        settings.http_api.token = Some(token.clone());
+        write_settings(&state.settings_path, &settings)?;
+        settings.clone()
+    };
+    let _ = app.emit("settings-updated", settings);
+    sync(&app).await;
+    Ok(token)
+}
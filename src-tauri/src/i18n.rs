@@ -0,0 +1,188 @@
+use chrono::{DateTime, Local};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::OnceLock;
+use tauri::State;
+
+use crate::error::AppError;
+use crate::AppState;
+
+const FALLBACK_LANG: &str = "en";
+
+const CATALOGS: &[(&str, &str)] = &[
+    ("en", include_str!("../locales/en.json")),
+    ("de", include_str!("../locales/de.json")),
+    ("es", include_str!("../locales/es.json")),
+    ("fr", include_str!("../locales/fr.json")),
+    ("ja", include_str!("../locales/ja.json")),
+    ("zh-CN", include_str!("../locales/zh-CN.json")),
+];
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct LocaleStrings {
+    lang: String,
+    system_lang: String,
+    available: Vec<String>,
+    strings: BTreeMap<String, String>,
+}
+
+fn catalogs() -> &'static BTreeMap<&'static str, BTreeMap<String, String>> {
+    static CATALOGS_CELL: OnceLock<BTreeMap<&'static str, BTreeMap<String, String>>> =
+        OnceLock::new();
+    CATALOGS_CELL.get_or_init(|| {
+        CATALOGS
+            .iter()
+            .map(|(lang, raw)| {
+                let strings = serde_json::from_str(raw).unwrap_or_else(|err| {
+                    tracing::warn!(lang, "invalid locale catalog: {err}");
+                    BTreeMap::new()
+                });
+                (*lang, strings)
+            })
+            .collect()
+    })
+}
+
+// Maps a BCP 47 or POSIX tag ("de_AT.UTF-8", "zh-Hans-CN") onto a bundled
+// catalog: exact match first, then the language subtag alone.
+fn resolve(tag: &str) -> Option<&'static str> {
+    let tag = tag.split(['.', '@']).next()?.trim().replace('_', "-");
+    if tag.is_empty() {
+        return None;
+    }
+    let language = tag.split('-').next()?.to_ascii_lowercase();
+    let catalog = CATALOGS
+        .iter()
+        .find(|(lang, _)| lang.eq_ignore_ascii_case(&tag))
+        .or_else(|| {
+            CATALOGS.iter().find(|(lang, _)| {
+                lang.split('-')
+                    .next()
+                    .is_some_and(|primary| primary == language)
+            })
+        })?;
+    Some(catalog.0)
+}
+
+#[cfg(target_os = "macos")]
+fn platform_locale() -> Option<String> {
+    let output = std::process::Command::new("defaults")
+        .args(["read", "-g", "AppleLocale"])
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+#[cfg(windows)]
+fn platform_locale() -> Option<String> {
+    let mut buffer = [0u16; 85];
+    let len = unsafe { windows::Win32::Globalization::GetUserDefaultLocaleName(&mut buffer) };
+    (len > 1).then(|| String::from_utf16_lossy(&buffer[..len as usize - 1]))
+}
+
+#[cfg(not(any(target_os = "macos", windows)))]
+fn platform_locale() -> Option<String> {
+    None
+}
+
+pub(crate) fn system_lang() -> &'static str {
+    static SYSTEM_LANG: OnceLock<&'static str> = OnceLock::new();
+    SYSTEM_LANG.get_or_init(|| {
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|name| std::env::var(name).ok())
+            .find(|value| !value.is_empty() && value != "C" && value != "POSIX")
+            .or_else(platform_locale)
+            .and_then(|tag| resolve(&tag))
+            .unwrap_or(FALLBACK_LANG)
+    })
+}
+
+pub(crate) fn lang_for(locale: Option<&str>) -> &'static str {
+    locale
+        .filter(|locale| !locale.trim().is_empty())
+        .and_then(resolve)
+        .unwrap_or_else(system_lang)
+}
+
+pub(crate) async fn app_lang(state: &AppState) -> &'static str {
+    lang_for(state.settings.lock().await.locale.as_deref())
+}
+
+pub(crate) fn t(lang: &str, key: &str) -> String {
+    let catalogs = catalogs();
+    catalogs
+        .get(lang)
+        .and_then(|strings| strings.get(key))
+        .or_else(|| catalogs.get(FALLBACK_LANG)?.get(key))
+        .cloned()
+        .unwrap_or_else(|| key.to_string())
+}
+
+pub(crate) fn t_with(lang: &str, key: &str, args: &[(&str, &str)]) -> String {
+    args.iter().fold(t(lang, key), |text, (name, value)| {
+        text.replace(&format!("{{{name}}}"), value)
+    })
+}
+
+pub(crate) fn format_time(lang: &str, time: DateTime<Local>) -> String {
+    time.format(&t(lang, "format.time")).to_string()
+}
+
+pub(crate) fn format_timestamp_ms(lang: &str, at_ms: i64) -> Option<String> {
+    let time = DateTime::from_timestamp_millis(at_ms)?.with_timezone(&Local);
+    let date = time.format(&t(lang, "format.date")).to_string();
+    Some(t_with(
+        lang,
+        "format.dateTime",
+        &[("date", &date), ("time", &format_time(lang, time))],
+    ))
+}
+
+pub(crate) fn format_duration(lang: &str, duration_ms: i64) -> String {
+    let seconds = duration_ms.max(0) / 1000;
+    let (minutes, hours, days) = (seconds / 60, seconds / 3600, seconds / 86_400);
+    let n = |key: &str, value: i64| t_with(lang, key, &[("n", &value.to_string())]);
+    match (days, hours % 24, minutes % 60) {
+        (0, 0, 0) => n("duration.seconds", seconds),
+        (0, 0, minutes) => n("duration.minutes", minutes),
+        (0, hours, 0) => n("duration.hours", hours),
+        (0, hours, minutes) => t_with(
+            lang,
+            "duration.hoursMinutes",
+            &[("h", &hours.to_string()), ("m", &minutes.to_string())],
+        ),
+        (days, 0, _) => n("duration.days", days),
+        (days, hours, _) => t_with(
+            lang,
+            "duration.daysHours",
+            &[("d", &days.to_string()), ("h", &hours.to_string())],
+        ),
+    }
+}
+
+#[tauri::command]
+pub(crate) async fn get_locale_strings(
+    lang: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<LocaleStrings, AppError> {
+    let lang = match lang.as_deref() {
+        Some(tag) => resolve(tag).unwrap_or(FALLBACK_LANG),
+        None => app_lang(&state).await,
+    };
+    let catalogs = catalogs();
+    let mut strings = catalogs.get(FALLBACK_LANG).cloned().unwrap_or_default();
+    if let Some(localized) = catalogs.get(lang) {
+        strings.extend(localized.clone());
+    }
+    Ok(LocaleStrings {
+        lang: lang.to_string(),
+        system_lang: system_lang().to_string(),
+        available: CATALOGS.iter().map(|(lang, _)| lang.to_string()).collect(),
+        strings,
+    })
+}
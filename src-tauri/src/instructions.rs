@@ -0,0 +1,201 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, State};
+use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
+
+use crate::error::AppError;
+use crate::{diff_patch_to_string, system_time_ms, workspace_codex_home, AppState};
+
+const INSTRUCTIONS_FILE: &str = "AGENTS.md";
+const WATCH_INTERVAL: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum InstructionsScope {
+    #[default]
+    Workspace,
+    Global,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct AgentInstructions {
+    workspace_id: String,
+    scope: InstructionsScope,
+    path: String,
+    exists: bool,
+    content: String,
+    modified_at_ms: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct InstructionsUpdate {
+    path: String,
+    scope: InstructionsScope,
+    diff: String,
+    changed: bool,
+    written: bool,
+    instructions: AgentInstructions,
+}
+
+type Fingerprint = Option<(u64, Option<i64>)>;
+
+#[derive(Default)]
+pub(crate) struct InstructionWatchers {
+    tasks: Mutex<HashMap<String, JoinHandle<()>>>,
+    seen: Mutex<HashMap<PathBuf, Fingerprint>>,
+}
+
+async fn instructions_path(
+    state: &AppState,
+    workspace_id: &str,
+    scope: InstructionsScope,
+) -> Result<PathBuf, AppError> {
+    let workspaces = state.workspaces.lock().await;
+    let entry = workspaces
+        .get(workspace_id)
+        .ok_or(AppError::WorkspaceNotFound)?;
+    match scope {
+        InstructionsScope::Workspace => Ok(PathBuf::from(&entry.path).join(INSTRUCTIONS_FILE)),
+        InstructionsScope::Global => Ok(workspace_codex_home(entry)
+            .ok_or("codex home unavailable")?
+            .join(INSTRUCTIONS_FILE)),
+    }
+}
+
+fn fingerprint(path: &Path) -> Fingerprint {
+    let metadata = fs::metadata(path).ok()?;
+    let modified = metadata.modified().ok().and_then(system_time_ms);
+    Some((metadata.len(), modified))
+}
+
+fn load(workspace_id: &str, scope: InstructionsScope, path: &Path) -> AgentInstructions {
+    let content = fs::read_to_string(path).ok();
+    AgentInstructions {
+        workspace_id: workspace_id.to_string(),
+        scope,
+        path: path.to_string_lossy().to_string(),
+        exists: content.is_some(),
+        content: content.unwrap_or_default(),
+        modified_at_ms: fingerprint(path).and_then(|(_, modified)| modified),
+    }
+}
+
+fn unified_diff(path: &Path, old: &str, new: &str) -> Result<String, git2::Error> {
+    let name = path.file_name().map(Path::new);
+    let mut patch = git2::Patch::from_buffers(old.as_bytes(), name, new.as_bytes(), name, None)?;
+    diff_patch_to_string(&mut patch)
+}
+
+async fn watch(app: &AppHandle, workspace_id: &str) -> Result<(), AppError> {
+    let state = app.state::<AppState>();
+    let mut paths = Vec::new();
+    for scope in [InstructionsScope::Workspace, InstructionsScope::Global] {
+        if let Ok(path) = instructions_path(&state, workspace_id, scope).await {
+            state
+                .instruction_watchers
+                .seen
+                .lock()
+                .await
+                .insert(path.clone(), fingerprint(&path));
+            paths.push((scope, path));
+        }
+    }
+    let mut tasks = state.instruction_watchers.tasks.lock().await;
+    if tasks.get(workspace_id).is_some_and(|task| !task.is_finished()) {
+        return Ok(());
+    }
+    let app = app.clone();
+    let id = workspace_id.to_string();
+    let handle = tokio::spawn(async move {
+        let state = app.state::<AppState>();
+        loop {
+            tokio::time::sleep(WATCH_INTERVAL).await;
+            for (scope, path) in &paths {
+                let current = fingerprint(path);
+                let previous = state
+                    .instruction_watchers
+                    .seen
+                    .lock()
+                    .await
+                    .insert(path.clone(), current);
+                if previous == Some(current) {
+                    continue;
+                }
+                let _ = app.emit("agent-instructions-changed", load(&id, *scope, path));
+            }
+        }
+    });
+    tasks.insert(workspace_id.to_string(), handle);
+    Ok(())
+}
+
+pub(crate) async fn stop_watching(state: &AppState, workspace_id: &str) {
+    if let Some(handle) = state
+        .instruction_watchers
+        .tasks
+        .lock()
+        .await
+        .remove(workspace_id)
+    {
+        handle.abort();
+    }
+}
+
+#[tauri::command]
+pub(crate) async fn read_agent_instructions(
+    workspace_id: String,
+    scope: Option<InstructionsScope>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<AgentInstructions, AppError> {
+    let scope = scope.unwrap_or_default();
+    let path = instructions_path(&state, &workspace_id, scope).await?;
+    watch(&app, &workspace_id).await?;
+    Ok(load(&workspace_id, scope, &path))
+}
+
+#[tauri::command]
+pub(crate) async fn write_agent_instructions(
+    workspace_id: String,
+    content: String,
+    scope: Option<InstructionsScope>,
+    preview: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<InstructionsUpdate, AppError> {
+    let scope = scope.unwrap_or_default();
+    let path = instructions_path(&state, &workspace_id, scope).await?;
+    let current = load(&workspace_id, scope, &path);
+    let diff = unified_diff(&path, &current.content, &content)?;
+    let changed = !current.exists || current.content != content;
+    let written = changed && !preview.unwrap_or(false);
+    if written {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&path, &content)?;
+        state
+            .instruction_watchers
+            .seen
+            .lock()
+            .await
+            .insert(path.clone(), fingerprint(&path));
+    }
+    Ok(InstructionsUpdate {
+        path: path.to_string_lossy().to_string(),
+        scope,
+        diff,
+        changed,
+        written,
+        instructions: if written {
+            load(&workspace_id, scope, &path)
+        } else {
+            current
+        },
+    })
+}
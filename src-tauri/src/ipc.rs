@@ -0,0 +1,284 @@
+use serde_json::{json, Value};
+use std::io::{BufRead, BufReader as StdBufReader, Write};
+use std::path::Path;
+use tauri::{AppHandle, Manager};
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+
+use crate::deep_link;
+use crate::error::AppError;
+use crate::{AppState, UserMessageRequest, WorkspaceEntry};
+
+#[cfg(unix)]
+const IPC_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+
+#[cfg(unix)]
+fn socket_path(data_dir: &Path) -> std::path::PathBuf {
+    data_dir.join("codexola.sock")
+}
+
+#[cfg(windows)]
+fn pipe_name(data_dir: &Path) -> String {
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+    let mut hasher = DefaultHasher::new();
+    data_dir.to_string_lossy().to_lowercase().hash(&mut hasher);
+    format!(r"\\.\pipe\codexola-{:016x}", hasher.finish())
+}
+
+fn string_field(request: &Value, key: &str) -> Option<String> {
+    request
+        .get(key)
+        .and_then(|value| value.as_str())
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+pub(crate) async fn resolve_workspace(
+    state: &AppState,
+    query: &str,
+) -> Result<WorkspaceEntry, AppError> {
+    let workspaces = state.workspaces.lock().await;
+    if let Some(entry) = workspaces.get(query) {
+        return Ok(entry.clone());
+    }
+    let by_name: Vec<&WorkspaceEntry> = workspaces
+        .values()
+        .filter(|entry| entry.name.eq_ignore_ascii_case(query))
+        .collect();
+    match by_name.as_slice() {
+        [entry] => return Ok((*entry).clone()),
+        [] => {}
+        _ => return Err(format!("multiple workspaces are named {query}").into()),
+    }
+    let target = std::fs::canonicalize(query).ok();
+    workspaces
+        .values()
+        .find(|entry| {
+            target.is_some() && std::fs::canonicalize(&entry.path).ok() == target
+        })
+        .cloned()
+        .ok_or(AppError::WorkspaceNotFound)
+}
+
+async fn handle_status(app: &AppHandle) -> Result<Value, AppError> {
+    let state = app.state::<AppState>();
+    let workspaces: Vec<WorkspaceEntry> =
+        state.workspaces.lock().await.values().cloned().collect();
+    let sessions = state.sessions.lock().await;
+    let mut workspaces: Vec<Value> = workspaces
+        .into_iter()
+        .map(|entry| {
+            json!({
+                "id": entry.id,
+                "name": entry.name,
+                "path": entry.path,
+                "connected": sessions.contains_key(&entry.id),
+            })
+        })
+        .collect();
+    workspaces.sort_by(|a, b| a["name"].as_str().cmp(&b["name"].as_str()));
+    Ok(json!({
+        "version": env!("CARGO_PKG_VERSION"),
+        "dataDir": state.data_dir.to_string_lossy(),
+        "workspaces": workspaces,
+    }))
+}
+
+async fn handle_send(app: &AppHandle, request: &Value) -> Result<Value, AppError> {
+    let query = string_field(request, "workspace").ok_or("missing workspace")?;
+    let prompt = string_field(request, "prompt").ok_or("missing prompt")?;
+    let model = string_field(request, "model");
+    let effort = string_field(request, "effort");
+    let state = app.state::<AppState>();
+    let entry = resolve_workspace(&state, &query).await?;
+    let connected = state.sessions.lock().await.contains_key(&entry.id);
+    if !connected {
+        crate::connect_workspace(entry.id.clone(), app.state(), app.clone()).await?;
+    }
+    let thread_id = match string_field(request, "threadId") {
+        Some(thread_id) => {
+            crate::resume_thread(
+                entry.id.clone(),
+                thread_id.clone(),
+                None,
+                None,
+                model.clone(),
+                effort.clone(),
+                app.state(),
+            )
+            .await?;
+            thread_id
+        }
+        None => {
+            let response = crate::start_thread(
+                entry.id.clone(),
+                None,
+                None,
+                model.clone(),
+                effort.clone(),
+                app.state(),
+            )
+            .await?;
+            if let Some(error) = AppError::from_rpc_response(&response) {
+                return Err(error);
+            }
+            let thread = response
+                .get("result")
+                .and_then(|result| result.get("thread"))
+                .or_else(|| response.get("thread"));
+            thread
+                .and_then(|thread| thread.get("id"))
+                .and_then(|id| id.as_str())
+                .map(|id| id.to_string())
+                .ok_or("thread/start returned no thread id")?
+        }
+    };
+    let message = UserMessageRequest {
+        thread_id: thread_id.clone(),
+        text: prompt,
+        model,
+        effort,
+        access_mode: string_field(request, "accessMode"),
+        attachments: Vec::new(),
+    };
+    let message = crate::secrets::screen(app, &state, &entry.id, message, None).await?;
+    let checkpoint = state.settings.lock().await.turn_checkpoints_enabled;
+    let session = crate::connected_session(&state, &entry.id).await?;
+    let response = crate::dispatch_user_message(app, &session, &message, checkpoint).await?;
+    if let Some(error) = AppError::from_rpc_response(&response) {
+        return Err(error);
+    }
+    Ok(json!({ "workspaceId": entry.id, "threadId": thread_id }))
+}
+
+fn respond(result: Result<Value, AppError>) -> Value {
+    match result {
+        Ok(result) => json!({ "ok": true, "result": result }),
+        Err(err) => json!({ "ok": false, "error": err }),
+    }
+}
+
+async fn handle_request(app: &AppHandle, request: Value) -> Value {
+    match request.get("type").and_then(|value| value.as_str()) {
+        Some("open") => {
+            let args = request
+                .get("args")
+                .and_then(|value| value.as_array())
+                .map(|args| {
+                    args.iter()
+                        .filter_map(|arg| arg.as_str().map(|arg| arg.to_string()))
+                        .collect::<Vec<_>>()
+                })
+                .unwrap_or_default();
+            deep_link::focus_main_window(app);
+            deep_link::handle_urls(app, deep_link::deep_link_args(args));
+            json!({ "ok": true })
+        }
+        Some("status") => respond(handle_status(app).await),
+        Some("send") => respond(handle_send(app, &request).await),
+        Some(other) => json!({ "ok": false, "error": format!("unknown request: {other}") }),
+        None => json!({ "ok": false, "error": "missing request type" }),
+    }
+}
+
+async fn serve_connection<S>(app: AppHandle, stream: S)
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let (reader, mut writer) = tokio::io::split(stream);
+    let mut lines = BufReader::new(reader).lines();
+    while let Ok(Some(line)) = lines.next_line().await {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let response = match serde_json::from_str::<Value>(&line) {
+            Ok(request) => handle_request(&app, request).await,
+            Err(err) => json!({ "ok": false, "error": err.to_string() }),
+        };
+        let mut payload = response.to_string();
+        payload.push('\n');
+        if writer.write_all(payload.as_bytes()).await.is_err() {
+            break;
+        }
+    }
+}
+
+#[cfg(unix)]
+pub(crate) fn start_listener(app: AppHandle, data_dir: &Path) {
+    let path = socket_path(data_dir);
+    tauri::async_runtime::spawn(async move {
+        let _ = std::fs::remove_file(&path);
+        let listener = match tokio::net::UnixListener::bind(&path) {
+            Ok(listener) => listener,
+            Err(err) => {
+                tracing::error!("failed to bind {}: {err}", path.display());
+                return;
+            }
+        };
+        while let Ok((stream, _)) = listener.accept().await {
+            tauri::async_runtime::spawn(serve_connection(app.clone(), stream));
+        }
+    });
+}
+
+#[cfg(windows)]
+pub(crate) fn start_listener(app: AppHandle, data_dir: &Path) {
+    use tokio::net::windows::named_pipe::ServerOptions;
+    let name = pipe_name(data_dir);
+    tauri::async_runtime::spawn(async move {
+        let mut first = true;
+        loop {
+            let server = match ServerOptions::new()
+                .first_pipe_instance(first)
+                .create(&name)
+            {
+                Ok(server) => server,
+                Err(err) => {
+                    tracing::error!("failed to create pipe {name}: {err}");
+                    return;
+                }
+            };
+            first = false;
+            if server.connect().await.is_err() {
+                continue;
+            }
+            tauri::async_runtime::spawn(serve_connection(app.clone(), server));
+        }
+    });
+}
+
+fn exchange<S: std::io::Read + Write>(stream: S, request: &Value) -> Result<Value, String> {
+    let mut reader = StdBufReader::new(stream);
+    let mut payload = request.to_string();
+    payload.push('\n');
+    reader
+        .get_mut()
+        .write_all(payload.as_bytes())
+        .map_err(|e| e.to_string())?;
+    let mut line = String::new();
+    reader.read_line(&mut line).map_err(|e| e.to_string())?;
+    if line.trim().is_empty() {
+        return Err("no response from running instance".to_string());
+    }
+    serde_json::from_str(&line).map_err(|e| e.to_string())
+}
+
+#[cfg(unix)]
+pub(crate) fn send_request(data_dir: &Path, request: &Value) -> Result<Value, String> {
+    let stream = std::os::unix::net::UnixStream::connect(socket_path(data_dir))
+        .map_err(|e| e.to_string())?;
+    stream
+        .set_read_timeout(Some(IPC_TIMEOUT))
+        .map_err(|e| e.to_string())?;
+    exchange(stream, request)
+}
+
+#[cfg(windows)]
+pub(crate) fn send_request(data_dir: &Path, request: &Value) -> Result<Value, String> {
+    let pipe = std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(pipe_name(data_dir))
+        .map_err(|e| e.to_string())?;
+    exchange(pipe, request)
+}
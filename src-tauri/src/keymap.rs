@@ -0,0 +1,271 @@
+use serde::Serialize;
+use std::collections::BTreeMap;
+use tauri::menu::{MenuItemKind, Submenu};
+use tauri::{AppHandle, Emitter, State};
+
+use crate::error::AppError;
+use crate::{write_settings, AppState};
+
+pub(crate) const ACTIONS: &[(&str, &str)] = &[
+    ("new-thread", "CmdOrCtrl+N"),
+    ("preferences", "CmdOrCtrl+,"),
+    ("quick-switch", "CmdOrCtrl+K"),
+    ("interrupt-turn", "CmdOrCtrl+."),
+    ("quit", "CmdOrCtrl+Q"),
+];
+
+const RESERVED: &[&str] = &[
+    "CmdOrCtrl+A",
+    "CmdOrCtrl+C",
+    "CmdOrCtrl+V",
+    "CmdOrCtrl+X",
+    "CmdOrCtrl+Z",
+    "CmdOrCtrl+Shift+Z",
+];
+
+#[cfg(target_os = "macos")]
+const PLATFORM_RESERVED: &[&str] = &["Cmd+Tab", "Cmd+Space", "Cmd+H", "Cmd+M", "Cmd+W"];
+#[cfg(not(target_os = "macos"))]
+const PLATFORM_RESERVED: &[&str] = &["Alt+Tab", "Alt+F4", "Super+L"];
+
+const NAMED_KEYS: &[&str] = &[
+    "Backspace", "Delete", "Down", "End", "Enter", "Escape", "Home", "Insert", "Left",
+    "PageDown", "PageUp", "Right", "Space", "Tab", "Up",
+];
+
+const MODIFIER_ORDER: &[&str] = &["Cmd", "Super", "Ctrl", "Alt", "Shift"];
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Keybinding {
+    action: String,
+    accelerator: Option<String>,
+    default_accelerator: String,
+    customized: bool,
+}
+
+pub(crate) fn default_accelerator(action: &str) -> Option<&'static str> {
+    ACTIONS
+        .iter()
+        .find(|(id, _)| *id == action)
+        .map(|(_, accelerator)| *accelerator)
+}
+
+fn resolve(keymap: &BTreeMap<String, String>, action: &str) -> Option<String> {
+    match keymap.get(action) {
+        Some(accelerator) if accelerator.trim().is_empty() => None,
+        Some(accelerator) => Some(accelerator.trim().to_string()),
+        None => default_accelerator(action).map(str::to_string),
+    }
+}
+
+fn normalize_key(key: &str) -> Option<String> {
+    let mut chars = key.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        if c.is_ascii_alphanumeric() || ",./;'[]\\-=`".contains(c) {
+            return Some(c.to_ascii_uppercase().to_string());
+        }
+        return None;
+    }
+    let lower = key.to_ascii_lowercase();
+    if let Some(number) = lower.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+        return (1..=24).contains(&number).then(|| format!("F{number}"));
+    }
+    let lower = match lower.as_str() {
+        "esc" => "escape",
+        "return" => "enter",
+        "arrowup" => "up",
+        "arrowdown" => "down",
+        "arrowleft" => "left",
+        "arrowright" => "right",
+        other => other,
+    };
+    NAMED_KEYS
+        .iter()
+        .find(|named| named.to_ascii_lowercase() == lower)
+        .map(|named| named.to_string())
+}
+
+// Returns the accelerator in a canonical, platform-resolved form so that
+// "CmdOrCtrl+K" and "Ctrl+k" compare equal on Windows and Linux.
+fn normalize(accelerator: &str) -> Result<String, String> {
+    let mut modifiers = Vec::new();
+    let mut key = None;
+    for part in accelerator.split('+').map(str::trim) {
+        let modifier = match part.to_ascii_lowercase().as_str() {
+            "cmdorctrl" | "commandorcontrol" => {
+                if cfg!(target_os = "macos") {
+                    "Cmd"
+                } else {
+                    "Ctrl"
+                }
+            }
+            "cmd" | "command" if !cfg!(target_os = "macos") => {
+                return Err(format!("{part} is only available on macOS; use CmdOrCtrl."))
+            }
+            "option" if !cfg!(target_os = "macos") => {
+                return Err("Option is only available on macOS; use Alt.".to_string())
+            }
+            "cmd" | "command" => "Cmd",
+            "super" | "meta" => "Super",
+            "ctrl" | "control" => "Ctrl",
+            "alt" | "option" => "Alt",
+            "shift" => "Shift",
+            "" => return Err(format!("Invalid accelerator: {accelerator}")),
+            _ => {
+                if key.is_some() {
+                    return Err(format!("{accelerator} has more than one key."));
+                }
+                key = Some(normalize_key(part).ok_or_else(|| format!("Unknown key: {part}"))?);
+                continue;
+            }
+        };
+        if !modifiers.contains(&modifier) {
+            modifiers.push(modifier);
+        }
+    }
+    let key = key.ok_or_else(|| format!("{accelerator} has no key."))?;
+    let function_key = key.len() > 1 && key.starts_with('F') && key[1..].parse::<u8>().is_ok();
+    if !function_key && !modifiers.iter().any(|modifier| *modifier != "Shift") {
+        return Err(format!("{accelerator} needs a Cmd, Ctrl or Alt modifier."));
+    }
+    let mut parts: Vec<String> = MODIFIER_ORDER
+        .iter()
+        .filter(|modifier| modifiers.contains(modifier))
+        .map(|modifier| modifier.to_string())
+        .collect();
+    parts.push(key);
+    Ok(parts.join("+"))
+}
+
+pub(crate) fn validate(keymap: &BTreeMap<String, String>) -> Result<(), String> {
+    if let Some(action) = keymap.keys().find(|action| default_accelerator(action).is_none()) {
+        return Err(format!("Unknown shortcut action: {action}"));
+    }
+    let reserved: Vec<String> = RESERVED
+        .iter()
+        .chain(PLATFORM_RESERVED)
+        .filter_map(|accelerator| normalize(accelerator).ok())
+        .collect();
+    let mut seen: BTreeMap<String, &str> = BTreeMap::new();
+    for (action, _) in ACTIONS {
+        let Some(accelerator) = resolve(keymap, action) else {
+            continue;
+        };
+        let normalized = normalize(&accelerator)?;
+        if reserved.contains(&normalized) {
+            return Err(format!("{accelerator} is reserved by the system."));
+        }
+        if let Some(other) = seen.insert(normalized, action) {
+            return Err(format!("{accelerator} is already used by {other}."));
+        }
+    }
+    Ok(())
+}
+
+fn keybindings(keymap: &BTreeMap<String, String>) -> Vec<Keybinding> {
+    ACTIONS
+        .iter()
+        .map(|(action, default)| Keybinding {
+            action: action.to_string(),
+            accelerator: resolve(keymap, action),
+            default_accelerator: default.to_string(),
+            customized: keymap.contains_key(*action),
+        })
+        .collect()
+}
+
+fn find_menu_item(
+    items: Vec<MenuItemKind<tauri::Wry>>,
+    id: &str,
+) -> Option<MenuItemKind<tauri::Wry>> {
+    for item in items {
+        if item.id() == id {
+            return Some(item);
+        }
+        if let MenuItemKind::Submenu(submenu) = &item {
+            if let Some(found) = find_menu_item(submenu.items().unwrap_or_default(), id) {
+                return Some(found);
+            }
+        }
+    }
+    None
+}
+
+pub(crate) fn find_submenu(
+    items: Vec<MenuItemKind<tauri::Wry>>,
+    text: &str,
+) -> Option<Submenu<tauri::Wry>> {
+    items.into_iter().find_map(|item| match item {
+        MenuItemKind::Submenu(submenu) if submenu.text().is_ok_and(|t| t == text) => Some(submenu),
+        _ => None,
+    })
+}
+
+pub(crate) fn apply(app: &AppHandle, keymap: &BTreeMap<String, String>) {
+    let Some(menu) = app.menu() else {
+        return;
+    };
+    let items = menu.items().unwrap_or_default();
+    for (action, _) in ACTIONS {
+        let Some(MenuItemKind::MenuItem(item)) = find_menu_item(items.clone(), action) else {
+            continue;
+        };
+        if let Err(err) = item.set_accelerator(resolve(keymap, action)) {
+            tracing::warn!(action, "failed to set menu accelerator: {err}");
+        }
+    }
+}
+
+async fn update_keymap(
+    app: &AppHandle,
+    state: &AppState,
+    change: impl FnOnce(&mut BTreeMap<String, String>),
+) -> Result<Vec<Keybinding>, AppError> {
+    let settings = {
+        let mut settings = state.settings.lock().await;
+        let mut keymap = settings.keymap.clone();
+        change(&mut keymap);
+        validate(&keymap)?;
+        settings.keymap = keymap;
+        write_settings(&state.settings_path, &settings)?;
+        settings.clone()
+    };
+    apply(app, &settings.keymap);
+    let _ = app.emit("settings-updated", settings.clone());
+    Ok(keybindings(&settings.keymap))
+}
+
+#[tauri::command]
+pub(crate) async fn get_keymap(state: State<'_, AppState>) -> Result<Vec<Keybinding>, AppError> {
+    Ok(keybindings(&state.settings.lock().await.keymap))
+}
+
+#[tauri::command]
+pub(crate) async fn set_keybinding(
+    action: String,
+    accelerator: Option<String>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Vec<Keybinding>, AppError> {
+    if default_accelerator(&action).is_none() {
+        return Err(format!("Unknown shortcut action: {action}").into());
+    }
+    update_keymap(&app, &state, |keymap| match accelerator {
+        Some(accelerator) => {
+            keymap.insert(action, accelerator.trim().to_string());
+        }
+        None => {
+            keymap.remove(&action);
+        }
+    })
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn reset_keymap(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Vec<Keybinding>, AppError> {
+    update_keymap(&app, &state, BTreeMap::clear).await
+}
@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Map, Value};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, VecDeque};
 use std::env;
 use std::fs;
 use std::io::{BufRead, BufReader as StdBufReader};
@@ -8,19 +8,88 @@ use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
-use chrono::DateTime;
+use chrono::{DateTime, Local};
 use git2::{DiffOptions, Repository, Status, StatusOptions, Tree};
+use ignore::overrides::{Override, OverrideBuilder};
 use ignore::WalkBuilder;
-use tauri::{
-    menu::{Menu, MenuItem, MenuItemKind},
-    AppHandle, Emitter, Manager, State,
-};
-use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+#[cfg(target_os = "macos")]
+use tauri::menu::{Menu, MenuItem, MenuItemKind};
+use tauri::{AppHandle, Emitter, Manager, State};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
 use tokio::process::{Child, ChildStdin, Command};
-use tokio::sync::{Mutex, oneshot};
+use tokio::sync::{mpsc, Mutex, Semaphore, oneshot};
 use tokio::task::JoinHandle;
 use uuid::Uuid;
 
+use crate::error::AppError;
+
+mod analytics;
+mod approval_context;
+mod attachments;
+mod audit;
+mod auto_connect;
+mod badge;
+mod batch_report;
+mod batching;
+mod broadcast;
+mod capabilities;
+mod cli;
+mod code_blocks;
+mod command_allowlist;
+mod compaction;
+mod container;
+mod context;
+mod crash;
+mod deep_link;
+mod deferrals;
+mod discovery;
+mod dock;
+mod error;
+mod event_blobs;
+mod event_bridge;
+mod file_versions;
+mod hooks;
+mod http_api;
+mod i18n;
+mod instructions;
+mod ipc;
+mod keymap;
+mod logging;
+mod menu;
+mod metrics;
+mod notifications;
+mod observer;
+mod patch;
+mod path_guard;
+mod platform;
+mod quick_switch;
+mod rate_history;
+mod recording;
+mod review;
+mod review_findings;
+mod scheduler;
+mod secrets;
+mod shell_env;
+mod skills;
+mod speech;
+mod snapshot;
+mod stats;
+mod storage;
+mod system_events;
+mod task_queue;
+mod tasks;
+mod templates;
+mod terminal;
+mod thread_access;
+mod thread_state;
+mod titles;
+mod tray;
+mod undo;
+mod updater;
+mod window_state;
+mod workspace_edit;
+mod workspace_merge;
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 struct GitFileStatus {
     path: String,
@@ -30,12 +99,133 @@ struct GitFileStatus {
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
 struct GitFileDiff {
     path: String,
     diff: String,
+    #[serde(default)]
+    is_binary: bool,
+    #[serde(default)]
+    old_size: u64,
+    #[serde(default)]
+    new_size: u64,
+    #[serde(default)]
+    truncated: bool,
+    #[serde(default)]
+    image: Option<DiffImagePaths>,
+    #[serde(default)]
+    denied: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct DiffImagePaths {
+    before: Option<String>,
+    after: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct ConflictSide {
+    oid: String,
+    content: Option<String>,
+    is_binary: bool,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct GitConflict {
+    path: String,
+    base: Option<ConflictSide>,
+    ours: Option<ConflictSide>,
+    theirs: Option<ConflictSide>,
 }
 
 #[derive(Debug, Deserialize, Clone)]
+#[serde(tag = "type", rename_all = "camelCase")]
+enum ConflictResolution {
+    Ours,
+    Theirs,
+    Content { content: String },
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct BlameLine {
+    line: usize,
+    content: String,
+    commit: Option<String>,
+    author: Option<String>,
+    email: Option<String>,
+    timestamp: Option<i64>,
+    summary: Option<String>,
+    uncommitted: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct Checkpoint {
+    id: String,
+    thread_id: String,
+    commit: String,
+    head: Option<String>,
+    created_at_ms: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+struct CheckpointStore {
+    #[serde(default)]
+    checkpoints: Vec<Checkpoint>,
+}
+
+const CHECKPOINT_REF_PREFIX: &str = "refs/codexmonitor/checkpoints/";
+const CHECKPOINT_MAX_PER_WORKSPACE: usize = 200;
+
+const DIFF_IMAGE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "gif", "webp", "bmp", "ico", "svg"];
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct DiffSegment {
+    text: String,
+    changed: bool,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct DiffLine {
+    kind: String,
+    old_line: Option<u32>,
+    new_line: Option<u32>,
+    content: String,
+    segments: Option<Vec<DiffSegment>>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct DiffHunk {
+    header: String,
+    old_start: u32,
+    old_lines: u32,
+    new_start: u32,
+    new_lines: u32,
+    lines: Vec<DiffLine>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct StructuredFileDiff {
+    path: String,
+    old_path: Option<String>,
+    status: String,
+    additions: usize,
+    deletions: usize,
+    hunks: Vec<DiffHunk>,
+}
+
+const WORD_DIFF_MAX_CELLS: usize = 40_000;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
 struct LocalImageInput {
     path: String,
 }
@@ -47,6 +237,7 @@ struct PromptListItem {
     path: String,
     description: Option<String>,
     argument_hint: Option<String>,
+    last_used: Option<i64>,
 }
 
 #[derive(Debug, Serialize, Clone)]
@@ -58,6 +249,32 @@ struct PromptFile {
     argument_hint: Option<String>,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct PromptUsageEvent {
+    name: String,
+    workspace_id: Option<String>,
+    timestamp_ms: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+struct PromptUsageStore {
+    #[serde(default)]
+    events: Vec<PromptUsageEvent>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct PromptStat {
+    name: String,
+    count: usize,
+    last_used: i64,
+    workspace_ids: Vec<String>,
+}
+
+const PROMPT_USAGE_MAX_EVENTS: usize = 2000;
+
 #[derive(Default)]
 struct PromptFrontMatter {
     description: Option<String>,
@@ -72,6 +289,101 @@ fn normalize_path(path: &Path) -> String {
     path.to_string_lossy().replace('\\', "/")
 }
 
+fn resolve_workspace_path(root: &str, relative: &str) -> Result<PathBuf, String> {
+    use std::path::Component;
+    let root = fs::canonicalize(root).map_err(|e| e.to_string())?;
+    let display_root = platform::simplify_path(&root);
+    let requested = Path::new(relative.trim());
+    let requested = requested
+        .strip_prefix(&root)
+        .or_else(|_| requested.strip_prefix(&display_root))
+        .unwrap_or(requested);
+    let mut resolved = root.clone();
+    for component in requested.components() {
+        match component {
+            Component::Normal(part) => resolved.push(part),
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if resolved == root || !resolved.pop() {
+                    return Err("path escapes the workspace".to_string());
+                }
+            }
+            Component::RootDir | Component::Prefix(_) => {
+                return Err("path must be relative to the workspace".to_string());
+            }
+        }
+    }
+    // Canonicalize the longest existing ancestor so a new path under a
+    // symlinked directory (or a dangling symlink) can't escape the root.
+    let mut existing = resolved.as_path();
+    let mut remainder = Vec::new();
+    while fs::symlink_metadata(existing).is_err() {
+        let (Some(name), Some(parent)) = (existing.file_name(), existing.parent()) else {
+            return Err("path escapes the workspace".to_string());
+        };
+        remainder.push(name.to_os_string());
+        existing = parent;
+    }
+    let mut canonical = fs::canonicalize(existing).map_err(|e| e.to_string())?;
+    if !canonical.starts_with(&root) {
+        return Err("path escapes the workspace".to_string());
+    }
+    canonical.extend(remainder.iter().rev());
+    Ok(canonical)
+}
+
+fn detect_language(path: &Path) -> Option<&'static str> {
+    let name = path.file_name()?.to_str()?;
+    match name {
+        "Dockerfile" => return Some("dockerfile"),
+        "Makefile" | "makefile" | "GNUmakefile" => return Some("makefile"),
+        _ => {}
+    }
+    let ext = path.extension()?.to_str()?.to_lowercase();
+    let language = match ext.as_str() {
+        "rs" => "rust",
+        "ts" | "mts" | "cts" => "typescript",
+        "tsx" => "tsx",
+        "js" | "mjs" | "cjs" => "javascript",
+        "jsx" => "jsx",
+        "py" => "python",
+        "go" => "go",
+        "rb" => "ruby",
+        "java" => "java",
+        "kt" | "kts" => "kotlin",
+        "swift" => "swift",
+        "c" | "h" => "c",
+        "cc" | "cpp" | "cxx" | "hpp" | "hh" => "cpp",
+        "cs" => "csharp",
+        "php" => "php",
+        "sh" | "bash" | "zsh" => "shell",
+        "json" => "json",
+        "toml" => "toml",
+        "yaml" | "yml" => "yaml",
+        "md" | "markdown" => "markdown",
+        "html" | "htm" => "html",
+        "css" => "css",
+        "scss" => "scss",
+        "sql" => "sql",
+        "xml" => "xml",
+        "lua" => "lua",
+        "txt" => "plaintext",
+        _ => return None,
+    };
+    Some(language)
+}
+
+fn looks_binary(bytes: &[u8]) -> bool {
+    let sample = &bytes[..bytes.len().min(8192)];
+    if sample.contains(&0) {
+        return true;
+    }
+    match std::str::from_utf8(sample) {
+        Ok(_) => false,
+        Err(err) => err.error_len().is_some(),
+    }
+}
+
 fn is_excluded_dir(path: &Path) -> bool {
     path.file_name()
         .and_then(|name| name.to_str())
@@ -79,1699 +391,5465 @@ fn is_excluded_dir(path: &Path) -> bool {
         .unwrap_or(false)
 }
 
-fn diff_stats_for_path(
-    repo: &Repository,
-    head_tree: Option<&Tree>,
-    path: &str,
-    include_index: bool,
-    include_workdir: bool,
-) -> Result<(i64, i64), git2::Error> {
-    let mut additions = 0i64;
-    let mut deletions = 0i64;
-
-    if include_index {
-        let mut options = DiffOptions::new();
-        options.pathspec(path).include_untracked(true);
-        let diff = repo.diff_tree_to_index(head_tree, None, Some(&mut options))?;
-        let stats = diff.stats()?;
-        additions += stats.insertions() as i64;
-        deletions += stats.deletions() as i64;
-    }
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+struct SearchConfig {
+    #[serde(default)]
+    exclude: Vec<String>,
+    #[serde(default)]
+    include: Vec<String>,
+}
 
-    if include_workdir {
-        let mut options = DiffOptions::new();
-        options
-            .pathspec(path)
-            .include_untracked(true)
-            .recurse_untracked_dirs(true)
-            .show_untracked_content(true);
-        let diff = repo.diff_index_to_workdir(None, Some(&mut options))?;
-        let stats = diff.stats()?;
-        additions += stats.insertions() as i64;
-        deletions += stats.deletions() as i64;
-    }
+fn workspace_search_config_path(workspace_path: &str) -> PathBuf {
+    PathBuf::from(workspace_path)
+        .join(".codexmonitor")
+        .join("search.json")
+}
 
-    Ok((additions, deletions))
+fn read_search_config(path: &Path) -> Result<SearchConfig, String> {
+    storage::read_json_or_default(path)
 }
 
-fn diff_patch_to_string(patch: &mut git2::Patch) -> Result<String, git2::Error> {
-    let buf = patch.to_buf()?;
-    Ok(buf
-        .as_str()
-        .map(|value| value.to_string())
-        .unwrap_or_else(|| String::from_utf8_lossy(&buf).to_string()))
+fn write_search_config(path: &Path, config: &SearchConfig) -> Result<(), String> {
+    storage::write_json_atomic(path, config)
 }
 
-fn prompts_dir() -> Option<PathBuf> {
-    if let Ok(value) = env::var("HOME") {
-        if !value.trim().is_empty() {
-            return Some(PathBuf::from(value).join(".codex").join("prompts"));
+fn build_search_overrides(root: &Path, config: &SearchConfig) -> Result<Override, String> {
+    let mut builder = OverrideBuilder::new(root);
+    for glob in &config.include {
+        let glob = glob.trim();
+        if !glob.is_empty() {
+            builder.add(glob).map_err(|e| e.to_string())?;
         }
     }
-    if let Ok(value) = env::var("USERPROFILE") {
-        if !value.trim().is_empty() {
-            return Some(PathBuf::from(value).join(".codex").join("prompts"));
+    for glob in &config.exclude {
+        let glob = glob.trim().trim_start_matches('!');
+        if !glob.is_empty() {
+            builder.add(&format!("!{glob}")).map_err(|e| e.to_string())?;
         }
     }
-    None
+    builder.build().map_err(|e| e.to_string())
 }
 
-fn parse_prompt_file(contents: &str) -> (PromptFrontMatter, String) {
-    let mut front_matter = PromptFrontMatter::default();
-    let mut lines = contents.lines();
-    let first_line = match lines.next() {
-        Some(line) => line.trim_end_matches('\r'),
-        None => return (front_matter, String::new()),
-    };
-    if first_line != "---" {
-        return (front_matter, contents.to_string());
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct FileSearchMatch {
+    path: String,
+    score: i64,
+    indices: Vec<usize>,
+}
+
+struct FileIndex {
+    files: Arc<Vec<String>>,
+    built_at_ms: i64,
+    fingerprint: u64,
+    stale: bool,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct FileIndexHealth {
+    files: usize,
+    built_at_ms: i64,
+    age_ms: i64,
+    stale: bool,
+    watching: bool,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct WorkspaceHealth {
+    workspace_id: String,
+    connected: bool,
+    file_index: Option<FileIndexHealth>,
+    requests: Option<RequestQueueHealth>,
+}
+
+const FILE_INDEX_DEBOUNCE: Duration = Duration::from_millis(300);
+const FILE_INDEX_POLL_MIN: Duration = Duration::from_secs(5);
+const FILE_INDEX_POLL_MAX: Duration = Duration::from_secs(60);
+
+const FUZZY_SCORE_MATCH: i64 = 16;
+const FUZZY_BONUS_BOUNDARY: i64 = 8;
+const FUZZY_BONUS_CONSECUTIVE: i64 = 6;
+const FUZZY_BONUS_BASENAME: i64 = 2;
+const FUZZY_PENALTY_GAP: i64 = 1;
+
+fn fuzzy_boundary_bonus(chars: &[char], index: usize) -> i64 {
+    if index == 0 {
+        return FUZZY_BONUS_BOUNDARY;
+    }
+    let prev = chars[index - 1];
+    let current = chars[index];
+    if matches!(prev, '/' | '_' | '-' | '.' | ' ') {
+        return FUZZY_BONUS_BOUNDARY;
     }
+    if prev.is_lowercase() && current.is_uppercase() {
+        return FUZZY_BONUS_BOUNDARY / 2;
+    }
+    0
+}
 
-    let mut front_lines: Vec<String> = Vec::new();
-    let mut body_start: Option<usize> = None;
-    let mut offset = 0usize;
-    for (index, line) in contents.split_inclusive('\n').enumerate() {
-        let trimmed = line.trim_end_matches(&['\r', '\n'][..]);
-        if index == 0 {
-            offset += line.len();
-            continue;
-        }
-        if trimmed == "---" {
-            body_start = Some(offset + line.len());
-            break;
+const FUZZY_NONE: i64 = i64::MIN / 4;
+
+fn fuzzy_lower(c: char) -> char {
+    c.to_lowercase().next().unwrap_or(c)
+}
+
+fn reset_buffer<T: Copy>(buffer: &mut Vec<T>, len: usize, value: T) {
+    buffer.clear();
+    buffer.resize(len, value);
+}
+
+// Built once per query and reused across candidates: a subsequence check
+// rejects non-matches before the scoring pass, and the score tables are flat
+// buffers that keep their allocation between candidates.
+struct FuzzyMatcher {
+    needle: Vec<char>,
+    chars: Vec<char>,
+    lower: Vec<char>,
+    matched: Vec<i64>,
+    from_consecutive: Vec<bool>,
+    best: Vec<i64>,
+    best_at: Vec<usize>,
+}
+
+impl FuzzyMatcher {
+    fn new(query: &str) -> Self {
+        Self {
+            needle: query
+                .chars()
+                .filter(|c| !c.is_whitespace())
+                .map(fuzzy_lower)
+                .collect(),
+            chars: Vec::new(),
+            lower: Vec::new(),
+            matched: Vec::new(),
+            from_consecutive: Vec::new(),
+            best: Vec::new(),
+            best_at: Vec::new(),
         }
-        front_lines.push(trimmed.to_string());
-        offset += line.len();
     }
 
-    if let Some(start) = body_start {
-        for line in front_lines {
-            if let Some((key, value)) = line.split_once(':') {
-                let key = key.trim();
-                let value = parse_prompt_value(value);
-                match key {
-                    "description" => front_matter.description = value,
-                    "argument-hint" | "argument_hint" => {
-                        front_matter.argument_hint = value
-                    }
-                    _ => {}
+    fn is_subsequence(&self, candidate: &str) -> bool {
+        let mut needle = self.needle.iter().peekable();
+        for c in candidate.chars() {
+            match needle.peek() {
+                Some(&&wanted) if fuzzy_lower(c) == wanted => {
+                    needle.next();
                 }
+                Some(_) => {}
+                None => break,
             }
         }
-        let body = contents.get(start..).unwrap_or_default().to_string();
-        return (front_matter, body);
+        needle.peek().is_none()
     }
 
-    (front_matter, contents.to_string())
-}
+    fn score(&mut self, candidate: &str) -> Option<(i64, Vec<usize>)> {
+        if self.needle.is_empty() || !self.is_subsequence(candidate) {
+            return None;
+        }
+        let Self {
+            needle,
+            chars,
+            lower,
+            matched,
+            from_consecutive,
+            best,
+            best_at,
+        } = self;
+        chars.clear();
+        chars.extend(candidate.chars());
+        lower.clear();
+        lower.extend(chars.iter().copied().map(fuzzy_lower));
+        let (n, m) = (lower.len(), needle.len());
+        let basename_start = candidate
+            .rfind('/')
+            .map(|index| candidate[..=index].chars().count())
+            .unwrap_or(0);
+
+        // matched[i * n + j]: best score with needle[i] matched exactly at j.
+        // best[i * n + j]: best score with needle[i] matched at or before j (gap-penalized).
+        reset_buffer(matched, m * n, FUZZY_NONE);
+        reset_buffer(from_consecutive, m * n, false);
+        reset_buffer(best, m * n, FUZZY_NONE);
+        reset_buffer(best_at, m * n, 0);
+
+        for (i, &wanted) in needle.iter().enumerate() {
+            for (j, &ch) in lower.iter().enumerate() {
+                let at = i * n + j;
+                if ch == wanted && j >= i {
+                    let mut base = FUZZY_SCORE_MATCH + fuzzy_boundary_bonus(chars, j);
+                    if j >= basename_start {
+                        base += FUZZY_BONUS_BASENAME;
+                    }
+                    if i == 0 {
+                        matched[at] = base;
+                    } else if j > 0 {
+                        let diagonal = at - n - 1;
+                        let consecutive =
+                            matched[diagonal].saturating_add(FUZZY_BONUS_CONSECUTIVE);
+                        let gapped = best[diagonal];
+                        if consecutive >= gapped && matched[diagonal] > FUZZY_NONE {
+                            matched[at] = base + consecutive;
+                            from_consecutive[at] = true;
+                        } else if gapped > FUZZY_NONE {
+                            matched[at] = base + gapped;
+                        }
+                    }
+                }
+                let carried = if j > 0 && best[at - 1] > FUZZY_NONE {
+                    best[at - 1] - FUZZY_PENALTY_GAP
+                } else {
+                    FUZZY_NONE
+                };
+                if matched[at] > FUZZY_NONE && matched[at] >= carried {
+                    best[at] = matched[at];
+                    best_at[at] = j;
+                } else {
+                    best[at] = carried;
+                    best_at[at] = if j > 0 { best_at[at - 1] } else { 0 };
+                }
+            }
+        }
 
-fn parse_prompt_value(raw: &str) -> Option<String> {
-    let trimmed = raw.trim();
-    if trimmed.is_empty() {
-        return None;
+        let last = m * n - 1;
+        let score = best[last];
+        if score <= FUZZY_NONE {
+            return None;
+        }
+        let mut indices = vec![0usize; m];
+        let mut j = best_at[last];
+        for i in (0..m).rev() {
+            indices[i] = j;
+            if i == 0 {
+                break;
+            }
+            j = if from_consecutive[i * n + j] {
+                j - 1
+            } else {
+                best_at[(i - 1) * n + j - 1]
+            };
+        }
+        Some((score, indices))
     }
-    let unquoted = if (trimmed.starts_with('"') && trimmed.ends_with('"'))
-        || (trimmed.starts_with('\'') && trimmed.ends_with('\''))
-    {
-        trimmed[1..trimmed.len() - 1].to_string()
-    } else {
-        trimmed.to_string()
-    };
-    Some(unquoted)
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
-struct WorkspaceEntry {
-    id: String,
-    name: String,
-    path: String,
-    codex_bin: Option<String>,
+fn fuzzy_match(candidate: &str, query: &str) -> Option<(i64, Vec<usize>)> {
+    FuzzyMatcher::new(query).score(candidate)
 }
 
-fn default_session_store_version() -> u32 {
-    1
+fn workspace_walker(root: &Path, overrides: Override) -> ignore::Walk {
+    WalkBuilder::new(root)
+        .git_ignore(true)
+        .git_exclude(true)
+        .require_git(false)
+        .overrides(overrides)
+        .filter_entry(|entry| {
+            if entry.depth() == 0 {
+                return true;
+            }
+            if entry
+                .file_type()
+                .map(|file_type| file_type.is_dir())
+                .unwrap_or(false)
+                && is_excluded_dir(entry.path())
+            {
+                return false;
+            }
+            true
+        })
+        .build()
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
-#[serde(rename_all = "lowercase")]
-enum SessionNameSource {
-    Default,
-    Custom,
+fn collect_workspace_files(root: &Path, overrides: Override) -> Vec<String> {
+    let mut files = Vec::new();
+    for entry in workspace_walker(root, overrides) {
+        let entry = match entry {
+            Ok(value) => value,
+            Err(_) => continue,
+        };
+        if !entry
+            .file_type()
+            .map(|file_type| file_type.is_file())
+            .unwrap_or(false)
+        {
+            continue;
+        }
+        if let Ok(relative) = entry.path().strip_prefix(root) {
+            files.push(normalize_path(relative));
+        }
+    }
+    files
 }
 
-impl Default for SessionNameSource {
-    fn default() -> Self {
-        SessionNameSource::Default
+fn workspace_dir_fingerprint(root: &Path, overrides: Override) -> u64 {
+    use std::hash::{Hash, Hasher};
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    for entry in workspace_walker(root, overrides) {
+        let entry = match entry {
+            Ok(value) => value,
+            Err(_) => continue,
+        };
+        if !entry
+            .file_type()
+            .map(|file_type| file_type.is_dir())
+            .unwrap_or(false)
+        {
+            continue;
+        }
+        entry.path().hash(&mut hasher);
+        if let Some(modified) = entry
+            .metadata()
+            .ok()
+            .and_then(|metadata| metadata.modified().ok())
+            .and_then(system_time_ms)
+        {
+            modified.hash(&mut hasher);
+        }
     }
+    hasher.finish()
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
-#[serde(rename_all = "camelCase")]
-struct SessionMetadata {
-    #[serde(default)]
-    name: String,
-    #[serde(default)]
-    archived: bool,
-    #[serde(default)]
-    name_source: SessionNameSource,
+async fn build_file_index(
+    state: &AppState,
+    entry: &WorkspaceEntry,
+) -> Result<Arc<Vec<String>>, String> {
+    let root = platform::long_path(Path::new(&entry.path));
+    let config = read_search_config(&workspace_search_config_path(&entry.path))?;
+    let overrides = build_search_overrides(&root, &config)?;
+    let (files, fingerprint) = tokio::task::spawn_blocking(move || {
+        let fingerprint = workspace_dir_fingerprint(&root, overrides.clone());
+        (collect_workspace_files(&root, overrides), fingerprint)
+    })
+    .await
+    .map_err(|_| "file index build failed".to_string())?;
+    let guard = path_guard::PathGuard::load(&entry.path);
+    let files: Vec<String> = files.into_iter().filter(|file| !guard.denies(file)).collect();
+    let files = Arc::new(files);
+    state.file_indexes.lock().await.insert(
+        entry.id.clone(),
+        FileIndex {
+            files: Arc::clone(&files),
+            built_at_ms: now_ms(),
+            fingerprint,
+            stale: false,
+        },
+    );
+    Ok(files)
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
-#[serde(rename_all = "camelCase")]
-struct WorkspaceSessionStore {
-    #[serde(default = "default_session_store_version")]
-    version: u32,
-    #[serde(default)]
-    sessions: HashMap<String, SessionMetadata>,
+async fn workspace_file_list(
+    state: &AppState,
+    entry: &WorkspaceEntry,
+) -> Result<Arc<Vec<String>>, String> {
+    if let Some(index) = state.file_indexes.lock().await.get(&entry.id) {
+        if !index.stale {
+            return Ok(Arc::clone(&index.files));
+        }
+    }
+    build_file_index(state, entry).await
 }
 
-impl Default for WorkspaceSessionStore {
-    fn default() -> Self {
-        Self {
-            version: default_session_store_version(),
-            sessions: HashMap::new(),
-        }
+async fn mark_file_index_stale(state: &AppState, workspace_id: &str) {
+    if let Some(index) = state.file_indexes.lock().await.get_mut(workspace_id) {
+        index.stale = true;
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
-#[serde(rename_all = "kebab-case")]
-enum ThemePreference {
-    System,
-    Light,
-    Dark,
+fn is_indexed_change(root: &Path, path: &Path) -> bool {
+    let relative = path.strip_prefix(root).unwrap_or(path);
+    !is_monitor_path(relative) && !relative.ancestors().any(is_excluded_dir)
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
-#[serde(rename_all = "kebab-case")]
-enum AccessMode {
-    ReadOnly,
-    Current,
-    FullAccess,
+fn watch_workspace_files(
+    root: &Path,
+) -> notify::Result<(notify::RecommendedWatcher, mpsc::UnboundedReceiver<()>)> {
+    use notify::Watcher;
+    let (tx, rx) = mpsc::unbounded_channel();
+    let watched_root = root.to_path_buf();
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        let relevant = match event {
+            Ok(event) => {
+                !matches!(event.kind, notify::EventKind::Access(_))
+                    && event
+                        .paths
+                        .iter()
+                        .any(|path| is_indexed_change(&watched_root, path))
+            }
+            // Overflows and backend errors may hide changes; rebuild to be safe.
+            Err(_) => true,
+        };
+        if relevant {
+            let _ = tx.send(());
+        }
+    })?;
+    watcher.watch(root, notify::RecursiveMode::Recursive)?;
+    Ok((watcher, rx))
 }
 
-fn default_sidebar_width() -> i64 {
-    280
+// Fallback for trees the OS watcher can't cover (watch limits, network mounts):
+// compare directory mtimes only, backing off while the tree is quiet.
+async fn poll_file_index(state: &AppState, entry: &WorkspaceEntry) {
+    let mut interval = FILE_INDEX_POLL_MIN;
+    loop {
+        tokio::time::sleep(interval).await;
+        interval = (interval * 2).min(FILE_INDEX_POLL_MAX);
+        let (known, stale) = match state.file_indexes.lock().await.get(&entry.id) {
+            Some(index) => (Some(index.fingerprint), index.stale),
+            None => (None, true),
+        };
+        if !stale {
+            let root = platform::long_path(Path::new(&entry.path));
+            let config_path = workspace_search_config_path(&entry.path);
+            let config = read_search_config(&config_path).unwrap_or_default();
+            let Ok(overrides) = build_search_overrides(&root, &config) else {
+                continue;
+            };
+            let fingerprint =
+                tokio::task::spawn_blocking(move || workspace_dir_fingerprint(&root, overrides))
+                    .await
+                    .ok();
+            if fingerprint.is_none() || fingerprint == known {
+                continue;
+            }
+        }
+        interval = FILE_INDEX_POLL_MIN;
+        if let Err(err) = build_file_index(state, entry).await {
+            tracing::warn!(workspace_id = %entry.id, "file index rebuild failed: {err}");
+        }
+    }
 }
 
-fn default_glass_blur_light() -> f64 {
-    32.0
+async fn start_file_index_watcher(app: &AppHandle, entry: WorkspaceEntry) {
+    let state = app.state::<AppState>();
+    let app_handle = app.clone();
+    let workspace_id = entry.id.clone();
+    let handle = tauri::async_runtime::spawn(async move {
+        let state = app_handle.state::<AppState>();
+        let root = platform::long_path(Path::new(&entry.path));
+        let watcher = watch_workspace_files(&root);
+        if let Err(err) = build_file_index(&state, &entry).await {
+            tracing::warn!(workspace_id = %entry.id, "file index build failed: {err}");
+        }
+        let (_watcher, mut changes) = match watcher {
+            Ok(watcher) => watcher,
+            Err(err) => {
+                tracing::warn!(workspace_id = %entry.id, "file watcher unavailable: {err}");
+                poll_file_index(&state, &entry).await;
+                return;
+            }
+        };
+        while changes.recv().await.is_some() {
+            // Let bursts (checkouts, builds) settle into a single rebuild.
+            while tokio::time::timeout(FILE_INDEX_DEBOUNCE, changes.recv())
+                .await
+                .is_ok_and(|change| change.is_some())
+            {}
+            if let Err(err) = build_file_index(&state, &entry).await {
+                tracing::warn!(workspace_id = %entry.id, "file index rebuild failed: {err}");
+            }
+        }
+    });
+    let previous = state
+        .file_index_watchers
+        .lock()
+        .await
+        .insert(workspace_id, handle);
+    if let Some(previous) = previous {
+        previous.abort();
+    }
 }
 
-fn default_glass_blur_dark() -> f64 {
-    32.0
+async fn stop_file_index_watcher(state: &AppState, workspace_id: &str) {
+    if let Some(handle) = state.file_index_watchers.lock().await.remove(workspace_id) {
+        handle.abort();
+    }
+    state.file_indexes.lock().await.remove(workspace_id);
 }
 
-fn default_glass_opacity_light() -> f64 {
-    1.0
+fn is_file_change_notification(message: &Value) -> bool {
+    let method = message
+        .get("method")
+        .and_then(|method| method.as_str())
+        .unwrap_or("");
+    if method == "turn/completed" {
+        return true;
+    }
+    if method != "item/completed" {
+        return false;
+    }
+    message
+        .get("params")
+        .and_then(|params| params.get("item"))
+        .and_then(|item| item.get("type"))
+        .and_then(|kind| kind.as_str())
+        .map(|kind| kind == "fileChange")
+        .unwrap_or(false)
 }
 
-fn default_glass_opacity_dark() -> f64 {
-    1.0
+fn diff_stats_for_path(
+    repo: &Repository,
+    head_tree: Option<&Tree>,
+    path: &str,
+    include_index: bool,
+    include_workdir: bool,
+) -> Result<(i64, i64), git2::Error> {
+    let mut additions = 0i64;
+    let mut deletions = 0i64;
+
+    if include_index {
+        let mut options = DiffOptions::new();
+        options.pathspec(path).include_untracked(true);
+        let diff = repo.diff_tree_to_index(head_tree, None, Some(&mut options))?;
+        let stats = diff.stats()?;
+        additions += stats.insertions() as i64;
+        deletions += stats.deletions() as i64;
+    }
+
+    if include_workdir {
+        let mut options = DiffOptions::new();
+        options
+            .pathspec(path)
+            .include_untracked(true)
+            .recurse_untracked_dirs(true)
+            .show_untracked_content(true);
+        let diff = repo.diff_index_to_workdir(None, Some(&mut options))?;
+        let stats = diff.stats()?;
+        additions += stats.insertions() as i64;
+        deletions += stats.deletions() as i64;
+    }
+
+    Ok((additions, deletions))
 }
 
-fn default_usage_polling_enabled() -> bool {
-    true
+fn diff_patch_to_string(patch: &mut git2::Patch) -> Result<String, git2::Error> {
+    let buf = patch.to_buf()?;
+    Ok(buf
+        .as_str()
+        .map(|value| value.to_string())
+        .unwrap_or_else(|| String::from_utf8_lossy(&buf).to_string()))
 }
 
-fn default_usage_polling_interval_minutes() -> i64 {
-    5
+fn worktree_diff<'a>(
+    repo: &'a Repository,
+    pathspec: Option<&str>,
+) -> Result<git2::Diff<'a>, String> {
+    let head_tree = repo
+        .head()
+        .ok()
+        .and_then(|head| head.peel_to_tree().ok());
+
+    let mut options = DiffOptions::new();
+    options
+        .include_untracked(true)
+        .recurse_untracked_dirs(true)
+        .show_untracked_content(true);
+    if let Some(pathspec) = pathspec {
+        options.pathspec(pathspec).disable_pathspec_match(true);
+    }
+
+    repo.diff_tree_to_workdir_with_index(head_tree.as_ref(), Some(&mut options))
+        .map_err(|e| e.to_string())
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
-#[serde(rename_all = "camelCase")]
-struct AppSettings {
-    theme_preference: ThemePreference,
-    access_mode: AccessMode,
-    bypass_approvals_and_sandbox: bool,
-    enable_web_search_request: bool,
-    #[serde(default)]
-    confirm_before_quit: bool,
-    #[serde(default)]
-    enable_completion_notifications: bool,
-    #[serde(default = "default_usage_polling_enabled")]
-    usage_polling_enabled: bool,
-    #[serde(default = "default_usage_polling_interval_minutes")]
-    usage_polling_interval_minutes: i64,
-    #[serde(default = "default_sidebar_width")]
-    sidebar_width: i64,
-    #[serde(default = "default_glass_blur_light")]
-    glass_blur_light: f64,
-    #[serde(default = "default_glass_blur_dark")]
-    glass_blur_dark: f64,
-    #[serde(default = "default_glass_opacity_light")]
-    glass_opacity_light: f64,
-    #[serde(default = "default_glass_opacity_dark")]
-    glass_opacity_dark: f64,
-    #[serde(default)]
-    codex_bin_path: Option<String>,
-    #[serde(default)]
-    node_bin_path: Option<String>,
-    #[serde(default)]
-    workspace_sidebar_expanded: HashMap<String, bool>,
+fn resolve_diff_base<'a>(
+    repo: &'a Repository,
+    base: &str,
+    head: Option<&git2::Commit<'a>>,
+) -> Result<Tree<'a>, String> {
+    let base = base.trim();
+    if let Some(branch) = base.strip_suffix("...") {
+        let branch_commit = repo
+            .revparse_single(branch)
+            .and_then(|object| object.peel_to_commit())
+            .map_err(|e| e.to_string())?;
+        let head_oid = match head {
+            Some(commit) => commit.id(),
+            None => repo
+                .head()
+                .and_then(|reference| reference.peel_to_commit())
+                .map_err(|e| e.to_string())?
+                .id(),
+        };
+        let merge_base = repo
+            .merge_base(branch_commit.id(), head_oid)
+            .map_err(|e| e.to_string())?;
+        return repo
+            .find_commit(merge_base)
+            .and_then(|commit| commit.tree())
+            .map_err(|e| e.to_string());
+    }
+    repo.revparse_single(base)
+        .and_then(|object| object.peel_to_tree())
+        .map_err(|e| e.to_string())
 }
 
-impl Default for AppSettings {
-    fn default() -> Self {
-        Self {
-            theme_preference: ThemePreference::System,
-            access_mode: AccessMode::Current,
-            bypass_approvals_and_sandbox: false,
-            enable_web_search_request: false,
-            confirm_before_quit: false,
-            enable_completion_notifications: false,
-            usage_polling_enabled: default_usage_polling_enabled(),
-            usage_polling_interval_minutes: default_usage_polling_interval_minutes(),
-            sidebar_width: default_sidebar_width(),
-            glass_blur_light: default_glass_blur_light(),
-            glass_blur_dark: default_glass_blur_dark(),
-            glass_opacity_light: default_glass_opacity_light(),
-            glass_opacity_dark: default_glass_opacity_dark(),
-            codex_bin_path: None,
-            node_bin_path: None,
-            workspace_sidebar_expanded: HashMap::new(),
-        }
+fn truncate_patch(content: &mut String, max_bytes: usize) -> bool {
+    if max_bytes == 0 || content.len() <= max_bytes {
+        return false;
+    }
+    let mut cut = max_bytes;
+    while !content.is_char_boundary(cut) {
+        cut -= 1;
     }
+    if let Some(newline) = content[..cut].rfind('\n') {
+        cut = newline + 1;
+    }
+    content.truncate(cut);
+    true
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
-#[serde(rename_all = "kebab-case")]
-enum UsageSource {
-    None,
-    AppServer,
-    Sessions,
+fn is_image_path(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| DIFF_IMAGE_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()))
+        .unwrap_or(false)
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
-#[serde(rename_all = "camelCase")]
-struct UsagePoint {
-    timestamp_ms: i64,
-    tokens: i64,
+fn export_diff_blob(repo: &Repository, oid: git2::Oid, path: &Path) -> Option<String> {
+    if oid.is_zero() {
+        return None;
+    }
+    let blob = repo.find_blob(oid).ok()?;
+    let ext = path.extension().and_then(|ext| ext.to_str()).unwrap_or("bin");
+    let dir = env::temp_dir().join("codexmonitor-diff-images");
+    fs::create_dir_all(&dir).ok()?;
+    let target = dir.join(format!("{oid}.{ext}"));
+    if !target.exists() {
+        fs::write(&target, blob.content()).ok()?;
+    }
+    Some(normalize_path(&target))
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
-#[serde(rename_all = "camelCase")]
-struct UsageSnapshot {
-    total_tokens_24h: Option<i64>,
-    updated_at_ms: Option<i64>,
-    source: UsageSource,
-    rate_limits: Option<RateLimitSnapshot>,
+fn diff_image_paths(repo: &Repository, delta: &git2::DiffDelta) -> Option<DiffImagePaths> {
+    let path = delta.new_file().path().or_else(|| delta.old_file().path())?;
+    if !is_image_path(path) {
+        return None;
+    }
+    let before = delta
+        .old_file()
+        .path()
+        .and_then(|old| export_diff_blob(repo, delta.old_file().id(), old));
+    let after = delta.new_file().path().and_then(|new| {
+        export_diff_blob(repo, delta.new_file().id(), new).or_else(|| {
+            let absolute = repo.workdir()?.join(new);
+            absolute.is_file().then(|| normalize_path(&absolute))
+        })
+    });
+    Some(DiffImagePaths { before, after })
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
-#[serde(rename_all = "camelCase")]
-struct UsageStore {
-    #[serde(default)]
-    app_server_points: Vec<UsagePoint>,
-    #[serde(default)]
-    last_snapshot: Option<UsageSnapshot>,
-    #[serde(default)]
-    last_rate_limits: Option<RateLimitSnapshot>,
-}
-
-impl Default for UsageStore {
-    fn default() -> Self {
-        Self {
-            app_server_points: Vec::new(),
-            last_snapshot: None,
-            last_rate_limits: None,
+fn collect_file_diffs(
+    repo: &Repository,
+    diff: &git2::Diff,
+    max_bytes: usize,
+) -> Vec<GitFileDiff> {
+    let guard = repo
+        .workdir()
+        .map(|workdir| path_guard::PathGuard::load(&workdir.to_string_lossy()));
+    let mut results = Vec::new();
+    for (index, delta) in diff.deltas().enumerate() {
+        let Some(path) = delta.new_file().path().or_else(|| delta.old_file().path()) else {
+            continue;
+        };
+        let Ok(Some(mut patch)) = git2::Patch::from_diff(diff, index) else {
+            continue;
+        };
+        let Ok(mut content) = diff_patch_to_string(&mut patch) else {
+            continue;
+        };
+        let patch_delta = patch.delta();
+        let is_binary = patch_delta.flags().is_binary()
+            || patch_delta.old_file().is_binary()
+            || patch_delta.new_file().is_binary();
+        if content.trim().is_empty() && !is_binary {
+            continue;
         }
+        let truncated = truncate_patch(&mut content, max_bytes);
+        let path = normalize_git_path(path.to_string_lossy().as_ref());
+        results.push(GitFileDiff {
+            denied: guard.as_ref().is_some_and(|guard| guard.denies(&path)),
+            path,
+            diff: content,
+            is_binary,
+            old_size: patch_delta.old_file().size(),
+            new_size: patch_delta.new_file().size(),
+            truncated,
+            image: diff_image_paths(repo, &patch_delta),
+        });
     }
+    results
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
-#[serde(rename_all = "camelCase")]
-struct RateLimitWindow {
-    used_percent: i64,
-    window_duration_mins: Option<i64>,
-    resets_at: Option<i64>,
-}
-
-#[derive(Debug, Serialize, Deserialize, Clone)]
-#[serde(rename_all = "camelCase")]
-struct RateLimitSnapshot {
-    primary: Option<RateLimitWindow>,
-    secondary: Option<RateLimitWindow>,
-}
-
-#[derive(Debug, Serialize, Deserialize, Clone)]
-struct WorkspaceInfo {
-    id: String,
-    name: String,
-    path: String,
-    connected: bool,
-    codex_bin: Option<String>,
+fn parse_progress_percent(message: &str) -> Option<u8> {
+    let end = message.find('%')?;
+    let digits: String = message[..end]
+        .chars()
+        .rev()
+        .take_while(|ch| ch.is_ascii_digit())
+        .collect::<Vec<_>>()
+        .into_iter()
+        .rev()
+        .collect();
+    digits.parse::<u8>().ok()
 }
 
-#[derive(Serialize, Clone)]
-struct AppServerEvent {
-    workspace_id: String,
-    message: Value,
+fn open_repository(path: impl AsRef<Path>) -> Result<Repository, git2::Error> {
+    Repository::open(platform::simplify_path(path.as_ref()))
 }
 
-struct WorkspaceSession {
-    entry: WorkspaceEntry,
-    child: Mutex<Child>,
-    stdin: Mutex<ChildStdin>,
-    pending: Mutex<HashMap<u64, oneshot::Sender<Value>>>,
-    next_id: AtomicU64,
+fn default_remote_name(path: &str) -> Result<String, String> {
+    let repo = open_repository(path).map_err(|e| e.to_string())?;
+    let upstream_remote = repo
+        .head()
+        .ok()
+        .and_then(|head| head.name().map(|name| name.to_string()))
+        .and_then(|name| repo.branch_upstream_remote(&name).ok())
+        .and_then(|buf| buf.as_str().map(|value| value.to_string()));
+    if let Some(remote) = upstream_remote {
+        return Ok(remote);
+    }
+    let remotes = repo.remotes().map_err(|e| e.to_string())?;
+    let names: Vec<&str> = remotes.iter().flatten().collect();
+    if names.contains(&"origin") {
+        return Ok("origin".to_string());
+    }
+    names
+        .first()
+        .map(|name| name.to_string())
+        .ok_or_else(|| "no git remote configured".to_string())
 }
 
-impl WorkspaceSession {
-    async fn write_message(&self, value: Value) -> Result<(), String> {
-        let mut stdin = self.stdin.lock().await;
-        let mut line = serde_json::to_string(&value).map_err(|e| e.to_string())?;
-        line.push('\n');
-        stdin
-            .write_all(line.as_bytes())
-            .await
-            .map_err(|e| e.to_string())
+async fn run_git_remote_command(
+    app: &AppHandle,
+    workspace_id: &str,
+    path: &str,
+    operation: &str,
+    args: &[String],
+) -> Result<String, String> {
+    let mut command = platform::command("git");
+    command
+        .args(args)
+        .current_dir(platform::simplify_path(Path::new(path)))
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped());
+    // Without a terminal an ssh passphrase or host-key prompt would hang forever.
+    let custom_ssh = std::env::var_os("GIT_SSH_COMMAND").is_some()
+        || std::env::var_os("GIT_SSH").is_some()
+        || Repository::open(path)
+            .and_then(|repo| repo.config())
+            .and_then(|config| config.get_string("core.sshCommand"))
+            .is_ok();
+    if !custom_ssh {
+        command.env("GIT_SSH_COMMAND", "ssh -o BatchMode=yes");
     }
+    let mut child = command.spawn().map_err(|e| e.to_string())?;
+    let mut stdout = child.stdout.take().ok_or("missing stdout")?;
+    let mut stderr = child.stderr.take().ok_or("missing stderr")?;
 
-    async fn send_request(&self, method: &str, params: Value) -> Result<Value, String> {
-        let id = self.next_id.fetch_add(1, Ordering::SeqCst);
-        let (tx, rx) = oneshot::channel();
-        self.pending.lock().await.insert(id, tx);
-        self.write_message(json!({ "id": id, "method": method, "params": params }))
-            .await?;
-        rx.await.map_err(|_| "request canceled".to_string())
-    }
+    let stdout_task = tauri::async_runtime::spawn(async move {
+        let mut output = String::new();
+        let _ = stdout.read_to_string(&mut output).await;
+        output
+    });
 
-    async fn send_notification(&self, method: &str, params: Option<Value>) -> Result<(), String> {
-        let value = if let Some(params) = params {
-            json!({ "method": method, "params": params })
-        } else {
-            json!({ "method": method })
+    let mut collected = String::new();
+    let mut pending = String::new();
+    let mut buffer = [0u8; 4096];
+    loop {
+        let count = match stderr.read(&mut buffer).await {
+            Ok(0) | Err(_) => break,
+            Ok(count) => count,
         };
-        self.write_message(value).await
+        pending.push_str(&String::from_utf8_lossy(&buffer[..count]));
+        while let Some(index) = pending.find(['\r', '\n']) {
+            let message = pending[..index].trim().to_string();
+            let terminator = pending.as_bytes()[index];
+            pending.drain(..=index);
+            if message.is_empty() {
+                continue;
+            }
+            let _ = app.emit(
+                "git-progress",
+                json!({
+                    "workspaceId": workspace_id,
+                    "operation": operation,
+                    "message": message,
+                    "percent": parse_progress_percent(&message),
+                }),
+            );
+            if terminator == b'\n' {
+                collected.push_str(&message);
+                collected.push('\n');
+            }
+        }
     }
-
-    async fn send_response(&self, id: u64, result: Value) -> Result<(), String> {
-        self.write_message(json!({ "id": id, "result": result }))
-            .await
+    if !pending.trim().is_empty() {
+        collected.push_str(pending.trim());
+        collected.push('\n');
     }
-}
 
-struct AppState {
-    workspaces: Mutex<HashMap<String, WorkspaceEntry>>,
-    sessions: Mutex<HashMap<String, Arc<WorkspaceSession>>>,
-    storage_path: PathBuf,
-    settings: Mutex<AppSettings>,
-    settings_path: PathBuf,
-    allow_quit: AtomicBool,
-    usage_store: Mutex<UsageStore>,
-    usage_path: PathBuf,
-    usage_poll_handle: Mutex<Option<JoinHandle<()>>>,
-    usage_probe_inflight: AtomicBool,
+    let status = child.wait().await.map_err(|e| e.to_string())?;
+    let stdout = stdout_task.await.unwrap_or_default();
+    let output = format!("{stdout}{collected}").trim().to_string();
+    if status.success() {
+        Ok(output)
+    } else if output.is_empty() {
+        Err(format!("git {operation} failed"))
+    } else {
+        Err(output)
+    }
 }
 
-impl AppState {
-    fn load(app: &AppHandle) -> Self {
-        let app_data_dir = app
-            .path()
-            .app_data_dir()
-            .unwrap_or_else(|_| std::env::current_dir().unwrap_or_else(|_| ".".into()))
-            .to_path_buf();
-        let storage_path = app_data_dir.join("workspaces.json");
-        let settings_path = app_data_dir.join("settings.json");
-        let usage_path = app_data_dir.join("usage.json");
-        let workspaces = read_workspaces(&storage_path).unwrap_or_default();
-        let settings = read_settings(&settings_path).unwrap_or_default();
-        let usage_store = read_usage_store(&usage_path).unwrap_or_default();
-        Self {
-            workspaces: Mutex::new(workspaces),
-            sessions: Mutex::new(HashMap::new()),
-            storage_path,
-            settings: Mutex::new(settings),
-            settings_path,
-            allow_quit: AtomicBool::new(false),
-            usage_store: Mutex::new(usage_store),
-            usage_path,
-            usage_poll_handle: Mutex::new(None),
-            usage_probe_inflight: AtomicBool::new(false),
+fn thread_summary_body(entry: &WorkspaceEntry, thread_id: &str) -> Option<String> {
+    let codex_home = workspace_codex_home(entry)?;
+    let rollout = find_rollout_file(&codex_home, thread_id)?;
+    let messages = read_rollout_transcript(&rollout, None).ok()?;
+    let request = messages.iter().find(|message| message.role == "user");
+    let outcome = messages.iter().rev().find(|message| message.role == "assistant");
+    if request.is_none() && outcome.is_none() {
+        return None;
+    }
+    let mut body = String::new();
+    if let Some(request) = request {
+        body.push_str(&format!("## Request\n\n{}\n", request.text.trim()));
+    }
+    if let Some(outcome) = outcome {
+        if !body.is_empty() {
+            body.push('\n');
         }
+        body.push_str(&format!("## Summary\n\n{}\n", outcome.text.trim()));
     }
+    Some(body)
 }
 
-fn read_workspaces(path: &PathBuf) -> Result<HashMap<String, WorkspaceEntry>, String> {
-    if !path.exists() {
-        return Ok(HashMap::new());
-    }
-    let data = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
-    let list: Vec<WorkspaceEntry> = serde_json::from_str(&data).map_err(|e| e.to_string())?;
-    Ok(list.into_iter().map(|entry| (entry.id.clone(), entry)).collect())
+fn last_url(output: &str) -> Option<String> {
+    output
+        .split_whitespace()
+        .rev()
+        .find(|token| token.starts_with("https://") || token.starts_with("http://"))
+        .map(|token| token.to_string())
 }
 
-fn write_workspaces(path: &PathBuf, entries: &[WorkspaceEntry]) -> Result<(), String> {
-    if let Some(parent) = path.parent() {
-        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
-    }
-    let data = serde_json::to_string_pretty(entries).map_err(|e| e.to_string())?;
-    std::fs::write(path, data).map_err(|e| e.to_string())
+fn conflict_side(repo: &Repository, entry: Option<&git2::IndexEntry>) -> Option<ConflictSide> {
+    let entry = entry?;
+    let blob = repo.find_blob(entry.id).ok();
+    let is_binary = blob.as_ref().map(|blob| blob.is_binary()).unwrap_or(false);
+    let content = blob
+        .filter(|blob| !blob.is_binary())
+        .map(|blob| String::from_utf8_lossy(blob.content()).to_string());
+    Some(ConflictSide {
+        oid: entry.id.to_string(),
+        content,
+        is_binary,
+    })
 }
 
-fn read_settings(path: &PathBuf) -> Result<AppSettings, String> {
-    if !path.exists() {
-        return Ok(AppSettings::default());
-    }
-    let data = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
-    serde_json::from_str(&data).map_err(|e| e.to_string())
+fn conflict_entry_path(conflict: &git2::IndexConflict) -> Option<String> {
+    let entry = conflict
+        .our
+        .as_ref()
+        .or(conflict.their.as_ref())
+        .or(conflict.ancestor.as_ref())?;
+    Some(normalize_git_path(&String::from_utf8_lossy(&entry.path)))
 }
 
-fn write_settings(path: &PathBuf, settings: &AppSettings) -> Result<(), String> {
-    if let Some(parent) = path.parent() {
-        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+fn list_git_conflicts(repo: &Repository) -> Result<Vec<GitConflict>, String> {
+    let index = repo.index().map_err(|e| e.to_string())?;
+    if !index.has_conflicts() {
+        return Ok(Vec::new());
     }
-    let data = serde_json::to_string_pretty(settings).map_err(|e| e.to_string())?;
-    std::fs::write(path, data).map_err(|e| e.to_string())
-}
-
-fn read_usage_store(path: &PathBuf) -> Result<UsageStore, String> {
-    if !path.exists() {
-        return Ok(UsageStore::default());
+    let mut conflicts = Vec::new();
+    for conflict in index.conflicts().map_err(|e| e.to_string())? {
+        let conflict = conflict.map_err(|e| e.to_string())?;
+        let Some(path) = conflict_entry_path(&conflict) else {
+            continue;
+        };
+        conflicts.push(GitConflict {
+            path,
+            base: conflict_side(repo, conflict.ancestor.as_ref()),
+            ours: conflict_side(repo, conflict.our.as_ref()),
+            theirs: conflict_side(repo, conflict.their.as_ref()),
+        });
     }
-    let data = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
-    serde_json::from_str(&data).map_err(|e| e.to_string())
+    Ok(conflicts)
 }
 
-fn write_usage_store(path: &PathBuf, store: &UsageStore) -> Result<(), String> {
-    if let Some(parent) = path.parent() {
-        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
-    }
-    let data = serde_json::to_string_pretty(store).map_err(|e| e.to_string())?;
-    std::fs::write(path, data).map_err(|e| e.to_string())
+fn workspace_checkpoints_path(workspace_path: &str) -> PathBuf {
+    PathBuf::from(workspace_path)
+        .join(".codexmonitor")
+        .join("checkpoints.json")
 }
 
-fn now_ms() -> i64 {
-    SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap_or_else(|_| Duration::from_secs(0))
-        .as_millis() as i64
+fn read_checkpoints(path: &Path) -> Result<CheckpointStore, String> {
+    storage::read_json_or_default(path)
 }
 
-fn cutoff_ms(now: i64) -> i64 {
-    now.saturating_sub(24 * 60 * 60 * 1000)
+fn write_checkpoints(path: &Path, store: &CheckpointStore) -> Result<(), String> {
+    storage::write_json_atomic(path, store)
 }
 
-fn prune_points(points: &mut Vec<UsagePoint>, cutoff: i64) {
-    points.retain(|point| point.timestamp_ms >= cutoff);
+fn is_monitor_path(path: &Path) -> bool {
+    path.starts_with(".codexmonitor")
 }
 
-fn sum_points(points: &[UsagePoint]) -> i64 {
-    points.iter().map(|point| point.tokens).sum()
+fn snapshot_worktree(repo: &Repository) -> Result<git2::Oid, git2::Error> {
+    let mut index = repo.index()?;
+    let mut skip_monitor = |path: &Path, _: &[u8]| -> i32 {
+        if is_monitor_path(path) {
+            1
+        } else {
+            0
+        }
+    };
+    index.add_all(["*"], git2::IndexAddOption::DEFAULT, Some(&mut skip_monitor))?;
+    index.update_all(["*"], Some(&mut skip_monitor))?;
+    index.write_tree()
 }
 
-fn empty_usage_snapshot() -> UsageSnapshot {
-    UsageSnapshot {
-        total_tokens_24h: None,
-        updated_at_ms: None,
-        source: UsageSource::None,
-        rate_limits: None,
+fn create_checkpoint(workspace_path: &str, thread_id: &str) -> Result<Checkpoint, String> {
+    let repo = open_repository(workspace_path).map_err(|e| e.to_string())?;
+    let tree_id = snapshot_worktree(&repo).map_err(|e| e.to_string())?;
+    let tree = repo.find_tree(tree_id).map_err(|e| e.to_string())?;
+    let head = repo.head().ok().and_then(|head| head.peel_to_commit().ok());
+    let signature = repo
+        .signature()
+        .or_else(|_| git2::Signature::now("CodexMonitor", "codexmonitor@localhost"))
+        .map_err(|e| e.to_string())?;
+    let id = Uuid::new_v4().to_string();
+    let message = format!("checkpoint before turn in thread {thread_id}");
+    let parents: Vec<&git2::Commit> = head.iter().collect();
+    let commit = repo
+        .commit(None, &signature, &signature, &message, &tree, &parents)
+        .map_err(|e| e.to_string())?;
+    repo.reference(&format!("{CHECKPOINT_REF_PREFIX}{id}"), commit, true, &message)
+        .map_err(|e| e.to_string())?;
+
+    let checkpoint = Checkpoint {
+        id,
+        thread_id: thread_id.to_string(),
+        commit: commit.to_string(),
+        head: head.map(|commit| commit.id().to_string()),
+        created_at_ms: now_ms(),
+    };
+    let path = workspace_checkpoints_path(workspace_path);
+    let mut store = read_checkpoints(&path).unwrap_or_default();
+    store.checkpoints.push(checkpoint.clone());
+    if store.checkpoints.len() > CHECKPOINT_MAX_PER_WORKSPACE {
+        let excess = store.checkpoints.len() - CHECKPOINT_MAX_PER_WORKSPACE;
+        for pruned in store.checkpoints.drain(..excess) {
+            if let Ok(mut reference) =
+                repo.find_reference(&format!("{CHECKPOINT_REF_PREFIX}{}", pruned.id))
+            {
+                let _ = reference.delete();
+            }
+        }
     }
+    write_checkpoints(&path, &store)?;
+    Ok(checkpoint)
 }
 
-fn parse_rfc3339_ms(value: &str) -> Option<i64> {
-    DateTime::parse_from_rfc3339(value)
-        .ok()
-        .map(|dt| dt.timestamp_millis())
+fn restore_worktree_checkpoint(
+    workspace_path: &str,
+    checkpoint: &Checkpoint,
+) -> Result<(), String> {
+    let repo = open_repository(workspace_path).map_err(|e| e.to_string())?;
+    let commit_id = git2::Oid::from_str(&checkpoint.commit).map_err(|e| e.to_string())?;
+    let tree = repo
+        .find_commit(commit_id)
+        .and_then(|commit| commit.tree())
+        .map_err(|e| e.to_string())?;
+
+    // Only the worktree is restored: refs and the index stay as they are, so
+    // commits made after the checkpoint remain reachable.
+    let mut options = DiffOptions::new();
+    options.include_untracked(true).recurse_untracked_dirs(true);
+    let diff = repo
+        .diff_tree_to_workdir(Some(&tree), Some(&mut options))
+        .map_err(|e| e.to_string())?;
+    let paths: Vec<PathBuf> = diff
+        .deltas()
+        .filter(|delta| {
+            delta.old_file().mode() != git2::FileMode::Commit
+                && delta.new_file().mode() != git2::FileMode::Commit
+        })
+        .filter_map(|delta| {
+            delta
+                .old_file()
+                .path()
+                .or_else(|| delta.new_file().path())
+                .map(Path::to_path_buf)
+        })
+        .filter(|path| !is_monitor_path(path))
+        .collect();
+    if paths.is_empty() {
+        return Ok(());
+    }
+    let mut checkout = git2::build::CheckoutBuilder::new();
+    checkout
+        .force()
+        .remove_untracked(true)
+        .update_index(false)
+        .disable_pathspec_match(true);
+    for path in &paths {
+        checkout.path(path);
+    }
+    repo.checkout_tree(tree.as_object(), Some(&mut checkout))
+        .map_err(|e| e.to_string())
 }
 
-fn system_time_ms(value: SystemTime) -> Option<i64> {
-    value
-        .duration_since(UNIX_EPOCH)
-        .ok()
-        .map(|duration| duration.as_millis() as i64)
+fn thread_created_at(thread: &Value) -> i64 {
+    thread
+        .get("createdAt")
+        .or_else(|| thread.get("created_at"))
+        .and_then(|value| value.as_i64())
+        .unwrap_or(0)
 }
 
-fn resolve_codex_home() -> Option<PathBuf> {
-    if let Ok(value) = env::var("CODEX_HOME") {
-        let trimmed = value.trim();
-        if !trimmed.is_empty() {
-            return Some(PathBuf::from(trimmed));
+async fn fetch_workspace_threads(session: &WorkspaceSession) -> Result<Vec<Value>, AppError> {
+    let mut threads: Vec<Value> = Vec::new();
+    let mut cursor: Option<String> = None;
+    for _ in 0..THREAD_LIST_MAX_PAGES {
+        let response = session
+            .send_request(
+                "thread/list",
+                json!({ "cursor": cursor, "limit": THREAD_LIST_PAGE_SIZE }),
+            )
+            .await?;
+        if let Some(error) = AppError::from_rpc_response(&response) {
+            return Err(error);
         }
-    }
-    if let Ok(value) = env::var("HOME") {
-        let trimmed = value.trim();
-        if !trimmed.is_empty() {
-            return Some(PathBuf::from(trimmed).join(".codex"));
+        let result = response.get("result").unwrap_or(&response);
+        if let Some(data) = result.get("data").and_then(|data| data.as_array()) {
+            threads.extend(data.iter().cloned());
+        }
+        cursor = result
+            .get("nextCursor")
+            .or_else(|| result.get("next_cursor"))
+            .and_then(|value| value.as_str())
+            .map(|value| value.to_string());
+        if cursor.is_none() {
+            break;
         }
     }
-    if let Ok(value) = env::var("USERPROFILE") {
-        let trimmed = value.trim();
-        if !trimmed.is_empty() {
-            return Some(PathBuf::from(trimmed).join(".codex"));
+
+    let store = read_workspace_sessions(&workspace_sessions_path(&session.entry.path))
+        .unwrap_or_default();
+    let agent_cwd = container::agent_cwd(&session.entry);
+    let mut seen = std::collections::HashSet::new();
+    let mut matching: Vec<Value> = threads
+        .into_iter()
+        .filter(|thread| {
+            thread.get("cwd").and_then(|cwd| cwd.as_str()) == Some(agent_cwd.as_str())
+        })
+        .filter(|thread| {
+            thread
+                .get("id")
+                .and_then(|id| id.as_str())
+                .map(|id| seen.insert(id.to_string()))
+                .unwrap_or(false)
+        })
+        .collect();
+    matching.sort_by_key(|thread| std::cmp::Reverse(thread_created_at(thread)));
+    for thread in matching.iter_mut() {
+        let metadata = thread
+            .get("id")
+            .and_then(|id| id.as_str())
+            .and_then(|id| store.sessions.get(id))
+            .map(|metadata| json!(metadata));
+        if let Some(object) = thread.as_object_mut() {
+            object.insert("metadata".to_string(), metadata.unwrap_or(Value::Null));
         }
     }
-    None
+    Ok(matching)
 }
 
-fn parse_token_count_from_rollout(value: &Value) -> Option<i64> {
-    let item_type = value.get("type")?.as_str()?;
-    if item_type != "event_msg" {
-        return None;
+fn delta_status_label(status: git2::Delta) -> &'static str {
+    match status {
+        git2::Delta::Added | git2::Delta::Untracked => "A",
+        git2::Delta::Deleted => "D",
+        git2::Delta::Renamed => "R",
+        git2::Delta::Copied => "C",
+        git2::Delta::Typechange => "T",
+        _ => "M",
     }
-    let payload = value.get("payload")?;
-    let payload_type = payload.get("type")?.as_str()?;
-    if payload_type != "token_count" {
-        return None;
+}
+
+fn tokenize_words(line: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    let mut start = 0;
+    let mut previous: Option<u8> = None;
+    for (index, ch) in line.char_indices() {
+        let class = if ch.is_alphanumeric() || ch == '_' {
+            0
+        } else if ch.is_whitespace() {
+            1
+        } else {
+            2
+        };
+        let boundary = match previous {
+            Some(prev) => prev != class || class == 2,
+            None => false,
+        };
+        if boundary {
+            tokens.push(&line[start..index]);
+            start = index;
+        }
+        previous = Some(class);
     }
-    let info = payload.get("info")?;
-    let last_usage = info
-        .get("last_token_usage")
-        .or_else(|| info.get("lastTokenUsage"))?;
-    let total_tokens = last_usage
-        .get("total_tokens")
-        .or_else(|| last_usage.get("totalTokens"))?
-        .as_i64()?;
-    if total_tokens > 0 {
-        Some(total_tokens)
-    } else {
-        None
+    if start < line.len() {
+        tokens.push(&line[start..]);
     }
+    tokens
 }
 
-fn parse_used_percent(value: &Value) -> Option<i64> {
-    if let Some(int) = value.as_i64() {
-        return Some(int);
+fn push_segment(segments: &mut Vec<DiffSegment>, text: &str, changed: bool) {
+    if let Some(last) = segments.last_mut() {
+        if last.changed == changed {
+            last.text.push_str(text);
+            return;
+        }
     }
-    value.as_f64().map(|float| float.round() as i64)
+    segments.push(DiffSegment {
+        text: text.to_string(),
+        changed,
+    });
 }
 
-fn parse_rate_limit_window(value: &Value) -> Option<RateLimitWindow> {
-    let used_percent = value
-        .get("usedPercent")
-        .or_else(|| value.get("used_percent"))
-        .and_then(parse_used_percent)?;
-    let window_duration_mins = value
-        .get("windowDurationMins")
-        .or_else(|| value.get("window_duration_mins"))
-        .and_then(|value| value.as_i64());
-    let resets_at = value
-        .get("resetsAt")
-        .or_else(|| value.get("resets_at"))
-        .and_then(|value| value.as_i64());
-    Some(RateLimitWindow {
-        used_percent,
-        window_duration_mins,
-        resets_at,
-    })
+fn word_diff(old: &str, new: &str) -> (Vec<DiffSegment>, Vec<DiffSegment>) {
+    let old_tokens = tokenize_words(old);
+    let new_tokens = tokenize_words(new);
+    let rows = old_tokens.len();
+    let cols = new_tokens.len();
+    if rows * cols > WORD_DIFF_MAX_CELLS {
+        return (
+            vec![DiffSegment { text: old.to_string(), changed: true }],
+            vec![DiffSegment { text: new.to_string(), changed: true }],
+        );
+    }
+    let mut lcs = vec![0u32; (rows + 1) * (cols + 1)];
+    for i in (0..rows).rev() {
+        for j in (0..cols).rev() {
+            lcs[i * (cols + 1) + j] = if old_tokens[i] == new_tokens[j] {
+                lcs[(i + 1) * (cols + 1) + j + 1] + 1
+            } else {
+                lcs[(i + 1) * (cols + 1) + j].max(lcs[i * (cols + 1) + j + 1])
+            };
+        }
+    }
+    let mut old_segments = Vec::new();
+    let mut new_segments = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < rows || j < cols {
+        if i < rows && j < cols && old_tokens[i] == new_tokens[j] {
+            push_segment(&mut old_segments, old_tokens[i], false);
+            push_segment(&mut new_segments, new_tokens[j], false);
+            i += 1;
+            j += 1;
+        } else if j < cols
+            && (i == rows || lcs[i * (cols + 1) + j + 1] >= lcs[(i + 1) * (cols + 1) + j])
+        {
+            push_segment(&mut new_segments, new_tokens[j], true);
+            j += 1;
+        } else {
+            push_segment(&mut old_segments, old_tokens[i], true);
+            i += 1;
+        }
+    }
+    (old_segments, new_segments)
 }
 
-fn parse_rate_limits_from_container(container: &Value) -> Option<RateLimitSnapshot> {
-    let rate_limits = container
-        .get("rateLimits")
-        .or_else(|| container.get("rate_limits"))?;
-    let primary = rate_limits
-        .get("primary")
-        .and_then(|value| parse_rate_limit_window(value));
-    let secondary = rate_limits
-        .get("secondary")
-        .and_then(|value| parse_rate_limit_window(value));
-    Some(RateLimitSnapshot { primary, secondary })
+fn annotate_word_diffs(lines: &mut [DiffLine]) {
+    let mut index = 0;
+    while index < lines.len() {
+        if lines[index].kind != "delete" {
+            index += 1;
+            continue;
+        }
+        let delete_start = index;
+        while index < lines.len() && lines[index].kind == "delete" {
+            index += 1;
+        }
+        let add_start = index;
+        while index < lines.len() && lines[index].kind == "add" {
+            index += 1;
+        }
+        let pairs = (add_start - delete_start).min(index - add_start);
+        for offset in 0..pairs {
+            let (old_segments, new_segments) = word_diff(
+                &lines[delete_start + offset].content,
+                &lines[add_start + offset].content,
+            );
+            lines[delete_start + offset].segments = Some(old_segments);
+            lines[add_start + offset].segments = Some(new_segments);
+        }
+    }
 }
 
-fn scan_session_tokens_24h(codex_home: &Path, cutoff: i64) -> Result<Option<i64>, String> {
-    let sessions_dir = codex_home.join("sessions");
-    if !sessions_dir.exists() {
-        return Ok(None);
+fn structured_patch(
+    patch: &git2::Patch,
+) -> Result<(Vec<DiffHunk>, usize, usize), git2::Error> {
+    let mut hunks = Vec::new();
+    let mut additions = 0;
+    let mut deletions = 0;
+    for hunk_index in 0..patch.num_hunks() {
+        let (hunk, line_count) = patch.hunk(hunk_index)?;
+        let mut lines = Vec::with_capacity(line_count);
+        for line_index in 0..line_count {
+            let line = patch.line_in_hunk(hunk_index, line_index)?;
+            let kind = match line.origin() {
+                '+' => "add",
+                '-' => "delete",
+                ' ' => "context",
+                _ => continue,
+            };
+            match kind {
+                "add" => additions += 1,
+                "delete" => deletions += 1,
+                _ => {}
+            }
+            let content = String::from_utf8_lossy(line.content())
+                .trim_end_matches(['\n', '\r'])
+                .to_string();
+            lines.push(DiffLine {
+                kind: kind.to_string(),
+                old_line: line.old_lineno(),
+                new_line: line.new_lineno(),
+                content,
+                segments: None,
+            });
+        }
+        annotate_word_diffs(&mut lines);
+        hunks.push(DiffHunk {
+            header: String::from_utf8_lossy(hunk.header()).trim_end().to_string(),
+            old_start: hunk.old_start(),
+            old_lines: hunk.old_lines(),
+            new_start: hunk.new_start(),
+            new_lines: hunk.new_lines(),
+            lines,
+        });
     }
+    Ok((hunks, additions, deletions))
+}
 
-    let mut total_tokens: i64 = 0;
-    let walker = WalkBuilder::new(&sessions_dir)
-        .follow_links(false)
-        .max_depth(Some(6))
-        .build();
+fn prompts_dir() -> Option<PathBuf> {
+    if let Ok(value) = env::var("HOME") {
+        if !value.trim().is_empty() {
+            return Some(PathBuf::from(value).join(".codex").join("prompts"));
+        }
+    }
+    if let Ok(value) = env::var("USERPROFILE") {
+        if !value.trim().is_empty() {
+            return Some(PathBuf::from(value).join(".codex").join("prompts"));
+        }
+    }
+    None
+}
 
-    for entry in walker {
-        let entry = entry.map_err(|e| e.to_string())?;
-        let path = entry.path();
-        if !entry
-            .file_type()
-            .map(|file_type| file_type.is_file())
-            .unwrap_or(false)
-        {
+fn parse_prompt_file(contents: &str) -> (PromptFrontMatter, String) {
+    let mut front_matter = PromptFrontMatter::default();
+    let mut lines = contents.lines();
+    let first_line = match lines.next() {
+        Some(line) => line.trim_end_matches('\r'),
+        None => return (front_matter, String::new()),
+    };
+    if first_line != "---" {
+        return (front_matter, contents.to_string());
+    }
+
+    let mut front_lines: Vec<String> = Vec::new();
+    let mut body_start: Option<usize> = None;
+    let mut offset = 0usize;
+    for (index, line) in contents.split_inclusive('\n').enumerate() {
+        let trimmed = line.trim_end_matches(&['\r', '\n'][..]);
+        if index == 0 {
+            offset += line.len();
             continue;
         }
-        if path.extension().and_then(|ext| ext.to_str()) != Some("jsonl") {
-            continue;
+        if trimmed == "---" {
+            body_start = Some(offset + line.len());
+            break;
         }
-        if let Ok(metadata) = entry.metadata() {
-            if let Some(modified_ms) = metadata.modified().ok().and_then(system_time_ms) {
-                if modified_ms < cutoff {
-                    continue;
+        front_lines.push(trimmed.to_string());
+        offset += line.len();
+    }
+
+    if let Some(start) = body_start {
+        for line in front_lines {
+            if let Some((key, value)) = line.split_once(':') {
+                let key = key.trim();
+                let value = parse_prompt_value(value);
+                match key {
+                    "description" => front_matter.description = value,
+                    "argument-hint" | "argument_hint" => {
+                        front_matter.argument_hint = value
+                    }
+                    _ => {}
                 }
             }
         }
+        let body = contents.get(start..).unwrap_or_default().to_string();
+        return (front_matter, body);
+    }
 
-        let file = fs::File::open(path).map_err(|e| e.to_string())?;
-        let reader = StdBufReader::new(file);
-        for line in reader.lines() {
-            let line = line.map_err(|e| e.to_string())?;
-            let trimmed = line.trim();
-            if trimmed.is_empty() {
-                continue;
-            }
-            let value: Value = match serde_json::from_str(trimmed) {
-                Ok(value) => value,
-                Err(_) => continue,
-            };
-            let timestamp = value
-                .get("timestamp")
-                .and_then(|ts| ts.as_str())
-                .and_then(parse_rfc3339_ms);
-            if let Some(timestamp_ms) = timestamp {
-                if timestamp_ms < cutoff {
-                    continue;
-                }
-            } else {
-                continue;
-            }
+    (front_matter, contents.to_string())
+}
+
+fn render_prompt_body(body: &str, args: &HashMap<String, String>) -> String {
+    let mut positional: Vec<(usize, &String)> = args
+        .iter()
+        .filter_map(|(key, value)| key.parse::<usize>().ok().map(|index| (index, value)))
+        .collect();
+    positional.sort_by_key(|(index, _)| *index);
+    let all_arguments = args.get("ARGUMENTS").cloned().unwrap_or_else(|| {
+        positional
+            .iter()
+            .map(|(_, value)| value.as_str())
+            .collect::<Vec<_>>()
+            .join(" ")
+    });
 
-            if let Some(tokens) = parse_token_count_from_rollout(&value) {
-                total_tokens += tokens;
+    let mut output = String::with_capacity(body.len());
+    let mut rest = body;
+    while let Some(index) = rest.find(['$', '{']) {
+        output.push_str(&rest[..index]);
+        rest = &rest[index..];
+        if let Some(after) = rest.strip_prefix("$$") {
+            output.push('$');
+            rest = after;
+        } else if let Some(after) = rest.strip_prefix("$ARGUMENTS") {
+            output.push_str(&all_arguments);
+            rest = after;
+        } else if rest.starts_with('$')
+            && rest[1..].starts_with(|c: char| c.is_ascii_digit() && c != '0')
+        {
+            let digit = &rest[1..2];
+            output.push_str(args.get(digit).map(|value| value.as_str()).unwrap_or(""));
+            rest = &rest[2..];
+        } else if let Some(after) = rest.strip_prefix("{{") {
+            match after.find("}}") {
+                Some(end) => {
+                    let key = after[..end].trim();
+                    match args.get(key) {
+                        Some(value) => output.push_str(value),
+                        None => output.push_str(&rest[..end + 4]),
+                    }
+                    rest = &after[end + 2..];
+                }
+                None => {
+                    output.push_str(rest);
+                    rest = "";
+                }
             }
+        } else {
+            output.push_str(&rest[..1]);
+            rest = &rest[1..];
         }
     }
+    output.push_str(rest);
+    output
+}
 
-    Ok(Some(total_tokens))
+fn workspace_git_context(path: &str) -> Option<(String, Vec<String>)> {
+    let repo = open_repository(path).ok()?;
+    let branch_name = repo
+        .head()
+        .ok()
+        .and_then(|head| head.shorthand().map(|s| s.to_string()))
+        .unwrap_or_else(|| "unknown".to_string());
+    let mut status_options = StatusOptions::new();
+    status_options
+        .include_untracked(true)
+        .recurse_untracked_dirs(true)
+        .include_ignored(false);
+    let statuses = repo.statuses(Some(&mut status_options)).ok()?;
+    let files = statuses
+        .iter()
+        .filter_map(|entry| entry.path().map(normalize_git_path))
+        .collect();
+    Some((branch_name, files))
 }
 
-fn extract_app_server_token_delta(message: &Value) -> Option<i64> {
-    let params = message.get("params")?;
-    let token_usage = params.get("tokenUsage").or_else(|| params.get("token_usage"))?;
-    let last_usage = token_usage.get("last").or_else(|| token_usage.get("last_usage"))?;
-    let total_tokens = last_usage
-        .get("totalTokens")
-        .or_else(|| last_usage.get("total_tokens"))?
-        .as_i64()?;
-    if total_tokens > 0 {
-        Some(total_tokens)
-    } else {
-        None
+fn parse_prompt_value(raw: &str) -> Option<String> {
+    let trimmed = raw.trim();
+    if trimmed.is_empty() {
+        return None;
     }
+    let unquoted = if (trimmed.starts_with('"') && trimmed.ends_with('"'))
+        || (trimmed.starts_with('\'') && trimmed.ends_with('\''))
+    {
+        trimmed[1..trimmed.len() - 1].to_string()
+    } else {
+        trimmed.to_string()
+    };
+    Some(unquoted)
 }
 
-async fn emit_usage_snapshot(app: &AppHandle, snapshot: UsageSnapshot) {
-    let _ = app.emit("usage-updated", snapshot);
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct WorkspaceEntry {
+    id: String,
+    name: String,
+    path: String,
+    codex_bin: Option<String>,
+    #[serde(default)]
+    codex_home: Option<String>,
+    #[serde(default)]
+    codex_version: Option<String>,
+    #[serde(default)]
+    template_id: Option<String>,
+    #[serde(default)]
+    defaults: templates::WorkspaceDefaults,
+    #[serde(default)]
+    container: Option<container::ContainerConfig>,
+    #[serde(default)]
+    pre_connect_commands: Vec<tasks::PreConnectCommand>,
+    #[serde(default)]
+    observer_mode: bool,
+    #[serde(default)]
+    speak_completions: bool,
+    #[serde(default)]
+    auto_connect: bool,
 }
 
-async fn record_app_server_usage(app: &AppHandle, tokens: i64) -> Result<UsageSnapshot, String> {
-    let state = app.state::<AppState>();
-    let now = now_ms();
-    let cutoff = cutoff_ms(now);
-
-    let mut store = state.usage_store.lock().await;
-    store.app_server_points.push(UsagePoint {
-        timestamp_ms: now,
-        tokens,
-    });
-    prune_points(&mut store.app_server_points, cutoff);
-    let total = sum_points(&store.app_server_points);
-    let rate_limits = store.last_rate_limits.clone();
-
-    let snapshot = UsageSnapshot {
-        total_tokens_24h: Some(total),
-        updated_at_ms: Some(now),
-        source: UsageSource::AppServer,
-        rate_limits,
-    };
-    store.last_snapshot = Some(snapshot.clone());
-    write_usage_store(&state.usage_path, &store)?;
-    drop(store);
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct CodexAccount {
+    name: String,
+    codex_home: String,
+}
 
-    emit_usage_snapshot(app, snapshot.clone()).await;
-    Ok(snapshot)
+fn default_session_store_version() -> u32 {
+    1
 }
 
-async fn record_rate_limits(
-    app: &AppHandle,
-    rate_limits: RateLimitSnapshot,
-) -> Result<UsageSnapshot, String> {
-    let state = app.state::<AppState>();
-    let now = now_ms();
-    let cutoff = cutoff_ms(now);
-    let mut store = state.usage_store.lock().await;
-    prune_points(&mut store.app_server_points, cutoff);
-    store.last_rate_limits = Some(rate_limits.clone());
-    let total_tokens_24h = if !store.app_server_points.is_empty() {
-        Some(sum_points(&store.app_server_points))
-    } else {
-        store.last_snapshot.as_ref().and_then(|snapshot| snapshot.total_tokens_24h)
-    };
-    let snapshot = UsageSnapshot {
-        total_tokens_24h,
-        updated_at_ms: Some(now),
-        source: UsageSource::AppServer,
-        rate_limits: Some(rate_limits),
-    };
-    store.last_snapshot = Some(snapshot.clone());
-    write_usage_store(&state.usage_path, &store)?;
-    drop(store);
-    emit_usage_snapshot(app, snapshot.clone()).await;
-    Ok(snapshot)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "lowercase")]
+enum SessionNameSource {
+    Default,
+    Custom,
 }
 
-async fn fetch_rate_limits_via_app_server(
-    codex_bin: String,
-    settings: AppSettings,
-) -> Result<Option<RateLimitSnapshot>, String> {
-    let mut command = Command::new(codex_bin);
-    if settings.bypass_approvals_and_sandbox {
-        command.arg("--dangerously-bypass-approvals-and-sandbox");
-    }
-    if settings.enable_web_search_request {
-        command.arg("--enable").arg("web_search_request");
+impl Default for SessionNameSource {
+    fn default() -> Self {
+        SessionNameSource::Default
     }
-    command.arg("app-server");
-    command.stdin(std::process::Stdio::piped());
-    command.stdout(std::process::Stdio::piped());
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+struct SessionMetadata {
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    archived: bool,
+    #[serde(default)]
+    name_source: SessionNameSource,
+    #[serde(default)]
+    last_state: Option<thread_state::ThreadActivity>,
+    #[serde(default)]
+    last_state_at_ms: Option<i64>,
+    #[serde(default)]
+    auto_titled: bool,
+    #[serde(default)]
+    access_mode: Option<AccessMode>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct WorkspaceSessionStore {
+    #[serde(default = "default_session_store_version")]
+    version: u32,
+    #[serde(default)]
+    sessions: HashMap<String, SessionMetadata>,
+}
+
+impl Default for WorkspaceSessionStore {
+    fn default() -> Self {
+        Self {
+            version: default_session_store_version(),
+            sessions: HashMap::new(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "kebab-case")]
+enum ThemePreference {
+    System,
+    Light,
+    Dark,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+enum AccessMode {
+    ReadOnly,
+    Current,
+    FullAccess,
+}
+
+fn default_sidebar_width() -> i64 {
+    280
+}
+
+fn default_glass_blur_light() -> f64 {
+    32.0
+}
+
+fn default_glass_blur_dark() -> f64 {
+    32.0
+}
+
+fn default_glass_opacity_light() -> f64 {
+    1.0
+}
+
+fn default_glass_opacity_dark() -> f64 {
+    1.0
+}
+
+fn default_usage_polling_enabled() -> bool {
+    true
+}
+
+fn default_usage_polling_interval_minutes() -> i64 {
+    5
+}
+
+fn default_rate_limit_alerts_enabled() -> bool {
+    true
+}
+
+fn default_rate_limit_warning_percent() -> i64 {
+    80
+}
+
+fn default_turn_checkpoints_enabled() -> bool {
+    false
+}
+
+fn default_log_level() -> String {
+    logging::DEFAULT_LOG_LEVEL.to_string()
+}
+
+fn default_attachment_max_dimension() -> u32 {
+    2048
+}
+
+fn default_diff_max_bytes() -> usize {
+    256 * 1024
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct AppSettings {
+    theme_preference: ThemePreference,
+    access_mode: AccessMode,
+    bypass_approvals_and_sandbox: bool,
+    enable_web_search_request: bool,
+    #[serde(default)]
+    confirm_before_quit: bool,
+    #[serde(default)]
+    quit_behavior: tray::QuitBehavior,
+    #[serde(default, skip_serializing)]
+    enable_completion_notifications: Option<bool>,
+    #[serde(default = "default_usage_polling_enabled")]
+    usage_polling_enabled: bool,
+    #[serde(default = "default_usage_polling_interval_minutes")]
+    usage_polling_interval_minutes: i64,
+    #[serde(default = "default_rate_limit_alerts_enabled")]
+    rate_limit_alerts_enabled: bool,
+    #[serde(default = "default_rate_limit_warning_percent")]
+    rate_limit_warning_percent: i64,
+    #[serde(default, skip_serializing)]
+    rate_limit_notifications: Option<bool>,
+    #[serde(default)]
+    notifications: notifications::NotificationConfig,
+    #[serde(default)]
+    approval_auto_defer: deferrals::AutoDeferConfig,
+    #[serde(default)]
+    context_compaction: compaction::CompactionConfig,
+    #[serde(default)]
+    thread_titles: titles::ThreadTitleConfig,
+    #[serde(default)]
+    secret_scanning: secrets::SecretScanConfig,
+    #[serde(default = "default_diff_max_bytes")]
+    diff_max_bytes: usize,
+    #[serde(default = "default_turn_checkpoints_enabled")]
+    turn_checkpoints_enabled: bool,
+    #[serde(default = "batching::default_event_batch_window_ms")]
+    event_batch_window_ms: u64,
+    #[serde(default)]
+    git_hosting_token: Option<String>,
+    #[serde(default)]
+    hooks: Vec<hooks::HookConfig>,
+    #[serde(default = "default_log_level")]
+    log_level: String,
+    #[serde(default = "default_sidebar_width")]
+    sidebar_width: i64,
+    #[serde(default = "default_glass_blur_light")]
+    glass_blur_light: f64,
+    #[serde(default = "default_glass_blur_dark")]
+    glass_blur_dark: f64,
+    #[serde(default = "default_glass_opacity_light")]
+    glass_opacity_light: f64,
+    #[serde(default = "default_glass_opacity_dark")]
+    glass_opacity_dark: f64,
+    #[serde(default)]
+    codex_bin_path: Option<String>,
+    #[serde(default)]
+    node_bin_path: Option<String>,
+    #[serde(default)]
+    login_shell_env_enabled: bool,
+    #[serde(default)]
+    workspace_sidebar_expanded: HashMap<String, bool>,
+    #[serde(default)]
+    keymap: BTreeMap<String, String>,
+    #[serde(default)]
+    locale: Option<String>,
+    #[serde(default)]
+    update_channel: updater::UpdateChannel,
+    #[serde(default)]
+    crash_reporting_enabled: bool,
+    #[serde(default)]
+    local_analytics_enabled: bool,
+    #[serde(default)]
+    approval_command_allowlist: Vec<String>,
+    #[serde(default = "default_attachment_max_dimension")]
+    attachment_max_dimension: u32,
+    #[serde(default)]
+    transcription_command: Option<String>,
+    #[serde(default)]
+    http_api: http_api::HttpApiConfig,
+}
+
+impl Default for AppSettings {
+    fn default() -> Self {
+        Self {
+            theme_preference: ThemePreference::System,
+            access_mode: AccessMode::Current,
+            bypass_approvals_and_sandbox: false,
+            enable_web_search_request: false,
+            confirm_before_quit: false,
+            quit_behavior: tray::QuitBehavior::default(),
+            enable_completion_notifications: None,
+            usage_polling_enabled: default_usage_polling_enabled(),
+            usage_polling_interval_minutes: default_usage_polling_interval_minutes(),
+            rate_limit_alerts_enabled: default_rate_limit_alerts_enabled(),
+            rate_limit_warning_percent: default_rate_limit_warning_percent(),
+            rate_limit_notifications: None,
+            notifications: notifications::NotificationConfig::default(),
+            approval_auto_defer: deferrals::AutoDeferConfig::default(),
+            context_compaction: compaction::CompactionConfig::default(),
+            thread_titles: titles::ThreadTitleConfig::default(),
+            secret_scanning: secrets::SecretScanConfig::default(),
+            diff_max_bytes: default_diff_max_bytes(),
+            turn_checkpoints_enabled: default_turn_checkpoints_enabled(),
+            event_batch_window_ms: batching::default_event_batch_window_ms(),
+            git_hosting_token: None,
+            hooks: Vec::new(),
+            log_level: default_log_level(),
+            sidebar_width: default_sidebar_width(),
+            glass_blur_light: default_glass_blur_light(),
+            glass_blur_dark: default_glass_blur_dark(),
+            glass_opacity_light: default_glass_opacity_light(),
+            glass_opacity_dark: default_glass_opacity_dark(),
+            codex_bin_path: None,
+            node_bin_path: None,
+            login_shell_env_enabled: false,
+            workspace_sidebar_expanded: HashMap::new(),
+            keymap: BTreeMap::new(),
+            locale: None,
+            update_channel: updater::UpdateChannel::default(),
+            crash_reporting_enabled: false,
+            local_analytics_enabled: false,
+            approval_command_allowlist: Vec::new(),
+            attachment_max_dimension: default_attachment_max_dimension(),
+            transcription_command: None,
+            http_api: http_api::HttpApiConfig::default(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "kebab-case")]
+enum UsageSource {
+    None,
+    AppServer,
+    Sessions,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct UsagePoint {
+    timestamp_ms: i64,
+    tokens: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct UsageSnapshot {
+    total_tokens_24h: Option<i64>,
+    updated_at_ms: Option<i64>,
+    source: UsageSource,
+    rate_limits: Option<RateLimitSnapshot>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+struct SessionFileCache {
+    #[serde(default)]
+    inode: u64,
+    #[serde(default)]
+    modified_ms: i64,
+    #[serde(default)]
+    offset: u64,
+    #[serde(default)]
+    points: Vec<UsagePoint>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct UsageStore {
+    #[serde(default)]
+    app_server_points: Vec<UsagePoint>,
+    #[serde(default)]
+    last_snapshot: Option<UsageSnapshot>,
+    #[serde(default)]
+    last_rate_limits: Option<RateLimitSnapshot>,
+    #[serde(default)]
+    session_files: HashMap<String, SessionFileCache>,
+    #[serde(default)]
+    rate_limit_history: Vec<rate_history::RateLimitSample>,
+}
+
+impl Default for UsageStore {
+    fn default() -> Self {
+        Self {
+            app_server_points: Vec::new(),
+            last_snapshot: None,
+            last_rate_limits: None,
+            session_files: HashMap::new(),
+            rate_limit_history: Vec::new(),
+        }
+    }
+}
+
+impl UsageStore {
+    fn set_rate_limits(&mut self, now: i64, rate_limits: RateLimitSnapshot) {
+        rate_history::push(&mut self.rate_limit_history, now, &rate_limits);
+        self.last_rate_limits = Some(rate_limits);
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct RateLimitWindow {
+    used_percent: i64,
+    window_duration_mins: Option<i64>,
+    resets_at: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct RateLimitSnapshot {
+    primary: Option<RateLimitWindow>,
+    secondary: Option<RateLimitWindow>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct WorkspaceInfo {
+    id: String,
+    name: String,
+    path: String,
+    connected: bool,
+    codex_bin: Option<String>,
+    codex_home: Option<String>,
+    #[serde(default)]
+    codex_version: Option<String>,
+    #[serde(default)]
+    codex_version_supported: Option<bool>,
+    #[serde(default)]
+    template_id: Option<String>,
+    #[serde(default)]
+    defaults: templates::WorkspaceDefaults,
+    #[serde(default)]
+    container: Option<container::ContainerConfig>,
+    #[serde(default)]
+    pre_connect_commands: Vec<tasks::PreConnectCommand>,
+    #[serde(default)]
+    observer_mode: bool,
+    #[serde(default)]
+    speak_completions: bool,
+    #[serde(default)]
+    auto_connect: bool,
+}
+
+#[derive(Serialize, Clone)]
+struct AppServerEvent {
+    workspace_id: String,
+    message: Value,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RequestPriority {
+    Interactive,
+    Bulk,
+}
+
+const SESSION_MAX_IN_FLIGHT: usize = 4;
+const BULK_REQUEST_METHODS: &[&str] = &[
+    "thread/list",
+    "model/list",
+    "skills/list",
+    "account/rateLimits/read",
+];
+
+fn request_priority(method: &str) -> RequestPriority {
+    if BULK_REQUEST_METHODS.contains(&method) {
+        RequestPriority::Bulk
+    } else {
+        RequestPriority::Interactive
+    }
+}
+
+#[derive(Default)]
+struct RequestLimiterState {
+    in_flight: usize,
+    interactive: VecDeque<oneshot::Sender<()>>,
+    bulk: VecDeque<oneshot::Sender<()>>,
+    total_requests: u64,
+    peak_queue_depth: usize,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct RequestQueueHealth {
+    in_flight: usize,
+    max_in_flight: usize,
+    queued_interactive: usize,
+    queued_bulk: usize,
+    peak_queue_depth: usize,
+    total_requests: u64,
+}
+
+struct RequestLimiter {
+    max_in_flight: usize,
+    state: std::sync::Mutex<RequestLimiterState>,
+}
+
+struct RequestPermit<'a> {
+    limiter: &'a RequestLimiter,
+}
+
+impl RequestLimiter {
+    fn new(max_in_flight: usize) -> Self {
+        Self {
+            max_in_flight: max_in_flight.max(1),
+            state: std::sync::Mutex::new(RequestLimiterState::default()),
+        }
+    }
+
+    async fn acquire(&self, priority: RequestPriority) -> RequestPermit<'_> {
+        let waiter = {
+            let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+            state.total_requests += 1;
+            if state.in_flight < self.max_in_flight {
+                state.in_flight += 1;
+                None
+            } else {
+                let (tx, rx) = oneshot::channel();
+                match priority {
+                    RequestPriority::Interactive => state.interactive.push_back(tx),
+                    RequestPriority::Bulk => state.bulk.push_back(tx),
+                }
+                let depth = state.interactive.len() + state.bulk.len();
+                state.peak_queue_depth = state.peak_queue_depth.max(depth);
+                Some(rx)
+            }
+        };
+        let mut queued = QueuedPermit {
+            limiter: self,
+            receiver: waiter,
+        };
+        if let Some(receiver) = queued.receiver.as_mut() {
+            let _ = receiver.await;
+        }
+        queued.receiver = None;
+        RequestPermit { limiter: self }
+    }
+
+    fn release(&self) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        loop {
+            let next = state
+                .interactive
+                .pop_front()
+                .or_else(|| state.bulk.pop_front());
+            match next {
+                Some(waiter) => {
+                    if waiter.send(()).is_ok() {
+                        return;
+                    }
+                }
+                None => {
+                    state.in_flight = state.in_flight.saturating_sub(1);
+                    return;
+                }
+            }
+        }
+    }
+
+    fn health(&self) -> RequestQueueHealth {
+        let state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        RequestQueueHealth {
+            in_flight: state.in_flight,
+            max_in_flight: self.max_in_flight,
+            queued_interactive: state.interactive.len(),
+            queued_bulk: state.bulk.len(),
+            peak_queue_depth: state.peak_queue_depth,
+            total_requests: state.total_requests,
+        }
+    }
+}
+
+struct QueuedPermit<'a> {
+    limiter: &'a RequestLimiter,
+    receiver: Option<oneshot::Receiver<()>>,
+}
+
+impl Drop for QueuedPermit<'_> {
+    fn drop(&mut self) {
+        if let Some(mut receiver) = self.receiver.take() {
+            receiver.close();
+            if receiver.try_recv().is_ok() {
+                self.limiter.release();
+            }
+        }
+    }
+}
+
+impl Drop for RequestPermit<'_> {
+    fn drop(&mut self) {
+        self.limiter.release();
+    }
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct WorkspaceThreads {
+    workspace_id: String,
+    threads: Vec<Value>,
+    error: Option<AppError>,
+    pending: bool,
+}
+
+const THREAD_LIST_CONCURRENCY: usize = 4;
+const THREAD_LIST_PAGE_SIZE: u32 = 50;
+const THREAD_LIST_MAX_PAGES: usize = 20;
+const THREAD_LIST_DEADLINE: Duration = Duration::from_secs(4);
+
+struct WorkspaceSession {
+    entry: WorkspaceEntry,
+    child: Mutex<Child>,
+    stdin: Mutex<ChildStdin>,
+    pending: Mutex<HashMap<u64, oneshot::Sender<Value>>>,
+    next_id: AtomicU64,
+    limiter: RequestLimiter,
+    codex_version: Option<String>,
+    capabilities: capabilities::SessionCapabilities,
+    observer_mode: AtomicBool,
+}
+
+impl WorkspaceSession {
+    async fn write_message(&self, value: Value) -> Result<(), AppError> {
+        let mut stdin = self.stdin.lock().await;
+        let mut line = serde_json::to_string(&value)?;
+        line.push('\n');
+        stdin.write_all(line.as_bytes()).await?;
+        Ok(())
+    }
+
+    async fn send_request(&self, method: &str, params: Value) -> Result<Value, AppError> {
+        let codex_version = self.codex_version.as_deref();
+        self.capabilities.check(method, codex_version)?;
+        let _permit = self.limiter.acquire(request_priority(method)).await;
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst);
+        let (tx, rx) = oneshot::channel();
+        self.pending.lock().await.insert(id, tx);
+        self.write_message(json!({ "id": id, "method": method, "params": params }))
+            .await?;
+        let response = rx.await.map_err(|_| AppError::NotConnected)?;
+        self.capabilities
+            .observe_response(method, &response, codex_version)?;
+        Ok(response)
+    }
+
+    async fn send_notification(
+        &self,
+        method: &str,
+        params: Option<Value>,
+    ) -> Result<(), AppError> {
+        let value = if let Some(params) = params {
+            json!({ "method": method, "params": params })
+        } else {
+            json!({ "method": method })
+        };
+        self.write_message(value).await
+    }
+
+    async fn send_response(&self, id: u64, result: Value) -> Result<(), AppError> {
+        self.write_message(json!({ "id": id, "result": result }))
+            .await
+    }
+}
+
+struct AppState {
+    workspaces: Mutex<HashMap<String, WorkspaceEntry>>,
+    sessions: Mutex<HashMap<String, Arc<WorkspaceSession>>>,
+    storage_path: PathBuf,
+    settings: Mutex<AppSettings>,
+    settings_path: PathBuf,
+    allow_quit: AtomicBool,
+    usage_store: Mutex<UsageStore>,
+    usage_path: PathBuf,
+    usage_poll_handle: Mutex<Option<JoinHandle<()>>>,
+    usage_probe_inflight: AtomicBool,
+    window_focused: AtomicBool,
+    accounts: Mutex<Vec<CodexAccount>>,
+    accounts_path: PathBuf,
+    rate_limit_alerts: Mutex<HashMap<String, Option<i64>>>,
+    delete_tokens: Mutex<HashMap<String, DeleteToken>>,
+    message_queue_lock: Mutex<()>,
+    drafts_lock: Mutex<()>,
+    outbox_lock: Mutex<()>,
+    review_findings_lock: Mutex<()>,
+    skill_settings_lock: Mutex<()>,
+    secret_scan_lock: Mutex<()>,
+    prompt_usage: Mutex<PromptUsageStore>,
+    prompt_usage_path: PathBuf,
+    file_indexes: Mutex<HashMap<String, FileIndex>>,
+    file_index_watchers: Mutex<HashMap<String, tauri::async_runtime::JoinHandle<()>>>,
+    terminals: terminal::TerminalRegistry,
+    tasks: tasks::TaskRegistry,
+    deep_links: deep_link::DeepLinkQueue,
+    hooks: hooks::HookLog,
+    shell_env: shell_env::ShellEnv,
+    thread_states: thread_state::ThreadStates,
+    turn_metrics: metrics::TurnMetricsTracker,
+    notifier: notifications::Notifier,
+    badge: badge::Badge,
+    dock: dock::Dock,
+    deferrals: deferrals::Deferrals,
+    workspace_templates: templates::WorkspaceTemplates,
+    context_usage: context::ContextTracker,
+    compactions: compaction::Compactions,
+    thread_titles: titles::ThreadTitles,
+    instruction_watchers: instructions::InstructionWatchers,
+    stats_jobs: stats::StatsJobs,
+    audit: audit::AuditLog,
+    path_guards: path_guard::PathGuards,
+    approval_contexts: approval_context::ApprovalContexts,
+    recorder: recording::Recorder,
+    speaker: speech::Speaker,
+    scheduler: scheduler::Scheduler,
+    task_queues: task_queue::TaskQueues,
+    broadcasts: broadcast::Broadcasts,
+    http_api: http_api::HttpApi,
+    window_state: window_state::WindowStateStore,
+    updater: updater::UpdaterState,
+    analytics: analytics::Analytics,
+    data_dir: PathBuf,
+    instance_lock: Option<storage::InstanceLock>,
+    storage_error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct StorageStatus {
+    data_dir: String,
+    read_only: bool,
+    error: Option<String>,
+}
+
+const DATA_DIR_ENV: &str = "CODEXOLA_DATA_DIR";
+
+fn expand_home(path: &str) -> PathBuf {
+    if let Some(rest) = path.strip_prefix("~/") {
+        if let Some(home) = env::var_os("HOME").or_else(|| env::var_os("USERPROFILE")) {
+            return PathBuf::from(home).join(rest);
+        }
+    }
+    PathBuf::from(path)
+}
+
+fn data_dir_override() -> Option<PathBuf> {
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--data-dir" {
+            return args.next().map(|value| expand_home(&value));
+        }
+        if let Some(value) = arg.strip_prefix("--data-dir=") {
+            return Some(expand_home(value));
+        }
+    }
+    env::var(DATA_DIR_ENV)
+        .ok()
+        .filter(|value| !value.trim().is_empty())
+        .map(|value| expand_home(value.trim()))
+}
+
+fn store_or_default<T: Default>(name: &str, result: Result<T, String>) -> T {
+    result.unwrap_or_else(|err| {
+        tracing::error!("failed to load {name} store: {err}");
+        T::default()
+    })
+}
+
+impl AppState {
+    fn load(app: &AppHandle) -> Self {
+        let app_data_dir = data_dir_override().unwrap_or_else(|| {
+            app.path()
+                .app_data_dir()
+                .unwrap_or_else(|_| std::env::current_dir().unwrap_or_else(|_| ".".into()))
+        });
+        let (instance_lock, storage_error) = match storage::acquire_instance_lock(&app_data_dir) {
+            Ok(lock) => (Some(lock), None),
+            Err(err) => (None, Some(err)),
+        };
+        storage::set_read_only(storage_error.is_some());
+        let storage_path = app_data_dir.join("workspaces.json");
+        let settings_path = app_data_dir.join("settings.json");
+        let usage_path = app_data_dir.join("usage.json");
+        let accounts_path = app_data_dir.join("accounts.json");
+        let prompt_usage_path = app_data_dir.join("prompt_usage.json");
+        let settings = read_settings(&settings_path);
+        let log_level = settings
+            .as_ref()
+            .map(|settings| settings.log_level.as_str())
+            .unwrap_or(logging::DEFAULT_LOG_LEVEL);
+        logging::init(&app_data_dir, log_level);
+        crash::install(&app_data_dir);
+        if let Some(err) = storage_error.as_ref() {
+            tracing::error!("{err}");
+        } else {
+            event_blobs::clear_stale(&app_data_dir);
+        }
+        let settings = store_or_default("settings", settings);
+        let workspaces = store_or_default("workspaces", read_workspaces(&storage_path));
+        let usage_store = store_or_default("usage", read_usage_store(&usage_path));
+        let accounts = store_or_default("accounts", read_accounts(&accounts_path));
+        let prompt_usage =
+            store_or_default("prompt usage", read_prompt_usage(&prompt_usage_path));
+        let analytics = analytics::Analytics::load(&app_data_dir, settings.local_analytics_enabled);
+        Self {
+            workspaces: Mutex::new(workspaces),
+            sessions: Mutex::new(HashMap::new()),
+            storage_path,
+            settings: Mutex::new(settings),
+            settings_path,
+            allow_quit: AtomicBool::new(false),
+            usage_store: Mutex::new(usage_store),
+            usage_path,
+            usage_poll_handle: Mutex::new(None),
+            usage_probe_inflight: AtomicBool::new(false),
+            window_focused: AtomicBool::new(true),
+            accounts: Mutex::new(accounts),
+            accounts_path,
+            rate_limit_alerts: Mutex::new(HashMap::new()),
+            delete_tokens: Mutex::new(HashMap::new()),
+            message_queue_lock: Mutex::new(()),
+            drafts_lock: Mutex::new(()),
+            outbox_lock: Mutex::new(()),
+            review_findings_lock: Mutex::new(()),
+            skill_settings_lock: Mutex::new(()),
+            secret_scan_lock: Mutex::new(()),
+            prompt_usage: Mutex::new(prompt_usage),
+            prompt_usage_path,
+            file_indexes: Mutex::new(HashMap::new()),
+            file_index_watchers: Mutex::new(HashMap::new()),
+            terminals: terminal::TerminalRegistry::default(),
+            tasks: tasks::TaskRegistry::default(),
+            deep_links: deep_link::DeepLinkQueue::default(),
+            hooks: hooks::HookLog::default(),
+            shell_env: shell_env::ShellEnv::default(),
+            thread_states: thread_state::ThreadStates::default(),
+            turn_metrics: metrics::TurnMetricsTracker::default(),
+            notifier: notifications::Notifier::default(),
+            badge: badge::Badge::default(),
+            dock: dock::Dock::default(),
+            deferrals: deferrals::Deferrals::default(),
+            workspace_templates: templates::WorkspaceTemplates::default(),
+            context_usage: context::ContextTracker::default(),
+            compactions: compaction::Compactions::default(),
+            thread_titles: titles::ThreadTitles::default(),
+            instruction_watchers: instructions::InstructionWatchers::default(),
+            stats_jobs: stats::StatsJobs::default(),
+            audit: audit::AuditLog::default(),
+            path_guards: path_guard::PathGuards::default(),
+            approval_contexts: approval_context::ApprovalContexts::default(),
+            recorder: recording::Recorder::default(),
+            speaker: speech::Speaker::default(),
+            scheduler: scheduler::Scheduler::default(),
+            task_queues: task_queue::TaskQueues::default(),
+            broadcasts: broadcast::Broadcasts::default(),
+            http_api: http_api::HttpApi::default(),
+            window_state: window_state::WindowStateStore::default(),
+            updater: updater::UpdaterState::default(),
+            analytics,
+            data_dir: app_data_dir,
+            instance_lock,
+            storage_error,
+        }
+    }
+
+    fn storage_status(&self) -> StorageStatus {
+        StorageStatus {
+            data_dir: self.data_dir.to_string_lossy().to_string(),
+            read_only: self.instance_lock.is_none(),
+            error: self.storage_error.clone(),
+        }
+    }
+}
+
+#[tauri::command]
+async fn get_storage_status(state: State<'_, AppState>) -> Result<StorageStatus, AppError> {
+    Ok(state.storage_status())
+}
+
+fn read_workspaces(path: &Path) -> Result<HashMap<String, WorkspaceEntry>, String> {
+    let list: Vec<WorkspaceEntry> = storage::read_json_or_default(path)?;
+    Ok(list.into_iter().map(|entry| (entry.id.clone(), entry)).collect())
+}
+
+fn write_workspaces(path: &Path, entries: &[WorkspaceEntry]) -> Result<(), String> {
+    storage::write_json_atomic(path, entries)
+}
+
+fn read_settings(path: &Path) -> Result<AppSettings, String> {
+    let mut settings: AppSettings = storage::read_json_or_default(path)?;
+    if let Some(enabled) = settings.enable_completion_notifications.take() {
+        settings.notifications.turn_complete.native = enabled;
+    }
+    if let Some(enabled) = settings.rate_limit_notifications.take() {
+        settings.notifications.rate_limit.native = enabled;
+    }
+    Ok(settings)
+}
+
+fn write_settings(path: &Path, settings: &AppSettings) -> Result<(), String> {
+    storage::write_json_atomic(path, settings)
+}
+
+fn read_usage_store(path: &Path) -> Result<UsageStore, String> {
+    storage::read_json_or_default(path)
+}
+
+fn write_usage_store(path: &Path, store: &UsageStore) -> Result<(), String> {
+    storage::write_json_atomic(path, store)
+}
+
+fn read_accounts(path: &Path) -> Result<Vec<CodexAccount>, String> {
+    storage::read_json_or_default(path)
+}
+
+fn write_accounts(path: &Path, accounts: &[CodexAccount]) -> Result<(), String> {
+    storage::write_json_atomic(path, accounts)
+}
+
+fn read_prompt_usage(path: &Path) -> Result<PromptUsageStore, String> {
+    storage::read_json_or_default(path)
+}
+
+fn write_prompt_usage(path: &Path, store: &PromptUsageStore) -> Result<(), String> {
+    storage::write_json_atomic(path, store)
+}
+
+fn summarize_prompt_usage(events: &[PromptUsageEvent]) -> Vec<PromptStat> {
+    let mut stats: HashMap<String, PromptStat> = HashMap::new();
+    for event in events {
+        let stat = stats.entry(event.name.clone()).or_insert_with(|| PromptStat {
+            name: event.name.clone(),
+            count: 0,
+            last_used: 0,
+            workspace_ids: Vec::new(),
+        });
+        stat.count += 1;
+        stat.last_used = stat.last_used.max(event.timestamp_ms);
+        if let Some(workspace_id) = event.workspace_id.as_ref() {
+            if !stat.workspace_ids.contains(workspace_id) {
+                stat.workspace_ids.push(workspace_id.clone());
+            }
+        }
+    }
+    let mut stats: Vec<PromptStat> = stats.into_values().collect();
+    stats.sort_by(|a, b| {
+        b.count
+            .cmp(&a.count)
+            .then_with(|| b.last_used.cmp(&a.last_used))
+    });
+    stats
+}
+
+async fn record_prompt_usage_event(
+    state: &AppState,
+    name: &str,
+    workspace_id: Option<String>,
+) -> Result<(), String> {
+    let mut store = state.prompt_usage.lock().await;
+    store.events.push(PromptUsageEvent {
+        name: name.to_string(),
+        workspace_id,
+        timestamp_ms: now_ms(),
+    });
+    if store.events.len() > PROMPT_USAGE_MAX_EVENTS {
+        let overflow = store.events.len() - PROMPT_USAGE_MAX_EVENTS;
+        store.events.drain(..overflow);
+    }
+    write_prompt_usage(&state.prompt_usage_path, &store)
+}
+
+fn now_ms() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_else(|_| Duration::from_secs(0))
+        .as_millis() as i64
+}
+
+fn cutoff_ms(now: i64) -> i64 {
+    now.saturating_sub(24 * 60 * 60 * 1000)
+}
+
+fn prune_points(points: &mut Vec<UsagePoint>, cutoff: i64) {
+    points.retain(|point| point.timestamp_ms >= cutoff);
+}
+
+fn sum_points(points: &[UsagePoint]) -> i64 {
+    points.iter().map(|point| point.tokens).sum()
+}
+
+fn empty_usage_snapshot() -> UsageSnapshot {
+    UsageSnapshot {
+        total_tokens_24h: None,
+        updated_at_ms: None,
+        source: UsageSource::None,
+        rate_limits: None,
+    }
+}
+
+fn parse_rfc3339_ms(value: &str) -> Option<i64> {
+    DateTime::parse_from_rfc3339(value)
+        .ok()
+        .map(|dt| dt.timestamp_millis())
+}
+
+fn system_time_ms(value: SystemTime) -> Option<i64> {
+    value
+        .duration_since(UNIX_EPOCH)
+        .ok()
+        .map(|duration| duration.as_millis() as i64)
+}
+
+fn resolve_codex_home() -> Option<PathBuf> {
+    if let Ok(value) = env::var("CODEX_HOME") {
+        let trimmed = value.trim();
+        if !trimmed.is_empty() {
+            return Some(PathBuf::from(trimmed));
+        }
+    }
+    if let Ok(value) = env::var("HOME") {
+        let trimmed = value.trim();
+        if !trimmed.is_empty() {
+            return Some(PathBuf::from(trimmed).join(".codex"));
+        }
+    }
+    if let Ok(value) = env::var("USERPROFILE") {
+        let trimmed = value.trim();
+        if !trimmed.is_empty() {
+            return Some(PathBuf::from(trimmed).join(".codex"));
+        }
+    }
+    None
+}
+
+fn workspace_codex_home(entry: &WorkspaceEntry) -> Option<PathBuf> {
+    entry
+        .codex_home
+        .as_ref()
+        .map(|value| value.trim())
+        .filter(|value| !value.is_empty())
+        .map(PathBuf::from)
+        .or_else(resolve_codex_home)
+}
+
+fn parse_token_count_from_rollout(value: &Value) -> Option<i64> {
+    let item_type = value.get("type")?.as_str()?;
+    if item_type != "event_msg" {
+        return None;
+    }
+    let payload = value.get("payload")?;
+    let payload_type = payload.get("type")?.as_str()?;
+    if payload_type != "token_count" {
+        return None;
+    }
+    let info = payload.get("info")?;
+    let last_usage = info
+        .get("last_token_usage")
+        .or_else(|| info.get("lastTokenUsage"))?;
+    let total_tokens = last_usage
+        .get("total_tokens")
+        .or_else(|| last_usage.get("totalTokens"))?
+        .as_i64()?;
+    if total_tokens > 0 {
+        Some(total_tokens)
+    } else {
+        None
+    }
+}
+
+fn parse_used_percent(value: &Value) -> Option<i64> {
+    if let Some(int) = value.as_i64() {
+        return Some(int);
+    }
+    value.as_f64().map(|float| float.round() as i64)
+}
+
+fn parse_rate_limit_window(value: &Value) -> Option<RateLimitWindow> {
+    let used_percent = value
+        .get("usedPercent")
+        .or_else(|| value.get("used_percent"))
+        .and_then(parse_used_percent)?;
+    let window_duration_mins = value
+        .get("windowDurationMins")
+        .or_else(|| value.get("window_duration_mins"))
+        .and_then(|value| value.as_i64());
+    let resets_at = value
+        .get("resetsAt")
+        .or_else(|| value.get("resets_at"))
+        .and_then(|value| value.as_i64());
+    Some(RateLimitWindow {
+        used_percent,
+        window_duration_mins,
+        resets_at,
+    })
+}
+
+fn parse_rate_limits_from_container(container: &Value) -> Option<RateLimitSnapshot> {
+    let rate_limits = container
+        .get("rateLimits")
+        .or_else(|| container.get("rate_limits"))?;
+    let primary = rate_limits
+        .get("primary")
+        .and_then(|value| parse_rate_limit_window(value));
+    let secondary = rate_limits
+        .get("secondary")
+        .and_then(|value| parse_rate_limit_window(value));
+    Some(RateLimitSnapshot { primary, secondary })
+}
+
+fn file_inode(metadata: &fs::Metadata) -> u64 {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        metadata.ino()
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = metadata;
+        0
+    }
+}
+
+fn read_session_file_increment(
+    path: &Path,
+    cache: &mut SessionFileCache,
+    cutoff: i64,
+) -> Result<(), String> {
+    use std::io::{Read, Seek, SeekFrom};
+    let mut file = fs::File::open(path).map_err(|e| e.to_string())?;
+    file.seek(SeekFrom::Start(cache.offset))
+        .map_err(|e| e.to_string())?;
+    let mut bytes = Vec::new();
+    file.read_to_end(&mut bytes).map_err(|e| e.to_string())?;
+    let Some(consumed) = bytes.iter().rposition(|byte| *byte == b'\n').map(|index| index + 1)
+    else {
+        return Ok(());
+    };
+    for line in bytes[..consumed].split(|byte| *byte == b'\n') {
+        let Ok(line) = std::str::from_utf8(line) else {
+            continue;
+        };
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        let value: Value = match serde_json::from_str(trimmed) {
+            Ok(value) => value,
+            Err(_) => continue,
+        };
+        let Some(timestamp_ms) = value
+            .get("timestamp")
+            .and_then(|ts| ts.as_str())
+            .and_then(parse_rfc3339_ms)
+        else {
+            continue;
+        };
+        if timestamp_ms < cutoff {
+            continue;
+        }
+        if let Some(tokens) = parse_token_count_from_rollout(&value) {
+            cache.points.push(UsagePoint {
+                timestamp_ms,
+                tokens,
+            });
+        }
+    }
+    cache.offset += consumed as u64;
+    Ok(())
+}
+
+fn scan_session_tokens_24h(
+    codex_home: &Path,
+    cutoff: i64,
+    cache: &mut HashMap<String, SessionFileCache>,
+    seen: &mut std::collections::HashSet<String>,
+) -> Result<Option<i64>, String> {
+    let sessions_dir = codex_home.join("sessions");
+    if !sessions_dir.exists() {
+        return Ok(None);
+    }
+
+    let mut total_tokens: i64 = 0;
+    let walker = WalkBuilder::new(&sessions_dir)
+        .follow_links(false)
+        .max_depth(Some(6))
+        .build();
+
+    for entry in walker {
+        let entry = entry.map_err(|e| e.to_string())?;
+        let path = entry.path();
+        if !entry
+            .file_type()
+            .map(|file_type| file_type.is_file())
+            .unwrap_or(false)
+        {
+            continue;
+        }
+        if path.extension().and_then(|ext| ext.to_str()) != Some("jsonl") {
+            continue;
+        }
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        let modified_ms = metadata.modified().ok().and_then(system_time_ms);
+        if modified_ms.map(|modified_ms| modified_ms < cutoff).unwrap_or(false) {
+            continue;
+        }
+
+        let key = normalize_path(path);
+        seen.insert(key.clone());
+        let file_cache = cache.entry(key).or_default();
+        let inode = file_inode(&metadata);
+        if file_cache.inode != inode || metadata.len() < file_cache.offset {
+            *file_cache = SessionFileCache {
+                inode,
+                ..SessionFileCache::default()
+            };
+        }
+        if metadata.len() > file_cache.offset {
+            read_session_file_increment(path, file_cache, cutoff)?;
+        }
+        file_cache.modified_ms = modified_ms.unwrap_or(0);
+        prune_points(&mut file_cache.points, cutoff);
+        total_tokens += sum_points(&file_cache.points);
+    }
+
+    Ok(Some(total_tokens))
+}
+
+fn find_rollout_file(codex_home: &Path, thread_id: &str) -> Option<PathBuf> {
+    // Rollouts are named `rollout-<timestamp>-<uuid>.jsonl`; anything that is
+    // not a UUID could match unrelated files by suffix.
+    if Uuid::parse_str(thread_id).is_err() {
+        return None;
+    }
+    let sessions_dir = codex_home.join("sessions");
+    if !sessions_dir.exists() {
+        return None;
+    }
+    let suffix = format!("-{thread_id}.jsonl");
+    let walker = WalkBuilder::new(&sessions_dir)
+        .follow_links(false)
+        .max_depth(Some(6))
+        .build();
+    for entry in walker {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(_) => continue,
+        };
+        if !entry
+            .file_type()
+            .map(|file_type| file_type.is_file())
+            .unwrap_or(false)
+        {
+            continue;
+        }
+        let matches = entry
+            .file_name()
+            .to_str()
+            .map(|name| name.ends_with(&suffix))
+            .unwrap_or(false);
+        if matches {
+            return Some(entry.path().to_path_buf());
+        }
+    }
+    None
+}
+
+#[derive(Debug, Clone)]
+struct TranscriptMessage {
+    role: String,
+    text: String,
+}
+
+fn parse_rollout_message(value: &Value) -> Option<TranscriptMessage> {
+    if value.get("type")?.as_str()? != "response_item" {
+        return None;
+    }
+    let payload = value.get("payload")?;
+    if payload.get("type")?.as_str()? != "message" {
+        return None;
+    }
+    let role = payload.get("role")?.as_str()?;
+    if role != "user" && role != "assistant" {
+        return None;
+    }
+    let text = payload
+        .get("content")?
+        .as_array()?
+        .iter()
+        .filter_map(|item| item.get("text").and_then(|text| text.as_str()))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let trimmed = text.trim();
+    if trimmed.is_empty()
+        || trimmed.starts_with("<environment_context>")
+        || trimmed.starts_with("<user_instructions>")
+    {
+        return None;
+    }
+    Some(TranscriptMessage {
+        role: role.to_string(),
+        text: trimmed.to_string(),
+    })
+}
+
+fn read_rollout_transcript(
+    path: &Path,
+    up_to_turn: Option<u32>,
+) -> Result<Vec<TranscriptMessage>, String> {
+    let file = fs::File::open(path).map_err(|e| e.to_string())?;
+    let reader = StdBufReader::new(file);
+    let mut messages: Vec<TranscriptMessage> = Vec::new();
+    let mut turns = 0u32;
+    for line in reader.lines() {
+        let line = line.map_err(|e| e.to_string())?;
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        let value: Value = match serde_json::from_str(trimmed) {
+            Ok(value) => value,
+            Err(_) => continue,
+        };
+        let Some(message) = parse_rollout_message(&value) else {
+            continue;
+        };
+        if message.role == "user" {
+            if let Some(limit) = up_to_turn {
+                if turns >= limit {
+                    break;
+                }
+            }
+            turns += 1;
+        }
+        messages.push(message);
+    }
+    Ok(messages)
+}
+
+fn format_fork_seed(messages: &[TranscriptMessage]) -> String {
+    let mut seed = String::from(
+        "This thread continues an earlier conversation. The transcript so far is below; \
+         use it as context and wait for my next instruction.\n",
+    );
+    for message in messages {
+        let label = if message.role == "user" { "User" } else { "Assistant" };
+        seed.push_str(&format!("\n[{label}]\n{}\n", message.text));
+    }
+    seed
+}
+
+fn is_method_not_found(response: &Value) -> bool {
+    let Some(error) = response.get("error") else {
+        return false;
+    };
+    if error.get("code").and_then(|code| code.as_i64()) == Some(-32601) {
+        return true;
+    }
+    error
+        .get("message")
+        .and_then(|message| message.as_str())
+        .map(|message| {
+            let lower = message.to_lowercase();
+            lower.contains("method not found") || lower.contains("unknown variant")
+        })
+        .unwrap_or(false)
+}
+
+fn extract_app_server_token_delta(message: &Value) -> Option<i64> {
+    let params = message.get("params")?;
+    let token_usage = params.get("tokenUsage").or_else(|| params.get("token_usage"))?;
+    let last_usage = token_usage.get("last").or_else(|| token_usage.get("last_usage"))?;
+    let total_tokens = last_usage
+        .get("totalTokens")
+        .or_else(|| last_usage.get("total_tokens"))?
+        .as_i64()?;
+    if total_tokens > 0 {
+        Some(total_tokens)
+    } else {
+        None
+    }
+}
+
+async fn emit_usage_snapshot(app: &AppHandle, snapshot: UsageSnapshot) {
+    let _ = app.emit("usage-updated", snapshot);
+}
+
+async fn record_app_server_usage(app: &AppHandle, tokens: i64) -> Result<UsageSnapshot, String> {
+    let state = app.state::<AppState>();
+    let now = now_ms();
+    let cutoff = cutoff_ms(now);
+
+    let mut store = state.usage_store.lock().await;
+    store.app_server_points.push(UsagePoint {
+        timestamp_ms: now,
+        tokens,
+    });
+    prune_points(&mut store.app_server_points, cutoff);
+    let total = sum_points(&store.app_server_points);
+    let rate_limits = store.last_rate_limits.clone();
+
+    let snapshot = UsageSnapshot {
+        total_tokens_24h: Some(total),
+        updated_at_ms: Some(now),
+        source: UsageSource::AppServer,
+        rate_limits,
+    };
+    store.last_snapshot = Some(snapshot.clone());
+    write_usage_store(&state.usage_path, &store)?;
+    drop(store);
+
+    emit_usage_snapshot(app, snapshot.clone()).await;
+    Ok(snapshot)
+}
+
+fn format_reset_time(lang: &str, resets_at: Option<i64>) -> Option<String> {
+    let resets_at = resets_at?;
+    let utc = DateTime::from_timestamp(resets_at, 0)?;
+    Some(i18n::format_time(lang, utc.with_timezone(&Local)))
+}
+
+async fn check_rate_limit_thresholds(app: &AppHandle, rate_limits: &RateLimitSnapshot) {
+    let state = app.state::<AppState>();
+    let settings = state.settings.lock().await.clone();
+    if !settings.rate_limit_alerts_enabled {
+        return;
+    }
+    let threshold = settings.rate_limit_warning_percent.clamp(1, 100);
+    let lang = i18n::lang_for(settings.locale.as_deref());
+    let windows = [
+        ("primary", rate_limits.primary.as_ref()),
+        ("secondary", rate_limits.secondary.as_ref()),
+    ];
+    let mut alerts = state.rate_limit_alerts.lock().await;
+    for (name, window) in windows {
+        let Some(window) = window else {
+            continue;
+        };
+        if window.used_percent < threshold {
+            alerts.remove(name);
+            continue;
+        }
+        if alerts.get(name) == Some(&window.resets_at) {
+            continue;
+        }
+        alerts.insert(name.to_string(), window.resets_at);
+
+        let reset_time = format_reset_time(lang, window.resets_at);
+        let warning = json!({
+            "window": name,
+            "usedPercent": window.used_percent,
+            "threshold": threshold,
+            "windowDurationMins": window.window_duration_mins,
+            "resetsAt": window.resets_at,
+        });
+        let _ = app.emit("rate-limit-warning", &warning);
+        hooks::fire(app, hooks::HOOK_RATE_LIMIT_WARNING, None, warning);
+        let window_label = match window.window_duration_mins {
+            Some(mins) if mins > 0 => i18n::format_duration(lang, mins * 60 * 1000),
+            _ => i18n::t(lang, &format!("rateLimit.{name}")),
+        };
+        let percent = window.used_percent.to_string();
+        let body = match reset_time {
+            Some(time) => i18n::t_with(
+                lang,
+                "rateLimit.usedResets",
+                &[("percent", &percent), ("window", &window_label), ("time", &time)],
+            ),
+            None => i18n::t_with(
+                lang,
+                "rateLimit.used",
+                &[("percent", &percent), ("window", &window_label)],
+            ),
+        };
+        notifications::dispatch(
+            app,
+            notifications::NotificationEvent::RateLimit,
+            None,
+            None,
+            i18n::t(lang, "rateLimit.title"),
+            body,
+        )
+        .await;
+    }
+}
+
+async fn record_rate_limits(
+    app: &AppHandle,
+    rate_limits: RateLimitSnapshot,
+) -> Result<UsageSnapshot, String> {
+    let state = app.state::<AppState>();
+    let now = now_ms();
+    let cutoff = cutoff_ms(now);
+    let mut store = state.usage_store.lock().await;
+    prune_points(&mut store.app_server_points, cutoff);
+    store.set_rate_limits(now, rate_limits.clone());
+    let total_tokens_24h = if !store.app_server_points.is_empty() {
+        Some(sum_points(&store.app_server_points))
+    } else {
+        store.last_snapshot.as_ref().and_then(|snapshot| snapshot.total_tokens_24h)
+    };
+    let snapshot = UsageSnapshot {
+        total_tokens_24h,
+        updated_at_ms: Some(now),
+        source: UsageSource::AppServer,
+        rate_limits: Some(rate_limits.clone()),
+    };
+    store.last_snapshot = Some(snapshot.clone());
+    write_usage_store(&state.usage_path, &store)?;
+    drop(store);
+    check_rate_limit_thresholds(app, &rate_limits).await;
+    emit_usage_snapshot(app, snapshot.clone()).await;
+    Ok(snapshot)
+}
+
+async fn fetch_rate_limits_via_app_server(
+    codex_bin: String,
+    codex_home: Option<String>,
+    settings: AppSettings,
+) -> Result<Option<RateLimitSnapshot>, String> {
+    let mut command = build_codex_command(&codex_bin, &settings);
+    if let Some(home) = codex_home {
+        command.env("CODEX_HOME", home);
+    }
+    if settings.bypass_approvals_and_sandbox {
+        command.arg("--dangerously-bypass-approvals-and-sandbox");
+    }
+    if settings.enable_web_search_request {
+        command.arg("--enable").arg("web_search_request");
+    }
+    command.arg("app-server");
+    command.stdin(std::process::Stdio::piped());
+    command.stdout(std::process::Stdio::piped());
+    command.stderr(std::process::Stdio::piped());
+
+    let mut child = command.spawn().map_err(|e| e.to_string())?;
+    let mut stdin = child.stdin.take().ok_or("missing stdin")?;
+    let stdout = child.stdout.take().ok_or("missing stdout")?;
+    let stderr = child.stderr.take().ok_or("missing stderr")?;
+
+    tauri::async_runtime::spawn(async move {
+        let mut lines = BufReader::new(stderr).lines();
+        while let Ok(Some(_)) = lines.next_line().await {}
+    });
+
+    let mut lines = BufReader::new(stdout).lines();
+    let init = json!({
+        "id": 1,
+        "method": "initialize",
+        "params": { "clientInfo": { "name": "codexola", "version": "0.1.0" } }
+    });
+    let mut line = serde_json::to_string(&init).map_err(|e| e.to_string())?;
+    line.push('\n');
+    stdin.write_all(line.as_bytes()).await.map_err(|e| e.to_string())?;
+    let initialized = json!({ "method": "initialized" });
+    let mut line = serde_json::to_string(&initialized).map_err(|e| e.to_string())?;
+    line.push('\n');
+    stdin.write_all(line.as_bytes()).await.map_err(|e| e.to_string())?;
+
+    let request = json!({
+        "id": 2,
+        "method": "account/rateLimits/read",
+        "params": {}
+    });
+    let mut line = serde_json::to_string(&request).map_err(|e| e.to_string())?;
+    line.push('\n');
+    stdin.write_all(line.as_bytes()).await.map_err(|e| e.to_string())?;
+
+    let mut result: Option<RateLimitSnapshot> = None;
+    while let Ok(Some(line)) = lines.next_line().await {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        let value: Value = match serde_json::from_str(trimmed) {
+            Ok(value) => value,
+            Err(_) => continue,
+        };
+        let id = value.get("id").and_then(|id| id.as_i64());
+        if id == Some(2) {
+            if value.get("error").is_some() {
+                break;
+            }
+            let result_value = value.get("result").cloned().unwrap_or_default();
+            result = parse_rate_limits_from_container(&result_value);
+            break;
+        }
+    }
+
+    let _ = child.kill().await;
+    let _ = child.wait().await;
+    Ok(result)
+}
+
+async fn probe_rate_limits_via_temp_app_server(
+    app: &AppHandle,
+) -> Result<Option<RateLimitSnapshot>, String> {
+    let state = app.state::<AppState>();
+    if state
+        .usage_probe_inflight
+        .swap(true, Ordering::SeqCst)
+    {
+        return Ok(None);
+    }
+
+    let settings = state.settings.lock().await.clone();
+    let (codex_bin, codex_home) = {
+        let workspaces = state.workspaces.lock().await;
+        let entry = workspaces
+            .values()
+            .find(|entry| entry.codex_bin.is_some())
+            .or_else(|| workspaces.values().next());
+        (
+            entry
+                .and_then(|entry| entry.codex_bin.clone())
+                .unwrap_or_else(|| "codex".to_string()),
+            entry.and_then(|entry| entry.codex_home.clone()),
+        )
+    };
+    let result = fetch_rate_limits_via_app_server(codex_bin, codex_home, settings).await;
+    state.usage_probe_inflight.store(false, Ordering::SeqCst);
+    result
+}
+
+async fn fetch_rate_limits_from_any_session(
+    state: &AppState,
+) -> Result<Option<RateLimitSnapshot>, AppError> {
+    let session = {
+        let sessions = state.sessions.lock().await;
+        sessions.values().next().cloned()
+    };
+    let Some(session) = session else {
+        return Ok(None);
+    };
+    let response = session
+        .send_request("account/rateLimits/read", json!({}))
+        .await?;
+    let result = response.get("result").cloned().unwrap_or_default();
+    Ok(parse_rate_limits_from_container(&result))
+}
+
+async fn refresh_usage_snapshot(app: &AppHandle) -> Result<UsageSnapshot, String> {
+    let state = app.state::<AppState>();
+    let now = now_ms();
+    let cutoff = cutoff_ms(now);
+    let mut rate_limits = match fetch_rate_limits_from_any_session(&state).await {
+        Ok(rate_limits) => rate_limits,
+        Err(_) => None,
+    };
+    if rate_limits.is_none() {
+        rate_limits = match probe_rate_limits_via_temp_app_server(app).await {
+            Ok(rate_limits) => rate_limits,
+            Err(_) => None,
+        };
+    }
+    if let Some(rate_limits) = rate_limits.as_ref() {
+        check_rate_limit_thresholds(app, rate_limits).await;
+    }
+
+    {
+        let mut store = state.usage_store.lock().await;
+        prune_points(&mut store.app_server_points, cutoff);
+        if !store.app_server_points.is_empty() {
+            let total = sum_points(&store.app_server_points);
+            let snapshot = UsageSnapshot {
+                total_tokens_24h: Some(total),
+                updated_at_ms: Some(now),
+                source: UsageSource::AppServer,
+                rate_limits: rate_limits.clone().or_else(|| store.last_rate_limits.clone()),
+            };
+            store.last_snapshot = Some(snapshot.clone());
+            if let Some(rate_limits) = rate_limits.clone() {
+                store.set_rate_limits(now, rate_limits);
+            }
+            write_usage_store(&state.usage_path, &store)?;
+            drop(store);
+            emit_usage_snapshot(app, snapshot.clone()).await;
+            return Ok(snapshot);
+        }
+    }
+
+    let codex_homes = {
+        let workspaces = state.workspaces.lock().await;
+        let mut homes: Vec<PathBuf> = resolve_codex_home().into_iter().collect();
+        for entry in workspaces.values() {
+            if let Some(home) = workspace_codex_home(entry) {
+                if !homes.contains(&home) {
+                    homes.push(home);
+                }
+            }
+        }
+        homes
+    };
+    let scan_result = if codex_homes.is_empty() {
+        Ok(None)
+    } else {
+        let cutoff_copy = cutoff;
+        let mut cache = state.usage_store.lock().await.session_files.clone();
+        let (result, cache) = tokio::task::spawn_blocking(move || {
+            let mut seen = std::collections::HashSet::new();
+            let mut total: Option<i64> = None;
+            for home in &codex_homes {
+                match scan_session_tokens_24h(home, cutoff_copy, &mut cache, &mut seen) {
+                    Ok(Some(tokens)) => total = Some(total.unwrap_or(0) + tokens),
+                    Ok(None) => {}
+                    Err(err) => return (Err(err), cache),
+                }
+            }
+            cache.retain(|path, _| seen.contains(path));
+            (Ok::<Option<i64>, String>(total), cache)
+        })
+        .await
+        .map_err(|e| e.to_string())?;
+        if result.is_ok() {
+            state.usage_store.lock().await.session_files = cache;
+        }
+        result
+    };
+
+    let total_tokens = match scan_result {
+        Ok(value) => value,
+        Err(_) => {
+            let store = state.usage_store.lock().await;
+            return Ok(store
+                .last_snapshot
+                .clone()
+                .unwrap_or_else(empty_usage_snapshot));
+        }
+    };
+
+    let mut store = state.usage_store.lock().await;
+    prune_points(&mut store.app_server_points, cutoff);
+    if !store.app_server_points.is_empty() {
+        let total = sum_points(&store.app_server_points);
+        let snapshot = UsageSnapshot {
+            total_tokens_24h: Some(total),
+            updated_at_ms: Some(now),
+            source: UsageSource::AppServer,
+            rate_limits: rate_limits.clone().or_else(|| store.last_rate_limits.clone()),
+        };
+        store.last_snapshot = Some(snapshot.clone());
+        if let Some(rate_limits) = rate_limits.clone() {
+            store.set_rate_limits(now, rate_limits);
+        }
+        write_usage_store(&state.usage_path, &store)?;
+        drop(store);
+        emit_usage_snapshot(app, snapshot.clone()).await;
+        return Ok(snapshot);
+    }
+
+    let snapshot = match total_tokens {
+        Some(total) => UsageSnapshot {
+            total_tokens_24h: Some(total),
+            updated_at_ms: Some(now),
+            source: UsageSource::Sessions,
+            rate_limits: rate_limits.clone().or_else(|| store.last_rate_limits.clone()),
+        },
+        None => UsageSnapshot {
+            rate_limits: rate_limits.clone().or_else(|| store.last_rate_limits.clone()),
+            ..empty_usage_snapshot()
+        },
+    };
+    store.last_snapshot = Some(snapshot.clone());
+    if let Some(rate_limits) = rate_limits.clone() {
+        store.set_rate_limits(now, rate_limits);
+    }
+    write_usage_store(&state.usage_path, &store)?;
+    drop(store);
+    emit_usage_snapshot(app, snapshot.clone()).await;
+    Ok(snapshot)
+}
+
+const USAGE_BATTERY_BACKOFF: u32 = 3;
+const USAGE_FOCUS_REFRESH_AFTER: Duration = Duration::from_secs(60);
+
+#[cfg(target_os = "macos")]
+async fn on_battery_or_low_power() -> bool {
+    let output = match Command::new("pmset").arg("-g").arg("batt").output().await {
+        Ok(output) => String::from_utf8_lossy(&output.stdout).to_string(),
+        Err(_) => return false,
+    };
+    if output.contains("'Battery Power'") {
+        return true;
+    }
+    Command::new("pmset")
+        .arg("-g")
+        .output()
+        .await
+        .map(|output| {
+            String::from_utf8_lossy(&output.stdout)
+                .lines()
+                .any(|line| {
+                    let mut parts = line.split_whitespace();
+                    parts.next() == Some("lowpowermode") && parts.next() == Some("1")
+                })
+        })
+        .unwrap_or(false)
+}
+
+#[cfg(target_os = "linux")]
+async fn on_battery_or_low_power() -> bool {
+    let Ok(entries) = fs::read_dir("/sys/class/power_supply") else {
+        return false;
+    };
+    entries.flatten().any(|entry| {
+        fs::read_to_string(entry.path().join("status"))
+            .map(|status| status.trim() == "Discharging")
+            .unwrap_or(false)
+    })
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux")))]
+async fn on_battery_or_low_power() -> bool {
+    false
+}
+
+async fn usage_polling_idle(app: &AppHandle) -> bool {
+    let state = app.state::<AppState>();
+    let focused = state.window_focused.load(Ordering::SeqCst);
+    let connected = !state.sessions.lock().await.is_empty();
+    !focused && !connected
+}
+
+async fn refresh_usage_on_focus(app: &AppHandle) {
+    let state = app.state::<AppState>();
+    if !state.settings.lock().await.usage_polling_enabled {
+        return;
+    }
+    let updated_at = state
+        .usage_store
+        .lock()
+        .await
+        .last_snapshot
+        .as_ref()
+        .and_then(|snapshot| snapshot.updated_at_ms);
+    let stale = updated_at
+        .map(|updated_at| now_ms() - updated_at >= USAGE_FOCUS_REFRESH_AFTER.as_millis() as i64)
+        .unwrap_or(true);
+    if stale {
+        if let Err(err) = refresh_usage_snapshot(app).await {
+            tracing::debug!("usage refresh failed: {err}");
+        }
+    }
+}
+
+async fn restart_usage_polling(app: &AppHandle) {
+    let state = app.state::<AppState>();
+    if let Some(handle) = state.usage_poll_handle.lock().await.take() {
+        handle.abort();
+    }
+
+    let settings = state.settings.lock().await.clone();
+    if !settings.usage_polling_enabled {
+        return;
+    }
+
+    let interval_minutes = settings.usage_polling_interval_minutes.max(1).min(120);
+    let interval_duration = Duration::from_secs(interval_minutes as u64 * 60);
+    let app_handle = app.clone();
+    let handle = tokio::spawn(async move {
+        if let Err(err) = refresh_usage_snapshot(&app_handle).await {
+            tracing::debug!("usage refresh failed: {err}");
+        }
+        let mut last_refresh = tokio::time::Instant::now();
+        let mut ticker = tokio::time::interval(interval_duration);
+        ticker.tick().await;
+        loop {
+            ticker.tick().await;
+            if usage_polling_idle(&app_handle).await {
+                continue;
+            }
+            if on_battery_or_low_power().await
+                && last_refresh.elapsed() < interval_duration * USAGE_BATTERY_BACKOFF
+            {
+                continue;
+            }
+            if let Err(err) = refresh_usage_snapshot(&app_handle).await {
+                tracing::debug!("usage refresh failed: {err}");
+            }
+            last_refresh = tokio::time::Instant::now();
+        }
+    });
+
+    *state.usage_poll_handle.lock().await = Some(handle);
+}
+
+fn workspace_sessions_path(workspace_path: &str) -> PathBuf {
+    PathBuf::from(workspace_path)
+        .join(".codexmonitor")
+        .join("sessions.json")
+}
+
+fn read_workspace_sessions(path: &Path) -> Result<WorkspaceSessionStore, String> {
+    storage::read_json_or_default(path)
+}
+
+fn write_workspace_sessions(path: &Path, sessions: &WorkspaceSessionStore) -> Result<(), String> {
+    storage::write_json_atomic(path, sessions)
+}
+
+const CODEX_SUPPORTED_VERSIONS: &str = ">=0.58.0";
+const CODEX_VERSION_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct CodexVersionInfo {
+    raw: String,
+    version: Option<String>,
+    supported: bool,
+    supported_range: String,
+}
+
+fn parse_codex_version(output: &str) -> Option<semver::Version> {
+    output.split_whitespace().find_map(|token| {
+        let token = token.trim_start_matches('v').trim_end_matches(',');
+        semver::Version::parse(token).ok()
+    })
+}
+
+fn codex_version_supported(version: Option<&str>) -> Option<bool> {
+    let version = semver::Version::parse(version?).ok()?;
+    let range = semver::VersionReq::parse(CODEX_SUPPORTED_VERSIONS).ok()?;
+    let mut release = version.clone();
+    release.pre = semver::Prerelease::EMPTY;
+    Some(range.matches(&release))
+}
+
+async fn codex_version(
+    codex_bin: &str,
+    settings: &AppSettings,
+    env: Option<&HashMap<String, String>>,
+) -> Result<CodexVersionInfo, String> {
+    let mut command = build_codex_command(codex_bin, settings);
+    if let Some(env) = env {
+        command.envs(env);
+    }
+    probe_codex_version(command).await
+}
+
+async fn probe_codex_version(mut command: Command) -> Result<CodexVersionInfo, String> {
+    command
+        .arg("--version")
+        .stdin(std::process::Stdio::null())
+        .kill_on_drop(true);
+    let output = tokio::time::timeout(CODEX_VERSION_TIMEOUT, command.output())
+        .await
+        .map_err(|_| "codex --version timed out".to_string())?
+        .map_err(|e| e.to_string())?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        return Err(if stderr.is_empty() {
+            format!("codex --version exited with {}", output.status)
+        } else {
+            stderr
+        });
+    }
+    let raw = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    let version = parse_codex_version(&raw).map(|version| version.to_string());
+    Ok(CodexVersionInfo {
+        supported: codex_version_supported(version.as_deref()).unwrap_or(false),
+        raw,
+        version,
+        supported_range: CODEX_SUPPORTED_VERSIONS.to_string(),
+    })
+}
+
+#[tauri::command]
+async fn get_codex_version(
+    path: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<CodexVersionInfo, AppError> {
+    let settings = state.settings.lock().await.clone();
+    let codex_bin = path
+        .filter(|path| !path.trim().is_empty())
+        .or_else(|| settings.codex_bin_path.clone())
+        .unwrap_or_else(|| "codex".into());
+    let env = shell_env::spawn_env(&state, &settings).await;
+    Ok(codex_version(&codex_bin, &settings, env.as_ref()).await?)
+}
+
+fn build_codex_command(codex_bin: &str, settings: &AppSettings) -> Command {
+    let codex_path = resolve_binary_path(codex_bin);
+    let requires_node = read_first_line(&codex_path)
+        .ok()
+        .flatten()
+        .map(|line| shebang_requires_node(&line))
+        .unwrap_or(false);
+    let mut node_bin = settings.node_bin_path.clone();
+    if requires_node && node_bin.is_none() {
+        if let Some(suggested) = suggest_node_path(&codex_path) {
+            node_bin = Some(suggested.to_string_lossy().to_string());
+        }
+    }
+    if requires_node {
+        if let Some(node_path) = node_bin {
+            let mut cmd = platform::command(node_path);
+            cmd.arg(codex_path.to_string_lossy().to_string());
+            return cmd;
+        }
+    }
+    platform::script_command(&codex_path)
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct QueuedMessage {
+    id: String,
+    queued_at_ms: i64,
+    #[serde(default)]
+    attempts: u32,
+    #[serde(default)]
+    last_error: Option<String>,
+    #[serde(default)]
+    retry_at_ms: Option<i64>,
+    #[serde(default)]
+    failed: bool,
+    #[serde(flatten)]
+    message: UserMessageRequest,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct MessageQueueStore {
+    #[serde(default = "default_session_store_version")]
+    version: u32,
+    #[serde(default)]
+    messages: Vec<QueuedMessage>,
+}
+
+impl Default for MessageQueueStore {
+    fn default() -> Self {
+        Self {
+            version: default_session_store_version(),
+            messages: Vec::new(),
+        }
+    }
+}
+
+const MESSAGE_QUEUE_MAX_ATTEMPTS: u32 = 5;
+
+fn workspace_queue_path(workspace_path: &str) -> PathBuf {
+    PathBuf::from(workspace_path)
+        .join(".codexmonitor")
+        .join("queue.json")
+}
+
+fn read_message_queue(path: &Path) -> Result<MessageQueueStore, String> {
+    storage::read_json_or_default(path)
+}
+
+fn write_message_queue(path: &Path, queue: &MessageQueueStore) -> Result<(), String> {
+    storage::write_json_atomic(path, queue)
+}
+
+// The outbox holds sends made while disconnected and drains in order on reconnect.
+// The message queue above is user-initiated and waits out rate limits on a live session
+// with per-message backoff; keeping them apart means a reconnect never flushes messages
+// the user deliberately deferred.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct PendingMessage {
+    id: String,
+    created_at_ms: i64,
+    #[serde(default)]
+    attempts: u32,
+    #[serde(default)]
+    last_error: Option<String>,
+    #[serde(flatten)]
+    message: UserMessageRequest,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct OutboxStore {
+    #[serde(default = "default_session_store_version")]
+    version: u32,
+    #[serde(default)]
+    messages: Vec<PendingMessage>,
+}
+
+impl Default for OutboxStore {
+    fn default() -> Self {
+        Self {
+            version: default_session_store_version(),
+            messages: Vec::new(),
+        }
+    }
+}
+
+fn workspace_outbox_path(workspace_path: &str) -> PathBuf {
+    PathBuf::from(workspace_path)
+        .join(".codexmonitor")
+        .join("outbox.json")
+}
+
+fn read_outbox(path: &Path) -> Result<OutboxStore, String> {
+    storage::read_json_or_default(path)
+}
+
+fn write_outbox(path: &Path, outbox: &OutboxStore) -> Result<(), String> {
+    storage::write_json_atomic(path, outbox)
+}
+
+fn emit_outbox_event(
+    app: &AppHandle,
+    workspace_id: &str,
+    message_id: &str,
+    status: &str,
+    detail: Value,
+) {
+    let _ = app.emit(
+        "outbox-updated",
+        json!({
+            "workspaceId": workspace_id,
+            "messageId": message_id,
+            "status": status,
+            "detail": detail,
+        }),
+    );
+}
+
+async fn deliver_outbox(app: &AppHandle, workspace_id: &str) {
+    let state = app.state::<AppState>();
+    let Some(session) = state.sessions.lock().await.get(workspace_id).cloned() else {
+        return;
+    };
+    let _guard = state.outbox_lock.lock().await;
+    let path = workspace_outbox_path(&session.entry.path);
+    loop {
+        let mut outbox = match read_outbox(&path) {
+            Ok(outbox) => outbox,
+            Err(err) => {
+                tracing::warn!(workspace_id, "failed to read outbox: {err}");
+                return;
+            }
+        };
+        let Some(pending) = outbox.messages.first().cloned() else {
+            return;
+        };
+        let checkpoint = state.settings.lock().await.turn_checkpoints_enabled;
+        let result = dispatch_user_message(app, &session, &pending.message, checkpoint).await;
+        let error = match result {
+            Ok(response) => response.get("error").map(|error| error.to_string()),
+            Err(err) => Some(err.to_string()),
+        };
+        match error {
+            None => {
+                outbox.messages.remove(0);
+                if let Err(err) = write_outbox(&path, &outbox) {
+                    tracing::warn!(workspace_id, "failed to persist outbox: {err}");
+                    return;
+                }
+                emit_outbox_event(
+                    app,
+                    workspace_id,
+                    &pending.id,
+                    "delivered",
+                    json!({ "threadId": pending.message.thread_id }),
+                );
+            }
+            Some(error) => {
+                // Later messages stay queued behind the failed one to preserve ordering.
+                if let Some(first) = outbox.messages.first_mut() {
+                    first.attempts += 1;
+                    first.last_error = Some(error.clone());
+                }
+                if let Err(err) = write_outbox(&path, &outbox) {
+                    tracing::warn!(workspace_id, "failed to persist outbox: {err}");
+                }
+                emit_outbox_event(
+                    app,
+                    workspace_id,
+                    &pending.id,
+                    "failed",
+                    json!({ "threadId": pending.message.thread_id, "error": error }),
+                );
+                return;
+            }
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct MessageDraft {
+    #[serde(default)]
+    text: String,
+    #[serde(default)]
+    attachments: Vec<LocalImageInput>,
+    updated_at_ms: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct DraftStore {
+    #[serde(default = "default_session_store_version")]
+    version: u32,
+    #[serde(default)]
+    drafts: HashMap<String, MessageDraft>,
+}
+
+impl Default for DraftStore {
+    fn default() -> Self {
+        Self {
+            version: default_session_store_version(),
+            drafts: HashMap::new(),
+        }
+    }
+}
+
+fn workspace_drafts_path(workspace_path: &str) -> PathBuf {
+    PathBuf::from(workspace_path)
+        .join(".codexmonitor")
+        .join("drafts.json")
+}
+
+fn read_drafts(path: &Path) -> Result<DraftStore, String> {
+    storage::read_json_or_default(path)
+}
+
+fn write_drafts(path: &Path, drafts: &DraftStore) -> Result<(), String> {
+    storage::write_json_atomic(path, drafts)
+}
+
+async fn remove_draft(
+    state: &AppState,
+    workspace_path: &str,
+    thread_id: &str,
+) -> Result<(), String> {
+    let _guard = state.drafts_lock.lock().await;
+    let path = workspace_drafts_path(workspace_path);
+    let mut store = read_drafts(&path)?;
+    if store.drafts.remove(thread_id).is_some() {
+        write_drafts(&path, &store)?;
+    }
+    Ok(())
+}
+
+fn rate_limit_blocked_until(rate_limits: &RateLimitSnapshot, now_secs: i64) -> Option<i64> {
+    [rate_limits.primary.as_ref(), rate_limits.secondary.as_ref()]
+        .into_iter()
+        .flatten()
+        .filter(|window| window.used_percent >= 100)
+        .filter_map(|window| match window.resets_at {
+            Some(resets_at) if resets_at <= now_secs => None,
+            Some(resets_at) => Some(resets_at),
+            None => Some(now_secs + 60),
+        })
+        .max()
+}
+
+fn emit_message_queue_event(
+    app: &AppHandle,
+    workspace_id: &str,
+    message_id: &str,
+    status: &str,
+    detail: Value,
+) {
+    let _ = app.emit(
+        "message-queue-updated",
+        json!({
+            "workspaceId": workspace_id,
+            "messageId": message_id,
+            "status": status,
+            "detail": detail,
+        }),
+    );
+}
+
+async fn dispatch_queued_messages(app: &AppHandle) -> Option<i64> {
+    let state = app.state::<AppState>();
+    let rate_limits = state.usage_store.lock().await.last_rate_limits.clone();
+    let now_secs = now_ms() / 1000;
+    if let Some(resets_at) = rate_limits
+        .as_ref()
+        .and_then(|rate_limits| rate_limit_blocked_until(rate_limits, now_secs))
+    {
+        return Some(resets_at);
+    }
+
+    let entries: Vec<WorkspaceEntry> = state.workspaces.lock().await.values().cloned().collect();
+    for entry in entries {
+        let Some(session) = state.sessions.lock().await.get(&entry.id).cloned() else {
+            continue;
+        };
+        let path = workspace_queue_path(&entry.path);
+        // Failed messages stay listed until cancelled but no longer block the
+        // queue; a message that is backing off keeps later ones waiting.
+        let queued = {
+            let _guard = state.message_queue_lock.lock().await;
+            let Ok(queue) = read_message_queue(&path) else {
+                continue;
+            };
+            let Some(queued) = queue.messages.into_iter().find(|queued| !queued.failed) else {
+                continue;
+            };
+            if queued.retry_at_ms.is_some_and(|retry_at_ms| retry_at_ms > now_ms()) {
+                continue;
+            }
+            queued
+        };
+        let checkpoint = state.settings.lock().await.turn_checkpoints_enabled;
+        let error = match dispatch_user_message(app, &session, &queued.message, checkpoint).await {
+            Ok(response) => response.get("error").map(|error| error.to_string()),
+            Err(err) => Some(err.to_string()),
+        };
+
+        let _guard = state.message_queue_lock.lock().await;
+        let mut queue = match read_message_queue(&path) {
+            Ok(queue) => queue,
+            Err(err) => {
+                tracing::warn!("failed to read message queue: {err}");
+                continue;
+            }
+        };
+        let Some(index) = queue
+            .messages
+            .iter()
+            .position(|message| message.id == queued.id)
+        else {
+            continue;
+        };
+        let (status, detail) = match error {
+            None => {
+                queue.messages.remove(index);
+                ("sent", json!({ "threadId": queued.message.thread_id }))
+            }
+            Some(error) => {
+                let message = &mut queue.messages[index];
+                message.attempts += 1;
+                message.last_error = Some(error.clone());
+                message.failed = message.attempts >= MESSAGE_QUEUE_MAX_ATTEMPTS;
+                let backoff_secs = 15i64 << message.attempts.min(5);
+                message.retry_at_ms =
+                    (!message.failed).then(|| now_ms() + backoff_secs * 1000);
+                let status = if message.failed { "failed" } else { "retrying" };
+                let detail = json!({
+                    "error": error,
+                    "attempts": message.attempts,
+                    "retryAtMs": message.retry_at_ms,
+                });
+                (status, detail)
+            }
+        };
+        if let Err(err) = write_message_queue(&path, &queue) {
+            tracing::warn!("failed to persist message queue: {err}");
+        }
+        emit_message_queue_event(app, &entry.id, &queued.id, status, detail);
+    }
+    None
+}
+
+async fn run_message_queue_scheduler(app: AppHandle) {
+    let mut blocked_until: Option<i64> = None;
+    loop {
+        tokio::time::sleep(Duration::from_secs(15)).await;
+        let next = dispatch_queued_messages(&app).await;
+        if next.is_none() && blocked_until.is_some() {
+            if let Err(err) = refresh_usage_snapshot(&app).await {
+                tracing::debug!("usage refresh failed: {err}");
+            }
+        }
+        if next != blocked_until {
+            if let Some(resets_at) = next {
+                let _ = app.emit("message-queue-waiting", json!({ "resetsAt": resets_at }));
+            }
+        }
+        blocked_until = next;
+    }
+}
+
+async fn spawn_workspace_session(
+    entry: WorkspaceEntry,
+    app_handle: AppHandle,
+) -> Result<Arc<WorkspaceSession>, AppError> {
+    let settings = {
+        let state = app_handle.state::<AppState>();
+        let settings = state.settings.lock().await.clone();
+        settings
+    };
+    let codex_bin = entry
+        .codex_bin
+        .clone()
+        .or_else(|| settings.codex_bin_path.clone())
+        .unwrap_or_else(|| "codex".into());
+    let env = shell_env::spawn_env(&app_handle.state::<AppState>(), &settings).await;
+    tasks::run_pre_connect_commands(&app_handle, &entry, env.as_ref()).await?;
+    if let Some(config) = entry.container.as_ref() {
+        container::ensure_running(config, env.as_ref()).await?;
+    }
+    let version_result = match entry.container.as_ref() {
+        Some(config) => {
+            probe_codex_version(container::exec_command(config, &entry, env.as_ref())).await
+        }
+        None => codex_version(&codex_bin, &settings, env.as_ref()).await,
+    };
+    let codex_version = match version_result {
+        Ok(info) => {
+            if !info.supported {
+                let _ = app_handle.emit(
+                    "codex-version-warning",
+                    json!({
+                        "workspaceId": entry.id,
+                        "version": info.version,
+                        "raw": info.raw,
+                        "supportedRange": info.supported_range,
+                    }),
+                );
+            }
+            info.version
+        }
+        Err(err) => {
+            tracing::warn!(workspace_id = %entry.id, "codex version check failed: {err}");
+            None
+        }
+    };
+    let mut command = match entry.container.as_ref() {
+        Some(config) => container::exec_command(config, &entry, env.as_ref()),
+        None => {
+            let mut command = build_codex_command(&codex_bin, &settings);
+            if let Some(env) = env.as_ref() {
+                command.envs(env);
+            }
+            command.envs(&entry.defaults.env);
+            if let Some(home) = entry.codex_home.as_ref().filter(|home| !home.trim().is_empty()) {
+                command.env("CODEX_HOME", home);
+            }
+            command
+        }
+    };
+    if settings.bypass_approvals_and_sandbox {
+        command.arg("--dangerously-bypass-approvals-and-sandbox");
+    }
+    if settings.enable_web_search_request {
+        command.arg("--enable").arg("web_search_request");
+    }
+    command.arg("app-server");
+    command.stdin(std::process::Stdio::piped());
+    command.stdout(std::process::Stdio::piped());
     command.stderr(std::process::Stdio::piped());
 
-    let mut child = command.spawn().map_err(|e| e.to_string())?;
-    let mut stdin = child.stdin.take().ok_or("missing stdin")?;
-    let stdout = child.stdout.take().ok_or("missing stdout")?;
-    let stderr = child.stderr.take().ok_or("missing stderr")?;
+    let mut child = command.spawn().map_err(|err| {
+        if err.kind() == std::io::ErrorKind::NotFound {
+            format!(
+                "Codex binary not found: {codex_bin}. Set the Codex path in settings \
+                 or pick one of the discovered installations."
+            )
+        } else {
+            err.to_string()
+        }
+    })?;
+    let stdin = child.stdin.take().ok_or("missing stdin")?;
+    let stdout = child.stdout.take().ok_or("missing stdout")?;
+    let stderr = child.stderr.take().ok_or("missing stderr")?;
+
+    let session = Arc::new(WorkspaceSession {
+        entry: entry.clone(),
+        child: Mutex::new(child),
+        stdin: Mutex::new(stdin),
+        pending: Mutex::new(HashMap::new()),
+        next_id: AtomicU64::new(1),
+        limiter: RequestLimiter::new(SESSION_MAX_IN_FLIGHT),
+        codex_version,
+        capabilities: capabilities::SessionCapabilities::default(),
+        observer_mode: AtomicBool::new(entry.observer_mode),
+    });
+
+    let session_clone = Arc::clone(&session);
+    let workspace_id = entry.id.clone();
+    let app_handle_clone = app_handle.clone();
+    let batcher = batching::EventBatcher::spawn(app_handle.clone(), entry.id.clone());
+    crash::spawn_monitored("app-server-reader", async move {
+        let mut lines = BufReader::new(stdout).lines();
+        let result = loop {
+            let line = match lines.next_line().await {
+                Ok(Some(line)) => line,
+                Ok(None) => break Ok(()),
+                Err(err) => break Err(format!("failed to read output of {workspace_id}: {err}")),
+            };
+            if line.trim().is_empty() {
+                continue;
+            }
+            let mut value: Value = match serde_json::from_str(&line) {
+                Ok(value) => value,
+                Err(err) => {
+                    batcher.send(json!({
+                        "method": "codex/parseError",
+                        "params": { "error": err.to_string(), "raw": line },
+                    }));
+                    continue;
+                }
+            };
+
+            let maybe_id = value.get("id").and_then(|id| id.as_u64());
+            let has_method = value.get("method").is_some();
+            let has_result_or_error =
+                value.get("result").is_some() || value.get("error").is_some();
+            let method_name = value
+                .get("method")
+                .and_then(|method| method.as_str())
+                .unwrap_or("")
+                .to_string();
+
+            if !has_result_or_error
+                && titles::intercept(&app_handle_clone, &workspace_id, &value).await
+            {
+                continue;
+            }
+            if !has_result_or_error
+                && observer::intercept(&app_handle_clone, &session_clone, &value).await
+            {
+                continue;
+            }
+            if !has_result_or_error
+                && path_guard::intercept(&app_handle_clone, &session_clone, &value).await
+            {
+                continue;
+            }
+            if !has_result_or_error {
+                approval_context::enrich(&app_handle_clone, &workspace_id, &mut value).await;
+            }
+            if !has_result_or_error
+                && command_allowlist::intercept(&app_handle_clone, &session_clone, &value).await
+            {
+                continue;
+            }
+            if method_name == "thread/tokenUsage/updated" {
+                if let Some(tokens) = extract_app_server_token_delta(&value) {
+                    if let Err(err) = record_app_server_usage(&app_handle_clone, tokens).await {
+                        tracing::warn!("failed to record token usage: {err}");
+                    }
+                }
+            }
+            if is_file_change_notification(&value) {
+                let state = app_handle_clone.state::<AppState>();
+                mark_file_index_stale(&state, &workspace_id).await;
+            }
+            if method_name == "turn/completed" {
+                let params = value.get("params").cloned().unwrap_or(Value::Null);
+                hooks::fire(
+                    &app_handle_clone,
+                    hooks::HOOK_TURN_COMPLETED,
+                    Some(workspace_id.clone()),
+                    params,
+                );
+            }
+            if let Some(method) = hooks::approval_request_method(&value) {
+                hooks::fire(
+                    &app_handle_clone,
+                    hooks::HOOK_APPROVAL_REQUESTED,
+                    Some(workspace_id.clone()),
+                    json!({
+                        "method": method,
+                        "requestId": value.get("id"),
+                        "params": value.get("params"),
+                    }),
+                );
+            }
+            thread_state::observe(&app_handle_clone, &workspace_id, &value).await;
+            metrics::observe(&app_handle_clone, &workspace_id, &value).await;
+            notifications::observe(&app_handle_clone, &workspace_id, &value).await;
+            deferrals::observe(&app_handle_clone, &workspace_id, &value).await;
+            review_findings::observe(&app_handle_clone, &workspace_id, &value).await;
+            context::observe(&app_handle_clone, &workspace_id, &value).await;
+            compaction::observe(&app_handle_clone, &workspace_id, &value).await;
+            titles::observe(&app_handle_clone, &workspace_id, &value).await;
+            audit::observe(&app_handle_clone, &workspace_id, &value).await;
+            task_queue::observe(&app_handle_clone, &workspace_id, &value).await;
+            broadcast::observe(&app_handle_clone, &workspace_id, &value).await;
+            analytics::observe(&app_handle_clone, &value);
+            if method_name == "account/rateLimits/updated" {
+                if let Some(params) = value.get("params") {
+                    if let Some(rate_limits) = parse_rate_limits_from_container(params) {
+                        if let Err(err) = record_rate_limits(&app_handle_clone, rate_limits).await {
+                            tracing::warn!("failed to record rate limits: {err}");
+                        }
+                    }
+                }
+            }
+            if let Some(id) = maybe_id {
+                if has_result_or_error {
+                    if let Some(tx) = session_clone.pending.lock().await.remove(&id) {
+                        let _ = tx.send(value);
+                    }
+                } else if has_method {
+                    batcher.send(value);
+                } else if let Some(tx) = session_clone.pending.lock().await.remove(&id) {
+                    let _ = tx.send(value);
+                }
+            } else if has_method {
+                batcher.send(value);
+            }
+        };
+        thread_state::session_exited(&app_handle_clone, &workspace_id).await;
+        task_queue::session_exited(&app_handle_clone, &workspace_id).await;
+        broadcast::session_exited(&app_handle_clone, &workspace_id).await;
+        result
+    });
+
+    let workspace_id = entry.id.clone();
+    let app_handle_clone = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        let mut lines = BufReader::new(stderr).lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let payload = AppServerEvent {
+                workspace_id: workspace_id.clone(),
+                message: json!({
+                    "method": "codex/stderr",
+                    "params": { "message": line },
+                }),
+            };
+            let _ = app_handle_clone.emit("app-server-event", payload);
+        }
+    });
+
+    let init_params = json!({
+        "clientInfo": {
+            "name": "codexola",
+            "title": "Codexola",
+            "version": "0.1.0"
+        }
+    });
+    let initialize = tokio::time::timeout(
+        Duration::from_secs(30),
+        session.send_request("initialize", init_params),
+    )
+    .await??;
+    session.capabilities.record_initialize(&initialize);
+    session.send_notification("initialized", None).await?;
+
+    let payload = AppServerEvent {
+        workspace_id: entry.id.clone(),
+        message: json!({
+            "method": "codex/connected",
+            "params": { "workspaceId": entry.id.clone() }
+        }),
+    };
+    let _ = app_handle.emit("app-server-event", payload);
+    tasks::run_startup_commands(&app_handle, &entry).await;
+    start_file_index_watcher(&app_handle, entry).await;
+
+    Ok(session)
+}
 
-    tauri::async_runtime::spawn(async move {
-        let mut lines = BufReader::new(stderr).lines();
-        while let Ok(Some(_)) = lines.next_line().await {}
-    });
+async fn workspace_infos(state: &AppState) -> Vec<WorkspaceInfo> {
+    let workspaces = state.workspaces.lock().await;
+    let sessions = state.sessions.lock().await;
+    let mut result = Vec::new();
+    for entry in workspaces.values() {
+        result.push(WorkspaceInfo {
+            id: entry.id.clone(),
+            name: entry.name.clone(),
+            path: entry.path.clone(),
+            codex_bin: entry.codex_bin.clone(),
+            codex_home: entry.codex_home.clone(),
+            connected: sessions.contains_key(&entry.id),
+            codex_version: entry.codex_version.clone(),
+            codex_version_supported: codex_version_supported(entry.codex_version.as_deref()),
+            template_id: entry.template_id.clone(),
+            defaults: entry.defaults.clone(),
+            container: entry.container.clone(),
+            pre_connect_commands: entry.pre_connect_commands.clone(),
+            observer_mode: entry.observer_mode,
+            speak_completions: entry.speak_completions,
+            auto_connect: entry.auto_connect,
+        });
+    }
+    result.sort_by(|a, b| a.name.cmp(&b.name));
+    result
+}
 
-    let mut lines = BufReader::new(stdout).lines();
-    let init = json!({
-        "id": 1,
-        "method": "initialize",
-        "params": { "clientInfo": { "name": "codexola", "version": "0.1.0" } }
-    });
-    let mut line = serde_json::to_string(&init).map_err(|e| e.to_string())?;
-    line.push('\n');
-    stdin.write_all(line.as_bytes()).await.map_err(|e| e.to_string())?;
-    let initialized = json!({ "method": "initialized" });
-    let mut line = serde_json::to_string(&initialized).map_err(|e| e.to_string())?;
-    line.push('\n');
-    stdin.write_all(line.as_bytes()).await.map_err(|e| e.to_string())?;
+#[tauri::command]
+async fn list_workspaces(state: State<'_, AppState>) -> Result<Vec<WorkspaceInfo>, AppError> {
+    Ok(workspace_infos(&state).await)
+}
 
-    let request = json!({
-        "id": 2,
-        "method": "account/rateLimits/read",
-        "params": {}
-    });
-    let mut line = serde_json::to_string(&request).map_err(|e| e.to_string())?;
-    line.push('\n');
-    stdin.write_all(line.as_bytes()).await.map_err(|e| e.to_string())?;
+#[tauri::command]
+async fn add_workspace(
+    path: String,
+    codex_bin: Option<String>,
+    codex_home: Option<String>,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<WorkspaceInfo, AppError> {
+    let entry = new_workspace_entry(&path, codex_bin, codex_home);
+    insert_workspace(entry, &state, app).await
+}
 
-    let mut result: Option<RateLimitSnapshot> = None;
-    while let Ok(Some(line)) = lines.next_line().await {
-        let trimmed = line.trim();
-        if trimmed.is_empty() {
-            continue;
-        }
-        let value: Value = match serde_json::from_str(trimmed) {
-            Ok(value) => value,
-            Err(_) => continue,
-        };
-        let id = value.get("id").and_then(|id| id.as_i64());
-        if id == Some(2) {
-            if value.get("error").is_some() {
-                break;
-            }
-            let result_value = value.get("result").cloned().unwrap_or_default();
-            result = parse_rate_limits_from_container(&result_value);
-            break;
-        }
+fn new_workspace_entry(
+    path: &str,
+    codex_bin: Option<String>,
+    codex_home: Option<String>,
+) -> WorkspaceEntry {
+    let path = platform::simplify_path(Path::new(path))
+        .to_string_lossy()
+        .to_string();
+    let name = PathBuf::from(&path)
+        .file_name()
+        .and_then(|s| s.to_str())
+        .unwrap_or("Workspace")
+        .to_string();
+    WorkspaceEntry {
+        id: Uuid::new_v4().to_string(),
+        name,
+        path,
+        codex_bin,
+        codex_home,
+        codex_version: None,
+        template_id: None,
+        defaults: templates::WorkspaceDefaults::default(),
+        container: None,
+        pre_connect_commands: Vec::new(),
+        observer_mode: false,
+        speak_completions: false,
+        auto_connect: false,
     }
+}
 
-    let _ = child.kill().await;
-    let _ = child.wait().await;
-    Ok(result)
+async fn insert_workspace(
+    mut entry: WorkspaceEntry,
+    state: &AppState,
+    app: AppHandle,
+) -> Result<WorkspaceInfo, AppError> {
+    let session = spawn_workspace_session(entry.clone(), app.clone()).await?;
+    entry.codex_version = session.codex_version.clone();
+    {
+        let mut workspaces = state.workspaces.lock().await;
+        workspaces.insert(entry.id.clone(), entry.clone());
+        let list: Vec<_> = workspaces.values().cloned().collect();
+        write_workspaces(&state.storage_path, &list)?;
+    }
+    state
+        .sessions
+        .lock()
+        .await
+        .insert(entry.id.clone(), session);
+    menu::rebuild(&app).await;
+
+    Ok(WorkspaceInfo {
+        id: entry.id,
+        name: entry.name,
+        path: entry.path,
+        codex_bin: entry.codex_bin,
+        codex_home: entry.codex_home,
+        connected: true,
+        codex_version_supported: codex_version_supported(entry.codex_version.as_deref()),
+        codex_version: entry.codex_version,
+        template_id: entry.template_id,
+        defaults: entry.defaults,
+        container: entry.container,
+        pre_connect_commands: entry.pre_connect_commands,
+        observer_mode: entry.observer_mode,
+        speak_completions: entry.speak_completions,
+        auto_connect: entry.auto_connect,
+    })
 }
 
-async fn probe_rate_limits_via_temp_app_server(
-    app: &AppHandle,
-) -> Result<Option<RateLimitSnapshot>, String> {
-    let state = app.state::<AppState>();
-    if state
-        .usage_probe_inflight
-        .swap(true, Ordering::SeqCst)
+#[tauri::command]
+async fn remove_workspace(
+    id: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<(), AppError> {
     {
-        return Ok(None);
+        let mut workspaces = state.workspaces.lock().await;
+        workspaces.remove(&id);
+        let list: Vec<_> = workspaces.values().cloned().collect();
+        write_workspaces(&state.storage_path, &list)?;
+    }
+
+    if let Some(session) = state.sessions.lock().await.remove(&id) {
+        let mut child = session.child.lock().await;
+        let _ = child.kill().await;
+    }
+    stop_file_index_watcher(&state, &id).await;
+    instructions::stop_watching(&state, &id).await;
+    state.terminals.kill_workspace(&id).await;
+    state.tasks.kill_workspace(&id).await;
+    menu::rebuild(&app).await;
+
+    Ok(())
+}
+
+fn default_approval_policy(access_mode: &AccessMode) -> &'static str {
+    match access_mode {
+        AccessMode::FullAccess => "never",
+        _ => "on-request",
+    }
+}
+
+fn default_sandbox_mode(access_mode: &AccessMode) -> &'static str {
+    match access_mode {
+        AccessMode::ReadOnly => "read-only",
+        AccessMode::Current => "workspace-write",
+        AccessMode::FullAccess => "danger-full-access",
+    }
+}
+
+const APPROVAL_POLICIES: &[&str] = &["untrusted", "on-failure", "on-request", "never"];
+const SANDBOX_MODES: &[&str] = &["read-only", "workspace-write", "danger-full-access"];
+
+fn known_choice(
+    kind: &str,
+    value: Option<String>,
+    allowed: &[&str],
+) -> Result<Option<String>, String> {
+    match value.map(|value| value.trim().to_string()) {
+        None => Ok(None),
+        Some(value) if value.is_empty() => Ok(None),
+        Some(value) if allowed.contains(&value.as_str()) => Ok(Some(value)),
+        Some(value) => Err(format!(
+            "Unknown {kind} `{value}` (expected one of: {}).",
+            allowed.join(", ")
+        )),
+    }
+}
+
+fn thread_override_params(
+    approval_policy: Option<String>,
+    sandbox_mode: Option<String>,
+    model: Option<String>,
+    effort: Option<String>,
+) -> Result<Map<String, Value>, String> {
+    let mut params = Map::new();
+    let approval_policy = known_choice("approval policy", approval_policy, APPROVAL_POLICIES)?;
+    if let Some(approval_policy) = approval_policy {
+        params.insert("approvalPolicy".to_string(), json!(approval_policy));
+    }
+    let sandbox_mode = known_choice("sandbox mode", sandbox_mode, SANDBOX_MODES)?;
+    if let Some(sandbox_mode) = sandbox_mode {
+        params.insert("sandbox".to_string(), json!(sandbox_mode));
+    }
+    if let Some(model) = model {
+        params.insert("model".to_string(), json!(model));
     }
+    if let Some(effort) = effort {
+        params.insert(
+            "config".to_string(),
+            json!({ "model_reasoning_effort": effort }),
+        );
+    }
+    Ok(params)
+}
+
+// New threads fall back to the app's access mode; resumed threads keep their stored policy.
+fn apply_policy_defaults(settings: &AppSettings, params: &mut Map<String, Value>) {
+    params
+        .entry("approvalPolicy")
+        .or_insert_with(|| json!(default_approval_policy(&settings.access_mode)));
+    params
+        .entry("sandbox")
+        .or_insert_with(|| json!(default_sandbox_mode(&settings.access_mode)));
+}
 
+#[tauri::command]
+async fn start_thread(
+    workspace_id: String,
+    approval_policy: Option<String>,
+    sandbox_mode: Option<String>,
+    model: Option<String>,
+    effort: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<Value, AppError> {
     let settings = state.settings.lock().await.clone();
-    let codex_bin = {
-        let workspaces = state.workspaces.lock().await;
-        workspaces
-            .values()
-            .find_map(|entry| entry.codex_bin.clone())
-            .unwrap_or_else(|| "codex".to_string())
+    let session = connected_session(&state, &workspace_id).await?;
+    let sandbox_mode = if observer::is_observing(&session) {
+        Some(observer::READ_ONLY_SANDBOX.to_string())
+    } else {
+        sandbox_mode
     };
-    let result = fetch_rate_limits_via_app_server(codex_bin, settings).await;
-    state.usage_probe_inflight.store(false, Ordering::SeqCst);
-    result
+    let mut params = thread_override_params(approval_policy, sandbox_mode, model, effort)?;
+    apply_policy_defaults(&settings, &mut params);
+    params.insert("cwd".to_string(), json!(container::agent_cwd(&session.entry)));
+    session
+        .send_request("thread/start", Value::Object(params))
+        .await
 }
 
-async fn fetch_rate_limits_from_any_session(
-    state: &AppState,
-) -> Result<Option<RateLimitSnapshot>, String> {
-    let session = {
-        let sessions = state.sessions.lock().await;
-        sessions.values().next().cloned()
-    };
-    let Some(session) = session else {
-        return Ok(None);
+#[tauri::command]
+async fn resume_thread(
+    workspace_id: String,
+    thread_id: String,
+    approval_policy: Option<String>,
+    sandbox_mode: Option<String>,
+    model: Option<String>,
+    effort: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<Value, AppError> {
+    let session = connected_session(&state, &workspace_id).await?;
+    let sandbox_mode = if observer::is_observing(&session) {
+        Some(observer::READ_ONLY_SANDBOX.to_string())
+    } else {
+        sandbox_mode
     };
-    let response = session
-        .send_request("account/rateLimits/read", json!({}))
-        .await?;
-    let result = response.get("result").cloned().unwrap_or_default();
-    Ok(parse_rate_limits_from_container(&result))
+    let mut params = thread_override_params(approval_policy, sandbox_mode, model, effort)?;
+    params.insert("threadId".to_string(), json!(thread_id));
+    window_state::note_active(&state, &workspace_id, &thread_id);
+    session
+        .send_request("thread/resume", Value::Object(params))
+        .await
 }
 
-async fn refresh_usage_snapshot(app: &AppHandle) -> Result<UsageSnapshot, String> {
-    let state = app.state::<AppState>();
-    let now = now_ms();
-    let cutoff = cutoff_ms(now);
-    let mut rate_limits = match fetch_rate_limits_from_any_session(&state).await {
-        Ok(rate_limits) => rate_limits,
-        Err(_) => None,
-    };
-    if rate_limits.is_none() {
-        rate_limits = match probe_rate_limits_via_temp_app_server(app).await {
-            Ok(rate_limits) => rate_limits,
-            Err(_) => None,
-        };
-    }
+#[tauri::command]
+async fn list_threads(
+    workspace_id: String,
+    cursor: Option<String>,
+    limit: Option<u32>,
+    state: State<'_, AppState>,
+) -> Result<Value, AppError> {
+    let session = connected_session(&state, &workspace_id).await?;
+    let params = json!({
+        "cursor": cursor,
+        "limit": limit,
+    });
+    session.send_request("thread/list", params).await
+}
 
-    {
-        let mut store = state.usage_store.lock().await;
-        prune_points(&mut store.app_server_points, cutoff);
-        if !store.app_server_points.is_empty() {
-            let total = sum_points(&store.app_server_points);
-            let snapshot = UsageSnapshot {
-                total_tokens_24h: Some(total),
-                updated_at_ms: Some(now),
-                source: UsageSource::AppServer,
-                rate_limits: rate_limits.clone().or_else(|| store.last_rate_limits.clone()),
+#[tauri::command]
+async fn list_all_threads(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Vec<WorkspaceThreads>, AppError> {
+    let sessions: Vec<Arc<WorkspaceSession>> =
+        state.sessions.lock().await.values().cloned().collect();
+    let semaphore = Arc::new(Semaphore::new(THREAD_LIST_CONCURRENCY));
+    let (tx, mut rx) = mpsc::channel::<WorkspaceThreads>(sessions.len().max(1));
+    let mut outstanding: Vec<String> = Vec::new();
+    for session in sessions {
+        outstanding.push(session.entry.id.clone());
+        let semaphore = Arc::clone(&semaphore);
+        let tx = tx.clone();
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            let _permit = semaphore.acquire_owned().await;
+            let (threads, error) = match fetch_workspace_threads(&session).await {
+                Ok(threads) => (threads, None),
+                Err(error) => (Vec::new(), Some(error)),
             };
-            store.last_snapshot = Some(snapshot.clone());
-            if rate_limits.is_some() {
-                store.last_rate_limits = rate_limits.clone();
+            let result = WorkspaceThreads {
+                workspace_id: session.entry.id.clone(),
+                threads,
+                error,
+                pending: false,
+            };
+            let _ = app.emit("workspace-threads-loaded", &result);
+            let _ = tx.send(result).await;
+        });
+    }
+    drop(tx);
+
+    let deadline = tokio::time::Instant::now() + THREAD_LIST_DEADLINE;
+    let mut results: Vec<WorkspaceThreads> = Vec::new();
+    while !outstanding.is_empty() {
+        match tokio::time::timeout_at(deadline, rx.recv()).await {
+            Ok(Some(result)) => {
+                outstanding.retain(|id| id != &result.workspace_id);
+                results.push(result);
             }
-            write_usage_store(&state.usage_path, &store)?;
-            drop(store);
-            emit_usage_snapshot(app, snapshot.clone()).await;
-            return Ok(snapshot);
+            Ok(None) | Err(_) => break,
         }
     }
+    results.extend(outstanding.into_iter().map(|workspace_id| WorkspaceThreads {
+        workspace_id,
+        threads: Vec::new(),
+        error: None,
+        pending: true,
+    }));
+    Ok(results)
+}
+
+#[tauri::command]
+async fn archive_thread(
+    workspace_id: String,
+    thread_id: String,
+    state: State<'_, AppState>,
+) -> Result<Value, AppError> {
+    let session = connected_session(&state, &workspace_id).await?;
+    let params = json!({
+        "threadId": thread_id
+    });
+    session.send_request("thread/archive", params).await
+}
+
+const DELETE_TOKEN_TTL_MS: i64 = 60_000;
+
+struct DeleteToken {
+    workspace_id: String,
+    thread_id: String,
+    expires_at_ms: i64,
+}
+
+// Deletion is a two-step handshake: the caller asks for a single-use token
+// scoped to one thread and must spend it within a minute.
+#[tauri::command]
+async fn prepare_delete_thread(
+    workspace_id: String,
+    thread_id: String,
+    state: State<'_, AppState>,
+) -> Result<Value, AppError> {
+    if thread_id.trim().is_empty() {
+        return Err("Thread id is required.".into());
+    }
+    workspace_entry(&state, &workspace_id).await?;
+    let token = Uuid::new_v4().to_string();
+    let expires_at_ms = now_ms() + DELETE_TOKEN_TTL_MS;
+    let mut tokens = state.delete_tokens.lock().await;
+    tokens.retain(|_, pending| pending.expires_at_ms > now_ms());
+    tokens.insert(
+        token.clone(),
+        DeleteToken {
+            workspace_id,
+            thread_id,
+            expires_at_ms,
+        },
+    );
+    Ok(json!({ "confirmToken": token, "expiresAtMs": expires_at_ms }))
+}
 
-    let codex_home = resolve_codex_home();
-    let scan_result = if let Some(home) = codex_home {
-        let cutoff_copy = cutoff;
-        tokio::task::spawn_blocking(move || scan_session_tokens_24h(&home, cutoff_copy))
-            .await
-            .map_err(|e| e.to_string())?
-    } else {
-        Ok(None)
+#[tauri::command]
+async fn delete_thread(
+    workspace_id: String,
+    thread_id: String,
+    confirm_token: String,
+    delete_rollout: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<Value, AppError> {
+    if thread_id.trim().is_empty() {
+        return Err("Thread id is required.".into());
+    }
+    let pending = state.delete_tokens.lock().await.remove(&confirm_token);
+    let confirmed = pending.is_some_and(|pending| {
+        pending.workspace_id == workspace_id
+            && pending.thread_id == thread_id
+            && pending.expires_at_ms > now_ms()
+    });
+    if !confirmed {
+        return Err("Confirmation token is invalid or expired; request a new one.".into());
+    }
+    let entry = {
+        let workspaces = state.workspaces.lock().await;
+        workspaces
+            .get(&workspace_id)
+            .cloned()
+            .ok_or(AppError::WorkspaceNotFound)?
     };
+    let session = state.sessions.lock().await.get(&workspace_id).cloned();
 
-    let total_tokens = match scan_result {
-        Ok(value) => value,
-        Err(_) => {
-            let store = state.usage_store.lock().await;
-            return Ok(store
-                .last_snapshot
-                .clone()
-                .unwrap_or_else(empty_usage_snapshot));
+    let mut server_deleted = false;
+    if let Some(session) = session {
+        let response = session
+            .send_request("thread/delete", json!({ "threadId": thread_id }))
+            .await?;
+        if response.get("error").is_none() {
+            server_deleted = true;
+        } else if !is_method_not_found(&response) {
+            return Err(AppError::from_rpc_response(&response).unwrap_or(AppError::NotConnected));
         }
-    };
+    }
 
-    let mut store = state.usage_store.lock().await;
-    prune_points(&mut store.app_server_points, cutoff);
-    if !store.app_server_points.is_empty() {
-        let total = sum_points(&store.app_server_points);
-        let snapshot = UsageSnapshot {
-            total_tokens_24h: Some(total),
-            updated_at_ms: Some(now),
-            source: UsageSource::AppServer,
-            rate_limits: rate_limits.clone().or_else(|| store.last_rate_limits.clone()),
-        };
-        store.last_snapshot = Some(snapshot.clone());
-        if rate_limits.is_some() {
-            store.last_rate_limits = rate_limits.clone();
-        }
-        write_usage_store(&state.usage_path, &store)?;
-        drop(store);
-        emit_usage_snapshot(app, snapshot.clone()).await;
-        return Ok(snapshot);
+    let path = workspace_sessions_path(&entry.path);
+    let mut store = read_workspace_sessions(&path)?;
+    let metadata_removed = store.sessions.remove(&thread_id).is_some();
+    if metadata_removed {
+        write_workspace_sessions(&path, &store)?;
     }
 
-    let snapshot = match total_tokens {
-        Some(total) => UsageSnapshot {
-            total_tokens_24h: Some(total),
-            updated_at_ms: Some(now),
-            source: UsageSource::Sessions,
-            rate_limits: rate_limits.clone().or_else(|| store.last_rate_limits.clone()),
-        },
-        None => UsageSnapshot {
-            rate_limits: rate_limits.clone().or_else(|| store.last_rate_limits.clone()),
-            ..empty_usage_snapshot()
-        },
-    };
-    store.last_snapshot = Some(snapshot.clone());
-    if rate_limits.is_some() {
-        store.last_rate_limits = rate_limits.clone();
+    let mut rollout_path: Option<String> = None;
+    if delete_rollout.unwrap_or(false) {
+        if let Some(home) = workspace_codex_home(&entry) {
+            let thread_id = thread_id.clone();
+            let found = tokio::task::spawn_blocking(move || find_rollout_file(&home, &thread_id))
+                .await?;
+            if let Some(found) = found {
+                fs::remove_file(&found)?;
+                rollout_path = Some(found.to_string_lossy().to_string());
+            }
+        }
     }
-    write_usage_store(&state.usage_path, &store)?;
-    drop(store);
-    emit_usage_snapshot(app, snapshot.clone()).await;
-    Ok(snapshot)
+
+    Ok(json!({
+        "threadId": thread_id,
+        "serverDeleted": server_deleted,
+        "metadataRemoved": metadata_removed,
+        "rolloutPath": rollout_path,
+    }))
 }
 
-async fn restart_usage_polling(app: &AppHandle) {
-    let state = app.state::<AppState>();
-    if let Some(handle) = state.usage_poll_handle.lock().await.take() {
-        handle.abort();
+#[tauri::command]
+async fn fork_thread(
+    workspace_id: String,
+    thread_id: String,
+    up_to_turn: Option<u32>,
+    state: State<'_, AppState>,
+) -> Result<Value, AppError> {
+    let session = state
+        .sessions
+        .lock()
+        .await
+        .get(&workspace_id)
+        .cloned()
+        .ok_or(AppError::NotConnected)?;
+    let home = workspace_codex_home(&session.entry).ok_or("codex home unavailable")?;
+    let source_id = thread_id.clone();
+    let transcript = tokio::task::spawn_blocking(move || {
+        let path = find_rollout_file(&home, &source_id).ok_or("source transcript not found")?;
+        read_rollout_transcript(&path, up_to_turn)
+    })
+    .await??;
+    if transcript.is_empty() {
+        return Err("source thread has no messages to fork".into());
     }
 
     let settings = state.settings.lock().await.clone();
-    if !settings.usage_polling_enabled {
-        return;
+    let mut params = Map::new();
+    apply_policy_defaults(&settings, &mut params);
+    params.insert("cwd".to_string(), json!(container::agent_cwd(&session.entry)));
+    let response = session
+        .send_request("thread/start", Value::Object(params))
+        .await?;
+    if let Some(error) = AppError::from_rpc_response(&response) {
+        return Err(error);
     }
-
-    let interval_minutes = settings.usage_polling_interval_minutes.max(1).min(120);
-    let interval_duration = Duration::from_secs(interval_minutes as u64 * 60);
-    let app_handle = app.clone();
-    let handle = tokio::spawn(async move {
-        let _ = refresh_usage_snapshot(&app_handle).await;
-        let mut ticker = tokio::time::interval(interval_duration);
-        loop {
-            ticker.tick().await;
-            let _ = refresh_usage_snapshot(&app_handle).await;
-        }
+    let new_thread_id = response
+        .get("result")
+        .and_then(|result| result.get("thread"))
+        .and_then(|thread| thread.get("id"))
+        .and_then(|id| id.as_str())
+        .map(|id| id.to_string())
+        .ok_or("thread/start returned no thread id")?;
+
+    let seed = format_fork_seed(&transcript);
+    let params = json!({
+        "threadId": new_thread_id,
+        "input": [{ "type": "text", "text": seed }],
+        "cwd": container::agent_cwd(&session.entry),
     });
+    let seeded = session.send_request("turn/start", params).await?;
+    if let Some(error) = AppError::from_rpc_response(&seeded) {
+        return Err(format!(
+            "Forked thread {new_thread_id} was created but could not be seeded: {error}"
+        )
+        .into());
+    }
 
-    *state.usage_poll_handle.lock().await = Some(handle);
+    Ok(json!({
+        "sourceThreadId": thread_id,
+        "threadId": new_thread_id,
+        "seededMessages": transcript.len(),
+        "thread": response.get("result").and_then(|result| result.get("thread")).cloned(),
+    }))
 }
 
-fn workspace_sessions_path(workspace_path: &str) -> PathBuf {
-    PathBuf::from(workspace_path)
-        .join(".codexmonitor")
-        .join("sessions.json")
+#[tauri::command]
+async fn get_workspace_sessions(
+    workspace_id: String,
+    state: State<'_, AppState>,
+) -> Result<WorkspaceSessionStore, AppError> {
+    let workspaces = state.workspaces.lock().await;
+    let entry = workspaces
+        .get(&workspace_id)
+        .ok_or(AppError::WorkspaceNotFound)?;
+    let path = workspace_sessions_path(&entry.path);
+    Ok(read_workspace_sessions(&path)?)
 }
 
-fn read_workspace_sessions(path: &PathBuf) -> Result<WorkspaceSessionStore, String> {
-    if !path.exists() {
-        return Ok(WorkspaceSessionStore::default());
+#[tauri::command]
+async fn save_workspace_sessions(
+    workspace_id: String,
+    sessions: WorkspaceSessionStore,
+    state: State<'_, AppState>,
+) -> Result<WorkspaceSessionStore, AppError> {
+    let workspaces = state.workspaces.lock().await;
+    let entry = workspaces
+        .get(&workspace_id)
+        .ok_or(AppError::WorkspaceNotFound)?;
+    let path = workspace_sessions_path(&entry.path);
+    let mut store = sessions;
+    if store.version == 0 {
+        store.version = default_session_store_version();
     }
-    let data = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
-    serde_json::from_str(&data).map_err(|e| e.to_string())
-}
-
-fn write_workspace_sessions(
-    path: &PathBuf,
-    sessions: &WorkspaceSessionStore,
-) -> Result<(), String> {
-    if let Some(parent) = path.parent() {
-        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    if let Ok(existing) = read_workspace_sessions(&path) {
+        for (thread_id, metadata) in store.sessions.iter_mut() {
+            let Some(previous) = existing.sessions.get(thread_id) else {
+                continue;
+            };
+            metadata.auto_titled |= previous.auto_titled;
+            metadata.access_mode = previous.access_mode;
+            if metadata.last_state.is_some() {
+                continue;
+            }
+            metadata.last_state = previous.last_state;
+            metadata.last_state_at_ms = previous.last_state_at_ms;
+        }
     }
-    let data = serde_json::to_string_pretty(sessions).map_err(|e| e.to_string())?;
-    std::fs::write(path, data).map_err(|e| e.to_string())
+    write_workspace_sessions(&path, &store)?;
+    Ok(store)
 }
 
-async fn spawn_workspace_session(
-    entry: WorkspaceEntry,
-    app_handle: AppHandle,
-) -> Result<Arc<WorkspaceSession>, String> {
-    let settings = {
-        let state = app_handle.state::<AppState>();
-        let settings = state.settings.lock().await.clone();
-        settings
-    };
-    let codex_bin = entry
-        .codex_bin
-        .clone()
-        .or_else(|| settings.codex_bin_path.clone())
-        .unwrap_or_else(|| "codex".into());
-    let codex_path = resolve_binary_path(&codex_bin);
-    let requires_node = read_first_line(&codex_path)
-        .ok()
-        .flatten()
-        .map(|line| shebang_requires_node(&line))
-        .unwrap_or(false);
-    let mut node_bin = settings.node_bin_path.clone();
-    if requires_node && node_bin.is_none() {
-        if let Some(suggested) = suggest_node_path(&codex_path) {
-            node_bin = Some(suggested.to_string_lossy().to_string());
-        }
-    }
-    let mut command = if requires_node {
-        if let Some(node_path) = node_bin {
-            let mut cmd = Command::new(node_path);
-            cmd.arg(codex_path.to_string_lossy().to_string());
-            cmd
-        } else {
-            Command::new(codex_path.to_string_lossy().to_string())
-        }
-    } else {
-        Command::new(codex_path.to_string_lossy().to_string())
-    };
-    if settings.bypass_approvals_and_sandbox {
-        command.arg("--dangerously-bypass-approvals-and-sandbox");
-    }
-    if settings.enable_web_search_request {
-        command.arg("--enable").arg("web_search_request");
+#[tauri::command]
+async fn save_attachment(
+    workspace_id: String,
+    bytes: Vec<u8>,
+    name: Option<String>,
+    mime: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<Value, AppError> {
+    if bytes.is_empty() {
+        return Err("empty attachment".into());
     }
-    command.arg("app-server");
-    command.stdin(std::process::Stdio::piped());
-    command.stdout(std::process::Stdio::piped());
-    command.stderr(std::process::Stdio::piped());
-
-    let mut child = command.spawn().map_err(|e| e.to_string())?;
-    let stdin = child.stdin.take().ok_or("missing stdin")?;
-    let stdout = child.stdout.take().ok_or("missing stdout")?;
-    let stderr = child.stderr.take().ok_or("missing stderr")?;
+    let entry = workspace_entry(&state, &workspace_id).await?;
+    let max_dimension = state.settings.lock().await.attachment_max_dimension;
+    let mut dir = PathBuf::from(&entry.path);
+    dir.push(".codex");
+    dir.push("attachments");
+    std::fs::create_dir_all(&dir)?;
 
-    let session = Arc::new(WorkspaceSession {
-        entry: entry.clone(),
-        child: Mutex::new(child),
-        stdin: Mutex::new(stdin),
-        pending: Mutex::new(HashMap::new()),
-        next_id: AtomicU64::new(1),
+    let name_ext = name
+        .as_deref()
+        .and_then(|value| Path::new(value).extension())
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.to_lowercase());
+    let mime_ext = mime.as_deref().and_then(|value| match value {
+        "image/heic" => Some("heic"),
+        "image/heif" => Some("heif"),
+        _ => None,
     });
+    let extension = name_ext.or(mime_ext.map(str::to_string)).unwrap_or_default();
+    let original_size = bytes.len();
+    let image = tokio::task::spawn_blocking(move || {
+        attachments::normalize(bytes, &extension, max_dimension)
+    })
+    .await??;
 
-    let session_clone = Arc::clone(&session);
-    let workspace_id = entry.id.clone();
-    let app_handle_clone = app_handle.clone();
-    tauri::async_runtime::spawn(async move {
-        let mut lines = BufReader::new(stdout).lines();
-        while let Ok(Some(line)) = lines.next_line().await {
-            if line.trim().is_empty() {
-                continue;
-            }
-            let value: Value = match serde_json::from_str(&line) {
-                Ok(value) => value,
-                Err(err) => {
-                    let payload = AppServerEvent {
-                        workspace_id: workspace_id.clone(),
-                        message: json!({
-                            "method": "codex/parseError",
-                            "params": { "error": err.to_string(), "raw": line },
-                        }),
-                    };
-                    let _ = app_handle_clone.emit("app-server-event", payload);
-                    continue;
-                }
-            };
+    let filename = format!("{}.{}", Uuid::new_v4(), image.extension);
+    let mut path = dir.clone();
+    path.push(filename);
+    std::fs::write(&path, &image.bytes)?;
+    Ok(json!({
+        "path": path.to_string_lossy().to_string(),
+        "mime": image.mime,
+        "width": image.width,
+        "height": image.height,
+        "size": image.bytes.len(),
+        "originalSize": original_size,
+        "downscaled": image.downscaled,
+    }))
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct UserMessageRequest {
+    thread_id: String,
+    text: String,
+    #[serde(default)]
+    model: Option<String>,
+    #[serde(default)]
+    effort: Option<String>,
+    #[serde(default)]
+    access_mode: Option<String>,
+    #[serde(default)]
+    attachments: Vec<LocalImageInput>,
+}
+
+async fn dispatch_user_message(
+    app: &AppHandle,
+    session: &WorkspaceSession,
+    message: &UserMessageRequest,
+    checkpoint: bool,
+) -> Result<Value, AppError> {
+    // Every send path ends here, so the thread's access lock and the path guard
+    // are applied once for all of them.
+    let mut message = message.clone();
+    thread_access::enforce(&session.entry, &mut message)?;
+    message.attachments = path_guard::filter_attachments(
+        app,
+        &session.entry.id,
+        &session.entry.path,
+        &message.thread_id,
+        std::mem::take(&mut message.attachments),
+    );
+    let access_mode = if observer::is_observing(session) {
+        "read-only".to_string()
+    } else {
+        message
+            .access_mode
+            .clone()
+            .or_else(|| session.entry.defaults.access_mode.clone())
+            .unwrap_or_else(|| "current".to_string())
+    };
+    let sandbox_policy = match access_mode.as_str() {
+        "full-access" => json!({
+            "type": "dangerFullAccess"
+        }),
+        "read-only" => json!({
+            "type": "readOnly"
+        }),
+        _ => json!({
+            "type": "workspaceWrite",
+            "writableRoots": [container::agent_cwd(&session.entry)],
+            "networkAccess": true
+        }),
+    };
 
-            let maybe_id = value.get("id").and_then(|id| id.as_u64());
-            let has_method = value.get("method").is_some();
-            let has_result_or_error =
-                value.get("result").is_some() || value.get("error").is_some();
-            let method_name = value
-                .get("method")
-                .and_then(|method| method.as_str())
-                .unwrap_or("");
+    let approval_policy = if access_mode == "full-access" {
+        "never"
+    } else {
+        "on-request"
+    };
 
-            if method_name == "thread/tokenUsage/updated" {
-                if let Some(tokens) = extract_app_server_token_delta(&value) {
-                    let _ = record_app_server_usage(&app_handle_clone, tokens).await;
-                }
-            }
-            if method_name == "account/rateLimits/updated" {
-                if let Some(params) = value.get("params") {
-                    if let Some(rate_limits) = parse_rate_limits_from_container(params) {
-                        let _ = record_rate_limits(&app_handle_clone, rate_limits).await;
-                    }
-                }
-            }
-            if let Some(id) = maybe_id {
-                if has_result_or_error {
-                    if let Some(tx) = session_clone.pending.lock().await.remove(&id) {
-                        let _ = tx.send(value);
-                    }
-                } else if has_method {
-                    let payload = AppServerEvent {
-                        workspace_id: workspace_id.clone(),
-                        message: value,
-                    };
-                    let _ = app_handle_clone.emit("app-server-event", payload);
-                } else if let Some(tx) = session_clone.pending.lock().await.remove(&id) {
-                    let _ = tx.send(value);
-                }
-            } else if has_method {
-                let payload = AppServerEvent {
-                    workspace_id: workspace_id.clone(),
-                    message: value,
-                };
-                let _ = app_handle_clone.emit("app-server-event", payload);
-            }
+    let mut input: Vec<Value> = Vec::new();
+    if !message.text.trim().is_empty() {
+        input.push(json!({ "type": "text", "text": message.text }));
+    }
+    for attachment in &message.attachments {
+        if !attachment.path.trim().is_empty() {
+            let path = container::agent_attachment_path(&session.entry, &attachment.path);
+            input.push(json!({ "type": "localImage", "path": path }));
         }
+    }
+    if input.is_empty() {
+        return Err("empty input".into());
+    }
+
+    let params = json!({
+        "threadId": message.thread_id,
+        "input": input,
+        "cwd": container::agent_cwd(&session.entry),
+        "approvalPolicy": approval_policy,
+        "sandboxPolicy": sandbox_policy,
+        "model": message.model,
+        "effort": message.effort,
     });
+    if checkpoint {
+        let path = session.entry.path.clone();
+        let thread_id = message.thread_id.clone();
+        let result =
+            tokio::task::spawn_blocking(move || create_checkpoint(&path, &thread_id)).await;
+        if let Ok(Err(err)) = result {
+            tracing::warn!(workspace_id = %session.entry.id, "checkpoint failed: {err}");
+        }
+    }
+    session.send_request("turn/start", params).await
+}
 
-    let workspace_id = entry.id.clone();
-    let app_handle_clone = app_handle.clone();
-    tauri::async_runtime::spawn(async move {
-        let mut lines = BufReader::new(stderr).lines();
-        while let Ok(Some(line)) = lines.next_line().await {
-            if line.trim().is_empty() {
-                continue;
+#[tauri::command]
+async fn send_user_message(
+    workspace_id: String,
+    mut message: UserMessageRequest,
+    secret_action: Option<secrets::SecretAction>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Value, AppError> {
+    let checkpoint = state.settings.lock().await.turn_checkpoints_enabled;
+    window_state::note_active(&state, &workspace_id, &message.thread_id);
+    let entry = workspace_entry(&state, &workspace_id).await?;
+    message.attachments = path_guard::filter_attachments(
+        &app,
+        &workspace_id,
+        &entry.path,
+        &message.thread_id,
+        std::mem::take(&mut message.attachments),
+    );
+    let message = secrets::screen(&app, &state, &workspace_id, message, secret_action).await?;
+    state.deferrals.touch();
+    let session = state.sessions.lock().await.get(&workspace_id).cloned();
+    let Some(session) = session else {
+        let pending = enqueue_outbox_message(&app, &state, &workspace_id, message).await?;
+        let workspace_path = state
+            .workspaces
+            .lock()
+            .await
+            .get(&workspace_id)
+            .map(|entry| entry.path.clone());
+        if let Some(workspace_path) = workspace_path {
+            let thread_id = &pending.message.thread_id;
+            if let Err(err) = remove_draft(&state, &workspace_path, thread_id).await {
+                tracing::warn!(workspace_id = %workspace_id, "failed to clear draft: {err}");
             }
-            let payload = AppServerEvent {
-                workspace_id: workspace_id.clone(),
-                message: json!({
-                    "method": "codex/stderr",
-                    "params": { "message": line },
-                }),
-            };
-            let _ = app_handle_clone.emit("app-server-event", payload);
         }
-    });
-
-    let init_params = json!({
-        "clientInfo": {
-            "name": "codexola",
-            "title": "Codexola",
-            "version": "0.1.0"
+        return Ok(json!({ "pending": true, "pendingMessage": pending }));
+    };
+    let response = dispatch_user_message(&app, &session, &message, checkpoint).await?;
+    if response.get("error").is_none() {
+        if let Err(err) = remove_draft(&state, &session.entry.path, &message.thread_id).await {
+            tracing::warn!(workspace_id = %workspace_id, "failed to clear draft: {err}");
         }
-    });
-    session.send_request("initialize", init_params).await?;
-    session.send_notification("initialized", None).await?;
+    }
+    Ok(response)
+}
 
-    let payload = AppServerEvent {
-        workspace_id: entry.id.clone(),
-        message: json!({
-            "method": "codex/connected",
-            "params": { "workspaceId": entry.id.clone() }
-        }),
+async fn enqueue_outbox_message(
+    app: &AppHandle,
+    state: &AppState,
+    workspace_id: &str,
+    message: UserMessageRequest,
+) -> Result<PendingMessage, AppError> {
+    let entry = state
+        .workspaces
+        .lock()
+        .await
+        .get(workspace_id)
+        .cloned()
+        .ok_or(AppError::WorkspaceNotFound)?;
+    let pending = PendingMessage {
+        id: Uuid::new_v4().to_string(),
+        created_at_ms: now_ms(),
+        attempts: 0,
+        last_error: None,
+        message,
     };
-    let _ = app_handle.emit("app-server-event", payload);
-
-    Ok(session)
+    {
+        let _guard = state.outbox_lock.lock().await;
+        let path = workspace_outbox_path(&entry.path);
+        let mut outbox = read_outbox(&path)?;
+        outbox.messages.push(pending.clone());
+        write_outbox(&path, &outbox)?;
+    }
+    emit_outbox_event(
+        app,
+        workspace_id,
+        &pending.id,
+        "pending",
+        json!({ "threadId": pending.message.thread_id }),
+    );
+    Ok(pending)
 }
 
 #[tauri::command]
-async fn list_workspaces(state: State<'_, AppState>) -> Result<Vec<WorkspaceInfo>, String> {
-    let workspaces = state.workspaces.lock().await;
-    let sessions = state.sessions.lock().await;
-    let mut result = Vec::new();
-    for entry in workspaces.values() {
-        result.push(WorkspaceInfo {
-            id: entry.id.clone(),
-            name: entry.name.clone(),
-            path: entry.path.clone(),
-            codex_bin: entry.codex_bin.clone(),
-            connected: sessions.contains_key(&entry.id),
-        });
-    }
-    result.sort_by(|a, b| a.name.cmp(&b.name));
-    Ok(result)
+async fn list_pending_messages(
+    workspace_id: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<PendingMessage>, AppError> {
+    let entry = state
+        .workspaces
+        .lock()
+        .await
+        .get(&workspace_id)
+        .cloned()
+        .ok_or(AppError::WorkspaceNotFound)?;
+    let _guard = state.outbox_lock.lock().await;
+    Ok(read_outbox(&workspace_outbox_path(&entry.path))?.messages)
 }
 
 #[tauri::command]
-async fn add_workspace(
-    path: String,
-    codex_bin: Option<String>,
+async fn cancel_pending_message(
+    workspace_id: String,
+    message_id: String,
+    app: AppHandle,
     state: State<'_, AppState>,
+) -> Result<(), AppError> {
+    let entry = state
+        .workspaces
+        .lock()
+        .await
+        .get(&workspace_id)
+        .cloned()
+        .ok_or(AppError::WorkspaceNotFound)?;
+    let _guard = state.outbox_lock.lock().await;
+    let path = workspace_outbox_path(&entry.path);
+    let mut outbox = read_outbox(&path)?;
+    outbox.messages.retain(|message| message.id != message_id);
+    write_outbox(&path, &outbox)?;
+    emit_outbox_event(&app, &workspace_id, &message_id, "canceled", json!({}));
+    Ok(())
+}
+
+#[tauri::command]
+async fn queue_message(
+    workspace_id: String,
+    mut message: UserMessageRequest,
+    secret_action: Option<secrets::SecretAction>,
     app: AppHandle,
-) -> Result<WorkspaceInfo, String> {
-    let name = PathBuf::from(&path)
-        .file_name()
-        .and_then(|s| s.to_str())
-        .unwrap_or("Workspace")
-        .to_string();
-    let entry = WorkspaceEntry {
+    state: State<'_, AppState>,
+) -> Result<QueuedMessage, AppError> {
+    let entry = workspace_entry(&state, &workspace_id).await?;
+    // Screen now: the dispatcher sends later with nobody around to confirm findings.
+    message.attachments = path_guard::filter_attachments(
+        &app,
+        &workspace_id,
+        &entry.path,
+        &message.thread_id,
+        std::mem::take(&mut message.attachments),
+    );
+    let message = secrets::screen(&app, &state, &workspace_id, message, secret_action).await?;
+    let queued = QueuedMessage {
         id: Uuid::new_v4().to_string(),
-        name: name.clone(),
-        path: path.clone(),
-        codex_bin,
+        queued_at_ms: now_ms(),
+        attempts: 0,
+        last_error: None,
+        retry_at_ms: None,
+        failed: false,
+        message,
     };
-
-    let session = spawn_workspace_session(entry.clone(), app).await?;
-    {
-        let mut workspaces = state.workspaces.lock().await;
-        workspaces.insert(entry.id.clone(), entry.clone());
-        let list: Vec<_> = workspaces.values().cloned().collect();
-        write_workspaces(&state.storage_path, &list)?;
+    if queued.message.text.trim().is_empty() && queued.message.attachments.is_empty() {
+        return Err("empty input".into());
     }
-    state
-        .sessions
+    let _guard = state.message_queue_lock.lock().await;
+    let path = workspace_queue_path(&entry.path);
+    let mut queue = read_message_queue(&path)?;
+    queue.messages.push(queued.clone());
+    write_message_queue(&path, &queue)?;
+    emit_message_queue_event(&app, &workspace_id, &queued.id, "queued", json!({}));
+    Ok(queued)
+}
+
+#[tauri::command]
+async fn list_queued_messages(
+    workspace_id: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<QueuedMessage>, AppError> {
+    let entry = state
+        .workspaces
         .lock()
         .await
-        .insert(entry.id.clone(), session);
+        .get(&workspace_id)
+        .cloned()
+        .ok_or(AppError::WorkspaceNotFound)?;
+    let _guard = state.message_queue_lock.lock().await;
+    Ok(read_message_queue(&workspace_queue_path(&entry.path))?.messages)
+}
 
-    Ok(WorkspaceInfo {
-        id: entry.id,
-        name: entry.name,
-        path: entry.path,
-        codex_bin: entry.codex_bin,
-        connected: true,
-    })
+#[tauri::command]
+async fn cancel_queued_message(
+    workspace_id: String,
+    message_id: String,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<(), AppError> {
+    let entry = state
+        .workspaces
+        .lock()
+        .await
+        .get(&workspace_id)
+        .cloned()
+        .ok_or(AppError::WorkspaceNotFound)?;
+    let _guard = state.message_queue_lock.lock().await;
+    let path = workspace_queue_path(&entry.path);
+    let mut queue = read_message_queue(&path)?;
+    queue.messages.retain(|message| message.id != message_id);
+    write_message_queue(&path, &queue)?;
+    emit_message_queue_event(&app, &workspace_id, &message_id, "canceled", json!({}));
+    Ok(())
 }
 
 #[tauri::command]
-async fn remove_workspace(
-    id: String,
+async fn save_draft(
+    workspace_id: String,
+    thread_id: String,
+    text: String,
+    attachments: Option<Vec<LocalImageInput>>,
     state: State<'_, AppState>,
-) -> Result<(), String> {
-    {
-        let mut workspaces = state.workspaces.lock().await;
-        workspaces.remove(&id);
-        let list: Vec<_> = workspaces.values().cloned().collect();
-        write_workspaces(&state.storage_path, &list)?;
+) -> Result<Option<MessageDraft>, AppError> {
+    let entry = state
+        .workspaces
+        .lock()
+        .await
+        .get(&workspace_id)
+        .cloned()
+        .ok_or(AppError::WorkspaceNotFound)?;
+    let attachments = attachments.unwrap_or_default();
+    if text.trim().is_empty() && attachments.is_empty() {
+        remove_draft(&state, &entry.path, &thread_id).await?;
+        return Ok(None);
     }
+    let draft = MessageDraft {
+        text,
+        attachments,
+        updated_at_ms: now_ms(),
+    };
+    let _guard = state.drafts_lock.lock().await;
+    let path = workspace_drafts_path(&entry.path);
+    let mut store = read_drafts(&path)?;
+    store.drafts.insert(thread_id, draft.clone());
+    write_drafts(&path, &store)?;
+    Ok(Some(draft))
+}
 
-    if let Some(session) = state.sessions.lock().await.remove(&id) {
-        let mut child = session.child.lock().await;
-        let _ = child.kill().await;
-    }
+#[tauri::command]
+async fn get_draft(
+    workspace_id: String,
+    thread_id: String,
+    state: State<'_, AppState>,
+) -> Result<Option<MessageDraft>, AppError> {
+    let entry = state
+        .workspaces
+        .lock()
+        .await
+        .get(&workspace_id)
+        .cloned()
+        .ok_or(AppError::WorkspaceNotFound)?;
+    let _guard = state.drafts_lock.lock().await;
+    let mut store = read_drafts(&workspace_drafts_path(&entry.path))?;
+    Ok(store.drafts.remove(&thread_id))
+}
 
-    Ok(())
+#[tauri::command]
+async fn clear_draft(
+    workspace_id: String,
+    thread_id: String,
+    state: State<'_, AppState>,
+) -> Result<(), AppError> {
+    let entry = state
+        .workspaces
+        .lock()
+        .await
+        .get(&workspace_id)
+        .cloned()
+        .ok_or(AppError::WorkspaceNotFound)?;
+    Ok(remove_draft(&state, &entry.path, &thread_id).await?)
+}
+
+#[tauri::command]
+async fn cancel_turn(
+    workspace_id: String,
+    thread_id: String,
+    state: State<'_, AppState>,
+) -> Result<Value, AppError> {
+    let session = connected_session(&state, &workspace_id).await?;
+    let params = json!({
+        "threadId": thread_id,
+        "reason": "user_cancel"
+    });
+    session.send_request("turn/cancel", params).await
+}
+
+#[tauri::command]
+async fn start_review(
+    workspace_id: String,
+    thread_id: String,
+    target: Value,
+    delivery: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<Value, AppError> {
+    request_review(&state, &workspace_id, thread_id, target, delivery).await
+}
+
+async fn request_review(
+    state: &AppState,
+    workspace_id: &str,
+    thread_id: String,
+    target: Value,
+    delivery: Option<String>,
+) -> Result<Value, AppError> {
+    let session = connected_session(state, workspace_id).await?;
+    let mut params = Map::new();
+    params.insert("threadId".to_string(), json!(thread_id));
+    params.insert("target".to_string(), target);
+    if let Some(delivery) = delivery {
+        params.insert("delivery".to_string(), json!(delivery));
+    }
+    session
+        .send_request("review/start", Value::Object(params))
+        .await
 }
 
 #[tauri::command]
-async fn start_thread(
+async fn model_list(
     workspace_id: String,
     state: State<'_, AppState>,
-) -> Result<Value, String> {
-    let sessions = state.sessions.lock().await;
-    let session = sessions
-        .get(&workspace_id)
-        .ok_or("workspace not connected")?;
-    let params = json!({
-        "cwd": session.entry.path,
-        "approvalPolicy": "on-request"
-    });
-    session.send_request("thread/start", params).await
+) -> Result<Value, AppError> {
+    let session = connected_session(&state, &workspace_id).await?;
+    let params = json!({});
+    session.send_request("model/list", params).await
 }
 
 #[tauri::command]
-async fn resume_thread(
+async fn skills_list(
     workspace_id: String,
-    thread_id: String,
     state: State<'_, AppState>,
-) -> Result<Value, String> {
-    let sessions = state.sessions.lock().await;
-    let session = sessions
-        .get(&workspace_id)
-        .ok_or("workspace not connected")?;
+) -> Result<Value, AppError> {
+    let session = connected_session(&state, &workspace_id).await?;
     let params = json!({
-        "threadId": thread_id
+        "cwd": container::agent_cwd(&session.entry)
     });
-    session.send_request("thread/resume", params).await
+    let mut response = session.send_request("skills/list", params).await?;
+    skills::annotate_enabled(&session.entry, &mut response);
+    Ok(response)
 }
 
 #[tauri::command]
-async fn list_threads(
-    workspace_id: String,
-    cursor: Option<String>,
-    limit: Option<u32>,
-    state: State<'_, AppState>,
-) -> Result<Value, String> {
-    let sessions = state.sessions.lock().await;
-    let session = sessions
-        .get(&workspace_id)
-        .ok_or("workspace not connected")?;
-    let params = json!({
-        "cursor": cursor,
-        "limit": limit,
-    });
-    session.send_request("thread/list", params).await
+async fn prompts_list(state: State<'_, AppState>) -> Result<Vec<PromptListItem>, AppError> {
+    let last_used: HashMap<String, i64> = {
+        let store = state.prompt_usage.lock().await;
+        let mut map = HashMap::new();
+        for event in &store.events {
+            let entry = map.entry(event.name.clone()).or_insert(0);
+            *entry = (*entry).max(event.timestamp_ms);
+        }
+        map
+    };
+    let Some(dir) = prompts_dir() else {
+        return Ok(Vec::new());
+    };
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let entries = fs::read_dir(&dir)?;
+    let mut items: Vec<PromptListItem> = Vec::new();
+    for entry in entries {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(_) => continue,
+        };
+        let path = entry.path();
+        if path.extension().and_then(|ext| ext.to_str()) != Some("md") {
+            continue;
+        }
+        let name = match path.file_stem().and_then(|stem| stem.to_str()) {
+            Some(value) if !value.trim().is_empty() => value.to_string(),
+            _ => continue,
+        };
+        let contents = match fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(_) => continue,
+        };
+        let (meta, _body) = parse_prompt_file(&contents);
+        let last_used = last_used.get(&name).copied();
+        items.push(PromptListItem {
+            name,
+            path: path.to_string_lossy().to_string(),
+            description: meta.description,
+            argument_hint: meta.argument_hint,
+            last_used,
+        });
+    }
+    items.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(items)
 }
 
 #[tauri::command]
-async fn archive_thread(
-    workspace_id: String,
-    thread_id: String,
-    state: State<'_, AppState>,
-) -> Result<Value, String> {
-    let sessions = state.sessions.lock().await;
-    let session = sessions
-        .get(&workspace_id)
-        .ok_or("workspace not connected")?;
-    let params = json!({
-        "threadId": thread_id
-    });
-    session.send_request("thread/archive", params).await
+async fn prompt_read(name: String) -> Result<PromptFile, AppError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("prompt name is empty".into());
+    }
+    if name.contains('/') || name.contains('\\') {
+        return Err("invalid prompt name".into());
+    }
+    let dir = prompts_dir().ok_or("prompt directory unavailable")?;
+    let path = dir.join(format!("{name}.md"));
+    let contents = fs::read_to_string(&path)?;
+    let (meta, body) = parse_prompt_file(&contents);
+    Ok(PromptFile {
+        name: name.to_string(),
+        body,
+        description: meta.description,
+        argument_hint: meta.argument_hint,
+    })
 }
 
 #[tauri::command]
-async fn get_workspace_sessions(
-    workspace_id: String,
+async fn prompt_render(
+    name: String,
+    args: Option<HashMap<String, String>>,
+    workspace_id: Option<String>,
+    include_context: Option<bool>,
     state: State<'_, AppState>,
-) -> Result<WorkspaceSessionStore, String> {
-    let workspaces = state.workspaces.lock().await;
-    let entry = workspaces
-        .get(&workspace_id)
-        .ok_or("workspace not found")?;
-    let path = workspace_sessions_path(&entry.path);
-    read_workspace_sessions(&path)
+) -> Result<String, AppError> {
+    let prompt = prompt_read(name).await?;
+    if let Err(err) = record_prompt_usage_event(&state, &prompt.name, workspace_id.clone()).await {
+        tracing::warn!("failed to record prompt usage: {err}");
+    }
+    let mut args = args.unwrap_or_default();
+
+    let mut context: Option<(String, Vec<String>)> = None;
+    if let Some(workspace_id) = workspace_id {
+        let entry = state
+            .workspaces
+            .lock()
+            .await
+            .get(&workspace_id)
+            .cloned()
+            .ok_or(AppError::WorkspaceNotFound)?;
+        context = workspace_git_context(&entry.path);
+        args.entry("workspace".to_string())
+            .or_insert_with(|| entry.name.clone());
+    }
+    if let Some((branch, files)) = context.as_ref() {
+        args.entry("branch".to_string())
+            .or_insert_with(|| branch.clone());
+        args.entry("changedFiles".to_string())
+            .or_insert_with(|| files.join("\n"));
+    }
+
+    let mut rendered = render_prompt_body(&prompt.body, &args);
+    if include_context.unwrap_or(false) {
+        if let Some((branch, files)) = context {
+            rendered.push_str(&format!("\n\nCurrent branch: {branch}"));
+            if !files.is_empty() {
+                rendered.push_str("\nChanged files:");
+                for file in files {
+                    rendered.push_str(&format!("\n- {file}"));
+                }
+            }
+        }
+    }
+    Ok(rendered.trim().to_string())
 }
 
 #[tauri::command]
-async fn save_workspace_sessions(
-    workspace_id: String,
-    sessions: WorkspaceSessionStore,
+async fn record_prompt_usage(
+    name: String,
+    workspace_id: Option<String>,
     state: State<'_, AppState>,
-) -> Result<WorkspaceSessionStore, String> {
-    let workspaces = state.workspaces.lock().await;
-    let entry = workspaces
-        .get(&workspace_id)
-        .ok_or("workspace not found")?;
-    let path = workspace_sessions_path(&entry.path);
-    let mut store = sessions;
-    if store.version == 0 {
-        store.version = default_session_store_version();
+) -> Result<(), AppError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("prompt name is empty".into());
     }
-    write_workspace_sessions(&path, &store)?;
-    Ok(store)
+    Ok(record_prompt_usage_event(&state, name, workspace_id).await?)
 }
 
 #[tauri::command]
-async fn save_attachment(
+async fn prompt_stats(state: State<'_, AppState>) -> Result<Vec<PromptStat>, AppError> {
+    let store = state.prompt_usage.lock().await;
+    Ok(summarize_prompt_usage(&store.events))
+}
+
+#[tauri::command]
+async fn search_files(
     workspace_id: String,
-    bytes: Vec<u8>,
-    name: Option<String>,
-    mime: Option<String>,
+    query: String,
+    limit: Option<usize>,
     state: State<'_, AppState>,
-) -> Result<Value, String> {
-    if bytes.is_empty() {
-        return Err("empty attachment".to_string());
+) -> Result<Vec<FileSearchMatch>, AppError> {
+    let trimmed = query.trim().to_string();
+    if trimmed.is_empty() {
+        return Ok(Vec::new());
     }
     let workspaces = state.workspaces.lock().await;
     let entry = workspaces
         .get(&workspace_id)
-        .ok_or("workspace not found")?;
-    let mut dir = PathBuf::from(&entry.path);
-    dir.push(".codex");
-    dir.push("attachments");
-    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+        .ok_or(AppError::WorkspaceNotFound)?
+        .clone();
+    drop(workspaces);
 
-    let name_ext = name
-        .as_deref()
-        .and_then(|value| Path::new(value).extension())
-        .and_then(|ext| ext.to_str())
-        .map(|ext| ext.to_lowercase());
-    let mime_ext = mime.as_deref().and_then(|value| match value {
-        "image/png" => Some("png"),
-        "image/jpeg" => Some("jpg"),
-        "image/jpg" => Some("jpg"),
-        "image/webp" => Some("webp"),
-        "image/gif" => Some("gif"),
-        "image/heic" => Some("heic"),
-        "image/heif" => Some("heif"),
-        "image/bmp" => Some("bmp"),
-        "image/tiff" => Some("tiff"),
-        _ => None,
-    });
-    let extension = name_ext
-        .as_deref()
-        .or(mime_ext)
-        .unwrap_or("img");
+    let files = workspace_file_list(&state, &entry).await?;
+    let limit = limit.unwrap_or(200);
+    let results = tokio::task::spawn_blocking(move || {
+        let mut matcher = FuzzyMatcher::new(&trimmed);
+        let mut matches: Vec<FileSearchMatch> = files
+            .iter()
+            .filter_map(|path| {
+                matcher.score(path).map(|(score, indices)| FileSearchMatch {
+                    path: path.clone(),
+                    score,
+                    indices,
+                })
+            })
+            .collect();
+        matches.sort_by(|a, b| {
+            b.score
+                .cmp(&a.score)
+                .then_with(|| a.path.len().cmp(&b.path.len()))
+                .then_with(|| a.path.cmp(&b.path))
+        });
+        matches.truncate(limit);
+        matches
+    })
+    .await
+    .map_err(|_| "search failed".to_string())?;
 
-    let filename = format!("{}.{}", Uuid::new_v4(), extension);
-    let mut path = dir.clone();
-    path.push(filename);
-    std::fs::write(&path, bytes).map_err(|e| e.to_string())?;
-    Ok(json!({ "path": path.to_string_lossy().to_string() }))
+    Ok(results)
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct WorkspaceFileContent {
+    path: String,
+    content: Option<String>,
+    encoding: String,
+    size: u64,
+    modified_ms: Option<i64>,
+    language: Option<String>,
+    is_binary: bool,
+    truncated: bool,
 }
 
 #[tauri::command]
-async fn send_user_message(
+async fn read_workspace_file(
     workspace_id: String,
-    thread_id: String,
-    text: String,
-    model: Option<String>,
-    effort: Option<String>,
-    access_mode: Option<String>,
-    attachments: Option<Vec<LocalImageInput>>,
+    path: String,
+    max_bytes: Option<u64>,
+    encoding_hint: Option<String>,
     state: State<'_, AppState>,
-) -> Result<Value, String> {
-    let sessions = state.sessions.lock().await;
-    let session = sessions
+) -> Result<WorkspaceFileContent, AppError> {
+    let entry = state
+        .workspaces
+        .lock()
+        .await
         .get(&workspace_id)
-        .ok_or("workspace not connected")?;
-    let access_mode = access_mode.unwrap_or_else(|| "current".to_string());
-    let sandbox_policy = match access_mode.as_str() {
-        "full-access" => json!({
-            "type": "dangerFullAccess"
-        }),
-        "read-only" => json!({
-            "type": "readOnly"
-        }),
-        _ => json!({
-            "type": "workspaceWrite",
-            "writableRoots": [session.entry.path],
-            "networkAccess": true
-        }),
-    };
-
-    let approval_policy = if access_mode == "full-access" {
-        "never"
-    } else {
-        "on-request"
-    };
+        .cloned()
+        .ok_or(AppError::WorkspaceNotFound)?;
+    let resolved = resolve_workspace_path(&entry.path, &path)?;
+    let max_bytes = max_bytes.unwrap_or(512 * 1024).max(1);
+    let encoding_hint = encoding_hint.unwrap_or_else(|| "utf-8".to_string());
+    let relative = path.clone();
+    tokio::task::spawn_blocking(move || {
+        use std::io::Read;
+        let metadata = fs::metadata(&resolved)?;
+        if !metadata.is_file() {
+            return Err("path is not a file".into());
+        }
+        let size = metadata.len();
+        let mut bytes = Vec::new();
+        fs::File::open(&resolved)?
+            .take(max_bytes)
+            .read_to_end(&mut bytes)?;
+        let truncated = size > bytes.len() as u64;
+        let is_binary = looks_binary(&bytes);
+        let (content, encoding) = match encoding_hint.to_lowercase().as_str() {
+            "base64" => {
+                use base64::Engine;
+                (
+                    Some(base64::engine::general_purpose::STANDARD.encode(&bytes)),
+                    "base64",
+                )
+            }
+            "latin1" | "iso-8859-1" if !is_binary => {
+                (Some(bytes.iter().map(|byte| *byte as char).collect()), "latin1")
+            }
+            _ if is_binary => (None, "binary"),
+            _ => (Some(String::from_utf8_lossy(&bytes).to_string()), "utf-8"),
+        };
+        Ok(WorkspaceFileContent {
+            path: normalize_path(Path::new(&relative)),
+            content,
+            encoding: encoding.to_string(),
+            size,
+            modified_ms: metadata.modified().ok().and_then(system_time_ms),
+            language: detect_language(&resolved).map(|value| value.to_string()),
+            is_binary,
+            truncated,
+        })
+    })
+    .await?
+}
 
-    let mut input: Vec<Value> = Vec::new();
-    if !text.trim().is_empty() {
-        input.push(json!({ "type": "text", "text": text }));
+fn reveal_path_command(path: &Path) -> Command {
+    #[cfg(target_os = "macos")]
+    {
+        let mut command = Command::new("open");
+        command.arg("-R").arg(path);
+        command
     }
-    if let Some(attachments) = attachments {
-        for attachment in attachments {
-            if !attachment.path.trim().is_empty() {
-                input.push(json!({ "type": "localImage", "path": attachment.path }));
-            }
-        }
+    #[cfg(target_os = "windows")]
+    {
+        let mut command = Command::new("explorer");
+        command.arg(format!("/select,{}", path.to_string_lossy()));
+        command
     }
-    if input.is_empty() {
-        return Err("empty input".to_string());
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    {
+        let target = if path.is_dir() {
+            path.to_path_buf()
+        } else {
+            path.parent().map(Path::to_path_buf).unwrap_or_else(|| path.to_path_buf())
+        };
+        let mut command = Command::new("xdg-open");
+        command.arg(target);
+        command
     }
-
-    let params = json!({
-        "threadId": thread_id,
-        "input": input,
-        "cwd": session.entry.path,
-        "approvalPolicy": approval_policy,
-        "sandboxPolicy": sandbox_policy,
-        "model": model,
-        "effort": effort,
-    });
-    session.send_request("turn/start", params).await
 }
 
 #[tauri::command]
-async fn cancel_turn(
+async fn resolve_absolute_path(
     workspace_id: String,
-    thread_id: String,
+    relative: String,
     state: State<'_, AppState>,
-) -> Result<Value, String> {
-    let sessions = state.sessions.lock().await;
-    let session = sessions
+) -> Result<String, AppError> {
+    let workspaces = state.workspaces.lock().await;
+    let entry = workspaces
         .get(&workspace_id)
-        .ok_or("workspace not connected")?;
-    let params = json!({
-        "threadId": thread_id,
-        "reason": "user_cancel"
-    });
-    session.send_request("turn/cancel", params).await
+        .ok_or(AppError::WorkspaceNotFound)?;
+    let resolved = resolve_workspace_path(&entry.path, &relative)?;
+    Ok(resolved.to_string_lossy().to_string())
 }
 
 #[tauri::command]
-async fn start_review(
+async fn reveal_in_file_manager(
     workspace_id: String,
-    thread_id: String,
-    target: Value,
-    delivery: Option<String>,
+    path: String,
     state: State<'_, AppState>,
-) -> Result<Value, String> {
-    let sessions = state.sessions.lock().await;
-    let session = sessions
-        .get(&workspace_id)
-        .ok_or("workspace not connected")?;
-    let mut params = Map::new();
-    params.insert("threadId".to_string(), json!(thread_id));
-    params.insert("target".to_string(), target);
-    if let Some(delivery) = delivery {
-        params.insert("delivery".to_string(), json!(delivery));
+) -> Result<(), AppError> {
+    let resolved = {
+        let workspaces = state.workspaces.lock().await;
+        let entry = workspaces
+            .get(&workspace_id)
+            .ok_or(AppError::WorkspaceNotFound)?;
+        resolve_workspace_path(&entry.path, &path)?
+    };
+    if !resolved.exists() {
+        return Err("path does not exist".into());
     }
-    session
-        .send_request("review/start", Value::Object(params))
-        .await
-}
-#[tauri::command]
-async fn model_list(
-    workspace_id: String,
-    state: State<'_, AppState>,
-) -> Result<Value, String> {
-    let sessions = state.sessions.lock().await;
-    let session = sessions
-        .get(&workspace_id)
-        .ok_or("workspace not connected")?;
-    let params = json!({});
-    session.send_request("model/list", params).await
+    let mut command = reveal_path_command(&resolved);
+    command.stdin(std::process::Stdio::null());
+    command.stdout(std::process::Stdio::null());
+    command.stderr(std::process::Stdio::null());
+    let mut child = command.spawn()?;
+    tauri::async_runtime::spawn(async move {
+        let _ = child.wait().await;
+    });
+    Ok(())
 }
 
 #[tauri::command]
-async fn skills_list(
+async fn rebuild_file_index(
     workspace_id: String,
     state: State<'_, AppState>,
-) -> Result<Value, String> {
-    let sessions = state.sessions.lock().await;
-    let session = sessions
+) -> Result<usize, AppError> {
+    let entry = state
+        .workspaces
+        .lock()
+        .await
         .get(&workspace_id)
-        .ok_or("workspace not connected")?;
-    let params = json!({
-        "cwd": session.entry.path
-    });
-    session.send_request("skills/list", params).await
+        .cloned()
+        .ok_or(AppError::WorkspaceNotFound)?;
+    let files = build_file_index(&state, &entry).await?;
+    Ok(files.len())
 }
 
 #[tauri::command]
-async fn prompts_list() -> Result<Vec<PromptListItem>, String> {
-    let Some(dir) = prompts_dir() else {
-        return Ok(Vec::new());
-    };
-    if !dir.exists() {
-        return Ok(Vec::new());
+async fn workspace_health(
+    workspace_id: String,
+    state: State<'_, AppState>,
+) -> Result<WorkspaceHealth, AppError> {
+    if !state.workspaces.lock().await.contains_key(&workspace_id) {
+        return Err(AppError::WorkspaceNotFound);
     }
-    let entries = fs::read_dir(&dir).map_err(|e| e.to_string())?;
-    let mut items: Vec<PromptListItem> = Vec::new();
-    for entry in entries {
-        let entry = match entry {
-            Ok(entry) => entry,
-            Err(_) => continue,
-        };
-        let path = entry.path();
-        if path.extension().and_then(|ext| ext.to_str()) != Some("md") {
-            continue;
-        }
-        let name = match path.file_stem().and_then(|stem| stem.to_str()) {
-            Some(value) if !value.trim().is_empty() => value.to_string(),
-            _ => continue,
-        };
-        let contents = match fs::read_to_string(&path) {
-            Ok(contents) => contents,
-            Err(_) => continue,
-        };
-        let (meta, _body) = parse_prompt_file(&contents);
-        items.push(PromptListItem {
-            name,
-            path: path.to_string_lossy().to_string(),
-            description: meta.description,
-            argument_hint: meta.argument_hint,
+    let requests = state
+        .sessions
+        .lock()
+        .await
+        .get(&workspace_id)
+        .map(|session| session.limiter.health());
+    let connected = requests.is_some();
+    let watching = state
+        .file_index_watchers
+        .lock()
+        .await
+        .get(&workspace_id)
+        .map(|handle| !handle.inner().is_finished())
+        .unwrap_or(false);
+    let now = now_ms();
+    let file_index = state
+        .file_indexes
+        .lock()
+        .await
+        .get(&workspace_id)
+        .map(|index| FileIndexHealth {
+            files: index.files.len(),
+            built_at_ms: index.built_at_ms,
+            age_ms: now - index.built_at_ms,
+            stale: index.stale,
+            watching,
         });
-    }
-    items.sort_by(|a, b| a.name.cmp(&b.name));
-    Ok(items)
+    Ok(WorkspaceHealth {
+        workspace_id,
+        connected,
+        file_index,
+        requests,
+    })
 }
 
 #[tauri::command]
-async fn prompt_read(name: String) -> Result<PromptFile, String> {
-    let name = name.trim();
-    if name.is_empty() {
-        return Err("prompt name is empty".to_string());
-    }
-    if name.contains('/') || name.contains('\\') {
-        return Err("invalid prompt name".to_string());
-    }
-    let dir = prompts_dir().ok_or("prompt directory unavailable")?;
-    let path = dir.join(format!("{name}.md"));
-    let contents = fs::read_to_string(&path).map_err(|e| e.to_string())?;
-    let (meta, body) = parse_prompt_file(&contents);
-    Ok(PromptFile {
-        name: name.to_string(),
-        body,
-        description: meta.description,
-        argument_hint: meta.argument_hint,
-    })
+async fn get_search_config(
+    workspace_id: String,
+    state: State<'_, AppState>,
+) -> Result<SearchConfig, AppError> {
+    let workspaces = state.workspaces.lock().await;
+    let entry = workspaces
+        .get(&workspace_id)
+        .ok_or(AppError::WorkspaceNotFound)?;
+    Ok(read_search_config(&workspace_search_config_path(&entry.path))?)
 }
 
 #[tauri::command]
-async fn search_files(
+async fn save_search_config(
     workspace_id: String,
-    query: String,
-    limit: Option<usize>,
+    config: SearchConfig,
     state: State<'_, AppState>,
-) -> Result<Vec<String>, String> {
-    let trimmed = query.trim().to_lowercase();
-    if trimmed.len() < 1 {
-        return Ok(Vec::new());
-    }
+) -> Result<SearchConfig, AppError> {
     let workspaces = state.workspaces.lock().await;
     let entry = workspaces
         .get(&workspace_id)
-        .ok_or("workspace not found")?
-        .clone();
+        .ok_or(AppError::WorkspaceNotFound)?;
+    let config = SearchConfig {
+        exclude: config
+            .exclude
+            .into_iter()
+            .map(|glob| glob.trim().to_string())
+            .filter(|glob| !glob.is_empty())
+            .collect(),
+        include: config
+            .include
+            .into_iter()
+            .map(|glob| glob.trim().to_string())
+            .filter(|glob| !glob.is_empty())
+            .collect(),
+    };
+    build_search_overrides(Path::new(&entry.path), &config)?;
+    write_search_config(&workspace_search_config_path(&entry.path), &config)?;
     drop(workspaces);
-
-    let root = PathBuf::from(entry.path);
-    let limit = limit.unwrap_or(200);
-    let max_scan = limit.saturating_mul(5).max(limit).max(200);
-    let results = tokio::task::spawn_blocking(move || {
-        let mut matches: Vec<String> = Vec::new();
-        let walker = WalkBuilder::new(&root)
-            .filter_entry(|entry| {
-                if entry.depth() == 0 {
-                    return true;
-                }
-                if entry
-                    .file_type()
-                    .map(|file_type| file_type.is_dir())
-                    .unwrap_or(false)
-                    && is_excluded_dir(entry.path())
-                {
-                    return false;
-                }
-                true
-            })
-            .build();
-
-        for entry in walker {
-            let entry = match entry {
-                Ok(value) => value,
-                Err(_) => continue,
-            };
-            if !entry
-                .file_type()
-                .map(|file_type| file_type.is_file())
-                .unwrap_or(false)
-            {
-                continue;
-            }
-            let relative = match entry.path().strip_prefix(&root) {
-                Ok(value) => value,
-                Err(_) => continue,
-            };
-            let relative_string = normalize_path(relative);
-            let lower = relative_string.to_lowercase();
-            if !lower.contains(&trimmed) {
-                continue;
-            }
-            matches.push(relative_string);
-            if matches.len() >= max_scan {
-                break;
-            }
-        }
-
-        matches.sort_by(|a, b| {
-            let a_lower = a.to_lowercase();
-            let b_lower = b.to_lowercase();
-            let a_starts = a_lower.starts_with(&trimmed);
-            let b_starts = b_lower.starts_with(&trimmed);
-            if a_starts && !b_starts {
-                return std::cmp::Ordering::Less;
-            }
-            if !a_starts && b_starts {
-                return std::cmp::Ordering::Greater;
-            }
-            a_lower.cmp(&b_lower)
-        });
-        matches.truncate(limit);
-        Ok::<Vec<String>, String>(matches)
-    })
-    .await
-    .map_err(|_| "search failed".to_string())??;
-
-    Ok(results)
+    mark_file_index_stale(&state, &workspace_id).await;
+    Ok(config)
 }
 
 #[tauri::command]
@@ -1780,12 +5858,14 @@ async fn respond_to_server_request(
     request_id: u64,
     result: Value,
     state: State<'_, AppState>,
-) -> Result<(), String> {
-    let sessions = state.sessions.lock().await;
-    let session = sessions
-        .get(&workspace_id)
-        .ok_or("workspace not connected")?;
-    session.send_response(request_id, result).await
+    app: AppHandle,
+) -> Result<(), AppError> {
+    state.deferrals.touch();
+    let session = connected_session(&state, &workspace_id).await?;
+    session.send_response(request_id, result.clone()).await?;
+    audit::record_decision(&app, &workspace_id, request_id, &result).await;
+    thread_state::approval_resolved(&app, &workspace_id, request_id).await;
+    Ok(())
 }
 
 #[tauri::command]
@@ -1793,17 +5873,35 @@ async fn connect_workspace(
     id: String,
     state: State<'_, AppState>,
     app: AppHandle,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
     let entry = {
         let workspaces = state.workspaces.lock().await;
         workspaces
             .get(&id)
             .cloned()
-            .ok_or("workspace not found")?
+            .ok_or(AppError::WorkspaceNotFound)?
     };
+    connect_entry(entry, &state, app).await
+}
 
-    let session = spawn_workspace_session(entry.clone(), app).await?;
-    state.sessions.lock().await.insert(entry.id, session);
+async fn connect_entry(
+    entry: WorkspaceEntry,
+    state: &AppState,
+    app: AppHandle,
+) -> Result<(), AppError> {
+    let session = spawn_workspace_session(entry.clone(), app.clone()).await?;
+    if session.codex_version.is_some() && session.codex_version != entry.codex_version {
+        let mut workspaces = state.workspaces.lock().await;
+        if let Some(stored) = workspaces.get_mut(&entry.id) {
+            stored.codex_version = session.codex_version.clone();
+        }
+        let list: Vec<_> = workspaces.values().cloned().collect();
+        write_workspaces(&state.storage_path, &list)?;
+    }
+    state.sessions.lock().await.insert(entry.id.clone(), session);
+    tauri::async_runtime::spawn(async move {
+        deliver_outbox(&app, &entry.id).await;
+    });
     Ok(())
 }
 
@@ -1811,14 +5909,14 @@ async fn connect_workspace(
 async fn get_git_status(
     workspace_id: String,
     state: State<'_, AppState>,
-) -> Result<serde_json::Value, String> {
+) -> Result<serde_json::Value, AppError> {
     let workspaces = state.workspaces.lock().await;
     let entry = workspaces
         .get(&workspace_id)
-        .ok_or("workspace not found")?
+        .ok_or(AppError::WorkspaceNotFound)?
         .clone();
 
-    let repo = Repository::open(&entry.path).map_err(|e| e.to_string())?;
+    let repo = open_repository(&entry.path)?;
 
     let branch_name = repo
         .head()
@@ -1835,8 +5933,7 @@ async fn get_git_status(
         .include_ignored(false);
 
     let statuses = repo
-        .statuses(Some(&mut status_options))
-        .map_err(|e| e.to_string())?;
+        .statuses(Some(&mut status_options))?;
 
     let head_tree = repo.head().ok().and_then(|head| head.peel_to_tree().ok());
 
@@ -1883,8 +5980,7 @@ async fn get_git_status(
             path,
             include_index,
             include_workdir,
-        )
-        .map_err(|e| e.to_string())?;
+        )?;
         total_additions += additions;
         total_deletions += deletions;
         files.push(GitFileStatus {
@@ -1894,83 +5990,533 @@ async fn get_git_status(
             deletions,
         });
     }
-
-    Ok(json!({
-        "branchName": branch_name,
-        "files": files,
-        "totalAdditions": total_additions,
-        "totalDeletions": total_deletions,
-    }))
+
+    Ok(json!({
+        "branchName": branch_name,
+        "files": files,
+        "totalAdditions": total_additions,
+        "totalDeletions": total_deletions,
+    }))
+}
+
+#[tauri::command]
+async fn get_git_diffs(
+    workspace_id: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<GitFileDiff>, AppError> {
+    let workspaces = state.workspaces.lock().await;
+    let entry = workspaces
+        .get(&workspace_id)
+        .ok_or(AppError::WorkspaceNotFound)?
+        .clone();
+
+    let max_bytes = state.settings.lock().await.diff_max_bytes;
+    let repo = open_repository(&entry.path)?;
+    let diff = worktree_diff(&repo, None)?;
+
+    Ok(collect_file_diffs(&repo, &diff, max_bytes))
+}
+
+async fn workspace_entry(
+    state: &AppState,
+    workspace_id: &str,
+) -> Result<WorkspaceEntry, AppError> {
+    state
+        .workspaces
+        .lock()
+        .await
+        .get(workspace_id)
+        .cloned()
+        .ok_or(AppError::WorkspaceNotFound)
+}
+
+// Clones the session out so the global lock is never held across an RPC; the
+// reader task that resolves responses also needs it.
+async fn connected_session(
+    state: &AppState,
+    workspace_id: &str,
+) -> Result<Arc<WorkspaceSession>, AppError> {
+    state
+        .sessions
+        .lock()
+        .await
+        .get(workspace_id)
+        .cloned()
+        .ok_or(AppError::NotConnected)
+}
+
+#[tauri::command]
+async fn git_fetch(
+    workspace_id: String,
+    remote: Option<String>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<String, AppError> {
+    let entry = workspace_entry(&state, &workspace_id).await?;
+    let mut args = vec!["fetch".to_string(), "--progress".to_string(), "--prune".to_string()];
+    match remote.filter(|value| !value.trim().is_empty()) {
+        Some(remote) if remote.trim_start().starts_with('-') => {
+            return Err(format!("Invalid remote name: {remote}").into());
+        }
+        Some(remote) => args.push(remote),
+        None => args.push("--all".to_string()),
+    }
+    Ok(run_git_remote_command(&app, &workspace_id, &entry.path, "fetch", &args).await?)
+}
+
+#[tauri::command]
+async fn git_pull(
+    workspace_id: String,
+    rebase: bool,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<String, AppError> {
+    let entry = workspace_entry(&state, &workspace_id).await?;
+    observer::ensure_writable(&entry, "git pull")?;
+    let mode = if rebase { "--rebase" } else { "--no-rebase" };
+    let args = vec!["pull".to_string(), "--progress".to_string(), mode.to_string()];
+    Ok(run_git_remote_command(&app, &workspace_id, &entry.path, "pull", &args).await?)
+}
+
+#[tauri::command]
+async fn git_push(
+    workspace_id: String,
+    set_upstream: bool,
+    force_with_lease: bool,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<String, AppError> {
+    let entry = workspace_entry(&state, &workspace_id).await?;
+    observer::ensure_writable(&entry, "git push")?;
+    let mut args = vec!["push".to_string(), "--progress".to_string()];
+    if force_with_lease {
+        args.push("--force-with-lease".to_string());
+    }
+    if set_upstream {
+        args.push("--set-upstream".to_string());
+        args.push(default_remote_name(&entry.path)?);
+        args.push("HEAD".to_string());
+    }
+    Ok(run_git_remote_command(&app, &workspace_id, &entry.path, "push", &args).await?)
+}
+
+#[tauri::command]
+async fn create_pull_request(
+    workspace_id: String,
+    title: Option<String>,
+    body: Option<String>,
+    base: Option<String>,
+    draft: bool,
+    thread_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<String, AppError> {
+    let entry = workspace_entry(&state, &workspace_id).await?;
+    observer::ensure_writable(&entry, "creating pull requests")?;
+    let token = state
+        .settings
+        .lock()
+        .await
+        .git_hosting_token
+        .clone()
+        .filter(|value| !value.trim().is_empty());
+
+    let (branch, remote_url, last_summary) = {
+        let repo = open_repository(&entry.path)?;
+        let head = repo.head()?;
+        let branch = head
+            .shorthand()
+            .filter(|_| head.is_branch())
+            .map(|name| name.to_string())
+            .ok_or("HEAD is detached; check out a branch first")?;
+        let last_summary = head
+            .peel_to_commit()
+            .ok()
+            .and_then(|commit| commit.summary().map(|value| value.to_string()));
+        let remote_name = default_remote_name(&entry.path)?;
+        let remote_url = repo
+            .find_remote(&remote_name)
+            .ok()
+            .and_then(|remote| remote.url().map(|url| url.to_string()))
+            .unwrap_or_default();
+        (branch, remote_url, last_summary)
+    };
+
+    let title = title
+        .filter(|value| !value.trim().is_empty())
+        .or(last_summary)
+        .ok_or("pull request title is required")?;
+    let body = match body.filter(|value| !value.trim().is_empty()) {
+        Some(body) => body,
+        None => thread_id
+            .as_deref()
+            .and_then(|thread_id| thread_summary_body(&entry, thread_id))
+            .unwrap_or_default(),
+    };
+    let base = base.filter(|value| !value.trim().is_empty());
+
+    let is_gitlab = remote_url.to_ascii_lowercase().contains("gitlab");
+    let mut command = if is_gitlab {
+        let mut command = platform::command("glab");
+        command.args([
+            "mr",
+            "create",
+            "--yes",
+            "--title",
+            &title,
+            "--description",
+            &body,
+            "--source-branch",
+            &branch,
+        ]);
+        if let Some(base) = base.as_ref() {
+            command.args(["--target-branch", base]);
+        }
+        if let Some(token) = token.as_ref() {
+            command.env("GITLAB_TOKEN", token);
+        }
+        command
+    } else {
+        let mut command = platform::command("gh");
+        command.args(["pr", "create", "--title", &title, "--body", &body, "--head", &branch]);
+        if let Some(base) = base.as_ref() {
+            command.args(["--base", base]);
+        }
+        if let Some(token) = token.as_ref() {
+            command.env("GH_TOKEN", token);
+        }
+        command
+    };
+    if draft {
+        command.arg("--draft");
+    }
+    command
+        .current_dir(&entry.path)
+        .env("GH_PROMPT_DISABLED", "1")
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .stdin(std::process::Stdio::null());
+
+    let output = command.output().await.map_err(|e| AppError::IoError {
+        message: format!("failed to run {}: {e}", if is_gitlab { "glab" } else { "gh" }),
+    })?;
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+    if !output.status.success() {
+        let message = stderr.trim();
+        return Err(if message.is_empty() {
+            "failed to create pull request".into()
+        } else {
+            message.into()
+        });
+    }
+    last_url(&stdout)
+        .or_else(|| last_url(&stderr))
+        .ok_or_else(|| "pull request created but no URL was returned".into())
+}
+
+#[tauri::command]
+async fn git_conflicts(
+    workspace_id: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<GitConflict>, AppError> {
+    let entry = workspace_entry(&state, &workspace_id).await?;
+    let repo = open_repository(&entry.path)?;
+    Ok(list_git_conflicts(&repo)?)
+}
+
+#[tauri::command]
+async fn git_resolve(
+    workspace_id: String,
+    path: String,
+    resolution: ConflictResolution,
+    state: State<'_, AppState>,
+) -> Result<Vec<GitConflict>, AppError> {
+    let entry = workspace_entry(&state, &workspace_id).await?;
+    observer::ensure_writable(&entry, "conflict resolution")?;
+    let repo = open_repository(&entry.path)?;
+    let target = resolve_workspace_path(&entry.path, &path)?;
+    let relative = normalize_git_path(path.trim());
+    let mut index = repo.index()?;
+    let conflict = index
+        .conflicts()?
+        .filter_map(|conflict| conflict.ok())
+        .find(|conflict| conflict_entry_path(conflict).as_deref() == Some(relative.as_str()))
+        .ok_or("path is not in conflict")?;
+
+    let contents = match resolution {
+        ConflictResolution::Content { content } => Some(content.into_bytes()),
+        ConflictResolution::Ours | ConflictResolution::Theirs => {
+            let side = if matches!(resolution, ConflictResolution::Ours) {
+                conflict.our.as_ref()
+            } else {
+                conflict.their.as_ref()
+            };
+            match side {
+                Some(side) => Some(
+                    repo.find_blob(side.id)?
+                        .content()
+                        .to_vec(),
+                ),
+                None => None,
+            }
+        }
+    };
+
+    let relative_path = Path::new(&relative);
+    match contents {
+        Some(contents) => {
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(&target, contents)?;
+            index
+                .remove_path(relative_path)
+                .and_then(|_| index.add_path(relative_path))?;
+        }
+        None => {
+            if target.exists() {
+                fs::remove_file(&target)?;
+            }
+            index.remove_path(relative_path)?;
+        }
+    }
+    index.write()?;
+    Ok(list_git_conflicts(&repo)?)
+}
+
+#[tauri::command]
+async fn git_blame(
+    workspace_id: String,
+    path: String,
+    range: Option<(u32, u32)>,
+    state: State<'_, AppState>,
+) -> Result<Vec<BlameLine>, AppError> {
+    let entry = workspace_entry(&state, &workspace_id).await?;
+    let target = resolve_workspace_path(&entry.path, &path)?;
+    let contents = fs::read(&target)?;
+    let text = String::from_utf8_lossy(&contents).to_string();
+    let relative = normalize_git_path(path.trim());
+
+    let repo = open_repository(&entry.path)?;
+    let file_blame = repo.blame_file(Path::new(&relative), None).ok();
+    let blame = file_blame
+        .as_ref()
+        .and_then(|blame| blame.blame_buffer(&contents).ok());
+
+    let (start, end) = match range {
+        Some((start, end)) => (start.max(1) as usize, end.max(start).max(1) as usize),
+        None => (1, usize::MAX),
+    };
+    let mut summaries: HashMap<git2::Oid, Option<String>> = HashMap::new();
+    let mut lines = Vec::new();
+    for (index, content) in text.lines().enumerate() {
+        let line = index + 1;
+        if line < start {
+            continue;
+        }
+        if line > end {
+            break;
+        }
+        let hunk = blame.as_ref().and_then(|blame| blame.get_line(line));
+        let Some(hunk) = hunk.filter(|hunk| !hunk.final_commit_id().is_zero()) else {
+            lines.push(BlameLine {
+                line,
+                content: content.to_string(),
+                commit: None,
+                author: None,
+                email: None,
+                timestamp: None,
+                summary: None,
+                uncommitted: true,
+            });
+            continue;
+        };
+        let oid = hunk.final_commit_id();
+        let signature = hunk.final_signature();
+        let summary = summaries
+            .entry(oid)
+            .or_insert_with(|| {
+                repo.find_commit(oid)
+                    .ok()
+                    .and_then(|commit| commit.summary().map(|value| value.to_string()))
+            })
+            .clone();
+        lines.push(BlameLine {
+            line,
+            content: content.to_string(),
+            commit: Some(oid.to_string()),
+            author: signature.name().map(|value| value.to_string()),
+            email: signature.email().map(|value| value.to_string()),
+            timestamp: Some(signature.when().seconds()),
+            summary,
+            uncommitted: false,
+        });
+    }
+    Ok(lines)
+}
+
+#[tauri::command]
+async fn list_checkpoints(
+    workspace_id: String,
+    thread_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<Vec<Checkpoint>, AppError> {
+    let entry = workspace_entry(&state, &workspace_id).await?;
+    let store = read_checkpoints(&workspace_checkpoints_path(&entry.path))?;
+    let mut checkpoints: Vec<Checkpoint> = store
+        .checkpoints
+        .into_iter()
+        .filter(|checkpoint| {
+            thread_id
+                .as_ref()
+                .map(|thread_id| &checkpoint.thread_id == thread_id)
+                .unwrap_or(true)
+        })
+        .collect();
+    checkpoints.sort_by_key(|checkpoint| std::cmp::Reverse(checkpoint.created_at_ms));
+    Ok(checkpoints)
+}
+
+#[tauri::command]
+async fn restore_checkpoint(
+    checkpoint_id: String,
+    state: State<'_, AppState>,
+) -> Result<Checkpoint, AppError> {
+    let entries: Vec<WorkspaceEntry> = state.workspaces.lock().await.values().cloned().collect();
+    for entry in entries {
+        let store = read_checkpoints(&workspace_checkpoints_path(&entry.path)).unwrap_or_default();
+        let Some(checkpoint) = store
+            .checkpoints
+            .into_iter()
+            .find(|checkpoint| checkpoint.id == checkpoint_id)
+        else {
+            continue;
+        };
+        observer::ensure_writable(&entry, "restoring checkpoints")?;
+        let path = entry.path.clone();
+        let restored = checkpoint.clone();
+        tokio::task::spawn_blocking(move || restore_worktree_checkpoint(&path, &restored))
+            .await??;
+        mark_file_index_stale(&state, &entry.id).await;
+        return Ok(checkpoint);
+    }
+    Err("checkpoint not found".into())
 }
 
 #[tauri::command]
-async fn get_git_diffs(
+async fn get_full_diff(
     workspace_id: String,
+    path: String,
     state: State<'_, AppState>,
-) -> Result<Vec<GitFileDiff>, String> {
-    let workspaces = state.workspaces.lock().await;
-    let entry = workspaces
+) -> Result<Option<GitFileDiff>, AppError> {
+    let entry = state
+        .workspaces
+        .lock()
+        .await
         .get(&workspace_id)
-        .ok_or("workspace not found")?
-        .clone();
+        .cloned()
+        .ok_or(AppError::WorkspaceNotFound)?;
 
-    let repo = Repository::open(&entry.path).map_err(|e| e.to_string())?;
-    let head_tree = repo
-        .head()
-        .ok()
-        .and_then(|head| head.peel_to_tree().ok());
+    let repo = open_repository(&entry.path)?;
+    let diff = worktree_diff(&repo, Some(&path))?;
+    Ok(collect_file_diffs(&repo, &diff, 0).into_iter().next())
+}
+
+#[tauri::command]
+async fn get_git_diff_range(
+    workspace_id: String,
+    base: String,
+    head: Option<String>,
+    path: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<Vec<GitFileDiff>, AppError> {
+    let entry = state
+        .workspaces
+        .lock()
+        .await
+        .get(&workspace_id)
+        .cloned()
+        .ok_or(AppError::WorkspaceNotFound)?;
+
+    let max_bytes = state.settings.lock().await.diff_max_bytes;
+    let repo = open_repository(&entry.path)?;
+    let head_commit = match head.as_deref().map(str::trim).filter(|value| !value.is_empty()) {
+        Some(head) => Some(
+            repo.revparse_single(head)
+                .and_then(|object| object.peel_to_commit())?,
+        ),
+        None => None,
+    };
+    let base_tree = resolve_diff_base(&repo, &base, head_commit.as_ref())?;
 
     let mut options = DiffOptions::new();
-    options
-        .include_untracked(true)
-        .recurse_untracked_dirs(true)
-        .show_untracked_content(true);
+    if let Some(path) = path.as_deref().filter(|value| !value.trim().is_empty()) {
+        options.pathspec(path).disable_pathspec_match(true);
+    }
+    let diff = match head_commit.as_ref() {
+        Some(commit) => {
+            let head_tree = commit.tree()?;
+            repo.diff_tree_to_tree(Some(&base_tree), Some(&head_tree), Some(&mut options))
+        }
+        None => {
+            options
+                .include_untracked(true)
+                .recurse_untracked_dirs(true)
+                .show_untracked_content(true);
+            repo.diff_tree_to_workdir_with_index(Some(&base_tree), Some(&mut options))
+        }
+    }?;
 
-    let diff = match head_tree.as_ref() {
-        Some(tree) => repo
-            .diff_tree_to_workdir_with_index(Some(tree), Some(&mut options))
-            .map_err(|e| e.to_string())?,
-        None => repo
-            .diff_tree_to_workdir_with_index(None, Some(&mut options))
-            .map_err(|e| e.to_string())?,
-    };
+    Ok(collect_file_diffs(&repo, &diff, max_bytes))
+}
 
-    let mut results = Vec::new();
+#[tauri::command]
+async fn get_git_file_diff_structured(
+    workspace_id: String,
+    path: String,
+    state: State<'_, AppState>,
+) -> Result<Option<StructuredFileDiff>, AppError> {
+    let entry = state
+        .workspaces
+        .lock()
+        .await
+        .get(&workspace_id)
+        .cloned()
+        .ok_or(AppError::WorkspaceNotFound)?;
+
+    let repo = open_repository(&entry.path)?;
+    let diff = worktree_diff(&repo, Some(&path))?;
     for (index, delta) in diff.deltas().enumerate() {
-        let path = delta
-            .new_file()
-            .path()
-            .or_else(|| delta.old_file().path());
-        let Some(path) = path else {
+        let Some(new_path) = delta.new_file().path().or_else(|| delta.old_file().path()) else {
             continue;
         };
-        let patch = match git2::Patch::from_diff(&diff, index) {
-            Ok(patch) => patch,
-            Err(_) => continue,
-        };
-        let Some(mut patch) = patch else {
+        let new_path = normalize_git_path(new_path.to_string_lossy().as_ref());
+        let old_path = delta
+            .old_file()
+            .path()
+            .map(|old| normalize_git_path(old.to_string_lossy().as_ref()))
+            .filter(|old| old != &new_path);
+        let Some(patch) = git2::Patch::from_diff(&diff, index)? else {
             continue;
         };
-        let content = match diff_patch_to_string(&mut patch) {
-            Ok(content) => content,
-            Err(_) => continue,
-        };
-        if content.trim().is_empty() {
-            continue;
-        }
-        results.push(GitFileDiff {
-            path: normalize_git_path(path.to_string_lossy().as_ref()),
-            diff: content,
-        });
+        let (hunks, additions, deletions) =
+            structured_patch(&patch)?;
+        return Ok(Some(StructuredFileDiff {
+            path: new_path,
+            old_path,
+            status: delta_status_label(delta.status()).to_string(),
+            additions,
+            deletions,
+            hunks,
+        }));
     }
-
-    Ok(results)
+    Ok(None)
 }
 
 #[cfg(target_os = "macos")]
 fn insert_preferences_menu_item<R: tauri::Runtime>(
     app: &AppHandle<R>,
     menu: &Menu<R>,
+    lang: &str,
 ) -> tauri::Result<()> {
     let app_name = app.package_info().name.clone();
     let submenu = menu.items()?.into_iter().find_map(|item| match item {
@@ -1981,8 +6527,9 @@ fn insert_preferences_menu_item<R: tauri::Runtime>(
         _ => None,
     });
     if let Some(submenu) = submenu {
+        let label = i18n::t(lang, "menu.preferences");
         let preferences_item =
-            MenuItem::with_id(app, "preferences", "Preferences...", true, Some("CmdOrCtrl+,"))?;
+            MenuItem::with_id(app, "preferences", label, true, Some("CmdOrCtrl+,"))?;
         submenu.insert(&preferences_item, 1)?;
         let items = submenu.items()?;
         let mut quit_index = None;
@@ -2099,6 +6646,12 @@ fn is_executable_path(path: &Path) -> bool {
             return false;
         }
     }
+    #[cfg(windows)]
+    {
+        if !platform::has_windows_executable_extension(path) {
+            return false;
+        }
+    }
     true
 }
 
@@ -2122,7 +6675,10 @@ fn shebang_requires_node(line: &str) -> bool {
 }
 
 fn resolve_binary_path(raw: &str) -> PathBuf {
-    fs::canonicalize(raw).unwrap_or_else(|_| PathBuf::from(raw))
+    let resolved = platform::resolve_windows_shim(raw).unwrap_or_else(|| PathBuf::from(raw));
+    fs::canonicalize(&resolved)
+        .map(|path| platform::simplify_path(&path))
+        .unwrap_or(resolved)
 }
 
 fn suggest_node_path(codex_path: &Path) -> Option<PathBuf> {
@@ -2135,7 +6691,7 @@ fn suggest_node_path(codex_path: &Path) -> Option<PathBuf> {
 }
 
 #[tauri::command]
-async fn get_settings(state: State<'_, AppState>) -> Result<AppSettings, String> {
+async fn get_settings(state: State<'_, AppState>) -> Result<AppSettings, AppError> {
     Ok(state.settings.lock().await.clone())
 }
 
@@ -2144,22 +6700,45 @@ async fn update_settings(
     app: AppHandle,
     state: State<'_, AppState>,
     settings: AppSettings,
-) -> Result<AppSettings, String> {
-    {
+) -> Result<AppSettings, AppError> {
+    logging::apply_log_level(&settings.log_level)?;
+    keymap::validate(&settings.keymap)?;
+    let locale_changed = {
         let mut guard = state.settings.lock().await;
+        let locale_changed = guard.locale != settings.locale;
         *guard = settings.clone();
         write_settings(&state.settings_path, &settings)?;
-    }
+        locale_changed
+    };
     let _ = app.emit("settings-updated", settings.clone());
+    let lang = i18n::lang_for(settings.locale.as_deref());
+    if locale_changed {
+        menu::rebuild(&app).await;
+    } else {
+        keymap::apply(&app, &settings.keymap);
+    }
+    if let Err(err) = tray::sync(&app, settings.quit_behavior, lang) {
+        tracing::warn!("failed to update tray icon: {err}");
+    }
     restart_usage_polling(&app).await;
+    http_api::sync(&app).await;
+    badge::refresh(&app).await;
+    state.analytics.set_enabled(settings.local_analytics_enabled);
+    if settings.login_shell_env_enabled {
+        let app_handle = app.clone();
+        tauri::async_runtime::spawn(async move {
+            let state = app_handle.state::<AppState>();
+            state.shell_env.vars().await;
+        });
+    }
     Ok(settings)
 }
 
 #[tauri::command]
-async fn inspect_codex_bin(path: String) -> Result<CodexBinInspection, String> {
+async fn inspect_codex_bin(path: String) -> Result<CodexBinInspection, AppError> {
     let trimmed = path.trim();
     if trimmed.is_empty() {
-        return Err("Codex binary path is required.".to_string());
+        return Err("Codex binary path is required.".into());
     }
     let sanitized = trimmed.trim_matches('"').trim_matches('\'');
     let resolved_path = resolve_binary_path(sanitized);
@@ -2180,31 +6759,499 @@ async fn inspect_codex_bin(path: String) -> Result<CodexBinInspection, String> {
     })
 }
 
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct CodexBinaryCandidate {
+    path: String,
+    source: String,
+    version: Option<String>,
+    supported: Option<bool>,
+    requires_node: bool,
+    error: Option<String>,
+}
+
+fn user_home_dir() -> Option<PathBuf> {
+    env::var_os("HOME")
+        .or_else(|| env::var_os("USERPROFILE"))
+        .map(PathBuf::from)
+}
+
+fn codex_binary_names() -> &'static [&'static str] {
+    if cfg!(windows) {
+        &["codex.cmd", "codex.exe", "codex.ps1", "codex"]
+    } else {
+        &["codex"]
+    }
+}
+
+async fn login_shell_path() -> Option<String> {
+    if cfg!(windows) {
+        return None;
+    }
+    let shell = env::var("SHELL").unwrap_or_else(|_| "/bin/sh".to_string());
+    let mut command = platform::command(shell);
+    command
+        .args(["-lc", "printf %s \"$PATH\""])
+        .stdin(std::process::Stdio::null())
+        .kill_on_drop(true);
+    let output = tokio::time::timeout(Duration::from_secs(5), command.output())
+        .await
+        .ok()?
+        .ok()?;
+    let path = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (!path.is_empty()).then_some(path)
+}
+
+async fn npm_global_bin() -> Option<PathBuf> {
+    let npm = if cfg!(windows) { "npm.cmd" } else { "npm" };
+    let mut command = platform::command(npm);
+    command
+        .args(["config", "get", "prefix"])
+        .stdin(std::process::Stdio::null())
+        .kill_on_drop(true);
+    let output = tokio::time::timeout(Duration::from_secs(5), command.output())
+        .await
+        .ok()?
+        .ok()?;
+    let prefix = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if prefix.is_empty() || !output.status.success() {
+        return None;
+    }
+    let prefix = PathBuf::from(prefix);
+    Some(if cfg!(windows) { prefix } else { prefix.join("bin") })
+}
+
+fn glob_dirs(pattern: &Path) -> Vec<PathBuf> {
+    glob::glob(&pattern.to_string_lossy())
+        .map(|paths| paths.flatten().collect())
+        .unwrap_or_default()
+}
+
+async fn codex_search_dirs() -> Vec<(PathBuf, &'static str)> {
+    let mut dirs: Vec<(PathBuf, &'static str)> = Vec::new();
+    if let Some(path) = env::var_os("PATH") {
+        dirs.extend(env::split_paths(&path).map(|dir| (dir, "path")));
+    }
+    if let Some(path) = login_shell_path().await {
+        dirs.extend(env::split_paths(&path).map(|dir| (dir, "login-shell")));
+    }
+    for dir in ["/opt/homebrew/bin", "/usr/local/bin", "/home/linuxbrew/.linuxbrew/bin"] {
+        dirs.push((PathBuf::from(dir), "homebrew"));
+    }
+    let home = user_home_dir();
+    let nvm_dir = env::var_os("NVM_DIR")
+        .map(PathBuf::from)
+        .or_else(|| home.as_ref().map(|home| home.join(".nvm")));
+    if let Some(nvm_dir) = nvm_dir {
+        let pattern = nvm_dir.join("versions").join("node").join("*").join("bin");
+        dirs.extend(glob_dirs(&pattern).into_iter().map(|dir| (dir, "nvm")));
+    }
+    let mut fnm_roots: Vec<PathBuf> =
+        env::var_os("FNM_DIR").map(PathBuf::from).into_iter().collect();
+    if let Some(home) = home.as_ref() {
+        fnm_roots.push(home.join(".fnm"));
+        fnm_roots.push(home.join(".local").join("share").join("fnm"));
+        fnm_roots.push(home.join("Library").join("Application Support").join("fnm"));
+    }
+    if let Some(app_data) = env::var_os("APPDATA") {
+        fnm_roots.push(PathBuf::from(app_data).join("fnm"));
+    }
+    for root in fnm_roots {
+        let pattern = root.join("node-versions").join("*").join("installation");
+        for dir in glob_dirs(&pattern) {
+            dirs.push((dir.join("bin"), "fnm"));
+            dirs.push((dir, "fnm"));
+        }
+    }
+    let volta_home = env::var_os("VOLTA_HOME")
+        .map(PathBuf::from)
+        .or_else(|| home.as_ref().map(|home| home.join(".volta")));
+    if let Some(volta_home) = volta_home {
+        dirs.push((volta_home.join("bin"), "volta"));
+    }
+    if let Some(dir) = npm_global_bin().await {
+        dirs.push((dir, "npm"));
+    }
+    if let Some(home) = home.as_ref() {
+        dirs.push((home.join(".npm-global").join("bin"), "npm"));
+        dirs.push((home.join(".bun").join("bin"), "bun"));
+        dirs.push((home.join(".cargo").join("bin"), "cargo"));
+        dirs.push((home.join(".local").join("bin"), "local"));
+    }
+    if let Some(app_data) = env::var_os("APPDATA") {
+        dirs.push((PathBuf::from(app_data).join("npm"), "npm"));
+    }
+    dirs
+}
+
+#[tauri::command]
+async fn discover_codex_binaries(
+    state: State<'_, AppState>,
+) -> Result<Vec<CodexBinaryCandidate>, AppError> {
+    let settings = state.settings.lock().await.clone();
+    let env = shell_env::spawn_env(&state, &settings).await;
+    let mut seen: Vec<PathBuf> = Vec::new();
+    let mut found: Vec<(PathBuf, &'static str)> = Vec::new();
+    for (dir, source) in codex_search_dirs().await {
+        for name in codex_binary_names() {
+            let candidate = dir.join(name);
+            if !is_executable_path(&candidate) {
+                continue;
+            }
+            let resolved = resolve_binary_path(&candidate.to_string_lossy());
+            if seen.contains(&resolved) {
+                continue;
+            }
+            seen.push(resolved);
+            found.push((candidate, source));
+        }
+    }
+
+    let mut handles = Vec::new();
+    for (path, source) in found {
+        let settings = settings.clone();
+        let env = env.clone();
+        handles.push(tokio::spawn(async move {
+            let path_string = path.to_string_lossy().to_string();
+            let requires_node = read_first_line(&resolve_binary_path(&path_string))
+                .ok()
+                .flatten()
+                .map(|line| shebang_requires_node(&line))
+                .unwrap_or(false);
+            let (version, supported, error) =
+                match codex_version(&path_string, &settings, env.as_ref()).await {
+                    Ok(info) => (info.version, Some(info.supported), None),
+                    Err(err) => (None, None, Some(err)),
+                };
+            CodexBinaryCandidate {
+                path: path_string,
+                source: source.to_string(),
+                version,
+                supported,
+                requires_node,
+                error,
+            }
+        }));
+    }
+    let mut candidates = Vec::new();
+    for handle in handles {
+        candidates.push(handle.await?);
+    }
+    candidates.sort_by(|a, b| {
+        let version = |candidate: &CodexBinaryCandidate| {
+            candidate
+                .version
+                .as_deref()
+                .and_then(|version| semver::Version::parse(version).ok())
+        };
+        b.supported
+            .unwrap_or(false)
+            .cmp(&a.supported.unwrap_or(false))
+            .then_with(|| version(b).cmp(&version(a)))
+    });
+    Ok(candidates)
+}
+
 #[tauri::command]
-async fn validate_codex_bin(path: String) -> Result<(), String> {
+async fn validate_codex_bin(path: String) -> Result<(), AppError> {
     let trimmed = path.trim();
     if trimmed.is_empty() {
-        return Err("Codex binary path is required.".to_string());
+        return Err("Codex binary path is required.".into());
     }
     let sanitized = trimmed.trim_matches('"').trim_matches('\'');
     let metadata =
         fs::metadata(sanitized).map_err(|e| format!("Binary not found: {}", e))?;
     if !metadata.is_file() {
-        return Err("Binary path must point to a file.".to_string());
+        return Err("Binary path must point to a file.".into());
     }
     #[cfg(unix)]
     {
         use std::os::unix::fs::PermissionsExt;
         let mode = metadata.permissions().mode();
         if mode & 0o111 == 0 {
-            return Err("Binary is not executable.".to_string());
+            return Err("Binary is not executable.".into());
+        }
+    }
+    Ok(())
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct AccountStatus {
+    logged_in: bool,
+    auth_method: Option<String>,
+    plan: Option<String>,
+    email: Option<String>,
+    source: String,
+}
+
+fn parse_account_from_app_server(result: &Value) -> Option<AccountStatus> {
+    let account = result.get("account")?;
+    if account.is_null() {
+        return Some(AccountStatus {
+            logged_in: false,
+            auth_method: None,
+            plan: None,
+            email: None,
+            source: "app-server".to_string(),
+        });
+    }
+    let auth_method = account
+        .get("type")
+        .and_then(|value| value.as_str())
+        .map(|value| value.to_string());
+    let plan = account
+        .get("planType")
+        .or_else(|| account.get("plan_type"))
+        .and_then(|value| value.as_str())
+        .map(|value| value.to_string());
+    let email = account
+        .get("email")
+        .and_then(|value| value.as_str())
+        .map(|value| value.to_string());
+    Some(AccountStatus {
+        logged_in: true,
+        auth_method,
+        plan,
+        email,
+        source: "app-server".to_string(),
+    })
+}
+
+fn decode_jwt_claims(token: &str) -> Option<Value> {
+    use base64::Engine;
+    let payload = token.split('.').nth(1)?;
+    let bytes = base64::engine::general_purpose::URL_SAFE_NO_PAD
+        .decode(payload.trim_end_matches('='))
+        .ok()?;
+    serde_json::from_slice(&bytes).ok()
+}
+
+fn read_account_from_auth_file(codex_home: &Path) -> Result<AccountStatus, String> {
+    let path = codex_home.join("auth.json");
+    let mut status = AccountStatus {
+        logged_in: false,
+        auth_method: None,
+        plan: None,
+        email: None,
+        source: "auth-file".to_string(),
+    };
+    if !path.exists() {
+        return Ok(status);
+    }
+    let data = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    let auth: Value = serde_json::from_str(&data).map_err(|e| e.to_string())?;
+    let id_token = auth
+        .get("tokens")
+        .and_then(|tokens| tokens.get("id_token"))
+        .and_then(|token| token.as_str());
+    if let Some(id_token) = id_token {
+        status.logged_in = true;
+        status.auth_method = Some("chatgpt".to_string());
+        if let Some(claims) = decode_jwt_claims(id_token) {
+            status.email = claims
+                .get("email")
+                .and_then(|value| value.as_str())
+                .map(|value| value.to_string());
+            status.plan = claims
+                .get("https://api.openai.com/auth")
+                .and_then(|auth| auth.get("chatgpt_plan_type"))
+                .and_then(|value| value.as_str())
+                .map(|value| value.to_string());
+        }
+        return Ok(status);
+    }
+    let has_api_key = auth
+        .get("OPENAI_API_KEY")
+        .and_then(|value| value.as_str())
+        .map(|value| !value.trim().is_empty())
+        .unwrap_or(false);
+    if has_api_key {
+        status.logged_in = true;
+        status.auth_method = Some("apiKey".to_string());
+    }
+    Ok(status)
+}
+
+fn extract_login_url(line: &str) -> Option<String> {
+    let start = line.find("https://")?;
+    let url = line[start..]
+        .split_whitespace()
+        .next()?
+        .trim_end_matches(&['.', ',', ')'][..]);
+    Some(url.to_string())
+}
+
+#[tauri::command]
+async fn codex_account_status(
+    workspace_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<AccountStatus, AppError> {
+    let session = {
+        let sessions = state.sessions.lock().await;
+        match workspace_id.as_ref() {
+            Some(id) => sessions.get(id).cloned(),
+            None => sessions.values().next().cloned(),
+        }
+    };
+    if let Some(session) = session {
+        if let Ok(response) = session
+            .send_request("account/read", json!({ "refreshToken": false }))
+            .await
+        {
+            if let Some(status) = response
+                .get("result")
+                .and_then(parse_account_from_app_server)
+            {
+                return Ok(status);
+            }
+        }
+    }
+    let entry = match workspace_id.as_ref() {
+        Some(id) => state.workspaces.lock().await.get(id).cloned(),
+        None => None,
+    };
+    let home = entry
+        .as_ref()
+        .and_then(workspace_codex_home)
+        .or_else(resolve_codex_home)
+        .ok_or("codex home unavailable")?;
+    Ok(read_account_from_auth_file(&home)?)
+}
+
+#[tauri::command]
+async fn codex_login(
+    codex_home: Option<String>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<(), AppError> {
+    let settings = state.settings.lock().await.clone();
+    let codex_bin = settings
+        .codex_bin_path
+        .clone()
+        .unwrap_or_else(|| "codex".to_string());
+    let mut command = build_codex_command(&codex_bin, &settings);
+    if let Some(env) = shell_env::spawn_env(&state, &settings).await {
+        command.envs(env);
+    }
+    if let Some(home) = codex_home.as_ref().filter(|home| !home.trim().is_empty()) {
+        command.env("CODEX_HOME", home);
+    }
+    command.arg("login");
+    command.stdin(std::process::Stdio::null());
+    command.stdout(std::process::Stdio::piped());
+    command.stderr(std::process::Stdio::piped());
+
+    let mut child = command.spawn()?;
+    let stdout = child.stdout.take().ok_or("missing stdout")?;
+    let stderr = child.stderr.take().ok_or("missing stderr")?;
+
+    let app_handle = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let mut lines = BufReader::new(stderr).lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            let url = extract_login_url(&line);
+            let _ = app_handle.emit(
+                "codex-login-output",
+                json!({ "stream": "stderr", "line": line, "url": url }),
+            );
+        }
+    });
+
+    let app_handle = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let mut lines = BufReader::new(stdout).lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            let url = extract_login_url(&line);
+            let _ = app_handle.emit(
+                "codex-login-output",
+                json!({ "stream": "stdout", "line": line, "url": url }),
+            );
+        }
+        let exit_code = child.wait().await.ok().and_then(|status| status.code());
+        let _ = app_handle.emit(
+            "codex-login-completed",
+            json!({ "success": exit_code == Some(0), "exitCode": exit_code }),
+        );
+    });
+    Ok(())
+}
+
+#[tauri::command]
+async fn list_accounts(state: State<'_, AppState>) -> Result<Vec<CodexAccount>, AppError> {
+    Ok(state.accounts.lock().await.clone())
+}
+
+#[tauri::command]
+async fn save_account(
+    name: String,
+    codex_home: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<CodexAccount>, AppError> {
+    let name = name.trim().to_string();
+    let codex_home = codex_home.trim().to_string();
+    if name.is_empty() {
+        return Err("account name is required".into());
+    }
+    if codex_home.is_empty() {
+        return Err("CODEX_HOME path is required".into());
+    }
+    std::fs::create_dir_all(&codex_home)?;
+    let mut accounts = state.accounts.lock().await;
+    if let Some(existing) = accounts.iter_mut().find(|account| account.name == name) {
+        existing.codex_home = codex_home;
+    } else {
+        accounts.push(CodexAccount { name, codex_home });
+    }
+    write_accounts(&state.accounts_path, &accounts)?;
+    Ok(accounts.clone())
+}
+
+#[tauri::command]
+async fn remove_account(
+    name: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<CodexAccount>, AppError> {
+    let mut accounts = state.accounts.lock().await;
+    accounts.retain(|account| account.name != name);
+    write_accounts(&state.accounts_path, &accounts)?;
+    Ok(accounts.clone())
+}
+
+#[tauri::command]
+async fn set_workspace_codex_home(
+    id: String,
+    codex_home: Option<String>,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<(), AppError> {
+    let codex_home = codex_home
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty());
+    let entry = {
+        let mut workspaces = state.workspaces.lock().await;
+        let entry = workspaces.get_mut(&id).ok_or(AppError::WorkspaceNotFound)?;
+        if entry.codex_home == codex_home {
+            return Ok(());
         }
+        entry.codex_home = codex_home;
+        let entry = entry.clone();
+        let list: Vec<_> = workspaces.values().cloned().collect();
+        write_workspaces(&state.storage_path, &list)?;
+        entry
+    };
+
+    // A running app-server keeps the CODEX_HOME it was spawned with.
+    let previous = state.sessions.lock().await.remove(&id);
+    if let Some(session) = previous {
+        let _ = session.child.lock().await.kill().await;
+        connect_entry(entry, &state, app).await?;
     }
     Ok(())
 }
 
 #[tauri::command]
-async fn usage_get_snapshot(state: State<'_, AppState>) -> Result<UsageSnapshot, String> {
+async fn usage_get_snapshot(state: State<'_, AppState>) -> Result<UsageSnapshot, AppError> {
     let store = state.usage_store.lock().await;
     Ok(store
         .last_snapshot
@@ -2213,12 +7260,12 @@ async fn usage_get_snapshot(state: State<'_, AppState>) -> Result<UsageSnapshot,
 }
 
 #[tauri::command]
-async fn usage_refresh(app: AppHandle) -> Result<UsageSnapshot, String> {
-    refresh_usage_snapshot(&app).await
+async fn usage_refresh(app: AppHandle) -> Result<UsageSnapshot, AppError> {
+    Ok(refresh_usage_snapshot(&app).await?)
 }
 
 #[tauri::command]
-async fn confirm_quit(app: AppHandle, state: State<'_, AppState>) -> Result<(), String> {
+async fn confirm_quit(app: AppHandle, state: State<'_, AppState>) -> Result<(), AppError> {
     state.allow_quit.store(true, Ordering::SeqCst);
     app.exit(0);
     Ok(())
@@ -2226,73 +7273,277 @@ async fn confirm_quit(app: AppHandle, state: State<'_, AppState>) -> Result<(),
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    if let Some(code) = cli::run_from_args() {
+        std::process::exit(code);
+    }
     tauri::Builder::default()
         .enable_macos_default_menu(true)
-        .menu(|app| {
-            let menu = Menu::default(app)?;
-            #[cfg(target_os = "macos")]
-            insert_preferences_menu_item(app, &menu)?;
-            Ok(menu)
-        })
-        .on_menu_event(|app, event| {
-            if event.id() == "preferences" {
-                let _ = open_settings_window(app);
-            }
-            if event.id() == "quit" {
-                handle_quit_request(app);
-            }
-        })
+        .menu(|app| menu::build(app, &[], i18n::system_lang()))
+        .on_menu_event(|app, event| menu::handle_event(app, event.id().as_ref()))
         .setup(|app| {
             let state = AppState::load(&app.handle());
+            let storage_status = state.storage_status();
+            let data_dir = state.data_dir.clone();
             app.manage(state);
+            window_state::restore(app.handle());
+            let args: Vec<String> = env::args().skip(1).collect();
+            if storage_status.error.is_some() {
+                let forwarded =
+                    ipc::send_request(&data_dir, &json!({ "type": "open", "args": args }));
+                if forwarded.is_ok() {
+                    std::process::exit(0);
+                }
+                let _ = app.handle().emit("storage-locked", storage_status);
+            } else {
+                ipc::start_listener(app.handle().clone(), &data_dir);
+                deep_link::handle_urls(app.handle(), deep_link::deep_link_args(args));
+                if !cfg!(debug_assertions) {
+                    std::thread::spawn(|| {
+                        if let Err(err) = deep_link::register_url_scheme() {
+                            tracing::warn!("failed to register URL scheme: {err}");
+                        }
+                    });
+                }
+            }
+            tauri::async_runtime::block_on(menu::rebuild(app.handle()));
+            let (quit_behavior, lang) = tauri::async_runtime::block_on(async {
+                let settings = app.state::<AppState>().settings.lock().await.clone();
+                (settings.quit_behavior, i18n::lang_for(settings.locale.as_deref()))
+            });
+            if let Err(err) = tray::sync(app.handle(), quit_behavior, lang) {
+                tracing::warn!("failed to create tray icon: {err}");
+            }
             let app_handle = app.handle().clone();
             tauri::async_runtime::spawn(async move {
                 restart_usage_polling(&app_handle).await;
             });
+            let app_handle = app.handle().clone();
+            tauri::async_runtime::spawn(run_message_queue_scheduler(app_handle));
+            system_events::start(app.handle().clone());
+            analytics::start(app.handle().clone());
+            auto_connect::start(app.handle().clone());
+            scheduler::start(app.handle().clone());
+            http_api::start(app.handle().clone());
+            let app_handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                let state = app_handle.state::<AppState>();
+                let settings = state.settings.lock().await.clone();
+                shell_env::spawn_env(&state, &settings).await;
+            });
             Ok(())
         })
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_notification::init())
-        .invoke_handler(tauri::generate_handler![
+        .plugin(tauri_plugin_updater::Builder::new().build())
+        .invoke_handler(analytics::track(tauri::generate_handler![
+            get_storage_status,
+            get_codex_version,
+            discover_codex_binaries,
+            deep_link::take_pending_deep_links,
+            hooks::list_hook_executions,
+            hooks::clear_hook_executions,
+            hooks::test_hook,
+            logging::set_log_level,
+            logging::collect_diagnostics,
+            shell_env::refresh_login_shell_env,
+            thread_state::get_thread_states,
+            snapshot::get_app_snapshot,
+            metrics::get_thread_metrics,
+            metrics::get_workspace_metrics,
+            badge::get_badge_counts,
+            deferrals::report_user_activity,
+            deferrals::list_deferred_approvals,
+            deferrals::replay_deferred_approval,
+            review::review_uncommitted,
+            review::review_commit,
+            review::review_range,
+            review_findings::list_review_findings,
+            review_findings::set_review_finding_status,
+            review_findings::rerun_review_unresolved,
+            patch::apply_patch,
+            patch::preview_patch,
+            file_versions::get_file_versions,
+            file_versions::restore_file_version,
+            templates::list_workspace_templates,
+            templates::save_workspace_template,
+            templates::delete_workspace_template,
+            templates::add_workspace_from_template,
+            container::set_workspace_container,
+            container::container_start,
+            container::container_stop,
+            container::container_status,
+            discovery::discover_repos,
+            capabilities::get_workspace_capabilities,
+            event_blobs::read_event_blob,
+            context::get_thread_context_usage,
+            compaction::compact_thread,
+            instructions::read_agent_instructions,
+            instructions::write_agent_instructions,
+            skills::skill_read,
+            skills::skill_create,
+            skills::skill_update,
+            skills::skill_delete,
+            skills::skill_set_enabled,
+            tasks::set_workspace_pre_connect_commands,
+            observer::set_workspace_observer_mode,
+            metrics::get_turn_changes,
+            undo::undo_turn,
             list_workspaces,
             add_workspace,
             remove_workspace,
             start_thread,
             save_attachment,
             send_user_message,
+            secrets::get_secret_scan_store,
+            secrets::set_secret_allowlist,
+            secrets::clear_secret_scan_log,
+            queue_message,
+            list_queued_messages,
+            cancel_queued_message,
+            list_pending_messages,
+            cancel_pending_message,
+            save_draft,
+            get_draft,
+            clear_draft,
             cancel_turn,
             start_review,
             respond_to_server_request,
             resume_thread,
             list_threads,
+            list_all_threads,
             archive_thread,
+            prepare_delete_thread,
+            delete_thread,
+            fork_thread,
             get_workspace_sessions,
             save_workspace_sessions,
             connect_workspace,
             get_git_status,
             get_git_diffs,
+            get_git_file_diff_structured,
+            get_git_diff_range,
+            get_full_diff,
+            git_fetch,
+            git_pull,
+            git_push,
+            create_pull_request,
+            git_conflicts,
+            git_resolve,
+            git_blame,
+            list_checkpoints,
+            restore_checkpoint,
             model_list,
             skills_list,
             prompts_list,
             prompt_read,
+            prompt_render,
+            record_prompt_usage,
+            prompt_stats,
             search_files,
+            read_workspace_file,
+            resolve_absolute_path,
+            reveal_in_file_manager,
+            rebuild_file_index,
+            workspace_health,
+            stats::workspace_stats,
+            stats::cancel_workspace_stats,
+            audit::query_audit_log,
+            audit::export_audit_log_csv,
+            path_guard::get_path_guard,
+            path_guard::set_path_guard,
+            get_search_config,
+            save_search_config,
             get_settings,
             update_settings,
             inspect_codex_bin,
             validate_codex_bin,
             usage_get_snapshot,
             usage_refresh,
-            confirm_quit
-        ])
+            rate_history::rate_limit_history,
+            quick_switch::quick_switch_index,
+            terminal::terminal_create,
+            terminal::terminal_write,
+            terminal::terminal_resize,
+            terminal::terminal_kill,
+            tasks::detect_tasks,
+            tasks::run_task,
+            tasks::cancel_task,
+            codex_account_status,
+            codex_login,
+            list_accounts,
+            save_account,
+            remove_account,
+            set_workspace_codex_home,
+            confirm_quit,
+            tray::quit_and_stop_agents,
+            window_state::get_window_state,
+            keymap::get_keymap,
+            keymap::set_keybinding,
+            keymap::reset_keymap,
+            i18n::get_locale_strings,
+            updater::check_for_updates,
+            updater::download_update,
+            updater::install_update_and_restart,
+            crash::list_crash_reports,
+            crash::submit_crash_report,
+            analytics::get_local_analytics,
+            analytics::export_local_analytics,
+            analytics::reset_local_analytics,
+            thread_access::set_thread_access_mode,
+            command_allowlist::list_command_allowlist,
+            command_allowlist::add_command_allowlist_entry,
+            command_allowlist::remove_command_allowlist_entry,
+            recording::start_recording,
+            recording::stop_recording,
+            speech::set_workspace_speech,
+            auto_connect::set_workspace_auto_connect,
+            workspace_edit::rename_workspace,
+            workspace_edit::relocate_workspace,
+            workspace_merge::find_duplicate_workspaces,
+            workspace_merge::merge_workspaces,
+            workspace_merge::transfer_thread_metadata,
+            scheduler::list_scheduled_tasks,
+            scheduler::save_scheduled_task,
+            scheduler::delete_scheduled_task,
+            scheduler::run_scheduled_task_now,
+            scheduler::list_scheduled_task_runs,
+            task_queue::list_task_queue,
+            task_queue::enqueue_task,
+            task_queue::resume_task_queue,
+            task_queue::reorder_task_queue,
+            task_queue::cancel_queued_task,
+            broadcast::broadcast_message,
+            broadcast::list_broadcasts,
+            broadcast::get_broadcast,
+            batch_report::get_batch_report,
+            batch_report::export_batch_report_markdown,
+            code_blocks::save_code_block,
+            http_api::get_http_api_status,
+            http_api::regenerate_http_api_token
+        ]))
         .build(tauri::generate_context!())
         .expect("error while building tauri application")
         .run(|app_handle, event| {
             let state = app_handle.state::<AppState>();
+            #[cfg(target_os = "macos")]
+            if let tauri::RunEvent::Opened { urls } = &event {
+                let urls = urls.iter().map(|url| url.to_string()).collect();
+                deep_link::handle_urls(&app_handle, urls);
+                return;
+            }
+            #[cfg(target_os = "macos")]
+            if let tauri::RunEvent::Reopen { .. } = &event {
+                tray::show_main_window(&app_handle);
+                return;
+            }
             if let tauri::RunEvent::ExitRequested { api, .. } = event {
                 if should_confirm_quit(&state) {
                     api.prevent_exit();
                     emit_confirm_quit(&app_handle);
+                } else {
+                    window_state::save(app_handle);
+                    state.analytics.flush();
                 }
                 return;
             }
@@ -2302,10 +7553,28 @@ pub fn run() {
                     return;
                 }
                 if let tauri::WindowEvent::CloseRequested { api, .. } = event {
-                    if should_confirm_quit(&state) {
+                    window_state::save(app_handle);
+                    if tray::keeps_running(&state) {
+                        api.prevent_close();
+                        tray::hide_main_window(app_handle);
+                    } else if should_confirm_quit(&state) {
                         api.prevent_close();
                         emit_confirm_quit(&app_handle);
                     }
+                } else if matches!(
+                    event,
+                    tauri::WindowEvent::Moved(_) | tauri::WindowEvent::Resized(_)
+                ) {
+                    window_state::capture(app_handle);
+                } else if let tauri::WindowEvent::Focused(focused) = event {
+                    state.window_focused.store(focused, Ordering::SeqCst);
+                    if focused {
+                        state.deferrals.touch();
+                        let app_handle = app_handle.clone();
+                        tauri::async_runtime::spawn(async move {
+                            refresh_usage_on_focus(&app_handle).await;
+                        });
+                    }
                 }
             }
         });
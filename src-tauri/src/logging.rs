@@ -0,0 +1,458 @@
+use chrono::{Datelike, Local, Timelike};
+use flate2::write::DeflateEncoder;
+use flate2::{Compression, Crc};
+use serde_json::{json, Value};
+use std::fmt::{self, Write as _};
+use std::fs::{self, File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
+use tauri::State;
+use tracing::field::{Field, Visit};
+use tracing::level_filters::LevelFilter;
+use tracing::span::{Attributes, Id, Record};
+use tracing::subscriber::Interest;
+use tracing::{Event, Metadata, Subscriber};
+
+use crate::error::AppError;
+use crate::{write_settings, AppState};
+
+const CRATE_TARGET: &str = "codex_monitor_lib";
+const LOG_FILE_PREFIX: &str = "codexola-";
+const LOG_RETENTION_FILES: usize = 7;
+const DIAGNOSTICS_LOG_FILES: usize = 3;
+const DIAGNOSTICS_MAX_LOG_BYTES: u64 = 5 * 1024 * 1024;
+
+pub(crate) const DEFAULT_LOG_LEVEL: &str = "info";
+
+#[derive(Debug, Clone)]
+struct LogFilter {
+    default: LevelFilter,
+    directives: Vec<(String, LevelFilter)>,
+}
+
+impl LogFilter {
+    fn parse(spec: &str) -> Result<Self, String> {
+        let mut filter = LogFilter {
+            default: LevelFilter::INFO,
+            directives: Vec::new(),
+        };
+        for directive in spec.split(',').map(str::trim).filter(|part| !part.is_empty()) {
+            match directive.split_once('=') {
+                Some((target, level)) => {
+                    let level = parse_level(level)?;
+                    let target = target.trim();
+                    let target = if target.contains("::") || target == CRATE_TARGET {
+                        target.to_string()
+                    } else {
+                        format!("{CRATE_TARGET}::{target}")
+                    };
+                    filter.directives.retain(|(existing, _)| existing != &target);
+                    filter.directives.push((target, level));
+                }
+                None => filter.default = parse_level(directive)?,
+            }
+        }
+        filter
+            .directives
+            .sort_by_key(|(target, _)| std::cmp::Reverse(target.len()));
+        Ok(filter)
+    }
+
+    fn level_for(&self, target: &str) -> LevelFilter {
+        self.directives
+            .iter()
+            .find(|(prefix, _)| {
+                target == prefix
+                    || target
+                        .strip_prefix(prefix.as_str())
+                        .is_some_and(|rest| rest.starts_with("::"))
+            })
+            .map(|(_, level)| *level)
+            .unwrap_or(self.default)
+    }
+}
+
+fn parse_level(level: &str) -> Result<LevelFilter, String> {
+    LevelFilter::from_str(level.trim()).map_err(|_| format!("invalid log level: {level}"))
+}
+
+struct RollingFile {
+    dir: PathBuf,
+    date: String,
+    file: Option<File>,
+}
+
+impl RollingFile {
+    fn write_line(&mut self, line: &str) {
+        let today = Local::now().format("%Y-%m-%d").to_string();
+        if self.file.is_none() || self.date != today {
+            let _ = fs::create_dir_all(&self.dir);
+            let path = self.dir.join(format!("{LOG_FILE_PREFIX}{today}.log"));
+            self.file = OpenOptions::new().create(true).append(true).open(path).ok();
+            self.date = today;
+            prune_log_files(&self.dir);
+        }
+        if let Some(file) = self.file.as_mut() {
+            let _ = file.write_all(line.as_bytes());
+        }
+    }
+}
+
+fn log_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|entry| entry.path())
+                .filter(|path| {
+                    path.file_name()
+                        .and_then(|name| name.to_str())
+                        .is_some_and(|name| {
+                            name.starts_with(LOG_FILE_PREFIX) && name.ends_with(".log")
+                        })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    files.sort();
+    files.reverse();
+    files
+}
+
+fn prune_log_files(dir: &Path) {
+    for path in log_files(dir).into_iter().skip(LOG_RETENTION_FILES) {
+        let _ = fs::remove_file(path);
+    }
+}
+
+struct LogShared {
+    dir: PathBuf,
+    spec: RwLock<String>,
+    filter: RwLock<LogFilter>,
+    writer: Mutex<RollingFile>,
+}
+
+static LOGGING: OnceLock<Arc<LogShared>> = OnceLock::new();
+
+struct FileSubscriber {
+    shared: Arc<LogShared>,
+    next_span: AtomicU64,
+}
+
+#[derive(Default)]
+struct EventFields {
+    message: String,
+    fields: String,
+}
+
+impl Visit for EventFields {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message.push_str(value);
+        } else {
+            let _ = write!(self.fields, " {}={value:?}", field.name());
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            let _ = write!(self.message, "{value:?}");
+        } else {
+            let _ = write!(self.fields, " {}={value:?}", field.name());
+        }
+    }
+}
+
+impl Subscriber for FileSubscriber {
+    fn register_callsite(&self, _metadata: &'static Metadata<'static>) -> Interest {
+        Interest::sometimes()
+    }
+
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        let Ok(filter) = self.shared.filter.read() else {
+            return false;
+        };
+        filter.level_for(metadata.target()) >= *metadata.level()
+    }
+
+    fn new_span(&self, _attributes: &Attributes<'_>) -> Id {
+        Id::from_u64(self.next_span.fetch_add(1, Ordering::Relaxed))
+    }
+
+    fn record(&self, _span: &Id, _values: &Record<'_>) {}
+
+    fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        let metadata = event.metadata();
+        let mut fields = EventFields::default();
+        event.record(&mut fields);
+        let line = format!(
+            "{} {:>5} {}: {}{}\n",
+            Local::now().format("%Y-%m-%dT%H:%M:%S%.3f%:z"),
+            metadata.level(),
+            metadata.target(),
+            fields.message,
+            fields.fields
+        );
+        if cfg!(debug_assertions) {
+            eprint!("{line}");
+        }
+        if let Ok(mut writer) = self.shared.writer.lock() {
+            writer.write_line(&line);
+        }
+    }
+
+    fn enter(&self, _span: &Id) {}
+
+    fn exit(&self, _span: &Id) {}
+}
+
+pub(crate) fn init(data_dir: &Path, spec: &str) {
+    let filter = LogFilter::parse(spec).unwrap_or_else(|_| LogFilter {
+        default: LevelFilter::INFO,
+        directives: Vec::new(),
+    });
+    let dir = data_dir.join("logs");
+    let shared = Arc::new(LogShared {
+        dir: dir.clone(),
+        spec: RwLock::new(spec.to_string()),
+        filter: RwLock::new(filter),
+        writer: Mutex::new(RollingFile {
+            dir,
+            date: String::new(),
+            file: None,
+        }),
+    });
+    if LOGGING.set(Arc::clone(&shared)).is_err() {
+        return;
+    }
+    let subscriber = FileSubscriber {
+        shared,
+        next_span: AtomicU64::new(1),
+    };
+    let _ = tracing::subscriber::set_global_default(subscriber);
+}
+
+pub(crate) fn apply_log_level(spec: &str) -> Result<(), String> {
+    let filter = LogFilter::parse(spec)?;
+    let Some(shared) = LOGGING.get() else {
+        return Ok(());
+    };
+    if let Ok(mut current) = shared.filter.write() {
+        *current = filter;
+    }
+    if let Ok(mut current) = shared.spec.write() {
+        *current = spec.to_string();
+    }
+    tracing::callsite::rebuild_interest_cache();
+    Ok(())
+}
+
+fn dos_timestamp() -> (u16, u16) {
+    let now = Local::now();
+    let time =
+        ((now.hour() as u16) << 11) | ((now.minute() as u16) << 5) | (now.second() as u16 / 2);
+    let year = now.year().clamp(1980, 2107) as u16 - 1980;
+    let date = (year << 9) | ((now.month() as u16) << 5) | now.day() as u16;
+    (time, date)
+}
+
+fn write_zip(path: &Path, entries: &[(String, Vec<u8>)]) -> Result<(), String> {
+    let (time, date) = dos_timestamp();
+    let mut out: Vec<u8> = Vec::new();
+    let mut central: Vec<u8> = Vec::new();
+    for (name, data) in entries {
+        let mut crc = Crc::new();
+        crc.update(data);
+        let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(data).map_err(|e| e.to_string())?;
+        let compressed = encoder.finish().map_err(|e| e.to_string())?;
+        let offset = out.len() as u32;
+        let header = |sig: u32, central: bool| {
+            let mut bytes: Vec<u8> = Vec::new();
+            bytes.extend_from_slice(&sig.to_le_bytes());
+            if central {
+                bytes.extend_from_slice(&20u16.to_le_bytes());
+            }
+            bytes.extend_from_slice(&20u16.to_le_bytes());
+            bytes.extend_from_slice(&0u16.to_le_bytes());
+            bytes.extend_from_slice(&8u16.to_le_bytes());
+            bytes.extend_from_slice(&time.to_le_bytes());
+            bytes.extend_from_slice(&date.to_le_bytes());
+            bytes.extend_from_slice(&crc.sum().to_le_bytes());
+            bytes.extend_from_slice(&(compressed.len() as u32).to_le_bytes());
+            bytes.extend_from_slice(&(data.len() as u32).to_le_bytes());
+            bytes.extend_from_slice(&(name.len() as u16).to_le_bytes());
+            bytes.extend_from_slice(&0u16.to_le_bytes());
+            bytes
+        };
+        out.extend(header(0x0403_4b50, false));
+        out.extend_from_slice(name.as_bytes());
+        out.extend_from_slice(&compressed);
+
+        central.extend(header(0x0201_4b50, true));
+        central.extend_from_slice(&0u16.to_le_bytes());
+        central.extend_from_slice(&0u16.to_le_bytes());
+        central.extend_from_slice(&0u16.to_le_bytes());
+        central.extend_from_slice(&0u32.to_le_bytes());
+        central.extend_from_slice(&offset.to_le_bytes());
+        central.extend_from_slice(name.as_bytes());
+    }
+    let central_offset = out.len() as u32;
+    out.extend_from_slice(&central);
+    out.extend_from_slice(&0x0605_4b50u32.to_le_bytes());
+    out.extend_from_slice(&0u16.to_le_bytes());
+    out.extend_from_slice(&0u16.to_le_bytes());
+    out.extend_from_slice(&(entries.len() as u16).to_le_bytes());
+    out.extend_from_slice(&(entries.len() as u16).to_le_bytes());
+    out.extend_from_slice(&(central.len() as u32).to_le_bytes());
+    out.extend_from_slice(&central_offset.to_le_bytes());
+    out.extend_from_slice(&0u16.to_le_bytes());
+    fs::write(path, out).map_err(|e| e.to_string())
+}
+
+fn read_log_tail(path: &Path) -> Option<Vec<u8>> {
+    let mut file = File::open(path).ok()?;
+    let len = file.metadata().ok()?.len();
+    if len > DIAGNOSTICS_MAX_LOG_BYTES {
+        file.seek(SeekFrom::Start(len - DIAGNOSTICS_MAX_LOG_BYTES)).ok()?;
+    }
+    let mut data = Vec::new();
+    file.read_to_end(&mut data).ok()?;
+    Some(data)
+}
+
+pub(crate) fn tail_lines(count: usize) -> Vec<String> {
+    let Some(shared) = LOGGING.get() else {
+        return Vec::new();
+    };
+    let Some(data) = log_files(&shared.dir)
+        .first()
+        .and_then(|path| read_log_tail(path))
+    else {
+        return Vec::new();
+    };
+    let text = String::from_utf8_lossy(&data);
+    let lines: Vec<&str> = text.lines().collect();
+    lines[lines.len().saturating_sub(count)..]
+        .iter()
+        .map(|line| line.to_string())
+        .collect()
+}
+
+fn redact_url(url: &str) -> String {
+    match url::Url::parse(url) {
+        Ok(parsed) => format!(
+            "{}://{}/<redacted>",
+            parsed.scheme(),
+            parsed.host_str().unwrap_or_default()
+        ),
+        Err(_) => "<redacted>".to_string(),
+    }
+}
+
+fn sanitize_settings(mut settings: Value) -> Value {
+    if let Some(token) = settings.get_mut("gitHostingToken") {
+        if !token.is_null() {
+            *token = json!("<redacted>");
+        }
+    }
+    if let Some(token) = settings.pointer_mut("/httpApi/token") {
+        if !token.is_null() {
+            *token = json!("<redacted>");
+        }
+    }
+    if let Some(hooks) = settings.get_mut("hooks").and_then(|hooks| hooks.as_array_mut()) {
+        for hook in hooks {
+            let Some(action) = hook.get_mut("action") else {
+                continue;
+            };
+            if let Some(url) = action.get("url").and_then(|url| url.as_str()) {
+                let redacted = redact_url(url);
+                action["url"] = json!(redacted);
+            }
+            if let Some(headers) = action.get_mut("headers").and_then(|h| h.as_object_mut()) {
+                for value in headers.values_mut() {
+                    *value = json!("<redacted>");
+                }
+            }
+            if action.get("command").is_some() {
+                action["command"] = json!("<redacted>");
+            }
+        }
+    }
+    settings
+}
+
+#[tauri::command]
+pub(crate) async fn set_log_level(
+    level: String,
+    state: State<'_, AppState>,
+) -> Result<String, AppError> {
+    let level = level.trim().to_string();
+    apply_log_level(&level)?;
+    let mut settings = state.settings.lock().await;
+    settings.log_level = level.clone();
+    write_settings(&state.settings_path, &settings)?;
+    tracing::info!(level = %level, "log level updated");
+    Ok(level)
+}
+
+#[tauri::command]
+pub(crate) async fn collect_diagnostics(state: State<'_, AppState>) -> Result<String, AppError> {
+    let settings = serde_json::to_value(state.settings.lock().await.clone())?;
+    let workspace_count = state.workspaces.lock().await.len();
+    let connected_count = state.sessions.lock().await.len();
+    let data_dir = state.data_dir.clone();
+    let log_dir = LOGGING
+        .get()
+        .map(|shared| shared.dir.clone())
+        .unwrap_or_else(|| data_dir.join("logs"));
+    let log_level = LOGGING
+        .get()
+        .and_then(|shared| shared.spec.read().ok().map(|spec| spec.clone()));
+    let system = json!({
+        "version": env!("CARGO_PKG_VERSION"),
+        "os": std::env::consts::OS,
+        "arch": std::env::consts::ARCH,
+        "logLevel": log_level,
+        "workspaces": workspace_count,
+        "connectedWorkspaces": connected_count,
+        "collectedAt": Local::now().to_rfc3339(),
+    });
+    let path = tokio::task::spawn_blocking(move || -> Result<PathBuf, String> {
+        let mut entries: Vec<(String, Vec<u8>)> = vec![
+            (
+                "system.json".to_string(),
+                serde_json::to_vec_pretty(&system).map_err(|e| e.to_string())?,
+            ),
+            (
+                "settings.json".to_string(),
+                serde_json::to_vec_pretty(&sanitize_settings(settings))
+                    .map_err(|e| e.to_string())?,
+            ),
+        ];
+        for path in log_files(&log_dir).into_iter().take(DIAGNOSTICS_LOG_FILES) {
+            let Some(data) = read_log_tail(&path) else {
+                continue;
+            };
+            let name = path.file_name().unwrap_or_default().to_string_lossy();
+            entries.push((format!("logs/{name}"), data));
+        }
+        let output_dir = data_dir.join("diagnostics");
+        fs::create_dir_all(&output_dir).map_err(|e| e.to_string())?;
+        let output = output_dir.join(format!(
+            "codexola-diagnostics-{}.zip",
+            Local::now().format("%Y%m%d-%H%M%S")
+        ));
+        write_zip(&output, &entries)?;
+        Ok(output)
+    })
+    .await??;
+    tracing::info!(path = %path.display(), "diagnostics bundle written");
+    Ok(path.to_string_lossy().to_string())
+}
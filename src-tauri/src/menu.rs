@@ -0,0 +1,218 @@
+use serde_json::{json, Value};
+use tauri::menu::{Menu, MenuItem, PredefinedMenuItem, Submenu};
+use tauri::{AppHandle, Emitter, Manager, Wry};
+
+use crate::{
+    archive_thread, cancel_turn, connect_workspace, dock, handle_quit_request, i18n, keymap,
+    open_settings_window, start_thread, tray, window_state, AppState, WorkspaceEntry,
+};
+
+const OPEN_WORKSPACE_PREFIX: &str = "workspace-open:";
+const CONNECT_WORKSPACE_PREFIX: &str = "workspace-connect:";
+
+fn action_item(app: &AppHandle, id: &str, label: String) -> tauri::Result<MenuItem<Wry>> {
+    MenuItem::with_id(app, id, label, true, keymap::default_accelerator(id))
+}
+
+fn plain_item(
+    app: &AppHandle,
+    id: impl Into<String>,
+    label: String,
+) -> tauri::Result<MenuItem<Wry>> {
+    MenuItem::with_id(app, id.into(), label, true, None::<&str>)
+}
+
+fn workspaces_submenu(
+    app: &AppHandle,
+    entries: &[WorkspaceEntry],
+    lang: &str,
+) -> tauri::Result<Submenu<Wry>> {
+    let submenu = Submenu::new(app, i18n::t(lang, "menu.workspaces"), true)?;
+    if entries.is_empty() {
+        let empty = MenuItem::new(app, i18n::t(lang, "menu.noWorkspaces"), false, None::<&str>)?;
+        submenu.append(&empty)?;
+    }
+    for entry in entries {
+        let open_id = format!("{OPEN_WORKSPACE_PREFIX}{}", entry.id);
+        let connect_id = format!("{CONNECT_WORKSPACE_PREFIX}{}", entry.id);
+        let workspace = Submenu::with_items(
+            app,
+            &entry.name,
+            true,
+            &[
+                &plain_item(app, open_id, i18n::t(lang, "menu.open"))?,
+                &plain_item(app, connect_id, i18n::t(lang, "menu.connect"))?,
+            ],
+        )?;
+        submenu.append(&workspace)?;
+    }
+    Ok(submenu)
+}
+
+fn thread_submenu(app: &AppHandle, lang: &str) -> tauri::Result<Submenu<Wry>> {
+    Submenu::with_items(
+        app,
+        i18n::t(lang, "menu.thread"),
+        true,
+        &[
+            &action_item(app, "new-thread", i18n::t(lang, "menu.newThread"))?,
+            &PredefinedMenuItem::separator(app)?,
+            &action_item(app, "interrupt-turn", i18n::t(lang, "menu.interrupt"))?,
+            &plain_item(app, "archive-thread", i18n::t(lang, "menu.archive"))?,
+        ],
+    )
+}
+
+fn extend_view_menu(
+    app: &AppHandle,
+    menu: &Menu<Wry>,
+    lang: &str,
+) -> tauri::Result<Option<Submenu<Wry>>> {
+    let items = [
+        action_item(app, "quick-switch", i18n::t(lang, "menu.quickSwitch"))?,
+        plain_item(app, "toggle-sidebar", i18n::t(lang, "menu.toggleSidebar"))?,
+    ];
+    if let Some(view) = keymap::find_submenu(menu.items()?, "View") {
+        for (index, item) in items.iter().enumerate() {
+            view.insert(item, index)?;
+        }
+        view.insert(&PredefinedMenuItem::separator(app)?, items.len())?;
+        return Ok(None);
+    }
+    let view = Submenu::new(app, i18n::t(lang, "menu.view"), true)?;
+    for item in &items {
+        view.append(item)?;
+    }
+    Ok(Some(view))
+}
+
+pub(crate) fn build(
+    app: &AppHandle,
+    entries: &[WorkspaceEntry],
+    lang: &str,
+) -> tauri::Result<Menu<Wry>> {
+    let menu = Menu::default(app)?;
+    #[cfg(target_os = "macos")]
+    crate::insert_preferences_menu_item(app, &menu, lang)?;
+    let new_view = extend_view_menu(app, &menu, lang)?;
+    let mut index = menu
+        .items()?
+        .iter()
+        .position(|item| {
+            item.as_submenu()
+                .is_some_and(|submenu| submenu.text().is_ok_and(|text| text == "Window"))
+        })
+        .unwrap_or(menu.items()?.len());
+    if let Some(view) = new_view {
+        menu.insert(&view, index)?;
+        index += 1;
+    }
+    menu.insert(&workspaces_submenu(app, entries, lang)?, index)?;
+    menu.insert(&thread_submenu(app, lang)?, index + 1)?;
+    Ok(menu)
+}
+
+pub(crate) async fn rebuild(app: &AppHandle) {
+    let state = app.state::<AppState>();
+    let mut entries: Vec<WorkspaceEntry> =
+        state.workspaces.lock().await.values().cloned().collect();
+    entries.sort_by_key(|entry| entry.name.to_lowercase());
+    let (keymap, lang) = {
+        let settings = state.settings.lock().await;
+        (settings.keymap.clone(), i18n::lang_for(settings.locale.as_deref()))
+    };
+    let menu = match build(app, &entries, lang) {
+        Ok(menu) => menu,
+        Err(err) => {
+            tracing::warn!("failed to build menu: {err}");
+            return;
+        }
+    };
+    if let Err(err) = app.set_menu(menu) {
+        tracing::warn!("failed to set menu: {err}");
+        return;
+    }
+    keymap::apply(app, &keymap);
+    dock::refresh(app).await;
+}
+
+fn emit_action(app: &AppHandle, payload: Value) {
+    tray::show_main_window(app);
+    let _ = app.emit("menu-action", payload);
+}
+
+async fn run_thread_action(app: AppHandle, action: String) {
+    let state = app.state::<AppState>();
+    let Some((workspace_id, thread_id)) = window_state::last_active(&state) else {
+        emit_action(&app, json!({ "action": action }));
+        return;
+    };
+    let result = match (action.as_str(), thread_id.clone()) {
+        ("new-thread", _) => {
+            start_thread(workspace_id.clone(), None, None, None, None, app.state()).await
+        }
+        ("interrupt-turn", Some(thread_id)) => {
+            cancel_turn(workspace_id.clone(), thread_id, app.state()).await
+        }
+        ("archive-thread", Some(thread_id)) => {
+            archive_thread(workspace_id.clone(), thread_id, app.state()).await
+        }
+        _ => Err("No active thread.".into()),
+    };
+    let (result, error) = match result {
+        Ok(result) => (Some(result), None),
+        Err(err) => {
+            tracing::warn!(%workspace_id, action, "menu action failed: {err}");
+            (None, Some(err.to_string()))
+        }
+    };
+    emit_action(
+        &app,
+        json!({
+            "action": action,
+            "workspaceId": workspace_id,
+            "threadId": thread_id,
+            "result": result,
+            "error": error,
+        }),
+    );
+}
+
+pub(crate) fn handle_event(app: &AppHandle, id: &str) {
+    match id {
+        "preferences" => {
+            let _ = open_settings_window(app);
+        }
+        "quit" => handle_quit_request(app),
+        "new-thread" | "interrupt-turn" | "archive-thread" => {
+            tauri::async_runtime::spawn(run_thread_action(app.clone(), id.to_string()));
+        }
+        "quick-switch" | "toggle-sidebar" => emit_action(app, json!({ "action": id })),
+        _ if dock::handle_event(app, id) => {}
+        _ => {
+            if let Some(workspace_id) = id.strip_prefix(OPEN_WORKSPACE_PREFIX) {
+                emit_action(
+                    app,
+                    json!({ "action": "open-workspace", "workspaceId": workspace_id }),
+                );
+            } else if let Some(workspace_id) = id.strip_prefix(CONNECT_WORKSPACE_PREFIX) {
+                let app = app.clone();
+                let workspace_id = workspace_id.to_string();
+                tauri::async_runtime::spawn(async move {
+                    let error = connect_workspace(workspace_id.clone(), app.state(), app.clone())
+                        .await
+                        .err()
+                        .map(|err| err.to_string());
+                    emit_action(
+                        &app,
+                        json!({
+                            "action": "connect-workspace",
+                            "workspaceId": workspace_id,
+                            "error": error,
+                        }),
+                    );
+                });
+            }
+        }
+    }
+}
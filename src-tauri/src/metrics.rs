@@ -0,0 +1,619 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager, State};
+use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
+
+use crate::error::AppError;
+use crate::thread_state::{message_thread_id, str_at};
+use crate::{default_session_store_version, extract_app_server_token_delta, now_ms, storage};
+use crate::{delta_status_label, normalize_git_path, open_repository, snapshot_worktree};
+use crate::{AppState, WorkspaceEntry};
+
+const TOOL_ITEM_TYPES: &[&str] = &["commandExecution", "mcpToolCall", "webSearch", "fileChange"];
+const MAX_TURNS_PER_THREAD: usize = 500;
+
+struct ActiveTurn {
+    turn_id: Option<String>,
+    started_at_ms: i64,
+    tokens: i64,
+    tool_calls: u32,
+    files: BTreeSet<String>,
+    before_tree: Option<JoinHandle<Option<String>>>,
+}
+
+#[derive(Default)]
+pub(crate) struct TurnMetricsTracker {
+    active: Mutex<HashMap<(String, String), ActiveTurn>>,
+    store_lock: Mutex<()>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct TurnMetrics {
+    pub(crate) turn_id: Option<String>,
+    pub(crate) started_at_ms: i64,
+    pub(crate) completed_at_ms: i64,
+    pub(crate) duration_ms: i64,
+    tokens: i64,
+    tool_calls: u32,
+    #[serde(default)]
+    files_touched: Vec<String>,
+    #[serde(default)]
+    status: Option<String>,
+    #[serde(default)]
+    pub(crate) changes: Vec<TurnFileChange>,
+    #[serde(default)]
+    pub(crate) before_tree: Option<String>,
+    #[serde(default)]
+    pub(crate) after_tree: Option<String>,
+    #[serde(default)]
+    pub(crate) undone_at_ms: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct TurnFileChange {
+    pub(crate) path: String,
+    #[serde(default)]
+    pub(crate) old_path: Option<String>,
+    pub(crate) status: String,
+    pub(crate) additions: usize,
+    pub(crate) deletions: usize,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct TurnChanges {
+    thread_id: String,
+    turn_index: usize,
+    turn_id: Option<String>,
+    started_at_ms: i64,
+    completed_at_ms: i64,
+    tracked: bool,
+    changes: Vec<TurnFileChange>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct CompactionMetrics {
+    pub(crate) trigger: String,
+    started_at_ms: i64,
+    #[serde(default)]
+    completed_at_ms: Option<i64>,
+    pub(crate) before_tokens: i64,
+    #[serde(default)]
+    pub(crate) after_tokens: Option<i64>,
+    #[serde(default)]
+    pub(crate) error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct MetricsStore {
+    #[serde(default = "default_session_store_version")]
+    version: u32,
+    #[serde(default)]
+    threads: HashMap<String, Vec<TurnMetrics>>,
+    #[serde(default)]
+    compactions: HashMap<String, Vec<CompactionMetrics>>,
+}
+
+impl Default for MetricsStore {
+    fn default() -> Self {
+        Self {
+            version: default_session_store_version(),
+            threads: HashMap::new(),
+            compactions: HashMap::new(),
+        }
+    }
+}
+
+impl CompactionMetrics {
+    pub(crate) fn started(trigger: &str, started_at_ms: i64, before_tokens: i64) -> Self {
+        Self {
+            trigger: trigger.to_string(),
+            started_at_ms,
+            completed_at_ms: None,
+            before_tokens,
+            after_tokens: None,
+            error: None,
+        }
+    }
+
+    pub(crate) fn completed(mut self, after_tokens: i64) -> Self {
+        self.completed_at_ms = Some(now_ms());
+        self.after_tokens = Some(after_tokens);
+        self
+    }
+
+    pub(crate) fn failed(mut self, error: String) -> Self {
+        self.completed_at_ms = Some(now_ms());
+        self.error = Some(error);
+        self
+    }
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+struct MetricsSummary {
+    turn_count: usize,
+    total_duration_ms: i64,
+    average_duration_ms: i64,
+    total_tokens: i64,
+    tool_calls: u32,
+    files_touched: usize,
+    first_turn_at_ms: Option<i64>,
+    last_turn_at_ms: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ThreadMetrics {
+    thread_id: String,
+    turns: Vec<TurnMetrics>,
+    compactions: Vec<CompactionMetrics>,
+    summary: MetricsSummary,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct ThreadMetricsSummary {
+    thread_id: String,
+    summary: MetricsSummary,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct WorkspaceMetrics {
+    workspace_id: String,
+    summary: MetricsSummary,
+    threads: Vec<ThreadMetricsSummary>,
+}
+
+fn workspace_metrics_path(workspace_path: &str) -> PathBuf {
+    PathBuf::from(workspace_path)
+        .join(".codexmonitor")
+        .join("metrics.json")
+}
+
+fn read_metrics(path: &Path) -> Result<MetricsStore, String> {
+    storage::read_json_or_default(path)
+}
+
+fn write_metrics(path: &Path, store: &MetricsStore) -> Result<(), String> {
+    storage::write_json_atomic(path, store)
+}
+
+fn summarize<'a>(turns: impl IntoIterator<Item = &'a TurnMetrics>) -> MetricsSummary {
+    let mut summary = MetricsSummary::default();
+    let mut files: HashSet<&str> = HashSet::new();
+    for turn in turns {
+        summary.turn_count += 1;
+        summary.total_duration_ms += turn.duration_ms;
+        summary.total_tokens += turn.tokens;
+        summary.tool_calls += turn.tool_calls;
+        files.extend(turn.files_touched.iter().map(|path| path.as_str()));
+        summary.first_turn_at_ms = Some(
+            summary
+                .first_turn_at_ms
+                .map_or(turn.started_at_ms, |first| first.min(turn.started_at_ms)),
+        );
+        summary.last_turn_at_ms = Some(
+            summary
+                .last_turn_at_ms
+                .map_or(turn.completed_at_ms, |last| last.max(turn.completed_at_ms)),
+        );
+    }
+    summary.files_touched = files.len();
+    if summary.turn_count > 0 {
+        summary.average_duration_ms = summary.total_duration_ms / summary.turn_count as i64;
+    }
+    summary
+}
+
+fn item_file_paths(item: &Value) -> Vec<String> {
+    item.get("changes")
+        .and_then(|changes| changes.as_array())
+        .map(|changes| {
+            changes
+                .iter()
+                .filter_map(|change| change.get("path").and_then(|path| path.as_str()))
+                .map(|path| path.to_string())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn snapshot_tree(workspace_path: &str) -> Option<String> {
+    let repo = open_repository(workspace_path).ok()?;
+    snapshot_worktree(&repo).ok().map(|tree| tree.to_string())
+}
+
+fn tree_changes(
+    workspace_path: &str,
+    before: &str,
+    after: &str,
+) -> Result<Vec<TurnFileChange>, git2::Error> {
+    let repo = open_repository(workspace_path)?;
+    let before = repo.find_tree(git2::Oid::from_str(before)?)?;
+    let after = repo.find_tree(git2::Oid::from_str(after)?)?;
+    let mut diff = repo.diff_tree_to_tree(Some(&before), Some(&after), None)?;
+    diff.find_similar(None)?;
+    let mut changes = Vec::new();
+    for (index, delta) in diff.deltas().enumerate() {
+        let Some(path) = delta.new_file().path().or_else(|| delta.old_file().path()) else {
+            continue;
+        };
+        let old_path = (delta.status() == git2::Delta::Renamed)
+            .then(|| delta.old_file().path())
+            .flatten()
+            .map(|path| normalize_git_path(&path.to_string_lossy()));
+        let (additions, deletions) = match git2::Patch::from_diff(&diff, index)? {
+            Some(patch) => {
+                let (_, additions, deletions) = patch.line_stats()?;
+                (additions, deletions)
+            }
+            None => (0, 0),
+        };
+        changes.push(TurnFileChange {
+            path: normalize_git_path(&path.to_string_lossy()),
+            old_path,
+            status: delta_status_label(delta.status()).to_string(),
+            additions,
+            deletions,
+        });
+    }
+    Ok(changes)
+}
+
+// Snapshots are plain git trees of the worktree, so turns running at the
+// same time in one workspace share each other's edits.
+async fn turn_changes(
+    workspace_path: Option<String>,
+    before_tree: Option<JoinHandle<Option<String>>>,
+) -> (Option<String>, Option<String>, Vec<TurnFileChange>) {
+    let (Some(workspace_path), Some(before_tree)) = (workspace_path, before_tree) else {
+        return (None, None, Vec::new());
+    };
+    let Some(before) = before_tree.await.ok().flatten() else {
+        return (None, None, Vec::new());
+    };
+    let result = tokio::task::spawn_blocking(move || {
+        let after = snapshot_tree(&workspace_path)?;
+        let changes = tree_changes(&workspace_path, &before, &after)
+            .map_err(|err| tracing::warn!("failed to diff turn snapshots: {err}"))
+            .unwrap_or_default();
+        Some((before, after, changes))
+    })
+    .await
+    .ok()
+    .flatten();
+    match result {
+        Some((before, after, changes)) => (Some(before), Some(after), changes),
+        None => (None, None, Vec::new()),
+    }
+}
+
+async fn workspace_path(app: &AppHandle, workspace_id: &str) -> Option<String> {
+    app.state::<AppState>()
+        .workspaces
+        .lock()
+        .await
+        .get(workspace_id)
+        .map(|entry| entry.path.clone())
+}
+
+async fn update_store(
+    app: &AppHandle,
+    workspace_id: &str,
+    update: impl FnOnce(&mut MetricsStore),
+) -> Result<(), String> {
+    let state = app.state::<AppState>();
+    let Some(workspace_path) = state
+        .workspaces
+        .lock()
+        .await
+        .get(workspace_id)
+        .map(|entry| entry.path.clone())
+    else {
+        return Ok(());
+    };
+    let _guard = state.turn_metrics.store_lock.lock().await;
+    let path = workspace_metrics_path(&workspace_path);
+    let mut store = read_metrics(&path)?;
+    update(&mut store);
+    write_metrics(&path, &store)
+}
+
+async fn persist_turn(app: &AppHandle, workspace_id: &str, thread_id: String, turn: TurnMetrics) {
+    let result = update_store(app, workspace_id, |store| {
+        let turns = store.threads.entry(thread_id).or_default();
+        turns.push(turn);
+        if turns.len() > MAX_TURNS_PER_THREAD {
+            let excess = turns.len() - MAX_TURNS_PER_THREAD;
+            turns.drain(..excess);
+        }
+    })
+    .await;
+    if let Err(err) = result {
+        tracing::warn!(workspace_id, "failed to persist turn metrics: {err}");
+    }
+}
+
+pub(crate) fn read_workspace_turns(
+    workspace_path: &str,
+) -> Result<HashMap<String, Vec<TurnMetrics>>, String> {
+    Ok(read_metrics(&workspace_metrics_path(workspace_path))?.threads)
+}
+
+fn merge_thread(store: &mut MetricsStore, source: &mut MetricsStore, thread_id: &str) {
+    if let Some(incoming) = source.threads.remove(thread_id) {
+        let turns = store.threads.entry(thread_id.to_string()).or_default();
+        for turn in incoming {
+            let duplicate = turns.iter().any(|existing| {
+                existing.started_at_ms == turn.started_at_ms && existing.turn_id == turn.turn_id
+            });
+            if !duplicate {
+                turns.push(turn);
+            }
+        }
+        turns.sort_by_key(|turn| turn.started_at_ms);
+        if turns.len() > MAX_TURNS_PER_THREAD {
+            let excess = turns.len() - MAX_TURNS_PER_THREAD;
+            turns.drain(..excess);
+        }
+    }
+    if let Some(incoming) = source.compactions.remove(thread_id) {
+        let compactions = store.compactions.entry(thread_id.to_string()).or_default();
+        for compaction in incoming {
+            if !compactions
+                .iter()
+                .any(|existing| existing.started_at_ms == compaction.started_at_ms)
+            {
+                compactions.push(compaction);
+            }
+        }
+        compactions.sort_by_key(|compaction| compaction.started_at_ms);
+    }
+}
+
+// Turns recorded under both workspaces (same repo, same threads) are kept
+// once. With `thread_ids` only those threads move and the source keeps the
+// rest; without it the source store is left as it was.
+pub(crate) async fn merge_workspace_metrics(
+    state: &AppState,
+    from_path: &str,
+    to_path: &str,
+    thread_ids: Option<&[String]>,
+) -> Result<(), String> {
+    let _guard = state.turn_metrics.store_lock.lock().await;
+    let source_path = workspace_metrics_path(from_path);
+    let mut source = read_metrics(&source_path)?;
+    let path = workspace_metrics_path(to_path);
+    let mut store = read_metrics(&path)?;
+    let selected: Vec<String> = match thread_ids {
+        Some(thread_ids) => thread_ids.to_vec(),
+        None => source
+            .threads
+            .keys()
+            .chain(source.compactions.keys())
+            .cloned()
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect(),
+    };
+    for thread_id in &selected {
+        merge_thread(&mut store, &mut source, thread_id);
+    }
+    write_metrics(&path, &store)?;
+    if thread_ids.is_some() {
+        write_metrics(&source_path, &source)?;
+    }
+    Ok(())
+}
+
+pub(crate) async fn mark_turn_undone(
+    app: &AppHandle,
+    workspace_id: &str,
+    thread_id: &str,
+    turn_index: usize,
+) {
+    let result = update_store(app, workspace_id, |store| {
+        if let Some(turn) = store
+            .threads
+            .get_mut(thread_id)
+            .and_then(|turns| turns.get_mut(turn_index))
+        {
+            turn.undone_at_ms = Some(now_ms());
+        }
+    })
+    .await;
+    if let Err(err) = result {
+        tracing::warn!(workspace_id, "failed to record turn undo: {err}");
+    }
+}
+
+pub(crate) async fn record_compaction(
+    app: &AppHandle,
+    workspace_id: &str,
+    thread_id: String,
+    compaction: CompactionMetrics,
+) {
+    let result = update_store(app, workspace_id, |store| {
+        let compactions = store.compactions.entry(thread_id).or_default();
+        compactions.push(compaction);
+        if compactions.len() > MAX_TURNS_PER_THREAD {
+            let excess = compactions.len() - MAX_TURNS_PER_THREAD;
+            compactions.drain(..excess);
+        }
+    })
+    .await;
+    if let Err(err) = result {
+        tracing::warn!(workspace_id, "failed to persist compaction metrics: {err}");
+    }
+}
+
+pub(crate) async fn observe(app: &AppHandle, workspace_id: &str, message: &Value) {
+    let Some(method) = message.get("method").and_then(|method| method.as_str()) else {
+        return;
+    };
+    let params = message.get("params").unwrap_or(&Value::Null);
+    let Some(thread_id) = message_thread_id(params) else {
+        return;
+    };
+    let key = (workspace_id.to_string(), thread_id);
+    let state = app.state::<AppState>();
+    // Snapshots write blobs into the repo's object store, so per-turn diffs
+    // share the opt-in with worktree checkpoints.
+    let snapshots =
+        method == "turn/started" && state.settings.lock().await.turn_checkpoints_enabled;
+    let before_tree = if snapshots {
+        workspace_path(app, workspace_id)
+            .await
+            .map(|path| tokio::task::spawn_blocking(move || snapshot_tree(&path)))
+    } else {
+        None
+    };
+    let mut active = state.turn_metrics.active.lock().await;
+    match method {
+        "turn/started" => {
+            active.insert(
+                key,
+                ActiveTurn {
+                    turn_id: str_at(params, "/turn/id")
+                        .or_else(|| str_at(params, "/turnId"))
+                        .map(|id| id.to_string()),
+                    started_at_ms: now_ms(),
+                    tokens: 0,
+                    tool_calls: 0,
+                    files: BTreeSet::new(),
+                    before_tree,
+                },
+            );
+        }
+        "thread/tokenUsage/updated" => {
+            if let (Some(turn), Some(tokens)) =
+                (active.get_mut(&key), extract_app_server_token_delta(message))
+            {
+                turn.tokens += tokens;
+            }
+        }
+        "item/completed" => {
+            let Some(turn) = active.get_mut(&key) else {
+                return;
+            };
+            let item = params.get("item").unwrap_or(&Value::Null);
+            let kind = item.get("type").and_then(|kind| kind.as_str()).unwrap_or("");
+            if TOOL_ITEM_TYPES.contains(&kind) {
+                turn.tool_calls += 1;
+            }
+            if kind == "fileChange" {
+                turn.files.extend(item_file_paths(item));
+            }
+        }
+        "turn/completed" => {
+            let Some(turn) = active.remove(&key) else {
+                return;
+            };
+            drop(active);
+            let (before_tree, after_tree, changes) =
+                turn_changes(workspace_path(app, workspace_id).await, turn.before_tree).await;
+            let completed_at_ms = now_ms();
+            let metrics = TurnMetrics {
+                turn_id: turn.turn_id,
+                started_at_ms: turn.started_at_ms,
+                completed_at_ms,
+                duration_ms: completed_at_ms - turn.started_at_ms,
+                tokens: turn.tokens,
+                tool_calls: turn.tool_calls,
+                files_touched: turn.files.into_iter().collect(),
+                status: str_at(params, "/turn/status").map(|status| status.to_string()),
+                changes,
+                before_tree,
+                after_tree,
+                undone_at_ms: None,
+            };
+            persist_turn(app, workspace_id, key.1, metrics).await;
+        }
+        _ => {}
+    }
+}
+
+async fn workspace_entry(state: &AppState, workspace_id: &str) -> Result<WorkspaceEntry, AppError> {
+    state
+        .workspaces
+        .lock()
+        .await
+        .get(workspace_id)
+        .cloned()
+        .ok_or(AppError::WorkspaceNotFound)
+}
+
+#[tauri::command]
+pub(crate) async fn get_thread_metrics(
+    workspace_id: String,
+    thread_id: String,
+    state: State<'_, AppState>,
+) -> Result<ThreadMetrics, AppError> {
+    let entry = workspace_entry(&state, &workspace_id).await?;
+    let mut store = read_metrics(&workspace_metrics_path(&entry.path))?;
+    let turns = store.threads.remove(&thread_id).unwrap_or_default();
+    let compactions = store.compactions.remove(&thread_id).unwrap_or_default();
+    Ok(ThreadMetrics {
+        summary: summarize(&turns),
+        thread_id,
+        turns,
+        compactions,
+    })
+}
+
+#[tauri::command]
+pub(crate) async fn get_workspace_metrics(
+    workspace_id: String,
+    state: State<'_, AppState>,
+) -> Result<WorkspaceMetrics, AppError> {
+    let entry = workspace_entry(&state, &workspace_id).await?;
+    let store = read_metrics(&workspace_metrics_path(&entry.path))?;
+    let mut threads: Vec<ThreadMetricsSummary> = store
+        .threads
+        .iter()
+        .map(|(thread_id, turns)| ThreadMetricsSummary {
+            thread_id: thread_id.clone(),
+            summary: summarize(turns),
+        })
+        .collect();
+    threads.sort_by_key(|thread| std::cmp::Reverse(thread.summary.last_turn_at_ms));
+    Ok(WorkspaceMetrics {
+        workspace_id,
+        summary: summarize(store.threads.values().flatten()),
+        threads,
+    })
+}
+
+#[tauri::command]
+pub(crate) async fn get_turn_changes(
+    workspace_id: String,
+    thread_id: String,
+    turn_index: usize,
+    state: State<'_, AppState>,
+) -> Result<TurnChanges, AppError> {
+    let entry = workspace_entry(&state, &workspace_id).await?;
+    let mut store = read_metrics(&workspace_metrics_path(&entry.path))?;
+    let turn = store
+        .threads
+        .remove(&thread_id)
+        .and_then(|mut turns| (turn_index < turns.len()).then(|| turns.swap_remove(turn_index)))
+        .ok_or("turn not found")?;
+    Ok(TurnChanges {
+        thread_id,
+        turn_index,
+        turn_id: turn.turn_id,
+        started_at_ms: turn.started_at_ms,
+        completed_at_ms: turn.completed_at_ms,
+        tracked: turn.before_tree.is_some() && turn.after_tree.is_some(),
+        changes: turn.changes,
+    })
+}
@@ -0,0 +1,311 @@
+use chrono::{Local, NaiveTime};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::Mutex;
+
+use crate::thread_state::{message_thread_id, str_at};
+use crate::{hooks, i18n, read_workspace_sessions, speech, workspace_sessions_path, AppState};
+
+const BODY_LIMIT: usize = 160;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) enum NotificationEvent {
+    TurnComplete,
+    ApprovalNeeded,
+    Error,
+    RateLimit,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct NotificationChannels {
+    #[serde(default)]
+    pub(crate) native: bool,
+    #[serde(default)]
+    pub(crate) sound: bool,
+    #[serde(default)]
+    pub(crate) badge: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct QuietHours {
+    #[serde(default)]
+    enabled: bool,
+    #[serde(default = "default_quiet_hours_start")]
+    start: String,
+    #[serde(default = "default_quiet_hours_end")]
+    end: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct NotificationConfig {
+    #[serde(default)]
+    pub(crate) turn_complete: NotificationChannels,
+    #[serde(default = "default_approval_channels")]
+    pub(crate) approval_needed: NotificationChannels,
+    #[serde(default)]
+    pub(crate) error: NotificationChannels,
+    #[serde(default)]
+    pub(crate) rate_limit: NotificationChannels,
+    #[serde(default)]
+    quiet_hours: QuietHours,
+    #[serde(default)]
+    pub(crate) speech: speech::SpeechConfig,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct NotificationDispatch {
+    event: NotificationEvent,
+    title: String,
+    body: String,
+    workspace_id: Option<String>,
+    thread_id: Option<String>,
+    native: bool,
+    sound: bool,
+    badge: bool,
+    quiet: bool,
+}
+
+#[derive(Default)]
+pub(crate) struct Notifier {
+    last_agent_messages: Mutex<HashMap<(String, String), String>>,
+    failed_turns: Mutex<HashSet<(String, String)>>,
+}
+
+fn default_quiet_hours_start() -> String {
+    "22:00".to_string()
+}
+
+fn default_quiet_hours_end() -> String {
+    "07:00".to_string()
+}
+
+fn default_approval_channels() -> NotificationChannels {
+    NotificationChannels {
+        native: false,
+        sound: false,
+        badge: true,
+    }
+}
+
+impl Default for QuietHours {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            start: default_quiet_hours_start(),
+            end: default_quiet_hours_end(),
+        }
+    }
+}
+
+impl Default for NotificationConfig {
+    fn default() -> Self {
+        Self {
+            turn_complete: NotificationChannels::default(),
+            approval_needed: default_approval_channels(),
+            error: NotificationChannels::default(),
+            rate_limit: NotificationChannels::default(),
+            quiet_hours: QuietHours::default(),
+            speech: speech::SpeechConfig::default(),
+        }
+    }
+}
+
+impl NotificationConfig {
+    pub(crate) fn channels(&self, event: NotificationEvent) -> NotificationChannels {
+        match event {
+            NotificationEvent::TurnComplete => self.turn_complete,
+            NotificationEvent::ApprovalNeeded => self.approval_needed,
+            NotificationEvent::Error => self.error,
+            NotificationEvent::RateLimit => self.rate_limit,
+        }
+    }
+}
+
+impl QuietHours {
+    fn contains(&self, now: NaiveTime) -> bool {
+        if !self.enabled {
+            return false;
+        }
+        let (Ok(start), Ok(end)) = (
+            NaiveTime::parse_from_str(&self.start, "%H:%M"),
+            NaiveTime::parse_from_str(&self.end, "%H:%M"),
+        ) else {
+            return false;
+        };
+        if start <= end {
+            now >= start && now < end
+        } else {
+            now >= start || now < end
+        }
+    }
+}
+
+fn format_body(text: &str, fallback: &str) -> String {
+    let compact = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if compact.is_empty() {
+        return fallback.to_string();
+    }
+    match compact.char_indices().nth(BODY_LIMIT) {
+        Some((index, _)) => format!("{}…", &compact[..index]),
+        None => compact,
+    }
+}
+
+async fn thread_title(app: &AppHandle, workspace_id: &str, thread_id: &str) -> String {
+    let state = app.state::<AppState>();
+    let fallback = i18n::t(i18n::app_lang(&state).await, "notification.agent");
+    let Some(entry) = state.workspaces.lock().await.get(workspace_id).cloned() else {
+        return fallback;
+    };
+    let thread_name = read_workspace_sessions(&workspace_sessions_path(&entry.path))
+        .ok()
+        .and_then(|mut store| store.sessions.remove(thread_id))
+        .map(|metadata| metadata.name)
+        .filter(|name| !name.trim().is_empty())
+        .unwrap_or(fallback);
+    format!("{} · {}", entry.name, thread_name)
+}
+
+pub(crate) async fn dispatch(
+    app: &AppHandle,
+    event: NotificationEvent,
+    workspace_id: Option<String>,
+    thread_id: Option<String>,
+    title: String,
+    body: String,
+) {
+    let config = app.state::<AppState>().settings.lock().await.notifications.clone();
+    let quiet = config.quiet_hours.contains(Local::now().time());
+    if event == NotificationEvent::TurnComplete {
+        speech::speak_completion(app, workspace_id.as_deref(), &body, quiet).await;
+    }
+    let channels = config.channels(event);
+    if !channels.native && !channels.sound && !channels.badge {
+        return;
+    }
+    let payload = NotificationDispatch {
+        event,
+        title,
+        body,
+        workspace_id,
+        thread_id,
+        native: channels.native && !quiet,
+        sound: channels.sound && !quiet,
+        badge: channels.badge,
+        quiet,
+    };
+    let _ = app.emit("notification", payload);
+}
+
+async fn dispatch_for_thread(
+    app: &AppHandle,
+    event: NotificationEvent,
+    workspace_id: &str,
+    thread_id: String,
+    body: String,
+) {
+    let title = thread_title(app, workspace_id, &thread_id).await;
+    dispatch(
+        app,
+        event,
+        Some(workspace_id.to_string()),
+        Some(thread_id),
+        title,
+        body,
+    )
+    .await;
+}
+
+fn error_text(params: &Value) -> Option<&str> {
+    str_at(params, "/error/message")
+        .or_else(|| str_at(params, "/turn/error/message"))
+        .or_else(|| str_at(params, "/error"))
+        .or_else(|| str_at(params, "/message"))
+}
+
+pub(crate) async fn observe(app: &AppHandle, workspace_id: &str, message: &Value) {
+    let Some(method) = message.get("method").and_then(|method| method.as_str()) else {
+        return;
+    };
+    let params = message.get("params").unwrap_or(&Value::Null);
+    let Some(thread_id) = message_thread_id(params) else {
+        return;
+    };
+    let state = app.state::<AppState>();
+    if let Some(request) = hooks::approval_request_method(message) {
+        let lang = i18n::app_lang(&state).await;
+        let body = str_at(params, "/command")
+            .or_else(|| str_at(params, "/reason"))
+            .map(|text| format_body(text, &i18n::t(lang, "notification.approvalRequested")))
+            .unwrap_or_else(|| {
+                i18n::t_with(lang, "notification.approvalRequestedFor", &[("request", request)])
+            });
+        dispatch_for_thread(app, NotificationEvent::ApprovalNeeded, workspace_id, thread_id, body)
+            .await;
+        return;
+    }
+    let key = (workspace_id.to_string(), thread_id);
+    let notifier = &state.notifier;
+    match method {
+        "item/completed" => {
+            let item = params.get("item").unwrap_or(&Value::Null);
+            if item.get("type").and_then(|kind| kind.as_str()) != Some("agentMessage") {
+                return;
+            }
+            if let Some(text) = item.get("text").and_then(|text| text.as_str()) {
+                notifier
+                    .last_agent_messages
+                    .lock()
+                    .await
+                    .insert(key, text.to_string());
+            }
+        }
+        "turn/started" => {
+            notifier.last_agent_messages.lock().await.remove(&key);
+            notifier.failed_turns.lock().await.remove(&key);
+        }
+        "turn/completed" => {
+            let text = notifier.last_agent_messages.lock().await.remove(&key);
+            let already_reported = notifier.failed_turns.lock().await.remove(&key);
+            let (workspace_id, thread_id) = key;
+            let lang = i18n::app_lang(&state).await;
+            if str_at(params, "/turn/status") == Some("failed") {
+                if already_reported {
+                    return;
+                }
+                let fallback = i18n::t(lang, "notification.turnFailed");
+                let body = format_body(error_text(params).unwrap_or_default(), &fallback);
+                dispatch_for_thread(app, NotificationEvent::Error, &workspace_id, thread_id, body)
+                    .await;
+                return;
+            }
+            let fallback = i18n::t(lang, "notification.replyFinished");
+            let body = format_body(&text.unwrap_or_default(), &fallback);
+            dispatch_for_thread(
+                app,
+                NotificationEvent::TurnComplete,
+                &workspace_id,
+                thread_id,
+                body,
+            )
+            .await;
+        }
+        "error" => {
+            if params.get("willRetry").and_then(|value| value.as_bool()) == Some(true) {
+                return;
+            }
+            notifier.failed_turns.lock().await.insert(key.clone());
+            let fallback = i18n::t(i18n::app_lang(&state).await, "notification.turnFailed");
+            let body = format_body(error_text(params).unwrap_or_default(), &fallback);
+            dispatch_for_thread(app, NotificationEvent::Error, workspace_id, key.1, body).await;
+        }
+        _ => {}
+    }
+}
@@ -0,0 +1,75 @@
+use serde_json::{json, Value};
+use std::sync::atomic::Ordering;
+use tauri::{AppHandle, Emitter, State};
+
+use crate::error::AppError;
+use crate::thread_state::{message_thread_id, str_at};
+use crate::{audit, hooks, write_workspaces, AppState, WorkspaceEntry, WorkspaceSession};
+
+pub(crate) const READ_ONLY_SANDBOX: &str = "read-only";
+
+pub(crate) fn ensure_writable(entry: &WorkspaceEntry, action: &str) -> Result<(), AppError> {
+    if entry.observer_mode {
+        return Err(AppError::ObserverMode {
+            message: format!("{} is in observer mode; {action} is disabled.", entry.name),
+        });
+    }
+    Ok(())
+}
+
+pub(crate) fn is_observing(session: &WorkspaceSession) -> bool {
+    session.observer_mode.load(Ordering::Relaxed)
+}
+
+// Observer workspaces run with approvals on-request and a read-only sandbox,
+// so every approval the agent asks for is a write or exec and gets declined.
+// Runs on the session's reader task, so it must never wait on `state.sessions`.
+pub(crate) async fn intercept(
+    app: &AppHandle,
+    session: &WorkspaceSession,
+    message: &Value,
+) -> bool {
+    if hooks::approval_request_method(message).is_none() || !is_observing(session) {
+        return false;
+    }
+    let Some(request_id) = message.get("id").and_then(Value::as_u64) else {
+        return false;
+    };
+    let workspace_id = session.entry.id.as_str();
+    let result = json!({ "decision": "decline" });
+    if let Err(err) = session.send_response(request_id, result.clone()).await {
+        tracing::warn!(%workspace_id, request_id, "failed to decline observer approval: {err}");
+        return false;
+    }
+    audit::record_decision(app, workspace_id, request_id, &result).await;
+    let params = message.get("params").unwrap_or(&Value::Null);
+    let _ = app.emit(
+        "observer-approval-declined",
+        json!({
+            "workspaceId": workspace_id,
+            "threadId": message_thread_id(params),
+            "requestId": request_id,
+            "method": str_at(message, "/method"),
+        }),
+    );
+    true
+}
+
+#[tauri::command]
+pub(crate) async fn set_workspace_observer_mode(
+    id: String,
+    enabled: bool,
+    state: State<'_, AppState>,
+) -> Result<bool, AppError> {
+    {
+        let mut workspaces = state.workspaces.lock().await;
+        let entry = workspaces.get_mut(&id).ok_or(AppError::WorkspaceNotFound)?;
+        entry.observer_mode = enabled;
+        let list: Vec<_> = workspaces.values().cloned().collect();
+        write_workspaces(&state.storage_path, &list)?;
+    }
+    if let Some(session) = state.sessions.lock().await.get(&id) {
+        session.observer_mode.store(enabled, Ordering::Relaxed);
+    }
+    Ok(enabled)
+}
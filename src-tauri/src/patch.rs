@@ -0,0 +1,256 @@
+use git2::{ApplyLocation, ApplyOptions, Delta, Diff, Patch, Repository};
+use serde::Serialize;
+use std::cell::Cell;
+use std::path::{Component, Path};
+use tauri::State;
+
+use crate::error::AppError;
+use crate::{normalize_path, observer, open_repository, workspace_entry, AppState};
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct PatchHunkResult {
+    index: usize,
+    header: String,
+    old_start: u32,
+    old_lines: u32,
+    new_start: u32,
+    new_lines: u32,
+    ok: bool,
+    error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct PatchFileResult {
+    path: String,
+    old_path: Option<String>,
+    status: &'static str,
+    hunks: Vec<PatchHunkResult>,
+    ok: bool,
+    error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct PatchApplyResult {
+    dry_run: bool,
+    applied: bool,
+    files: Vec<PatchFileResult>,
+    error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct PatchPreview {
+    files: Vec<PatchFileResult>,
+    total_hunks: usize,
+    clean_hunks: usize,
+    conflicting_hunks: usize,
+    created: Vec<String>,
+    deleted: Vec<String>,
+    renamed: Vec<String>,
+    applies_cleanly: bool,
+}
+
+fn delta_status(status: Delta) -> &'static str {
+    match status {
+        Delta::Added => "added",
+        Delta::Deleted => "deleted",
+        Delta::Renamed => "renamed",
+        Delta::Copied => "copied",
+        _ => "modified",
+    }
+}
+
+fn validate_patch_path(path: &Path) -> Result<(), AppError> {
+    let escapes = path.components().any(|component| {
+        matches!(
+            component,
+            Component::ParentDir | Component::RootDir | Component::Prefix(_)
+        )
+    });
+    if escapes {
+        let message = format!("patch touches a path outside the workspace: {}", path.display());
+        return Err(message.into());
+    }
+    Ok(())
+}
+
+fn check_hunk(
+    repo: &Repository,
+    diff: &Diff<'_>,
+    delta_index: usize,
+    hunk_index: usize,
+) -> Result<(), git2::Error> {
+    let delta_seen = Cell::new(0usize);
+    let hunk_seen = Cell::new(0usize);
+    let mut options = ApplyOptions::new();
+    options
+        .check(true)
+        .delta_callback(|_| {
+            let current = delta_seen.get();
+            delta_seen.set(current + 1);
+            current == delta_index
+        })
+        .hunk_callback(|_| {
+            let current = hunk_seen.get();
+            hunk_seen.set(current + 1);
+            current == hunk_index
+        });
+    repo.apply(diff, ApplyLocation::WorkDir, Some(&mut options))
+}
+
+fn check_delta(repo: &Repository, diff: &Diff<'_>, delta_index: usize) -> Result<(), git2::Error> {
+    let delta_seen = Cell::new(0usize);
+    let mut options = ApplyOptions::new();
+    options.check(true).delta_callback(|_| {
+        let current = delta_seen.get();
+        delta_seen.set(current + 1);
+        current == delta_index
+    });
+    repo.apply(diff, ApplyLocation::WorkDir, Some(&mut options))
+}
+
+fn file_result(
+    repo: &Repository,
+    diff: &Diff<'_>,
+    delta_index: usize,
+) -> Result<PatchFileResult, AppError> {
+    let delta = diff
+        .get_delta(delta_index)
+        .ok_or_else(|| AppError::from("patch delta disappeared while checking"))?;
+    let new_path = delta.new_file().path().map(Path::to_path_buf);
+    let old_path = delta.old_file().path().map(Path::to_path_buf);
+    for path in new_path.iter().chain(old_path.iter()) {
+        validate_patch_path(path)?;
+    }
+    let path = new_path
+        .as_deref()
+        .or(old_path.as_deref())
+        .map(normalize_path)
+        .unwrap_or_default();
+    let old_path = old_path
+        .as_deref()
+        .map(normalize_path)
+        .filter(|old| *old != path);
+    let status = delta_status(delta.status());
+
+    let mut hunks = Vec::new();
+    if let Some(patch) = Patch::from_diff(diff, delta_index)? {
+        for index in 0..patch.num_hunks() {
+            let (hunk, _) = patch.hunk(index)?;
+            let result = check_hunk(repo, diff, delta_index, index);
+            hunks.push(PatchHunkResult {
+                index,
+                header: String::from_utf8_lossy(hunk.header()).trim_end().to_string(),
+                old_start: hunk.old_start(),
+                old_lines: hunk.old_lines(),
+                new_start: hunk.new_start(),
+                new_lines: hunk.new_lines(),
+                ok: result.is_ok(),
+                error: result.err().map(|err| err.message().to_string()),
+            });
+        }
+    }
+    // File-level operations (creation, deletion, renames) can fail even when
+    // every hunk would apply on its own.
+    let file_check = check_delta(repo, diff, delta_index);
+    Ok(PatchFileResult {
+        path,
+        old_path,
+        status,
+        ok: file_check.is_ok() && hunks.iter().all(|hunk| hunk.ok),
+        error: file_check.err().map(|err| err.message().to_string()),
+        hunks,
+    })
+}
+
+fn parse_patch(patch_text: &str) -> Result<Diff<'static>, AppError> {
+    if patch_text.trim().is_empty() {
+        return Err("Patch is empty.".into());
+    }
+    let mut text = patch_text.replace("\r\n", "\n");
+    if !text.ends_with('\n') {
+        text.push('\n');
+    }
+    let diff = Diff::from_buffer(text.as_bytes())
+        .map_err(|err| AppError::from(format!("Not a valid unified diff: {}", err.message())))?;
+    if diff.deltas().len() == 0 {
+        return Err("Patch does not contain any file changes.".into());
+    }
+    Ok(diff)
+}
+
+fn check_files(repo: &Repository, diff: &Diff<'_>) -> Result<Vec<PatchFileResult>, AppError> {
+    (0..diff.deltas().len())
+        .map(|index| file_result(repo, diff, index))
+        .collect()
+}
+
+#[tauri::command]
+pub(crate) async fn apply_patch(
+    workspace_id: String,
+    patch_text: String,
+    dry_run: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<PatchApplyResult, AppError> {
+    let dry_run = dry_run.unwrap_or(false);
+    let diff = parse_patch(&patch_text)?;
+    let entry = workspace_entry(&state, &workspace_id).await?;
+    if !dry_run {
+        observer::ensure_writable(&entry, "applying patches")?;
+    }
+    let repo = open_repository(&entry.path)?;
+    let files = check_files(&repo, &diff)?;
+    let all_ok = files.iter().all(|file| file.ok);
+    let mut result = PatchApplyResult {
+        dry_run,
+        applied: false,
+        files,
+        error: None,
+    };
+    if !all_ok {
+        result.error = Some("Some hunks do not apply; nothing was changed.".to_string());
+        return Ok(result);
+    }
+    if dry_run {
+        return Ok(result);
+    }
+    repo.apply(&diff, ApplyLocation::WorkDir, None)?;
+    result.applied = true;
+    Ok(result)
+}
+
+#[tauri::command]
+pub(crate) async fn preview_patch(
+    workspace_id: String,
+    patch_text: String,
+    state: State<'_, AppState>,
+) -> Result<PatchPreview, AppError> {
+    let diff = parse_patch(&patch_text)?;
+    let entry = workspace_entry(&state, &workspace_id).await?;
+    let repo = open_repository(&entry.path)?;
+    let files = check_files(&repo, &diff)?;
+
+    let hunks = files.iter().flat_map(|file| file.hunks.iter());
+    let total_hunks = hunks.clone().count();
+    let clean_hunks = hunks.filter(|hunk| hunk.ok).count();
+    let paths_with = |status: &str| -> Vec<String> {
+        files
+            .iter()
+            .filter(|file| file.status == status)
+            .map(|file| file.path.clone())
+            .collect()
+    };
+    Ok(PatchPreview {
+        total_hunks,
+        clean_hunks,
+        conflicting_hunks: total_hunks - clean_hunks,
+        created: paths_with("added"),
+        deleted: paths_with("deleted"),
+        renamed: paths_with("renamed"),
+        applies_cleanly: files.iter().all(|file| file.ok),
+        files,
+    })
+}
@@ -0,0 +1,295 @@
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+use tauri::{AppHandle, Emitter, Manager, State};
+use tokio::sync::Mutex;
+
+use crate::error::AppError;
+use crate::thread_state::{message_thread_id, str_at};
+use crate::{
+    audit, hooks, mark_file_index_stale, storage, workspace_entry, AppState, LocalImageInput,
+    WorkspaceSession,
+};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct PathGuardConfig {
+    #[serde(default)]
+    deny: Vec<String>,
+    #[serde(default)]
+    allow: Vec<String>,
+}
+
+#[derive(Default)]
+pub(crate) struct PathGuards {
+    items: Mutex<HashMap<(String, String), Vec<String>>>,
+}
+
+pub(crate) struct PathGuard {
+    root: PathBuf,
+    matcher: Option<Gitignore>,
+}
+
+fn path_guard_config_path(workspace_path: &str) -> PathBuf {
+    PathBuf::from(workspace_path)
+        .join(".codexmonitor")
+        .join("path-guard.json")
+}
+
+fn read_path_guard_config(path: &Path) -> Result<PathGuardConfig, String> {
+    storage::read_json_or_default(path)
+}
+
+fn write_path_guard_config(path: &Path, config: &PathGuardConfig) -> Result<(), String> {
+    storage::write_json_atomic(path, config)
+}
+
+fn build_matcher(root: &Path, config: &PathGuardConfig) -> Result<Option<Gitignore>, String> {
+    if config.deny.is_empty() {
+        return Ok(None);
+    }
+    let mut builder = GitignoreBuilder::new(root);
+    for glob in &config.deny {
+        builder.add_line(None, glob).map_err(|e| e.to_string())?;
+    }
+    for glob in &config.allow {
+        let glob = glob.trim_start_matches('!');
+        builder
+            .add_line(None, &format!("!{glob}"))
+            .map_err(|e| e.to_string())?;
+    }
+    builder.build().map(Some).map_err(|e| e.to_string())
+}
+
+fn clean_globs(globs: Vec<String>) -> Vec<String> {
+    globs
+        .into_iter()
+        .map(|glob| glob.trim().to_string())
+        .filter(|glob| !glob.is_empty())
+        .collect()
+}
+
+impl PathGuard {
+    pub(crate) fn load(workspace_path: &str) -> PathGuard {
+        let root = PathBuf::from(workspace_path);
+        let matcher = read_path_guard_config(&path_guard_config_path(workspace_path))
+            .and_then(|config| build_matcher(&root, &config))
+            .unwrap_or_else(|err| {
+                tracing::warn!(workspace_path, "invalid path guard config: {err}");
+                None
+            });
+        PathGuard { root, matcher }
+    }
+
+    pub(crate) fn denies(&self, path: &str) -> bool {
+        let Some(matcher) = &self.matcher else {
+            return false;
+        };
+        let path = Path::new(path);
+        let relative = if path.is_absolute() {
+            match path.strip_prefix(&self.root) {
+                Ok(relative) => relative,
+                Err(_) => return false,
+            }
+        } else {
+            path
+        };
+        if relative
+            .components()
+            .any(|component| !matches!(component, Component::Normal(_) | Component::CurDir))
+        {
+            return false;
+        }
+        let relative: PathBuf = relative
+            .components()
+            .filter(|component| matches!(component, Component::Normal(_)))
+            .collect();
+        if relative.as_os_str().is_empty() {
+            return false;
+        }
+        matcher
+            .matched_path_or_any_parents(&relative, false)
+            .is_ignore()
+    }
+
+    pub(crate) fn denied<'a>(&self, paths: impl IntoIterator<Item = &'a str>) -> Vec<String> {
+        paths
+            .into_iter()
+            .filter(|path| self.denies(path))
+            .map(str::to_string)
+            .collect()
+    }
+}
+
+fn change_paths(item: &Value) -> Vec<String> {
+    item.get("changes")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|change| str_at(change, "/path"))
+        .map(str::to_string)
+        .collect()
+}
+
+fn command_tokens(params: &Value) -> Vec<String> {
+    let command = match params.get("command") {
+        Some(Value::Array(parts)) => parts
+            .iter()
+            .filter_map(Value::as_str)
+            .collect::<Vec<_>>()
+            .join(" "),
+        Some(Value::String(command)) => command.clone(),
+        _ => return Vec::new(),
+    };
+    command
+        .split(|c: char| c.is_whitespace() || matches!(c, ';' | '|' | '&' | '<' | '>'))
+        .map(|token| token.trim_matches(|c| c == '"' || c == '\''))
+        .filter(|token| !token.is_empty() && !token.starts_with('-'))
+        .map(str::to_string)
+        .collect()
+}
+
+pub(crate) fn filter_attachments(
+    app: &AppHandle,
+    workspace_id: &str,
+    workspace_path: &str,
+    thread_id: &str,
+    attachments: Vec<LocalImageInput>,
+) -> Vec<LocalImageInput> {
+    let guard = PathGuard::load(workspace_path);
+    let (denied, allowed): (Vec<_>, Vec<_>) = attachments
+        .into_iter()
+        .partition(|attachment| guard.denies(&attachment.path));
+    if !denied.is_empty() {
+        let _ = app.emit(
+            "path-guard-blocked",
+            json!({
+                "workspaceId": workspace_id,
+                "threadId": thread_id,
+                "kind": "attachment",
+                "paths": denied.iter().map(|attachment| &attachment.path).collect::<Vec<_>>(),
+            }),
+        );
+    }
+    allowed
+}
+
+async fn decline(
+    app: &AppHandle,
+    session: &WorkspaceSession,
+    message: &Value,
+    paths: Vec<String>,
+) -> bool {
+    let Some(request_id) = message.get("id").and_then(Value::as_u64) else {
+        return false;
+    };
+    let workspace_id = session.entry.id.as_str();
+    let result = json!({ "decision": "decline" });
+    if let Err(err) = session.send_response(request_id, result.clone()).await {
+        tracing::warn!(%workspace_id, request_id, "failed to decline guarded approval: {err}");
+        return false;
+    }
+    audit::record_decision(app, workspace_id, request_id, &result).await;
+    let params = message.get("params").unwrap_or(&Value::Null);
+    let _ = app.emit(
+        "path-guard-blocked",
+        json!({
+            "workspaceId": workspace_id,
+            "threadId": message_thread_id(params),
+            "kind": "approval",
+            "requestId": request_id,
+            "method": str_at(message, "/method"),
+            "paths": paths,
+        }),
+    );
+    true
+}
+
+// Approval requests touching denied paths are declined here and never
+// reach the UI; agent edits that still land on them are reported. Runs on the
+// session's reader task, so it must never wait on `state.sessions`.
+pub(crate) async fn intercept(
+    app: &AppHandle,
+    session: &WorkspaceSession,
+    message: &Value,
+) -> bool {
+    let params = message.get("params").unwrap_or(&Value::Null);
+    let method = str_at(message, "/method").unwrap_or_default();
+    let item = params.get("item").unwrap_or(&Value::Null);
+    let is_file_change = str_at(item, "/type") == Some("fileChange");
+    let is_approval = hooks::approval_request_method(message).is_some();
+    let tracked_item = is_file_change && matches!(method, "item/started" | "item/completed");
+    if !is_approval && !tracked_item {
+        return false;
+    }
+    let state = app.state::<AppState>();
+    let workspace_id = session.entry.id.as_str();
+    let guard = PathGuard::load(&session.entry.path);
+    if guard.matcher.is_none() {
+        return false;
+    }
+
+    let item_key = |item_id: &str| (workspace_id.to_string(), item_id.to_string());
+    if is_approval {
+        let mut candidates = change_paths(params);
+        if let Some(item_id) = str_at(params, "/itemId") {
+            if let Some(paths) = state.path_guards.items.lock().await.get(&item_key(item_id)) {
+                candidates.extend(paths.iter().cloned());
+            }
+        }
+        candidates.extend(command_tokens(params));
+        let denied = guard.denied(candidates.iter().map(String::as_str));
+        return !denied.is_empty() && decline(app, session, message, denied).await;
+    }
+
+    let Some(item_id) = str_at(item, "/id") else {
+        return false;
+    };
+    let paths = change_paths(item);
+    if method == "item/started" {
+        state.path_guards.items.lock().await.insert(item_key(item_id), paths);
+        return false;
+    }
+    state.path_guards.items.lock().await.remove(&item_key(item_id));
+    let denied = guard.denied(paths.iter().map(String::as_str));
+    if !denied.is_empty() && str_at(item, "/status") != Some("declined") {
+        let _ = app.emit(
+            "path-guard-violation",
+            json!({
+                "workspaceId": workspace_id,
+                "threadId": message_thread_id(params),
+                "itemId": item_id,
+                "paths": denied,
+            }),
+        );
+    }
+    false
+}
+
+#[tauri::command]
+pub(crate) async fn get_path_guard(
+    workspace_id: String,
+    state: State<'_, AppState>,
+) -> Result<PathGuardConfig, AppError> {
+    let entry = workspace_entry(&state, &workspace_id).await?;
+    Ok(read_path_guard_config(&path_guard_config_path(&entry.path))?)
+}
+
+#[tauri::command]
+pub(crate) async fn set_path_guard(
+    workspace_id: String,
+    config: PathGuardConfig,
+    state: State<'_, AppState>,
+) -> Result<PathGuardConfig, AppError> {
+    let entry = workspace_entry(&state, &workspace_id).await?;
+    let config = PathGuardConfig {
+        deny: clean_globs(config.deny),
+        allow: clean_globs(config.allow),
+    };
+    build_matcher(Path::new(&entry.path), &config)?;
+    write_path_guard_config(&path_guard_config_path(&entry.path), &config)?;
+    mark_file_index_stale(&state, &workspace_id).await;
+    Ok(config)
+}
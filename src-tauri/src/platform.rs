@@ -0,0 +1,152 @@
+use std::collections::BTreeSet;
+use std::env;
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+use tokio::process::Command;
+
+#[cfg(windows)]
+const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+#[cfg(windows)]
+const MAX_PATH: usize = 260;
+
+const WINDOWS_SHIM_EXTENSIONS: &[&str] = &["exe", "cmd", "bat", "ps1"];
+
+pub(crate) fn command(program: impl AsRef<OsStr>) -> Command {
+    let mut command = Command::new(program);
+    hide_console_window(&mut command);
+    command
+}
+
+#[cfg(windows)]
+pub(crate) fn hide_console_window(command: &mut Command) {
+    command.creation_flags(CREATE_NO_WINDOW);
+}
+
+#[cfg(not(windows))]
+pub(crate) fn hide_console_window(_command: &mut Command) {}
+
+#[cfg(windows)]
+pub(crate) fn hide_std_console_window(command: &mut std::process::Command) {
+    use std::os::windows::process::CommandExt;
+    command.creation_flags(CREATE_NO_WINDOW);
+}
+
+fn extension_of(path: &Path) -> Option<String> {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.to_ascii_lowercase())
+}
+
+pub(crate) fn has_windows_executable_extension(path: &Path) -> bool {
+    extension_of(path)
+        .map(|ext| ext == "com" || WINDOWS_SHIM_EXTENSIONS.contains(&ext.as_str()))
+        .unwrap_or(false)
+}
+
+fn shim_candidates(base: &Path) -> impl Iterator<Item = PathBuf> + '_ {
+    WINDOWS_SHIM_EXTENSIONS.iter().map(move |ext| {
+        let mut name = base.as_os_str().to_os_string();
+        name.push(".");
+        name.push(ext);
+        PathBuf::from(name)
+    })
+}
+
+pub(crate) fn resolve_windows_shim(raw: &str) -> Option<PathBuf> {
+    if !cfg!(windows) {
+        return None;
+    }
+    let raw_path = Path::new(raw);
+    if has_windows_executable_extension(raw_path) {
+        return raw_path.is_file().then(|| raw_path.to_path_buf());
+    }
+    if raw_path.components().count() > 1 {
+        return shim_candidates(raw_path).find(|candidate| candidate.is_file());
+    }
+    let path = env::var_os("PATH")?;
+    env::split_paths(&path)
+        .flat_map(|dir| shim_candidates(&dir.join(raw)).collect::<Vec<_>>())
+        .find(|candidate| candidate.is_file())
+}
+
+pub(crate) fn script_command(path: &Path) -> Command {
+    if extension_of(path).as_deref() == Some("ps1") {
+        let mut command = self::command("powershell");
+        command
+            .args(["-NoLogo", "-NoProfile", "-ExecutionPolicy", "Bypass", "-File"])
+            .arg(path);
+        return command;
+    }
+    self::command(path)
+}
+
+pub(crate) fn simplify_path(path: &Path) -> PathBuf {
+    let raw = path.to_string_lossy();
+    if let Some(rest) = raw.strip_prefix(r"\\?\UNC\") {
+        return PathBuf::from(format!(r"\\{rest}"));
+    }
+    match raw.strip_prefix(r"\\?\") {
+        Some(rest) if rest.as_bytes().get(1) == Some(&b':') => PathBuf::from(rest),
+        _ => path.to_path_buf(),
+    }
+}
+
+#[cfg(windows)]
+pub(crate) fn long_path(path: &Path) -> PathBuf {
+    let raw = path.to_string_lossy().replace('/', "\\");
+    if raw.len() < MAX_PATH || raw.starts_with(r"\\?\") || !path.is_absolute() {
+        return path.to_path_buf();
+    }
+    match raw.strip_prefix(r"\\") {
+        Some(unc) => PathBuf::from(format!(r"\\?\UNC\{unc}")),
+        None => PathBuf::from(format!(r"\\?\{raw}")),
+    }
+}
+
+#[cfg(not(windows))]
+pub(crate) fn long_path(path: &Path) -> PathBuf {
+    path.to_path_buf()
+}
+
+#[cfg(unix)]
+pub(crate) fn network_addresses() -> BTreeSet<String> {
+    use std::ffi::CStr;
+    use std::net::{Ipv4Addr, Ipv6Addr};
+
+    let mut addresses = BTreeSet::new();
+    let mut list: *mut libc::ifaddrs = std::ptr::null_mut();
+    if unsafe { libc::getifaddrs(&mut list) } != 0 {
+        return addresses;
+    }
+    let mut cursor = list;
+    while !cursor.is_null() {
+        let entry = unsafe { &*cursor };
+        cursor = entry.ifa_next;
+        let flags = entry.ifa_flags as libc::c_int;
+        if entry.ifa_addr.is_null() || flags & libc::IFF_UP == 0 || flags & libc::IFF_LOOPBACK != 0
+        {
+            continue;
+        }
+        let address = match unsafe { (*entry.ifa_addr).sa_family } as libc::c_int {
+            libc::AF_INET => {
+                let addr = unsafe { &*(entry.ifa_addr as *const libc::sockaddr_in) };
+                Ipv4Addr::from(u32::from_be(addr.sin_addr.s_addr)).to_string()
+            }
+            libc::AF_INET6 => {
+                let addr = unsafe { &*(entry.ifa_addr as *const libc::sockaddr_in6) };
+                Ipv6Addr::from(addr.sin6_addr.s6_addr).to_string()
+            }
+            _ => continue,
+        };
+        let name = unsafe { CStr::from_ptr(entry.ifa_name) }.to_string_lossy();
+        addresses.insert(format!("{name}/{address}"));
+    }
+    unsafe { libc::freeifaddrs(list) };
+    addresses
+}
+
+#[cfg(not(unix))]
+pub(crate) fn network_addresses() -> BTreeSet<String> {
+    BTreeSet::new()
+}
@@ -0,0 +1,146 @@
+use serde::Serialize;
+use std::collections::HashMap;
+use tauri::State;
+
+use crate::error::AppError;
+use crate::thread_state::{self, ThreadActivity};
+use crate::{fuzzy_match, now_ms, read_workspace_sessions, workspace_sessions_path, AppState};
+
+const DEFAULT_LIMIT: usize = 50;
+const RECENCY_BONUS_MAX: i64 = 32;
+const HOUR_MS: i64 = 60 * 60 * 1000;
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum QuickSwitchKind {
+    Workspace,
+    Thread,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct QuickSwitchItem {
+    pub(crate) kind: QuickSwitchKind,
+    pub(crate) workspace_id: String,
+    pub(crate) workspace_name: String,
+    pub(crate) thread_id: Option<String>,
+    pub(crate) title: String,
+    pub(crate) last_activity_ms: Option<i64>,
+    state: Option<ThreadActivity>,
+    score: i64,
+    indices: Vec<usize>,
+}
+
+fn recency_bonus(now: i64, last_activity_ms: Option<i64>) -> i64 {
+    let Some(last) = last_activity_ms.filter(|last| *last > 0) else {
+        return 0;
+    };
+    let age_hours = (now - last).max(0) / HOUR_MS;
+    RECENCY_BONUS_MAX * 24 / (24 + age_hours)
+}
+
+// Without a query items are ordered by recency; with one, the fuzzy score on
+// the title dominates and recency only breaks near-ties.
+fn rank(mut items: Vec<QuickSwitchItem>, query: &str, limit: usize) -> Vec<QuickSwitchItem> {
+    let now = now_ms();
+    let query = query.trim();
+    if !query.is_empty() {
+        items = items
+            .into_iter()
+            .filter_map(|mut item| {
+                let (score, indices) = fuzzy_match(&item.title, query).or_else(|| {
+                    let qualified = format!("{} {}", item.workspace_name, item.title);
+                    fuzzy_match(&qualified, query).map(|(score, _)| (score / 2, Vec::new()))
+                })?;
+                item.score = score + recency_bonus(now, item.last_activity_ms);
+                item.indices = indices;
+                Some(item)
+            })
+            .collect();
+    } else {
+        for item in &mut items {
+            item.score = recency_bonus(now, item.last_activity_ms);
+        }
+    }
+    items.sort_by(|a, b| {
+        b.score
+            .cmp(&a.score)
+            .then_with(|| b.last_activity_ms.cmp(&a.last_activity_ms))
+            .then_with(|| a.title.cmp(&b.title))
+    });
+    items.truncate(limit);
+    items
+}
+
+async fn collect(state: &AppState) -> Vec<QuickSwitchItem> {
+    let workspaces: Vec<_> = state.workspaces.lock().await.values().cloned().collect();
+    let mut items = Vec::new();
+    for entry in workspaces {
+        let store = read_workspace_sessions(&workspace_sessions_path(&entry.path))
+            .unwrap_or_default();
+        let activity: HashMap<String, (i64, ThreadActivity)> =
+            thread_state::workspace_thread_states(state, &entry.id, &store)
+                .await
+                .into_iter()
+                .map(|thread| (thread.thread_id, (thread.updated_at_ms, thread.state)))
+                .collect();
+        let mut workspace_activity: Option<i64> = None;
+        for (thread_id, metadata) in &store.sessions {
+            if metadata.archived {
+                continue;
+            }
+            let (last_activity_ms, thread_state) = match activity.get(thread_id) {
+                Some((at_ms, thread_state)) => (Some(*at_ms), Some(*thread_state)),
+                None => (None, None),
+            };
+            workspace_activity = workspace_activity.max(last_activity_ms);
+            let title = if metadata.name.trim().is_empty() {
+                thread_id.clone()
+            } else {
+                metadata.name.clone()
+            };
+            items.push(QuickSwitchItem {
+                kind: QuickSwitchKind::Thread,
+                workspace_id: entry.id.clone(),
+                workspace_name: entry.name.clone(),
+                thread_id: Some(thread_id.clone()),
+                title,
+                last_activity_ms,
+                state: thread_state,
+                score: 0,
+                indices: Vec::new(),
+            });
+        }
+        items.push(QuickSwitchItem {
+            kind: QuickSwitchKind::Workspace,
+            workspace_id: entry.id.clone(),
+            workspace_name: entry.name.clone(),
+            thread_id: None,
+            title: entry.name.clone(),
+            last_activity_ms: workspace_activity,
+            state: None,
+            score: 0,
+            indices: Vec::new(),
+        });
+    }
+    items
+}
+
+pub(crate) async fn recent(state: &AppState, limit: usize) -> Vec<QuickSwitchItem> {
+    let items = collect(state).await;
+    tokio::task::spawn_blocking(move || rank(items, "", limit))
+        .await
+        .unwrap_or_default()
+}
+
+#[tauri::command]
+pub(crate) async fn quick_switch_index(
+    query: Option<String>,
+    limit: Option<usize>,
+    state: State<'_, AppState>,
+) -> Result<Vec<QuickSwitchItem>, AppError> {
+    let items = collect(&state).await;
+    let query = query.unwrap_or_default();
+    let limit = limit.unwrap_or(DEFAULT_LIMIT);
+    Ok(tokio::task::spawn_blocking(move || rank(items, &query, limit)).await?)
+}
@@ -0,0 +1,131 @@
+use chrono::{DateTime, Local, Timelike};
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::error::AppError;
+use crate::{now_ms, AppState, RateLimitSnapshot, RateLimitWindow};
+
+const RETENTION_MS: i64 = 30 * 24 * 60 * 60 * 1000;
+const MAX_SAMPLES: usize = 20_000;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum HistoryRange {
+    Day,
+    Week,
+    Month,
+}
+
+impl HistoryRange {
+    fn duration_ms(self) -> i64 {
+        let day = 24 * 60 * 60 * 1000;
+        match self {
+            HistoryRange::Day => day,
+            HistoryRange::Week => 7 * day,
+            HistoryRange::Month => 30 * day,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RateLimitSample {
+    timestamp_ms: i64,
+    #[serde(default)]
+    primary: Option<RateLimitWindow>,
+    #[serde(default)]
+    secondary: Option<RateLimitWindow>,
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct HourlyRateLimit {
+    hour: u32,
+    samples: usize,
+    peak_primary_percent: Option<i64>,
+    peak_secondary_percent: Option<i64>,
+    limit_hits: usize,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RateLimitHistory {
+    range: HistoryRange,
+    since_ms: i64,
+    samples: Vec<RateLimitSample>,
+    hourly: Vec<HourlyRateLimit>,
+}
+
+pub(crate) fn push(history: &mut Vec<RateLimitSample>, now: i64, rate_limits: &RateLimitSnapshot) {
+    history.push(RateLimitSample {
+        timestamp_ms: now,
+        primary: rate_limits.primary.clone(),
+        secondary: rate_limits.secondary.clone(),
+    });
+    let cutoff = now.saturating_sub(RETENTION_MS);
+    history.retain(|sample| sample.timestamp_ms >= cutoff);
+    if history.len() > MAX_SAMPLES {
+        let excess = history.len() - MAX_SAMPLES;
+        history.drain(..excess);
+    }
+}
+
+fn used_percent(window: &Option<RateLimitWindow>) -> Option<i64> {
+    window.as_ref().map(|window| window.used_percent)
+}
+
+// Buckets samples by local hour of day so the UI can show when limits are
+// usually reached.
+fn hourly(samples: &[RateLimitSample], threshold: i64) -> Vec<HourlyRateLimit> {
+    let mut hours: Vec<HourlyRateLimit> = (0..24)
+        .map(|hour| HourlyRateLimit {
+            hour,
+            ..HourlyRateLimit::default()
+        })
+        .collect();
+    for sample in samples {
+        let Some(time) = DateTime::from_timestamp_millis(sample.timestamp_ms) else {
+            continue;
+        };
+        let bucket = &mut hours[time.with_timezone(&Local).hour() as usize];
+        let primary = used_percent(&sample.primary);
+        let secondary = used_percent(&sample.secondary);
+        bucket.samples += 1;
+        bucket.peak_primary_percent = bucket.peak_primary_percent.max(primary);
+        bucket.peak_secondary_percent = bucket.peak_secondary_percent.max(secondary);
+        if primary.max(secondary).is_some_and(|percent| percent >= threshold) {
+            bucket.limit_hits += 1;
+        }
+    }
+    hours
+}
+
+#[tauri::command]
+pub(crate) async fn rate_limit_history(
+    range: HistoryRange,
+    state: State<'_, AppState>,
+) -> Result<RateLimitHistory, AppError> {
+    let threshold = state
+        .settings
+        .lock()
+        .await
+        .rate_limit_warning_percent
+        .clamp(1, 100);
+    let since_ms = now_ms().saturating_sub(range.duration_ms());
+    let samples: Vec<RateLimitSample> = state
+        .usage_store
+        .lock()
+        .await
+        .rate_limit_history
+        .iter()
+        .filter(|sample| sample.timestamp_ms >= since_ms)
+        .cloned()
+        .collect();
+    let hourly = hourly(&samples, threshold);
+    Ok(RateLimitHistory {
+        range,
+        since_ms,
+        samples,
+        hourly,
+    })
+}
@@ -0,0 +1,240 @@
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use cpal::{FromSample, SizedSample};
+use serde::Serialize;
+use std::fs::{self, File};
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex as StdMutex};
+use std::time::Duration;
+use tauri::State;
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::{now_ms, tasks, AppState};
+
+const TARGET_SAMPLE_RATE: u32 = 16_000;
+const TRANSCRIBE_TIMEOUT: Duration = Duration::from_secs(180);
+
+type WavSink = Arc<StdMutex<Option<hound::WavWriter<BufWriter<File>>>>>;
+
+struct ActiveRecording {
+    id: String,
+    path: PathBuf,
+    started_at_ms: i64,
+    stop: mpsc::Sender<()>,
+    thread: std::thread::JoinHandle<()>,
+}
+
+#[derive(Default)]
+pub(crate) struct Recorder {
+    active: Mutex<Option<ActiveRecording>>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RecordingStarted {
+    id: String,
+    started_at_ms: i64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RecordingResult {
+    id: String,
+    path: String,
+    duration_ms: i64,
+    text: Option<String>,
+}
+
+fn recordings_dir() -> PathBuf {
+    std::env::temp_dir().join("codexmonitor-recordings")
+}
+
+// Whisper and most speech tools expect 16 kHz mono, so channels are mixed
+// down and frames dropped or repeated on the way in.
+fn build_stream<T>(
+    device: &cpal::Device,
+    config: &cpal::StreamConfig,
+    sink: WavSink,
+) -> Result<cpal::Stream, String>
+where
+    T: SizedSample,
+    f32: FromSample<T>,
+{
+    let channels = usize::from(config.channels.max(1));
+    let step = f64::from(TARGET_SAMPLE_RATE) / f64::from(config.sample_rate.0);
+    let mut phase = 0.0f64;
+    device
+        .build_input_stream(
+            config,
+            move |data: &[T], _: &cpal::InputCallbackInfo| {
+                let mut sink = sink.lock().unwrap_or_else(|e| e.into_inner());
+                let Some(writer) = sink.as_mut() else {
+                    return;
+                };
+                for frame in data.chunks(channels) {
+                    let mixed = frame
+                        .iter()
+                        .map(|sample| sample.to_sample::<f32>())
+                        .sum::<f32>()
+                        / frame.len() as f32;
+                    phase += step;
+                    while phase >= 1.0 {
+                        let value = (mixed.clamp(-1.0, 1.0) * f32::from(i16::MAX)) as i16;
+                        let _ = writer.write_sample(value);
+                        phase -= 1.0;
+                    }
+                }
+            },
+            |err| tracing::warn!("microphone stream error: {err}"),
+            None,
+        )
+        .map_err(|err| format!("Failed to open the microphone: {err}"))
+}
+
+// cpal streams aren't Send on every platform, so each recording owns a
+// thread that keeps the stream alive until it is told to stop.
+fn record(path: &Path, ready: mpsc::Sender<Result<(), String>>, stop: mpsc::Receiver<()>) {
+    let started = (|| {
+        let device = cpal::default_host()
+            .default_input_device()
+            .ok_or("No microphone is available.")?;
+        let supported = device
+            .default_input_config()
+            .map_err(|err| format!("Failed to read microphone settings: {err}"))?;
+        let spec = hound::WavSpec {
+            channels: 1,
+            sample_rate: TARGET_SAMPLE_RATE,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let writer = hound::WavWriter::create(path, spec).map_err(|err| err.to_string())?;
+        let sink: WavSink = Arc::new(StdMutex::new(Some(writer)));
+        let format = supported.sample_format();
+        let config: cpal::StreamConfig = supported.into();
+        let stream = match format {
+            cpal::SampleFormat::F32 => build_stream::<f32>(&device, &config, sink.clone()),
+            cpal::SampleFormat::I16 => build_stream::<i16>(&device, &config, sink.clone()),
+            cpal::SampleFormat::U16 => build_stream::<u16>(&device, &config, sink.clone()),
+            cpal::SampleFormat::I32 => build_stream::<i32>(&device, &config, sink.clone()),
+            other => Err(format!("Unsupported microphone sample format: {other}")),
+        }?;
+        stream
+            .play()
+            .map_err(|err| format!("Failed to start recording: {err}"))?;
+        Ok::<_, String>((stream, sink))
+    })();
+    let (stream, sink) = match started {
+        Ok(started) => started,
+        Err(err) => {
+            let _ = ready.send(Err(err));
+            return;
+        }
+    };
+    let _ = ready.send(Ok(()));
+    let _ = stop.recv();
+    drop(stream);
+    let writer = sink.lock().unwrap_or_else(|e| e.into_inner()).take();
+    if let Some(writer) = writer {
+        if let Err(err) = writer.finalize() {
+            tracing::warn!("failed to finish recording: {err}");
+        }
+    }
+}
+
+async fn transcribe(command_line: &str, path: &Path) -> Result<String, String> {
+    let file = path.to_string_lossy();
+    let command_line = command_line.replace("{file}", &format!("\"{file}\""));
+    let mut command = tasks::shell_command(&command_line);
+    command
+        .env("CODEXOLA_AUDIO_FILE", path)
+        .stdin(std::process::Stdio::null())
+        .kill_on_drop(true);
+    let output = tokio::time::timeout(TRANSCRIBE_TIMEOUT, command.output())
+        .await
+        .map_err(|_| "Transcription timed out.".to_string())?
+        .map_err(|err| format!("Failed to run transcription command: {err}"))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("Transcription failed: {}", stderr.trim()));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+#[tauri::command]
+pub(crate) async fn start_recording(
+    state: State<'_, AppState>,
+) -> Result<RecordingStarted, AppError> {
+    let mut active = state.recorder.active.lock().await;
+    if active.is_some() {
+        return Err("A recording is already in progress.".into());
+    }
+    let dir = recordings_dir();
+    fs::create_dir_all(&dir)?;
+    let id = Uuid::new_v4().to_string();
+    let path = dir.join(format!("{id}.wav"));
+    let (ready_tx, ready_rx) = mpsc::channel();
+    let (stop_tx, stop_rx) = mpsc::channel();
+    let thread_path = path.clone();
+    let thread = std::thread::Builder::new()
+        .name("microphone".to_string())
+        .spawn(move || record(&thread_path, ready_tx, stop_rx))?;
+    let ready = tokio::task::spawn_blocking(move || ready_rx.recv()).await?;
+    if let Err(err) = ready.unwrap_or_else(|_| Err("Recording stopped unexpectedly.".to_string())) {
+        let _ = fs::remove_file(&path);
+        return Err(err.into());
+    }
+    let started_at_ms = now_ms();
+    *active = Some(ActiveRecording {
+        id: id.clone(),
+        path,
+        started_at_ms,
+        stop: stop_tx,
+        thread,
+    });
+    Ok(RecordingStarted { id, started_at_ms })
+}
+
+#[tauri::command]
+pub(crate) async fn stop_recording(
+    transcribe: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<RecordingResult, AppError> {
+    let recording = state
+        .recorder
+        .active
+        .lock()
+        .await
+        .take()
+        .ok_or("No recording is in progress.")?;
+    let duration_ms = now_ms() - recording.started_at_ms;
+    let _ = recording.stop.send(());
+    let thread = recording.thread;
+    tokio::task::spawn_blocking(move || thread.join())
+        .await?
+        .map_err(|_| "Recording thread panicked.")?;
+
+    let command_line = state
+        .settings
+        .lock()
+        .await
+        .transcription_command
+        .clone()
+        .filter(|command| !command.trim().is_empty());
+    let text = match (command_line, transcribe) {
+        (_, Some(false)) => None,
+        (Some(command_line), _) => Some(self::transcribe(&command_line, &recording.path).await?),
+        (None, Some(true)) => {
+            return Err("Set a transcription command in settings to transcribe recordings.".into())
+        }
+        (None, None) => None,
+    };
+    Ok(RecordingResult {
+        id: recording.id,
+        path: recording.path.to_string_lossy().to_string(),
+        duration_ms,
+        text,
+    })
+}
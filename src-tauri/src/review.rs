@@ -0,0 +1,218 @@
+use git2::{BranchType, Commit, Repository, Status, StatusOptions};
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::BTreeSet;
+use tauri::State;
+
+use crate::error::AppError;
+use crate::{normalize_git_path, open_repository, request_review, workspace_entry, AppState};
+
+const SHORT_SHA_LEN: usize = 7;
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ReviewStart {
+    target: Value,
+    response: Value,
+}
+
+fn resolve_commit<'repo>(
+    repo: &'repo Repository,
+    reference: &str,
+) -> Result<Commit<'repo>, AppError> {
+    let reference = reference.trim();
+    let invalid = |message: String| AppError::InvalidReference {
+        reference: reference.to_string(),
+        message,
+    };
+    if reference.is_empty() {
+        return Err(invalid("A commit or branch is required.".to_string()));
+    }
+    let object = repo
+        .revparse_single(reference)
+        .map_err(|_| invalid(format!("Unknown revision: {reference}")))?;
+    object
+        .peel_to_commit()
+        .map_err(|_| invalid(format!("{reference} does not point to a commit")))
+}
+
+fn short_sha(commit: &Commit<'_>) -> String {
+    commit.id().to_string().chars().take(SHORT_SHA_LEN).collect()
+}
+
+fn changed_paths(repo: &Repository) -> Result<BTreeSet<String>, AppError> {
+    let mut options = StatusOptions::new();
+    options
+        .include_untracked(true)
+        .recurse_untracked_dirs(true)
+        .include_ignored(false);
+    let statuses = repo.statuses(Some(&mut options))?;
+    Ok(statuses
+        .iter()
+        .filter(|entry| entry.status() != Status::CURRENT)
+        .filter_map(|entry| entry.path().map(normalize_git_path))
+        .collect())
+}
+
+fn uncommitted_target(repo: &Repository, paths: &[String]) -> Result<Value, AppError> {
+    let changed = changed_paths(repo)?;
+    if changed.is_empty() {
+        return Err(AppError::NothingToReview {
+            message: "There are no uncommitted changes to review.".to_string(),
+        });
+    }
+    let requested: Vec<String> = paths
+        .iter()
+        .map(|path| normalize_git_path(path.trim()).trim_matches('/').to_string())
+        .filter(|path| !path.is_empty())
+        .collect();
+    if requested.is_empty() {
+        return Ok(json!({ "type": "uncommittedChanges" }));
+    }
+    let selected: Vec<&String> = changed
+        .iter()
+        .filter(|path| {
+            requested
+                .iter()
+                .any(|prefix| *path == prefix || path.starts_with(&format!("{prefix}/")))
+        })
+        .collect();
+    if selected.is_empty() {
+        return Err(AppError::NothingToReview {
+            message: format!("No uncommitted changes in: {}", requested.join(", ")),
+        });
+    }
+    let list = selected
+        .iter()
+        .map(|path| format!("- {path}"))
+        .collect::<Vec<_>>()
+        .join("\n");
+    Ok(json!({
+        "type": "custom",
+        "instructions": format!(
+            "Review the uncommitted changes (staged, unstaged and untracked) in these files \
+             only:\n{list}\n\nIgnore changes to any other files."
+        ),
+    }))
+}
+
+fn commit_target(repo: &Repository, commit_id: &str) -> Result<Value, AppError> {
+    let commit = resolve_commit(repo, commit_id)?;
+    let mut target = json!({ "type": "commit", "sha": commit.id().to_string() });
+    if let Some(title) = commit.summary().filter(|title| !title.trim().is_empty()) {
+        target["title"] = json!(title);
+    }
+    Ok(target)
+}
+
+fn range_target(repo: &Repository, base: &str, head: Option<&str>) -> Result<Value, AppError> {
+    let base = base.trim();
+    let head = head.map(str::trim).filter(|head| !head.is_empty()).unwrap_or("HEAD");
+    let base_commit = resolve_commit(repo, base)?;
+    let head_commit = resolve_commit(repo, head)?;
+    if base_commit.id() == head_commit.id() {
+        return Err(AppError::NothingToReview {
+            message: format!("{base} and {head} point to the same commit."),
+        });
+    }
+    let merge_base = repo
+        .merge_base(base_commit.id(), head_commit.id())
+        .map_err(|_| AppError::InvalidReference {
+            reference: base.to_string(),
+            message: format!("{base} has no common history with {head}"),
+        })?;
+    if merge_base == head_commit.id() {
+        return Err(AppError::NothingToReview {
+            message: format!("{head} has no commits that are not already in {base}."),
+        });
+    }
+    let head_is_current = repo
+        .head()
+        .ok()
+        .and_then(|current| current.target())
+        .is_some_and(|current| current == head_commit.id());
+    let base_is_branch = repo
+        .find_branch(base, BranchType::Local)
+        .or_else(|_| repo.find_branch(base, BranchType::Remote))
+        .is_ok();
+    if head_is_current && base_is_branch {
+        return Ok(json!({ "type": "baseBranch", "branch": base }));
+    }
+    let base_sha = base_commit.id().to_string();
+    let head_sha = head_commit.id().to_string();
+    Ok(json!({
+        "type": "custom",
+        "instructions": format!(
+            "Review the changes introduced between {base} ({}) and {head} ({}). Inspect them \
+             with `git diff {base_sha}...{head_sha}` and `git log {base_sha}..{head_sha}`; do \
+             not review changes outside that range.",
+            short_sha(&base_commit),
+            short_sha(&head_commit),
+        ),
+    }))
+}
+
+async fn start(
+    state: &AppState,
+    workspace_id: &str,
+    thread_id: String,
+    delivery: Option<String>,
+    build: impl FnOnce(&Repository) -> Result<Value, AppError>,
+) -> Result<ReviewStart, AppError> {
+    let entry = workspace_entry(state, workspace_id).await?;
+    let target = {
+        let repo = open_repository(&entry.path)?;
+        build(&repo)?
+    };
+    let response =
+        request_review(state, workspace_id, thread_id, target.clone(), delivery).await?;
+    if let Some(error) = AppError::from_rpc_response(&response) {
+        return Err(error);
+    }
+    state.analytics.record("reviewRuns");
+    Ok(ReviewStart { target, response })
+}
+
+#[tauri::command]
+pub(crate) async fn review_uncommitted(
+    workspace_id: String,
+    thread_id: String,
+    paths: Option<Vec<String>>,
+    delivery: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<ReviewStart, AppError> {
+    let paths = paths.unwrap_or_default();
+    start(&state, &workspace_id, thread_id, delivery, |repo| {
+        uncommitted_target(repo, &paths)
+    })
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn review_commit(
+    workspace_id: String,
+    thread_id: String,
+    commit_id: String,
+    delivery: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<ReviewStart, AppError> {
+    start(&state, &workspace_id, thread_id, delivery, |repo| {
+        commit_target(repo, &commit_id)
+    })
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn review_range(
+    workspace_id: String,
+    thread_id: String,
+    base: String,
+    head: Option<String>,
+    delivery: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<ReviewStart, AppError> {
+    start(&state, &workspace_id, thread_id, delivery, |repo| {
+        range_target(repo, &base, head.as_deref())
+    })
+    .await
+}
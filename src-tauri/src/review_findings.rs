@@ -0,0 +1,412 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::error::AppError;
+use crate::thread_state::message_thread_id;
+use crate::{
+    default_session_store_version, normalize_git_path, now_ms, request_review, storage,
+    workspace_entry, AppState,
+};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+enum Severity {
+    Critical,
+    High,
+    Medium,
+    Low,
+    Info,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum FindingStatus {
+    Open,
+    Resolved,
+    Dismissed,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ReviewFinding {
+    id: String,
+    thread_id: String,
+    file: Option<String>,
+    line: Option<u32>,
+    end_line: Option<u32>,
+    severity: Severity,
+    title: String,
+    message: String,
+    status: FindingStatus,
+    created_at_ms: i64,
+    updated_at_ms: i64,
+    last_seen_at_ms: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct ReviewFindingStore {
+    #[serde(default = "default_session_store_version")]
+    version: u32,
+    #[serde(default)]
+    findings: Vec<ReviewFinding>,
+}
+
+impl Default for ReviewFindingStore {
+    fn default() -> Self {
+        Self {
+            version: default_session_store_version(),
+            findings: Vec::new(),
+        }
+    }
+}
+
+struct ParsedFinding {
+    title: String,
+    body: String,
+    file: Option<String>,
+    line: Option<u32>,
+    end_line: Option<u32>,
+    priority: Option<i64>,
+}
+
+fn workspace_findings_path(workspace_path: &str) -> PathBuf {
+    PathBuf::from(workspace_path)
+        .join(".codexmonitor")
+        .join("review-findings.json")
+}
+
+fn read_findings(path: &Path) -> Result<ReviewFindingStore, String> {
+    storage::read_json_or_default(path)
+}
+
+fn write_findings(path: &Path, store: &ReviewFindingStore) -> Result<(), String> {
+    storage::write_json_atomic(path, store)
+}
+
+fn finding_id(file: Option<&str>, title: &str) -> String {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in file.unwrap_or_default().bytes().chain([0]).chain(title.bytes()) {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    format!("{hash:016x}")
+}
+
+fn severity_for(priority: Option<i64>, title: &str) -> Severity {
+    let priority = priority.or_else(|| {
+        let rest = title.trim_start().strip_prefix("[P")?;
+        rest.chars().next()?.to_digit(10).map(|digit| digit as i64)
+    });
+    match priority {
+        Some(0) => Severity::Critical,
+        Some(1) => Severity::High,
+        Some(2) => Severity::Medium,
+        Some(3) => Severity::Low,
+        _ => Severity::Info,
+    }
+}
+
+fn relative_file(workspace_path: &str, file: &str) -> String {
+    let path = Path::new(file);
+    let relative = path.strip_prefix(workspace_path).unwrap_or(path);
+    normalize_git_path(&relative.to_string_lossy())
+}
+
+fn parse_location(location: &str) -> (String, Option<u32>, Option<u32>) {
+    let Some((file, lines)) = location.rsplit_once(':') else {
+        return (location.to_string(), None, None);
+    };
+    let (start, end) = lines.split_once('-').unwrap_or((lines, lines));
+    match (start.trim().parse().ok(), end.trim().parse().ok()) {
+        (Some(start), end) => (file.to_string(), Some(start), end),
+        _ => (location.to_string(), None, None),
+    }
+}
+
+fn structured_findings(output: &Value) -> Vec<ParsedFinding> {
+    let Some(findings) = output.get("findings").and_then(|findings| findings.as_array()) else {
+        return Vec::new();
+    };
+    findings
+        .iter()
+        .map(|finding| {
+            let location = finding
+                .get("codeLocation")
+                .or_else(|| finding.get("code_location"))
+                .unwrap_or(&Value::Null);
+            let range = location
+                .get("lineRange")
+                .or_else(|| location.get("line_range"))
+                .unwrap_or(&Value::Null);
+            let line = |key: &str| {
+                range
+                    .get(key)
+                    .and_then(|line| line.as_u64())
+                    .map(|line| line as u32)
+            };
+            ParsedFinding {
+                title: finding
+                    .get("title")
+                    .and_then(|title| title.as_str())
+                    .unwrap_or_default()
+                    .to_string(),
+                body: finding
+                    .get("body")
+                    .and_then(|body| body.as_str())
+                    .unwrap_or_default()
+                    .to_string(),
+                file: location
+                    .get("absoluteFilePath")
+                    .or_else(|| location.get("absolute_file_path"))
+                    .and_then(|file| file.as_str())
+                    .map(|file| file.to_string()),
+                line: line("start"),
+                end_line: line("end"),
+                priority: finding.get("priority").and_then(|priority| priority.as_i64()),
+            }
+        })
+        .collect()
+}
+
+fn text_findings(text: &str) -> Vec<ParsedFinding> {
+    let mut findings: Vec<ParsedFinding> = Vec::new();
+    for line in text.lines() {
+        if let Some(entry) = line.strip_prefix("- ") {
+            let (title, location) = match entry.rsplit_once(" — ") {
+                Some((title, location)) => (title, Some(location)),
+                None => (entry, None),
+            };
+            let (file, line, end_line) = match location.map(parse_location) {
+                Some((file, line, end_line)) => (Some(file), line, end_line),
+                None => (None, None, None),
+            };
+            findings.push(ParsedFinding {
+                title: title.trim().to_string(),
+                body: String::new(),
+                file,
+                line,
+                end_line,
+                priority: None,
+            });
+        } else if let Some(current) = findings.last_mut() {
+            if let Some(body) = line.strip_prefix("  ") {
+                if !current.body.is_empty() {
+                    current.body.push('\n');
+                }
+                current.body.push_str(body);
+            }
+        }
+    }
+    findings
+}
+
+fn review_findings(item: &Value) -> Vec<ParsedFinding> {
+    let structured = item
+        .get("reviewOutput")
+        .or_else(|| item.get("review").filter(|review| review.is_object()))
+        .map(structured_findings)
+        .unwrap_or_default();
+    if !structured.is_empty() {
+        return structured;
+    }
+    item.get("review")
+        .and_then(|review| review.as_str())
+        .map(text_findings)
+        .unwrap_or_default()
+}
+
+fn merge_findings(
+    store: &mut ReviewFindingStore,
+    workspace_path: &str,
+    thread_id: &str,
+    parsed: Vec<ParsedFinding>,
+) -> usize {
+    let now = now_ms();
+    let mut recorded = 0;
+    for finding in parsed {
+        if finding.title.is_empty() {
+            continue;
+        }
+        let file = finding
+            .file
+            .as_deref()
+            .map(|file| relative_file(workspace_path, file));
+        let id = finding_id(file.as_deref(), &finding.title);
+        let severity = severity_for(finding.priority, &finding.title);
+        match store.findings.iter_mut().find(|existing| existing.id == id) {
+            Some(existing) => {
+                // A resolved finding that shows up again was not actually fixed.
+                if existing.status == FindingStatus::Resolved {
+                    existing.status = FindingStatus::Open;
+                    existing.updated_at_ms = now;
+                }
+                existing.thread_id = thread_id.to_string();
+                existing.line = finding.line;
+                existing.end_line = finding.end_line;
+                existing.severity = severity;
+                existing.message = finding.body;
+                existing.last_seen_at_ms = now;
+            }
+            None => store.findings.push(ReviewFinding {
+                id,
+                thread_id: thread_id.to_string(),
+                file,
+                line: finding.line,
+                end_line: finding.end_line,
+                severity,
+                title: finding.title,
+                message: finding.body,
+                status: FindingStatus::Open,
+                created_at_ms: now,
+                updated_at_ms: now,
+                last_seen_at_ms: now,
+            }),
+        }
+        recorded += 1;
+    }
+    recorded
+}
+
+pub(crate) async fn observe(app: &AppHandle, workspace_id: &str, message: &Value) {
+    if message.get("method").and_then(|method| method.as_str()) != Some("item/completed") {
+        return;
+    }
+    let params = message.get("params").unwrap_or(&Value::Null);
+    let item = params.get("item").unwrap_or(&Value::Null);
+    if item.get("type").and_then(|kind| kind.as_str()) != Some("exitedReviewMode") {
+        return;
+    }
+    let parsed = review_findings(item);
+    if parsed.is_empty() {
+        return;
+    }
+    let thread_id = message_thread_id(params).unwrap_or_default();
+    let state = app.state::<AppState>();
+    let Ok(entry) = workspace_entry(&state, workspace_id).await else {
+        return;
+    };
+    let path = workspace_findings_path(&entry.path);
+    let result = {
+        let _guard = state.review_findings_lock.lock().await;
+        read_findings(&path).and_then(|mut store| {
+            let recorded = merge_findings(&mut store, &entry.path, &thread_id, parsed);
+            write_findings(&path, &store).map(|_| recorded)
+        })
+    };
+    match result {
+        Ok(recorded) => {
+            let _ = app.emit(
+                "review-findings-updated",
+                json!({
+                    "workspaceId": workspace_id,
+                    "threadId": thread_id,
+                    "recorded": recorded,
+                }),
+            );
+        }
+        Err(err) => tracing::warn!(workspace_id, "failed to persist review findings: {err}"),
+    }
+}
+
+#[tauri::command]
+pub(crate) async fn list_review_findings(
+    workspace_id: String,
+    status: Option<FindingStatus>,
+    state: State<'_, AppState>,
+) -> Result<Vec<ReviewFinding>, AppError> {
+    let entry = workspace_entry(&state, &workspace_id).await?;
+    let store = read_findings(&workspace_findings_path(&entry.path))?;
+    let mut findings: Vec<ReviewFinding> = store
+        .findings
+        .into_iter()
+        .filter(|finding| status.is_none_or(|status| finding.status == status))
+        .collect();
+    findings.sort_by(|a, b| {
+        a.file
+            .cmp(&b.file)
+            .then_with(|| a.line.cmp(&b.line))
+            .then_with(|| a.title.cmp(&b.title))
+    });
+    Ok(findings)
+}
+
+#[tauri::command]
+pub(crate) async fn set_review_finding_status(
+    workspace_id: String,
+    finding_ids: Vec<String>,
+    status: FindingStatus,
+    state: State<'_, AppState>,
+) -> Result<Vec<ReviewFinding>, AppError> {
+    let entry = workspace_entry(&state, &workspace_id).await?;
+    let path = workspace_findings_path(&entry.path);
+    let _guard = state.review_findings_lock.lock().await;
+    let mut store = read_findings(&path)?;
+    let now = now_ms();
+    let mut updated = Vec::new();
+    for finding in store
+        .findings
+        .iter_mut()
+        .filter(|finding| finding_ids.contains(&finding.id))
+    {
+        finding.status = status;
+        finding.updated_at_ms = now;
+        updated.push(finding.clone());
+    }
+    if updated.len() != finding_ids.len() {
+        let missing: Vec<&String> = finding_ids
+            .iter()
+            .filter(|id| !updated.iter().any(|finding| &finding.id == *id))
+            .collect();
+        return Err(format!("unknown review findings: {missing:?}").into());
+    }
+    write_findings(&path, &store)?;
+    Ok(updated)
+}
+
+#[tauri::command]
+pub(crate) async fn rerun_review_unresolved(
+    workspace_id: String,
+    thread_id: String,
+    delivery: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<Value, AppError> {
+    let entry = workspace_entry(&state, &workspace_id).await?;
+    let store = read_findings(&workspace_findings_path(&entry.path))?;
+    let mut by_file: BTreeMap<&str, Vec<&ReviewFinding>> = BTreeMap::new();
+    for finding in store
+        .findings
+        .iter()
+        .filter(|finding| finding.status == FindingStatus::Open)
+    {
+        let Some(file) = finding.file.as_deref() else {
+            continue;
+        };
+        by_file.entry(file).or_default().push(finding);
+    }
+    if by_file.is_empty() {
+        return Err(AppError::NothingToReview {
+            message: "There are no unresolved review findings tied to files.".to_string(),
+        });
+    }
+    let mut instructions = String::from(
+        "Re-review only the files below. Earlier reviews reported the listed issues; check \
+         whether each is fixed and report anything still wrong or newly introduced in these \
+         files. Do not review other files.\n",
+    );
+    for (file, findings) in &by_file {
+        instructions.push_str(&format!("\n{file}\n"));
+        for finding in findings {
+            let line = match finding.line {
+                Some(line) => format!("- {} (line {line})\n", finding.title),
+                None => format!("- {}\n", finding.title),
+            };
+            instructions.push_str(&line);
+        }
+    }
+    let target = json!({ "type": "custom", "instructions": instructions });
+    request_review(&state, &workspace_id, thread_id, target, delivery).await
+}
@@ -0,0 +1,507 @@
+use chrono::{Datelike, Duration as ChronoDuration, Local, NaiveTime, TimeZone};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, State};
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::thread_state::str_at;
+use crate::{
+    apply_policy_defaults, connect_entry, container, default_session_store_version,
+    dispatch_user_message, now_ms, observer, rate_limit_blocked_until, storage,
+    thread_override_params, workspace_entry, AppState, UserMessageRequest, WorkspaceSession,
+};
+
+const TICK: Duration = Duration::from_secs(30);
+const MAX_RUNS: usize = 200;
+const MIN_INTERVAL_MINUTES: u32 = 5;
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub(crate) enum Recurrence {
+    Daily { at: String },
+    Weekly { days: Vec<u32>, at: String },
+    Interval { minutes: u32 },
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct PromptOptions {
+    #[serde(default)]
+    pub(crate) thread_id: Option<String>,
+    #[serde(default)]
+    model: Option<String>,
+    #[serde(default)]
+    effort: Option<String>,
+    #[serde(default)]
+    access_mode: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ScheduledTask {
+    id: String,
+    name: String,
+    workspace_id: String,
+    prompt: String,
+    #[serde(flatten)]
+    options: PromptOptions,
+    recurrence: Recurrence,
+    #[serde(default)]
+    enabled: bool,
+    #[serde(default)]
+    next_run_at_ms: Option<i64>,
+    #[serde(default)]
+    last_run_at_ms: Option<i64>,
+    created_at_ms: i64,
+    updated_at_ms: i64,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ScheduledTaskInput {
+    #[serde(default)]
+    id: Option<String>,
+    name: String,
+    workspace_id: String,
+    prompt: String,
+    #[serde(flatten)]
+    options: PromptOptions,
+    recurrence: Recurrence,
+    #[serde(default)]
+    enabled: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+enum RunStatus {
+    Started,
+    Failed,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ScheduledRun {
+    id: String,
+    task_id: String,
+    workspace_id: String,
+    #[serde(default)]
+    scheduled_for_ms: Option<i64>,
+    started_at_ms: i64,
+    status: RunStatus,
+    #[serde(default)]
+    thread_id: Option<String>,
+    #[serde(default)]
+    turn_id: Option<String>,
+    #[serde(default)]
+    error: Option<String>,
+    #[serde(default)]
+    manual: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct SchedulerStore {
+    #[serde(default = "default_session_store_version")]
+    version: u32,
+    #[serde(default)]
+    tasks: Vec<ScheduledTask>,
+    #[serde(default)]
+    runs: Vec<ScheduledRun>,
+}
+
+impl Default for SchedulerStore {
+    fn default() -> Self {
+        Self {
+            version: default_session_store_version(),
+            tasks: Vec::new(),
+            runs: Vec::new(),
+        }
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct Scheduler {
+    lock: Mutex<()>,
+}
+
+fn store_path(state: &AppState) -> PathBuf {
+    state.data_dir.join("scheduled_tasks.json")
+}
+
+fn read_store(path: &Path) -> Result<SchedulerStore, String> {
+    storage::read_json_or_default(path)
+}
+
+fn write_store(path: &Path, store: &SchedulerStore) -> Result<(), String> {
+    storage::write_json_atomic(path, store)
+}
+
+fn parse_time(at: &str) -> Result<NaiveTime, String> {
+    NaiveTime::parse_from_str(at.trim(), "%H:%M")
+        .map_err(|_| format!("Invalid time \"{at}\"; use HH:MM."))
+}
+
+fn validate_recurrence(recurrence: &Recurrence) -> Result<(), String> {
+    match recurrence {
+        Recurrence::Daily { at } => parse_time(at).map(|_| ()),
+        Recurrence::Weekly { days, at } => {
+            if days.is_empty() || days.iter().any(|day| !(1..=7).contains(day)) {
+                return Err("Weekly schedules need days between 1 (Monday) and 7.".to_string());
+            }
+            parse_time(at).map(|_| ())
+        }
+        Recurrence::Interval { minutes } if *minutes < MIN_INTERVAL_MINUTES => Err(format!(
+            "Intervals must be at least {MIN_INTERVAL_MINUTES} minutes."
+        )),
+        Recurrence::Interval { .. } => Ok(()),
+    }
+}
+
+// Times are wall-clock in the local zone; a time skipped by a DST change
+// moves the run to the next matching day.
+fn next_run_after(recurrence: &Recurrence, after_ms: i64) -> Option<i64> {
+    let (days, at) = match recurrence {
+        Recurrence::Interval { minutes } => {
+            return Some(after_ms + i64::from(*minutes) * 60_000);
+        }
+        Recurrence::Daily { at } => (None, at),
+        Recurrence::Weekly { days, at } => (Some(days), at),
+    };
+    let time = parse_time(at).ok()?;
+    let after = Local.timestamp_millis_opt(after_ms).single()?;
+    (0..=7).find_map(|offset| {
+        let date = after.date_naive() + ChronoDuration::days(offset);
+        if days.is_some_and(|days| !days.contains(&date.weekday().number_from_monday())) {
+            return None;
+        }
+        let candidate = Local
+            .from_local_datetime(&date.and_time(time))
+            .earliest()?
+            .timestamp_millis();
+        (candidate > after_ms).then_some(candidate)
+    })
+}
+
+impl PromptOptions {
+    pub(crate) fn normalized(self) -> Self {
+        let non_empty = |value: Option<String>| {
+            value
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty())
+        };
+        Self {
+            thread_id: non_empty(self.thread_id),
+            model: non_empty(self.model),
+            effort: non_empty(self.effort),
+            access_mode: non_empty(self.access_mode),
+        }
+    }
+
+    pub(crate) fn with_thread(self, thread_id: Option<String>) -> Self {
+        Self { thread_id, ..self }
+    }
+}
+
+// Resumes the designated thread (or starts a new one), connecting the
+// workspace first if needed.
+pub(crate) async fn open_thread(
+    app: &AppHandle,
+    workspace_id: &str,
+    options: &PromptOptions,
+) -> Result<(Arc<WorkspaceSession>, String), AppError> {
+    let state = app.state::<AppState>();
+    let entry = workspace_entry(&state, workspace_id).await?;
+    let existing = state.sessions.lock().await.get(&entry.id).cloned();
+    let session = match existing {
+        Some(session) => session,
+        None => {
+            connect_entry(entry.clone(), &state, app.clone()).await?;
+            let connected = state.sessions.lock().await.get(&entry.id).cloned();
+            connected.ok_or(AppError::NotConnected)?
+        }
+    };
+    let settings = state.settings.lock().await.clone();
+    let sandbox_mode =
+        observer::is_observing(&session).then(|| observer::READ_ONLY_SANDBOX.to_string());
+    let mut params = thread_override_params(
+        None,
+        sandbox_mode,
+        options.model.clone(),
+        options.effort.clone(),
+    )?;
+    let method = match &options.thread_id {
+        Some(thread_id) => {
+            params.insert("threadId".to_string(), json!(thread_id));
+            "thread/resume"
+        }
+        None => {
+            apply_policy_defaults(&settings, &mut params);
+            params.insert(
+                "cwd".to_string(),
+                json!(container::agent_cwd(&session.entry)),
+            );
+            "thread/start"
+        }
+    };
+    let response = session.send_request(method, Value::Object(params)).await?;
+    if let Some(error) = AppError::from_rpc_response(&response) {
+        return Err(error);
+    }
+    let thread_id = str_at(&response, "/result/thread/id")
+        .map(|id| id.to_string())
+        .or_else(|| options.thread_id.clone())
+        .ok_or("thread/start returned no thread id")?;
+    Ok((session, thread_id))
+}
+
+// Returns the thread id and, when known, the turn id.
+pub(crate) async fn start_prompt(
+    app: &AppHandle,
+    workspace_id: &str,
+    prompt: &str,
+    options: &PromptOptions,
+) -> Result<(String, Option<String>), AppError> {
+    let (session, thread_id) = open_thread(app, workspace_id, options).await?;
+    let settings = app.state::<AppState>().settings.lock().await.clone();
+    let message = UserMessageRequest {
+        thread_id,
+        text: prompt.to_string(),
+        model: options.model.clone(),
+        effort: options.effort.clone(),
+        access_mode: options.access_mode.clone(),
+        attachments: Vec::new(),
+    };
+    let response =
+        dispatch_user_message(app, &session, &message, settings.turn_checkpoints_enabled).await?;
+    if let Some(error) = AppError::from_rpc_response(&response) {
+        return Err(error);
+    }
+    let turn_id = str_at(&response, "/result/turn/id").map(|id| id.to_string());
+    Ok((message.thread_id, turn_id))
+}
+
+async fn run_task(app: &AppHandle, task: ScheduledTask, manual: bool) -> ScheduledRun {
+    let started_at_ms = now_ms();
+    let result = start_prompt(app, &task.workspace_id, &task.prompt, &task.options).await;
+    if let Err(err) = &result {
+        tracing::warn!(task_id = %task.id, "scheduled task failed: {err}");
+    }
+    let (thread_id, turn_id, error) = match result {
+        Ok((thread_id, turn_id)) => (Some(thread_id), turn_id, None),
+        Err(err) => (None, None, Some(err.to_string())),
+    };
+    let run = ScheduledRun {
+        id: Uuid::new_v4().to_string(),
+        task_id: task.id.clone(),
+        workspace_id: task.workspace_id.clone(),
+        scheduled_for_ms: (!manual).then_some(task.next_run_at_ms).flatten(),
+        started_at_ms,
+        status: if error.is_some() {
+            RunStatus::Failed
+        } else {
+            RunStatus::Started
+        },
+        thread_id,
+        turn_id,
+        error,
+        manual,
+    };
+
+    let state = app.state::<AppState>();
+    let path = store_path(&state);
+    let _guard = state.scheduler.lock.lock().await;
+    let result = read_store(&path).and_then(|mut store| {
+        if let Some(stored) = store.tasks.iter_mut().find(|stored| stored.id == task.id) {
+            stored.last_run_at_ms = Some(started_at_ms);
+            // Missed runs (app closed, rate limited) are not replayed; the
+            // schedule resumes from now.
+            if !manual {
+                stored.next_run_at_ms = next_run_after(&stored.recurrence, now_ms());
+            }
+        }
+        store.runs.push(run.clone());
+        if store.runs.len() > MAX_RUNS {
+            let excess = store.runs.len() - MAX_RUNS;
+            store.runs.drain(..excess);
+        }
+        write_store(&path, &store)
+    });
+    if let Err(err) = result {
+        tracing::warn!(task_id = %task.id, "failed to record scheduled run: {err}");
+    }
+    let _ = app.emit("scheduled-task-run", &run);
+    run
+}
+
+async fn run_due(app: &AppHandle) {
+    let state = app.state::<AppState>();
+    let now = now_ms();
+    let due: Vec<ScheduledTask> = {
+        let _guard = state.scheduler.lock.lock().await;
+        match read_store(&store_path(&state)) {
+            Ok(store) => store
+                .tasks
+                .into_iter()
+                .filter(|task| task.enabled && task.next_run_at_ms.is_some_and(|at| at <= now))
+                .collect(),
+            Err(err) => {
+                tracing::warn!("failed to read scheduled tasks: {err}");
+                return;
+            }
+        }
+    };
+    if due.is_empty() {
+        return;
+    }
+    let rate_limits = state.usage_store.lock().await.last_rate_limits.clone();
+    if let Some(resets_at) = rate_limits
+        .as_ref()
+        .and_then(|rate_limits| rate_limit_blocked_until(rate_limits, now / 1000))
+    {
+        let task_ids: Vec<_> = due.iter().map(|task| task.id.clone()).collect();
+        let _ = app.emit(
+            "scheduled-tasks-waiting",
+            json!({ "resetsAt": resets_at, "taskIds": task_ids }),
+        );
+        return;
+    }
+    for task in due {
+        run_task(app, task, false).await;
+    }
+}
+
+pub(crate) fn start(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(TICK).await;
+            run_due(&app).await;
+        }
+    });
+}
+
+#[tauri::command]
+pub(crate) async fn list_scheduled_tasks(
+    state: State<'_, AppState>,
+) -> Result<Vec<ScheduledTask>, AppError> {
+    let _guard = state.scheduler.lock.lock().await;
+    let mut tasks = read_store(&store_path(&state))?.tasks;
+    tasks.sort_by_key(|task| task.name.to_lowercase());
+    Ok(tasks)
+}
+
+#[tauri::command]
+pub(crate) async fn save_scheduled_task(
+    task: ScheduledTaskInput,
+    state: State<'_, AppState>,
+) -> Result<ScheduledTask, AppError> {
+    let name = task.name.trim().to_string();
+    if name.is_empty() {
+        return Err("Task name is required.".into());
+    }
+    if task.prompt.trim().is_empty() {
+        return Err("Task prompt is required.".into());
+    }
+    workspace_entry(&state, &task.workspace_id).await?;
+    validate_recurrence(&task.recurrence)?;
+    let now = now_ms();
+    let enabled = task.enabled.unwrap_or(true);
+    let next_run_at_ms = enabled
+        .then(|| next_run_after(&task.recurrence, now))
+        .flatten();
+
+    let path = store_path(&state);
+    let _guard = state.scheduler.lock.lock().await;
+    let mut store = read_store(&path)?;
+    let (id, created_at_ms, last_run_at_ms) = match &task.id {
+        Some(id) => {
+            let existing = store
+                .tasks
+                .iter()
+                .find(|existing| &existing.id == id)
+                .ok_or_else(|| AppError::from(format!("scheduled task not found: {id}")))?;
+            (id.clone(), existing.created_at_ms, existing.last_run_at_ms)
+        }
+        None => (Uuid::new_v4().to_string(), now, None),
+    };
+    let saved = ScheduledTask {
+        id,
+        name,
+        workspace_id: task.workspace_id,
+        prompt: task.prompt,
+        options: task.options.normalized(),
+        recurrence: task.recurrence,
+        enabled,
+        next_run_at_ms,
+        last_run_at_ms,
+        created_at_ms,
+        updated_at_ms: now,
+    };
+    match store
+        .tasks
+        .iter_mut()
+        .find(|existing| existing.id == saved.id)
+    {
+        Some(existing) => *existing = saved.clone(),
+        None => store.tasks.push(saved.clone()),
+    }
+    write_store(&path, &store)?;
+    Ok(saved)
+}
+
+#[tauri::command]
+pub(crate) async fn delete_scheduled_task(
+    task_id: String,
+    state: State<'_, AppState>,
+) -> Result<(), AppError> {
+    let path = store_path(&state);
+    let _guard = state.scheduler.lock.lock().await;
+    let mut store = read_store(&path)?;
+    let before = store.tasks.len();
+    store.tasks.retain(|task| task.id != task_id);
+    if store.tasks.len() == before {
+        return Err(format!("scheduled task not found: {task_id}").into());
+    }
+    store.runs.retain(|run| run.task_id != task_id);
+    write_store(&path, &store)?;
+    Ok(())
+}
+
+#[tauri::command]
+pub(crate) async fn run_scheduled_task_now(
+    task_id: String,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<ScheduledRun, AppError> {
+    let task = {
+        let _guard = state.scheduler.lock.lock().await;
+        read_store(&store_path(&state))?
+            .tasks
+            .into_iter()
+            .find(|task| task.id == task_id)
+            .ok_or_else(|| AppError::from(format!("scheduled task not found: {task_id}")))?
+    };
+    Ok(run_task(&app, task, true).await)
+}
+
+#[tauri::command]
+pub(crate) async fn list_scheduled_task_runs(
+    task_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<Vec<ScheduledRun>, AppError> {
+    let _guard = state.scheduler.lock.lock().await;
+    let runs = read_store(&store_path(&state))?.runs;
+    Ok(runs
+        .into_iter()
+        .rev()
+        .filter(|run| {
+            task_id
+                .as_ref()
+                .is_none_or(|task_id| &run.task_id == task_id)
+        })
+        .collect())
+}
@@ -0,0 +1,346 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use tauri::{AppHandle, Emitter, State};
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::{
+    default_session_store_version, now_ms, storage, workspace_entry, AppState, LocalImageInput,
+    UserMessageRequest,
+};
+
+const MAX_LOG_ENTRIES: usize = 200;
+const MAX_ATTACHMENT_BYTES: u64 = 1024 * 1024;
+const IMAGE_EXTENSIONS: &[&str] = &[
+    "bmp", "gif", "heic", "jpeg", "jpg", "png", "tif", "tiff", "webp",
+];
+
+const RULES: &[(&str, &str)] = &[
+    ("aws-access-key-id", r"\b(?:AKIA|ASIA)[0-9A-Z]{16}\b"),
+    (
+        "aws-secret-access-key",
+        r#"(?i)aws_secret_access_key\s*[:=]\s*["']?([A-Za-z0-9/+=]{40})"#,
+    ),
+    (
+        "private-key",
+        r"-----BEGIN (?:[A-Z]+ )?PRIVATE KEY-----[\s\S]*?-----END (?:[A-Z]+ )?PRIVATE KEY-----",
+    ),
+    ("github-token", r"\b(?:gh[pousr]_[A-Za-z0-9]{36,}|github_pat_[A-Za-z0-9_]{22,})\b"),
+    ("slack-token", r"\bxox[abprs]-[A-Za-z0-9-]{10,}\b"),
+    ("openai-api-key", r"\bsk-(?:proj-|ant-)?[A-Za-z0-9_-]{20,}"),
+    ("google-api-key", r"\bAIza[0-9A-Za-z_-]{35}\b"),
+    ("stripe-live-key", r"\b[rs]k_live_[0-9A-Za-z]{24,}\b"),
+    (
+        "jwt",
+        r"\beyJ[A-Za-z0-9_-]{10,}\.[A-Za-z0-9_-]{10,}\.[A-Za-z0-9_-]{10,}",
+    ),
+    (
+        "generic-secret",
+        r#"(?i)\b(?:api[_-]?key|secret|token|passw(?:or)?d)\s*[:=]\s*["']([^"'\s]{12,})["']"#,
+    ),
+];
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum SecretScanMode {
+    #[default]
+    Off,
+    Block,
+    Redact,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum SecretAction {
+    Block,
+    Redact,
+    Allow,
+}
+
+fn default_scan_attachments() -> bool {
+    true
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct SecretScanConfig {
+    #[serde(default)]
+    mode: SecretScanMode,
+    #[serde(default = "default_scan_attachments")]
+    scan_attachments: bool,
+}
+
+impl Default for SecretScanConfig {
+    fn default() -> Self {
+        Self {
+            mode: SecretScanMode::Off,
+            scan_attachments: default_scan_attachments(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct SecretFinding {
+    rule: String,
+    source: String,
+    line: usize,
+    preview: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct SecretScanLogEntry {
+    at_ms: i64,
+    thread_id: String,
+    action: SecretAction,
+    findings: Vec<SecretFinding>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct SecretScanStore {
+    #[serde(default = "default_session_store_version")]
+    version: u32,
+    #[serde(default)]
+    allowlist: BTreeSet<String>,
+    #[serde(default)]
+    log: Vec<SecretScanLogEntry>,
+}
+
+impl Default for SecretScanStore {
+    fn default() -> Self {
+        Self {
+            version: default_session_store_version(),
+            allowlist: BTreeSet::new(),
+            log: Vec::new(),
+        }
+    }
+}
+
+fn secret_scan_path(workspace_path: &str) -> PathBuf {
+    PathBuf::from(workspace_path)
+        .join(".codexmonitor")
+        .join("secrets.json")
+}
+
+fn read_secret_scan_store(path: &Path) -> Result<SecretScanStore, String> {
+    storage::read_json_or_default(path)
+}
+
+fn write_secret_scan_store(path: &Path, store: &SecretScanStore) -> Result<(), String> {
+    storage::write_json_atomic(path, store)
+}
+
+fn rules() -> &'static [(&'static str, Regex)] {
+    static RULES_CELL: OnceLock<Vec<(&'static str, Regex)>> = OnceLock::new();
+    RULES_CELL.get_or_init(|| {
+        RULES
+            .iter()
+            .filter_map(|(id, pattern)| Regex::new(pattern).ok().map(|regex| (*id, regex)))
+            .collect()
+    })
+}
+
+fn preview(secret: &str) -> String {
+    let head: String = secret.chars().take(4).collect();
+    format!("{head}… ({} chars)", secret.chars().count())
+}
+
+// Returns the findings and the text with every non-allowlisted secret
+// replaced by a `[REDACTED:<rule>]` marker.
+fn scan(text: &str, source: &str, allowlist: &BTreeSet<String>) -> (Vec<SecretFinding>, String) {
+    let mut spans = Vec::new();
+    for (rule, regex) in rules() {
+        if allowlist.contains(*rule) {
+            continue;
+        }
+        for captures in regex.captures_iter(text) {
+            let Some(secret) = captures.get(1).or_else(|| captures.get(0)) else {
+                continue;
+            };
+            if allowlist.contains(secret.as_str()) {
+                continue;
+            }
+            let overlaps = spans
+                .iter()
+                .any(|(start, end, _): &(usize, usize, &str)| {
+                    secret.start() < *end && *start < secret.end()
+                });
+            if !overlaps {
+                spans.push((secret.start(), secret.end(), *rule));
+            }
+        }
+    }
+    spans.sort_by_key(|(start, _, _)| *start);
+    let mut findings = Vec::new();
+    let mut redacted = String::with_capacity(text.len());
+    let mut cursor = 0;
+    for (start, end, rule) in spans {
+        findings.push(SecretFinding {
+            rule: rule.to_string(),
+            source: source.to_string(),
+            line: text[..start].matches('\n').count() + 1,
+            preview: preview(&text[start..end]),
+        });
+        redacted.push_str(&text[cursor..start]);
+        redacted.push_str(&format!("[REDACTED:{rule}]"));
+        cursor = end;
+    }
+    redacted.push_str(&text[cursor..]);
+    (findings, redacted)
+}
+
+fn read_text_attachment(path: &Path) -> Option<String> {
+    let extension = path
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    if IMAGE_EXTENSIONS.contains(&extension.as_str()) {
+        return None;
+    }
+    let metadata = fs::metadata(path).ok()?;
+    if !metadata.is_file() || metadata.len() > MAX_ATTACHMENT_BYTES {
+        return None;
+    }
+    String::from_utf8(fs::read(path).ok()?).ok()
+}
+
+fn write_redacted_copy(path: &Path, content: &str) -> Result<String, String> {
+    let dir = std::env::temp_dir().join("codexola-redacted");
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| "attachment".to_string());
+    let target = dir.join(format!("{}-{name}", Uuid::new_v4()));
+    fs::write(&target, content).map_err(|e| e.to_string())?;
+    Ok(target.to_string_lossy().to_string())
+}
+
+pub(crate) async fn screen(
+    app: &AppHandle,
+    state: &AppState,
+    workspace_id: &str,
+    mut message: UserMessageRequest,
+    action: Option<SecretAction>,
+) -> Result<UserMessageRequest, AppError> {
+    let config = state.settings.lock().await.secret_scanning.clone();
+    let action = match (action, config.mode) {
+        (Some(action), _) => action,
+        (None, SecretScanMode::Off) => return Ok(message),
+        (None, SecretScanMode::Block) => SecretAction::Block,
+        (None, SecretScanMode::Redact) => SecretAction::Redact,
+    };
+    let entry = workspace_entry(state, workspace_id).await?;
+    let path = secret_scan_path(&entry.path);
+    let _guard = state.secret_scan_lock.lock().await;
+    let mut store = read_secret_scan_store(&path)?;
+
+    let (mut findings, redacted_text) = scan(&message.text, "message", &store.allowlist);
+    let mut redacted_attachments = Vec::new();
+    for attachment in &message.attachments {
+        let text = config
+            .scan_attachments
+            .then(|| read_text_attachment(Path::new(&attachment.path)))
+            .flatten();
+        let Some(text) = text else {
+            redacted_attachments.push(attachment.clone());
+            continue;
+        };
+        let (found, redacted) = scan(&text, &attachment.path, &store.allowlist);
+        if found.is_empty() || action != SecretAction::Redact {
+            redacted_attachments.push(attachment.clone());
+        } else {
+            let path = write_redacted_copy(Path::new(&attachment.path), &redacted)?;
+            redacted_attachments.push(LocalImageInput { path });
+        }
+        findings.extend(found);
+    }
+    if findings.is_empty() {
+        return Ok(message);
+    }
+
+    store.log.push(SecretScanLogEntry {
+        at_ms: now_ms(),
+        thread_id: message.thread_id.clone(),
+        action,
+        findings: findings.clone(),
+    });
+    if store.log.len() > MAX_LOG_ENTRIES {
+        let excess = store.log.len() - MAX_LOG_ENTRIES;
+        store.log.drain(..excess);
+    }
+    write_secret_scan_store(&path, &store)?;
+    let _ = app.emit(
+        "secrets-detected",
+        serde_json::json!({
+            "workspaceId": workspace_id,
+            "threadId": message.thread_id,
+            "action": action,
+            "findings": findings,
+        }),
+    );
+
+    match action {
+        SecretAction::Block => Err(AppError::SecretsDetected {
+            message: format!(
+                "Message not sent: {} possible secret(s) detected.",
+                findings.len()
+            ),
+        }),
+        SecretAction::Redact => {
+            message.text = redacted_text;
+            message.attachments = redacted_attachments;
+            Ok(message)
+        }
+        SecretAction::Allow => Ok(message),
+    }
+}
+
+#[tauri::command]
+pub(crate) async fn get_secret_scan_store(
+    workspace_id: String,
+    state: State<'_, AppState>,
+) -> Result<SecretScanStore, AppError> {
+    let entry = workspace_entry(&state, &workspace_id).await?;
+    let _guard = state.secret_scan_lock.lock().await;
+    Ok(read_secret_scan_store(&secret_scan_path(&entry.path))?)
+}
+
+#[tauri::command]
+pub(crate) async fn set_secret_allowlist(
+    workspace_id: String,
+    allowlist: Vec<String>,
+    state: State<'_, AppState>,
+) -> Result<SecretScanStore, AppError> {
+    let entry = workspace_entry(&state, &workspace_id).await?;
+    let path = secret_scan_path(&entry.path);
+    let _guard = state.secret_scan_lock.lock().await;
+    let mut store = read_secret_scan_store(&path)?;
+    store.allowlist = allowlist
+        .into_iter()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+        .collect();
+    write_secret_scan_store(&path, &store)?;
+    Ok(store)
+}
+
+#[tauri::command]
+pub(crate) async fn clear_secret_scan_log(
+    workspace_id: String,
+    state: State<'_, AppState>,
+) -> Result<(), AppError> {
+    let entry = workspace_entry(&state, &workspace_id).await?;
+    let path = secret_scan_path(&entry.path);
+    let _guard = state.secret_scan_lock.lock().await;
+    let mut store = read_secret_scan_store(&path)?;
+    store.log.clear();
+    write_secret_scan_store(&path, &store)?;
+    Ok(())
+}
@@ -0,0 +1,166 @@
+use serde::Serialize;
+use std::collections::HashMap;
+use std::env;
+use std::time::Duration;
+use tauri::State;
+use tokio::sync::Mutex;
+
+use crate::error::AppError;
+use crate::{now_ms, platform, AppSettings, AppState};
+
+const CAPTURE_TIMEOUT: Duration = Duration::from_secs(10);
+const SKIPPED_VARS: &[&str] = &["_", "PWD", "OLDPWD", "SHLVL", "PS1", "TERM_SESSION_ID"];
+
+#[derive(Default)]
+struct CaptureState {
+    vars: Option<HashMap<String, String>>,
+    shell: Option<String>,
+    captured_at_ms: Option<i64>,
+    error: Option<String>,
+}
+
+#[derive(Default)]
+pub(crate) struct ShellEnv {
+    state: Mutex<CaptureState>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ShellEnvStatus {
+    shell: Option<String>,
+    captured_at_ms: Option<i64>,
+    variable_count: usize,
+    error: Option<String>,
+}
+
+fn login_shell() -> Option<String> {
+    if cfg!(windows) {
+        return None;
+    }
+    let fallback = if cfg!(target_os = "macos") {
+        "/bin/zsh"
+    } else {
+        "/bin/sh"
+    };
+    Some(
+        env::var("SHELL")
+            .ok()
+            .filter(|shell| !shell.trim().is_empty())
+            .unwrap_or_else(|| fallback.to_string()),
+    )
+}
+
+fn is_env_key(key: &str) -> bool {
+    !key.is_empty()
+        && !key.starts_with(|ch: char| ch.is_ascii_digit())
+        && key.chars().all(|ch| ch.is_ascii_alphanumeric() || ch == '_')
+}
+
+fn parse_env_output(output: &str) -> HashMap<String, String> {
+    let mut vars: HashMap<String, String> = HashMap::new();
+    let mut last_key: Option<String> = None;
+    for line in output.lines() {
+        match line.split_once('=') {
+            Some((key, value)) if is_env_key(key) => {
+                vars.insert(key.to_string(), value.to_string());
+                last_key = Some(key.to_string());
+            }
+            _ => {
+                // Multi-line values continue on the following lines.
+                if let Some(value) = last_key.as_ref().and_then(|key| vars.get_mut(key)) {
+                    value.push('\n');
+                    value.push_str(line);
+                }
+            }
+        }
+    }
+    vars.retain(|key, _| !SKIPPED_VARS.contains(&key.as_str()));
+    vars
+}
+
+async fn capture(shell: &str) -> Result<HashMap<String, String>, String> {
+    let mut command = platform::command(shell);
+    command
+        .args(["-lc", "env"])
+        .stdin(std::process::Stdio::null())
+        .kill_on_drop(true);
+    let output = tokio::time::timeout(CAPTURE_TIMEOUT, command.output())
+        .await
+        .map_err(|_| format!("{shell} -lc env timed out"))?
+        .map_err(|e| e.to_string())?;
+    if !output.status.success() {
+        return Err(format!("{shell} -lc env exited with {}", output.status));
+    }
+    let vars = parse_env_output(&String::from_utf8_lossy(&output.stdout));
+    if vars.is_empty() {
+        return Err(format!("{shell} -lc env returned no variables"));
+    }
+    Ok(vars)
+}
+
+impl ShellEnv {
+    async fn refresh_locked(state: &mut CaptureState) {
+        let Some(shell) = login_shell() else {
+            *state = CaptureState {
+                error: Some("login shell capture is not supported on this platform".into()),
+                ..CaptureState::default()
+            };
+            return;
+        };
+        match capture(&shell).await {
+            Ok(vars) => {
+                tracing::info!("captured {} variables from {shell}", vars.len());
+                *state = CaptureState {
+                    vars: Some(vars),
+                    shell: Some(shell),
+                    captured_at_ms: Some(now_ms()),
+                    error: None,
+                };
+            }
+            Err(err) => {
+                tracing::warn!("failed to capture login shell environment: {err}");
+                *state = CaptureState {
+                    shell: Some(shell),
+                    error: Some(err),
+                    ..CaptureState::default()
+                };
+            }
+        }
+    }
+
+    pub(crate) async fn vars(&self) -> Option<HashMap<String, String>> {
+        let mut state = self.state.lock().await;
+        if state.vars.is_none() && state.error.is_none() {
+            Self::refresh_locked(&mut state).await;
+        }
+        state.vars.clone()
+    }
+
+    pub(crate) async fn refresh(&self) -> ShellEnvStatus {
+        let mut state = self.state.lock().await;
+        Self::refresh_locked(&mut state).await;
+        ShellEnvStatus {
+            shell: state.shell.clone(),
+            captured_at_ms: state.captured_at_ms,
+            variable_count: state.vars.as_ref().map(|vars| vars.len()).unwrap_or(0),
+            error: state.error.clone(),
+        }
+    }
+}
+
+pub(crate) async fn spawn_env(
+    state: &AppState,
+    settings: &AppSettings,
+) -> Option<HashMap<String, String>> {
+    if !settings.login_shell_env_enabled {
+        return None;
+    }
+    state.shell_env.vars().await
+}
+
+#[tauri::command]
+pub(crate) async fn refresh_login_shell_env(
+    state: State<'_, AppState>,
+) -> Result<ShellEnvStatus, AppError> {
+    Ok(state.shell_env.refresh().await)
+}
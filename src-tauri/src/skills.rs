@@ -0,0 +1,389 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::State;
+
+use crate::error::AppError;
+use crate::{
+    default_session_store_version, storage, workspace_codex_home, workspace_entry, AppState,
+    WorkspaceEntry,
+};
+
+const SKILL_FILE: &str = "SKILL.md";
+const MAX_NAME_CHARS: usize = 64;
+const MAX_DESCRIPTION_CHARS: usize = 1024;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum SkillScope {
+    Workspace,
+    Global,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Skill {
+    id: String,
+    scope: SkillScope,
+    name: String,
+    description: String,
+    path: String,
+    body: String,
+    content: String,
+    enabled: bool,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct SkillInput {
+    name: String,
+    description: String,
+    #[serde(default)]
+    body: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct SkillSettingsStore {
+    #[serde(default = "default_session_store_version")]
+    version: u32,
+    #[serde(default)]
+    disabled: BTreeSet<String>,
+}
+
+impl Default for SkillSettingsStore {
+    fn default() -> Self {
+        Self {
+            version: default_session_store_version(),
+            disabled: BTreeSet::new(),
+        }
+    }
+}
+
+struct FrontMatter {
+    name: String,
+    description: String,
+    body: String,
+}
+
+fn workspace_skill_settings_path(workspace_path: &str) -> PathBuf {
+    PathBuf::from(workspace_path)
+        .join(".codexmonitor")
+        .join("skills.json")
+}
+
+fn read_skill_settings(path: &Path) -> Result<SkillSettingsStore, String> {
+    storage::read_json_or_default(path)
+}
+
+fn write_skill_settings(path: &Path, store: &SkillSettingsStore) -> Result<(), String> {
+    storage::write_json_atomic(path, store)
+}
+
+fn skills_root(entry: &WorkspaceEntry, scope: SkillScope) -> Result<PathBuf, String> {
+    match scope {
+        SkillScope::Workspace => Ok(PathBuf::from(&entry.path).join(".codex").join("skills")),
+        SkillScope::Global => workspace_codex_home(entry)
+            .map(|home| home.join("skills"))
+            .ok_or_else(|| "codex home unavailable".to_string()),
+    }
+}
+
+fn scope_prefix(scope: SkillScope) -> &'static str {
+    match scope {
+        SkillScope::Workspace => "workspace",
+        SkillScope::Global => "global",
+    }
+}
+
+fn valid_dir_name(name: &str) -> bool {
+    !name.is_empty()
+        && name != "."
+        && name != ".."
+        && !name.contains(['/', '\\'])
+        && !name.starts_with('.')
+}
+
+fn parse_skill_id(skill_id: &str) -> Result<(SkillScope, &str), String> {
+    let (prefix, dir) = skill_id
+        .split_once(':')
+        .ok_or_else(|| format!("invalid skill id: {skill_id}"))?;
+    let scope = match prefix {
+        "workspace" => SkillScope::Workspace,
+        "global" => SkillScope::Global,
+        _ => return Err(format!("invalid skill scope: {prefix}")),
+    };
+    if !valid_dir_name(dir) {
+        return Err(format!("invalid skill id: {skill_id}"));
+    }
+    Ok((scope, dir))
+}
+
+fn slugify(name: &str) -> String {
+    let mut slug = String::new();
+    for c in name.trim().chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.ends_with('-') && !slug.is_empty() {
+            slug.push('-');
+        }
+    }
+    slug.trim_end_matches('-').to_string()
+}
+
+fn unquote(value: &str) -> &str {
+    let value = value.trim();
+    value
+        .strip_prefix('"')
+        .and_then(|rest| rest.strip_suffix('"'))
+        .or_else(|| value.strip_prefix('\'').and_then(|rest| rest.strip_suffix('\'')))
+        .unwrap_or(value)
+}
+
+fn parse_front_matter(content: &str) -> Result<FrontMatter, String> {
+    let content = content.strip_prefix('\u{feff}').unwrap_or(content);
+    let rest = content
+        .strip_prefix("---\n")
+        .or_else(|| content.strip_prefix("---\r\n"))
+        .ok_or("SKILL.md must start with a front matter block")?;
+    let mut name = None;
+    let mut description = None;
+    let mut consumed = 0;
+    let mut closed = false;
+    for line in rest.split_inclusive('\n') {
+        consumed += line.len();
+        let trimmed = line.trim();
+        if trimmed == "---" {
+            closed = true;
+            break;
+        }
+        if let Some((key, value)) = trimmed.split_once(':') {
+            match key.trim() {
+                "name" => name = Some(unquote(value).to_string()),
+                "description" => description = Some(unquote(value).to_string()),
+                _ => {}
+            }
+        }
+    }
+    if !closed {
+        return Err("front matter block is not closed".to_string());
+    }
+    let front = FrontMatter {
+        name: name.unwrap_or_default(),
+        description: description.unwrap_or_default(),
+        body: rest[consumed..].trim_start_matches(['\r', '\n']).to_string(),
+    };
+    validate(&front.name, &front.description)?;
+    Ok(front)
+}
+
+fn validate(name: &str, description: &str) -> Result<(), String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("skill name is required".to_string());
+    }
+    if name.chars().count() > MAX_NAME_CHARS {
+        return Err(format!("skill name must be at most {MAX_NAME_CHARS} characters"));
+    }
+    let description = description.trim();
+    if description.is_empty() {
+        return Err("skill description is required".to_string());
+    }
+    if description.chars().count() > MAX_DESCRIPTION_CHARS {
+        return Err(format!(
+            "skill description must be at most {MAX_DESCRIPTION_CHARS} characters"
+        ));
+    }
+    if name.contains('\n') || description.contains('\n') {
+        return Err("skill name and description must be a single line".to_string());
+    }
+    Ok(())
+}
+
+fn render(input: &SkillInput) -> Result<String, String> {
+    validate(&input.name, &input.description)?;
+    let body = input.body.trim_start_matches(['\r', '\n']);
+    let mut content = format!(
+        "---\nname: {}\ndescription: {}\n---\n",
+        input.name.trim(),
+        input.description.trim()
+    );
+    if !body.is_empty() {
+        content.push('\n');
+        content.push_str(body);
+        if !body.ends_with('\n') {
+            content.push('\n');
+        }
+    }
+    Ok(content)
+}
+
+fn load_skill(
+    root: &Path,
+    scope: SkillScope,
+    dir: &str,
+    disabled: &BTreeSet<String>,
+) -> Result<Skill, String> {
+    let path = root.join(dir).join(SKILL_FILE);
+    let content = fs::read_to_string(&path).map_err(|e| format!("{}: {e}", path.display()))?;
+    let front = parse_front_matter(&content)?;
+    let id = format!("{}:{dir}", scope_prefix(scope));
+    Ok(Skill {
+        enabled: !disabled.contains(&id),
+        id,
+        scope,
+        name: front.name,
+        description: front.description,
+        path: path.to_string_lossy().to_string(),
+        body: front.body,
+        content,
+    })
+}
+
+fn disabled_skills(entry: &WorkspaceEntry) -> BTreeSet<String> {
+    read_skill_settings(&workspace_skill_settings_path(&entry.path))
+        .map(|store| store.disabled)
+        .unwrap_or_default()
+}
+
+// Marks each skill reported by the app-server with the per-workspace
+// enabled flag so pickers can hide skills the user switched off.
+pub(crate) fn annotate_enabled(entry: &WorkspaceEntry, response: &mut Value) {
+    let disabled = disabled_skills(entry);
+    if disabled.is_empty() {
+        return;
+    }
+    let roots: Vec<(SkillScope, PathBuf)> = [SkillScope::Workspace, SkillScope::Global]
+        .into_iter()
+        .filter_map(|scope| skills_root(entry, scope).ok().map(|root| (scope, root)))
+        .collect();
+    let result = if response.get("result").is_some() {
+        &mut response["result"]
+    } else {
+        response
+    };
+    let mut lists: Vec<&mut Value> = Vec::new();
+    if result.get("skills").is_some() {
+        lists.push(&mut result["skills"]);
+    } else if let Some(Value::Array(buckets)) = result.get_mut("data") {
+        lists.extend(buckets.iter_mut().filter_map(|bucket| bucket.get_mut("skills")));
+    }
+    for skill in lists.into_iter().filter_map(Value::as_array_mut).flatten() {
+        let Some(path) = skill.get("path").and_then(Value::as_str).map(PathBuf::from) else {
+            continue;
+        };
+        let dir = if path.file_name().is_some_and(|name| name == SKILL_FILE) {
+            path.parent().map(Path::to_path_buf).unwrap_or(path)
+        } else {
+            path
+        };
+        let id = roots.iter().find_map(|(scope, root)| {
+            let name = dir.strip_prefix(root).ok()?.to_str()?;
+            Some(format!("{}:{name}", scope_prefix(*scope)))
+        });
+        let enabled = id.is_none_or(|id| !disabled.contains(&id));
+        if let Some(object) = skill.as_object_mut() {
+            object.insert("enabled".to_string(), Value::Bool(enabled));
+        }
+    }
+}
+
+#[tauri::command]
+pub(crate) async fn skill_read(
+    workspace_id: String,
+    skill_id: String,
+    state: State<'_, AppState>,
+) -> Result<Skill, AppError> {
+    let entry = workspace_entry(&state, &workspace_id).await?;
+    let (scope, dir) = parse_skill_id(&skill_id)?;
+    let root = skills_root(&entry, scope)?;
+    Ok(load_skill(&root, scope, dir, &disabled_skills(&entry))?)
+}
+
+#[tauri::command]
+pub(crate) async fn skill_create(
+    workspace_id: String,
+    scope: SkillScope,
+    skill: SkillInput,
+    state: State<'_, AppState>,
+) -> Result<Skill, AppError> {
+    let entry = workspace_entry(&state, &workspace_id).await?;
+    let content = render(&skill)?;
+    let dir = slugify(&skill.name);
+    if !valid_dir_name(&dir) {
+        return Err("skill name must contain letters or digits".into());
+    }
+    let root = skills_root(&entry, scope)?;
+    let skill_dir = root.join(&dir);
+    if skill_dir.exists() {
+        return Err(format!("a skill named {dir} already exists").into());
+    }
+    fs::create_dir_all(&skill_dir)?;
+    fs::write(skill_dir.join(SKILL_FILE), content)?;
+    Ok(load_skill(&root, scope, &dir, &disabled_skills(&entry))?)
+}
+
+#[tauri::command]
+pub(crate) async fn skill_update(
+    workspace_id: String,
+    skill_id: String,
+    skill: SkillInput,
+    state: State<'_, AppState>,
+) -> Result<Skill, AppError> {
+    let entry = workspace_entry(&state, &workspace_id).await?;
+    let (scope, dir) = parse_skill_id(&skill_id)?;
+    let root = skills_root(&entry, scope)?;
+    let path = root.join(dir).join(SKILL_FILE);
+    if !path.is_file() {
+        return Err(format!("skill not found: {skill_id}").into());
+    }
+    fs::write(&path, render(&skill)?)?;
+    Ok(load_skill(&root, scope, dir, &disabled_skills(&entry))?)
+}
+
+#[tauri::command]
+pub(crate) async fn skill_delete(
+    workspace_id: String,
+    skill_id: String,
+    state: State<'_, AppState>,
+) -> Result<(), AppError> {
+    let entry = workspace_entry(&state, &workspace_id).await?;
+    let (scope, dir) = parse_skill_id(&skill_id)?;
+    let skill_dir = skills_root(&entry, scope)?.join(dir);
+    if !skill_dir.join(SKILL_FILE).is_file() {
+        return Err(format!("skill not found: {skill_id}").into());
+    }
+    fs::remove_dir_all(&skill_dir)?;
+    let path = workspace_skill_settings_path(&entry.path);
+    let _guard = state.skill_settings_lock.lock().await;
+    let mut store = read_skill_settings(&path)?;
+    if store.disabled.remove(&skill_id) {
+        write_skill_settings(&path, &store)?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub(crate) async fn skill_set_enabled(
+    workspace_id: String,
+    skill_id: String,
+    enabled: bool,
+    state: State<'_, AppState>,
+) -> Result<(), AppError> {
+    let entry = workspace_entry(&state, &workspace_id).await?;
+    parse_skill_id(&skill_id)?;
+    let path = workspace_skill_settings_path(&entry.path);
+    let _guard = state.skill_settings_lock.lock().await;
+    let mut store = read_skill_settings(&path)?;
+    let changed = if enabled {
+        store.disabled.remove(&skill_id)
+    } else {
+        store.disabled.insert(skill_id)
+    };
+    if changed {
+        write_skill_settings(&path, &store)?;
+    }
+    Ok(())
+}
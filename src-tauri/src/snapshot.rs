@@ -0,0 +1,140 @@
+use serde::Serialize;
+use tauri::{AppHandle, Manager, State};
+
+use crate::error::AppError;
+use crate::thread_state::{self, PendingApproval, ThreadActivity};
+use crate::{
+    empty_usage_snapshot, now_ms, read_workspace_sessions, workspace_infos,
+    workspace_sessions_path, AppSettings, AppState, SessionNameSource, UsageSnapshot,
+    WorkspaceInfo, WorkspaceSessionStore,
+};
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct ThreadSummary {
+    thread_id: String,
+    name: String,
+    archived: bool,
+    name_source: SessionNameSource,
+    state: ThreadActivity,
+    state_updated_at_ms: Option<i64>,
+    error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct WorkspaceSnapshot {
+    #[serde(flatten)]
+    workspace: WorkspaceInfo,
+    threads: Vec<ThreadSummary>,
+    sessions_error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct AppSnapshot {
+    workspaces: Vec<WorkspaceSnapshot>,
+    settings: AppSettings,
+    usage: UsageSnapshot,
+    pending_approvals: Vec<PendingApproval>,
+    generated_at_ms: i64,
+}
+
+async fn workspace_snapshots(state: &AppState) -> Vec<WorkspaceSnapshot> {
+    let workspaces = workspace_infos(state).await;
+    let reads: Vec<_> = workspaces
+        .iter()
+        .map(|workspace| {
+            let path = workspace_sessions_path(&workspace.path);
+            tokio::task::spawn_blocking(move || read_workspace_sessions(&path))
+        })
+        .collect();
+    let mut snapshots = Vec::with_capacity(workspaces.len());
+    for (workspace, read) in workspaces.into_iter().zip(reads) {
+        let (store, sessions_error) = match read.await {
+            Ok(Ok(store)) => (store, None),
+            Ok(Err(err)) => (WorkspaceSessionStore::default(), Some(err)),
+            Err(err) => (WorkspaceSessionStore::default(), Some(err.to_string())),
+        };
+        let states = thread_state::workspace_thread_states(state, &workspace.id, &store).await;
+        let mut threads: Vec<ThreadSummary> = store
+            .sessions
+            .into_iter()
+            .map(|(thread_id, metadata)| ThreadSummary {
+                thread_id,
+                name: metadata.name,
+                archived: metadata.archived,
+                name_source: metadata.name_source,
+                state: ThreadActivity::Idle,
+                state_updated_at_ms: None,
+                error: None,
+            })
+            .collect();
+        for thread in states {
+            let index = match threads
+                .iter()
+                .position(|summary| summary.thread_id == thread.thread_id)
+            {
+                Some(index) => index,
+                None => {
+                    threads.push(ThreadSummary {
+                        thread_id: thread.thread_id.clone(),
+                        name: String::new(),
+                        archived: false,
+                        name_source: SessionNameSource::default(),
+                        state: ThreadActivity::Idle,
+                        state_updated_at_ms: None,
+                        error: None,
+                    });
+                    threads.len() - 1
+                }
+            };
+            let summary = &mut threads[index];
+            summary.state = thread.state;
+            summary.state_updated_at_ms = Some(thread.updated_at_ms);
+            summary.error = thread.error;
+        }
+        threads.sort_by(|a, b| {
+            b.state_updated_at_ms
+                .cmp(&a.state_updated_at_ms)
+                .then_with(|| a.name.cmp(&b.name))
+        });
+        snapshots.push(WorkspaceSnapshot {
+            workspace,
+            threads,
+            sessions_error,
+        });
+    }
+    snapshots
+}
+
+#[tauri::command]
+pub(crate) async fn get_app_snapshot(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<AppSnapshot, AppError> {
+    let workspaces = {
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            workspace_snapshots(&app.state::<AppState>()).await
+        })
+    };
+    let pending_approvals = tauri::async_runtime::spawn(async move {
+        thread_state::pending_approvals(&app.state::<AppState>()).await
+    });
+    let settings = state.settings.lock().await.clone();
+    let usage = state
+        .usage_store
+        .lock()
+        .await
+        .last_snapshot
+        .clone()
+        .unwrap_or_else(empty_usage_snapshot);
+    Ok(AppSnapshot {
+        workspaces: workspaces.await.map_err(|e| e.to_string())?,
+        settings,
+        usage,
+        pending_approvals: pending_approvals.await.map_err(|e| e.to_string())?,
+        generated_at_ms: now_ms(),
+    })
+}
@@ -0,0 +1,139 @@
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::Ordering;
+use tauri::{AppHandle, Manager, State};
+use tokio::process::Child;
+use tokio::sync::Mutex;
+
+use crate::error::AppError;
+use crate::{platform, write_workspaces, AppState};
+
+const SUMMARY_LIMIT: usize = 200;
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct SpeechConfig {
+    #[serde(default)]
+    voice: Option<String>,
+    #[serde(default = "default_only_when_unfocused")]
+    only_when_unfocused: bool,
+}
+
+#[derive(Default)]
+pub(crate) struct Speaker {
+    current: Mutex<Option<Child>>,
+}
+
+fn default_only_when_unfocused() -> bool {
+    true
+}
+
+impl Default for SpeechConfig {
+    fn default() -> Self {
+        Self {
+            voice: None,
+            only_when_unfocused: default_only_when_unfocused(),
+        }
+    }
+}
+
+fn summary(body: &str) -> String {
+    let body = body.trim_end_matches('…').trim();
+    let sentence_end = body
+        .char_indices()
+        .find(|(index, c)| {
+            matches!(c, '.' | '!' | '?' | '。' | '！' | '？')
+                && body[index + c.len_utf8()..]
+                    .chars()
+                    .next()
+                    .is_none_or(char::is_whitespace)
+        })
+        .map(|(index, c)| index + c.len_utf8())
+        .unwrap_or(body.len());
+    let sentence = &body[..sentence_end];
+    match sentence.char_indices().nth(SUMMARY_LIMIT) {
+        Some((index, _)) => sentence[..index].to_string(),
+        None => sentence.to_string(),
+    }
+}
+
+// Text and voice go through the environment on Windows so nothing from the
+// agent's reply is ever interpreted as PowerShell.
+fn speech_command(text: &str, voice: Option<&str>) -> Option<tokio::process::Command> {
+    if cfg!(target_os = "macos") {
+        let mut command = platform::command("say");
+        if let Some(voice) = voice {
+            command.args(["-v", voice]);
+        }
+        command.arg("--").arg(text);
+        Some(command)
+    } else if cfg!(windows) {
+        let mut command = platform::command("powershell");
+        command
+            .args(["-NoLogo", "-NoProfile", "-NonInteractive", "-Command"])
+            .arg(
+                "Add-Type -AssemblyName System.Speech; \
+                 $s = New-Object System.Speech.Synthesis.SpeechSynthesizer; \
+                 if ($env:CODEXOLA_SPEECH_VOICE) { $s.SelectVoice($env:CODEXOLA_SPEECH_VOICE) }; \
+                 $s.Speak($env:CODEXOLA_SPEECH_TEXT)",
+            )
+            .env("CODEXOLA_SPEECH_TEXT", text)
+            .env("CODEXOLA_SPEECH_VOICE", voice.unwrap_or_default());
+        Some(command)
+    } else {
+        None
+    }
+}
+
+pub(crate) async fn speak_completion(
+    app: &AppHandle,
+    workspace_id: Option<&str>,
+    body: &str,
+    quiet: bool,
+) {
+    let state = app.state::<AppState>();
+    let Some(workspace_id) = workspace_id else {
+        return;
+    };
+    let Some(workspace_name) = state
+        .workspaces
+        .lock()
+        .await
+        .get(workspace_id)
+        .filter(|entry| entry.speak_completions)
+        .map(|entry| entry.name.clone())
+    else {
+        return;
+    };
+    let config = state.settings.lock().await.notifications.speech.clone();
+    if quiet || (config.only_when_unfocused && state.window_focused.load(Ordering::SeqCst)) {
+        return;
+    }
+    let text = format!("{workspace_name}: {}", summary(body));
+    let voice = config
+        .voice
+        .as_deref()
+        .filter(|voice| !voice.trim().is_empty());
+    let Some(mut command) = speech_command(&text, voice) else {
+        return;
+    };
+    command.kill_on_drop(true);
+    match command.spawn() {
+        // Replacing the previous child stops it, so completions never pile up.
+        Ok(child) => *state.speaker.current.lock().await = Some(child),
+        Err(err) => tracing::warn!("failed to start speech: {err}"),
+    }
+}
+
+#[tauri::command]
+pub(crate) async fn set_workspace_speech(
+    id: String,
+    enabled: bool,
+    state: State<'_, AppState>,
+) -> Result<bool, AppError> {
+    let mut workspaces = state.workspaces.lock().await;
+    let entry = workspaces.get_mut(&id).ok_or(AppError::WorkspaceNotFound)?;
+    entry.speak_completions = enabled;
+    let list: Vec<_> = workspaces.values().cloned().collect();
+    write_workspaces(&state.storage_path, &list)?;
+    Ok(enabled)
+}
@@ -0,0 +1,226 @@
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+use tauri::State;
+use tokio::sync::Mutex;
+
+use crate::error::AppError;
+use crate::{
+    build_search_overrides, normalize_path, platform, read_search_config, workspace_entry,
+    workspace_search_config_path, workspace_walker, AppState,
+};
+
+const MAX_DIRECTORY_DEPTH: usize = 3;
+const LARGEST_DIRECTORIES: usize = 15;
+
+const LANGUAGES: &[(&str, &str)] = &[
+    ("c", "C"),
+    ("cc", "C++"),
+    ("cpp", "C++"),
+    ("cs", "C#"),
+    ("css", "CSS"),
+    ("go", "Go"),
+    ("h", "C"),
+    ("hpp", "C++"),
+    ("html", "HTML"),
+    ("java", "Java"),
+    ("js", "JavaScript"),
+    ("json", "JSON"),
+    ("jsx", "JavaScript"),
+    ("kt", "Kotlin"),
+    ("md", "Markdown"),
+    ("mjs", "JavaScript"),
+    ("php", "PHP"),
+    ("py", "Python"),
+    ("rb", "Ruby"),
+    ("rs", "Rust"),
+    ("scss", "CSS"),
+    ("sh", "Shell"),
+    ("sql", "SQL"),
+    ("swift", "Swift"),
+    ("toml", "TOML"),
+    ("ts", "TypeScript"),
+    ("tsx", "TypeScript"),
+    ("yaml", "YAML"),
+    ("yml", "YAML"),
+];
+
+#[derive(Default)]
+pub(crate) struct StatsJobs {
+    running: Mutex<HashMap<String, Arc<AtomicBool>>>,
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+struct Tally {
+    files: u64,
+    bytes: u64,
+}
+
+impl Tally {
+    fn add(&mut self, bytes: u64) {
+        self.files += 1;
+        self.bytes += bytes;
+    }
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct DirectoryStats {
+    path: String,
+    #[serde(flatten)]
+    tally: Tally,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct LanguageStats {
+    language: String,
+    #[serde(flatten)]
+    tally: Tally,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct WorkspaceStats {
+    workspace_id: String,
+    file_count: u64,
+    directory_count: u64,
+    total_bytes: u64,
+    largest_directories: Vec<DirectoryStats>,
+    languages: Vec<LanguageStats>,
+    elapsed_ms: u64,
+}
+
+fn language_of(path: &Path) -> String {
+    let Some(extension) = path.extension().map(|ext| ext.to_string_lossy().to_lowercase()) else {
+        return "Other".to_string();
+    };
+    LANGUAGES
+        .iter()
+        .find(|(known, _)| *known == extension)
+        .map(|(_, language)| language.to_string())
+        .unwrap_or_else(|| format!(".{extension}"))
+}
+
+fn ranked<T>(
+    tallies: HashMap<String, Tally>,
+    limit: usize,
+    wrap: fn(String, Tally) -> T,
+) -> Vec<T> {
+    let mut entries: Vec<_> = tallies.into_iter().collect();
+    entries.sort_by(|a, b| b.1.bytes.cmp(&a.1.bytes).then_with(|| a.0.cmp(&b.0)));
+    entries
+        .into_iter()
+        .take(limit)
+        .map(|(key, tally)| wrap(key, tally))
+        .collect()
+}
+
+fn collect_stats(
+    workspace_id: String,
+    root: &Path,
+    overrides: ignore::overrides::Override,
+    cancelled: &AtomicBool,
+) -> Result<WorkspaceStats, String> {
+    let started = Instant::now();
+    let mut total = Tally::default();
+    let mut directory_count = 0;
+    let mut directories: HashMap<String, Tally> = HashMap::new();
+    let mut languages: HashMap<String, Tally> = HashMap::new();
+    for entry in workspace_walker(root, overrides) {
+        if cancelled.load(Ordering::Relaxed) {
+            return Err("workspace stats cancelled".to_string());
+        }
+        let Ok(entry) = entry else {
+            continue;
+        };
+        let Some(file_type) = entry.file_type() else {
+            continue;
+        };
+        if file_type.is_dir() {
+            if entry.depth() > 0 {
+                directory_count += 1;
+            }
+            continue;
+        }
+        if !file_type.is_file() {
+            continue;
+        }
+        let bytes = entry.metadata().map(|metadata| metadata.len()).unwrap_or(0);
+        total.add(bytes);
+        languages.entry(language_of(entry.path())).or_default().add(bytes);
+        let Ok(relative) = entry.path().strip_prefix(root) else {
+            continue;
+        };
+        for ancestor in relative.ancestors().skip(1) {
+            let depth = ancestor.components().count();
+            if depth == 0 || depth > MAX_DIRECTORY_DEPTH {
+                continue;
+            }
+            directories.entry(normalize_path(ancestor)).or_default().add(bytes);
+        }
+    }
+    Ok(WorkspaceStats {
+        workspace_id,
+        file_count: total.files,
+        directory_count,
+        total_bytes: total.bytes,
+        largest_directories: ranked(directories, LARGEST_DIRECTORIES, |path, tally| {
+            DirectoryStats { path, tally }
+        }),
+        languages: ranked(languages, usize::MAX, |language, tally| LanguageStats {
+            language,
+            tally,
+        }),
+        elapsed_ms: started.elapsed().as_millis() as u64,
+    })
+}
+
+#[tauri::command]
+pub(crate) async fn workspace_stats(
+    workspace_id: String,
+    state: State<'_, AppState>,
+) -> Result<WorkspaceStats, AppError> {
+    let entry = workspace_entry(&state, &workspace_id).await?;
+    let root = platform::long_path(Path::new(&entry.path));
+    let config = read_search_config(&workspace_search_config_path(&entry.path))?;
+    let overrides = build_search_overrides(&root, &config)?;
+    let cancelled = Arc::new(AtomicBool::new(false));
+    if let Some(previous) = state
+        .stats_jobs
+        .running
+        .lock()
+        .await
+        .insert(workspace_id.clone(), Arc::clone(&cancelled))
+    {
+        previous.store(true, Ordering::Relaxed);
+    }
+    let flag = Arc::clone(&cancelled);
+    let id = workspace_id.clone();
+    let result =
+        tokio::task::spawn_blocking(move || collect_stats(id, &root, overrides, &flag)).await?;
+    let mut running = state.stats_jobs.running.lock().await;
+    if running
+        .get(&workspace_id)
+        .is_some_and(|current| Arc::ptr_eq(current, &cancelled))
+    {
+        running.remove(&workspace_id);
+    }
+    Ok(result?)
+}
+
+#[tauri::command]
+pub(crate) async fn cancel_workspace_stats(
+    workspace_id: String,
+    state: State<'_, AppState>,
+) -> Result<bool, AppError> {
+    let job = state.stats_jobs.running.lock().await.remove(&workspace_id);
+    if let Some(cancelled) = &job {
+        cancelled.store(true, Ordering::Relaxed);
+    }
+    Ok(job.is_some())
+}
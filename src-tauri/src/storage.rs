@@ -0,0 +1,159 @@
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fs::{self, File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
+static READ_ONLY: AtomicBool = AtomicBool::new(false);
+static TMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+pub(crate) fn set_read_only(read_only: bool) {
+    READ_ONLY.store(read_only, Ordering::SeqCst);
+}
+
+pub(crate) struct InstanceLock {
+    _file: File,
+}
+
+#[cfg(unix)]
+fn try_lock_file(file: &File) -> bool {
+    use std::os::unix::io::AsRawFd;
+    unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX | libc::LOCK_NB) == 0 }
+}
+
+#[cfg(not(unix))]
+fn try_lock_file(_file: &File) -> bool {
+    true
+}
+
+fn open_lock_file(path: &Path) -> std::io::Result<File> {
+    let mut options = OpenOptions::new();
+    options.read(true).write(true).create(true).truncate(false);
+    #[cfg(windows)]
+    {
+        use std::os::windows::fs::OpenOptionsExt;
+        options.share_mode(0);
+    }
+    options.open(path)
+}
+
+pub(crate) fn acquire_instance_lock(dir: &Path) -> Result<InstanceLock, String> {
+    fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    let path = dir.join("instance.lock");
+    let locked_message = |owner: Option<String>| match owner {
+        Some(pid) => format!(
+            "{} is in use by another Codexola instance (pid {pid})",
+            dir.display()
+        ),
+        None => format!("{} is in use by another Codexola instance", dir.display()),
+    };
+    let mut file = match open_lock_file(&path) {
+        Ok(file) => file,
+        Err(err) if cfg!(windows) && err.raw_os_error() == Some(32) => {
+            return Err(locked_message(None));
+        }
+        Err(err) => return Err(err.to_string()),
+    };
+    if !try_lock_file(&file) {
+        let mut owner = String::new();
+        let _ = file.read_to_string(&mut owner);
+        let owner = owner.trim();
+        return Err(locked_message((!owner.is_empty()).then(|| owner.to_string())));
+    }
+    file.set_len(0).map_err(|e| e.to_string())?;
+    file.seek(SeekFrom::Start(0)).map_err(|e| e.to_string())?;
+    write!(file, "{}", std::process::id()).map_err(|e| e.to_string())?;
+    let _ = file.sync_all();
+    Ok(InstanceLock { _file: file })
+}
+
+fn sibling_path(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(suffix);
+    path.with_file_name(name)
+}
+
+pub(crate) fn backup_path(path: &Path) -> PathBuf {
+    sibling_path(path, ".bak")
+}
+
+#[cfg(unix)]
+fn sync_dir(dir: &Path) {
+    if let Ok(handle) = File::open(dir) {
+        let _ = handle.sync_all();
+    }
+}
+
+#[cfg(not(unix))]
+fn sync_dir(_dir: &Path) {}
+
+pub(crate) fn write_json_atomic<T: Serialize + ?Sized>(
+    path: &Path,
+    value: &T,
+) -> Result<(), String> {
+    if READ_ONLY.load(Ordering::SeqCst) {
+        return Err("data directory is locked by another instance".to_string());
+    }
+    let parent = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    let data = serde_json::to_vec_pretty(value).map_err(|e| e.to_string())?;
+
+    // Concurrent writers of the same store must not share a temp file.
+    let tmp_id = TMP_COUNTER.fetch_add(1, Ordering::Relaxed);
+    let tmp_path = sibling_path(path, &format!(".{}.{tmp_id}.tmp", std::process::id()));
+    let write_tmp = || -> std::io::Result<()> {
+        let mut file = File::create(&tmp_path)?;
+        file.write_all(&data)?;
+        file.sync_all()
+    };
+    if let Err(err) = write_tmp() {
+        let _ = fs::remove_file(&tmp_path);
+        return Err(err.to_string());
+    }
+
+    if path.exists() {
+        let current = fs::read(path).unwrap_or_default();
+        if serde_json::from_slice::<serde_json::Value>(&current).is_ok() {
+            let _ = fs::copy(path, backup_path(path));
+        }
+    }
+    if let Err(err) = fs::rename(&tmp_path, path) {
+        let _ = fs::remove_file(&tmp_path);
+        return Err(err.to_string());
+    }
+    sync_dir(parent);
+    Ok(())
+}
+
+fn parse_json_file<T: DeserializeOwned>(path: &Path) -> Result<T, String> {
+    let data = fs::read(path).map_err(|e| e.to_string())?;
+    serde_json::from_slice(&data).map_err(|e| format!("{}: {e}", path.display()))
+}
+
+pub(crate) fn read_json_or_default<T: DeserializeOwned + Default>(
+    path: &Path,
+) -> Result<T, String> {
+    let backup = backup_path(path);
+    if !path.exists() {
+        if backup.exists() {
+            return parse_json_file(&backup);
+        }
+        return Ok(T::default());
+    }
+    match parse_json_file(path) {
+        Ok(value) => Ok(value),
+        Err(err) => {
+            if !backup.exists() {
+                return Err(err);
+            }
+            let value = parse_json_file(&backup).map_err(|_| err.clone())?;
+            tracing::warn!("recovered {} from backup: {err}", path.display());
+            let _ = fs::copy(&backup, path);
+            Ok(value)
+        }
+    }
+}
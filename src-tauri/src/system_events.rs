@@ -0,0 +1,150 @@
+use serde::Serialize;
+use serde_json::json;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::{
+    connect_workspace, now_ms, platform, refresh_usage_snapshot, AppState, WorkspaceSession,
+};
+
+const TICK: Duration = Duration::from_secs(5);
+const SLEEP_GAP: Duration = Duration::from_secs(30);
+const PROBE_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Serialize, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+enum RevalidateReason {
+    Wake,
+    NetworkChange,
+}
+
+#[derive(Debug, Serialize, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+enum SessionStatus {
+    Healthy,
+    Reconnected,
+    Failed,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct SessionRevalidation {
+    workspace_id: String,
+    status: SessionStatus,
+    error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct SessionsRevalidated {
+    reason: RevalidateReason,
+    checked_at_ms: i64,
+    sessions: Vec<SessionRevalidation>,
+    usage_refreshed: bool,
+}
+
+async fn probe(session: &WorkspaceSession) -> bool {
+    if !matches!(session.child.lock().await.try_wait(), Ok(None)) {
+        return false;
+    }
+    let request = session.send_request("thread/list", json!({ "limit": 1 }));
+    matches!(
+        tokio::time::timeout(PROBE_TIMEOUT, request).await,
+        Ok(Ok(ref response)) if response.get("error").is_none()
+    )
+}
+
+async fn reconnect(
+    app: &AppHandle,
+    workspace_id: &str,
+    stale: &Arc<WorkspaceSession>,
+) -> Result<(), String> {
+    let state = app.state::<AppState>();
+    {
+        let mut sessions = state.sessions.lock().await;
+        if sessions
+            .get(workspace_id)
+            .is_some_and(|current| Arc::ptr_eq(current, stale))
+        {
+            sessions.remove(workspace_id);
+        }
+    }
+    let _ = stale.child.lock().await.kill().await;
+    stale.pending.lock().await.clear();
+    connect_workspace(workspace_id.to_string(), app.state(), app.clone())
+        .await
+        .map_err(|err| err.to_string())
+}
+
+async fn revalidate(app: &AppHandle, reason: RevalidateReason) {
+    tracing::info!(?reason, "revalidating sessions");
+    let state = app.state::<AppState>();
+    let sessions: Vec<_> = state
+        .sessions
+        .lock()
+        .await
+        .iter()
+        .map(|(id, session)| (id.clone(), session.clone()))
+        .collect();
+    let mut results = Vec::new();
+    for (workspace_id, session) in sessions {
+        let (status, error) = if probe(&session).await {
+            (SessionStatus::Healthy, None)
+        } else {
+            match reconnect(app, &workspace_id, &session).await {
+                Ok(()) => (SessionStatus::Reconnected, None),
+                Err(err) => {
+                    tracing::warn!(%workspace_id, "failed to reconnect session: {err}");
+                    (SessionStatus::Failed, Some(err))
+                }
+            }
+        };
+        results.push(SessionRevalidation {
+            workspace_id,
+            status,
+            error,
+        });
+    }
+    let usage_refreshed = match refresh_usage_snapshot(app).await {
+        Ok(_) => true,
+        Err(err) => {
+            tracing::debug!("usage refresh failed: {err}");
+            false
+        }
+    };
+    let _ = app.emit(
+        "sessions-revalidated",
+        SessionsRevalidated {
+            reason,
+            checked_at_ms: now_ms(),
+            sessions: results,
+            usage_refreshed,
+        },
+    );
+}
+
+// Sleep shows up as a wall-clock jump between ticks; network changes as a
+// different set of interface addresses.
+pub(crate) fn start(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut last_tick = SystemTime::now();
+        let mut addresses = platform::network_addresses();
+        loop {
+            tokio::time::sleep(TICK).await;
+            let now = SystemTime::now();
+            let elapsed = now.duration_since(last_tick).unwrap_or_default();
+            last_tick = now;
+            let current = platform::network_addresses();
+            let network_changed = current != addresses;
+            addresses = current;
+            if elapsed > TICK + SLEEP_GAP {
+                revalidate(&app, RevalidateReason::Wake).await;
+                last_tick = SystemTime::now();
+            } else if network_changed {
+                revalidate(&app, RevalidateReason::NetworkChange).await;
+                last_tick = SystemTime::now();
+            }
+        }
+    });
+}
@@ -0,0 +1,480 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter, Manager, State};
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::scheduler::{self, PromptOptions};
+use crate::thread_state::{self, message_thread_id, str_at, ThreadActivity};
+use crate::{
+    default_session_store_version, now_ms, storage, workspace_entry, AppState, WorkspaceEntry,
+};
+
+const MAX_FINISHED: usize = 100;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum TaskStatus {
+    Queued,
+    Running,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct QueuedTask {
+    id: String,
+    pub(crate) prompt: String,
+    #[serde(default)]
+    options: PromptOptions,
+    #[serde(default)]
+    batch_id: Option<String>,
+    pub(crate) status: TaskStatus,
+    pub(crate) created_at_ms: i64,
+    #[serde(default)]
+    pub(crate) started_at_ms: Option<i64>,
+    #[serde(default)]
+    pub(crate) finished_at_ms: Option<i64>,
+    #[serde(default)]
+    pub(crate) thread_id: Option<String>,
+    #[serde(default)]
+    pub(crate) turn_id: Option<String>,
+    #[serde(default)]
+    pub(crate) error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct TaskQueueStore {
+    #[serde(default = "default_session_store_version")]
+    version: u32,
+    #[serde(default)]
+    tasks: Vec<QueuedTask>,
+}
+
+impl Default for TaskQueueStore {
+    fn default() -> Self {
+        Self {
+            version: default_session_store_version(),
+            tasks: Vec::new(),
+        }
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct TaskQueues {
+    lock: Mutex<()>,
+}
+
+fn queue_path(workspace_path: &str) -> PathBuf {
+    PathBuf::from(workspace_path)
+        .join(".codexmonitor")
+        .join("task_queue.json")
+}
+
+fn read_queue(path: &Path) -> Result<TaskQueueStore, String> {
+    storage::read_json_or_default(path)
+}
+
+fn write_queue(path: &Path, store: &TaskQueueStore) -> Result<(), String> {
+    storage::write_json_atomic(path, store)
+}
+
+fn finish(task: &mut QueuedTask, status: TaskStatus, error: Option<String>) {
+    task.status = status;
+    task.error = error;
+    task.finished_at_ms = Some(now_ms());
+}
+
+fn prune(store: &mut TaskQueueStore) {
+    let finished = store
+        .tasks
+        .iter()
+        .filter(|task| !matches!(task.status, TaskStatus::Queued | TaskStatus::Running))
+        .count();
+    let mut excess = finished.saturating_sub(MAX_FINISHED);
+    store.tasks.retain(|task| {
+        let finished = !matches!(task.status, TaskStatus::Queued | TaskStatus::Running);
+        if finished && excess > 0 {
+            excess -= 1;
+            return false;
+        }
+        true
+    });
+}
+
+async fn queue_file(app: &AppHandle, workspace_id: &str) -> Option<PathBuf> {
+    app.state::<AppState>()
+        .workspaces
+        .lock()
+        .await
+        .get(workspace_id)
+        .map(|entry| queue_path(&entry.path))
+}
+
+// Callers hold the queue lock; the update runs against a fresh read so
+// concurrent edits from the UI and the runner never overwrite each other.
+async fn update_queue<T>(
+    app: &AppHandle,
+    workspace_id: &str,
+    update: impl FnOnce(&mut TaskQueueStore) -> Result<T, AppError>,
+) -> Result<T, AppError> {
+    let path = queue_file(app, workspace_id)
+        .await
+        .ok_or(AppError::WorkspaceNotFound)?;
+    let mut store = read_queue(&path)?;
+    let result = update(&mut store)?;
+    prune(&mut store);
+    write_queue(&path, &store)?;
+    let _ = app.emit(
+        "task-queue-updated",
+        json!({ "workspaceId": workspace_id, "tasks": store.tasks }),
+    );
+    Ok(result)
+}
+
+// The queue lock is never held while a turn is being started: the reader
+// loop calls `observe` and would otherwise wait on the response it carries.
+async fn advance(app: &AppHandle, workspace_id: &str) {
+    let state = app.state::<AppState>();
+    let next = {
+        let _guard = state.task_queues.lock.lock().await;
+        update_queue(app, workspace_id, |store| {
+            if store
+                .tasks
+                .iter()
+                .any(|task| task.status == TaskStatus::Running)
+            {
+                return Ok(None);
+            }
+            let Some(task) = store
+                .tasks
+                .iter_mut()
+                .find(|task| task.status == TaskStatus::Queued)
+            else {
+                return Ok(None);
+            };
+            task.status = TaskStatus::Running;
+            task.started_at_ms = Some(now_ms());
+            Ok(Some(task.clone()))
+        })
+        .await
+    };
+    let task = match next {
+        Ok(Some(task)) => task,
+        Ok(None) => return,
+        Err(err) => {
+            tracing::warn!(workspace_id, "failed to advance task queue: {err}");
+            return;
+        }
+    };
+
+    let started = scheduler::start_prompt(app, workspace_id, &task.prompt, &task.options).await;
+    let failed = started.is_err();
+    let guard = state.task_queues.lock.lock().await;
+    let result = update_queue(app, workspace_id, |store| {
+        let Some(stored) = store.tasks.iter_mut().find(|stored| stored.id == task.id) else {
+            return Ok(());
+        };
+        if stored.status != TaskStatus::Running {
+            return Ok(());
+        }
+        match started {
+            Ok((thread_id, turn_id)) => {
+                stored.thread_id = Some(thread_id);
+                stored.turn_id = turn_id;
+            }
+            Err(err) => finish(stored, TaskStatus::Failed, Some(err.to_string())),
+        }
+        Ok(())
+    })
+    .await;
+    if let Err(err) = result {
+        tracing::warn!(workspace_id, "failed to update task queue: {err}");
+    }
+    drop(guard);
+    if failed {
+        spawn_advance(app, workspace_id);
+    }
+}
+
+fn spawn_advance(app: &AppHandle, workspace_id: &str) {
+    let app = app.clone();
+    let workspace_id = workspace_id.to_string();
+    tauri::async_runtime::spawn(async move {
+        advance(&app, &workspace_id).await;
+    });
+}
+
+pub(crate) async fn observe(app: &AppHandle, workspace_id: &str, message: &Value) {
+    if message.get("method").and_then(|method| method.as_str()) != Some("turn/completed") {
+        return;
+    }
+    let params = message.get("params").unwrap_or(&Value::Null);
+    let Some(thread_id) = message_thread_id(params) else {
+        return;
+    };
+    let turn_id = str_at(params, "/turn/id").or_else(|| str_at(params, "/turnId"));
+    let status = str_at(params, "/turn/status").unwrap_or_default();
+    let error = str_at(params, "/turn/error/message").map(|error| error.to_string());
+    let state = app.state::<AppState>();
+    let _guard = state.task_queues.lock.lock().await;
+    let Some(path) = queue_file(app, workspace_id).await else {
+        return;
+    };
+    // Most workspaces never queue anything; skip the write in that case.
+    let running = read_queue(&path).is_ok_and(|store| {
+        store
+            .tasks
+            .iter()
+            .any(|task| task.status == TaskStatus::Running)
+    });
+    if !running {
+        return;
+    }
+    let result = update_queue(app, workspace_id, |store| {
+        let Some(task) = store.tasks.iter_mut().find(|task| {
+            task.status == TaskStatus::Running
+                && task.thread_id.as_deref() == Some(thread_id.as_str())
+                && (task.turn_id.is_none() || task.turn_id.as_deref() == turn_id)
+        }) else {
+            return Ok(false);
+        };
+        if status == "failed" || error.is_some() {
+            let error = error.or_else(|| Some("Turn failed.".to_string()));
+            finish(task, TaskStatus::Failed, error);
+        } else if status == "interrupted" {
+            finish(task, TaskStatus::Cancelled, None);
+        } else {
+            finish(task, TaskStatus::Completed, None);
+        }
+        Ok(true)
+    })
+    .await;
+    match result {
+        Ok(true) => spawn_advance(app, workspace_id),
+        Ok(false) => {}
+        Err(err) => tracing::warn!(workspace_id, "failed to update task queue: {err}"),
+    }
+}
+
+// A running task cannot outlive its app-server. The rest of the queue waits
+// for the next enqueue or resume instead of reconnecting in a loop.
+pub(crate) async fn session_exited(app: &AppHandle, workspace_id: &str) {
+    let state = app.state::<AppState>();
+    let _guard = state.task_queues.lock.lock().await;
+    let Some(path) = queue_file(app, workspace_id).await else {
+        return;
+    };
+    let running = read_queue(&path).is_ok_and(|store| {
+        store
+            .tasks
+            .iter()
+            .any(|task| task.status == TaskStatus::Running && task.thread_id.is_some())
+    });
+    if !running {
+        return;
+    }
+    let result = update_queue(app, workspace_id, |store| {
+        for task in store.tasks.iter_mut() {
+            if task.status == TaskStatus::Running && task.thread_id.is_some() {
+                finish(
+                    task,
+                    TaskStatus::Failed,
+                    Some("The app-server exited during the turn.".to_string()),
+                );
+            }
+        }
+        Ok(())
+    })
+    .await;
+    if let Err(err) = result {
+        tracing::warn!(workspace_id, "failed to update task queue: {err}");
+    }
+}
+
+pub(crate) async fn batch_tasks(
+    state: &AppState,
+    batch_id: &str,
+) -> Vec<(WorkspaceEntry, QueuedTask)> {
+    let entries: Vec<WorkspaceEntry> = state.workspaces.lock().await.values().cloned().collect();
+    let _guard = state.task_queues.lock.lock().await;
+    let mut tasks = Vec::new();
+    for entry in entries {
+        let Ok(store) = read_queue(&queue_path(&entry.path)) else {
+            continue;
+        };
+        for task in store.tasks {
+            if task.batch_id.as_deref() == Some(batch_id) {
+                tasks.push((entry.clone(), task));
+            }
+        }
+    }
+    tasks
+}
+
+#[tauri::command]
+pub(crate) async fn list_task_queue(
+    workspace_id: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<QueuedTask>, AppError> {
+    let entry = workspace_entry(&state, &workspace_id).await?;
+    let _guard = state.task_queues.lock.lock().await;
+    Ok(read_queue(&queue_path(&entry.path))?.tasks)
+}
+
+#[tauri::command]
+pub(crate) async fn enqueue_task(
+    workspace_id: String,
+    prompt: String,
+    options: Option<PromptOptions>,
+    batch_id: Option<String>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<QueuedTask, AppError> {
+    if prompt.trim().is_empty() {
+        return Err("Task prompt is required.".into());
+    }
+    let task = QueuedTask {
+        id: Uuid::new_v4().to_string(),
+        prompt,
+        options: options.unwrap_or_default().normalized(),
+        batch_id: batch_id.filter(|id| !id.trim().is_empty()),
+        status: TaskStatus::Queued,
+        created_at_ms: now_ms(),
+        started_at_ms: None,
+        finished_at_ms: None,
+        thread_id: None,
+        turn_id: None,
+        error: None,
+    };
+    {
+        let _guard = state.task_queues.lock.lock().await;
+        update_queue(&app, &workspace_id, |store| {
+            store.tasks.push(task.clone());
+            Ok(())
+        })
+        .await?;
+    }
+    spawn_advance(&app, &workspace_id);
+    Ok(task)
+}
+
+// Tasks still marked running from before a restart have no live turn behind
+// them; they are failed so the rest of the queue can continue.
+#[tauri::command]
+pub(crate) async fn resume_task_queue(
+    workspace_id: String,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Vec<QueuedTask>, AppError> {
+    let entry = workspace_entry(&state, &workspace_id).await?;
+    let _guard = state.task_queues.lock.lock().await;
+    let running: Vec<(String, String)> = read_queue(&queue_path(&entry.path))?
+        .tasks
+        .into_iter()
+        .filter(|task| task.status == TaskStatus::Running)
+        .filter_map(|task| Some((task.id, task.thread_id?)))
+        .collect();
+    let mut stale = Vec::new();
+    for (task_id, thread_id) in running {
+        let activity = thread_state::thread_activity(&state, &workspace_id, &thread_id).await;
+        if !matches!(
+            activity,
+            ThreadActivity::Generating | ThreadActivity::AwaitingApproval
+        ) {
+            stale.push(task_id);
+        }
+    }
+    let tasks = update_queue(&app, &workspace_id, |store| {
+        for task in store.tasks.iter_mut() {
+            if stale.contains(&task.id) {
+                let error = Some("Interrupted before the turn completed.".to_string());
+                finish(task, TaskStatus::Failed, error);
+            }
+        }
+        Ok(store.tasks.clone())
+    })
+    .await?;
+    spawn_advance(&app, &workspace_id);
+    Ok(tasks)
+}
+
+#[tauri::command]
+pub(crate) async fn reorder_task_queue(
+    workspace_id: String,
+    task_ids: Vec<String>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Vec<QueuedTask>, AppError> {
+    let _guard = state.task_queues.lock.lock().await;
+    update_queue(&app, &workspace_id, |store| {
+        // Only queued tasks move; finished and running ones keep their slots.
+        let mut queued: Vec<QueuedTask> = store
+            .tasks
+            .iter()
+            .filter(|task| task.status == TaskStatus::Queued)
+            .cloned()
+            .collect();
+        queued.sort_by_key(|task| {
+            task_ids
+                .iter()
+                .position(|id| id == &task.id)
+                .unwrap_or(usize::MAX)
+        });
+        let mut queued = queued.into_iter();
+        for slot in store.tasks.iter_mut() {
+            if slot.status == TaskStatus::Queued {
+                if let Some(task) = queued.next() {
+                    *slot = task;
+                }
+            }
+        }
+        Ok(store.tasks.clone())
+    })
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn cancel_queued_task(
+    workspace_id: String,
+    task_id: String,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Vec<QueuedTask>, AppError> {
+    let (tasks, interrupt) = {
+        let _guard = state.task_queues.lock.lock().await;
+        update_queue(&app, &workspace_id, |store| {
+            let task = store
+                .tasks
+                .iter_mut()
+                .find(|task| task.id == task_id)
+                .ok_or_else(|| AppError::from(format!("queued task not found: {task_id}")))?;
+            let interrupt = match task.status {
+                TaskStatus::Queued => None,
+                TaskStatus::Running => task
+                    .thread_id
+                    .clone()
+                    .map(|thread_id| json!({ "threadId": thread_id, "turnId": task.turn_id })),
+                _ => return Err("Task has already finished.".into()),
+            };
+            finish(task, TaskStatus::Cancelled, None);
+            Ok((store.tasks.clone(), interrupt))
+        })
+        .await?
+    };
+    if let Some(params) = interrupt {
+        let session = state.sessions.lock().await.get(&workspace_id).cloned();
+        if let Some(session) = session {
+            if let Err(err) = session.send_request("turn/interrupt", params).await {
+                tracing::warn!(workspace_id = %workspace_id, "failed to interrupt task: {err}");
+            }
+        }
+        spawn_advance(&app, &workspace_id);
+    }
+    Ok(tasks)
+}
@@ -0,0 +1,587 @@
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::{HashMap, VecDeque};
+use std::path::Path;
+use std::process::Stdio;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager, State};
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
+use tokio::process::Command;
+use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::{observer, workspace_entry, write_workspaces, AppState, WorkspaceEntry};
+
+const OUTPUT_TAIL_LINES: usize = 20;
+const OUTPUT_DRAIN_TIMEOUT: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct WorkspaceTask {
+    id: String,
+    source: String,
+    name: String,
+    command: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct TaskRun {
+    run_id: String,
+    task_id: String,
+    workspace_id: String,
+    command: String,
+}
+
+#[derive(Default)]
+pub(crate) struct TaskRegistry {
+    runs: Mutex<HashMap<String, TaskRunHandle>>,
+}
+
+struct TaskRunHandle {
+    workspace_id: String,
+    pid: Option<u32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct PreConnectCommand {
+    command: String,
+    #[serde(default = "default_pre_connect_timeout_secs")]
+    timeout_secs: u64,
+}
+
+fn default_pre_connect_timeout_secs() -> u64 {
+    120
+}
+
+impl TaskRegistry {
+    pub(crate) async fn kill_workspace(&self, workspace_id: &str) {
+        let mut runs = self.runs.lock().await;
+        runs.retain(|_, run| {
+            if run.workspace_id == workspace_id {
+                kill_process_group(run.pid);
+                false
+            } else {
+                true
+            }
+        });
+    }
+}
+
+fn task(source: &str, name: &str, command: String) -> WorkspaceTask {
+    WorkspaceTask {
+        id: format!("{source}:{name}"),
+        source: source.to_string(),
+        name: name.to_string(),
+        command,
+    }
+}
+
+fn package_runner(root: &Path) -> &'static str {
+    if root.join("pnpm-lock.yaml").exists() {
+        "pnpm run"
+    } else if root.join("yarn.lock").exists() {
+        "yarn run"
+    } else if root.join("bun.lockb").exists() || root.join("bun.lock").exists() {
+        "bun run"
+    } else {
+        "npm run"
+    }
+}
+
+fn detect_package_scripts(root: &Path) -> Vec<WorkspaceTask> {
+    let Ok(data) = std::fs::read_to_string(root.join("package.json")) else {
+        return Vec::new();
+    };
+    let Ok(value) = serde_json::from_str::<serde_json::Value>(&data) else {
+        return Vec::new();
+    };
+    let Some(scripts) = value.get("scripts").and_then(|scripts| scripts.as_object()) else {
+        return Vec::new();
+    };
+    let runner = package_runner(root);
+    scripts
+        .keys()
+        .map(|name| task("npm", name, format!("{runner} {name}")))
+        .collect()
+}
+
+fn parse_make_targets(contents: &str) -> Vec<String> {
+    let mut targets: Vec<String> = Vec::new();
+    for line in contents.lines() {
+        if line.starts_with(['\t', ' ', '#', '.']) {
+            continue;
+        }
+        let Some((head, rest)) = line.split_once(':') else {
+            continue;
+        };
+        if rest.starts_with('=') || head.contains(['=', '%', '$']) {
+            continue;
+        }
+        for name in head.split_whitespace() {
+            if !targets.iter().any(|existing| existing == name) {
+                targets.push(name.to_string());
+            }
+        }
+    }
+    targets
+}
+
+fn detect_make_targets(root: &Path) -> Vec<WorkspaceTask> {
+    let contents = ["GNUmakefile", "makefile", "Makefile"]
+        .iter()
+        .find_map(|name| std::fs::read_to_string(root.join(name)).ok());
+    let Some(contents) = contents else {
+        return Vec::new();
+    };
+    parse_make_targets(&contents)
+        .iter()
+        .map(|name| task("make", name, format!("make {name}")))
+        .collect()
+}
+
+fn cargo_package_name(manifest: &Path) -> Option<String> {
+    let data = std::fs::read_to_string(manifest).ok()?;
+    let value: toml::Value = toml::from_str(&data).ok()?;
+    value
+        .get("package")?
+        .get("name")?
+        .as_str()
+        .map(|name| name.to_string())
+}
+
+fn detect_cargo_tasks(root: &Path) -> Vec<WorkspaceTask> {
+    let Ok(data) = std::fs::read_to_string(root.join("Cargo.toml")) else {
+        return Vec::new();
+    };
+    let Ok(value) = toml::from_str::<toml::Value>(&data) else {
+        return Vec::new();
+    };
+    let mut tasks: Vec<WorkspaceTask> = ["build", "test", "check", "clippy"]
+        .iter()
+        .map(|name| task("cargo", name, format!("cargo {name}")))
+        .collect();
+    let members = value
+        .get("workspace")
+        .and_then(|workspace| workspace.get("members"))
+        .and_then(|members| members.as_array())
+        .cloned()
+        .unwrap_or_default();
+    let mut packages: Vec<String> = Vec::new();
+    for member in members.iter().filter_map(|member| member.as_str()) {
+        let pattern = root.join(member).join("Cargo.toml");
+        let Ok(paths) = glob::glob(&pattern.to_string_lossy()) else {
+            continue;
+        };
+        for manifest in paths.flatten() {
+            if let Some(name) = cargo_package_name(&manifest) {
+                if !packages.contains(&name) {
+                    packages.push(name);
+                }
+            }
+        }
+    }
+    for package in packages {
+        for command in ["build", "test"] {
+            tasks.push(task(
+                "cargo",
+                &format!("{command} -p {package}"),
+                format!("cargo {command} -p {package}"),
+            ));
+        }
+    }
+    tasks
+}
+
+fn parse_just_recipes(contents: &str) -> Vec<String> {
+    let mut recipes: Vec<String> = Vec::new();
+    for line in contents.lines() {
+        if line.starts_with([' ', '\t', '#', '[']) || line.trim().is_empty() {
+            continue;
+        }
+        let line = line.strip_prefix('@').unwrap_or(line);
+        let Some((head, rest)) = line.split_once(':') else {
+            continue;
+        };
+        if rest.starts_with('=') {
+            continue;
+        }
+        let mut words = head.split_whitespace();
+        let Some(name) = words.next() else {
+            continue;
+        };
+        if matches!(name, "set" | "alias" | "export" | "import" | "mod") {
+            continue;
+        }
+        let valid = name
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || ch == '_' || ch == '-');
+        if valid && !name.starts_with('_') && !recipes.iter().any(|existing| existing == name) {
+            recipes.push(name.to_string());
+        }
+    }
+    recipes
+}
+
+fn detect_just_recipes(root: &Path) -> Vec<WorkspaceTask> {
+    let contents = ["justfile", "Justfile", ".justfile"]
+        .iter()
+        .find_map(|name| std::fs::read_to_string(root.join(name)).ok());
+    let Some(contents) = contents else {
+        return Vec::new();
+    };
+    parse_just_recipes(&contents)
+        .iter()
+        .map(|name| task("just", name, format!("just {name}")))
+        .collect()
+}
+
+fn workspace_tasks(root: &Path) -> Vec<WorkspaceTask> {
+    let mut tasks = detect_package_scripts(root);
+    tasks.extend(detect_make_targets(root));
+    tasks.extend(detect_cargo_tasks(root));
+    tasks.extend(detect_just_recipes(root));
+    tasks
+}
+
+pub(crate) fn shell_command(command_line: &str) -> Command {
+    #[cfg(windows)]
+    {
+        let mut command = crate::platform::command("cmd");
+        command.arg("/C").arg(command_line);
+        command
+    }
+    #[cfg(not(windows))]
+    {
+        let mut command = Command::new("sh");
+        command.arg("-c").arg(command_line);
+        command.process_group(0);
+        command
+    }
+}
+
+fn kill_process_group(pid: Option<u32>) {
+    let Some(pid) = pid else {
+        return;
+    };
+    #[cfg(unix)]
+    unsafe {
+        libc::kill(-(pid as libc::pid_t), libc::SIGTERM);
+    }
+    #[cfg(windows)]
+    {
+        let mut taskkill = std::process::Command::new("taskkill");
+        crate::platform::hide_std_console_window(&mut taskkill);
+        let _ = taskkill
+            .args(["/PID", &pid.to_string(), "/T", "/F"])
+            .status();
+    }
+}
+
+fn spawn_stream_reader<R>(
+    app: AppHandle,
+    run: TaskRun,
+    stream: &'static str,
+    reader: R,
+) -> JoinHandle<VecDeque<String>>
+where
+    R: AsyncRead + Unpin + Send + 'static,
+{
+    tokio::spawn(async move {
+        let mut tail = VecDeque::new();
+        let mut lines = BufReader::new(reader).lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            let _ = app.emit(
+                "task-output",
+                json!({
+                    "runId": run.run_id,
+                    "taskId": run.task_id,
+                    "workspaceId": run.workspace_id,
+                    "stream": stream,
+                    "line": line,
+                }),
+            );
+            tail.push_back(line);
+            if tail.len() > OUTPUT_TAIL_LINES {
+                tail.pop_front();
+            }
+        }
+        tail
+    })
+}
+
+fn emit_exit(
+    app: &AppHandle,
+    run: &TaskRun,
+    exit_code: Option<i32>,
+    cancelled: bool,
+    started: Instant,
+    error: Option<String>,
+) {
+    let _ = app.emit(
+        "task-exit",
+        json!({
+            "runId": run.run_id,
+            "taskId": run.task_id,
+            "workspaceId": run.workspace_id,
+            "exitCode": exit_code,
+            "success": exit_code == Some(0),
+            "cancelled": cancelled,
+            "durationMs": started.elapsed().as_millis() as u64,
+            "error": error,
+        }),
+    );
+}
+
+async fn spawn_run(
+    app: &AppHandle,
+    state: &AppState,
+    workspace_id: &str,
+    root: &str,
+    task_id: &str,
+    command_line: &str,
+) -> Result<TaskRun, AppError> {
+    let mut command = shell_command(command_line);
+    command
+        .current_dir(root)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    let mut child = command.spawn()?;
+    let run = TaskRun {
+        run_id: Uuid::new_v4().to_string(),
+        task_id: task_id.to_string(),
+        workspace_id: workspace_id.to_string(),
+        command: command_line.to_string(),
+    };
+    state.tasks.runs.lock().await.insert(
+        run.run_id.clone(),
+        TaskRunHandle {
+            workspace_id: workspace_id.to_string(),
+            pid: child.id(),
+        },
+    );
+    if let Some(stdout) = child.stdout.take() {
+        spawn_stream_reader(app.clone(), run.clone(), "stdout", stdout);
+    }
+    if let Some(stderr) = child.stderr.take() {
+        spawn_stream_reader(app.clone(), run.clone(), "stderr", stderr);
+    }
+
+    let exit_run = run.clone();
+    let started = Instant::now();
+    let app = app.clone();
+    tokio::spawn(async move {
+        let status = child.wait().await;
+        let state = app.state::<AppState>();
+        let cancelled = state
+            .tasks
+            .runs
+            .lock()
+            .await
+            .remove(&exit_run.run_id)
+            .is_none();
+        let (exit_code, error) = match status {
+            Ok(status) => (status.code(), None),
+            Err(err) => (None, Some(err.to_string())),
+        };
+        emit_exit(&app, &exit_run, exit_code, cancelled, started, error);
+    });
+    Ok(run)
+}
+
+async fn workspace_root(state: &AppState, workspace_id: &str) -> Result<String, AppError> {
+    state
+        .workspaces
+        .lock()
+        .await
+        .get(workspace_id)
+        .map(|entry| entry.path.clone())
+        .ok_or(AppError::WorkspaceNotFound)
+}
+
+#[tauri::command]
+pub(crate) async fn detect_tasks(
+    workspace_id: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<WorkspaceTask>, AppError> {
+    let root = workspace_root(&state, &workspace_id).await?;
+    Ok(tokio::task::spawn_blocking(move || workspace_tasks(Path::new(&root))).await?)
+}
+
+#[tauri::command]
+pub(crate) async fn run_task(
+    workspace_id: String,
+    task_id: String,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<TaskRun, AppError> {
+    let entry = workspace_entry(&state, &workspace_id).await?;
+    observer::ensure_writable(&entry, "running tasks")?;
+    let root = entry.path;
+    let scan_root = root.clone();
+    let tasks = tokio::task::spawn_blocking(move || workspace_tasks(Path::new(&scan_root))).await?;
+    let task = tasks
+        .into_iter()
+        .find(|task| task.id == task_id)
+        .ok_or("task not found")?;
+
+    spawn_run(&app, &state, &workspace_id, &root, &task.id, &task.command).await
+}
+
+#[tauri::command]
+pub(crate) async fn cancel_task(
+    run_id: String,
+    state: State<'_, AppState>,
+) -> Result<(), AppError> {
+    let run = state
+        .tasks
+        .runs
+        .lock()
+        .await
+        .remove(&run_id)
+        .ok_or("task run not found")?;
+    kill_process_group(run.pid);
+    Ok(())
+}
+
+pub(crate) async fn run_startup_commands(app: &AppHandle, entry: &WorkspaceEntry) {
+    if observer::ensure_writable(entry, "startup commands").is_err() {
+        let workspace_id = entry.id.as_str();
+        tracing::info!(workspace_id, "skipping startup commands in observer mode");
+        return;
+    }
+    let state = app.state::<AppState>();
+    for (index, command_line) in entry.defaults.startup_commands.iter().enumerate() {
+        let task_id = format!("startup:{index}");
+        let result = spawn_run(app, &state, &entry.id, &entry.path, &task_id, command_line).await;
+        if let Err(err) = result {
+            let workspace_id = entry.id.as_str();
+            tracing::warn!(workspace_id, "startup command `{command_line}` failed: {err}");
+        }
+    }
+}
+
+async fn output_tail(reader: Option<JoinHandle<VecDeque<String>>>) -> Vec<String> {
+    let Some(reader) = reader else {
+        return Vec::new();
+    };
+    match tokio::time::timeout(OUTPUT_DRAIN_TIMEOUT, reader).await {
+        Ok(Ok(tail)) => tail.into(),
+        _ => Vec::new(),
+    }
+}
+
+async fn run_pre_connect_command(
+    app: &AppHandle,
+    entry: &WorkspaceEntry,
+    env: Option<&HashMap<String, String>>,
+    task_id: String,
+    pre_connect: &PreConnectCommand,
+) -> Result<(), String> {
+    let mut command = shell_command(&pre_connect.command);
+    if let Some(env) = env {
+        command.envs(env);
+    }
+    command
+        .envs(&entry.defaults.env)
+        .current_dir(&entry.path)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    let mut child = command
+        .spawn()
+        .map_err(|err| format!("Failed to start `{}`: {err}", pre_connect.command))?;
+    let run = TaskRun {
+        run_id: Uuid::new_v4().to_string(),
+        task_id,
+        workspace_id: entry.id.clone(),
+        command: pre_connect.command.clone(),
+    };
+    let stdout = child
+        .stdout
+        .take()
+        .map(|stdout| spawn_stream_reader(app.clone(), run.clone(), "stdout", stdout));
+    let stderr = child
+        .stderr
+        .take()
+        .map(|stderr| spawn_stream_reader(app.clone(), run.clone(), "stderr", stderr));
+
+    let started = Instant::now();
+    let timeout = Duration::from_secs(pre_connect.timeout_secs.max(1));
+    let (exit_code, failure) = match tokio::time::timeout(timeout, child.wait()).await {
+        Ok(Ok(status)) if status.success() => (status.code(), None),
+        Ok(Ok(status)) => (
+            status.code(),
+            Some(match status.code() {
+                Some(code) => format!("exited with code {code}"),
+                None => "was terminated by a signal".to_string(),
+            }),
+        ),
+        Ok(Err(err)) => (None, Some(err.to_string())),
+        Err(_) => {
+            kill_process_group(child.id());
+            let _ = child.kill().await;
+            (None, Some(format!("timed out after {}s", timeout.as_secs())))
+        }
+    };
+    emit_exit(app, &run, exit_code, false, started, failure.clone());
+    let Some(failure) = failure else {
+        return Ok(());
+    };
+    let mut tail = output_tail(stderr).await;
+    if tail.is_empty() {
+        tail = output_tail(stdout).await;
+    }
+    let mut message = format!("Pre-connect command `{}` {failure}.", pre_connect.command);
+    if !tail.is_empty() {
+        message.push('\n');
+        message.push_str(&tail.join("\n"));
+    }
+    Err(message)
+}
+
+// Runs before the app-server is spawned; the first failure aborts the
+// connect so the session never starts in a half-prepared environment.
+pub(crate) async fn run_pre_connect_commands(
+    app: &AppHandle,
+    entry: &WorkspaceEntry,
+    env: Option<&HashMap<String, String>>,
+) -> Result<(), AppError> {
+    if observer::ensure_writable(entry, "pre-connect commands").is_err() {
+        let workspace_id = entry.id.as_str();
+        tracing::info!(workspace_id, "skipping pre-connect commands in observer mode");
+        return Ok(());
+    }
+    for (index, pre_connect) in entry.pre_connect_commands.iter().enumerate() {
+        let task_id = format!("preconnect:{index}");
+        run_pre_connect_command(app, entry, env, task_id, pre_connect).await?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub(crate) async fn set_workspace_pre_connect_commands(
+    id: String,
+    commands: Vec<PreConnectCommand>,
+    state: State<'_, AppState>,
+) -> Result<Vec<PreConnectCommand>, AppError> {
+    let commands: Vec<PreConnectCommand> = commands
+        .into_iter()
+        .map(|command| PreConnectCommand {
+            command: command.command.trim().to_string(),
+            timeout_secs: command.timeout_secs.max(1),
+        })
+        .filter(|command| !command.command.is_empty())
+        .collect();
+    let mut workspaces = state.workspaces.lock().await;
+    let entry = workspaces.get_mut(&id).ok_or(AppError::WorkspaceNotFound)?;
+    entry.pre_connect_commands = commands.clone();
+    let list: Vec<_> = workspaces.values().cloned().collect();
+    write_workspaces(&state.storage_path, &list)?;
+    Ok(commands)
+}
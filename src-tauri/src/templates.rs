@@ -0,0 +1,219 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, State};
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::{
+    default_session_store_version, insert_workspace, new_workspace_entry, now_ms, storage,
+    AppState, WorkspaceInfo,
+};
+
+const ACCESS_MODES: &[&str] = &["read-only", "current", "full-access"];
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct WorkspaceDefaults {
+    #[serde(default)]
+    pub(crate) env: BTreeMap<String, String>,
+    #[serde(default)]
+    pub(crate) access_mode: Option<String>,
+    #[serde(default)]
+    pub(crate) default_prompts: Vec<String>,
+    #[serde(default)]
+    pub(crate) startup_commands: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct WorkspaceTemplate {
+    id: String,
+    name: String,
+    #[serde(default)]
+    codex_bin: Option<String>,
+    #[serde(flatten)]
+    defaults: WorkspaceDefaults,
+    created_at_ms: i64,
+    updated_at_ms: i64,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct WorkspaceTemplateInput {
+    #[serde(default)]
+    id: Option<String>,
+    name: String,
+    #[serde(default)]
+    codex_bin: Option<String>,
+    #[serde(flatten)]
+    defaults: WorkspaceDefaults,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct WorkspaceTemplateStore {
+    #[serde(default = "default_session_store_version")]
+    version: u32,
+    #[serde(default)]
+    templates: Vec<WorkspaceTemplate>,
+}
+
+#[derive(Default)]
+pub(crate) struct WorkspaceTemplates {
+    lock: Mutex<()>,
+}
+
+impl Default for WorkspaceTemplateStore {
+    fn default() -> Self {
+        Self {
+            version: default_session_store_version(),
+            templates: Vec::new(),
+        }
+    }
+}
+
+fn templates_path(state: &AppState) -> PathBuf {
+    state.data_dir.join("workspace_templates.json")
+}
+
+fn read_templates(path: &Path) -> Result<WorkspaceTemplateStore, String> {
+    storage::read_json_or_default(path)
+}
+
+fn write_templates(path: &Path, store: &WorkspaceTemplateStore) -> Result<(), String> {
+    storage::write_json_atomic(path, store)
+}
+
+fn normalize_defaults(defaults: WorkspaceDefaults) -> Result<WorkspaceDefaults, AppError> {
+    let mut env = BTreeMap::new();
+    for (key, value) in defaults.env {
+        let key = key.trim().to_string();
+        if key.is_empty() {
+            continue;
+        }
+        if key.contains('=') || key.contains('\0') {
+            return Err(format!("Invalid environment variable name: {key}").into());
+        }
+        env.insert(key, value);
+    }
+    let access_mode = defaults
+        .access_mode
+        .map(|mode| mode.trim().to_string())
+        .filter(|mode| !mode.is_empty());
+    if let Some(mode) = access_mode.as_deref() {
+        if !ACCESS_MODES.contains(&mode) {
+            return Err(format!("Unknown sandbox profile: {mode}").into());
+        }
+    }
+    let non_empty = |items: Vec<String>| -> Vec<String> {
+        items
+            .into_iter()
+            .map(|item| item.trim().to_string())
+            .filter(|item| !item.is_empty())
+            .collect()
+    };
+    Ok(WorkspaceDefaults {
+        env,
+        access_mode,
+        default_prompts: non_empty(defaults.default_prompts),
+        startup_commands: non_empty(defaults.startup_commands),
+    })
+}
+
+#[tauri::command]
+pub(crate) async fn list_workspace_templates(
+    state: State<'_, AppState>,
+) -> Result<Vec<WorkspaceTemplate>, AppError> {
+    let _guard = state.workspace_templates.lock.lock().await;
+    let mut templates = read_templates(&templates_path(&state))?.templates;
+    templates.sort_by_key(|template| template.name.to_lowercase());
+    Ok(templates)
+}
+
+#[tauri::command]
+pub(crate) async fn save_workspace_template(
+    template: WorkspaceTemplateInput,
+    state: State<'_, AppState>,
+) -> Result<WorkspaceTemplate, AppError> {
+    let name = template.name.trim().to_string();
+    if name.is_empty() {
+        return Err("Template name is required.".into());
+    }
+    let codex_bin = template
+        .codex_bin
+        .map(|bin| bin.trim().to_string())
+        .filter(|bin| !bin.is_empty());
+    let defaults = normalize_defaults(template.defaults)?;
+    let path = templates_path(&state);
+    let _guard = state.workspace_templates.lock.lock().await;
+    let mut store = read_templates(&path)?;
+    let now = now_ms();
+    let saved = match template.id {
+        Some(id) => {
+            let existing = store
+                .templates
+                .iter_mut()
+                .find(|existing| existing.id == id)
+                .ok_or_else(|| AppError::from(format!("workspace template not found: {id}")))?;
+            existing.name = name;
+            existing.codex_bin = codex_bin;
+            existing.defaults = defaults;
+            existing.updated_at_ms = now;
+            existing.clone()
+        }
+        None => {
+            let created = WorkspaceTemplate {
+                id: Uuid::new_v4().to_string(),
+                name,
+                codex_bin,
+                defaults,
+                created_at_ms: now,
+                updated_at_ms: now,
+            };
+            store.templates.push(created.clone());
+            created
+        }
+    };
+    write_templates(&path, &store)?;
+    Ok(saved)
+}
+
+#[tauri::command]
+pub(crate) async fn delete_workspace_template(
+    template_id: String,
+    state: State<'_, AppState>,
+) -> Result<(), AppError> {
+    let path = templates_path(&state);
+    let _guard = state.workspace_templates.lock.lock().await;
+    let mut store = read_templates(&path)?;
+    let before = store.templates.len();
+    store.templates.retain(|template| template.id != template_id);
+    if store.templates.len() == before {
+        return Err(format!("workspace template not found: {template_id}").into());
+    }
+    write_templates(&path, &store)?;
+    Ok(())
+}
+
+#[tauri::command]
+pub(crate) async fn add_workspace_from_template(
+    path: String,
+    template_id: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<WorkspaceInfo, AppError> {
+    let template = {
+        let _guard = state.workspace_templates.lock.lock().await;
+        read_templates(&templates_path(&state))?
+            .templates
+            .into_iter()
+            .find(|template| template.id == template_id)
+            .ok_or_else(|| AppError::from(format!("workspace template not found: {template_id}")))?
+    };
+    let mut entry = new_workspace_entry(&path, template.codex_bin.clone(), None);
+    entry.template_id = Some(template.id);
+    entry.defaults = template.defaults;
+    insert_workspace(entry, &state, app).await
+}
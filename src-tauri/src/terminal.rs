@@ -0,0 +1,284 @@
+use portable_pty::{native_pty_system, Child, CommandBuilder, MasterPty, PtySize};
+use serde::Serialize;
+use serde_json::json;
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::sync::{Arc, Mutex as StdMutex};
+use tauri::{AppHandle, Emitter, Manager, State};
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::{observer, AppState};
+
+pub(crate) struct TerminalSession {
+    id: String,
+    workspace_id: String,
+    master: StdMutex<Box<dyn MasterPty + Send>>,
+    writer: StdMutex<Box<dyn Write + Send>>,
+    child: StdMutex<Box<dyn Child + Send + Sync>>,
+}
+
+#[derive(Default)]
+pub(crate) struct TerminalRegistry {
+    sessions: Mutex<HashMap<String, Arc<TerminalSession>>>,
+}
+
+impl TerminalRegistry {
+    pub(crate) async fn kill_workspace(&self, workspace_id: &str) {
+        let mut sessions = self.sessions.lock().await;
+        let ids: Vec<String> = sessions
+            .values()
+            .filter(|session| session.workspace_id == workspace_id)
+            .map(|session| session.id.clone())
+            .collect();
+        for id in ids {
+            if let Some(session) = sessions.remove(&id) {
+                if let Ok(mut child) = session.child.lock() {
+                    kill_process_group(child.as_mut());
+                }
+            }
+        }
+    }
+}
+
+// On unix the shell runs in its own session, so its pid doubles as the process
+// group id; hanging up the group also stops jobs started from the shell.
+fn kill_process_group(child: &mut (dyn Child + Send + Sync)) {
+    #[cfg(unix)]
+    if let Some(pid) = child.process_id() {
+        unsafe {
+            libc::killpg(pid as libc::pid_t, libc::SIGHUP);
+        }
+    }
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct TerminalInfo {
+    id: String,
+    workspace_id: String,
+    shell: String,
+}
+
+#[cfg(unix)]
+fn default_shell() -> String {
+    std::env::var("SHELL")
+        .ok()
+        .filter(|value| !value.trim().is_empty())
+        .unwrap_or_else(|| "/bin/sh".to_string())
+}
+
+#[cfg(windows)]
+fn default_shell() -> String {
+    std::env::var("COMSPEC")
+        .ok()
+        .filter(|value| !value.trim().is_empty())
+        .unwrap_or_else(|| "cmd.exe".to_string())
+}
+
+fn pty_size(cols: u16, rows: u16) -> PtySize {
+    PtySize {
+        rows: rows.max(1),
+        cols: cols.max(1),
+        pixel_width: 0,
+        pixel_height: 0,
+    }
+}
+
+type SpawnedPty = (Box<dyn MasterPty + Send>, Box<dyn Child + Send + Sync>);
+
+fn spawn_pty(shell: &str, cwd: &str, cols: u16, rows: u16) -> Result<SpawnedPty, String> {
+    let pair = native_pty_system()
+        .openpty(pty_size(cols, rows))
+        .map_err(|err| err.to_string())?;
+    let mut command = CommandBuilder::new(shell);
+    #[cfg(unix)]
+    command.arg("-l");
+    command.cwd(cwd);
+    command.env("TERM", "xterm-256color");
+    command.env("COLORTERM", "truecolor");
+    let child = pair
+        .slave
+        .spawn_command(command)
+        .map_err(|err| err.to_string())?;
+    Ok((pair.master, child))
+}
+
+fn split_utf8(pending: &mut Vec<u8>) -> String {
+    match std::str::from_utf8(pending) {
+        Ok(text) => {
+            let text = text.to_string();
+            pending.clear();
+            text
+        }
+        Err(err) if err.error_len().is_none() => {
+            let valid = err.valid_up_to();
+            let text = String::from_utf8_lossy(&pending[..valid]).to_string();
+            pending.drain(..valid);
+            text
+        }
+        Err(_) => {
+            let text = String::from_utf8_lossy(pending).to_string();
+            pending.clear();
+            text
+        }
+    }
+}
+
+fn spawn_output_reader(
+    app: AppHandle,
+    session: Arc<TerminalSession>,
+    mut reader: Box<dyn Read + Send>,
+) {
+    std::thread::spawn(move || {
+        let mut buffer = [0u8; 8192];
+        let mut pending: Vec<u8> = Vec::new();
+        loop {
+            let count = match reader.read(&mut buffer) {
+                Ok(0) | Err(_) => break,
+                Ok(count) => count,
+            };
+            pending.extend_from_slice(&buffer[..count]);
+            let data = split_utf8(&mut pending);
+            if data.is_empty() {
+                continue;
+            }
+            let _ = app.emit(
+                "terminal-output",
+                json!({
+                    "terminalId": session.id,
+                    "workspaceId": session.workspace_id,
+                    "data": data,
+                }),
+            );
+        }
+        let exit_code = session
+            .child
+            .lock()
+            .ok()
+            .and_then(|mut child| child.wait().ok())
+            .map(|status| status.exit_code());
+        // Drop the registry's handle so the pty and child are released.
+        tauri::async_runtime::block_on(async {
+            let state = app.state::<AppState>();
+            state.terminals.sessions.lock().await.remove(&session.id);
+        });
+        let _ = app.emit(
+            "terminal-exit",
+            json!({
+                "terminalId": session.id,
+                "workspaceId": session.workspace_id,
+                "exitCode": exit_code,
+            }),
+        );
+    });
+}
+
+async fn terminal_session(
+    state: &AppState,
+    terminal_id: &str,
+) -> Result<Arc<TerminalSession>, String> {
+    state
+        .terminals
+        .sessions
+        .lock()
+        .await
+        .get(terminal_id)
+        .cloned()
+        .ok_or_else(|| "terminal not found".to_string())
+}
+
+#[tauri::command]
+pub(crate) async fn terminal_create(
+    workspace_id: String,
+    cols: Option<u16>,
+    rows: Option<u16>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<TerminalInfo, AppError> {
+    let entry = state
+        .workspaces
+        .lock()
+        .await
+        .get(&workspace_id)
+        .cloned()
+        .ok_or(AppError::WorkspaceNotFound)?;
+    observer::ensure_writable(&entry, "opening terminals")?;
+    let shell = default_shell();
+    let (master, child) = spawn_pty(&shell, &entry.path, cols.unwrap_or(80), rows.unwrap_or(24))?;
+    let reader = master.try_clone_reader().map_err(|err| err.to_string())?;
+    let writer = master.take_writer().map_err(|err| err.to_string())?;
+    let session = Arc::new(TerminalSession {
+        id: Uuid::new_v4().to_string(),
+        workspace_id: workspace_id.clone(),
+        master: StdMutex::new(master),
+        writer: StdMutex::new(writer),
+        child: StdMutex::new(child),
+    });
+    state
+        .terminals
+        .sessions
+        .lock()
+        .await
+        .insert(session.id.clone(), Arc::clone(&session));
+    spawn_output_reader(app, Arc::clone(&session), reader);
+    Ok(TerminalInfo {
+        id: session.id.clone(),
+        workspace_id,
+        shell,
+    })
+}
+
+#[tauri::command]
+pub(crate) async fn terminal_write(
+    terminal_id: String,
+    data: String,
+    state: State<'_, AppState>,
+) -> Result<(), AppError> {
+    let session = terminal_session(&state, &terminal_id).await?;
+    let mut writer = session
+        .writer
+        .lock()
+        .map_err(|_| "terminal lock poisoned")?;
+    writer.write_all(data.as_bytes())?;
+    writer.flush()?;
+    Ok(())
+}
+
+#[tauri::command]
+pub(crate) async fn terminal_resize(
+    terminal_id: String,
+    cols: u16,
+    rows: u16,
+    state: State<'_, AppState>,
+) -> Result<(), AppError> {
+    let session = terminal_session(&state, &terminal_id).await?;
+    let master = session
+        .master
+        .lock()
+        .map_err(|_| "terminal lock poisoned")?;
+    master
+        .resize(pty_size(cols, rows))
+        .map_err(|err| err.to_string())?;
+    Ok(())
+}
+
+#[tauri::command]
+pub(crate) async fn terminal_kill(
+    terminal_id: String,
+    state: State<'_, AppState>,
+) -> Result<(), AppError> {
+    let session = state
+        .terminals
+        .sessions
+        .lock()
+        .await
+        .remove(&terminal_id)
+        .ok_or("terminal not found")?;
+    let mut child = session.child.lock().map_err(|_| "terminal lock poisoned")?;
+    kill_process_group(child.as_mut());
+    Ok(())
+}
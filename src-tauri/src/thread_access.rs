@@ -0,0 +1,77 @@
+use serde_json::json;
+use tauri::State;
+
+use crate::error::AppError;
+use crate::{
+    read_workspace_sessions, workspace_entry, workspace_sessions_path, write_workspace_sessions,
+    AccessMode, AppState, UserMessageRequest, WorkspaceEntry,
+};
+
+fn access_mode_name(mode: AccessMode) -> &'static str {
+    match mode {
+        AccessMode::ReadOnly => "read-only",
+        AccessMode::Current => "current",
+        AccessMode::FullAccess => "full-access",
+    }
+}
+
+fn parse_access_mode(value: &str) -> Result<AccessMode, AppError> {
+    serde_json::from_value(json!(value.trim()))
+        .map_err(|_| format!("Unknown access mode: {value}").into())
+}
+
+// A thread locked to a mode only accepts that mode. Messages that leave the
+// mode unset inherit the lock; the first full-access message locks the thread.
+pub(crate) fn enforce(
+    entry: &WorkspaceEntry,
+    message: &mut UserMessageRequest,
+) -> Result<(), AppError> {
+    let path = workspace_sessions_path(&entry.path);
+    let mut store = read_workspace_sessions(&path)?;
+    let locked = store
+        .sessions
+        .get(&message.thread_id)
+        .and_then(|metadata| metadata.access_mode);
+    let requested = message
+        .access_mode
+        .as_deref()
+        .map(parse_access_mode)
+        .transpose()?;
+    match (locked, requested) {
+        (Some(locked), Some(requested)) if locked != requested => Err(AppError::AccessModeLocked {
+            locked: access_mode_name(locked).to_string(),
+            requested: access_mode_name(requested).to_string(),
+            message: format!(
+                "This thread is locked to {} access; {} was requested.",
+                access_mode_name(locked),
+                access_mode_name(requested)
+            ),
+        }),
+        (Some(locked), None) => {
+            message.access_mode = Some(access_mode_name(locked).to_string());
+            Ok(())
+        }
+        (None, Some(AccessMode::FullAccess)) => {
+            let metadata = store.sessions.entry(message.thread_id.clone()).or_default();
+            metadata.access_mode = Some(AccessMode::FullAccess);
+            write_workspace_sessions(&path, &store)?;
+            Ok(())
+        }
+        _ => Ok(()),
+    }
+}
+
+#[tauri::command]
+pub(crate) async fn set_thread_access_mode(
+    workspace_id: String,
+    thread_id: String,
+    access_mode: Option<AccessMode>,
+    state: State<'_, AppState>,
+) -> Result<Option<AccessMode>, AppError> {
+    let entry = workspace_entry(&state, &workspace_id).await?;
+    let path = workspace_sessions_path(&entry.path);
+    let mut store = read_workspace_sessions(&path)?;
+    store.sessions.entry(thread_id).or_default().access_mode = access_mode;
+    write_workspace_sessions(&path, &store)?;
+    Ok(access_mode)
+}
@@ -0,0 +1,400 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use tauri::{AppHandle, Emitter, Manager, State};
+use tokio::sync::Mutex;
+
+use crate::error::AppError;
+use crate::{
+    badge, dock, hooks, now_ms, read_workspace_sessions, workspace_sessions_path,
+    write_workspace_sessions, AppState, WorkspaceSessionStore,
+};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum ThreadActivity {
+    #[default]
+    Idle,
+    Generating,
+    AwaitingApproval,
+    Errored,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ThreadState {
+    pub(crate) thread_id: String,
+    pub(crate) state: ThreadActivity,
+    pub(crate) updated_at_ms: i64,
+    pub(crate) turn_id: Option<String>,
+    pub(crate) pending_request_id: Option<u64>,
+    pub(crate) error: Option<String>,
+    #[serde(skip)]
+    pending_request: Option<Value>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct PendingApproval {
+    workspace_id: String,
+    thread_id: String,
+    request_id: u64,
+    method: Option<String>,
+    params: Option<Value>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct ThreadStateChanged {
+    workspace_id: String,
+    thread_id: String,
+    state: ThreadActivity,
+    previous_state: ThreadActivity,
+    updated_at_ms: i64,
+    turn_id: Option<String>,
+    error: Option<String>,
+}
+
+#[derive(Default)]
+pub(crate) struct ThreadStates {
+    workspaces: Mutex<HashMap<String, HashMap<String, ThreadState>>>,
+}
+
+struct Transition {
+    thread_id: String,
+    state: ThreadActivity,
+    turn_id: Option<String>,
+    request_id: Option<u64>,
+    request: Option<Value>,
+    error: Option<String>,
+}
+
+pub(crate) fn str_at<'a>(value: &'a Value, pointer: &str) -> Option<&'a str> {
+    value.pointer(pointer).and_then(|value| value.as_str())
+}
+
+pub(crate) fn message_thread_id(params: &Value) -> Option<String> {
+    str_at(params, "/threadId")
+        .or_else(|| str_at(params, "/thread/id"))
+        .or_else(|| str_at(params, "/conversationId"))
+        .map(|id| id.to_string())
+}
+
+fn message_error(params: &Value) -> Option<String> {
+    let error = params
+        .get("error")
+        .or_else(|| params.pointer("/turn/error"))
+        .filter(|error| !error.is_null())?;
+    Some(
+        error
+            .get("message")
+            .and_then(|message| message.as_str())
+            .or_else(|| error.as_str())
+            .map(|message| message.to_string())
+            .unwrap_or_else(|| error.to_string()),
+    )
+}
+
+fn transition_for(message: &Value) -> Option<Transition> {
+    let method = message.get("method")?.as_str()?;
+    let params = message.get("params").unwrap_or(&Value::Null);
+    let thread_id = message_thread_id(params)?;
+    let turn_id = str_at(params, "/turnId")
+        .or_else(|| str_at(params, "/turn/id"))
+        .map(|id| id.to_string());
+    let mut error = None;
+    let mut request_id = None;
+    let mut request = None;
+    let state = if hooks::approval_request_method(message).is_some() {
+        request_id = message.get("id").and_then(|id| id.as_u64());
+        request = Some(message.clone());
+        ThreadActivity::AwaitingApproval
+    } else {
+        match method {
+            "thread/started" => ThreadActivity::Idle,
+            "turn/started" | "item/started" => ThreadActivity::Generating,
+            "turn/completed" => {
+                let status = str_at(params, "/turn/status").unwrap_or_default();
+                error = message_error(params);
+                if status == "failed" || error.is_some() {
+                    ThreadActivity::Errored
+                } else {
+                    ThreadActivity::Idle
+                }
+            }
+            "error" => {
+                if params.get("willRetry").and_then(|value| value.as_bool()) == Some(true) {
+                    return None;
+                }
+                error = message_error(params).or_else(|| Some("turn failed".to_string()));
+                ThreadActivity::Errored
+            }
+            _ => return None,
+        }
+    };
+    Some(Transition {
+        thread_id,
+        state,
+        turn_id,
+        request_id,
+        request,
+        error,
+    })
+}
+
+async fn persist_state(app: &AppHandle, workspace_id: &str, thread_id: &str, state: &ThreadState) {
+    let app_state = app.state::<AppState>();
+    let Some(workspace_path) = app_state
+        .workspaces
+        .lock()
+        .await
+        .get(workspace_id)
+        .map(|entry| entry.path.clone())
+    else {
+        return;
+    };
+    let path = workspace_sessions_path(&workspace_path);
+    let result = read_workspace_sessions(&path).and_then(|mut store| {
+        let metadata = store.sessions.entry(thread_id.to_string()).or_default();
+        metadata.last_state = Some(state.state);
+        metadata.last_state_at_ms = Some(state.updated_at_ms);
+        write_workspace_sessions(&path, &store)
+    });
+    if let Err(err) = result {
+        tracing::warn!(workspace_id, thread_id, "failed to persist thread state: {err}");
+    }
+}
+
+async fn apply(app: &AppHandle, workspace_id: &str, transition: Transition) {
+    let state = app.state::<AppState>();
+    let (changed, snapshot) = {
+        let mut workspaces = state.thread_states.workspaces.lock().await;
+        let threads = workspaces.entry(workspace_id.to_string()).or_default();
+        let entry = threads
+            .entry(transition.thread_id.clone())
+            .or_insert_with(|| ThreadState {
+                thread_id: transition.thread_id.clone(),
+                state: ThreadActivity::Idle,
+                updated_at_ms: now_ms(),
+                turn_id: None,
+                pending_request_id: None,
+                error: None,
+                pending_request: None,
+            });
+        let previous = entry.state;
+        if previous == transition.state && transition.request_id.is_none() {
+            return;
+        }
+        entry.state = transition.state;
+        entry.updated_at_ms = now_ms();
+        if transition.turn_id.is_some() {
+            entry.turn_id = transition.turn_id;
+        }
+        entry.pending_request_id = transition.request_id;
+        entry.pending_request = transition.request;
+        entry.error = transition.error;
+        (
+            ThreadStateChanged {
+                workspace_id: workspace_id.to_string(),
+                thread_id: entry.thread_id.clone(),
+                state: entry.state,
+                previous_state: previous,
+                updated_at_ms: entry.updated_at_ms,
+                turn_id: entry.turn_id.clone(),
+                error: entry.error.clone(),
+            },
+            entry.clone(),
+        )
+    };
+    let state_changed = changed.previous_state != changed.state;
+    if state_changed {
+        persist_state(app, workspace_id, &snapshot.thread_id, &snapshot).await;
+    }
+    let _ = app.emit("thread-state-changed", changed);
+    badge::refresh(app).await;
+    if state_changed {
+        dock::refresh(app).await;
+    }
+}
+
+pub(crate) async fn observe(app: &AppHandle, workspace_id: &str, message: &Value) {
+    if let Some(transition) = transition_for(message) {
+        apply(app, workspace_id, transition).await;
+    }
+}
+
+pub(crate) async fn pending_thread(
+    state: &AppState,
+    workspace_id: &str,
+    request_id: u64,
+) -> Option<String> {
+    let workspaces = state.thread_states.workspaces.lock().await;
+    workspaces.get(workspace_id).and_then(|threads| {
+        threads
+            .values()
+            .find(|thread| thread.pending_request_id == Some(request_id))
+            .map(|thread| thread.thread_id.clone())
+    })
+}
+
+pub(crate) async fn approval_resolved(app: &AppHandle, workspace_id: &str, request_id: u64) {
+    let thread_id = pending_thread(&app.state::<AppState>(), workspace_id, request_id).await;
+    if let Some(thread_id) = thread_id {
+        let transition = Transition {
+            thread_id,
+            state: ThreadActivity::Generating,
+            turn_id: None,
+            request_id: None,
+            request: None,
+            error: None,
+        };
+        apply(app, workspace_id, transition).await;
+    }
+}
+
+pub(crate) async fn session_exited(app: &AppHandle, workspace_id: &str) {
+    let active: Vec<String> = {
+        let state = app.state::<AppState>();
+        let workspaces = state.thread_states.workspaces.lock().await;
+        workspaces
+            .get(workspace_id)
+            .map(|threads| {
+                threads
+                    .values()
+                    .filter(|thread| thread.state != ThreadActivity::Idle)
+                    .map(|thread| thread.thread_id.clone())
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+    for thread_id in active {
+        let transition = Transition {
+            thread_id,
+            state: ThreadActivity::Errored,
+            turn_id: None,
+            request_id: None,
+            request: None,
+            error: Some("app-server exited".to_string()),
+        };
+        apply(app, workspace_id, transition).await;
+    }
+}
+
+pub(crate) async fn workspace_thread_states(
+    state: &AppState,
+    workspace_id: &str,
+    store: &WorkspaceSessionStore,
+) -> Vec<ThreadState> {
+    let mut threads: HashMap<String, ThreadState> = store
+        .sessions
+        .iter()
+        .filter_map(|(thread_id, metadata)| {
+            let last_state = metadata.last_state?;
+            Some((
+                thread_id.clone(),
+                ThreadState {
+                    thread_id: thread_id.clone(),
+                    // A persisted in-flight state cannot outlive the process that produced it.
+                    state: match last_state {
+                        ThreadActivity::Errored => ThreadActivity::Errored,
+                        _ => ThreadActivity::Idle,
+                    },
+                    updated_at_ms: metadata.last_state_at_ms.unwrap_or_default(),
+                    turn_id: None,
+                    pending_request_id: None,
+                    error: None,
+                    pending_request: None,
+                },
+            ))
+        })
+        .collect();
+    if let Some(live) = state.thread_states.workspaces.lock().await.get(workspace_id) {
+        for (thread_id, thread) in live {
+            threads.insert(thread_id.clone(), thread.clone());
+        }
+    }
+    let mut threads: Vec<ThreadState> = threads.into_values().collect();
+    threads.sort_by_key(|thread| std::cmp::Reverse(thread.updated_at_ms));
+    threads
+}
+
+pub(crate) async fn thread_activity(
+    state: &AppState,
+    workspace_id: &str,
+    thread_id: &str,
+) -> ThreadActivity {
+    state
+        .thread_states
+        .workspaces
+        .lock()
+        .await
+        .get(workspace_id)
+        .and_then(|threads| threads.get(thread_id))
+        .map(|thread| thread.state)
+        .unwrap_or_default()
+}
+
+fn count_activity<'a>(threads: impl Iterator<Item = &'a ThreadState>) -> (usize, usize) {
+    threads.fold((0, 0), |(running, approvals), thread| match thread.state {
+        ThreadActivity::Generating => (running + 1, approvals),
+        ThreadActivity::AwaitingApproval => (running, approvals + 1),
+        _ => (running, approvals),
+    })
+}
+
+pub(crate) async fn activity_counts(state: &AppState) -> (usize, usize) {
+    let workspaces = state.thread_states.workspaces.lock().await;
+    count_activity(workspaces.values().flat_map(|threads| threads.values()))
+}
+
+pub(crate) async fn workspace_activity_counts(
+    state: &AppState,
+    workspace_id: &str,
+) -> (usize, usize) {
+    let workspaces = state.thread_states.workspaces.lock().await;
+    let threads = workspaces.get(workspace_id).into_iter().flat_map(|threads| threads.values());
+    count_activity(threads)
+}
+
+pub(crate) async fn pending_approvals(state: &AppState) -> Vec<PendingApproval> {
+    let workspaces = state.thread_states.workspaces.lock().await;
+    let mut approvals: Vec<PendingApproval> = workspaces
+        .iter()
+        .flat_map(|(workspace_id, threads)| {
+            threads
+                .values()
+                .filter(|thread| thread.state == ThreadActivity::AwaitingApproval)
+                .filter_map(move |thread| {
+                    let request = thread.pending_request.as_ref();
+                    Some(PendingApproval {
+                        workspace_id: workspace_id.clone(),
+                        thread_id: thread.thread_id.clone(),
+                        request_id: thread.pending_request_id?,
+                        method: request
+                            .and_then(|request| request.get("method"))
+                            .and_then(|method| method.as_str())
+                            .map(|method| method.to_string()),
+                        params: request.and_then(|request| request.get("params")).cloned(),
+                    })
+                })
+        })
+        .collect();
+    approvals.sort_by_key(|approval| approval.request_id);
+    approvals
+}
+
+#[tauri::command]
+pub(crate) async fn get_thread_states(
+    workspace_id: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<ThreadState>, AppError> {
+    let workspace_path = state
+        .workspaces
+        .lock()
+        .await
+        .get(&workspace_id)
+        .map(|entry| entry.path.clone())
+        .ok_or(AppError::WorkspaceNotFound)?;
+    let store = read_workspace_sessions(&workspace_sessions_path(&workspace_path))?;
+    Ok(workspace_thread_states(&state, &workspace_id, &store).await)
+}
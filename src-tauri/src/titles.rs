@@ -0,0 +1,287 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::{oneshot, Mutex};
+
+use crate::container;
+use crate::error::AppError;
+use crate::thread_state::{message_thread_id, str_at};
+use crate::{
+    read_workspace_sessions, workspace_sessions_path, write_workspace_sessions, AppState,
+    SessionNameSource,
+};
+
+const TITLE_TIMEOUT: Duration = Duration::from_secs(60);
+const MAX_PROMPT_CHARS: usize = 2000;
+const MAX_TITLE_CHARS: usize = 60;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ThreadTitleConfig {
+    #[serde(default)]
+    enabled: bool,
+    #[serde(default)]
+    model: Option<String>,
+}
+
+struct TitleJob {
+    reply: String,
+    done: Option<oneshot::Sender<String>>,
+}
+
+#[derive(Default)]
+pub(crate) struct ThreadTitles {
+    prompts: Mutex<HashMap<(String, String), String>>,
+    requested: Mutex<HashSet<(String, String)>>,
+    jobs: Mutex<HashMap<(String, String), TitleJob>>,
+}
+
+fn user_message_text(item: &Value) -> Option<String> {
+    let text = item
+        .get("content")?
+        .as_array()?
+        .iter()
+        .filter(|input| str_at(input, "/type") == Some("text"))
+        .filter_map(|input| str_at(input, "/text"))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let text = text.trim();
+    (!text.is_empty()).then(|| text.to_string())
+}
+
+fn title_prompt(request: &str) -> String {
+    let request: String = request.chars().take(MAX_PROMPT_CHARS).collect();
+    format!(
+        "Write a short title (at most six words) for a coding conversation that starts \
+         with the request below. Reply with the title only, without quotes or a trailing \
+         period. Do not run any commands.\n\n{request}"
+    )
+}
+
+fn clean_title(reply: &str) -> Option<String> {
+    let line = reply.lines().map(str::trim).find(|line| !line.is_empty())?;
+    let line = line
+        .trim_start_matches('#')
+        .trim()
+        .trim_matches(|c| c == '"' || c == '\'' || c == '`' || c == '*')
+        .trim_end_matches('.')
+        .trim();
+    let mut title: String = line.chars().take(MAX_TITLE_CHARS).collect();
+    if title.chars().count() < line.chars().count() {
+        title = title.trim_end().to_string();
+        title.push('…');
+    }
+    (!title.is_empty()).then_some(title)
+}
+
+// Events from the hidden title threads are consumed here so they never
+// reach the UI or the per-thread observers.
+pub(crate) async fn intercept(app: &AppHandle, workspace_id: &str, message: &Value) -> bool {
+    let Some(thread_id) = message.get("params").and_then(message_thread_id) else {
+        return false;
+    };
+    let state = app.state::<AppState>();
+    let mut jobs = state.thread_titles.jobs.lock().await;
+    let Some(job) = jobs.get_mut(&(workspace_id.to_string(), thread_id)) else {
+        return false;
+    };
+    match str_at(message, "/method").unwrap_or_default() {
+        "item/completed" if str_at(message, "/params/item/type") == Some("agentMessage") => {
+            job.reply = str_at(message, "/params/item/text")
+                .unwrap_or_default()
+                .to_string();
+        }
+        "turn/completed" => {
+            if let Some(done) = job.done.take() {
+                let _ = done.send(std::mem::take(&mut job.reply));
+            }
+        }
+        _ => {}
+    }
+    true
+}
+
+async fn generate(
+    app: &AppHandle,
+    workspace_id: &str,
+    request: &str,
+    config: &ThreadTitleConfig,
+) -> Result<Option<String>, AppError> {
+    let state = app.state::<AppState>();
+    let session = state
+        .sessions
+        .lock()
+        .await
+        .get(workspace_id)
+        .cloned()
+        .ok_or(AppError::NotConnected)?;
+    let cwd = container::agent_cwd(&session.entry);
+    let mut params = Map::new();
+    params.insert("approvalPolicy".to_string(), json!("never"));
+    params.insert("sandbox".to_string(), json!("read-only"));
+    params.insert("cwd".to_string(), json!(cwd));
+    if let Some(model) = &config.model {
+        params.insert("model".to_string(), json!(model));
+    }
+    let response = session
+        .send_request("thread/start", Value::Object(params))
+        .await?;
+    if let Some(error) = AppError::from_rpc_response(&response) {
+        return Err(error);
+    }
+    let title_thread_id = str_at(&response, "/result/thread/id")
+        .ok_or("thread/start returned no thread id")?
+        .to_string();
+    let key = (workspace_id.to_string(), title_thread_id.clone());
+    let (tx, rx) = oneshot::channel();
+    state.thread_titles.jobs.lock().await.insert(
+        key.clone(),
+        TitleJob {
+            reply: String::new(),
+            done: Some(tx),
+        },
+    );
+
+    let params = json!({
+        "threadId": title_thread_id,
+        "input": [{ "type": "text", "text": title_prompt(request) }],
+        "cwd": cwd,
+    });
+    let started = session.send_request("turn/start", params).await;
+    let reply = match started {
+        Ok(response) if response.get("error").is_none() => {
+            tokio::time::timeout(TITLE_TIMEOUT, rx).await.ok().and_then(Result::ok)
+        }
+        _ => None,
+    };
+    state.thread_titles.jobs.lock().await.remove(&key);
+    let _ = session
+        .send_request("thread/archive", json!({ "threadId": title_thread_id }))
+        .await;
+    Ok(reply.as_deref().and_then(clean_title))
+}
+
+async fn store_title(
+    app: &AppHandle,
+    workspace_id: &str,
+    thread_id: &str,
+    title: &str,
+) -> Result<bool, String> {
+    let state = app.state::<AppState>();
+    let workspace_path = state
+        .workspaces
+        .lock()
+        .await
+        .get(workspace_id)
+        .map(|entry| entry.path.clone())
+        .ok_or("workspace not found")?;
+    let path = workspace_sessions_path(&workspace_path);
+    let mut store = read_workspace_sessions(&path)?;
+    let metadata = store.sessions.entry(thread_id.to_string()).or_default();
+    if matches!(metadata.name_source, SessionNameSource::Custom) {
+        return Ok(false);
+    }
+    metadata.name = title.to_string();
+    metadata.name_source = SessionNameSource::Default;
+    metadata.auto_titled = true;
+    write_workspace_sessions(&path, &store)?;
+    Ok(true)
+}
+
+async fn should_title(app: &AppHandle, workspace_id: &str, thread_id: &str) -> bool {
+    let state = app.state::<AppState>();
+    let Some(workspace_path) = state
+        .workspaces
+        .lock()
+        .await
+        .get(workspace_id)
+        .map(|entry| entry.path.clone())
+    else {
+        return false;
+    };
+    let store = read_workspace_sessions(&workspace_sessions_path(&workspace_path))
+        .unwrap_or_default();
+    match store.sessions.get(thread_id) {
+        Some(metadata) => {
+            !metadata.auto_titled && !matches!(metadata.name_source, SessionNameSource::Custom)
+        }
+        None => true,
+    }
+}
+
+async fn title_thread(app: AppHandle, workspace_id: String, thread_id: String, request: String) {
+    let config = app.state::<AppState>().settings.lock().await.thread_titles.clone();
+    if !should_title(&app, &workspace_id, &thread_id).await {
+        return;
+    }
+    let title = match generate(&app, &workspace_id, &request, &config).await {
+        Ok(Some(title)) => title,
+        Ok(None) => return,
+        Err(err) => {
+            tracing::warn!(%workspace_id, %thread_id, "failed to generate thread title: {err}");
+            return;
+        }
+    };
+    match store_title(&app, &workspace_id, &thread_id, &title).await {
+        Ok(true) => {
+            let _ = app.emit(
+                "thread-renamed",
+                json!({
+                    "workspaceId": workspace_id,
+                    "threadId": thread_id,
+                    "name": title,
+                    "nameSource": SessionNameSource::Default,
+                }),
+            );
+        }
+        Ok(false) => {}
+        Err(err) => {
+            tracing::warn!(%workspace_id, %thread_id, "failed to store thread title: {err}");
+        }
+    }
+}
+
+pub(crate) async fn observe(app: &AppHandle, workspace_id: &str, message: &Value) {
+    let Some(method) = str_at(message, "/method") else {
+        return;
+    };
+    let params = message.get("params").unwrap_or(&Value::Null);
+    let Some(thread_id) = message_thread_id(params) else {
+        return;
+    };
+    let state = app.state::<AppState>();
+    let key = (workspace_id.to_string(), thread_id);
+    match method {
+        "item/completed" => {
+            let item = params.get("item").unwrap_or(&Value::Null);
+            if str_at(item, "/type") != Some("userMessage") {
+                return;
+            }
+            if let Some(text) = user_message_text(item) {
+                state.thread_titles.prompts.lock().await.entry(key).or_insert(text);
+            }
+        }
+        "turn/completed" => {
+            if !state.settings.lock().await.thread_titles.enabled {
+                return;
+            }
+            let Some(request) = state.thread_titles.prompts.lock().await.get(&key).cloned()
+            else {
+                return;
+            };
+            if !state.thread_titles.requested.lock().await.insert(key.clone()) {
+                return;
+            }
+            let (workspace_id, thread_id) = key;
+            tauri::async_runtime::spawn(title_thread(
+                app.clone(),
+                workspace_id,
+                thread_id,
+                request,
+            ));
+        }
+        _ => {}
+    }
+}
@@ -0,0 +1,130 @@
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::Ordering;
+use tauri::menu::{Menu, MenuItem};
+use tauri::tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent};
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::error::AppError;
+use crate::{i18n, AppState};
+
+const TRAY_ID: &str = "main";
+const SHOW_ITEM: &str = "tray-show";
+const QUIT_ITEM: &str = "tray-quit";
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum QuitBehavior {
+    #[default]
+    Quit,
+    KeepRunning,
+}
+
+pub(crate) fn keeps_running(state: &AppState) -> bool {
+    if state.allow_quit.load(Ordering::SeqCst) {
+        return false;
+    }
+    let behavior =
+        tauri::async_runtime::block_on(async { state.settings.lock().await.quit_behavior });
+    behavior == QuitBehavior::KeepRunning
+}
+
+pub(crate) fn show_main_window<R: Runtime>(app: &AppHandle<R>) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.show();
+        let _ = window.unminimize();
+        let _ = window.set_focus();
+    }
+}
+
+pub(crate) fn hide_main_window<R: Runtime>(app: &AppHandle<R>) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.hide();
+    }
+    app.state::<AppState>()
+        .window_focused
+        .store(false, Ordering::SeqCst);
+}
+
+fn tray_menu<R: Runtime>(app: &AppHandle<R>, lang: &str) -> tauri::Result<Menu<R>> {
+    let show = MenuItem::with_id(app, SHOW_ITEM, i18n::t(lang, "tray.show"), true, None::<&str>)?;
+    let quit = MenuItem::with_id(app, QUIT_ITEM, i18n::t(lang, "tray.quit"), true, None::<&str>)?;
+    Menu::with_items(app, &[&show, &quit])
+}
+
+pub(crate) fn sync<R: Runtime>(
+    app: &AppHandle<R>,
+    behavior: QuitBehavior,
+    lang: &str,
+) -> Result<(), String> {
+    let existing = app.tray_by_id(TRAY_ID);
+    match (behavior, existing) {
+        (QuitBehavior::KeepRunning, None) => {
+            let menu = tray_menu(app, lang).map_err(|e| e.to_string())?;
+            let mut builder = TrayIconBuilder::with_id(TRAY_ID)
+                .tooltip("Codexola")
+                .menu(&menu)
+                .show_menu_on_left_click(false)
+                .on_menu_event(|app, event| {
+                    if event.id() == SHOW_ITEM {
+                        show_main_window(app);
+                    } else if event.id() == QUIT_ITEM {
+                        let app = app.clone();
+                        tauri::async_runtime::spawn(async move {
+                            stop_agents_and_exit(&app).await;
+                        });
+                    }
+                })
+                .on_tray_icon_event(|tray, event| {
+                    if let TrayIconEvent::Click {
+                        button: MouseButton::Left,
+                        button_state: MouseButtonState::Up,
+                        ..
+                    } = event
+                    {
+                        show_main_window(tray.app_handle());
+                    }
+                });
+            if let Some(icon) = app.default_window_icon() {
+                builder = builder.icon(icon.clone());
+            }
+            builder.build(app).map_err(|e| e.to_string())?;
+        }
+        (QuitBehavior::KeepRunning, Some(tray)) => {
+            let menu = tray_menu(app, lang).map_err(|e| e.to_string())?;
+            tray.set_menu(Some(menu)).map_err(|e| e.to_string())?;
+        }
+        (QuitBehavior::Quit, Some(_)) => {
+            let _ = app.remove_tray_by_id(TRAY_ID);
+            show_main_window(app);
+        }
+        (QuitBehavior::Quit, None) => {}
+    }
+    Ok(())
+}
+
+pub(crate) async fn stop_agents<R: Runtime>(app: &AppHandle<R>) {
+    let state = app.state::<AppState>();
+    if let Some(handle) = state.usage_poll_handle.lock().await.take() {
+        handle.abort();
+    }
+    let sessions: Vec<_> = state.sessions.lock().await.drain().collect();
+    for (workspace_id, session) in sessions {
+        let _ = session.child.lock().await.kill().await;
+        state.terminals.kill_workspace(&workspace_id).await;
+        state.tasks.kill_workspace(&workspace_id).await;
+    }
+}
+
+async fn stop_agents_and_exit<R: Runtime>(app: &AppHandle<R>) {
+    app.state::<AppState>()
+        .allow_quit
+        .store(true, Ordering::SeqCst);
+    stop_agents(app).await;
+    app.exit(0);
+}
+
+#[tauri::command]
+pub(crate) async fn quit_and_stop_agents(app: AppHandle) -> Result<(), AppError> {
+    stop_agents_and_exit(&app).await;
+    Ok(())
+}
@@ -0,0 +1,286 @@
+use serde::Serialize;
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::path::Path;
+use tauri::{AppHandle, State};
+
+use crate::error::AppError;
+use crate::metrics::{self, TurnMetrics};
+use crate::thread_state::{self, ThreadActivity};
+use crate::{
+    dispatch_user_message, enqueue_outbox_message, mark_file_index_stale, observer,
+    open_repository, workspace_entry, AppState, UserMessageRequest,
+};
+
+#[derive(Debug, Serialize, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+enum ConflictReason {
+    LaterTurn,
+    ModifiedSince,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct UndoConflict {
+    path: String,
+    reason: ConflictReason,
+    thread_id: Option<String>,
+    turn_index: Option<usize>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct UndoResult {
+    thread_id: String,
+    turn_index: usize,
+    applied: bool,
+    restored: Vec<String>,
+    removed: Vec<String>,
+    conflicts: Vec<UndoConflict>,
+    note_posted: bool,
+}
+
+fn affected_paths(turn: &TurnMetrics) -> BTreeSet<String> {
+    turn.changes
+        .iter()
+        .flat_map(|change| std::iter::once(change.path.clone()).chain(change.old_path.clone()))
+        .collect()
+}
+
+fn blob_at(
+    repo: &git2::Repository,
+    tree: &str,
+    path: &Path,
+) -> Result<Option<Vec<u8>>, git2::Error> {
+    let tree = repo.find_tree(git2::Oid::from_str(tree)?)?;
+    let Ok(entry) = tree.get_path(path) else {
+        return Ok(None);
+    };
+    Ok(Some(repo.find_blob(entry.id())?.content().to_vec()))
+}
+
+fn current_bytes(target: &Path) -> Result<Option<Vec<u8>>, std::io::Error> {
+    if target.is_file() {
+        fs::read(target).map(Some)
+    } else {
+        Ok(None)
+    }
+}
+
+fn later_turn_conflicts(
+    turns: &HashMap<String, Vec<TurnMetrics>>,
+    turn: &TurnMetrics,
+    paths: &BTreeSet<String>,
+) -> Vec<UndoConflict> {
+    let mut conflicts = Vec::new();
+    for (thread_id, thread_turns) in turns {
+        for (index, later) in thread_turns.iter().enumerate() {
+            if later.completed_at_ms <= turn.completed_at_ms || later.undone_at_ms.is_some() {
+                continue;
+            }
+            for path in affected_paths(later).intersection(paths) {
+                if conflicts.iter().any(|conflict: &UndoConflict| &conflict.path == path) {
+                    continue;
+                }
+                conflicts.push(UndoConflict {
+                    path: path.clone(),
+                    reason: ConflictReason::LaterTurn,
+                    thread_id: Some(thread_id.clone()),
+                    turn_index: Some(index),
+                });
+            }
+        }
+    }
+    conflicts
+}
+
+fn modified_since(
+    workspace_path: &str,
+    after_tree: &str,
+    paths: &BTreeSet<String>,
+) -> Result<Vec<UndoConflict>, AppError> {
+    let repo = open_repository(workspace_path)?;
+    let mut conflicts = Vec::new();
+    for path in paths {
+        let after = blob_at(&repo, after_tree, Path::new(path))?;
+        let current = current_bytes(&Path::new(workspace_path).join(path))?;
+        if after != current {
+            conflicts.push(UndoConflict {
+                path: path.clone(),
+                reason: ConflictReason::ModifiedSince,
+                thread_id: None,
+                turn_index: None,
+            });
+        }
+    }
+    Ok(conflicts)
+}
+
+fn restore_paths(
+    workspace_path: &str,
+    before_tree: &str,
+    paths: &BTreeSet<String>,
+) -> Result<(Vec<String>, Vec<String>), AppError> {
+    let repo = open_repository(workspace_path)?;
+    let mut restored = Vec::new();
+    let mut removed = Vec::new();
+    for path in paths {
+        let target = Path::new(workspace_path).join(path);
+        match blob_at(&repo, before_tree, Path::new(path))? {
+            Some(bytes) => {
+                if let Some(parent) = target.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                fs::write(&target, bytes)?;
+                restored.push(path.clone());
+            }
+            None => {
+                if target.is_file() {
+                    fs::remove_file(&target)?;
+                }
+                removed.push(path.clone());
+            }
+        }
+    }
+    Ok((restored, removed))
+}
+
+fn undo_note(restored: &[String], removed: &[String]) -> String {
+    let mut note = String::from(
+        "Note: I reverted the file changes from one of your earlier turns in this thread.\n",
+    );
+    if !restored.is_empty() {
+        note.push_str("\nRestored to their previous content:\n");
+        for path in restored {
+            note.push_str(&format!("- {path}\n"));
+        }
+    }
+    if !removed.is_empty() {
+        note.push_str("\nRemoved (they did not exist before that turn):\n");
+        for path in removed {
+            note.push_str(&format!("- {path}\n"));
+        }
+    }
+    note.push_str("\nRe-read these files before editing them again. No reply is needed.");
+    note
+}
+
+async fn post_note(
+    app: &AppHandle,
+    state: &AppState,
+    workspace_id: &str,
+    thread_id: &str,
+    text: String,
+) -> Result<(), AppError> {
+    let message = UserMessageRequest {
+        thread_id: thread_id.to_string(),
+        text,
+        model: None,
+        effort: None,
+        access_mode: None,
+        attachments: Vec::new(),
+    };
+    let session = state.sessions.lock().await.get(workspace_id).cloned();
+    match session {
+        Some(session) => {
+            let response = dispatch_user_message(app, &session, &message, false).await?;
+            if let Some(error) = AppError::from_rpc_response(&response) {
+                return Err(error);
+            }
+        }
+        None => {
+            enqueue_outbox_message(app, state, workspace_id, message).await?;
+        }
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub(crate) async fn undo_turn(
+    workspace_id: String,
+    thread_id: String,
+    turn_index: usize,
+    force: Option<bool>,
+    post_note_to_thread: Option<bool>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<UndoResult, AppError> {
+    let entry = workspace_entry(&state, &workspace_id).await?;
+    observer::ensure_writable(&entry, "undoing turns")?;
+    if matches!(
+        thread_state::thread_activity(&state, &workspace_id, &thread_id).await,
+        ThreadActivity::Generating | ThreadActivity::AwaitingApproval
+    ) {
+        return Err("Wait for the current turn to finish before undoing.".into());
+    }
+    let turns = metrics::read_workspace_turns(&entry.path)?;
+    let turn = turns
+        .get(&thread_id)
+        .and_then(|thread_turns| thread_turns.get(turn_index))
+        .cloned()
+        .ok_or("turn not found")?;
+    let (Some(before_tree), Some(after_tree)) =
+        (turn.before_tree.clone(), turn.after_tree.clone())
+    else {
+        return Err("No file snapshots were recorded for this turn.".into());
+    };
+    if turn.undone_at_ms.is_some() {
+        return Err("This turn has already been undone.".into());
+    }
+    let paths = affected_paths(&turn);
+    if paths.is_empty() {
+        return Err("This turn did not change any files.".into());
+    }
+
+    let force = force.unwrap_or(false);
+    let mut conflicts = later_turn_conflicts(&turns, &turn, &paths);
+    let workspace_path = entry.path.clone();
+    let check_paths = paths.clone();
+    let modified = tokio::task::spawn_blocking(move || {
+        modified_since(&workspace_path, &after_tree, &check_paths)
+    })
+    .await??;
+    for conflict in modified {
+        if !conflicts.iter().any(|existing| existing.path == conflict.path) {
+            conflicts.push(conflict);
+        }
+    }
+    if !conflicts.is_empty() && !force {
+        return Ok(UndoResult {
+            thread_id,
+            turn_index,
+            applied: false,
+            restored: Vec::new(),
+            removed: Vec::new(),
+            conflicts,
+            note_posted: false,
+        });
+    }
+
+    let workspace_path = entry.path.clone();
+    let (restored, removed) =
+        tokio::task::spawn_blocking(move || restore_paths(&workspace_path, &before_tree, &paths))
+            .await??;
+    mark_file_index_stale(&state, &workspace_id).await;
+    metrics::mark_turn_undone(&app, &workspace_id, &thread_id, turn_index).await;
+
+    let mut note_posted = false;
+    if post_note_to_thread.unwrap_or(true) {
+        let note = undo_note(&restored, &removed);
+        match post_note(&app, &state, &workspace_id, &thread_id, note).await {
+            Ok(()) => note_posted = true,
+            Err(err) => {
+                tracing::warn!(%workspace_id, %thread_id, "failed to post undo note: {err}");
+            }
+        }
+    }
+    Ok(UndoResult {
+        thread_id,
+        turn_index,
+        applied: true,
+        restored,
+        removed,
+        conflicts,
+        note_posted,
+    })
+}
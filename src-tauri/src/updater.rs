@@ -0,0 +1,188 @@
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tauri::{AppHandle, Emitter, State};
+use tauri_plugin_updater::{Update, Updater, UpdaterExt};
+use tokio::sync::Mutex;
+
+use crate::error::AppError;
+use crate::{thread_state, tray, AppState};
+
+const STABLE_ENDPOINT: &str =
+    "https://github.com/chenghuzi/Codexola/releases/latest/download/latest.json";
+const BETA_ENDPOINT: &str =
+    "https://github.com/chenghuzi/Codexola/releases/download/beta/latest.json";
+const PROGRESS_STEP_BYTES: u64 = 256 * 1024;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum UpdateChannel {
+    #[default]
+    Stable,
+    Beta,
+}
+
+#[derive(Default)]
+struct PendingUpdate {
+    update: Option<Update>,
+    bytes: Option<Vec<u8>>,
+}
+
+#[derive(Default)]
+pub(crate) struct UpdaterState {
+    pending: Mutex<PendingUpdate>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct UpdateInfo {
+    current_version: String,
+    version: String,
+    notes: Option<String>,
+    published_at: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct UpdateCheck {
+    channel: UpdateChannel,
+    current_version: String,
+    update: Option<UpdateInfo>,
+}
+
+fn info(update: &Update) -> UpdateInfo {
+    UpdateInfo {
+        current_version: update.current_version.clone(),
+        version: update.version.clone(),
+        notes: update.body.clone().filter(|notes| !notes.trim().is_empty()),
+        published_at: update
+            .raw_json
+            .get("pub_date")
+            .and_then(|date| date.as_str())
+            .map(str::to_string),
+    }
+}
+
+// Release builds get the signing public key at compile time; without it an
+// update could never be verified, so checking is refused up front.
+fn build_updater(app: &AppHandle, channel: UpdateChannel) -> Result<Updater, String> {
+    let pubkey = option_env!("CODEXOLA_UPDATER_PUBKEY").unwrap_or_default();
+    if pubkey.trim().is_empty() {
+        return Err("Updates are not configured for this build.".to_string());
+    }
+    let endpoint = match channel {
+        UpdateChannel::Stable => STABLE_ENDPOINT,
+        UpdateChannel::Beta => BETA_ENDPOINT,
+    };
+    let endpoint = url::Url::parse(endpoint).map_err(|err| err.to_string())?;
+    app.updater_builder()
+        .pubkey(pubkey)
+        .endpoints(vec![endpoint])
+        .map_err(|err| err.to_string())?
+        .build()
+        .map_err(|err| err.to_string())
+}
+
+#[tauri::command]
+pub(crate) async fn check_for_updates(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<UpdateCheck, AppError> {
+    let channel = state.settings.lock().await.update_channel;
+    let update = build_updater(&app, channel)?
+        .check()
+        .await
+        .map_err(|err| format!("Failed to check for updates: {err}"))?;
+    let info = update.as_ref().map(info);
+    *state.updater.pending.lock().await = PendingUpdate {
+        update,
+        bytes: None,
+    };
+    if let Some(info) = &info {
+        let _ = app.emit("update-available", info);
+    }
+    Ok(UpdateCheck {
+        channel,
+        current_version: app.package_info().version.to_string(),
+        update: info,
+    })
+}
+
+#[tauri::command]
+pub(crate) async fn download_update(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<UpdateInfo, AppError> {
+    let update = state
+        .updater
+        .pending
+        .lock()
+        .await
+        .update
+        .clone()
+        .ok_or("No update available. Check for updates first.")?;
+    let version = update.version.clone();
+    let mut downloaded: u64 = 0;
+    let mut reported: u64 = 0;
+    let bytes = update
+        .download(
+            |chunk, total| {
+                downloaded += chunk as u64;
+                if downloaded - reported >= PROGRESS_STEP_BYTES || Some(downloaded) == total {
+                    reported = downloaded;
+                    let _ = app.emit(
+                        "update-download-progress",
+                        json!({
+                            "version": version,
+                            "downloaded": downloaded,
+                            "contentLength": total,
+                        }),
+                    );
+                }
+            },
+            || {},
+        )
+        .await
+        .map_err(|err| format!("Failed to download update: {err}"))?;
+    let info = info(&update);
+    {
+        let mut pending = state.updater.pending.lock().await;
+        if pending.update.as_ref().map(|pending| &pending.version) != Some(&update.version) {
+            return Err("A newer update check replaced this download.".into());
+        }
+        pending.bytes = Some(bytes);
+    }
+    let _ = app.emit("update-downloaded", &info);
+    Ok(info)
+}
+
+#[tauri::command]
+pub(crate) async fn install_update_and_restart(
+    force: Option<bool>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<(), AppError> {
+    let (update, bytes) = {
+        let mut pending = state.updater.pending.lock().await;
+        match (pending.update.clone(), pending.bytes.take()) {
+            (Some(update), Some(bytes)) => (update, bytes),
+            _ => return Err("Download the update before installing it.".into()),
+        }
+    };
+    let (running_turns, pending_approvals) = thread_state::activity_counts(&state).await;
+    let active = running_turns + pending_approvals;
+    if active > 0 && !force.unwrap_or(false) {
+        state.updater.pending.lock().await.bytes = Some(bytes);
+        return Err(AppError::TurnsRunning {
+            running_turns: active,
+            message: format!(
+                "{active} turn(s) still running. Let them finish or interrupt them before updating."
+            ),
+        });
+    }
+    let _ = app.emit("update-installing", info(&update));
+    tray::stop_agents(&app).await;
+    update
+        .install(bytes)
+        .map_err(|err| format!("Failed to install update: {err}"))?;
+    app.restart()
+}
@@ -0,0 +1,199 @@
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager, PhysicalPosition, PhysicalSize, State, WebviewWindow};
+
+use crate::error::AppError;
+use crate::{default_session_store_version, storage, AppState};
+
+const MIN_VISIBLE: i64 = 64;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct WindowGeometry {
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct WindowState {
+    #[serde(default = "default_session_store_version")]
+    version: u32,
+    #[serde(default)]
+    geometry: Option<WindowGeometry>,
+    #[serde(default)]
+    maximized: bool,
+    #[serde(default)]
+    last_workspace_id: Option<String>,
+    #[serde(default)]
+    last_thread_id: Option<String>,
+}
+
+impl Default for WindowState {
+    fn default() -> Self {
+        Self {
+            version: default_session_store_version(),
+            geometry: None,
+            maximized: false,
+            last_workspace_id: None,
+            last_thread_id: None,
+        }
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct WindowStateStore {
+    current: Mutex<WindowState>,
+}
+
+impl WindowStateStore {
+    fn lock(&self) -> std::sync::MutexGuard<'_, WindowState> {
+        self.current.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+fn window_state_path(state: &AppState) -> PathBuf {
+    state.data_dir.join("window-state.json")
+}
+
+fn overlap(a: &WindowGeometry, b: &WindowGeometry) -> (i64, i64) {
+    let span = |start: i32, len: u32, other_start: i32, other_len: u32| {
+        let end = (start as i64 + len as i64).min(other_start as i64 + other_len as i64);
+        (end - (start as i64).max(other_start as i64)).max(0)
+    };
+    (
+        span(a.x, a.width, b.x, b.width),
+        span(a.y, a.height, b.y, b.height),
+    )
+}
+
+// Keeps the saved rect on the monitor it overlaps most; if that monitor is
+// gone (or only a sliver is visible), moves it onto the fallback monitor.
+fn fit(
+    saved: WindowGeometry,
+    areas: &[WindowGeometry],
+    fallback: WindowGeometry,
+) -> WindowGeometry {
+    let target = areas
+        .iter()
+        .map(|area| (area, overlap(&saved, area)))
+        .filter(|(_, (w, h))| *w >= MIN_VISIBLE && *h >= MIN_VISIBLE)
+        .max_by_key(|(_, (w, h))| w * h)
+        .map(|(area, _)| *area)
+        .unwrap_or(fallback);
+    let width = saved.width.min(target.width);
+    let height = saved.height.min(target.height);
+    let max_x = target.x + (target.width - width) as i32;
+    let max_y = target.y + (target.height - height) as i32;
+    WindowGeometry {
+        x: saved.x.clamp(target.x, max_x),
+        y: saved.y.clamp(target.y, max_y),
+        width,
+        height,
+    }
+}
+
+fn work_areas(window: &WebviewWindow) -> (Vec<WindowGeometry>, Option<WindowGeometry>) {
+    let to_geometry = |monitor: &tauri::Monitor| {
+        let area = monitor.work_area();
+        WindowGeometry {
+            x: area.position.x,
+            y: area.position.y,
+            width: area.size.width,
+            height: area.size.height,
+        }
+    };
+    let areas: Vec<_> = window
+        .available_monitors()
+        .unwrap_or_default()
+        .iter()
+        .map(to_geometry)
+        .collect();
+    let primary = window
+        .primary_monitor()
+        .ok()
+        .flatten()
+        .map(|monitor| to_geometry(&monitor))
+        .or_else(|| areas.first().copied());
+    (areas, primary)
+}
+
+pub(crate) fn restore(app: &AppHandle) {
+    let state = app.state::<AppState>();
+    let saved: WindowState = storage::read_json_or_default(&window_state_path(&state))
+        .unwrap_or_else(|err| {
+            tracing::warn!("failed to read window state: {err}");
+            WindowState::default()
+        });
+    *state.window_state.lock() = saved.clone();
+    let Some(window) = app.get_webview_window("main") else {
+        return;
+    };
+    if let Some(geometry) = saved.geometry {
+        let (areas, primary) = work_areas(&window);
+        if let Some(fallback) = primary {
+            let fitted = fit(geometry, &areas, fallback);
+            let _ = window.set_size(PhysicalSize::new(fitted.width, fitted.height));
+            let _ = window.set_position(PhysicalPosition::new(fitted.x, fitted.y));
+        }
+    }
+    if saved.maximized {
+        let _ = window.maximize();
+    }
+}
+
+pub(crate) fn capture(app: &AppHandle) {
+    let Some(window) = app.get_webview_window("main") else {
+        return;
+    };
+    if window.is_minimized().unwrap_or(false) || !window.is_visible().unwrap_or(true) {
+        return;
+    }
+    let maximized = window.is_maximized().unwrap_or(false);
+    let geometry = match (window.outer_position(), window.inner_size()) {
+        (Ok(position), Ok(size)) if !maximized => Some(WindowGeometry {
+            x: position.x,
+            y: position.y,
+            width: size.width,
+            height: size.height,
+        }),
+        _ => None,
+    };
+    let state = app.state::<AppState>();
+    let mut current = state.window_state.lock();
+    current.maximized = maximized;
+    if geometry.is_some() {
+        current.geometry = geometry;
+    }
+}
+
+pub(crate) fn save(app: &AppHandle) {
+    capture(app);
+    let state = app.state::<AppState>();
+    let current = state.window_state.lock().clone();
+    if let Err(err) = storage::write_json_atomic(&window_state_path(&state), &current) {
+        tracing::warn!("failed to save window state: {err}");
+    }
+}
+
+pub(crate) fn note_active(state: &AppState, workspace_id: &str, thread_id: &str) {
+    let mut current = state.window_state.lock();
+    current.last_workspace_id = Some(workspace_id.to_string());
+    current.last_thread_id = Some(thread_id.to_string());
+}
+
+pub(crate) fn last_active(state: &AppState) -> Option<(String, Option<String>)> {
+    let current = state.window_state.lock();
+    let workspace_id = current.last_workspace_id.clone()?;
+    Some((workspace_id, current.last_thread_id.clone()))
+}
+
+#[tauri::command]
+pub(crate) async fn get_window_state(
+    state: State<'_, AppState>,
+) -> Result<WindowState, AppError> {
+    Ok(state.window_state.lock().clone())
+}
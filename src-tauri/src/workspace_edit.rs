@@ -0,0 +1,140 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter, State};
+
+use crate::error::AppError;
+use crate::{
+    connect_entry, instructions, menu, platform, stop_file_index_watcher, thread_state,
+    workspace_infos, write_workspaces, AppState, WorkspaceInfo,
+};
+
+const STORE_DIR: &str = ".codexmonitor";
+
+fn copy_dir(source: &Path, target: &Path) -> std::io::Result<()> {
+    fs::create_dir_all(target)?;
+    for entry in fs::read_dir(source)? {
+        let entry = entry?;
+        let destination = target.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir(&entry.path(), &destination)?;
+        } else {
+            fs::copy(entry.path(), destination)?;
+        }
+    }
+    Ok(())
+}
+
+// The old directory may still exist (a copied checkout rather than a move),
+// so the store is copied and never removed from the old location.
+fn migrate_store(old_path: &Path, new_path: &Path) -> Result<(), String> {
+    let source = old_path.join(STORE_DIR);
+    let target = new_path.join(STORE_DIR);
+    if !source.is_dir() || target.exists() {
+        return Ok(());
+    }
+    copy_dir(&source, &target).map_err(|err| format!("Failed to migrate {STORE_DIR}: {err}"))
+}
+
+fn validate_path(raw: &str) -> Result<String, AppError> {
+    let path = PathBuf::from(raw.trim());
+    if !path.is_absolute() {
+        return Err(format!("Workspace path must be absolute: {raw}").into());
+    }
+    let path = fs::canonicalize(&path)
+        .map_err(|err| format!("Workspace path is not accessible: {raw} ({err})"))?;
+    if !path.is_dir() {
+        return Err(format!("Workspace path is not a directory: {raw}").into());
+    }
+    Ok(platform::simplify_path(&path).to_string_lossy().to_string())
+}
+
+async fn updated_info(
+    app: &AppHandle,
+    state: &AppState,
+    id: &str,
+) -> Result<WorkspaceInfo, AppError> {
+    let info = workspace_infos(state)
+        .await
+        .into_iter()
+        .find(|info| info.id == id)
+        .ok_or(AppError::WorkspaceNotFound)?;
+    let _ = app.emit("workspace-updated", &info);
+    menu::rebuild(app).await;
+    Ok(info)
+}
+
+#[tauri::command]
+pub(crate) async fn rename_workspace(
+    id: String,
+    name: String,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<WorkspaceInfo, AppError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Workspace name cannot be empty.".into());
+    }
+    {
+        let mut workspaces = state.workspaces.lock().await;
+        let entry = workspaces.get_mut(&id).ok_or(AppError::WorkspaceNotFound)?;
+        entry.name = name.to_string();
+        let list: Vec<_> = workspaces.values().cloned().collect();
+        write_workspaces(&state.storage_path, &list)?;
+    }
+    updated_info(&app, &state, &id).await
+}
+
+// A running app-server keeps the old cwd and writable roots, so a connected
+// workspace is reconnected; that is refused while any of its turns are live.
+#[tauri::command]
+pub(crate) async fn relocate_workspace(
+    id: String,
+    new_path: String,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<WorkspaceInfo, AppError> {
+    let new_path = validate_path(&new_path)?;
+    let (running, approvals) = thread_state::workspace_activity_counts(&state, &id).await;
+    if running + approvals > 0 {
+        return Err(AppError::TurnsRunning {
+            running_turns: running + approvals,
+            message: format!(
+                "{} turn(s) still running in this workspace. Let them finish before moving it.",
+                running + approvals
+            ),
+        });
+    }
+    let entry = {
+        let mut workspaces = state.workspaces.lock().await;
+        let taken = workspaces
+            .values()
+            .any(|entry| entry.id != id && entry.path == new_path);
+        if taken {
+            return Err(format!("Another workspace already uses {new_path}.").into());
+        }
+        let entry = workspaces.get_mut(&id).ok_or(AppError::WorkspaceNotFound)?;
+        if entry.path == new_path {
+            drop(workspaces);
+            return updated_info(&app, &state, &id).await;
+        }
+        let old_path = PathBuf::from(&entry.path);
+        let target = PathBuf::from(&new_path);
+        tokio::task::spawn_blocking(move || migrate_store(&old_path, &target)).await??;
+        entry.path = new_path;
+        let entry = entry.clone();
+        let list: Vec<_> = workspaces.values().cloned().collect();
+        write_workspaces(&state.storage_path, &list)?;
+        entry
+    };
+
+    stop_file_index_watcher(&state, &id).await;
+    instructions::stop_watching(&state, &id).await;
+    let previous = state.sessions.lock().await.remove(&id);
+    if let Some(session) = previous {
+        let _ = session.child.lock().await.kill().await;
+        if let Err(err) = connect_entry(entry, &state, app.clone()).await {
+            tracing::warn!(workspace_id = %id, "reconnect after relocation failed: {err}");
+        }
+    }
+    updated_info(&app, &state, &id).await
+}
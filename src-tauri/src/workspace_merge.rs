@@ -0,0 +1,272 @@
+use git2::Repository;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use tauri::{AppHandle, State};
+
+use crate::error::AppError;
+use crate::{
+    metrics, platform, read_drafts, read_workspace_sessions, remove_workspace, thread_state,
+    workspace_drafts_path, workspace_infos, workspace_sessions_path, write_drafts,
+    write_workspace_sessions, AppState, WorkspaceInfo,
+};
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+enum DuplicateReason {
+    Path,
+    GitRoot,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct DuplicateWorkspaces {
+    key: String,
+    reason: DuplicateReason,
+    workspaces: Vec<WorkspaceInfo>,
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum TransferConflict {
+    #[default]
+    Skip,
+    Overwrite,
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct TransferResult {
+    transferred: Vec<String>,
+    conflicts: Vec<String>,
+    skipped: Vec<String>,
+}
+
+fn canonical_path(path: &str) -> Option<String> {
+    let path = fs::canonicalize(path).ok()?;
+    Some(platform::simplify_path(&path).to_string_lossy().to_string())
+}
+
+fn git_root(path: &str) -> Option<String> {
+    let repo = Repository::discover(platform::simplify_path(Path::new(path))).ok()?;
+    canonical_path(&repo.workdir()?.to_string_lossy())
+}
+
+// Entries are grouped by git root when there is one, so a repo added once at
+// its root and once at a subdirectory is still reported.
+fn group_duplicates(
+    paths: Vec<(String, Option<String>, Option<String>)>,
+) -> BTreeMap<String, Vec<String>> {
+    let mut groups: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for (id, canonical, root) in paths {
+        if let Some(key) = root.or(canonical) {
+            groups.entry(key).or_default().push(id);
+        }
+    }
+    groups.retain(|_, ids| ids.len() > 1);
+    groups
+}
+
+fn merge_sessions(from_path: &str, to_path: &str) -> Result<(), String> {
+    let source = read_workspace_sessions(&workspace_sessions_path(from_path))?;
+    if source.sessions.is_empty() {
+        return Ok(());
+    }
+    let path = workspace_sessions_path(to_path);
+    let mut store = read_workspace_sessions(&path)?;
+    for (thread_id, metadata) in source.sessions {
+        store.sessions.entry(thread_id).or_insert(metadata);
+    }
+    write_workspace_sessions(&path, &store)
+}
+
+// A thread conflicts when the target already has metadata or a draft for it.
+// Skipped threads stay entirely in the source so nothing is split.
+fn transfer_stores(
+    from_path: &str,
+    to_path: &str,
+    thread_ids: &[String],
+    conflict: TransferConflict,
+) -> Result<TransferResult, String> {
+    let source_sessions_path = workspace_sessions_path(from_path);
+    let target_sessions_path = workspace_sessions_path(to_path);
+    let source_drafts_path = workspace_drafts_path(from_path);
+    let target_drafts_path = workspace_drafts_path(to_path);
+    let mut source_sessions = read_workspace_sessions(&source_sessions_path)?;
+    let mut target_sessions = read_workspace_sessions(&target_sessions_path)?;
+    let mut source_drafts = read_drafts(&source_drafts_path)?;
+    let mut target_drafts = read_drafts(&target_drafts_path)?;
+
+    let mut result = TransferResult::default();
+    for thread_id in thread_ids {
+        let conflicting = target_sessions.sessions.contains_key(thread_id)
+            || target_drafts.drafts.contains_key(thread_id);
+        if conflicting {
+            result.conflicts.push(thread_id.clone());
+            if conflict == TransferConflict::Skip {
+                result.skipped.push(thread_id.clone());
+                continue;
+            }
+        }
+        if let Some(metadata) = source_sessions.sessions.remove(thread_id) {
+            target_sessions.sessions.insert(thread_id.clone(), metadata);
+        }
+        if let Some(draft) = source_drafts.drafts.remove(thread_id) {
+            target_drafts.drafts.insert(thread_id.clone(), draft);
+        }
+        result.transferred.push(thread_id.clone());
+    }
+    if result.transferred.is_empty() {
+        return Ok(result);
+    }
+    write_workspace_sessions(&target_sessions_path, &target_sessions)?;
+    write_drafts(&target_drafts_path, &target_drafts)?;
+    write_workspace_sessions(&source_sessions_path, &source_sessions)?;
+    write_drafts(&source_drafts_path, &source_drafts)?;
+    Ok(result)
+}
+
+#[tauri::command]
+pub(crate) async fn find_duplicate_workspaces(
+    state: State<'_, AppState>,
+) -> Result<Vec<DuplicateWorkspaces>, AppError> {
+    let infos = workspace_infos(&state).await;
+    let paths: Vec<_> = infos
+        .iter()
+        .map(|info| (info.id.clone(), info.path.clone()))
+        .collect();
+    let resolved = tokio::task::spawn_blocking(move || {
+        paths
+            .into_iter()
+            .map(|(id, path)| {
+                let canonical = canonical_path(&path);
+                let root = git_root(&path);
+                (id, canonical, root)
+            })
+            .collect::<Vec<_>>()
+    })
+    .await?;
+    let canonical: BTreeMap<_, _> = resolved
+        .iter()
+        .map(|(id, canonical, _)| (id.clone(), canonical.clone()))
+        .collect();
+    let groups = group_duplicates(resolved);
+    Ok(groups
+        .into_iter()
+        .map(|(key, ids)| {
+            let first = canonical.get(&ids[0]).cloned().flatten();
+            let same_path = ids
+                .iter()
+                .all(|id| canonical.get(id).cloned().flatten() == first);
+            let workspaces: Vec<_> = infos
+                .iter()
+                .filter(|info| ids.contains(&info.id))
+                .cloned()
+                .collect();
+            DuplicateWorkspaces {
+                key,
+                reason: if same_path {
+                    DuplicateReason::Path
+                } else {
+                    DuplicateReason::GitRoot
+                },
+                workspaces,
+            }
+        })
+        .collect())
+}
+
+#[tauri::command]
+pub(crate) async fn merge_workspaces(
+    keep_id: String,
+    remove_id: String,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<WorkspaceInfo, AppError> {
+    if keep_id == remove_id {
+        return Err("Cannot merge a workspace into itself.".into());
+    }
+    let (keep_path, remove_path) = {
+        let workspaces = state.workspaces.lock().await;
+        let keep = workspaces
+            .get(&keep_id)
+            .ok_or(AppError::WorkspaceNotFound)?;
+        let remove = workspaces
+            .get(&remove_id)
+            .ok_or(AppError::WorkspaceNotFound)?;
+        (keep.path.clone(), remove.path.clone())
+    };
+    let (running, approvals) = thread_state::workspace_activity_counts(&state, &remove_id).await;
+    if running + approvals > 0 {
+        return Err(AppError::TurnsRunning {
+            running_turns: running + approvals,
+            message: format!(
+                "{} turn(s) still running in the workspace being merged. Let them finish first.",
+                running + approvals
+            ),
+        });
+    }
+
+    let (keep_canonical, remove_canonical) = {
+        let (keep, remove) = (keep_path.clone(), remove_path.clone());
+        tokio::task::spawn_blocking(move || (canonical_path(&keep), canonical_path(&remove)))
+            .await?
+    };
+    // Entries at the same directory already share one .codexmonitor store.
+    if keep_canonical.is_none() || keep_canonical != remove_canonical {
+        let (from, to) = (remove_path.clone(), keep_path.clone());
+        tokio::task::spawn_blocking(move || merge_sessions(&from, &to)).await??;
+        metrics::merge_workspace_metrics(&state, &remove_path, &keep_path, None).await?;
+    }
+
+    remove_workspace(remove_id, state.clone(), app).await?;
+    workspace_infos(&state)
+        .await
+        .into_iter()
+        .find(|info| info.id == keep_id)
+        .ok_or(AppError::WorkspaceNotFound)
+}
+
+#[tauri::command]
+pub(crate) async fn transfer_thread_metadata(
+    from_workspace: String,
+    to_workspace: String,
+    thread_ids: Vec<String>,
+    conflict: Option<TransferConflict>,
+    state: State<'_, AppState>,
+) -> Result<TransferResult, AppError> {
+    if from_workspace == to_workspace {
+        return Err("Source and target workspace are the same.".into());
+    }
+    let (from_path, to_path) = {
+        let workspaces = state.workspaces.lock().await;
+        let from = workspaces
+            .get(&from_workspace)
+            .ok_or(AppError::WorkspaceNotFound)?;
+        let to = workspaces
+            .get(&to_workspace)
+            .ok_or(AppError::WorkspaceNotFound)?;
+        (from.path.clone(), to.path.clone())
+    };
+    let (from_canonical, to_canonical) = {
+        let (from, to) = (from_path.clone(), to_path.clone());
+        tokio::task::spawn_blocking(move || (canonical_path(&from), canonical_path(&to))).await?
+    };
+    if from_canonical.is_some() && from_canonical == to_canonical {
+        return Ok(TransferResult {
+            transferred: thread_ids,
+            ..TransferResult::default()
+        });
+    }
+
+    let result = {
+        let _guard = state.drafts_lock.lock().await;
+        let (from, to, ids) = (from_path.clone(), to_path.clone(), thread_ids.clone());
+        let conflict = conflict.unwrap_or_default();
+        tokio::task::spawn_blocking(move || transfer_stores(&from, &to, &ids, conflict)).await??
+    };
+    metrics::merge_workspace_metrics(&state, &from_path, &to_path, Some(&result.transferred))
+        .await?;
+    Ok(result)
+}
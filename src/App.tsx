@@ -21,6 +21,7 @@ import "./styles/debug.css";
 import "./styles/settings.css";
 import "./styles/confirm-quit.css";
 import "./styles/codex-path.css";
+import "./styles/storage-lock.css";
 import { Sidebar } from "./components/Sidebar";
 import { Home } from "./components/Home";
 import { MainHeader } from "./components/MainHeader";
@@ -32,6 +33,7 @@ import { GitDiffViewer } from "./components/GitDiffViewer";
 import { DebugPanel } from "./components/DebugPanel";
 import { ConfirmQuitModal } from "./components/ConfirmQuitModal";
 import { CodexPathModal } from "./components/CodexPathModal";
+import { StorageLockBanner } from "./components/StorageLockBanner";
 import { useWorkspaces } from "./hooks/useWorkspaces";
 import { useThreads } from "./hooks/useThreads";
 import { useGitStatus } from "./hooks/useGitStatus";
@@ -46,25 +48,35 @@ import { useWorkspaceRestore } from "./hooks/useWorkspaceRestore";
 import { Settings } from "./components/Settings";
 import { useSettings } from "./hooks/useSettings";
 import { useUsage } from "./hooks/useUsage";
+import { useStorageStatus } from "./hooks/useStorageStatus";
+import { useDeepLinks } from "./hooks/useDeepLinks";
+import { useNotifications } from "./hooks/useNotifications";
+import { useUserActivity } from "./hooks/useUserActivity";
 import {
   confirmQuit,
   pickCodexBinPath,
   pickNodeBinPath,
   inspectCodexBin,
   readPrompt,
+  recordPromptUsage,
   saveAttachment,
   validateCodexBin,
 } from "./services/tauri";
 import { buildPromptSlashItems } from "./utils/slash";
 import { expandPromptTemplate, parsePromptInvocation } from "./utils/prompts";
-import type { AccessMode, ComposerAttachment, UsageSnapshot } from "./types";
+import type {
+  AccessMode,
+  ComposerAttachment,
+  DeepLinkEvent,
+  UsageSnapshot,
+} from "./types";
+import { errorMessage } from "./utils/errors";
 
 type MainAppProps = {
   accessMode: AccessMode;
   onAccessModeChange: (mode: AccessMode) => void;
   sidebarWidth: number;
   onSidebarWidthChange: (width: number) => void;
-  enableCompletionNotifications: boolean;
   usageSnapshot: UsageSnapshot | null;
   workspaceSidebarExpanded: Record<string, boolean>;
   onWorkspaceSidebarExpandedChange: (next: Record<string, boolean>) => void;
@@ -86,7 +98,6 @@ function MainApp({
   onAccessModeChange,
   sidebarWidth: persistedSidebarWidth,
   onSidebarWidthChange,
-  enableCompletionNotifications,
   usageSnapshot,
   workspaceSidebarExpanded,
   onWorkspaceSidebarExpandedChange,
@@ -95,6 +106,10 @@ function MainApp({
   const [centerMode, setCenterMode] = useState<"chat" | "diff">("chat");
   const [selectedDiffPath, setSelectedDiffPath] = useState<string | null>(null);
   const [isConfirmQuitOpen, setIsConfirmQuitOpen] = useState(false);
+  const [composerPrefill, setComposerPrefill] = useState<{
+    id: number;
+    text: string;
+  } | null>(null);
   const [removingWorkspaceIds, setRemovingWorkspaceIds] = useState<Set<string>>(
     () => new Set(),
   );
@@ -208,12 +223,13 @@ function MainApp({
     effort: selectedEffort,
     accessMode,
     onMessageActivity: refreshGitStatus,
-    notifications: {
-      enabled: enableCompletionNotifications,
-      workspaces,
-      onOpenThread: (workspaceId, threadId) => {
-        openThreadRef.current(workspaceId, threadId);
-      },
+  });
+
+  useNotifications({
+    activeWorkspaceId,
+    activeThreadId,
+    onOpenThread: (workspaceId, threadId) => {
+      openThreadRef.current(workspaceId, threadId);
     },
   });
 
@@ -340,7 +356,7 @@ function MainApp({
             timestamp: Date.now(),
             source: "error",
             label: "attachment save error",
-            payload: error instanceof Error ? error.message : String(error),
+            payload: errorMessage(error),
           });
         } finally {
           setPendingAttachmentCount((prev) => Math.max(0, prev - 1));
@@ -401,6 +417,65 @@ function MainApp({
 
   openThreadRef.current = handleSelectThread;
 
+  const handleDeepLink = useCallback(
+    async (event: DeepLinkEvent) => {
+      if (event.kind === "error") {
+        addDebugEntry({
+          id: `${Date.now()}-client-deep-link-error`,
+          timestamp: Date.now(),
+          source: "error",
+          label: "deep-link error",
+          payload: `${event.url}: ${event.message}`,
+        });
+        return;
+      }
+      setCenterMode("chat");
+      setSelectedDiffPath(null);
+      if (event.kind === "openThread") {
+        const workspace = workspaces.find(
+          (entry) => entry.id === event.workspaceId,
+        );
+        if (!workspace) {
+          return;
+        }
+        setActiveWorkspaceId(workspace.id);
+        if (!workspace.connected) {
+          await connectWorkspace(workspace);
+        }
+        if (event.threadId) {
+          setActiveThreadId(event.threadId, workspace.id);
+        }
+        return;
+      }
+      const { workspace, prompt } = event;
+      if (!workspaces.some((entry) => entry.id === workspace.id)) {
+        await refreshWorkspaces();
+      }
+      setActiveWorkspaceId(workspace.id);
+      if (!workspace.connected) {
+        await connectWorkspace(workspace);
+      }
+      setActiveThreadId(null, workspace.id);
+      if (prompt) {
+        setComposerPrefill({ id: Date.now(), text: prompt });
+      }
+    },
+    [
+      addDebugEntry,
+      connectWorkspace,
+      refreshWorkspaces,
+      setActiveThreadId,
+      setActiveWorkspaceId,
+      workspaces,
+    ],
+  );
+
+  useDeepLinks((event) => {
+    void handleDeepLink(event);
+  });
+
+  useUserActivity();
+
   const handleToggleWorkspaceExpanded = useCallback(
     (workspaceId: string) => {
       const current = expandedWorkspaceIds[workspaceId];
@@ -480,18 +555,20 @@ function MainApp({
       return;
     }
     let messageText = trimmed;
+    let usedPrompt: string | null = null;
     const invocation = parsePromptInvocation(messageText);
     if (invocation) {
       try {
         const promptFile = await readPrompt(invocation.name);
         messageText = expandPromptTemplate(promptFile.body, invocation);
+        usedPrompt = invocation.name;
       } catch (error) {
         addDebugEntry({
           id: `${Date.now()}-prompt-expand-error`,
           timestamp: Date.now(),
           source: "error",
           label: "prompt/expand error",
-          payload: error instanceof Error ? error.message : String(error),
+          payload: errorMessage(error),
         });
       }
     }
@@ -500,6 +577,17 @@ function MainApp({
       nextAttachments.map((attachment) => ({ path: attachment.path })),
     );
     clearAttachments();
+    if (usedPrompt) {
+      recordPromptUsage(usedPrompt, activeWorkspace?.id).catch((error) => {
+        addDebugEntry({
+          id: `${Date.now()}-prompt-usage-error`,
+          timestamp: Date.now(),
+          source: "error",
+          label: "prompt/usage error",
+          payload: errorMessage(error),
+        });
+      });
+    }
   }
 
   const handleConfirmQuit = useCallback(async () => {
@@ -512,7 +600,7 @@ function MainApp({
         timestamp: Date.now(),
         source: "error",
         label: "confirm quit error",
-        payload: error instanceof Error ? error.message : String(error),
+        payload: errorMessage(error),
       });
     }
   }, [addDebugEntry]);
@@ -740,6 +828,7 @@ function MainApp({
                 slashItems={slashItems}
                 fileItems={fileItems}
                 onAtQueryChange={setAtQuery}
+                prefill={composerPrefill}
               />
             )}
             <DebugPanel
@@ -766,6 +855,7 @@ function App() {
   const [route, setRoute] = useState(() => window.location.hash);
   const { settings, updateSettings, isLoaded } = useSettings();
   const { snapshot: usageSnapshot } = useUsage(settings);
+  const { status: storageStatus } = useStorageStatus();
   const [codexModalOpen, setCodexModalOpen] = useState(false);
   const [codexModalForced, setCodexModalForced] = useState(false);
   const [codexPathDraft, setCodexPathDraft] = useState("");
@@ -894,7 +984,7 @@ function App() {
           return false;
         }
         setCodexTestStatus("error");
-        setCodexTestMessage(error instanceof Error ? error.message : String(error));
+        setCodexTestMessage(errorMessage(error));
         return false;
       }
     },
@@ -1012,7 +1102,6 @@ function App() {
       onAccessModeChange={(mode) => updateSettings({ accessMode: mode })}
       sidebarWidth={settings.sidebarWidth}
       onSidebarWidthChange={(width) => updateSettings({ sidebarWidth: width })}
-      enableCompletionNotifications={settings.enableCompletionNotifications}
       usageSnapshot={usageSnapshot}
       workspaceSidebarExpanded={settings.workspaceSidebarExpanded}
       onWorkspaceSidebarExpandedChange={(next) =>
@@ -1025,6 +1114,7 @@ function App() {
   return (
     <>
       {content}
+      <StorageLockBanner status={storageStatus} />
       <CodexPathModal
         isOpen={codexModalOpen}
         path={codexPathDraft}
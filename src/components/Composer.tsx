@@ -66,6 +66,7 @@ type ComposerProps = {
   slashItems: SlashItem[];
   fileItems: SlashItem[];
   onAtQueryChange: (query: string | null) => void;
+  prefill?: { id: number; text: string } | null;
 };
 
 export function Composer({
@@ -87,11 +88,21 @@ export function Composer({
   slashItems,
   fileItems,
   onAtQueryChange,
+  prefill = null,
 }: ComposerProps) {
   const [text, setText] = useState("");
   const [completionIndex, setCompletionIndex] = useState(0);
   const [cursorIndex, setCursorIndex] = useState(0);
   const textareaRef = useRef<HTMLTextAreaElement | null>(null);
+
+  useEffect(() => {
+    if (!prefill) {
+      return;
+    }
+    setText(prefill.text);
+    setCursorIndex(prefill.text.length);
+    textareaRef.current?.focus();
+  }, [prefill]);
   const canSend = useMemo(
     () => !disabled && !isSavingAttachments && (!!text.trim() || attachments.length > 0),
     [attachments.length, disabled, isSavingAttachments, text],
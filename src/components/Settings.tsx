@@ -1,4 +1,15 @@
-import type { AppSettings, AccessMode, ThemePreference } from "../types";
+import type {
+  AppSettings,
+  AccessMode,
+  AutoDeferConfig,
+  CompactionMode,
+  DeferAction,
+  NotificationChannels,
+  NotificationConfig,
+  QuitBehavior,
+  SecretScanMode,
+  ThemePreference,
+} from "../types";
 
 type SettingsProps = {
   settings: AppSettings;
@@ -12,11 +23,46 @@ const ACCESS_MODE_LABELS: Record<AccessMode, string> = {
   "full-access": "Full access",
 };
 
+type NotificationEventKey = Exclude<keyof NotificationConfig, "quietHours">;
+
+const NOTIFICATION_EVENTS: { key: NotificationEventKey; label: string }[] = [
+  { key: "turnComplete", label: "Agent finishes a reply" },
+  { key: "approvalNeeded", label: "Approval needed" },
+  { key: "error", label: "Turn fails" },
+  { key: "rateLimit", label: "Usage limit warning" },
+];
+
+const NOTIFICATION_CHANNELS: {
+  key: keyof NotificationChannels;
+  label: string;
+}[] = [
+  { key: "native", label: "Notification" },
+  { key: "sound", label: "Sound" },
+  { key: "badge", label: "Badge" },
+];
+
 export function Settings({
   settings,
   onUpdateSettings,
   onOpenCodexPathModal,
 }: SettingsProps) {
+  const { notifications, approvalAutoDefer } = settings;
+  const updateNotifications = (partial: Partial<NotificationConfig>) =>
+    onUpdateSettings({ notifications: { ...notifications, ...partial } });
+  const updateAutoDefer = (partial: Partial<AutoDeferConfig>) =>
+    onUpdateSettings({
+      approvalAutoDefer: { ...approvalAutoDefer, ...partial },
+    });
+  const updateChannel = (
+    eventKey: NotificationEventKey,
+    channelKey: keyof NotificationChannels,
+    checked: boolean,
+  ) => {
+    const next = { ...notifications };
+    next[eventKey] = { ...notifications[eventKey], [channelKey]: checked };
+    onUpdateSettings({ notifications: next });
+  };
+
   return (
     <div className="settings-shell">
       <header className="settings-header" data-tauri-drag-region>
@@ -255,26 +301,123 @@ export function Settings({
             <div className="settings-help">
               When enabled, quitting the app requires confirmation.
             </div>
+            <div className="settings-field">
+              <label className="settings-label" htmlFor="quit-behavior">
+                Closing the main window
+              </label>
+              <select
+                id="quit-behavior"
+                className="settings-select"
+                value={settings.quitBehavior}
+                onChange={(event) =>
+                  onUpdateSettings({
+                    quitBehavior: event.target.value as QuitBehavior,
+                  })
+                }
+              >
+                <option value="quit">Quits the app</option>
+                <option value="keepRunning">Hides to tray, agents keep running</option>
+              </select>
+            </div>
+            <div className="settings-help">
+              In tray mode, sessions, usage polling, and notifications stay active.
+              Use "Quit and Stop Agents" from the tray menu to shut everything down.
+            </div>
           </div>
           <div className="settings-card">
             <div className="settings-card-title">Notifications</div>
+            <table className="settings-notification-matrix">
+              <thead>
+                <tr>
+                  <th scope="col">Event</th>
+                  {NOTIFICATION_CHANNELS.map((channel) => (
+                    <th key={channel.key} scope="col">
+                      {channel.label}
+                    </th>
+                  ))}
+                </tr>
+              </thead>
+              <tbody>
+                {NOTIFICATION_EVENTS.map((event) => (
+                  <tr key={event.key}>
+                    <th scope="row">{event.label}</th>
+                    {NOTIFICATION_CHANNELS.map((channel) => (
+                      <td key={channel.key}>
+                        <input
+                          type="checkbox"
+                          aria-label={`${event.label}: ${channel.label}`}
+                          checked={notifications[event.key][channel.key]}
+                          onChange={(change) =>
+                            updateChannel(
+                              event.key,
+                              channel.key,
+                              change.target.checked,
+                            )
+                          }
+                        />
+                      </td>
+                    ))}
+                  </tr>
+                ))}
+              </tbody>
+            </table>
+            <div className="settings-help">
+              Clicking a notification opens the related thread. The app badge
+              counts running turns and pending approvals for the events that
+              have Badge enabled.
+            </div>
             <div className="settings-toggle">
               <input
-                id="completion-notifications"
+                id="quiet-hours-enabled"
                 type="checkbox"
-                checked={settings.enableCompletionNotifications}
+                checked={notifications.quietHours.enabled}
                 onChange={(event) =>
-                  onUpdateSettings({
-                    enableCompletionNotifications: event.target.checked,
+                  updateNotifications({
+                    quietHours: {
+                      ...notifications.quietHours,
+                      enabled: event.target.checked,
+                    },
                   })
                 }
               />
-              <label htmlFor="completion-notifications">
-                Notify when an agent finishes a reply
-              </label>
+              <label htmlFor="quiet-hours-enabled">Quiet hours</label>
+            </div>
+            <div className="settings-inline settings-quiet-hours">
+              <input
+                className="settings-select"
+                type="time"
+                aria-label="Quiet hours start"
+                value={notifications.quietHours.start}
+                onChange={(event) =>
+                  updateNotifications({
+                    quietHours: {
+                      ...notifications.quietHours,
+                      start: event.target.value,
+                    },
+                  })
+                }
+                disabled={!notifications.quietHours.enabled}
+              />
+              <span>to</span>
+              <input
+                className="settings-select"
+                type="time"
+                aria-label="Quiet hours end"
+                value={notifications.quietHours.end}
+                onChange={(event) =>
+                  updateNotifications({
+                    quietHours: {
+                      ...notifications.quietHours,
+                      end: event.target.value,
+                    },
+                  })
+                }
+                disabled={!notifications.quietHours.enabled}
+              />
             </div>
             <div className="settings-help">
-              Clicking the notification opens the related thread.
+              During quiet hours, notifications and sounds are held back; badges
+              still update.
             </div>
           </div>
           <div className="settings-card">
@@ -324,6 +467,68 @@ export function Settings({
               Controls how often the app refreshes usage data for the sidebar.
             </div>
           </div>
+          <div className="settings-card">
+            <div className="settings-card-title">Approvals while away</div>
+            <div className="settings-toggle">
+              <input
+                id="approval-auto-defer"
+                type="checkbox"
+                checked={approvalAutoDefer.enabled}
+                onChange={(event) =>
+                  updateAutoDefer({ enabled: event.target.checked })
+                }
+              />
+              <label htmlFor="approval-auto-defer">
+                Answer approval requests automatically when idle
+              </label>
+            </div>
+            <div className="settings-field">
+              <label className="settings-label" htmlFor="approval-idle-minutes">
+                Idle time before answering (minutes)
+              </label>
+              <input
+                id="approval-idle-minutes"
+                className="settings-select"
+                type="number"
+                min="1"
+                max="720"
+                step="1"
+                value={approvalAutoDefer.idleMinutes}
+                onChange={(event) => {
+                  const value = Number(event.target.value);
+                  if (!Number.isFinite(value)) {
+                    return;
+                  }
+                  const next = Math.max(1, Math.min(720, Math.round(value)));
+                  updateAutoDefer({ idleMinutes: next });
+                }}
+                disabled={!approvalAutoDefer.enabled}
+              />
+            </div>
+            <div className="settings-field">
+              <label className="settings-label" htmlFor="approval-defer-action">
+                Response
+              </label>
+              <select
+                id="approval-defer-action"
+                className="settings-select"
+                value={approvalAutoDefer.action}
+                onChange={(event) =>
+                  updateAutoDefer({
+                    action: event.target.value as DeferAction,
+                  })
+                }
+                disabled={!approvalAutoDefer.enabled}
+              >
+                <option value="defer">Defer (stop the turn)</option>
+                <option value="deny">Deny (let the agent continue)</option>
+              </select>
+            </div>
+            <div className="settings-help">
+              Answered requests are kept for review so you can approve them
+              later.
+            </div>
+          </div>
           <div className="settings-card">
             <div className="settings-card-title">Advanced runtime flags</div>
             <div className="settings-toggle">
@@ -360,6 +565,179 @@ export function Settings({
               Changes apply to new app-server sessions only.
             </div>
           </div>
+          <div className="settings-card">
+            <div className="settings-card-title">Streaming</div>
+            <div className="settings-field">
+              <label className="settings-label" htmlFor="event-batch-window">
+                Batch streamed output (ms)
+              </label>
+              <input
+                id="event-batch-window"
+                className="settings-select"
+                type="number"
+                min="0"
+                max="250"
+                step="1"
+                value={settings.eventBatchWindowMs}
+                onChange={(event) => {
+                  const value = Number(event.target.value);
+                  if (!Number.isFinite(value)) {
+                    return;
+                  }
+                  const next = Math.max(0, Math.min(250, Math.round(value)));
+                  onUpdateSettings({ eventBatchWindowMs: next });
+                }}
+              />
+            </div>
+            <div className="settings-help">
+              Merges token-by-token updates that arrive within this window
+              before sending them to the UI. Set to 0 to disable batching.
+            </div>
+          </div>
+          <div className="settings-card">
+            <div className="settings-card-title">Context compaction</div>
+            <div className="settings-field">
+              <label className="settings-label" htmlFor="compaction-mode">
+                When context runs low
+              </label>
+              <select
+                id="compaction-mode"
+                className="settings-select"
+                value={settings.contextCompaction.mode}
+                onChange={(event) =>
+                  onUpdateSettings({
+                    contextCompaction: {
+                      ...settings.contextCompaction,
+                      mode: event.target.value as CompactionMode,
+                    },
+                  })
+                }
+              >
+                <option value="off">Do nothing</option>
+                <option value="offer">Suggest compaction</option>
+                <option value="auto">Compact automatically</option>
+              </select>
+            </div>
+            <div className="settings-field">
+              <label className="settings-label" htmlFor="compaction-threshold">
+                Threshold (% of context window)
+              </label>
+              <input
+                id="compaction-threshold"
+                className="settings-select"
+                type="number"
+                min="10"
+                max="100"
+                step="1"
+                value={settings.contextCompaction.thresholdPercent}
+                onChange={(event) => {
+                  const value = Number(event.target.value);
+                  if (!Number.isFinite(value)) {
+                    return;
+                  }
+                  onUpdateSettings({
+                    contextCompaction: {
+                      ...settings.contextCompaction,
+                      thresholdPercent: Math.max(10, Math.min(100, Math.round(value))),
+                    },
+                  });
+                }}
+              />
+            </div>
+            <div className="settings-help">
+              Automatic compaction runs once the current turn finishes.
+            </div>
+          </div>
+          <div className="settings-card">
+            <div className="settings-card-title">Thread titles</div>
+            <div className="settings-toggle">
+              <input
+                id="thread-titles-enabled"
+                type="checkbox"
+                checked={settings.threadTitles.enabled}
+                onChange={(event) =>
+                  onUpdateSettings({
+                    threadTitles: {
+                      ...settings.threadTitles,
+                      enabled: event.target.checked,
+                    },
+                  })
+                }
+              />
+              <label htmlFor="thread-titles-enabled">
+                Generate titles after the first turn
+              </label>
+            </div>
+            <div className="settings-field">
+              <label className="settings-label" htmlFor="thread-titles-model">
+                Title model
+              </label>
+              <input
+                id="thread-titles-model"
+                className="settings-select"
+                type="text"
+                placeholder="Session default"
+                value={settings.threadTitles.model ?? ""}
+                onChange={(event) => {
+                  const model = event.target.value.trim();
+                  onUpdateSettings({
+                    threadTitles: {
+                      ...settings.threadTitles,
+                      model: model ? model : null,
+                    },
+                  });
+                }}
+              />
+            </div>
+            <div className="settings-help">
+              A small, fast model keeps titles cheap. Renamed threads are left alone.
+            </div>
+          </div>
+          <div className="settings-card">
+            <div className="settings-card-title">Secret scanning</div>
+            <div className="settings-field">
+              <label className="settings-label" htmlFor="secret-scan-mode">
+                When a message looks like it contains a secret
+              </label>
+              <select
+                id="secret-scan-mode"
+                className="settings-select"
+                value={settings.secretScanning.mode}
+                onChange={(event) =>
+                  onUpdateSettings({
+                    secretScanning: {
+                      ...settings.secretScanning,
+                      mode: event.target.value as SecretScanMode,
+                    },
+                  })
+                }
+              >
+                <option value="off">Don't scan</option>
+                <option value="block">Block and warn</option>
+                <option value="redact">Redact automatically</option>
+              </select>
+            </div>
+            <div className="settings-toggle">
+              <input
+                id="secret-scan-attachments"
+                type="checkbox"
+                checked={settings.secretScanning.scanAttachments}
+                onChange={(event) =>
+                  onUpdateSettings({
+                    secretScanning: {
+                      ...settings.secretScanning,
+                      scanAttachments: event.target.checked,
+                    },
+                  })
+                }
+              />
+              <label htmlFor="secret-scan-attachments">Scan text attachments</label>
+            </div>
+            <div className="settings-help">
+              Looks for AWS keys, private keys, and common API tokens. Allowlisted
+              values are stored per workspace.
+            </div>
+          </div>
         </main>
       </div>
     </div>
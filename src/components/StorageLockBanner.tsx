@@ -0,0 +1,20 @@
+import type { StorageStatus } from "../types";
+
+type StorageLockBannerProps = {
+  status: StorageStatus | null;
+};
+
+export function StorageLockBanner({ status }: StorageLockBannerProps) {
+  if (!status?.error) {
+    return null;
+  }
+
+  return (
+    <div className="storage-lock-banner" role="alert">
+      <div className="storage-lock-title">Read-only mode</div>
+      <div className="storage-lock-body">
+        {status.error}. Changes made in this window won't be saved.
+      </div>
+    </div>
+  );
+}
@@ -1,5 +1,6 @@
 import { useEffect } from "react";
 import { listen } from "@tauri-apps/api/event";
+import { readEventBlob } from "../services/tauri";
 import type { AppServerEvent, ApprovalRequest } from "../types";
 
 type AgentDelta = {
@@ -34,14 +35,30 @@ type AppServerEventHandlers = {
   onTurnDiffUpdated?: (workspaceId: string, threadId: string, diff: string) => void;
 };
 
+async function resolvePayload(payload: AppServerEvent): Promise<AppServerEvent | null> {
+  if (payload.message.type !== "blob" || typeof payload.message.path !== "string") {
+    return payload;
+  }
+  try {
+    const message = await readEventBlob(payload.message.path);
+    return { ...payload, message };
+  } catch (error) {
+    console.error("Failed to read event payload from disk", error);
+    return null;
+  }
+}
+
 export function useAppServerEvents(handlers: AppServerEventHandlers) {
   useEffect(() => {
     let unlisten: (() => void) | null = null;
     let canceled = false;
-    listen<AppServerEvent>("app-server-event", (event) => {
-      handlers.onAppServerEvent?.(event.payload);
+    // Events spilled to disk are resolved asynchronously, so everything goes
+    // through one chain to keep delivery in arrival order.
+    let queue: Promise<void> = Promise.resolve();
+    const handle = (payload: AppServerEvent) => {
+      handlers.onAppServerEvent?.(payload);
 
-      const { workspace_id, message } = event.payload;
+      const { workspace_id, message } = payload;
       const method = String(message.method ?? "");
 
       if (method === "codex/connected") {
@@ -190,6 +207,15 @@ export function useAppServerEvents(handlers: AppServerEventHandlers) {
         }
         return;
       }
+    };
+
+    listen<AppServerEvent>("app-server-event", (event) => {
+      queue = queue.then(async () => {
+        const payload = await resolvePayload(event.payload);
+        if (payload && !canceled) {
+          handle(payload);
+        }
+      });
     }).then((handler) => {
       if (canceled) {
         try {
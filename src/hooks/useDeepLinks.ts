@@ -0,0 +1,31 @@
+import { useEffect, useRef } from "react";
+import { listen } from "@tauri-apps/api/event";
+import { takePendingDeepLinks } from "../services/tauri";
+import type { DeepLinkEvent } from "../types";
+
+export function useDeepLinks(onDeepLink: (event: DeepLinkEvent) => void) {
+  const handlerRef = useRef(onDeepLink);
+
+  useEffect(() => {
+    handlerRef.current = onDeepLink;
+  }, [onDeepLink]);
+
+  useEffect(() => {
+    let mounted = true;
+    const subscription = listen<DeepLinkEvent>("deep-link", (event) => {
+      handlerRef.current(event.payload);
+    });
+    subscription
+      .then(() => takePendingDeepLinks())
+      .then((pending) => {
+        if (mounted) {
+          pending.forEach((event) => handlerRef.current(event));
+        }
+      })
+      .catch(() => undefined);
+    return () => {
+      mounted = false;
+      subscription.then((unlisten) => unlisten());
+    };
+  }, []);
+}
@@ -1,6 +1,7 @@
 import { useEffect, useRef, useState } from "react";
 import type { DebugEntry } from "../types";
 import { searchFiles } from "../services/tauri";
+import { errorMessage } from "../utils/errors";
 
 type UseFileSearchOptions = {
   workspaceId: string | null;
@@ -50,7 +51,7 @@ export function useFileSearch({
           return;
         }
         const normalized = Array.isArray(response)
-          ? response.map((value) => String(value)).filter(Boolean)
+          ? response.map((match) => String(match.path)).filter(Boolean)
           : [];
         setItems(normalized);
         onDebug?.({
@@ -69,7 +70,7 @@ export function useFileSearch({
           timestamp: Date.now(),
           source: "error",
           label: "file/search error",
-          payload: error instanceof Error ? error.message : String(error),
+          payload: errorMessage(error),
         });
         setItems([]);
       } finally {
@@ -1,6 +1,7 @@
 import { useCallback, useEffect, useMemo, useState } from "react";
 import type { GitFileDiff, GitFileStatus, WorkspaceInfo } from "../types";
 import { getGitDiffs } from "../services/tauri";
+import { errorMessage } from "../utils/errors";
 
 type GitDiffState = {
   diffs: GitFileDiff[];
@@ -47,7 +48,7 @@ export function useGitDiffs(
       setState({
         diffs: [],
         isLoading: false,
-        error: error instanceof Error ? error.message : String(error),
+        error: errorMessage(error),
       });
     }
   }, [activeWorkspace]);
@@ -1,6 +1,7 @@
 import { useCallback, useEffect, useState } from "react";
 import type { GitFileStatus, WorkspaceInfo } from "../types";
 import { getGitStatus } from "../services/tauri";
+import { errorMessage } from "../utils/errors";
 
 type GitStatusState = {
   branchName: string;
@@ -35,7 +36,7 @@ export function useGitStatus(activeWorkspace: WorkspaceInfo | null) {
         setStatus({
           ...emptyStatus,
           branchName: "unknown",
-          error: err instanceof Error ? err.message : String(err),
+          error: errorMessage(err),
         });
       });
   }, [activeWorkspace]);
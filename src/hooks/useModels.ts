@@ -1,6 +1,7 @@
 import { useCallback, useEffect, useMemo, useRef, useState } from "react";
 import type { DebugEntry, ModelOption, WorkspaceInfo } from "../types";
 import { getModelList } from "../services/tauri";
+import { errorMessage } from "../utils/errors";
 
 type UseModelsOptions = {
   activeWorkspace: WorkspaceInfo | null;
@@ -92,7 +93,7 @@ export function useModels({ activeWorkspace, onDebug }: UseModelsOptions) {
         timestamp: Date.now(),
         source: "error",
         label: "model/list error",
-        payload: error instanceof Error ? error.message : String(error),
+        payload: errorMessage(error),
       });
     } finally {
       inFlight.current = false;
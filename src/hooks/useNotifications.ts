@@ -0,0 +1,154 @@
+import { useCallback, useEffect, useRef } from "react";
+import { listen } from "@tauri-apps/api/event";
+import { getCurrentWindow } from "@tauri-apps/api/window";
+import {
+  isPermissionGranted,
+  requestPermission,
+} from "@tauri-apps/plugin-notification";
+import type { NotificationDispatch } from "../types";
+
+type UseNotificationsOptions = {
+  activeWorkspaceId: string | null;
+  activeThreadId: string | null;
+  onOpenThread: (workspaceId: string, threadId: string) => void;
+};
+
+function playChime() {
+  try {
+    const context = new AudioContext();
+    const oscillator = context.createOscillator();
+    const gain = context.createGain();
+    oscillator.type = "sine";
+    oscillator.frequency.value = 880;
+    gain.gain.setValueAtTime(0.15, context.currentTime);
+    gain.gain.exponentialRampToValueAtTime(0.001, context.currentTime + 0.4);
+    oscillator.connect(gain);
+    gain.connect(context.destination);
+    oscillator.start();
+    oscillator.stop(context.currentTime + 0.4);
+    oscillator.onended = () => {
+      void context.close();
+    };
+  } catch {
+    // Ignore audio errors.
+  }
+}
+
+export function useNotifications({
+  activeWorkspaceId,
+  activeThreadId,
+  onOpenThread,
+}: UseNotificationsOptions) {
+  const activeRef = useRef({ activeWorkspaceId, activeThreadId });
+  const onOpenThreadRef = useRef(onOpenThread);
+  const notificationPermissionRef = useRef<"unknown" | "granted" | "denied">(
+    "unknown",
+  );
+
+  useEffect(() => {
+    activeRef.current = { activeWorkspaceId, activeThreadId };
+  }, [activeThreadId, activeWorkspaceId]);
+
+  useEffect(() => {
+    onOpenThreadRef.current = onOpenThread;
+  }, [onOpenThread]);
+
+  const ensureNotificationPermission = useCallback(async () => {
+    if (typeof window === "undefined" || !("Notification" in window)) {
+      return false;
+    }
+    const cached = notificationPermissionRef.current;
+    if (cached === "granted") {
+      return true;
+    }
+    if (cached === "denied") {
+      return false;
+    }
+    try {
+      const granted = await isPermissionGranted();
+      if (granted) {
+        notificationPermissionRef.current = "granted";
+        return true;
+      }
+      const permission = await requestPermission();
+      const allowed = permission === "granted";
+      notificationPermissionRef.current = allowed ? "granted" : "denied";
+      return allowed;
+    } catch {
+      return false;
+    }
+  }, []);
+
+  const handleDispatch = useCallback(
+    async (payload: NotificationDispatch) => {
+      const { workspaceId, threadId } = payload;
+      if (typeof document !== "undefined" && workspaceId && threadId) {
+        const { activeWorkspaceId, activeThreadId } = activeRef.current;
+        const isActiveThread =
+          workspaceId === activeWorkspaceId && threadId === activeThreadId;
+        const isForeground =
+          document.visibilityState === "visible" && document.hasFocus();
+        if (isActiveThread && isForeground) {
+          return;
+        }
+      }
+      if (!payload.native) {
+        if (payload.sound) {
+          playChime();
+        }
+        return;
+      }
+      const allowed = await ensureNotificationPermission();
+      if (!allowed) {
+        if (payload.sound) {
+          playChime();
+        }
+        return;
+      }
+      try {
+        const notification = new window.Notification(payload.title, {
+          body: payload.body,
+          tag: `${payload.event}:${workspaceId ?? ""}:${threadId ?? ""}`,
+          silent: !payload.sound,
+        });
+        notification.onclick = () => {
+          try {
+            notification.close?.();
+          } catch {
+            // Ignore close errors.
+          }
+          try {
+            const windowHandle = getCurrentWindow();
+            void windowHandle.show();
+            void windowHandle.setFocus();
+          } catch {
+            // Ignore focus errors.
+          }
+          if (!workspaceId || !threadId) {
+            return;
+          }
+          try {
+            onOpenThreadRef.current(workspaceId, threadId);
+          } catch {
+            // Ignore open errors.
+          }
+        };
+      } catch {
+        // Ignore notification errors.
+      }
+    },
+    [ensureNotificationPermission],
+  );
+
+  useEffect(() => {
+    const subscription = listen<NotificationDispatch>(
+      "notification",
+      (event) => {
+        void handleDispatch(event.payload);
+      },
+    );
+    return () => {
+      subscription.then((unlisten) => unlisten());
+    };
+  }, [handleDispatch]);
+}
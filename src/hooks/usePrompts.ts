@@ -1,6 +1,7 @@
 import { useCallback, useEffect, useRef, useState } from "react";
 import type { DebugEntry, PromptOption } from "../types";
 import { getPromptsList } from "../services/tauri";
+import { errorMessage } from "../utils/errors";
 
 type UsePromptsOptions = {
   onDebug?: (entry: DebugEntry) => void;
@@ -59,7 +60,7 @@ export function usePrompts({ onDebug, enabled = true }: UsePromptsOptions) {
         timestamp: Date.now(),
         source: "error",
         label: "prompts/list error",
-        payload: error instanceof Error ? error.message : String(error),
+        payload: errorMessage(error),
       });
     } finally {
       inFlight.current = false;
@@ -9,9 +9,17 @@ const DEFAULT_SETTINGS: AppSettings = {
   bypassApprovalsAndSandbox: false,
   enableWebSearchRequest: false,
   confirmBeforeQuit: false,
-  enableCompletionNotifications: false,
+  quitBehavior: "quit",
   usagePollingEnabled: true,
   usagePollingIntervalMinutes: 5,
+  rateLimitAlertsEnabled: true,
+  rateLimitWarningPercent: 80,
+  diffMaxBytes: 262144,
+  gitHostingToken: null,
+  turnCheckpointsEnabled: false,
+  eventBatchWindowMs: 16,
+  hooks: [],
+  logLevel: "info",
   sidebarWidth: 280,
   glassBlurLight: 32,
   glassBlurDark: 32,
@@ -19,7 +27,34 @@ const DEFAULT_SETTINGS: AppSettings = {
   glassOpacityDark: 1,
   codexBinPath: null,
   nodeBinPath: null,
+  loginShellEnvEnabled: false,
+  notifications: {
+    turnComplete: { native: false, sound: false, badge: false },
+    approvalNeeded: { native: false, sound: false, badge: true },
+    error: { native: false, sound: false, badge: false },
+    rateLimit: { native: false, sound: false, badge: false },
+    quietHours: { enabled: false, start: "22:00", end: "07:00" },
+    speech: { voice: null, onlyWhenUnfocused: true },
+  },
+  approvalAutoDefer: { enabled: false, idleMinutes: 30, action: "defer" },
+  contextCompaction: { mode: "offer", thresholdPercent: 85 },
+  threadTitles: { enabled: false, model: null },
+  secretScanning: { mode: "off", scanAttachments: true },
   workspaceSidebarExpanded: {},
+  keymap: {},
+  locale: null,
+  updateChannel: "stable",
+  crashReportingEnabled: false,
+  localAnalyticsEnabled: false,
+  approvalCommandAllowlist: [],
+  attachmentMaxDimension: 2048,
+  transcriptionCommand: null,
+  httpApi: {
+    enabled: false,
+    port: 4870,
+    token: null,
+    websocketEnabled: false,
+  },
 };
 
 function resolveTheme(preference: ThemePreference, prefersDark: boolean) {
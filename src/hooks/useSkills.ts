@@ -1,6 +1,7 @@
 import { useCallback, useEffect, useMemo, useRef, useState } from "react";
 import type { DebugEntry, SkillOption, WorkspaceInfo } from "../types";
 import { getSkillsList } from "../services/tauri";
+import { errorMessage } from "../utils/errors";
 
 type UseSkillsOptions = {
   activeWorkspace: WorkspaceInfo | null;
@@ -50,6 +51,7 @@ export function useSkills({ activeWorkspace, onDebug }: UseSkillsOptions) {
         name: String(item.name ?? ""),
         path: String(item.path ?? ""),
         description: item.description ? String(item.description) : undefined,
+        enabled: item.enabled !== false,
       }));
       setSkills(data);
       lastFetchedWorkspaceId.current = workspaceId;
@@ -59,7 +61,7 @@ export function useSkills({ activeWorkspace, onDebug }: UseSkillsOptions) {
         timestamp: Date.now(),
         source: "error",
         label: "skills/list error",
-        payload: error instanceof Error ? error.message : String(error),
+        payload: errorMessage(error),
       });
     } finally {
       inFlight.current = false;
@@ -77,7 +79,7 @@ export function useSkills({ activeWorkspace, onDebug }: UseSkillsOptions) {
   }, [isConnected, refreshSkills, skills.length, workspaceId]);
 
   const skillOptions = useMemo(
-    () => skills.filter((skill) => skill.name),
+    () => skills.filter((skill) => skill.name && skill.enabled !== false),
     [skills],
   );
 
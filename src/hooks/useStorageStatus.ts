@@ -0,0 +1,33 @@
+import { useEffect, useState } from "react";
+import { listen } from "@tauri-apps/api/event";
+import { getStorageStatus } from "../services/tauri";
+import type { StorageStatus } from "../types";
+
+export function useStorageStatus() {
+  const [status, setStatus] = useState<StorageStatus | null>(null);
+
+  useEffect(() => {
+    let mounted = true;
+    getStorageStatus()
+      .then((data) => {
+        if (mounted) {
+          setStatus(data);
+        }
+      })
+      .catch(() => undefined);
+    return () => {
+      mounted = false;
+    };
+  }, []);
+
+  useEffect(() => {
+    const subscription = listen<StorageStatus>("storage-locked", (event) => {
+      setStatus(event.payload);
+    });
+    return () => {
+      subscription.then((unlisten) => unlisten());
+    };
+  }, []);
+
+  return { status };
+}
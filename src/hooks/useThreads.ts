@@ -1,17 +1,15 @@
 import { useCallback, useEffect, useMemo, useReducer, useRef } from "react";
-import { getCurrentWindow } from "@tauri-apps/api/window";
-import {
-  isPermissionGranted,
-  requestPermission,
-} from "@tauri-apps/plugin-notification";
+import { listen } from "@tauri-apps/api/event";
 import type {
   ApprovalRequest,
   AppServerEvent,
   ConversationItem,
   DebugEntry,
+  DeferredApproval,
   LocalImageInput,
   SessionMetadata,
   SessionNameSource,
+  ThreadRenamedEvent,
   ThreadSummary,
   WorkspaceSessionStore,
   WorkspaceInfo,
@@ -28,6 +26,7 @@ import {
   saveWorkspaceSessions,
 } from "../services/tauri";
 import { useAppServerEvents } from "./useAppServerEvents";
+import { errorMessage } from "../utils/errors";
 
 const emptyItems: Record<string, ConversationItem[]> = {};
 const DEFAULT_SESSION_STORE_VERSION = 1;
@@ -499,11 +498,6 @@ type UseThreadsOptions = {
   effort?: string | null;
   accessMode?: "read-only" | "current" | "full-access";
   onMessageActivity?: () => void;
-  notifications?: {
-    enabled: boolean;
-    workspaces: WorkspaceInfo[];
-    onOpenThread: (workspaceId: string, threadId: string) => void;
-  };
 };
 
 function asString(value: unknown) {
@@ -807,14 +801,6 @@ function previewThreadName(text: string, fallback: string) {
   return trimmed.length > 38 ? `${trimmed.slice(0, 38)}…` : trimmed;
 }
 
-function formatNotificationBody(text: string, limit = 160) {
-  const compact = text.replace(/\s+/g, " ").trim();
-  if (!compact) {
-    return "Agent finished a reply.";
-  }
-  return compact.length > limit ? `${compact.slice(0, limit)}…` : compact;
-}
-
 export function useThreads({
   activeWorkspace,
   onWorkspaceConnected,
@@ -823,7 +809,6 @@ export function useThreads({
   effort,
   accessMode,
   onMessageActivity,
-  notifications,
 }: UseThreadsOptions) {
   const [state, dispatch] = useReducer(threadReducer, initialState);
   const loadedThreads = useRef<Record<string, boolean>>({});
@@ -848,101 +833,6 @@ export function useThreads({
     [activeThreadId, state.itemsByThread],
   );
 
-  const notificationPermissionRef = useRef<"unknown" | "granted" | "denied">(
-    "unknown",
-  );
-
-  const ensureNotificationPermission = useCallback(async () => {
-    if (typeof window === "undefined" || !("Notification" in window)) {
-      return false;
-    }
-    const cached = notificationPermissionRef.current;
-    if (cached === "granted") {
-      return true;
-    }
-    if (cached === "denied") {
-      return false;
-    }
-    try {
-      const granted = await isPermissionGranted();
-      if (granted) {
-        notificationPermissionRef.current = "granted";
-        return true;
-      }
-      const permission = await requestPermission();
-      const allowed = permission === "granted";
-      notificationPermissionRef.current = allowed ? "granted" : "denied";
-      return allowed;
-    } catch {
-      return false;
-    }
-  }, []);
-
-  const notifyAgentCompletion = useCallback(
-    async (workspaceId: string, threadId: string, text: string) => {
-      const notificationConfig = notifications;
-      if (!notificationConfig?.enabled) {
-        return;
-      }
-      if (typeof document !== "undefined") {
-        const isActiveThread =
-          workspaceId === activeWorkspaceId && threadId === activeThreadId;
-        const isForeground =
-          document.visibilityState === "visible" && document.hasFocus();
-        if (isActiveThread && isForeground) {
-          return;
-        }
-      }
-      const allowed = await ensureNotificationPermission();
-      if (!allowed) {
-        return;
-      }
-      const workspaceName =
-        notificationConfig.workspaces.find(
-          (workspace) => workspace.id === workspaceId,
-        )?.name ?? "Workspace";
-      const threadName =
-        threadsByWorkspaceRef.current[workspaceId]?.find(
-          (thread) => thread.id === threadId,
-        )?.name ?? "Agent";
-      const title = `${workspaceName} · ${threadName}`;
-      const body = formatNotificationBody(text);
-      try {
-        const notification = new window.Notification(title, {
-          body,
-          tag: `${workspaceId}:${threadId}`,
-        });
-        notification.onclick = () => {
-          try {
-            notification.close?.();
-          } catch {
-            // Ignore close errors.
-          }
-          try {
-            const windowHandle = getCurrentWindow();
-            void windowHandle.show();
-            void windowHandle.setFocus();
-          } catch {
-            // Ignore focus errors.
-          }
-          try {
-            notificationConfig.onOpenThread(workspaceId, threadId);
-          } catch {
-            // Ignore open errors.
-          }
-        };
-      } catch {
-        // Ignore notification errors.
-      }
-    },
-    [
-      activeThreadId,
-      activeWorkspaceId,
-      ensureNotificationPermission,
-      notifications,
-    ],
-  );
-
   const logSessionError = useCallback(
     (label: string, error: unknown) => {
       onDebug?.({
@@ -950,7 +840,7 @@ export function useThreads({
         timestamp: Date.now(),
         source: "error",
         label: `session/${label} error`,
-        payload: error instanceof Error ? error.message : String(error),
+        payload: errorMessage(error),
       });
     },
     [onDebug],
@@ -1027,7 +917,7 @@ export function useThreads({
       }
       const store = await getSessionStore(workspaceId);
       const existing = store.sessions[threadId];
-      if (existing?.nameSource === "custom") {
+      if (existing?.nameSource === "custom" || existing?.autoTitled) {
         return;
       }
       const archived = existing?.archived ?? false;
@@ -1117,11 +1007,6 @@ export function useThreads({
         if (threadId !== activeThreadId) {
           dispatch({ type: "markUnread", threadId, hasUnread: true });
         }
-        try {
-          void notifyAgentCompletion(workspaceId, threadId, text);
-        } catch {
-          // Ignore notification errors.
-        }
       },
       onItemStarted: (
         workspaceId: string,
@@ -1235,9 +1120,7 @@ export function useThreads({
     }),
     [
       activeThreadId,
-      activeWorkspaceId,
       handleWorkspaceConnected,
-      notifyAgentCompletion,
       onDebug,
       onMessageActivity,
     ],
@@ -1245,6 +1128,39 @@ export function useThreads({
 
   useAppServerEvents(handlers);
 
+  useEffect(() => {
+    const subscription = listen<DeferredApproval>(
+      "approval-deferred",
+      (event) => {
+        dispatch({ type: "removeApproval", requestId: event.payload.requestId });
+      },
+    );
+    return () => {
+      subscription.then((unlisten) => unlisten());
+    };
+  }, []);
+
+  useEffect(() => {
+    const subscription = listen<ThreadRenamedEvent>("thread-renamed", (event) => {
+      const { workspaceId, threadId, name, nameSource } = event.payload;
+      const store = sessionStoreByWorkspaceRef.current[workspaceId];
+      if (store) {
+        const existing = store.sessions[threadId];
+        store.sessions[threadId] = {
+          ...existing,
+          name,
+          archived: existing?.archived ?? false,
+          nameSource,
+          autoTitled: true,
+        };
+      }
+      dispatch({ type: "setThreadName", workspaceId, threadId, name });
+    });
+    return () => {
+      subscription.then((unlisten) => unlisten());
+    };
+  }, []);
+
   const startThreadForWorkspace = useCallback(
     async (workspaceId: string) => {
       onDebug?.({
@@ -1281,7 +1197,7 @@ export function useThreads({
           timestamp: Date.now(),
           source: "error",
           label: "thread/start error",
-          payload: error instanceof Error ? error.message : String(error),
+          payload: errorMessage(error),
         });
         throw error;
       }
@@ -1346,7 +1262,7 @@ export function useThreads({
           timestamp: Date.now(),
           source: "error",
           label: "thread/resume error",
-          payload: error instanceof Error ? error.message : String(error),
+          payload: errorMessage(error),
         });
         return null;
       }
@@ -1408,7 +1324,7 @@ export function useThreads({
             const hasPreview = preview.length > 0;
 
             let name = existing?.name ?? "";
-            if (nameSource === "custom") {
+            if (nameSource === "custom" || existing?.autoTitled) {
               if (!name) {
                 name = fallbackName;
               }
@@ -1430,7 +1346,7 @@ export function useThreads({
               existing.archived !== archived ||
               existing.nameSource !== nameSource
             ) {
-              store.sessions[threadId] = { name, archived, nameSource };
+              store.sessions[threadId] = { ...existing, name, archived, nameSource };
               hasSessionUpdates = true;
             }
             return { id: threadId, name, archived };
@@ -1458,7 +1374,7 @@ export function useThreads({
           timestamp: Date.now(),
           source: "error",
           label: "thread/list error",
-          payload: error instanceof Error ? error.message : String(error),
+          payload: errorMessage(error),
         });
       }
     },
@@ -1540,7 +1456,7 @@ export function useThreads({
           timestamp: Date.now(),
           source: "error",
           label: "turn/start error",
-          payload: error instanceof Error ? error.message : String(error),
+          payload: errorMessage(error),
         });
         throw error;
       }
@@ -1595,7 +1511,7 @@ export function useThreads({
         timestamp: Date.now(),
         source: "error",
         label: "turn/cancel error",
-        payload: error instanceof Error ? error.message : String(error),
+        payload: errorMessage(error),
       });
     }
   }, [activeThreadId, activeWorkspaceId, onDebug, state.threadStatusById]);
@@ -1667,7 +1583,7 @@ export function useThreads({
           timestamp: Date.now(),
           source: "error",
           label: "review/start error",
-          payload: error instanceof Error ? error.message : String(error),
+          payload: errorMessage(error),
         });
         throw error;
       }
@@ -0,0 +1,27 @@
+import { useEffect } from "react";
+import { reportUserActivity } from "../services/tauri";
+
+const REPORT_INTERVAL_MS = 30_000;
+
+export function useUserActivity() {
+  useEffect(() => {
+    let lastReportedAt = 0;
+    const handleActivity = () => {
+      const now = Date.now();
+      if (now - lastReportedAt < REPORT_INTERVAL_MS) {
+        return;
+      }
+      lastReportedAt = now;
+      void reportUserActivity().catch(() => undefined);
+    };
+    const events = ["keydown", "pointerdown", "wheel"] as const;
+    events.forEach((name) =>
+      window.addEventListener(name, handleActivity, { passive: true }),
+    );
+    return () => {
+      events.forEach((name) =>
+        window.removeEventListener(name, handleActivity),
+      );
+    };
+  }, []);
+}
@@ -1,5 +1,10 @@
 import { useCallback, useEffect, useMemo, useState } from "react";
-import type { DebugEntry } from "../types";
+import { listen } from "@tauri-apps/api/event";
+import type {
+  CodexVersionWarning,
+  DebugEntry,
+  SessionsRevalidatedEvent,
+} from "../types";
 import type { WorkspaceInfo } from "../types";
 import {
   addWorkspace as addWorkspaceService,
@@ -8,6 +13,7 @@ import {
   pickWorkspacePath,
   removeWorkspace as removeWorkspaceService,
 } from "../services/tauri";
+import { errorMessage } from "../utils/errors";
 
 type UseWorkspacesOptions = {
   onDebug?: (entry: DebugEntry) => void;
@@ -21,7 +27,7 @@ export function useWorkspaces(options: UseWorkspacesOptions = {}) {
   const { onDebug, onCodexBinMissing } = options;
 
   function extractErrorMessage(error: unknown) {
-    return error instanceof Error ? error.message : String(error);
+    return errorMessage(error);
   }
 
   function isCodexNotFound(error: unknown) {
@@ -56,6 +62,60 @@ export function useWorkspaces(options: UseWorkspacesOptions = {}) {
     void refreshWorkspaces();
   }, [refreshWorkspaces]);
 
+  useEffect(() => {
+    const subscription = listen<CodexVersionWarning>(
+      "codex-version-warning",
+      (event) => {
+        const { workspaceId, version, raw, supportedRange } = event.payload;
+        setWorkspaces((prev) =>
+          prev.map((entry) =>
+            entry.id === workspaceId
+              ? {
+                  ...entry,
+                  codex_version: version,
+                  codex_version_supported: false,
+                }
+              : entry,
+          ),
+        );
+        onDebug?.({
+          id: `${Date.now()}-client-codex-version-warning`,
+          timestamp: Date.now(),
+          source: "error",
+          label: "codex version unsupported",
+          payload: `${version ?? raw} (supported: ${supportedRange})`,
+        });
+      },
+    );
+    return () => {
+      subscription.then((unlisten) => unlisten());
+    };
+  }, [onDebug]);
+
+  useEffect(() => {
+    const subscription = listen<SessionsRevalidatedEvent>(
+      "sessions-revalidated",
+      (event) => {
+        const { reason, sessions } = event.payload;
+        const changed = sessions.filter((session) => session.status !== "healthy");
+        if (changed.length === 0) {
+          return;
+        }
+        void refreshWorkspaces().catch(() => undefined);
+        onDebug?.({
+          id: `${Date.now()}-client-sessions-revalidated`,
+          timestamp: Date.now(),
+          source: "client",
+          label: `sessions revalidated (${reason})`,
+          payload: changed,
+        });
+      },
+    );
+    return () => {
+      subscription.then((unlisten) => unlisten());
+    };
+  }, [onDebug, refreshWorkspaces]);
+
   const activeWorkspace = useMemo(
     () => workspaces.find((entry) => entry.id === activeWorkspaceId) ?? null,
     [activeWorkspaceId, workspaces],
@@ -87,7 +147,7 @@ export function useWorkspaces(options: UseWorkspacesOptions = {}) {
         timestamp: Date.now(),
         source: "error",
         label: "workspace/add error",
-        payload: error instanceof Error ? error.message : String(error),
+        payload: errorMessage(error),
       });
       throw error;
     }
@@ -112,7 +172,7 @@ export function useWorkspaces(options: UseWorkspacesOptions = {}) {
         timestamp: Date.now(),
         source: "error",
         label: "workspace/connect error",
-        payload: error instanceof Error ? error.message : String(error),
+        payload: errorMessage(error),
       });
       throw error;
     }
@@ -144,7 +204,7 @@ export function useWorkspaces(options: UseWorkspacesOptions = {}) {
         timestamp: Date.now(),
         source: "error",
         label: "workspace/remove error",
-        payload: error instanceof Error ? error.message : String(error),
+        payload: errorMessage(error),
       });
       throw error;
     }
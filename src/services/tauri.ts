@@ -1,15 +1,107 @@
 import { invoke } from "@tauri-apps/api/core";
 import { open } from "@tauri-apps/plugin-dialog";
 import type {
+  AccessMode,
+  AccountStatus,
+  AgentInstructions,
   AppSettings,
+  AppSnapshot,
+  AuditEntry,
+  AuditFilter,
+  BadgeCounts,
+  BatchReport,
+  Broadcast,
+  CodexAccount,
   CodexBinInspection,
+  CodexBinaryCandidate,
+  CodexVersionInfo,
+  CommandAllowlistEntry,
+  CommandAllowlistScope,
+  ContainerConfig,
+  ContainerStatus,
+  ContextUsage,
+  CrashReport,
+  DeepLinkEvent,
+  DeferredApproval,
+  DiscoveredRepo,
+  DuplicateWorkspaces,
+  FileSearchMatch,
+  HookExecution,
+  HttpApiStatus,
+  Keybinding,
+  LocalAnalyticsReport,
+  LocaleStrings,
+  UpdateCheck,
+  UpdateInfo,
+  InstructionsScope,
+  InstructionsUpdate,
   LocalImageInput,
+  MessageDraft,
+  PathGuardConfig,
+  PendingMessage,
+  PreConnectCommand,
+  QueuedMessage,
+  QuickSwitchItem,
+  RateLimitHistory,
+  RateLimitHistoryRange,
+  RecordingResult,
+  RecordingStarted,
+  SaveCodeBlockMode,
+  SavedAttachment,
+  SavedCodeBlock,
+  SearchConfig,
+  SecretAction,
+  SecretScanStore,
+  ShellEnvStatus,
+  Skill,
+  SkillInput,
+  SkillScope,
+  StorageStatus,
+  TaskRun,
+  PromptOptions,
+  QueuedTask,
+  ScheduledRun,
+  ScheduledTask,
+  ScheduledTaskInput,
+  TerminalInfo,
+  ThreadMetrics,
+  ThreadOverrides,
+  ThreadState,
+  TransferConflict,
+  TransferResult,
+  TurnChanges,
+  UndoResult,
+  WorkspaceFileContent,
+  WorkspaceHealth,
+  WorkspaceStats,
   UsageSnapshot,
+  WorkspaceCapabilities,
   WorkspaceInfo,
+  WorkspaceMetrics,
   WorkspaceSessionStore,
+  WorkspaceTask,
+  WindowState,
+  WorkspaceTemplate,
+  WorkspaceTemplateInput,
+  WorkspaceThreads,
 } from "../types";
-import type { GitFileDiff, GitFileStatus, ReviewTarget } from "../types";
-import type { PromptFile, PromptOption } from "../types";
+import type {
+  BlameLine,
+  Checkpoint,
+  FileVersions,
+  ConflictResolution,
+  GitConflict,
+  GitFileDiff,
+  GitFileStatus,
+  PatchApplyResult,
+  PatchPreview,
+  ReviewFinding,
+  ReviewFindingStatus,
+  ReviewStart,
+  ReviewTarget,
+  StructuredFileDiff,
+} from "../types";
+import type { PromptFile, PromptOption, PromptStat } from "../types";
 
 export async function pickWorkspacePath(): Promise<string | null> {
   const selection = await open({ directory: true, multiple: false });
@@ -46,6 +138,79 @@ export async function addWorkspace(
   return invoke<WorkspaceInfo>("add_workspace", { path, codex_bin });
 }
 
+export async function addWorkspaceFromTemplate(
+  path: string,
+  templateId: string,
+): Promise<WorkspaceInfo> {
+  return invoke<WorkspaceInfo>("add_workspace_from_template", { path, templateId });
+}
+
+export async function discoverRepos(
+  roots: string[] = [],
+  maxDepth?: number,
+): Promise<DiscoveredRepo[]> {
+  return invoke<DiscoveredRepo[]>("discover_repos", { roots, maxDepth: maxDepth ?? null });
+}
+
+export async function listWorkspaceTemplates(): Promise<WorkspaceTemplate[]> {
+  return invoke<WorkspaceTemplate[]>("list_workspace_templates");
+}
+
+export async function saveWorkspaceTemplate(
+  template: WorkspaceTemplateInput,
+): Promise<WorkspaceTemplate> {
+  return invoke<WorkspaceTemplate>("save_workspace_template", { template });
+}
+
+export async function deleteWorkspaceTemplate(templateId: string): Promise<void> {
+  return invoke("delete_workspace_template", { templateId });
+}
+
+export async function getThreadContextUsage(
+  workspaceId: string,
+  threadId: string,
+): Promise<ContextUsage | null> {
+  return invoke<ContextUsage | null>("get_thread_context_usage", { workspaceId, threadId });
+}
+
+export async function readAgentInstructions(
+  workspaceId: string,
+  scope?: InstructionsScope,
+): Promise<AgentInstructions> {
+  return invoke<AgentInstructions>("read_agent_instructions", { workspaceId, scope });
+}
+
+export async function writeAgentInstructions(
+  workspaceId: string,
+  content: string,
+  scope?: InstructionsScope,
+  preview?: boolean,
+): Promise<InstructionsUpdate> {
+  return invoke<InstructionsUpdate>("write_agent_instructions", {
+    workspaceId,
+    content,
+    scope,
+    preview,
+  });
+}
+
+export async function compactThread(
+  workspaceId: string,
+  threadId: string,
+): Promise<Record<string, unknown>> {
+  return invoke<Record<string, unknown>>("compact_thread", { workspaceId, threadId });
+}
+
+export async function readEventBlob(path: string): Promise<Record<string, unknown>> {
+  return invoke<Record<string, unknown>>("read_event_blob", { path });
+}
+
+export async function getWorkspaceCapabilities(
+  workspaceId: string,
+): Promise<WorkspaceCapabilities> {
+  return invoke<WorkspaceCapabilities>("get_workspace_capabilities", { workspaceId });
+}
+
 export async function connectWorkspace(id: string): Promise<void> {
   return invoke("connect_workspace", { id });
 }
@@ -54,8 +219,17 @@ export async function removeWorkspace(id: string): Promise<void> {
   return invoke("remove_workspace", { id });
 }
 
-export async function startThread(workspaceId: string) {
-  return invoke<any>("start_thread", { workspaceId });
+export async function startThread(
+  workspaceId: string,
+  overrides?: ThreadOverrides,
+) {
+  return invoke<any>("start_thread", {
+    workspaceId,
+    approvalPolicy: overrides?.approvalPolicy ?? null,
+    sandboxMode: overrides?.sandboxMode ?? null,
+    model: overrides?.model ?? null,
+    effort: overrides?.effort ?? null,
+  });
 }
 
 export async function sendUserMessage(
@@ -66,20 +240,78 @@ export async function sendUserMessage(
     model?: string | null;
     effort?: string | null;
     accessMode?: "read-only" | "current" | "full-access";
+    secretAction?: SecretAction | null;
   },
   attachments?: LocalImageInput[],
 ) {
   return invoke("send_user_message", {
     workspaceId,
-    threadId,
-    text,
-    model: options?.model ?? null,
-    effort: options?.effort ?? null,
-    accessMode: options?.accessMode ?? null,
-    attachments: attachments && attachments.length > 0 ? attachments : null,
+    message: {
+      threadId,
+      text,
+      model: options?.model ?? null,
+      effort: options?.effort ?? null,
+      accessMode: options?.accessMode ?? null,
+      attachments: attachments ?? [],
+    },
+    secretAction: options?.secretAction ?? null,
   });
 }
 
+export async function getSecretScanStore(workspaceId: string): Promise<SecretScanStore> {
+  return invoke<SecretScanStore>("get_secret_scan_store", { workspaceId });
+}
+
+export async function setSecretAllowlist(
+  workspaceId: string,
+  allowlist: string[],
+): Promise<SecretScanStore> {
+  return invoke<SecretScanStore>("set_secret_allowlist", { workspaceId, allowlist });
+}
+
+export async function clearSecretScanLog(workspaceId: string): Promise<void> {
+  return invoke<void>("clear_secret_scan_log", { workspaceId });
+}
+
+export async function queueMessage(
+  workspaceId: string,
+  threadId: string,
+  text: string,
+  options?: {
+    model?: string | null;
+    effort?: string | null;
+    accessMode?: "read-only" | "current" | "full-access";
+    secretAction?: SecretAction | null;
+  },
+  attachments?: LocalImageInput[],
+): Promise<QueuedMessage> {
+  return invoke<QueuedMessage>("queue_message", {
+    workspaceId,
+    message: {
+      threadId,
+      text,
+      model: options?.model ?? null,
+      effort: options?.effort ?? null,
+      accessMode: options?.accessMode ?? null,
+      attachments: attachments ?? [],
+    },
+    secretAction: options?.secretAction ?? null,
+  });
+}
+
+export async function listQueuedMessages(
+  workspaceId: string,
+): Promise<QueuedMessage[]> {
+  return invoke<QueuedMessage[]>("list_queued_messages", { workspaceId });
+}
+
+export async function cancelQueuedMessage(
+  workspaceId: string,
+  messageId: string,
+): Promise<void> {
+  return invoke("cancel_queued_message", { workspaceId, messageId });
+}
+
 export async function cancelTurn(workspaceId: string, threadId: string) {
   return invoke<any>("cancel_turn", { workspaceId, threadId });
 }
@@ -87,8 +319,8 @@ export async function cancelTurn(workspaceId: string, threadId: string) {
 export async function saveAttachment(
   workspaceId: string,
   payload: { bytes: number[]; name?: string | null; mime?: string | null },
-): Promise<{ path: string }> {
-  return invoke("save_attachment", {
+): Promise<SavedAttachment> {
+  return invoke<SavedAttachment>("save_attachment", {
     workspaceId,
     bytes: payload.bytes,
     name: payload.name ?? null,
@@ -109,6 +341,103 @@ export async function startReview(
   return invoke("start_review", payload);
 }
 
+export async function applyPatch(
+  workspaceId: string,
+  patchText: string,
+  dryRun = false,
+): Promise<PatchApplyResult> {
+  return invoke<PatchApplyResult>("apply_patch", {
+    workspaceId,
+    patchText,
+    dryRun,
+  });
+}
+
+export async function previewPatch(
+  workspaceId: string,
+  patchText: string,
+): Promise<PatchPreview> {
+  return invoke<PatchPreview>("preview_patch", { workspaceId, patchText });
+}
+
+export async function listReviewFindings(
+  workspaceId: string,
+  status?: ReviewFindingStatus,
+): Promise<ReviewFinding[]> {
+  return invoke<ReviewFinding[]>("list_review_findings", {
+    workspaceId,
+    status: status ?? null,
+  });
+}
+
+export async function setReviewFindingStatus(
+  workspaceId: string,
+  findingIds: string[],
+  status: ReviewFindingStatus,
+): Promise<ReviewFinding[]> {
+  return invoke<ReviewFinding[]>("set_review_finding_status", {
+    workspaceId,
+    findingIds,
+    status,
+  });
+}
+
+export async function rerunReviewUnresolved(
+  workspaceId: string,
+  threadId: string,
+  delivery?: "inline" | "detached",
+) {
+  return invoke("rerun_review_unresolved", {
+    workspaceId,
+    threadId,
+    delivery: delivery ?? null,
+  });
+}
+
+export async function reviewUncommitted(
+  workspaceId: string,
+  threadId: string,
+  paths?: string[],
+  delivery?: "inline" | "detached",
+): Promise<ReviewStart> {
+  return invoke<ReviewStart>("review_uncommitted", {
+    workspaceId,
+    threadId,
+    paths: paths ?? null,
+    delivery: delivery ?? null,
+  });
+}
+
+export async function reviewCommit(
+  workspaceId: string,
+  threadId: string,
+  commitId: string,
+  delivery?: "inline" | "detached",
+): Promise<ReviewStart> {
+  return invoke<ReviewStart>("review_commit", {
+    workspaceId,
+    threadId,
+    commitId,
+    delivery: delivery ?? null,
+  });
+}
+
+export async function reviewRange(
+  workspaceId: string,
+  threadId: string,
+  base: string,
+  head?: string,
+  delivery?: "inline" | "detached",
+): Promise<ReviewStart> {
+  return invoke<ReviewStart>("review_range", {
+    workspaceId,
+    threadId,
+    base,
+    head: head ?? null,
+    delivery: delivery ?? null,
+  });
+}
+
 export async function respondToServerRequest(
   workspaceId: string,
   requestId: number,
@@ -136,6 +465,126 @@ export async function getGitDiffs(
   return invoke("get_git_diffs", { workspaceId: workspace_id });
 }
 
+export async function getFullDiff(
+  workspaceId: string,
+  path: string,
+): Promise<GitFileDiff | null> {
+  return invoke("get_full_diff", { workspaceId, path });
+}
+
+export async function getGitDiffRange(
+  workspaceId: string,
+  base: string,
+  options?: { head?: string | null; path?: string | null },
+): Promise<GitFileDiff[]> {
+  return invoke("get_git_diff_range", {
+    workspaceId,
+    base,
+    head: options?.head ?? null,
+    path: options?.path ?? null,
+  });
+}
+
+export async function getGitFileDiffStructured(
+  workspaceId: string,
+  path: string,
+): Promise<StructuredFileDiff | null> {
+  return invoke("get_git_file_diff_structured", { workspaceId, path });
+}
+
+export async function gitFetch(
+  workspaceId: string,
+  remote?: string | null,
+): Promise<string> {
+  return invoke<string>("git_fetch", { workspaceId, remote: remote ?? null });
+}
+
+export async function gitPull(workspaceId: string, rebase: boolean): Promise<string> {
+  return invoke<string>("git_pull", { workspaceId, rebase });
+}
+
+export async function gitPush(
+  workspaceId: string,
+  options?: { setUpstream?: boolean; forceWithLease?: boolean },
+): Promise<string> {
+  return invoke<string>("git_push", {
+    workspaceId,
+    setUpstream: options?.setUpstream ?? false,
+    forceWithLease: options?.forceWithLease ?? false,
+  });
+}
+
+export async function createPullRequest(
+  workspaceId: string,
+  options: {
+    title?: string | null;
+    body?: string | null;
+    base?: string | null;
+    draft?: boolean;
+    threadId?: string | null;
+  },
+): Promise<string> {
+  return invoke<string>("create_pull_request", {
+    workspaceId,
+    title: options.title ?? null,
+    body: options.body ?? null,
+    base: options.base ?? null,
+    draft: options.draft ?? false,
+    threadId: options.threadId ?? null,
+  });
+}
+
+export async function getGitConflicts(workspaceId: string): Promise<GitConflict[]> {
+  return invoke<GitConflict[]>("git_conflicts", { workspaceId });
+}
+
+export async function resolveGitConflict(
+  workspaceId: string,
+  path: string,
+  resolution: ConflictResolution,
+): Promise<GitConflict[]> {
+  return invoke<GitConflict[]>("git_resolve", { workspaceId, path, resolution });
+}
+
+export async function getGitBlame(
+  workspaceId: string,
+  path: string,
+  range?: [number, number] | null,
+): Promise<BlameLine[]> {
+  return invoke<BlameLine[]>("git_blame", { workspaceId, path, range: range ?? null });
+}
+
+export async function listCheckpoints(
+  workspaceId: string,
+  threadId?: string | null,
+): Promise<Checkpoint[]> {
+  return invoke<Checkpoint[]>("list_checkpoints", { workspaceId, threadId: threadId ?? null });
+}
+
+export async function restoreCheckpoint(checkpointId: string): Promise<Checkpoint> {
+  return invoke<Checkpoint>("restore_checkpoint", { checkpointId });
+}
+
+export async function getFileVersions(
+  workspaceId: string,
+  path: string,
+  threadId: string,
+): Promise<FileVersions> {
+  return invoke<FileVersions>("get_file_versions", { workspaceId, path, threadId });
+}
+
+export async function restoreFileVersion(
+  workspaceId: string,
+  path: string,
+  checkpointId: string,
+): Promise<Checkpoint> {
+  return invoke<Checkpoint>("restore_file_version", {
+    workspaceId,
+    path,
+    checkpointId,
+  });
+}
+
 export async function getModelList(workspaceId: string) {
   return invoke<any>("model_list", { workspaceId });
 }
@@ -144,6 +593,38 @@ export async function getSkillsList(workspaceId: string) {
   return invoke<any>("skills_list", { workspaceId });
 }
 
+export async function skillRead(workspaceId: string, skillId: string): Promise<Skill> {
+  return invoke<Skill>("skill_read", { workspaceId, skillId });
+}
+
+export async function skillCreate(
+  workspaceId: string,
+  scope: SkillScope,
+  skill: SkillInput,
+): Promise<Skill> {
+  return invoke<Skill>("skill_create", { workspaceId, scope, skill });
+}
+
+export async function skillUpdate(
+  workspaceId: string,
+  skillId: string,
+  skill: SkillInput,
+): Promise<Skill> {
+  return invoke<Skill>("skill_update", { workspaceId, skillId, skill });
+}
+
+export async function skillDelete(workspaceId: string, skillId: string): Promise<void> {
+  return invoke("skill_delete", { workspaceId, skillId });
+}
+
+export async function skillSetEnabled(
+  workspaceId: string,
+  skillId: string,
+  enabled: boolean,
+): Promise<void> {
+  return invoke("skill_set_enabled", { workspaceId, skillId, enabled });
+}
+
 export async function getPromptsList(): Promise<PromptOption[]> {
   return invoke<PromptOption[]>("prompts_list");
 }
@@ -152,12 +633,122 @@ export async function readPrompt(name: string): Promise<PromptFile> {
   return invoke<PromptFile>("prompt_read", { name });
 }
 
+export async function renderPrompt(
+  name: string,
+  args: Record<string, string>,
+  options?: { workspaceId?: string | null; includeContext?: boolean },
+): Promise<string> {
+  return invoke<string>("prompt_render", {
+    name,
+    args,
+    workspaceId: options?.workspaceId ?? null,
+    includeContext: options?.includeContext ?? null,
+  });
+}
+
+export async function recordPromptUsage(
+  name: string,
+  workspaceId?: string | null,
+): Promise<void> {
+  return invoke("record_prompt_usage", { name, workspaceId: workspaceId ?? null });
+}
+
+export async function getPromptStats(): Promise<PromptStat[]> {
+  return invoke<PromptStat[]>("prompt_stats");
+}
+
 export async function searchFiles(
   workspaceId: string,
   query: string,
   limit?: number,
-): Promise<string[]> {
-  return invoke<string[]>("search_files", { workspaceId, query, limit });
+): Promise<FileSearchMatch[]> {
+  return invoke<FileSearchMatch[]>("search_files", { workspaceId, query, limit });
+}
+
+export async function readWorkspaceFile(
+  workspaceId: string,
+  path: string,
+  options?: { maxBytes?: number | null; encodingHint?: string | null },
+): Promise<WorkspaceFileContent> {
+  return invoke<WorkspaceFileContent>("read_workspace_file", {
+    workspaceId,
+    path,
+    maxBytes: options?.maxBytes ?? null,
+    encodingHint: options?.encodingHint ?? null,
+  });
+}
+
+export async function resolveAbsolutePath(
+  workspaceId: string,
+  relative: string,
+): Promise<string> {
+  return invoke<string>("resolve_absolute_path", { workspaceId, relative });
+}
+
+export async function revealInFileManager(
+  workspaceId: string,
+  path: string,
+): Promise<void> {
+  return invoke("reveal_in_file_manager", { workspaceId, path });
+}
+
+export async function rebuildFileIndex(workspaceId: string): Promise<number> {
+  return invoke<number>("rebuild_file_index", { workspaceId });
+}
+
+export async function getWorkspaceHealth(
+  workspaceId: string,
+): Promise<WorkspaceHealth> {
+  return invoke<WorkspaceHealth>("workspace_health", { workspaceId });
+}
+
+export async function getWorkspaceStats(workspaceId: string): Promise<WorkspaceStats> {
+  return invoke<WorkspaceStats>("workspace_stats", { workspaceId });
+}
+
+export async function queryAuditLog(
+  workspaceId: string,
+  filter?: AuditFilter,
+): Promise<AuditEntry[]> {
+  return invoke<AuditEntry[]>("query_audit_log", { workspaceId, filter: filter ?? null });
+}
+
+export async function exportAuditLogCsv(
+  workspaceId: string,
+  path: string,
+  filter?: AuditFilter,
+): Promise<number> {
+  return invoke<number>("export_audit_log_csv", {
+    workspaceId,
+    filter: filter ?? null,
+    path,
+  });
+}
+
+export async function cancelWorkspaceStats(workspaceId: string): Promise<boolean> {
+  return invoke<boolean>("cancel_workspace_stats", { workspaceId });
+}
+
+export async function getSearchConfig(workspaceId: string): Promise<SearchConfig> {
+  return invoke<SearchConfig>("get_search_config", { workspaceId });
+}
+
+export async function saveSearchConfig(
+  workspaceId: string,
+  config: SearchConfig,
+): Promise<SearchConfig> {
+  return invoke<SearchConfig>("save_search_config", { workspaceId, config });
+}
+
+export async function getPathGuard(workspaceId: string): Promise<PathGuardConfig> {
+  return invoke<PathGuardConfig>("get_path_guard", { workspaceId });
+}
+
+export async function setPathGuard(
+  workspaceId: string,
+  config: PathGuardConfig,
+): Promise<PathGuardConfig> {
+  return invoke<PathGuardConfig>("set_path_guard", { workspaceId, config });
 }
 
 export async function listThreads(
@@ -168,14 +759,62 @@ export async function listThreads(
   return invoke<any>("list_threads", { workspaceId, cursor, limit });
 }
 
-export async function resumeThread(workspaceId: string, threadId: string) {
-  return invoke<any>("resume_thread", { workspaceId, threadId });
+export async function listAllThreads(): Promise<WorkspaceThreads[]> {
+  return invoke<WorkspaceThreads[]>("list_all_threads");
+}
+
+export async function resumeThread(
+  workspaceId: string,
+  threadId: string,
+  overrides?: ThreadOverrides,
+) {
+  return invoke<any>("resume_thread", {
+    workspaceId,
+    threadId,
+    approvalPolicy: overrides?.approvalPolicy ?? null,
+    sandboxMode: overrides?.sandboxMode ?? null,
+    model: overrides?.model ?? null,
+    effort: overrides?.effort ?? null,
+  });
 }
 
 export async function archiveThread(workspaceId: string, threadId: string) {
   return invoke<any>("archive_thread", { workspaceId, threadId });
 }
 
+export async function prepareDeleteThread(
+  workspaceId: string,
+  threadId: string,
+): Promise<{ confirmToken: string; expiresAtMs: number }> {
+  return invoke("prepare_delete_thread", { workspaceId, threadId });
+}
+
+export async function deleteThread(
+  workspaceId: string,
+  threadId: string,
+  confirmToken: string,
+  deleteRollout?: boolean,
+) {
+  return invoke<any>("delete_thread", {
+    workspaceId,
+    threadId,
+    confirmToken,
+    deleteRollout: deleteRollout ?? null,
+  });
+}
+
+export async function forkThread(
+  workspaceId: string,
+  threadId: string,
+  upToTurn?: number | null,
+) {
+  return invoke<any>("fork_thread", {
+    workspaceId,
+    threadId,
+    upToTurn: upToTurn ?? null,
+  });
+}
+
 export async function getWorkspaceSessions(
   workspaceId: string,
 ): Promise<WorkspaceSessionStore> {
@@ -208,6 +847,159 @@ export async function validateCodexBin(path: string): Promise<void> {
   return invoke("validate_codex_bin", { path });
 }
 
+export async function listHookExecutions(
+  limit?: number,
+): Promise<HookExecution[]> {
+  return invoke<HookExecution[]>("list_hook_executions", { limit });
+}
+
+export async function clearHookExecutions(): Promise<void> {
+  return invoke("clear_hook_executions");
+}
+
+export async function testHook(hookId: string): Promise<HookExecution> {
+  return invoke<HookExecution>("test_hook", { hookId });
+}
+
+export async function setLogLevel(level: string): Promise<string> {
+  return invoke<string>("set_log_level", { level });
+}
+
+export async function collectDiagnostics(): Promise<string> {
+  return invoke<string>("collect_diagnostics");
+}
+
+export async function takePendingDeepLinks(): Promise<DeepLinkEvent[]> {
+  return invoke<DeepLinkEvent[]>("take_pending_deep_links");
+}
+
+export async function getCodexVersion(
+  path?: string | null,
+): Promise<CodexVersionInfo> {
+  return invoke<CodexVersionInfo>("get_codex_version", { path: path ?? null });
+}
+
+export async function discoverCodexBinaries(): Promise<CodexBinaryCandidate[]> {
+  return invoke<CodexBinaryCandidate[]>("discover_codex_binaries");
+}
+
+export async function refreshLoginShellEnv(): Promise<ShellEnvStatus> {
+  return invoke<ShellEnvStatus>("refresh_login_shell_env");
+}
+
+export async function getThreadStates(workspaceId: string): Promise<ThreadState[]> {
+  return invoke<ThreadState[]>("get_thread_states", { workspaceId });
+}
+
+export async function getAppSnapshot(): Promise<AppSnapshot> {
+  return invoke<AppSnapshot>("get_app_snapshot");
+}
+
+export async function getTurnChanges(
+  workspaceId: string,
+  threadId: string,
+  turnIndex: number,
+): Promise<TurnChanges> {
+  return invoke<TurnChanges>("get_turn_changes", { workspaceId, threadId, turnIndex });
+}
+
+export async function undoTurn(
+  workspaceId: string,
+  threadId: string,
+  turnIndex: number,
+  force?: boolean,
+  postNoteToThread?: boolean,
+): Promise<UndoResult> {
+  return invoke<UndoResult>("undo_turn", {
+    workspaceId,
+    threadId,
+    turnIndex,
+    force: force ?? null,
+    postNoteToThread: postNoteToThread ?? null,
+  });
+}
+
+export async function getThreadMetrics(
+  workspaceId: string,
+  threadId: string,
+): Promise<ThreadMetrics> {
+  return invoke<ThreadMetrics>("get_thread_metrics", { workspaceId, threadId });
+}
+
+export async function getWorkspaceMetrics(workspaceId: string): Promise<WorkspaceMetrics> {
+  return invoke<WorkspaceMetrics>("get_workspace_metrics", { workspaceId });
+}
+
+export async function getBadgeCounts(): Promise<BadgeCounts> {
+  return invoke<BadgeCounts>("get_badge_counts");
+}
+
+export async function reportUserActivity(): Promise<void> {
+  return invoke("report_user_activity");
+}
+
+export async function listDeferredApprovals(
+  workspaceId?: string,
+  includeReplayed?: boolean,
+): Promise<DeferredApproval[]> {
+  return invoke<DeferredApproval[]>("list_deferred_approvals", {
+    workspaceId: workspaceId ?? null,
+    includeReplayed: includeReplayed ?? null,
+  });
+}
+
+export async function replayDeferredApproval(
+  workspaceId: string,
+  deferralId: string,
+  decision: "accept" | "decline",
+): Promise<DeferredApproval> {
+  return invoke<DeferredApproval>("replay_deferred_approval", {
+    workspaceId,
+    deferralId,
+    decision,
+  });
+}
+
+export async function listPendingMessages(workspaceId: string): Promise<PendingMessage[]> {
+  return invoke<PendingMessage[]>("list_pending_messages", { workspaceId });
+}
+
+export async function cancelPendingMessage(
+  workspaceId: string,
+  messageId: string,
+): Promise<void> {
+  return invoke("cancel_pending_message", { workspaceId, messageId });
+}
+
+export async function saveDraft(
+  workspaceId: string,
+  threadId: string,
+  text: string,
+  attachments: LocalImageInput[] = [],
+): Promise<MessageDraft | null> {
+  return invoke<MessageDraft | null>("save_draft", {
+    workspaceId,
+    threadId,
+    text,
+    attachments,
+  });
+}
+
+export async function getDraft(
+  workspaceId: string,
+  threadId: string,
+): Promise<MessageDraft | null> {
+  return invoke<MessageDraft | null>("get_draft", { workspaceId, threadId });
+}
+
+export async function clearDraft(workspaceId: string, threadId: string): Promise<void> {
+  return invoke("clear_draft", { workspaceId, threadId });
+}
+
+export async function getStorageStatus(): Promise<StorageStatus> {
+  return invoke<StorageStatus>("get_storage_status");
+}
+
 export async function getUsageSnapshot(): Promise<UsageSnapshot> {
   return invoke<UsageSnapshot>("usage_get_snapshot");
 }
@@ -216,6 +1008,372 @@ export async function refreshUsageSnapshot(): Promise<UsageSnapshot> {
   return invoke<UsageSnapshot>("usage_refresh");
 }
 
+export async function getRateLimitHistory(
+  range: RateLimitHistoryRange,
+): Promise<RateLimitHistory> {
+  return invoke<RateLimitHistory>("rate_limit_history", { range });
+}
+
 export async function confirmQuit(): Promise<void> {
   return invoke<void>("confirm_quit");
 }
+
+export async function getKeymap(): Promise<Keybinding[]> {
+  return invoke<Keybinding[]>("get_keymap");
+}
+
+export async function setKeybinding(
+  action: string,
+  accelerator: string | null,
+): Promise<Keybinding[]> {
+  return invoke<Keybinding[]>("set_keybinding", { action, accelerator });
+}
+
+export async function resetKeymap(): Promise<Keybinding[]> {
+  return invoke<Keybinding[]>("reset_keymap");
+}
+
+export async function getLocaleStrings(lang?: string | null): Promise<LocaleStrings> {
+  return invoke<LocaleStrings>("get_locale_strings", { lang: lang ?? null });
+}
+
+export async function checkForUpdates(): Promise<UpdateCheck> {
+  return invoke<UpdateCheck>("check_for_updates");
+}
+
+export async function downloadUpdate(): Promise<UpdateInfo> {
+  return invoke<UpdateInfo>("download_update");
+}
+
+export async function installUpdateAndRestart(force = false): Promise<void> {
+  return invoke("install_update_and_restart", { force });
+}
+
+export async function listCrashReports(): Promise<CrashReport[]> {
+  return invoke<CrashReport[]>("list_crash_reports");
+}
+
+export async function submitCrashReport(id: string): Promise<CrashReport> {
+  return invoke<CrashReport>("submit_crash_report", { id });
+}
+
+export async function getLocalAnalytics(): Promise<LocalAnalyticsReport> {
+  return invoke<LocalAnalyticsReport>("get_local_analytics");
+}
+
+export async function exportLocalAnalytics(path: string): Promise<void> {
+  return invoke("export_local_analytics", { path });
+}
+
+export async function resetLocalAnalytics(): Promise<LocalAnalyticsReport> {
+  return invoke<LocalAnalyticsReport>("reset_local_analytics");
+}
+
+export async function setThreadAccessMode(
+  workspaceId: string,
+  threadId: string,
+  accessMode: AccessMode | null,
+): Promise<AccessMode | null> {
+  return invoke<AccessMode | null>("set_thread_access_mode", {
+    workspaceId,
+    threadId,
+    accessMode,
+  });
+}
+
+export async function listCommandAllowlist(
+  workspaceId?: string | null,
+): Promise<CommandAllowlistEntry[]> {
+  return invoke<CommandAllowlistEntry[]>("list_command_allowlist", {
+    workspaceId: workspaceId ?? null,
+  });
+}
+
+export async function addCommandAllowlistEntry(
+  pattern: string,
+  scope: CommandAllowlistScope,
+  workspaceId?: string | null,
+): Promise<CommandAllowlistEntry[]> {
+  return invoke<CommandAllowlistEntry[]>("add_command_allowlist_entry", {
+    pattern,
+    scope,
+    workspaceId: workspaceId ?? null,
+  });
+}
+
+export async function removeCommandAllowlistEntry(
+  pattern: string,
+  scope: CommandAllowlistScope,
+  workspaceId?: string | null,
+): Promise<CommandAllowlistEntry[]> {
+  return invoke<CommandAllowlistEntry[]>("remove_command_allowlist_entry", {
+    pattern,
+    scope,
+    workspaceId: workspaceId ?? null,
+  });
+}
+
+export async function startRecording(): Promise<RecordingStarted> {
+  return invoke<RecordingStarted>("start_recording");
+}
+
+export async function stopRecording(transcribe?: boolean): Promise<RecordingResult> {
+  return invoke<RecordingResult>("stop_recording", {
+    transcribe: transcribe ?? null,
+  });
+}
+
+export async function getWindowState(): Promise<WindowState> {
+  return invoke<WindowState>("get_window_state");
+}
+
+export async function getQuickSwitchIndex(
+  query?: string,
+  limit?: number,
+): Promise<QuickSwitchItem[]> {
+  return invoke<QuickSwitchItem[]>("quick_switch_index", { query, limit });
+}
+
+export async function quitAndStopAgents(): Promise<void> {
+  return invoke<void>("quit_and_stop_agents");
+}
+
+export async function getCodexAccountStatus(
+  workspaceId?: string | null,
+): Promise<AccountStatus> {
+  return invoke<AccountStatus>("codex_account_status", {
+    workspaceId: workspaceId ?? null,
+  });
+}
+
+export async function codexLogin(codexHome?: string | null): Promise<void> {
+  return invoke("codex_login", { codexHome: codexHome ?? null });
+}
+
+export async function listAccounts(): Promise<CodexAccount[]> {
+  return invoke<CodexAccount[]>("list_accounts");
+}
+
+export async function saveAccount(
+  name: string,
+  codexHome: string,
+): Promise<CodexAccount[]> {
+  return invoke<CodexAccount[]>("save_account", { name, codexHome });
+}
+
+export async function removeAccount(name: string): Promise<CodexAccount[]> {
+  return invoke<CodexAccount[]>("remove_account", { name });
+}
+
+export async function setWorkspaceCodexHome(
+  id: string,
+  codexHome: string | null,
+): Promise<void> {
+  return invoke("set_workspace_codex_home", { id, codexHome });
+}
+
+export async function setWorkspaceContainer(
+  id: string,
+  container: ContainerConfig | null,
+): Promise<ContainerConfig | null> {
+  return invoke<ContainerConfig | null>("set_workspace_container", { id, container });
+}
+
+export async function setWorkspacePreConnectCommands(
+  id: string,
+  commands: PreConnectCommand[],
+): Promise<PreConnectCommand[]> {
+  return invoke<PreConnectCommand[]>("set_workspace_pre_connect_commands", { id, commands });
+}
+
+export async function setWorkspaceObserverMode(id: string, enabled: boolean): Promise<boolean> {
+  return invoke<boolean>("set_workspace_observer_mode", { id, enabled });
+}
+
+export async function setWorkspaceSpeech(id: string, enabled: boolean): Promise<boolean> {
+  return invoke<boolean>("set_workspace_speech", { id, enabled });
+}
+
+export async function setWorkspaceAutoConnect(id: string, enabled: boolean): Promise<boolean> {
+  return invoke<boolean>("set_workspace_auto_connect", { id, enabled });
+}
+
+export async function renameWorkspace(id: string, name: string): Promise<WorkspaceInfo> {
+  return invoke<WorkspaceInfo>("rename_workspace", { id, name });
+}
+
+export async function relocateWorkspace(id: string, newPath: string): Promise<WorkspaceInfo> {
+  return invoke<WorkspaceInfo>("relocate_workspace", { id, newPath });
+}
+
+export async function findDuplicateWorkspaces(): Promise<DuplicateWorkspaces[]> {
+  return invoke<DuplicateWorkspaces[]>("find_duplicate_workspaces");
+}
+
+export async function mergeWorkspaces(keepId: string, removeId: string): Promise<WorkspaceInfo> {
+  return invoke<WorkspaceInfo>("merge_workspaces", { keepId, removeId });
+}
+
+export async function transferThreadMetadata(
+  fromWorkspace: string,
+  toWorkspace: string,
+  threadIds: string[],
+  conflict?: TransferConflict,
+): Promise<TransferResult> {
+  return invoke<TransferResult>("transfer_thread_metadata", {
+    fromWorkspace,
+    toWorkspace,
+    threadIds,
+    conflict,
+  });
+}
+
+export async function listScheduledTasks(): Promise<ScheduledTask[]> {
+  return invoke<ScheduledTask[]>("list_scheduled_tasks");
+}
+
+export async function saveScheduledTask(task: ScheduledTaskInput): Promise<ScheduledTask> {
+  return invoke<ScheduledTask>("save_scheduled_task", { task });
+}
+
+export async function deleteScheduledTask(taskId: string): Promise<void> {
+  return invoke("delete_scheduled_task", { taskId });
+}
+
+export async function runScheduledTaskNow(taskId: string): Promise<ScheduledRun> {
+  return invoke<ScheduledRun>("run_scheduled_task_now", { taskId });
+}
+
+export async function listScheduledTaskRuns(taskId?: string): Promise<ScheduledRun[]> {
+  return invoke<ScheduledRun[]>("list_scheduled_task_runs", { taskId });
+}
+
+export async function listTaskQueue(workspaceId: string): Promise<QueuedTask[]> {
+  return invoke<QueuedTask[]>("list_task_queue", { workspaceId });
+}
+
+export async function enqueueTask(
+  workspaceId: string,
+  prompt: string,
+  options?: PromptOptions,
+  batchId?: string,
+): Promise<QueuedTask> {
+  return invoke<QueuedTask>("enqueue_task", { workspaceId, prompt, options, batchId });
+}
+
+export async function resumeTaskQueue(workspaceId: string): Promise<QueuedTask[]> {
+  return invoke<QueuedTask[]>("resume_task_queue", { workspaceId });
+}
+
+export async function reorderTaskQueue(
+  workspaceId: string,
+  taskIds: string[],
+): Promise<QueuedTask[]> {
+  return invoke<QueuedTask[]>("reorder_task_queue", { workspaceId, taskIds });
+}
+
+export async function cancelQueuedTask(
+  workspaceId: string,
+  taskId: string,
+): Promise<QueuedTask[]> {
+  return invoke<QueuedTask[]>("cancel_queued_task", { workspaceId, taskId });
+}
+
+export async function broadcastMessage(
+  workspaceIds: string[],
+  prompt: string,
+  options?: PromptOptions,
+  threads?: Record<string, string>,
+): Promise<Broadcast> {
+  return invoke<Broadcast>("broadcast_message", { workspaceIds, prompt, options, threads });
+}
+
+export async function listBroadcasts(): Promise<Broadcast[]> {
+  return invoke<Broadcast[]>("list_broadcasts");
+}
+
+export async function getBroadcast(batchId: string): Promise<Broadcast> {
+  return invoke<Broadcast>("get_broadcast", { batchId });
+}
+
+export async function getBatchReport(batchId: string): Promise<BatchReport> {
+  return invoke<BatchReport>("get_batch_report", { batchId });
+}
+
+export async function exportBatchReportMarkdown(batchId: string, path?: string): Promise<string> {
+  return invoke<string>("export_batch_report_markdown", { batchId, path });
+}
+
+export async function saveCodeBlock(
+  workspaceId: string,
+  relativePath: string,
+  content: string,
+  mode: SaveCodeBlockMode,
+): Promise<SavedCodeBlock> {
+  return invoke<SavedCodeBlock>("save_code_block", {
+    workspaceId,
+    relativePath,
+    content,
+    mode,
+  });
+}
+
+export async function getHttpApiStatus(): Promise<HttpApiStatus> {
+  return invoke<HttpApiStatus>("get_http_api_status");
+}
+
+export async function regenerateHttpApiToken(): Promise<string> {
+  return invoke<string>("regenerate_http_api_token");
+}
+
+export async function containerStart(workspaceId: string): Promise<ContainerStatus> {
+  return invoke<ContainerStatus>("container_start", { workspaceId });
+}
+
+export async function containerStop(workspaceId: string): Promise<ContainerStatus> {
+  return invoke<ContainerStatus>("container_stop", { workspaceId });
+}
+
+export async function containerStatus(workspaceId: string): Promise<ContainerStatus> {
+  return invoke<ContainerStatus>("container_status", { workspaceId });
+}
+
+export async function createTerminal(
+  workspaceId: string,
+  cols?: number,
+  rows?: number,
+): Promise<TerminalInfo> {
+  return invoke<TerminalInfo>("terminal_create", {
+    workspaceId,
+    cols: cols ?? null,
+    rows: rows ?? null,
+  });
+}
+
+export async function writeTerminal(terminalId: string, data: string): Promise<void> {
+  return invoke("terminal_write", { terminalId, data });
+}
+
+export async function resizeTerminal(
+  terminalId: string,
+  cols: number,
+  rows: number,
+): Promise<void> {
+  return invoke("terminal_resize", { terminalId, cols, rows });
+}
+
+export async function killTerminal(terminalId: string): Promise<void> {
+  return invoke("terminal_kill", { terminalId });
+}
+
+export async function detectTasks(workspaceId: string): Promise<WorkspaceTask[]> {
+  return invoke<WorkspaceTask[]>("detect_tasks", { workspaceId });
+}
+
+export async function runTask(workspaceId: string, taskId: string): Promise<TaskRun> {
+  return invoke<TaskRun>("run_task", { workspaceId, taskId });
+}
+
+export async function cancelTask(runId: string): Promise<void> {
+  return invoke("cancel_task", { runId });
+}
@@ -4,6 +4,191 @@ export type WorkspaceInfo = {
   path: string;
   connected: boolean;
   codex_bin?: string | null;
+  codex_home?: string | null;
+  codex_version?: string | null;
+  codex_version_supported?: boolean | null;
+  template_id?: string | null;
+  defaults?: WorkspaceDefaults;
+  container?: ContainerConfig | null;
+  pre_connect_commands?: PreConnectCommand[];
+  observer_mode?: boolean;
+  speak_completions?: boolean;
+  auto_connect?: boolean;
+};
+
+export type PreConnectCommand = {
+  command: string;
+  timeoutSecs: number;
+};
+
+export type ContainerKind = "docker" | "devcontainer";
+
+export type ContainerConfig = {
+  kind: ContainerKind;
+  container: string;
+  workdir: string;
+  codexBin?: string | null;
+  user?: string | null;
+  dockerBin?: string | null;
+};
+
+export type ContainerStatus = {
+  kind: ContainerKind;
+  container: string;
+  exists: boolean;
+  running: boolean;
+  status: string | null;
+  health: string | null;
+  startedAt: string | null;
+  codexVersion: string | null;
+  error: string | null;
+};
+
+export type DiscoveredRepo = {
+  path: string;
+  name: string;
+  branch: string | null;
+  lastCommitMs: number | null;
+  alreadyAdded: boolean;
+};
+
+export type WorkspaceDefaults = {
+  env: Record<string, string>;
+  accessMode: AccessMode | null;
+  defaultPrompts: string[];
+  startupCommands: string[];
+};
+
+export type WorkspaceTemplate = WorkspaceDefaults & {
+  id: string;
+  name: string;
+  codexBin: string | null;
+  createdAtMs: number;
+  updatedAtMs: number;
+};
+
+export type WorkspaceTemplateInput = Partial<WorkspaceDefaults> & {
+  id?: string | null;
+  name: string;
+  codexBin?: string | null;
+};
+
+export type CodexVersionInfo = {
+  raw: string;
+  version: string | null;
+  supported: boolean;
+  supportedRange: string;
+};
+
+export type CodexBinaryCandidate = {
+  path: string;
+  source: string;
+  version: string | null;
+  supported: boolean | null;
+  requiresNode: boolean;
+  error: string | null;
+};
+
+export type ShellEnvStatus = {
+  shell: string | null;
+  capturedAtMs: number | null;
+  variableCount: number;
+  error: string | null;
+};
+
+export type CodexVersionWarning = {
+  workspaceId: string;
+  version: string | null;
+  raw: string;
+  supportedRange: string;
+};
+
+export type AppError =
+  | { kind: "notConnected"; message: string }
+  | { kind: "workspaceNotFound"; message: string }
+  | { kind: "gitError"; message: string; code: number }
+  | { kind: "ioError"; message: string }
+  | { kind: "rpcError"; message: string; code: number }
+  | { kind: "timeout"; message: string }
+  | { kind: "invalidReference"; message: string; reference: string }
+  | { kind: "nothingToReview"; message: string }
+  | { kind: "secretsDetected"; message: string }
+  | { kind: "observerMode"; message: string }
+  | { kind: "turnsRunning"; message: string; runningTurns: number }
+  | {
+      kind: "accessModeLocked";
+      message: string;
+      lockedMode: AccessMode;
+      requestedMode: AccessMode;
+    }
+  | { kind: "unsupported"; message: string; method: string; codexVersion: string | null }
+  | { kind: "other"; message: string };
+
+export type MethodSupport = "supported" | "unsupported" | "unknown";
+
+export type WorkspaceCapabilities = {
+  workspaceId: string;
+  codexVersion: string | null;
+  userAgent: string | null;
+  methods: Record<string, MethodSupport>;
+  initialize: Record<string, unknown> | null;
+};
+
+export type ContextLevel = "ok" | "warning" | "critical";
+
+export type ContextUsage = {
+  workspaceId: string;
+  threadId: string;
+  tokensInContext: number;
+  contextWindow: number | null;
+  percentUsed: number | null;
+  totalTokens: number;
+  level: ContextLevel;
+  updatedAtMs: number;
+};
+
+export type CompactionMode = "off" | "offer" | "auto";
+
+export type CompactionConfig = {
+  mode: CompactionMode;
+  thresholdPercent: number;
+};
+
+export type CompactionStatus = "started" | "completed" | "failed";
+
+export type ThreadCompactionEvent = {
+  workspaceId: string;
+  threadId: string;
+  status: CompactionStatus;
+  trigger: string;
+  beforeTokens: number;
+  afterTokens: number | null;
+  error: string | null;
+};
+
+export type InstructionsScope = "workspace" | "global";
+
+export type AgentInstructions = {
+  workspaceId: string;
+  scope: InstructionsScope;
+  path: string;
+  exists: boolean;
+  content: string;
+  modifiedAtMs: number | null;
+};
+
+export type InstructionsUpdate = {
+  path: string;
+  scope: InstructionsScope;
+  diff: string;
+  changed: boolean;
+  written: boolean;
+  instructions: AgentInstructions;
+};
+
+export type CodexAccount = {
+  name: string;
+  codexHome: string;
 };
 
 export type AppServerEvent = {
@@ -11,6 +196,13 @@ export type AppServerEvent = {
   message: Record<string, unknown>;
 };
 
+export type EventBlobRef = {
+  type: "blob";
+  path: string;
+  bytes: number;
+  method: string | null;
+};
+
 export type Message = {
   id: string;
   role: "user" | "assistant";
@@ -41,10 +233,255 @@ export type ConversationItem =
 
 export type SessionNameSource = "default" | "custom";
 
+export type TurnMetrics = {
+  turnId: string | null;
+  startedAtMs: number;
+  completedAtMs: number;
+  durationMs: number;
+  tokens: number;
+  toolCalls: number;
+  filesTouched: string[];
+  status: string | null;
+  changes: TurnFileChange[];
+  beforeTree: string | null;
+  afterTree: string | null;
+  undoneAtMs: number | null;
+};
+
+export type TurnFileChange = {
+  path: string;
+  oldPath: string | null;
+  status: string;
+  additions: number;
+  deletions: number;
+};
+
+export type TurnChanges = {
+  threadId: string;
+  turnIndex: number;
+  turnId: string | null;
+  startedAtMs: number;
+  completedAtMs: number;
+  tracked: boolean;
+  changes: TurnFileChange[];
+};
+
+export type UndoConflict = {
+  path: string;
+  reason: "laterTurn" | "modifiedSince";
+  threadId: string | null;
+  turnIndex: number | null;
+};
+
+export type UndoResult = {
+  threadId: string;
+  turnIndex: number;
+  applied: boolean;
+  restored: string[];
+  removed: string[];
+  conflicts: UndoConflict[];
+  notePosted: boolean;
+};
+
+export type MetricsSummary = {
+  turnCount: number;
+  totalDurationMs: number;
+  averageDurationMs: number;
+  totalTokens: number;
+  toolCalls: number;
+  filesTouched: number;
+  firstTurnAtMs: number | null;
+  lastTurnAtMs: number | null;
+};
+
+export type CompactionMetrics = {
+  trigger: string;
+  startedAtMs: number;
+  completedAtMs: number | null;
+  beforeTokens: number;
+  afterTokens: number | null;
+  error: string | null;
+};
+
+export type ThreadMetrics = {
+  threadId: string;
+  turns: TurnMetrics[];
+  compactions: CompactionMetrics[];
+  summary: MetricsSummary;
+};
+
+export type WorkspaceMetrics = {
+  workspaceId: string;
+  summary: MetricsSummary;
+  threads: { threadId: string; summary: MetricsSummary }[];
+};
+
+export type BadgeCounts = {
+  runningTurns: number;
+  pendingApprovals: number;
+  count: number;
+};
+
+export type PendingMessage = {
+  id: string;
+  createdAtMs: number;
+  attempts: number;
+  lastError: string | null;
+  threadId: string;
+  text: string;
+  model: string | null;
+  effort: string | null;
+  accessMode: AccessMode | null;
+  attachments: LocalImageInput[];
+};
+
+export type OutboxEvent = {
+  workspaceId: string;
+  messageId: string;
+  status: "pending" | "delivered" | "failed" | "canceled";
+  detail: Record<string, unknown>;
+};
+
+export type MessageDraft = {
+  text: string;
+  attachments: LocalImageInput[];
+  updatedAtMs: number;
+};
+
+export type ThreadActivity = "idle" | "generating" | "awaitingApproval" | "errored";
+
 export type SessionMetadata = {
   name: string;
   archived: boolean;
   nameSource: SessionNameSource;
+  lastState?: ThreadActivity | null;
+  lastStateAtMs?: number | null;
+  autoTitled?: boolean;
+  accessMode?: AccessMode | null;
+};
+
+export type ThreadRenamedEvent = {
+  workspaceId: string;
+  threadId: string;
+  name: string;
+  nameSource: SessionNameSource;
+};
+
+export type SessionRevalidation = {
+  workspaceId: string;
+  status: "healthy" | "reconnected" | "failed";
+  error: string | null;
+};
+
+export type SessionsRevalidatedEvent = {
+  reason: "wake" | "networkChange";
+  checkedAtMs: number;
+  sessions: SessionRevalidation[];
+  usageRefreshed: boolean;
+};
+
+export type ThreadTitleConfig = {
+  enabled: boolean;
+  model: string | null;
+};
+
+export type SecretScanMode = "off" | "block" | "redact";
+
+export type SecretAction = "block" | "redact" | "allow";
+
+export type SecretScanConfig = {
+  mode: SecretScanMode;
+  scanAttachments: boolean;
+};
+
+export type HttpApiConfig = {
+  enabled: boolean;
+  port: number;
+  token: string | null;
+  websocketEnabled: boolean;
+};
+
+export type HttpApiStatus = {
+  running: boolean;
+  address: string | null;
+  error: string | null;
+};
+
+export type SecretFinding = {
+  rule: string;
+  source: string;
+  line: number;
+  preview: string;
+};
+
+export type SecretScanLogEntry = {
+  atMs: number;
+  threadId: string;
+  action: SecretAction;
+  findings: SecretFinding[];
+};
+
+export type SecretScanStore = {
+  version: number;
+  allowlist: string[];
+  log: SecretScanLogEntry[];
+};
+
+export type SecretsDetectedEvent = {
+  workspaceId: string;
+  threadId: string;
+  action: SecretAction;
+  findings: SecretFinding[];
+};
+
+export type ThreadState = {
+  threadId: string;
+  state: ThreadActivity;
+  updatedAtMs: number;
+  turnId: string | null;
+  pendingRequestId: number | null;
+  error: string | null;
+};
+
+export type PendingApproval = {
+  workspaceId: string;
+  threadId: string;
+  requestId: number;
+  method: string | null;
+  params: Record<string, unknown> | null;
+};
+
+export type ThreadSummary = {
+  threadId: string;
+  name: string;
+  archived: boolean;
+  nameSource: SessionNameSource;
+  state: ThreadActivity;
+  stateUpdatedAtMs: number | null;
+  error: string | null;
+};
+
+export type WorkspaceSnapshot = WorkspaceInfo & {
+  threads: ThreadSummary[];
+  sessionsError: string | null;
+};
+
+export type AppSnapshot = {
+  workspaces: WorkspaceSnapshot[];
+  settings: AppSettings;
+  usage: UsageSnapshot;
+  pendingApprovals: PendingApproval[];
+  generatedAtMs: number;
+};
+
+export type ThreadStateChanged = {
+  workspaceId: string;
+  threadId: string;
+  state: ThreadActivity;
+  previousState: ThreadActivity;
+  updatedAtMs: number;
+  turnId: string | null;
+  error: string | null;
 };
 
 export type WorkspaceSessionStore = {
@@ -58,14 +495,95 @@ export type ThreadSummary = {
   archived: boolean;
 };
 
+export type WorkspaceThreads = {
+  workspaceId: string;
+  threads: Record<string, unknown>[];
+  error: AppError | null;
+  pending: boolean;
+};
+
 export type ReviewTarget =
   | { type: "uncommittedChanges" }
   | { type: "baseBranch"; branch: string }
   | { type: "commit"; sha: string; title?: string }
   | { type: "custom"; instructions: string };
 
+export type ReviewStart = {
+  target: ReviewTarget;
+  response: Record<string, unknown>;
+};
+
+export type ReviewFindingSeverity =
+  | "critical"
+  | "high"
+  | "medium"
+  | "low"
+  | "info";
+
+export type ReviewFindingStatus = "open" | "resolved" | "dismissed";
+
+export type ReviewFinding = {
+  id: string;
+  threadId: string;
+  file: string | null;
+  line: number | null;
+  endLine: number | null;
+  severity: ReviewFindingSeverity;
+  title: string;
+  message: string;
+  status: ReviewFindingStatus;
+  createdAtMs: number;
+  updatedAtMs: number;
+  lastSeenAtMs: number;
+};
+
+export type PatchHunkResult = {
+  index: number;
+  header: string;
+  oldStart: number;
+  oldLines: number;
+  newStart: number;
+  newLines: number;
+  ok: boolean;
+  error: string | null;
+};
+
+export type PatchFileResult = {
+  path: string;
+  oldPath: string | null;
+  status: "added" | "deleted" | "renamed" | "copied" | "modified";
+  hunks: PatchHunkResult[];
+  ok: boolean;
+  error: string | null;
+};
+
+export type PatchApplyResult = {
+  dryRun: boolean;
+  applied: boolean;
+  files: PatchFileResult[];
+  error: string | null;
+};
+
+export type PatchPreview = {
+  files: PatchFileResult[];
+  totalHunks: number;
+  cleanHunks: number;
+  conflictingHunks: number;
+  created: string[];
+  deleted: string[];
+  renamed: string[];
+  appliesCleanly: boolean;
+};
+
 export type AccessMode = "read-only" | "current" | "full-access";
 
+export type ThreadOverrides = {
+  approvalPolicy?: "untrusted" | "on-failure" | "on-request" | "never" | null;
+  sandboxMode?: "read-only" | "workspace-write" | "danger-full-access" | null;
+  model?: string | null;
+  effort?: string | null;
+};
+
 export type ComposerAttachment = {
   id: string;
   name: string;
@@ -79,8 +597,25 @@ export type LocalImageInput = {
   path: string;
 };
 
+export type QueuedMessage = {
+  id: string;
+  queuedAtMs: number;
+  attempts: number;
+  lastError: string | null;
+  retryAtMs: number | null;
+  failed: boolean;
+  threadId: string;
+  text: string;
+  model: string | null;
+  effort: string | null;
+  accessMode: AccessMode | null;
+  attachments: LocalImageInput[];
+};
+
 export type ThemePreference = "system" | "light" | "dark";
 
+export type QuitBehavior = "quit" | "keepRunning";
+
 export type UsageSource = "app-server" | "sessions" | "none";
 
 export type UsageSnapshot = {
@@ -101,15 +636,44 @@ export type RateLimitSnapshot = {
   secondary: RateLimitWindow | null;
 };
 
+export type RateLimitHistoryRange = "day" | "week" | "month";
+
+export type RateLimitSample = RateLimitSnapshot & {
+  timestampMs: number;
+};
+
+export type HourlyRateLimit = {
+  hour: number;
+  samples: number;
+  peakPrimaryPercent: number | null;
+  peakSecondaryPercent: number | null;
+  limitHits: number;
+};
+
+export type RateLimitHistory = {
+  range: RateLimitHistoryRange;
+  sinceMs: number;
+  samples: RateLimitSample[];
+  hourly: HourlyRateLimit[];
+};
+
 export type AppSettings = {
   themePreference: ThemePreference;
   accessMode: AccessMode;
   bypassApprovalsAndSandbox: boolean;
   enableWebSearchRequest: boolean;
   confirmBeforeQuit: boolean;
-  enableCompletionNotifications: boolean;
+  quitBehavior: QuitBehavior;
   usagePollingEnabled: boolean;
   usagePollingIntervalMinutes: number;
+  rateLimitAlertsEnabled: boolean;
+  rateLimitWarningPercent: number;
+  diffMaxBytes: number;
+  gitHostingToken: string | null;
+  turnCheckpointsEnabled: boolean;
+  eventBatchWindowMs: number;
+  hooks: HookConfig[];
+  logLevel: string;
   sidebarWidth: number;
   glassBlurLight: number;
   glassBlurDark: number;
@@ -117,7 +681,377 @@ export type AppSettings = {
   glassOpacityDark: number;
   codexBinPath: string | null;
   nodeBinPath: string | null;
+  loginShellEnvEnabled: boolean;
+  notifications: NotificationConfig;
+  approvalAutoDefer: AutoDeferConfig;
+  contextCompaction: CompactionConfig;
+  threadTitles: ThreadTitleConfig;
+  secretScanning: SecretScanConfig;
   workspaceSidebarExpanded: Record<string, boolean>;
+  keymap: Record<string, string>;
+  locale: string | null;
+  updateChannel: UpdateChannel;
+  crashReportingEnabled: boolean;
+  localAnalyticsEnabled: boolean;
+  approvalCommandAllowlist: string[];
+  attachmentMaxDimension: number;
+  transcriptionCommand: string | null;
+  httpApi: HttpApiConfig;
+};
+
+export type Keybinding = {
+  action: string;
+  accelerator: string | null;
+  defaultAccelerator: string;
+  customized: boolean;
+};
+
+export type LocalAnalyticsReport = {
+  enabled: boolean;
+  sinceMs: number | null;
+  totalCommands: number;
+  commands: { name: string; count: number }[];
+  events: Record<string, number>;
+  daily: Record<string, number>;
+};
+
+export type CrashReport = {
+  id: string;
+  createdAtMs: number;
+  kind: "panic" | "taskError";
+  message: string;
+  location: string | null;
+  thread: string | null;
+  backtrace: string;
+  appVersion: string;
+  os: string;
+  arch: string;
+  logTail: string[];
+  submittedAtMs: number | null;
+};
+
+export type UpdateChannel = "stable" | "beta";
+
+export type UpdateInfo = {
+  currentVersion: string;
+  version: string;
+  notes: string | null;
+  publishedAt: string | null;
+};
+
+export type UpdateCheck = {
+  channel: UpdateChannel;
+  currentVersion: string;
+  update: UpdateInfo | null;
+};
+
+export type UpdateDownloadProgressEvent = {
+  version: string;
+  downloaded: number;
+  contentLength: number | null;
+};
+
+export type LocaleStrings = {
+  lang: string;
+  systemLang: string;
+  available: string[];
+  strings: Record<string, string>;
+};
+
+export type DeferAction = "deny" | "defer";
+
+export type AutoDeferConfig = {
+  enabled: boolean;
+  idleMinutes: number;
+  action: DeferAction;
+};
+
+export type DeferredApproval = {
+  id: string;
+  workspaceId: string;
+  threadId: string;
+  requestId: number;
+  method: string;
+  params: Record<string, unknown> | null;
+  summary: string;
+  action: DeferAction;
+  deferredAtMs: number;
+  idleMs: number;
+  replayedDecision: "accept" | "decline" | null;
+  replayedAtMs: number | null;
+};
+
+export type NotificationEventName =
+  | "turn-complete"
+  | "approval-needed"
+  | "error"
+  | "rate-limit";
+
+export type NotificationChannels = {
+  native: boolean;
+  sound: boolean;
+  badge: boolean;
+};
+
+export type QuietHours = {
+  enabled: boolean;
+  start: string;
+  end: string;
+};
+
+export type AutoConnectProgress = {
+  workspaceId: string;
+  status: "connecting" | "connected" | "failed";
+  error: string | null;
+  completed: number;
+  total: number;
+};
+
+export type DuplicateWorkspaces = {
+  key: string;
+  reason: "path" | "gitRoot";
+  workspaces: WorkspaceInfo[];
+};
+
+export type TransferConflict = "skip" | "overwrite";
+
+export type TransferResult = {
+  transferred: string[];
+  conflicts: string[];
+  skipped: string[];
+};
+
+export type ScheduleRecurrence =
+  | { type: "daily"; at: string }
+  | { type: "weekly"; days: number[]; at: string }
+  | { type: "interval"; minutes: number };
+
+export type PromptOptions = {
+  threadId?: string | null;
+  model?: string | null;
+  effort?: string | null;
+  accessMode?: string | null;
+};
+
+export type ScheduledTask = {
+  id: string;
+  name: string;
+  workspaceId: string;
+  prompt: string;
+  threadId: string | null;
+  model: string | null;
+  effort: string | null;
+  accessMode: string | null;
+  recurrence: ScheduleRecurrence;
+  enabled: boolean;
+  nextRunAtMs: number | null;
+  lastRunAtMs: number | null;
+  createdAtMs: number;
+  updatedAtMs: number;
+};
+
+export type ScheduledTaskInput = {
+  id?: string | null;
+  name: string;
+  workspaceId: string;
+  prompt: string;
+  threadId?: string | null;
+  model?: string | null;
+  effort?: string | null;
+  accessMode?: string | null;
+  recurrence: ScheduleRecurrence;
+  enabled?: boolean;
+};
+
+export type ScheduledRun = {
+  id: string;
+  taskId: string;
+  workspaceId: string;
+  scheduledForMs: number | null;
+  startedAtMs: number;
+  status: "started" | "failed";
+  threadId: string | null;
+  turnId: string | null;
+  error: string | null;
+  manual: boolean;
+};
+
+export type QueuedTask = {
+  id: string;
+  prompt: string;
+  options: PromptOptions;
+  batchId: string | null;
+  status: "queued" | "running" | "completed" | "failed" | "cancelled";
+  createdAtMs: number;
+  startedAtMs: number | null;
+  finishedAtMs: number | null;
+  threadId: string | null;
+  turnId: string | null;
+  error: string | null;
+};
+
+export type TaskQueueUpdate = {
+  workspaceId: string;
+  tasks: QueuedTask[];
+};
+
+export type BroadcastDeliveryStatus =
+  | "sending"
+  | "running"
+  | "completed"
+  | "failed"
+  | "interrupted";
+
+export type BroadcastTarget = {
+  workspaceId: string;
+  workspaceName: string;
+  status: BroadcastDeliveryStatus;
+  threadId: string | null;
+  turnId: string | null;
+  error: string | null;
+  startedAtMs: number | null;
+  finishedAtMs: number | null;
+};
+
+export type Broadcast = {
+  id: string;
+  prompt: string;
+  options: PromptOptions;
+  createdAtMs: number;
+  completedAtMs: number | null;
+  targets: BroadcastTarget[];
+};
+
+export type BroadcastCompleted = {
+  batchId: string;
+  total: number;
+  completed: number;
+  failed: number;
+  interrupted: number;
+};
+
+export type BatchOutcome = {
+  workspaceId: string;
+  workspaceName: string;
+  prompt: string;
+  status: "pending" | "running" | "completed" | "failed" | "interrupted" | "cancelled";
+  threadId: string | null;
+  turnId: string | null;
+  error: string | null;
+  startedAtMs: number | null;
+  finishedAtMs: number | null;
+  durationMs: number | null;
+  tracked: boolean;
+  files: TurnFileChange[];
+  additions: number;
+  deletions: number;
+};
+
+export type BatchReport = {
+  batchId: string;
+  kind: "broadcast" | "queue";
+  prompt: string | null;
+  createdAtMs: number;
+  completedAtMs: number | null;
+  totals: {
+    workspaces: number;
+    completed: number;
+    failed: number;
+    inProgress: number;
+    filesChanged: number;
+    additions: number;
+    deletions: number;
+  };
+  workspaces: BatchOutcome[];
+};
+
+export type SaveCodeBlockMode = "create" | "overwrite" | "append";
+
+export type SavedCodeBlock = {
+  path: string;
+  mode: SaveCodeBlockMode;
+  created: boolean;
+  bytesWritten: number;
+  gitStatus: {
+    branchName: string;
+    files: GitFileStatus[];
+    totalAdditions: number;
+    totalDeletions: number;
+  } | null;
+};
+
+export type SpeechConfig = {
+  voice: string | null;
+  onlyWhenUnfocused: boolean;
+};
+
+export type NotificationConfig = {
+  turnComplete: NotificationChannels;
+  approvalNeeded: NotificationChannels;
+  error: NotificationChannels;
+  rateLimit: NotificationChannels;
+  quietHours: QuietHours;
+  speech: SpeechConfig;
+};
+
+export type NotificationDispatch = {
+  event: NotificationEventName;
+  title: string;
+  body: string;
+  workspaceId: string | null;
+  threadId: string | null;
+  native: boolean;
+  sound: boolean;
+  badge: boolean;
+  quiet: boolean;
+};
+
+export type HookEventName =
+  | "turn-completed"
+  | "approval-requested"
+  | "rate-limit-warning";
+
+export type HookAction =
+  | { type: "webhook"; url: string; headers?: Record<string, string> }
+  | { type: "script"; command: string };
+
+export type HookConfig = {
+  id: string;
+  name: string;
+  events: HookEventName[];
+  action: HookAction;
+  enabled: boolean;
+};
+
+export type HookExecution = {
+  id: string;
+  hookId: string;
+  hookName: string;
+  event: HookEventName | "test";
+  workspaceId: string | null;
+  startedAtMs: number;
+  durationMs: number;
+  attempts: number;
+  success: boolean;
+  status: number | null;
+  error: string | null;
+  output: string | null;
+};
+
+export type AccountStatus = {
+  loggedIn: boolean;
+  authMethod: "chatgpt" | "apiKey" | string | null;
+  plan: string | null;
+  email: string | null;
+  source: "app-server" | "auth-file";
+};
+
+export type RateLimitWarning = {
+  window: "primary" | "secondary";
+  usedPercent: number;
+  threshold: number;
+  windowDurationMins: number | null;
+  resetsAt: number | null;
 };
 
 export type CodexBinInspection = {
@@ -126,11 +1060,57 @@ export type CodexBinInspection = {
   resolvedPath: string;
 };
 
+export type RecordingStarted = {
+  id: string;
+  startedAtMs: number;
+};
+
+export type RecordingResult = {
+  id: string;
+  path: string;
+  durationMs: number;
+  text: string | null;
+};
+
+export type SavedAttachment = {
+  path: string;
+  mime: string;
+  width: number;
+  height: number;
+  size: number;
+  originalSize: number;
+  downscaled: boolean;
+};
+
+export type ApprovalFileContext = {
+  path: string;
+  kind: "add" | "delete" | "update";
+  movePath: string | null;
+  diff: string;
+  additions: number;
+  deletions: number;
+  stale: boolean;
+  truncated: boolean;
+};
+
+export type ApprovalCommandContext = {
+  command: string;
+  cwd: string;
+  binary: string | null;
+  resolvedPath: string | null;
+  allowlisted: boolean;
+  allowlistPattern: string | null;
+};
+
+export type ApprovalContext =
+  | { files: ApprovalFileContext[]; omittedFiles: number }
+  | { command: ApprovalCommandContext };
+
 export type ApprovalRequest = {
   workspace_id: string;
   request_id: number;
   method: string;
-  params: Record<string, unknown>;
+  params: Record<string, unknown> & { approvalContext?: ApprovalContext };
 };
 
 export type GitFileStatus = {
@@ -143,6 +1123,108 @@ export type GitFileStatus = {
 export type GitFileDiff = {
   path: string;
   diff: string;
+  isBinary: boolean;
+  oldSize: number;
+  newSize: number;
+  truncated: boolean;
+  image: DiffImagePaths | null;
+  denied: boolean;
+};
+
+export type DiffImagePaths = {
+  before: string | null;
+  after: string | null;
+};
+
+export type Checkpoint = {
+  id: string;
+  threadId: string;
+  commit: string;
+  head: string | null;
+  createdAtMs: number;
+};
+
+export type FileVersion = {
+  checkpointId: string | null;
+  createdAtMs: number | null;
+  exists: boolean;
+  content: string | null;
+  size: number;
+  isBinary: boolean;
+  truncated: boolean;
+  changed: boolean;
+};
+
+export type FileVersions = {
+  path: string;
+  versions: FileVersion[];
+};
+
+export type BlameLine = {
+  line: number;
+  content: string;
+  commit: string | null;
+  author: string | null;
+  email: string | null;
+  timestamp: number | null;
+  summary: string | null;
+  uncommitted: boolean;
+};
+
+export type ConflictSide = {
+  oid: string;
+  content: string | null;
+  isBinary: boolean;
+};
+
+export type GitConflict = {
+  path: string;
+  base: ConflictSide | null;
+  ours: ConflictSide | null;
+  theirs: ConflictSide | null;
+};
+
+export type ConflictResolution =
+  | { type: "ours" }
+  | { type: "theirs" }
+  | { type: "content"; content: string };
+
+export type GitProgressEvent = {
+  workspaceId: string;
+  operation: "fetch" | "pull" | "push";
+  message: string;
+  percent: number | null;
+};
+
+export type DiffSegment = {
+  text: string;
+  changed: boolean;
+};
+
+export type DiffLine = {
+  kind: "context" | "add" | "delete";
+  oldLine: number | null;
+  newLine: number | null;
+  content: string;
+  segments: DiffSegment[] | null;
+};
+
+export type DiffHunk = {
+  header: string;
+  oldStart: number;
+  oldLines: number;
+  newStart: number;
+  newLines: number;
+  lines: DiffLine[];
+};
+
+export type StructuredFileDiff = {
+  path: string;
+  oldPath: string | null;
+  status: string;
+  additions: number;
+  deletions: number;
+  hunks: DiffHunk[];
 };
 
 export type ModelOption = {
@@ -159,6 +1241,26 @@ export type SkillOption = {
   name: string;
   path: string;
   description?: string;
+  enabled?: boolean;
+};
+
+export type SkillScope = "workspace" | "global";
+
+export type Skill = {
+  id: string;
+  scope: SkillScope;
+  name: string;
+  description: string;
+  path: string;
+  body: string;
+  content: string;
+  enabled: boolean;
+};
+
+export type SkillInput = {
+  name: string;
+  description: string;
+  body: string;
 };
 
 export type PromptOption = {
@@ -166,6 +1268,14 @@ export type PromptOption = {
   path: string;
   description?: string;
   argumentHint?: string;
+  lastUsed?: number | null;
+};
+
+export type PromptStat = {
+  name: string;
+  count: number;
+  lastUsed: number;
+  workspaceIds: string[];
 };
 
 export type PromptFile = {
@@ -175,6 +1285,153 @@ export type PromptFile = {
   argumentHint?: string;
 };
 
+export type FileSearchMatch = {
+  path: string;
+  score: number;
+  indices: number[];
+};
+
+export type WorkspaceFileContent = {
+  path: string;
+  content: string | null;
+  encoding: "utf-8" | "latin1" | "base64" | "binary";
+  size: number;
+  modifiedMs: number | null;
+  language: string | null;
+  isBinary: boolean;
+  truncated: boolean;
+};
+
+export type FileIndexHealth = {
+  files: number;
+  builtAtMs: number;
+  ageMs: number;
+  stale: boolean;
+  watching: boolean;
+};
+
+export type RequestQueueHealth = {
+  inFlight: number;
+  maxInFlight: number;
+  queuedInteractive: number;
+  queuedBulk: number;
+  peakQueueDepth: number;
+  totalRequests: number;
+};
+
+export type WorkspaceHealth = {
+  workspaceId: string;
+  connected: boolean;
+  fileIndex: FileIndexHealth | null;
+  requests: RequestQueueHealth | null;
+};
+
+export type DirectoryStats = {
+  path: string;
+  files: number;
+  bytes: number;
+};
+
+export type LanguageStats = {
+  language: string;
+  files: number;
+  bytes: number;
+};
+
+export type WorkspaceStats = {
+  workspaceId: string;
+  fileCount: number;
+  directoryCount: number;
+  totalBytes: number;
+  largestDirectories: DirectoryStats[];
+  languages: LanguageStats[];
+  elapsedMs: number;
+};
+
+export type AuditKind = "command" | "fileWrite";
+
+export type AuditEntry = {
+  id: string;
+  atMs: number;
+  threadId: string;
+  turnId: string | null;
+  itemId: string | null;
+  kind: AuditKind;
+  target: string;
+  change: string | null;
+  decision: string | null;
+  status: string | null;
+  exitCode: number | null;
+};
+
+export type AuditFilter = {
+  threadId?: string | null;
+  kind?: AuditKind | null;
+  sinceMs?: number | null;
+  untilMs?: number | null;
+  query?: string | null;
+  limit?: number | null;
+};
+
+export type DeepLinkEvent =
+  | { kind: "openThread"; workspaceId: string; threadId: string | null }
+  | { kind: "newTask"; workspace: WorkspaceInfo; prompt: string | null }
+  | { kind: "error"; url: string; message: string };
+
+export type StorageStatus = {
+  dataDir: string;
+  readOnly: boolean;
+  error: string | null;
+};
+
+export type SearchConfig = {
+  exclude: string[];
+  include: string[];
+};
+
+export type PathGuardConfig = {
+  deny: string[];
+  allow: string[];
+};
+
+export type PathGuardBlockedEvent = {
+  workspaceId: string;
+  threadId: string | null;
+  kind: "approval" | "attachment";
+  requestId?: number;
+  method?: string | null;
+  paths: string[];
+};
+
+export type PathGuardViolationEvent = {
+  workspaceId: string;
+  threadId: string | null;
+  itemId: string;
+  paths: string[];
+};
+
+export type ObserverApprovalDeclinedEvent = {
+  workspaceId: string;
+  threadId: string | null;
+  requestId: number;
+  method: string | null;
+};
+
+export type CommandAllowlistScope = "global" | "workspace";
+
+export type CommandAllowlistEntry = {
+  pattern: string;
+  scope: CommandAllowlistScope;
+};
+
+export type CommandAllowlistApprovedEvent = {
+  workspaceId: string;
+  threadId: string | null;
+  requestId: number;
+  command: string | null;
+  pattern: string;
+};
+
 export type SlashItem = {
   id: string;
   kind: "prompt" | "file";
@@ -191,3 +1448,89 @@ export type DebugEntry = {
   label: string;
   payload?: unknown;
 };
+
+export type TerminalInfo = {
+  id: string;
+  workspaceId: string;
+  shell: string;
+};
+
+export type TerminalOutputEvent = {
+  terminalId: string;
+  workspaceId: string;
+  data: string;
+};
+
+export type TerminalExitEvent = {
+  terminalId: string;
+  workspaceId: string;
+  exitCode: number | null;
+};
+
+export type WorkspaceTask = {
+  id: string;
+  source: "npm" | "make" | "cargo" | "just";
+  name: string;
+  command: string;
+};
+
+export type TaskRun = {
+  runId: string;
+  taskId: string;
+  workspaceId: string;
+  command: string;
+};
+
+export type TaskOutputEvent = {
+  runId: string;
+  taskId: string;
+  workspaceId: string;
+  stream: "stdout" | "stderr";
+  line: string;
+};
+
+export type TaskExitEvent = {
+  runId: string;
+  taskId: string;
+  workspaceId: string;
+  exitCode: number | null;
+  success: boolean;
+  cancelled: boolean;
+  durationMs: number;
+  error: string | null;
+};
+
+export type WindowGeometry = {
+  x: number;
+  y: number;
+  width: number;
+  height: number;
+};
+
+export type WindowState = {
+  version: number;
+  geometry: WindowGeometry | null;
+  maximized: boolean;
+  lastWorkspaceId: string | null;
+  lastThreadId: string | null;
+};
+
+export type QuickSwitchItem = {
+  kind: "workspace" | "thread";
+  workspaceId: string;
+  workspaceName: string;
+  threadId: string | null;
+  title: string;
+  lastActivityMs: number | null;
+  state: ThreadActivity | null;
+  score: number;
+  indices: number[];
+};
+
+export type MenuActionEvent = {
+  action: string;
+  workspaceId?: string;
+  threadId?: string | null;
+  result?: unknown;
+  error?: string | null;
+};
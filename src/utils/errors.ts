@@ -0,0 +1,17 @@
+import type { AppError } from "../types";
+
+export function isAppError(error: unknown): error is AppError {
+  return (
+    typeof error === "object" &&
+    error !== null &&
+    typeof (error as AppError).kind === "string" &&
+    typeof (error as AppError).message === "string"
+  );
+}
+
+export function errorMessage(error: unknown): string {
+  if (isAppError(error)) {
+    return error.message;
+  }
+  return error instanceof Error ? error.message : String(error);
+}